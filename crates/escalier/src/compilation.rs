@@ -0,0 +1,290 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::mem;
+
+use escalier_ast::{CompilerOptions, Script, StmtKind};
+use escalier_codegen::d_ts::codegen_d_ts;
+use escalier_codegen::js::codegen_js;
+use escalier_hm::checker::{Checker, Report};
+use escalier_hm::context::Context;
+use escalier_hm::diagnostic::Diagnostic;
+use escalier_hm::type_error::TypeError;
+use escalier_parser::parse;
+
+use crate::compile_error::CompileError;
+
+/// Identifies a file in a `Compilation`.  Ids are assigned when files are
+/// added and don't change when other files are added, updated, or removed so
+/// they can be used to refer to files across compiles.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ModuleId(usize);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SourceFile {
+    pub path: String,
+    pub src: String,
+}
+
+/// The outputs generated for a file.
+#[derive(Debug)]
+pub struct FileOutput {
+    /// The file's script after it was checked, its nodes have their
+    /// `inferred_type`s set.
+    pub script: Script,
+    pub js: String,
+    pub srcmap: String,
+    pub dts: String,
+}
+
+/// The result of `Compilation::compile`.
+#[derive(Debug, Default)]
+pub struct CompilationResult {
+    /// The outputs of the files that could be compiled.  Files with
+    /// diagnostics still have outputs, only the ones with `errors` don't.
+    pub outputs: BTreeMap<ModuleId, FileOutput>,
+    /// The diagnostics and warnings reported for each file.
+    pub reports: BTreeMap<ModuleId, Report>,
+    /// The errors that stopped files from being compiled, e.g. parse errors.
+    pub errors: BTreeMap<ModuleId, CompileError>,
+}
+
+impl CompilationResult {
+    /// Returns `true` if every file was compiled without any errors.
+    /// Warnings don't count as errors.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+            && self
+                .reports
+                .values()
+                .all(|report| report.diagnostics.is_empty())
+    }
+
+    /// The diagnostics from every file ordered by module id.
+    pub fn diagnostics(&self) -> impl Iterator<Item = (ModuleId, &Diagnostic)> {
+        self.reports
+            .iter()
+            .flat_map(|(id, report)| report.diagnostics.iter().map(move |d| (*id, d)))
+    }
+
+    /// The warnings from every file ordered by module id.
+    pub fn warnings(&self) -> impl Iterator<Item = (ModuleId, &Diagnostic)> {
+        self.reports
+            .iter()
+            .flat_map(|(id, report)| report.warnings.iter().map(move |d| (*id, d)))
+    }
+}
+
+/// A set of source files that are compiled together using the same options.
+/// This is the entry point for tools that compile whole programs, e.g. the
+/// CLI, and saves them from calling `parse`, `infer_script`, `codegen_js`,
+/// and `codegen_d_ts` for each file themselves.
+///
+/// Each file is checked separately against the same libs and can import the
+/// files it depends on using their paths, e.g. `import {a} from "a.esc"`.
+#[derive(Debug)]
+pub struct Compilation {
+    pub options: CompilerOptions,
+    files: BTreeMap<ModuleId, SourceFile>,
+    next_id: usize,
+    checker: Checker,
+    // The types and values from the libs, each file is checked in a copy of
+    // this.
+    ctx: Context,
+}
+
+impl Compilation {
+    /// Creates a compilation whose files are checked against the prelude and
+    /// `options.libs`.
+    pub fn new(options: CompilerOptions) -> Result<Self, TypeError> {
+        let mut checker = Checker::default();
+        let mut ctx = Context::default();
+        checker.load_libs(&options.libs, &mut ctx)?;
+        Ok(Compilation::with_env(options, checker, ctx))
+    }
+
+    /// Creates a compilation whose files are checked against the types and
+    /// values in `ctx`, e.g. ones loaded from a lib.d.ts file using
+    /// `parse_dts`.  `options.libs` aren't loaded.
+    pub fn with_env(options: CompilerOptions, checker: Checker, ctx: Context) -> Self {
+        Compilation {
+            options,
+            files: BTreeMap::new(),
+            next_id: 0,
+            checker,
+            ctx,
+        }
+    }
+
+    /// Adds a file and returns its id.  If a file with the same path was
+    /// already added, its source is replaced and it keeps its id.
+    pub fn add_file(&mut self, path: &str, src: &str) -> ModuleId {
+        if let Some(id) = self.module_id(path) {
+            self.update_file(id, src);
+            return id;
+        }
+
+        let id = ModuleId(self.next_id);
+        self.next_id += 1;
+        self.files.insert(
+            id,
+            SourceFile {
+                path: path.to_owned(),
+                src: src.to_owned(),
+            },
+        );
+        id
+    }
+
+    /// Replaces the source of a file, returns `false` if there's no file with
+    /// that id.
+    pub fn update_file(&mut self, id: ModuleId, src: &str) -> bool {
+        match self.files.get_mut(&id) {
+            Some(file) => {
+                file.src = src.to_owned();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a file, its id isn't reused.
+    pub fn remove_file(&mut self, id: ModuleId) -> Option<SourceFile> {
+        let file = self.files.remove(&id)?;
+        // Other files can no longer import it.
+        self.checker.modules.remove(&file.path);
+        Some(file)
+    }
+
+    pub fn module_id(&self, path: &str) -> Option<ModuleId> {
+        self.files
+            .iter()
+            .find(|(_, file)| file.path == path)
+            .map(|(id, _)| *id)
+    }
+
+    pub fn file(&self, id: ModuleId) -> Option<&SourceFile> {
+        self.files.get(&id)
+    }
+
+    pub fn files(&self) -> impl Iterator<Item = (ModuleId, &SourceFile)> {
+        self.files.iter().map(|(id, file)| (*id, file))
+    }
+
+    /// Parses, checks, and generates the JS and d.ts outputs for every file.
+    /// Files are checked after the files they import.  A file's errors don't
+    /// stop the other files from being compiled, but the files that import
+    /// it will report that they can't find it.
+    pub fn compile(&mut self) -> CompilationResult {
+        let mut result = CompilationResult::default();
+        let mut scripts: BTreeMap<ModuleId, Script> = BTreeMap::new();
+
+        for (id, file) in &self.files {
+            // The exports from the previous compile may be out of date.
+            self.checker.modules.remove(&file.path);
+            match parse(&file.src, &self.options) {
+                Ok(script) => {
+                    scripts.insert(*id, script);
+                }
+                Err(error) => {
+                    result.errors.insert(*id, error.into());
+                    result.reports.insert(*id, Report::default());
+                }
+            }
+        }
+
+        let mut order: Vec<ModuleId> = vec![];
+        let mut visited: BTreeSet<ModuleId> = BTreeSet::new();
+        for id in scripts.keys() {
+            add_to_order(*id, &self.files, &scripts, &mut visited, &mut order);
+        }
+
+        for id in order {
+            let (file, script) = match (self.files.get(&id), scripts.remove(&id)) {
+                (Some(file), Some(script)) => (file, script),
+                _ => continue,
+            };
+            let (output, report) =
+                compile_file(&mut self.checker, &self.ctx, &self.options, file, script);
+            match output {
+                Ok(output) => {
+                    result.outputs.insert(id, output);
+                }
+                Err(error) => {
+                    result.errors.insert(id, error);
+                }
+            }
+            result.reports.insert(id, report);
+        }
+
+        result
+    }
+}
+
+// Adds `id` to `order` after the files it imports.  Files in import cycles are
+// added in the order they're visited, the ones that are checked first report
+// that they can't find the modules they import.
+fn add_to_order(
+    id: ModuleId,
+    files: &BTreeMap<ModuleId, SourceFile>,
+    scripts: &BTreeMap<ModuleId, Script>,
+    visited: &mut BTreeSet<ModuleId>,
+    order: &mut Vec<ModuleId>,
+) {
+    if !visited.insert(id) {
+        return;
+    }
+    let script = match scripts.get(&id) {
+        Some(script) => script,
+        None => return,
+    };
+
+    for stmt in &script.stmts {
+        if let StmtKind::Import(import) = &stmt.kind {
+            let dep = files.iter().find(|(_, file)| file.path == import.source);
+            if let Some((dep, _)) = dep {
+                add_to_order(*dep, files, scripts, visited, order);
+            }
+        }
+    }
+
+    order.push(id);
+}
+
+// Checks `script` and generates its outputs.  Its exports are added to
+// `checker.modules` so that the files that import it can be checked.
+fn compile_file(
+    checker: &mut Checker,
+    ctx: &Context,
+    options: &CompilerOptions,
+    file: &SourceFile,
+    mut script: Script,
+) -> (Result<FileOutput, CompileError>, Report) {
+    let mut ctx = ctx.to_owned();
+    checker.current_report = Report::default();
+    let result = checker.infer_script(&mut script, &mut ctx, options);
+    let report = mem::take(&mut checker.current_report);
+    if let Err(error) = result {
+        return (Err(error.into()), report);
+    }
+
+    match checker.get_exports(&script, &ctx) {
+        Ok(exports) => {
+            checker.modules.insert(file.path.to_owned(), exports);
+        }
+        Err(error) => return (Err(error.into()), report),
+    }
+
+    // Codegen has to happen after type checking since the checker desugars
+    // overloaded operators into method calls.
+    let (js, srcmap) = codegen_js(&file.src, &script, options);
+    let output = match codegen_d_ts(&script, &ctx, checker, options) {
+        Ok(dts) => Ok(FileOutput {
+            script,
+            js,
+            srcmap,
+            dts,
+        }),
+        Err(error) => Err(error.into()),
+    };
+
+    (output, report)
+}
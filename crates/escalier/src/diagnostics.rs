@@ -53,11 +53,15 @@ pub fn get_diagnostics_from_compile_error(report: CompileError, src: &str) -> St
         CompileError::Diagnostic(diagnostics) => diagnostics
             .iter()
             .map(|diagnostic| {
-                format!(
+                let message = format!(
                     "{}: {}",
                     diagnostic.message,
                     type_errors_to_string(&diagnostic.reasons, src)
-                )
+                );
+                match &diagnostic.suggestion {
+                    Some(suggestion) => format!("{message}\nhelp: {suggestion}"),
+                    None => message,
+                }
             })
             .collect::<Vec<String>>()
             .join("\n"),
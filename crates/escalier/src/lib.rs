@@ -1,11 +1,14 @@
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
+use escalier_ast::CompilerOptions;
 use escalier_interop::parse::parse_dts;
 
+pub mod compilation;
 pub mod compile_error;
 pub mod diagnostics;
 
+use crate::compilation::Compilation;
 use crate::compile_error::CompileError;
 use crate::diagnostics::get_diagnostics_from_compile_error;
 
@@ -28,30 +31,27 @@ extern "C" {
 
 fn _compile(input: &str, lib: &str) -> Result<(String, String, String, String), CompileError> {
     log(&format!("parsing input: {input}"));
-    let mut program = escalier_parser::parse(input)?;
-    let ast = format!("{program:#?}");
-
-    let (js, srcmap) = escalier_codegen::js::codegen_js(input, &program);
 
     // TODO: return errors as part of CompileResult
-    let (mut checker, mut ctx) = parse_dts(lib).unwrap();
+    let (checker, ctx) = parse_dts(lib).unwrap();
 
-    // TODO: get rid of panics and return errors instead
-    match checker.infer_script(&mut program, &mut ctx) {
-        Ok(_) => {
-            if !checker.current_report.diagnostics.is_empty() {
-                panic!("was expecting infer_prog() to return no errors");
-            }
-        }
-        Err(error) => {
-            let message = error.to_string();
-            panic!("{message}");
+    let mut compilation = Compilation::with_env(CompilerOptions::default(), checker, ctx);
+    let id = compilation.add_file("input.esc", input);
+    let mut result = compilation.compile();
+
+    if let Some(error) = result.errors.remove(&id) {
+        return Err(error);
+    }
+    if let Some(report) = result.reports.remove(&id) {
+        if !report.diagnostics.is_empty() {
+            return Err(CompileError::Diagnostic(report.diagnostics));
         }
     }
 
-    let dts = escalier_codegen::d_ts::codegen_d_ts(&program, &ctx, &checker)?;
+    let output = result.outputs.remove(&id).unwrap();
+    let ast = format!("{:#?}", output.script);
 
-    Ok((js, srcmap, dts, ast))
+    Ok((output.js, output.srcmap, output.dts, ast))
 }
 
 #[wasm_bindgen]
@@ -77,7 +77,7 @@ pub fn compile(input: &str, lib: &str) -> Result<JsValue, JsValue> {
 
 #[wasm_bindgen]
 pub fn parse(input: &str) -> Result<JsValue, JsValue> {
-    match escalier_parser::parse(input) {
+    match escalier_parser::parse(input, &CompilerOptions::default()) {
         Ok(program) => {
             // TODO: update the AST to implement Serialize/Deserialize
             let ast = format!("{program:#?}");
@@ -0,0 +1,169 @@
+use pretty_assertions::assert_eq;
+
+use escalier::compilation::Compilation;
+use escalier::compile_error::CompileError;
+use escalier_ast::CompilerOptions;
+
+#[test]
+fn compiles_each_file() {
+    let mut compilation = Compilation::new(CompilerOptions::default()).unwrap();
+    let a = compilation.add_file("a.esc", "let a = 5");
+    let b = compilation.add_file("b.esc", "let b = \"hello\"");
+
+    let result = compilation.compile();
+    assert!(result.is_ok());
+
+    insta::assert_snapshot!(result.outputs[&a].js, @r###"
+    export const a = 5;
+    "###);
+    insta::assert_snapshot!(result.outputs[&a].dts, @r###"
+    export declare const a: 5;
+    "###);
+    insta::assert_snapshot!(result.outputs[&b].js, @r###"
+    export const b = "hello";
+    "###);
+    insta::assert_snapshot!(result.outputs[&b].dts, @r###"
+    export declare const b: "hello";
+    "###);
+}
+
+#[test]
+fn module_ids_are_stable() {
+    let mut compilation = Compilation::new(CompilerOptions::default()).unwrap();
+    let a = compilation.add_file("a.esc", "let a = 5");
+    let b = compilation.add_file("b.esc", "let b = 10");
+
+    compilation.remove_file(a);
+    let c = compilation.add_file("c.esc", "let c = 15");
+
+    assert_ne!(c, a);
+    assert_ne!(c, b);
+    assert_eq!(compilation.module_id("a.esc"), None);
+    assert_eq!(compilation.module_id("b.esc"), Some(b));
+    assert_eq!(compilation.module_id("c.esc"), Some(c));
+
+    // Adding a file with the same path updates it instead.
+    assert_eq!(compilation.add_file("b.esc", "let b = 20"), b);
+    assert_eq!(compilation.file(b).unwrap().src, "let b = 20");
+    assert_eq!(compilation.files().count(), 2);
+
+    let result = compilation.compile();
+    assert!(result.is_ok());
+
+    insta::assert_snapshot!(result.outputs[&b].js, @r###"
+    export const b = 20;
+    "###);
+    insta::assert_snapshot!(result.outputs[&c].js, @r###"
+    export const c = 15;
+    "###);
+}
+
+#[test]
+fn reports_diagnostics_for_each_file() {
+    let mut compilation = Compilation::new(CompilerOptions::default()).unwrap();
+    let a = compilation.add_file("a.esc", "let a = 5");
+    let b = compilation.add_file(
+        "b.esc",
+        "let point = {x: 5, y: 10}\nlet msg = `point = ${point}`",
+    );
+    let c = compilation.add_file("c.esc", "// @strict-returns maybe\nlet c = 5");
+
+    let result = compilation.compile();
+    assert!(!result.is_ok());
+
+    let diagnostics: Vec<_> = result
+        .diagnostics()
+        .map(|(id, diagnostic)| (id, diagnostic.code))
+        .collect();
+    assert_eq!(diagnostics, vec![(b, 1001)]);
+
+    let warnings: Vec<_> = result
+        .warnings()
+        .map(|(id, warning)| (id, warning.code))
+        .collect();
+    assert_eq!(warnings, vec![(c, 1023)]);
+
+    // Files with diagnostics still have outputs.
+    assert!(result.outputs.contains_key(&a));
+    assert!(result.outputs.contains_key(&b));
+    assert!(result.outputs.contains_key(&c));
+}
+
+#[test]
+fn errors_dont_stop_other_files_from_compiling() {
+    let mut compilation = Compilation::new(CompilerOptions::default()).unwrap();
+    let a = compilation.add_file("a.esc", "let a = ");
+    let b = compilation.add_file("b.esc", "let b: number = \"hello\"");
+    let c = compilation.add_file("c.esc", "let c = 5");
+
+    let result = compilation.compile();
+    assert!(!result.is_ok());
+
+    assert!(matches!(result.errors[&a], CompileError::ParseError(_)));
+    assert!(matches!(result.errors[&b], CompileError::TypeError(_)));
+    assert!(!result.errors.contains_key(&c));
+
+    assert!(!result.outputs.contains_key(&a));
+    assert!(!result.outputs.contains_key(&b));
+    insta::assert_snapshot!(result.outputs[&c].js, @r###"
+    export const c = 5;
+    "###);
+}
+
+#[test]
+fn jsx_requires_a_runtime() {
+    let options = CompilerOptions {
+        jsx: None,
+        ..Default::default()
+    };
+    let mut compilation = Compilation::new(options).unwrap();
+    let a = compilation.add_file("a.esc", "let elem = <div>hello</div>");
+
+    let result = compilation.compile();
+
+    assert!(matches!(result.errors[&a], CompileError::ParseError(_)));
+}
+
+#[test]
+fn files_are_checked_after_the_files_they_import() {
+    let mut compilation = Compilation::new(CompilerOptions::default()).unwrap();
+    let main = compilation.add_file(
+        "main.esc",
+        "import {add, type Point} from \"math.esc\"\nlet p: Point = {x: 5, y: 10}\nlet sum = add(p.x, p.y)",
+    );
+    compilation.add_file(
+        "math.esc",
+        "type Point = {x: number, y: number}\nlet add = fn (a: number, b: number) -> number => a + b",
+    );
+
+    let result = compilation.compile();
+    assert!(result.is_ok());
+
+    insta::assert_snapshot!(result.outputs[&main].js, @r###"
+    import { add } from "math.esc";
+    export const p = {
+        x: 5,
+        y: 10
+    };
+    export const sum = add(p.x, p.y);
+    "###);
+    insta::assert_snapshot!(result.outputs[&main].dts, @r###"
+    import { add, type Point } from "math.esc";
+    export declare const p: Readonly<Point>;
+    export declare const sum: number;
+    "###);
+}
+
+#[test]
+fn imports_of_removed_files_are_errors() {
+    let mut compilation = Compilation::new(CompilerOptions::default()).unwrap();
+    let a = compilation.add_file("a.esc", "let a = 5");
+    let b = compilation.add_file("b.esc", "import {a} from \"a.esc\"\nlet b = a + 1");
+
+    assert!(compilation.compile().is_ok());
+
+    compilation.remove_file(a);
+    let result = compilation.compile();
+
+    assert!(matches!(result.errors[&b], CompileError::TypeError(_)));
+}
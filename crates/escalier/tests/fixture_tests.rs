@@ -5,6 +5,7 @@ use std::io::Write;
 use std::path::PathBuf;
 use std::str;
 
+use escalier_ast::CompilerOptions;
 use escalier_hm::checker::{Checker, Report};
 use escalier_interop::parse::parse_dts;
 
@@ -115,7 +116,8 @@ pub fn all_reports_to_string(src: &str, checker: &Checker) -> String {
 }
 
 fn compile(input: &str, lib: &str) -> (String, String, String, String) {
-    let mut script = match escalier_parser::parse(input) {
+    let options = CompilerOptions::default();
+    let mut script = match escalier_parser::parse(input, &options) {
         Ok(script) => script,
         Err(error) => {
             return (
@@ -127,12 +129,16 @@ fn compile(input: &str, lib: &str) -> (String, String, String, String) {
         }
     };
 
-    let (js, srcmap) = escalier_codegen::js::codegen_js(input, &script);
-
     // TODO: return errors as part of CompileResult
     let (mut checker, mut ctx) = parse_dts(lib).unwrap();
 
-    match checker.infer_script(&mut script, &mut ctx) {
+    let result = checker.infer_script(&mut script, &mut ctx, &options);
+
+    // Codegen has to happen after type checking since the checker desugars
+    // overloaded operators into method calls.
+    let (js, srcmap) = escalier_codegen::js::codegen_js(input, &script, &options);
+
+    match result {
         Ok(_) => (),
         Err(error) => {
             return (
@@ -148,7 +154,7 @@ fn compile(input: &str, lib: &str) -> (String, String, String, String) {
         }
     };
 
-    let dts = match escalier_codegen::d_ts::codegen_d_ts(&script, &ctx, &checker) {
+    let dts = match escalier_codegen::d_ts::codegen_d_ts(&script, &ctx, &checker, &options) {
         Ok(value) => value,
         Err(error) => {
             return (
@@ -1,4 +1,3 @@
-;
 export const a = "boolean";
 export const b = "number";
 export const c = "string";
@@ -1,9 +1,9 @@
-export const [x, $temp_0, z] = [
+export const [x, $unused_0, z] = [
     1,
     2,
     3
 ];
-export const { a, b: $temp_1, e: f, ...rest } = {
+export const { a, b: $unused_1, e: f, ...rest } = {
     a: 1,
     b: 2,
     c: 3,
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::expr::{Expr, ExprKind};
+use crate::span::Span;
+
+// e.g. `@deprecated("use bar instead")`, the parens can be omitted when there
+// are no args.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Attribute {
+    pub name: String,
+    pub args: Vec<Expr>,
+    pub span: Span,
+}
+
+// Returns the reason given by the `@deprecated` attribute in `attrs`, which is
+// empty if no reason was given, or `None` if there isn't one.
+pub fn get_deprecation(attrs: &[Attribute]) -> Option<String> {
+    let attr = attrs.iter().find(|attr| attr.name == "deprecated")?;
+    match attr.args.first().map(|arg| &arg.kind) {
+        Some(ExprKind::Str(str)) => Some(str.value.to_owned()),
+        _ => Some("".to_string()),
+    }
+}
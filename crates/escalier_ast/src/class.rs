@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+
 use crate::block::Block;
 use crate::expr::*;
 use crate::func_param::FuncParam;
@@ -6,7 +8,7 @@ use crate::span::Span;
 use crate::type_ann::TypeAnn;
 use crate::type_param::TypeParam;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Class {
     pub span: Span,
     // pub name: Option<Ident>,
@@ -16,7 +18,7 @@ pub struct Class {
     pub body: Vec<ClassMember>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Method {
     pub span: Span,
     pub name: PropName,
@@ -26,7 +28,7 @@ pub struct Method {
     pub function: Function,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Getter {
     pub span: Span,
     pub name: PropName,
@@ -36,7 +38,7 @@ pub struct Getter {
     pub body: Block,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Setter {
     pub span: Span,
     pub name: PropName,
@@ -46,7 +48,7 @@ pub struct Setter {
     pub body: Block,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum PropName {
     Ident(Ident),
     // Str(Str),
@@ -54,7 +56,7 @@ pub enum PropName {
     Computed(Expr),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Constructor {
     pub span: Span,
     pub is_public: bool,
@@ -62,7 +64,7 @@ pub struct Constructor {
     pub body: Block,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Field {
     pub span: Span,
     pub name: Ident,
@@ -73,7 +75,7 @@ pub struct Field {
     // TODO: add `is_static` and `is_optional` fields
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ClassMember {
     Method(Method),
     Getter(Getter),
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::span::Span;
+
+// e.g. `// TODO: handle symbols`, `text` doesn't include the leading `//`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    pub text: String,
+    pub span: Span,
+}
+
+impl Comment {
+    // Doc comments start with `///`, but `////` comments aren't doc comments.
+    pub fn is_doc(&self) -> bool {
+        self.text.starts_with('/') && !self.text.starts_with("//")
+    }
+}
@@ -1,10 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use crate::attribute::{get_deprecation, Attribute};
+use crate::class::Class;
 use crate::expr::Expr;
 use crate::pattern::Pattern;
 use crate::span::Span;
 use crate::type_ann::TypeAnn;
 use crate::type_param::TypeParam;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct VarDecl {
     pub is_declare: bool,
     pub is_var: bool,
@@ -13,21 +17,76 @@ pub struct VarDecl {
     pub type_ann: Option<TypeAnn>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TypeDecl {
     pub name: String,
+    pub name_span: Span,
     pub type_ann: TypeAnn,
     pub type_params: Option<Vec<TypeParam>>,
+    // e.g. `opaque type UserId = string`, the type is only interchangeable
+    // with `type_ann` inside of the module that it's declared in.
+    pub is_opaque: bool,
+}
+
+// e.g. `declare global { let document: Document }`, adds the types and
+// values in `decls` to the global scope.  The `let` decls inside of it are
+// always `declare` decls.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct GlobalDecl {
+    pub decls: Vec<Decl>,
+}
+
+// e.g. `declare fn parseInt(value: string) -> number`, the function is
+// defined elsewhere so only its signature is given.  `type_ann` is always a
+// function type.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct FnDecl {
+    pub name: String,
+    pub name_span: Span,
+    pub type_ann: TypeAnn,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+// e.g. `declare class Map<K, V> { size: number, fn get(self, key: K) -> V }`,
+// the class is defined elsewhere so its methods don't have bodies.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ClassDecl {
+    pub name: String,
+    pub name_span: Span,
+    pub class: Class,
+}
+
+// e.g. `declare module Intl { type Locale = string, let locale: Locale }`,
+// the values in `decls` are properties of `Intl` and the types are referenced
+// as `Intl.Locale`.  Like `declare global`, all of the decls are ambient.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ModuleDecl {
+    pub name: String,
+    pub name_span: Span,
+    pub decls: Vec<Decl>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum DeclKind {
     TypeDecl(TypeDecl),
     VarDecl(VarDecl),
+    GlobalDecl(GlobalDecl),
+    FnDecl(FnDecl),
+    ClassDecl(ClassDecl),
+    ModuleDecl(ModuleDecl),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Decl {
     pub kind: DeclKind,
     pub span: Span,
+    pub is_export: bool,
+    // The text of the `///` comments before the decl with the slashes removed.
+    pub doc: Option<String>,
+    pub attrs: Vec<Attribute>,
+}
+
+impl Decl {
+    pub fn deprecation(&self) -> Option<String> {
+        get_deprecation(&self.attrs)
+    }
 }
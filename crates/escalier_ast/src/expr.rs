@@ -1,4 +1,5 @@
 use generational_arena::Index;
+use serde::{Deserialize, Serialize};
 
 use crate::block::Block;
 use crate::class::Class;
@@ -11,7 +12,7 @@ use crate::type_ann::TypeAnn;
 use crate::type_param::TypeParam;
 
 // TODO: track source location
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ObjectKey {
     Ident(Ident),
     String(String),
@@ -20,90 +21,98 @@ pub enum ObjectKey {
 }
 
 // TODO: track source location
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum Prop {
     Shorthand(Ident),
     Property { key: ObjectKey, value: Expr },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum PropOrSpread {
     Prop(Prop),
     Spread(Expr),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ExprOrSpread {
     Expr(Expr),
     Spread(Expr),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Num {
     pub value: String,
+    // The literal's source text when it's written differently from `value`,
+    // e.g. `0xFF` or `1e-3`, so that codegen can preserve it.
+    pub raw: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct BigInt {
+    pub value: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Str {
     pub span: Span,
     pub value: String,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Bool {
     pub value: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Null {}
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Undefined {}
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TemplateLiteral {
     pub parts: Vec<Str>,
     pub exprs: Vec<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TaggedTemplateLiteral {
     pub tag: Box<Expr>,
     pub template: TemplateLiteral,
     pub throws: Option<Index>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Object {
     pub properties: Vec<PropOrSpread>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Tuple {
     pub elements: Vec<ExprOrSpread>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Assign {
     pub left: Box<Expr>,
     pub op: AssignOp,
     pub right: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Binary {
     pub left: Box<Expr>,
     pub op: BinaryOp,
     pub right: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Unary {
     pub op: UnaryOp,
     pub right: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Function {
     pub type_params: Option<Vec<TypeParam>>,
     pub params: Vec<FuncParam>,
@@ -112,9 +121,20 @@ pub struct Function {
     pub throws: Option<TypeAnn>,
     pub is_async: bool,
     pub is_gen: bool,
+    // Bindings from enclosing scopes that are used by the function, this is
+    // filled in by the type checker.
+    pub captures: Option<Vec<Capture>>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Capture {
+    pub name: String,
+    // Set if the function never assigns to the binding or to any of its
+    // properties.
+    pub is_read_only: bool,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Call {
     pub callee: Box<Expr>,
     pub type_args: Option<Vec<TypeAnn>>,
@@ -123,7 +143,7 @@ pub struct Call {
     pub throws: Option<Index>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct New {
     pub callee: Box<Expr>,
     pub type_args: Option<Vec<TypeAnn>>,
@@ -131,44 +151,53 @@ pub struct New {
     pub throws: Option<Index>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Member {
     pub object: Box<Expr>,
     pub property: MemberProp,
     pub opt_chain: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MemberProp {
     Ident(Ident),
     Computed(ComputedPropName),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ComputedPropName {
     pub span: Span, // includes enclosing []
     pub expr: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct OptionalChain {
     pub base: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct IfElse {
     pub cond: Box<Expr>,
     pub consequent: Block,
     pub alternate: Option<BlockOrExpr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+// e.g. `if (let {type: "click", x, y} = event) { ... } else { ... }`
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct IfLet {
+    pub pattern: Pattern,
+    pub expr: Box<Expr>,
+    pub consequent: Block,
+    pub alternate: Option<BlockOrExpr>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Match {
     pub expr: Box<Expr>,
     pub arms: Vec<MatchArm>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Try {
     pub body: Block,
     // At least `catch` or `finally` must be present
@@ -176,33 +205,34 @@ pub struct Try {
     pub finally: Option<Block>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Do {
     pub body: Block,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Await {
     pub arg: Box<Expr>,
     // Awaiting a rejected promise turns it into a throw.
     pub throws: Option<Index>, // the type of the thrown value
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Yield {
     pub arg: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Throw {
     pub arg: Box<Expr>,
     pub throws: Option<Index>, // the type of the thrown value
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ExprKind {
     Ident(Ident),
     Num(Num),
+    BigInt(BigInt),
     Str(Str),
     Bool(Bool),
     Null(Null),
@@ -222,6 +252,7 @@ pub enum ExprKind {
     New(New),
     Member(Member),
     IfElse(IfElse),
+    IfLet(IfLet),
     Match(Match),
     Try(Try),
     Do(Do),
@@ -232,20 +263,20 @@ pub enum ExprKind {
     JSXFragment(JSXFragment),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Expr {
     pub kind: ExprKind,
     pub span: Span,
     pub inferred_type: Option<Index>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct CatchClause {
     pub param: Option<Pattern>,
     pub body: Block,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MatchArm {
     // pub loc: SourceLocation,
     pub span: Span,
@@ -254,13 +285,13 @@ pub struct MatchArm {
     pub body: BlockOrExpr,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum BlockOrExpr {
     Block(Block),
     Expr(Box<Expr>),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum AssignOp {
     Assign,
     AddAssign,
@@ -270,28 +301,37 @@ pub enum AssignOp {
     ModAssign,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum BinaryOp {
     Plus,
     Minus,
     Times,
     Divide,
     Modulo,
+    Power,
     Equals,
     NotEquals,
     LessThan,
     LessThanOrEqual,
     GreaterThan,
     GreaterThanOrEqual,
+    In,
     Or,
     And,
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    LeftShift,
+    RightShift,
+    UnsignedRightShift,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum UnaryOp {
     Plus,
     Minus,
     Not,
+    BitwiseNot,
 }
 
 impl Expr {
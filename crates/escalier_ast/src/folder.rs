@@ -0,0 +1,132 @@
+use std::mem;
+
+use crate::decl::*;
+use crate::expr::*;
+use crate::pattern::*;
+use crate::script::Script;
+use crate::stmt::*;
+use crate::type_ann::*;
+use crate::visitor_mut::*;
+
+// Like `VisitorMut`, but each node is replaced by the node it's folded into,
+// e.g. to desugar an expression into another kind of expression.  The
+// default implementations fold the children of a node and return it.
+pub trait Folder: Sized {
+    // TODO: add `fold_module`
+
+    // TODO: rename `fold_script`
+    fn fold_program(&mut self, program: Script) -> Script {
+        walk_program_fold(self, program)
+    }
+
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        walk_expr_fold(self, expr)
+    }
+
+    fn fold_pattern(&mut self, pattern: Pattern) -> Pattern {
+        walk_pattern_fold(self, pattern)
+    }
+
+    fn fold_decl(&mut self, decl: Decl) -> Decl {
+        walk_decl_fold(self, decl)
+    }
+
+    fn fold_stmt(&mut self, stmt: Stmt) -> Stmt {
+        walk_stmt_fold(self, stmt)
+    }
+
+    fn fold_type_ann(&mut self, type_ann: TypeAnn) -> TypeAnn {
+        walk_type_ann_fold(self, type_ann)
+    }
+}
+
+pub fn walk_program_fold<F: Folder>(folder: &mut F, mut program: Script) -> Script {
+    walk_program_mut(&mut FoldChildren(folder), &mut program);
+    program
+}
+
+pub fn walk_expr_fold<F: Folder>(folder: &mut F, mut expr: Expr) -> Expr {
+    walk_expr_mut(&mut FoldChildren(folder), &mut expr);
+    expr
+}
+
+pub fn walk_pattern_fold<F: Folder>(folder: &mut F, mut pattern: Pattern) -> Pattern {
+    walk_pattern_mut(&mut FoldChildren(folder), &mut pattern);
+    pattern
+}
+
+pub fn walk_decl_fold<F: Folder>(folder: &mut F, mut decl: Decl) -> Decl {
+    walk_decl_mut(&mut FoldChildren(folder), &mut decl);
+    decl
+}
+
+pub fn walk_stmt_fold<F: Folder>(folder: &mut F, mut stmt: Stmt) -> Stmt {
+    walk_stmt_mut(&mut FoldChildren(folder), &mut stmt);
+    stmt
+}
+
+pub fn walk_type_ann_fold<F: Folder>(folder: &mut F, mut type_ann: TypeAnn) -> TypeAnn {
+    walk_type_ann_mut(&mut FoldChildren(folder), &mut type_ann);
+    type_ann
+}
+
+// Folds the children that `VisitorMut` visits so that both traits walk the
+// same nodes.  Each child is swapped out for a placeholder while it's being
+// folded since folders take nodes by value.
+struct FoldChildren<'a, F: Folder>(&'a mut F);
+
+impl<F: Folder> VisitorMut for FoldChildren<'_, F> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        let placeholder = Expr {
+            kind: ExprKind::Null(Null {}),
+            span: expr.span,
+            inferred_type: None,
+        };
+        let child = mem::replace(expr, placeholder);
+        *expr = self.0.fold_expr(child);
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        let placeholder = Pattern {
+            kind: PatternKind::Wildcard,
+            span: pattern.span,
+            inferred_type: None,
+        };
+        let child = mem::replace(pattern, placeholder);
+        *pattern = self.0.fold_pattern(child);
+    }
+
+    fn visit_decl_mut(&mut self, decl: &mut Decl) {
+        let placeholder = Decl {
+            kind: DeclKind::GlobalDecl(GlobalDecl { decls: vec![] }),
+            span: decl.span,
+            is_export: false,
+            doc: None,
+            attrs: vec![],
+        };
+        let child = mem::replace(decl, placeholder);
+        *decl = self.0.fold_decl(child);
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        let placeholder = Stmt {
+            kind: StmtKind::Return(ReturnStmt { arg: None }),
+            span: stmt.span,
+            inferred_type: None,
+            leading_comments: vec![],
+            trailing_comment: None,
+        };
+        let child = mem::replace(stmt, placeholder);
+        *stmt = self.0.fold_stmt(child);
+    }
+
+    fn visit_type_ann_mut(&mut self, type_ann: &mut TypeAnn) {
+        let placeholder = TypeAnn {
+            kind: TypeAnnKind::Wildcard,
+            span: type_ann.span,
+            inferred_type: None,
+        };
+        let child = mem::replace(type_ann, placeholder);
+        *type_ann = self.0.fold_type_ann(child);
+    }
+}
@@ -1,7 +1,9 @@
+use serde::{Deserialize, Serialize};
+
 use crate::pattern::Pattern;
 use crate::type_ann::TypeAnn;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct FuncParam {
     pub pattern: Pattern,
     pub type_ann: Option<TypeAnn>,
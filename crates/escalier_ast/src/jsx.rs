@@ -1,33 +1,35 @@
+use serde::{Deserialize, Serialize};
+
 use crate::expr::Expr;
 use crate::identifier::Ident;
 use crate::span::Span;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum JSXElementName {
     Ident(Ident),
     JSXMemberExpr(JSXMemberExpr),
     // JSXNamespacedName(JSXNamespacedName),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXMemberExpr {
     pub obj: JSXObject,
     pub prop: Ident,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum JSXObject {
     JSXMemberExpr(Box<JSXMemberExpr>),
     Ident(Ident),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXAttr {
     pub name: String,
     pub value: Option<JSXAttrValue>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum JSXAttrValue {
     Str(String),
     ExprContainer(JSXExprContainer),
@@ -36,25 +38,25 @@ pub enum JSXAttrValue {
     // harder to read.
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXExprContainer {
     // pub span: Span,
     pub expr: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXOpeningElement {
     pub name: JSXElementName,
     pub attrs: Vec<JSXAttr>, // TODO: support spread
     pub self_closing: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXClosingElement {
     pub name: JSXElementName,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXElement {
     pub span: Span,
     pub opening: JSXOpeningElement,
@@ -62,7 +64,7 @@ pub struct JSXElement {
     pub closing: Option<JSXClosingElement>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXFragment {
     pub span: Span,
     pub opening: JSXOpeningFragment,
@@ -70,17 +72,17 @@ pub struct JSXFragment {
     pub closing: JSXClosingFragment,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXOpeningFragment {
     // pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXClosingFragment {
     // pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum JSXElementChild {
     Text(JSXText),
     ExprContainer(JSXExprContainer),
@@ -89,13 +91,13 @@ pub enum JSXElementChild {
     Fragment(Box<JSXFragment>),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXSpreadChild {
     // pub span: Span,
     pub expr: Box<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct JSXText {
     pub span: Span,
     pub value: String,
@@ -1,33 +1,45 @@
+pub mod attribute;
 pub mod block;
 pub mod class;
+pub mod comment;
 pub mod decl;
 pub mod expr;
+pub mod folder;
 pub mod func_param;
 pub mod identifier;
 pub mod jsx;
 pub mod literal;
 pub mod module;
+pub mod options;
 pub mod pattern;
+pub mod pragma;
 pub mod script;
 pub mod span;
 pub mod stmt;
 pub mod type_ann;
 pub mod type_param;
 pub mod visitor;
+pub mod visitor_mut;
 
+pub use attribute::*;
 pub use block::*;
 pub use class::*;
+pub use comment::*;
 pub use decl::*;
 pub use expr::*;
+pub use folder::*;
 pub use func_param::*;
 pub use identifier::*;
 pub use jsx::*;
 pub use literal::*;
 pub use module::*;
+pub use options::*;
 pub use pattern::*;
+pub use pragma::*;
 pub use script::*;
 pub use span::*;
 pub use stmt::*;
 pub use type_ann::*;
 pub use type_param::*;
 pub use visitor::*;
+pub use visitor_mut::*;
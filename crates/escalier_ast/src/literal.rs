@@ -1,10 +1,12 @@
+use serde::{Deserialize, Serialize};
 use std::fmt;
 use swc_common;
 use swc_ecma_ast::*;
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Literal {
     Number(String),
+    BigInt(String),
     String(String),
     Boolean(bool),
     Null,
@@ -15,6 +17,7 @@ impl Literal {
     pub fn get_scheme_name(&self) -> Option<&'static str> {
         match self {
             Literal::Number(_) => Some("Number"),
+            Literal::BigInt(_) => Some("BigInt"),
             Literal::String(_) => Some("String"),
             Literal::Boolean(_) => Some("Boolean"),
             Literal::Null => None,
@@ -27,6 +30,7 @@ impl fmt::Display for Literal {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Literal::Number(n) => write!(f, "{}", n),
+            Literal::BigInt(n) => write!(f, "{}n", n),
             Literal::Boolean(b) => write!(f, "{}", b),
             Literal::String(s) => write!(f, "\"{}\"", s),
             Literal::Null => write!(f, "null"),
@@ -46,6 +50,11 @@ impl From<&Literal> for swc_ecma_ast::Expr {
                 value: value.parse().unwrap(),
                 raw: None,
             }),
+            Literal::BigInt(value) => Lit::BigInt(BigInt {
+                span,
+                value: Box::new(value.parse().unwrap()),
+                raw: None,
+            }),
             Literal::String(value) => Lit::Str(Str {
                 span,
                 value: swc_atoms::JsWord::from(value.as_str()),
@@ -1,37 +1,91 @@
+use serde::{Deserialize, Serialize};
+
 use crate::decl::Decl;
 use crate::span::Span;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ImportSpecifier {
     pub local: String,            // the local name of the imported symbol
     pub imported: Option<String>, // the symbol being imported
+    // Whether the specifier is marked with `type`, e.g. `import {type Foo}`.
+    pub is_type_only: bool,
+    pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Import {
     pub specifiers: Vec<ImportSpecifier>,
     pub source: String,
+    // Whether the import is marked with `type`, e.g. `import type {Foo}`.
+    pub is_type_only: bool,
+}
+
+impl Import {
+    // The specifiers that import values.  Type-only imports are erased from
+    // the generated JS, as are imports whose specifiers are all type-only.
+    pub fn value_specifiers(&self) -> Vec<&ImportSpecifier> {
+        match self.is_type_only {
+            true => vec![],
+            false => self
+                .specifiers
+                .iter()
+                .filter(|specifier| !specifier.is_type_only)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ExportSpecifier {
+    pub local: String,            // the local name of the exported symbol
+    pub exported: Option<String>, // the name it's exported as, if different
+    // Whether the specifier is marked with `type`, e.g. `export {type Foo}`.
+    pub is_type_only: bool,
+    pub span: Span,
+}
+
+// `export {a, b as c}` or `export type {Foo}`.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct NamedExport {
+    pub specifiers: Vec<ExportSpecifier>,
+    // Whether the export is marked with `type`, e.g. `export type {Foo}`.
+    pub is_type_only: bool,
+}
+
+impl NamedExport {
+    // The specifiers that export values.  Type-only exports are erased from
+    // the generated JS.
+    pub fn value_specifiers(&self) -> Vec<&ExportSpecifier> {
+        match self.is_type_only {
+            true => vec![],
+            false => self
+                .specifiers
+                .iter()
+                .filter(|specifier| !specifier.is_type_only)
+                .collect(),
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Export {
     pub decl: Decl,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ModuleItemKind {
     Import(Import),
     Export(Export),
     Decl(Decl),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ModuleItem {
     pub kind: ModuleItemKind,
     pub span: Span,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Module {
     pub items: Vec<ModuleItem>,
 }
@@ -0,0 +1,236 @@
+use crate::pragma::Pragma;
+
+const DEFAULT_PRAGMA: &str = "React.createElement";
+const DEFAULT_PRAGMA_FRAG: &str = "React.Fragment";
+const DEFAULT_IMPORT_SOURCE: &str = "react";
+
+/// The options shared by the parser, the checker, and codegen.  Tools build
+/// these once and pass them to `parse`, `Checker::infer_script`,
+/// `codegen_js`, and `codegen_d_ts`.  Scripts can override some of them
+/// using pragmas, e.g. `// @strict-returns off`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompilerOptions {
+    /// The checks that are enabled when checking a script.
+    pub strictness: Strictness,
+    /// The version of JavaScript that the generated code is emitted for.
+    pub target: Target,
+    /// The module format of the generated code.
+    pub module_format: ModuleFormat,
+    /// The runtime to use for JSX, JSX can't be used if this is `None`.  The
+    /// runtime can be overridden in each file using pragmas, see
+    /// `JSXRuntime::with_pragmas`.
+    pub jsx: Option<JSXRuntime>,
+    /// The built-in libs to check scripts against.  The prelude is always
+    /// loaded.
+    pub libs: Vec<Lib>,
+}
+
+impl Default for CompilerOptions {
+    fn default() -> Self {
+        CompilerOptions {
+            strictness: Strictness::default(),
+            target: Target::default(),
+            module_format: ModuleFormat::default(),
+            jsx: Some(JSXRuntime::default()),
+            libs: vec![],
+        }
+    }
+}
+
+/// The optional checks done by the checker.  Each of these can be overridden
+/// in each file using pragmas, see `Checker::apply_pragmas`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Strictness {
+    pub interpolation_strictness: InterpolationStrictness,
+    pub index_access: IndexAccess,
+    /// Enables the `int` and `float` refinements of `number`.  When disabled
+    /// `int` and `float` type annotations are treated as `number`.
+    pub numeric_refinements: bool,
+    /// Reports functions whose end is reachable if they return values
+    /// elsewhere or their return type doesn't include `undefined`.  When
+    /// disabled `undefined` is added to the return type instead.
+    pub strict_returns: bool,
+    /// Reports conditions of `if` expressions whose types aren't `boolean`.
+    /// When disabled any value can be used as a condition, in which case its
+    /// truthiness is checked.
+    pub strict_boolean_expressions: bool,
+    /// Warns about `let` decls that shadow other bindings unless their
+    /// initializers use the bindings they shadow, e.g. `let x = parse(x)`.
+    pub warn_on_shadowing: bool,
+    /// Warns about intersection type annotations that are `never` because
+    /// their members don't have any values in common, e.g. `number & string`.
+    pub warn_on_empty_intersections: bool,
+}
+
+/// Controls which types are allowed to be interpolated in template literals.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InterpolationStrictness {
+    /// Any type can be interpolated.
+    Off,
+    /// `string`, `number`, `boolean`, and types with a `toString` method can
+    /// be interpolated.
+    #[default]
+    Loose,
+    /// Only `string` and `number` can be interpolated.
+    Strict,
+}
+
+/// Controls whether index accesses whose keys may not exist, e.g. `arr[i]`
+/// and `dict[key]`, include `undefined` in their types.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IndexAccess {
+    /// `undefined` is included unless the access is known to be safe, e.g.
+    /// it's inside of `if (i < arr.length) { ... }` or
+    /// `if (key in dict) { ... }`.
+    #[default]
+    Checked,
+    /// Keys are assumed to exist.
+    Unchecked,
+}
+
+/// The version of JavaScript that the generated code is emitted for.  The
+/// generated code isn't downleveled so this only affects how it's printed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Target {
+    Es2015,
+    Es2020,
+    #[default]
+    EsNext,
+}
+
+/// Determines how top-level bindings are exposed by the generated code.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ModuleFormat {
+    /// Top-level bindings are exported using `export` declarations.
+    #[default]
+    Esm,
+    /// Top-level bindings are exported by assigning them to `module.exports`
+    /// and imports are converted to calls to `require()`.
+    CommonJs,
+    /// The code is wrapped in an immediately invoked function expression so
+    /// that it can be loaded using a `<script>` tag.  Top-level bindings
+    /// aren't exported and imports aren't supported, which means that JSX
+    /// requires the classic runtime.
+    Iife,
+}
+
+/// Determines how JSX elements are lowered to function calls.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JSXRuntime {
+    /// Elements are lowered to calls to `pragma`, e.g. `React.createElement`
+    /// or `h` when using Preact, and fragments use `pragma_frag`.
+    Classic { pragma: String, pragma_frag: String },
+    /// Elements are lowered to calls to `jsx` and `jsxs` which are imported
+    /// from `<import_source>/jsx-runtime`.
+    Automatic { import_source: String },
+}
+
+impl Default for JSXRuntime {
+    fn default() -> Self {
+        JSXRuntime::Automatic {
+            import_source: DEFAULT_IMPORT_SOURCE.to_string(),
+        }
+    }
+}
+
+impl JSXRuntime {
+    pub fn classic() -> Self {
+        JSXRuntime::Classic {
+            pragma: DEFAULT_PRAGMA.to_string(),
+            pragma_frag: DEFAULT_PRAGMA_FRAG.to_string(),
+        }
+    }
+
+    /// Overrides `self` with the JSX pragmas at the start of a script.
+    ///
+    /// The following pragmas are supported:
+    /// - `// @jsxRuntime classic` or `// @jsxRuntime automatic`
+    /// - `// @jsx h` sets the factory used by the classic runtime
+    /// - `// @jsxFrag Fragment` sets the fragment used by the classic runtime
+    /// - `// @jsxImportSource preact` sets the import source used by the
+    ///   automatic runtime
+    ///
+    /// `@jsx` and `@jsxFrag` imply the classic runtime and `@jsxImportSource`
+    /// implies the automatic runtime unless `@jsxRuntime` says otherwise.
+    pub fn with_pragmas(&self, pragmas: &[Pragma]) -> Self {
+        let mut runtime: Option<&str> = None;
+        let mut pragma: Option<&str> = None;
+        let mut pragma_frag: Option<&str> = None;
+        let mut import_source: Option<&str> = None;
+
+        for Pragma { name, value, .. } in pragmas {
+            let setting = match name.as_str() {
+                "jsxRuntime" => &mut runtime,
+                "jsx" => &mut pragma,
+                "jsxFrag" => &mut pragma_frag,
+                "jsxImportSource" => &mut import_source,
+                _ => continue,
+            };
+            *setting = value.as_deref();
+        }
+
+        let is_classic = match runtime {
+            Some("classic") => true,
+            Some("automatic") => false,
+            _ => match self {
+                JSXRuntime::Classic { .. } => import_source.is_none(),
+                JSXRuntime::Automatic { .. } => pragma.is_some() || pragma_frag.is_some(),
+            },
+        };
+
+        let (default_pragma, default_pragma_frag) = match self {
+            JSXRuntime::Classic {
+                pragma,
+                pragma_frag,
+            } => (pragma.as_str(), pragma_frag.as_str()),
+            JSXRuntime::Automatic { .. } => (DEFAULT_PRAGMA, DEFAULT_PRAGMA_FRAG),
+        };
+        let default_import_source = match self {
+            JSXRuntime::Classic { .. } => DEFAULT_IMPORT_SOURCE,
+            JSXRuntime::Automatic { import_source } => import_source.as_str(),
+        };
+
+        match is_classic {
+            true => JSXRuntime::Classic {
+                pragma: pragma.unwrap_or(default_pragma).to_string(),
+                pragma_frag: pragma_frag.unwrap_or(default_pragma_frag).to_string(),
+            },
+            false => JSXRuntime::Automatic {
+                import_source: import_source.unwrap_or(default_import_source).to_string(),
+            },
+        }
+    }
+}
+
+/// Built-in type environments that can be loaded by the checker before
+/// checking a program.  These are handwritten subsets of the corresponding
+/// lib.d.ts files, a full lib.d.ts file can be loaded using `escalier_interop`
+/// instead.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Lib {
+    Es2015,
+    Es2020,
+    Dom,
+    Node,
+}
+
+impl Lib {
+    pub fn from_name(name: &str) -> Option<Lib> {
+        match name {
+            "es2015" => Some(Lib::Es2015),
+            "es2020" => Some(Lib::Es2020),
+            "dom" => Some(Lib::Dom),
+            "node" => Some(Lib::Node),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lib::Es2015 => "es2015",
+            Lib::Es2020 => "es2020",
+            Lib::Dom => "dom",
+            Lib::Node => "node",
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use generational_arena::Index;
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use crate::expr::Expr;
 use crate::identifier::{BindingIdent, Ident};
 use crate::literal::Literal;
 use crate::span::*;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum PatternKind {
     Ident(BindingIdent),
     Rest(RestPat),
@@ -13,12 +15,13 @@ pub enum PatternKind {
     Tuple(TuplePat),
     Lit(LitPat),
     Is(IsPat),
+    Range(RangePat),
     Wildcard,
     // This can't be used at the top level similar to rest
     // Assign(AssignPat),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Pattern {
     pub kind: PatternKind,
     pub span: Span,
@@ -32,52 +35,108 @@ impl Pattern {
             _ => format!("arg{index}"),
         }
     }
+
+    // Returns true if the pattern only matches some of the values of its type
+    // because it contains literal, `is`, or range patterns.
+    pub fn is_refinement(&self) -> bool {
+        match &self.kind {
+            PatternKind::Lit(_) | PatternKind::Is(_) | PatternKind::Range(_) => true,
+            PatternKind::Ident(_) | PatternKind::Wildcard => false,
+            PatternKind::Rest(RestPat { arg }) => arg.is_refinement(),
+            PatternKind::Object(ObjectPat { props, .. }) => props.iter().any(|prop| match prop {
+                ObjectPatProp::KeyValue(KeyValuePatProp { value, .. }) => value.is_refinement(),
+                ObjectPatProp::Shorthand(_) => false,
+                ObjectPatProp::Rest(RestPat { arg }) => arg.is_refinement(),
+            }),
+            PatternKind::Tuple(TuplePat { elems, .. }) => elems
+                .iter()
+                .flatten()
+                .any(|elem| elem.pattern.is_refinement()),
+        }
+    }
+
+    // Returns true if any of the bindings introduced by the pattern are `mut`.
+    pub fn has_mut_bindings(&self) -> bool {
+        match &self.kind {
+            PatternKind::Ident(BindingIdent { mutable, .. }) => *mutable,
+            PatternKind::Is(IsPat { ident, .. }) => ident.mutable,
+            PatternKind::Lit(_) | PatternKind::Range(_) | PatternKind::Wildcard => false,
+            PatternKind::Rest(RestPat { arg }) => arg.has_mut_bindings(),
+            PatternKind::Object(ObjectPat { props, .. }) => props.iter().any(|prop| match prop {
+                ObjectPatProp::KeyValue(KeyValuePatProp { value, .. }) => value.has_mut_bindings(),
+                ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) => ident.mutable,
+                ObjectPatProp::Rest(RestPat { arg }) => arg.has_mut_bindings(),
+            }),
+            PatternKind::Tuple(TuplePat { elems, .. }) => elems
+                .iter()
+                .flatten()
+                .any(|elem| elem.pattern.has_mut_bindings()),
+        }
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct LitPat {
     pub lit: Literal,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct IsPat {
     pub ident: BindingIdent,
     pub is_id: Ident,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Matches numbers within a range, e.g. `0..10`, `0..=9`, `..0`, or `10..`.
+// The bounds are the values of number literals.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RangePat {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub inclusive: bool,
+}
+
+impl fmt::Display for RangePat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let op = if self.inclusive { "..=" } else { ".." };
+        let start = self.start.as_deref().unwrap_or("");
+        let end = self.end.as_deref().unwrap_or("");
+        write!(f, "{start}{op}{end}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RestPat {
     pub arg: Box<Pattern>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TuplePat {
     // The elements are optional to support sparse arrays.
     pub elems: Vec<Option<TuplePatElem>>,
     pub optional: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TuplePatElem {
     // TODO: add .span property
     pub pattern: Pattern,
     pub init: Option<Box<Expr>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ObjectPat {
     pub props: Vec<ObjectPatProp>,
     pub optional: bool,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ObjectPatProp {
     KeyValue(KeyValuePatProp),
     Shorthand(ShorthandPatProp),
     Rest(RestPat), // TODO: create a new RestPatProp that includes a span
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct KeyValuePatProp {
     // pub loc: SourceLocation,
     pub span: Span,
@@ -86,7 +145,7 @@ pub struct KeyValuePatProp {
     pub init: Option<Box<Expr>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ShorthandPatProp {
     // pub loc: SourceLocation,
     pub span: Span,
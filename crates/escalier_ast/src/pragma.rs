@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::span::Span;
+
+// e.g. `// @strict-returns off`, a comment before the first statement of a
+// script that overrides one of the options used to check or compile it.
+// `value` is the word after the name unless that word is another pragma.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct Pragma {
+    pub name: String,
+    pub value: Option<String>,
+    pub span: Span,
+}
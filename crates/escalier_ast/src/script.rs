@@ -1,6 +1,30 @@
-use crate::stmt::Stmt;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+use crate::decl::Decl;
+use crate::pragma::Pragma;
+use crate::stmt::{Stmt, StmtKind};
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Script {
     pub stmts: Vec<Stmt>,
+    // The pragmas in the comments before the first statement, in the order
+    // they appear.  Later pragmas override earlier ones with the same name.
+    pub pragmas: Vec<Pragma>,
+}
+
+impl Script {
+    // Scripts that don't use `export` anywhere export all of their top-level
+    // decls, otherwise only decls marked with `export` and the names listed
+    // in `export {...}` are exported.
+    pub fn exports_all(&self) -> bool {
+        !self.stmts.iter().any(|stmt| {
+            matches!(
+                &stmt.kind,
+                StmtKind::Decl(Decl {
+                    is_export: true,
+                    ..
+                }) | StmtKind::Export(_)
+            )
+        })
+    }
 }
@@ -1,34 +1,49 @@
 use generational_arena::Index;
+use serde::{Deserialize, Serialize};
 
 use crate::block::Block;
+use crate::comment::Comment;
 use crate::decl::*;
 use crate::expr::Expr;
+use crate::module::{Import, NamedExport};
 use crate::pattern::Pattern;
 use crate::span::Span;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ExprStmt {
     pub expr: Expr,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ForStmt {
     pub left: Box<Pattern>,
     pub right: Box<Expr>,
     pub body: Block,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ReturnStmt {
     pub arg: Option<Expr>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+// e.g. `let [first, ...rest] = items else { return 0 }`, the `else` block
+// must return or throw if the pattern doesn't match.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct LetElseStmt {
+    pub pattern: Pattern,
+    pub expr: Expr,
+    pub alternate: Block,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum StmtKind {
     Expr(ExprStmt),
     For(ForStmt),
     Return(ReturnStmt),
+    LetElse(LetElseStmt),
     Decl(Decl),
+    Import(Import),
+    Export(NamedExport),
     // VarDecl(VarDecl),
     // TypeDecl(TypeDecl),
     // TODO:
@@ -37,9 +52,13 @@ pub enum StmtKind {
     // - class decls: `class Foo {}` desugars to `let Foo = class {}`
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Stmt {
     pub kind: StmtKind,
     pub span: Span,
     pub inferred_type: Option<Index>,
+    // Comments on the lines before the statement.
+    pub leading_comments: Vec<Comment>,
+    // A comment after the statement on the same line.
+    pub trailing_comment: Option<Comment>,
 }
@@ -1,20 +1,21 @@
 use generational_arena::Index;
+use serde::{Deserialize, Serialize};
 
-use crate::expr::BinaryOp;
+use crate::class::PropName;
+use crate::expr::{BinaryOp, Expr};
 // use crate::func_param::FuncParam;
-use crate::identifier::Ident;
 use crate::pattern::Pattern;
 use crate::span::*;
 use crate::type_param::TypeParam;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TypeAnnFuncParam {
     pub pattern: Pattern,
     pub type_ann: TypeAnn,
     pub optional: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum ObjectProp {
     Call(FunctionType),
     Constructor(FunctionType),
@@ -25,10 +26,10 @@ pub enum ObjectProp {
     Prop(Prop),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MethodType {
     pub span: Span,
-    pub name: String,
+    pub name: PropName,
     pub type_params: Option<Vec<TypeParam>>,
     pub params: Vec<TypeAnnFuncParam>,
     pub ret: Box<TypeAnn>,
@@ -36,14 +37,14 @@ pub struct MethodType {
     pub mutates: bool,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct GetterType {
     pub span: Span,
     pub name: String,
     pub ret: Box<TypeAnn>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct SetterType {
     pub span: Span,
     pub name: String,
@@ -51,29 +52,29 @@ pub struct SetterType {
 }
 
 // TODO: dedupe with TPropModifier
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum PropModifier {
     Getter,
     Setter,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Prop {
     pub span: Span,
-    pub name: String,
+    pub name: PropName,
     pub modifier: Option<PropModifier>,
     pub optional: bool,
     pub readonly: bool,
     pub type_ann: Box<TypeAnn>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum MappedModifier {
     Add,
     Remove,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct Mapped {
     pub key: Box<TypeAnn>,
     pub value: Box<TypeAnn>,
@@ -87,7 +88,7 @@ pub struct Mapped {
     pub extends: Option<Box<TypeAnn>>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct FunctionType {
     pub span: Span,
     pub type_params: Option<Vec<TypeParam>>,
@@ -96,7 +97,7 @@ pub struct FunctionType {
     pub throws: Option<Box<TypeAnn>>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct ConditionType {
     pub check: Box<TypeAnn>,
     pub extends: Box<TypeAnn>,
@@ -104,34 +105,37 @@ pub struct ConditionType {
     pub false_type: Box<TypeAnn>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MatchType {
     pub matchable: Box<TypeAnn>,
     pub cases: Vec<MatchTypeCase>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct MatchTypeCase {
     pub extends: Box<TypeAnn>,
     pub true_type: Box<TypeAnn>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct BinaryTypeAnn {
     pub left: Box<TypeAnn>,
     pub op: BinaryOp,
     pub right: Box<TypeAnn>,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub enum TypeAnnKind {
     BoolLit(bool),
     Boolean,
     NumLit(String),
     Number,
+    BigIntLit(String),
+    BigInt,
     StrLit(String),
     String,
     Symbol,
+    UniqueSymbol,
     Null,
     Undefined,
     Unknown,
@@ -146,7 +150,10 @@ pub enum TypeAnnKind {
     IndexedAccess(Box<TypeAnn>, Box<TypeAnn>),
     KeyOf(Box<TypeAnn>),
     Rest(Box<TypeAnn>),
-    TypeOf(Ident),
+    Readonly(Box<TypeAnn>), // Array or Tuple
+    // Only used for tuple elements, e.g. `[x: number, y: number]`
+    Labeled(String, Box<TypeAnn>),
+    TypeOf(Box<Expr>), // Ident or Member
     Condition(ConditionType),
     Match(MatchType),
     Wildcard,
@@ -154,7 +161,7 @@ pub enum TypeAnnKind {
     Binary(BinaryTypeAnn),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TypeAnn {
     pub kind: TypeAnnKind,
     pub span: Span,
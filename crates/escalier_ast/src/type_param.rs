@@ -1,10 +1,15 @@
+use serde::{Deserialize, Serialize};
+
 use crate::span::Span;
 use crate::type_ann::TypeAnn;
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct TypeParam {
     pub span: Span,
     pub name: String,
+    // The number of type args that the param takes if it's a type
+    // constructor, e.g. 1 for `F` in `F<_>`, otherwise 0.
+    pub arity: usize,
     pub bound: Option<TypeAnn>,
     pub default: Option<TypeAnn>,
 }
@@ -46,6 +46,7 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr) {
     match &expr.kind {
         crate::ExprKind::Ident(_) => {}
         crate::ExprKind::Num(_) => {}
+        crate::ExprKind::BigInt(_) => {}
         crate::ExprKind::Str(_) => {}
         crate::ExprKind::Bool(_) => {}
         crate::ExprKind::Null(_) => {}
@@ -106,6 +107,7 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr) {
             throws,
             is_async: _,
             is_gen: _,
+            captures: _,
         }) => {
             if let Some(type_params) = type_params {
                 for type_param in type_params {
@@ -226,6 +228,19 @@ pub fn walk_expr<V: Visitor>(visitor: &mut V, expr: &Expr) {
                 walk_block_or_expr(visitor, alternate);
             }
         }
+        crate::ExprKind::IfLet(IfLet {
+            pattern,
+            expr,
+            consequent,
+            alternate,
+        }) => {
+            visitor.visit_pattern(pattern);
+            visitor.visit_expr(expr);
+            walk_block(visitor, consequent);
+            if let Some(alternate) = alternate {
+                walk_block_or_expr(visitor, alternate);
+            }
+        }
         crate::ExprKind::Match(Match { expr, arms }) => {
             visitor.visit_expr(expr);
             for MatchArm {
@@ -302,6 +317,7 @@ pub fn walk_pattern<V: Visitor>(visitor: &mut V, pattern: &Pattern) {
         }
         crate::PatternKind::Lit(_) => {}
         crate::PatternKind::Is(_) => {}
+        crate::PatternKind::Range(_) => {}
         crate::PatternKind::Wildcard => {}
     }
 }
@@ -325,8 +341,10 @@ pub fn walk_decl<V: Visitor>(visitor: &mut V, decl: &Decl) {
         }
         DeclKind::TypeDecl(TypeDecl {
             name: _,
+            name_span: _,
             type_ann,
             type_params,
+            is_opaque: _,
         }) => {
             if let Some(type_params) = type_params {
                 for type_param in type_params {
@@ -340,6 +358,41 @@ pub fn walk_decl<V: Visitor>(visitor: &mut V, decl: &Decl) {
             }
             visitor.visit_type_ann(type_ann);
         }
+        DeclKind::GlobalDecl(crate::GlobalDecl { decls }) => {
+            for decl in decls {
+                visitor.visit_decl(decl);
+            }
+        }
+        DeclKind::FnDecl(crate::FnDecl {
+            name: _,
+            name_span: _,
+            type_ann,
+        }) => visitor.visit_type_ann(type_ann),
+        DeclKind::ClassDecl(crate::ClassDecl {
+            name: _,
+            name_span: _,
+            class,
+        }) => {
+            if let Some(type_params) = &class.type_params {
+                for type_param in type_params {
+                    if let Some(bound) = &type_param.bound {
+                        visitor.visit_type_ann(bound);
+                    }
+                    if let Some(default) = &type_param.default {
+                        visitor.visit_type_ann(default);
+                    }
+                }
+            }
+        }
+        DeclKind::ModuleDecl(crate::ModuleDecl {
+            name: _,
+            name_span: _,
+            decls,
+        }) => {
+            for decl in decls {
+                visitor.visit_decl(decl);
+            }
+        }
     }
 }
 
@@ -356,7 +409,17 @@ pub fn walk_stmt<V: Visitor>(visitor: &mut V, stmt: &Stmt) {
                 visitor.visit_expr(arg);
             }
         }
+        StmtKind::LetElse(LetElseStmt {
+            pattern,
+            expr,
+            alternate,
+        }) => {
+            visitor.visit_pattern(pattern);
+            visitor.visit_expr(expr);
+            walk_block(visitor, alternate);
+        }
         StmtKind::Decl(decl) => visitor.visit_decl(decl),
+        StmtKind::Import(_) | StmtKind::Export(_) => {}
     }
 }
 
@@ -367,9 +430,12 @@ pub fn walk_type_ann<V: Visitor>(_visitor: &mut V, type_ann: &TypeAnn) {
         crate::TypeAnnKind::Boolean => {}
         crate::TypeAnnKind::NumLit(_) => {}
         crate::TypeAnnKind::Number => {}
+        crate::TypeAnnKind::BigIntLit(_) => {}
+        crate::TypeAnnKind::BigInt => {}
         crate::TypeAnnKind::StrLit(_) => {}
         crate::TypeAnnKind::String => {}
         crate::TypeAnnKind::Symbol => {}
+        crate::TypeAnnKind::UniqueSymbol => {}
         crate::TypeAnnKind::Null => {}
         crate::TypeAnnKind::Undefined => {}
         crate::TypeAnnKind::Unknown => {}
@@ -384,6 +450,8 @@ pub fn walk_type_ann<V: Visitor>(_visitor: &mut V, type_ann: &TypeAnn) {
         crate::TypeAnnKind::IndexedAccess(_, _) => {}
         crate::TypeAnnKind::KeyOf(_) => {}
         crate::TypeAnnKind::Rest(_) => {}
+        crate::TypeAnnKind::Readonly(_) => {}
+        crate::TypeAnnKind::Labeled(_, _) => {}
         crate::TypeAnnKind::TypeOf(_) => {}
         crate::TypeAnnKind::Condition(_) => {}
         crate::TypeAnnKind::Match(_) => {}
@@ -0,0 +1,477 @@
+use crate::block::Block;
+use crate::class::*;
+use crate::decl::*;
+use crate::expr::*;
+use crate::pattern::*;
+use crate::script::Script;
+use crate::stmt::*;
+use crate::type_ann::TypeAnn;
+
+// Like `Visitor`, but allows the nodes being visited to be modified in place,
+// e.g. to update the `inferred_type` of nodes or to desugar expressions.
+pub trait VisitorMut: Sized {
+    // TODO: add `visit_module`
+
+    // TODO: rename `visit_script`
+    fn visit_program_mut(&mut self, program: &mut Script) {
+        walk_program_mut(self, program)
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        walk_expr_mut(self, expr)
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        walk_pattern_mut(self, pattern)
+    }
+
+    fn visit_decl_mut(&mut self, decl: &mut Decl) {
+        walk_decl_mut(self, decl)
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        walk_stmt_mut(self, stmt)
+    }
+
+    fn visit_type_ann_mut(&mut self, type_ann: &mut TypeAnn) {
+        walk_type_ann_mut(self, type_ann)
+    }
+}
+
+pub fn walk_program_mut<V: VisitorMut>(visitor: &mut V, program: &mut Script) {
+    for stmt in &mut program.stmts {
+        visitor.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn walk_expr_mut<V: VisitorMut>(visitor: &mut V, expr: &mut Expr) {
+    match &mut expr.kind {
+        crate::ExprKind::Ident(_) => {}
+        crate::ExprKind::Num(_) => {}
+        crate::ExprKind::BigInt(_) => {}
+        crate::ExprKind::Str(_) => {}
+        crate::ExprKind::Bool(_) => {}
+        crate::ExprKind::Null(_) => {}
+        crate::ExprKind::Undefined(_) => {}
+        crate::ExprKind::TemplateLiteral(TemplateLiteral { parts: _, exprs }) => {
+            for expr in exprs {
+                visitor.visit_expr_mut(expr);
+            }
+        }
+        crate::ExprKind::TaggedTemplateLiteral(TaggedTemplateLiteral {
+            tag,
+            template,
+            throws: _,
+        }) => {
+            visitor.visit_expr_mut(tag);
+
+            for expr in &mut template.exprs {
+                visitor.visit_expr_mut(expr);
+            }
+        }
+        crate::ExprKind::Object(Object { properties }) => {
+            for prop in properties {
+                match prop {
+                    crate::PropOrSpread::Prop(prop) => match prop {
+                        Prop::Shorthand(_) => {}
+                        Prop::Property { key: _, value } => {
+                            visitor.visit_expr_mut(value);
+                        }
+                    },
+                    crate::PropOrSpread::Spread(expr) => visitor.visit_expr_mut(expr),
+                }
+            }
+        }
+        crate::ExprKind::Tuple(Tuple { elements }) => {
+            for expr in elements {
+                match expr {
+                    ExprOrSpread::Expr(expr) => visitor.visit_expr_mut(expr),
+                    ExprOrSpread::Spread(expr) => visitor.visit_expr_mut(expr),
+                }
+            }
+        }
+        crate::ExprKind::Assign(Assign { left, op: _, right }) => {
+            visitor.visit_expr_mut(left);
+            visitor.visit_expr_mut(right);
+        }
+        crate::ExprKind::Binary(Binary { left, op: _, right }) => {
+            visitor.visit_expr_mut(left);
+            visitor.visit_expr_mut(right);
+        }
+        crate::ExprKind::Unary(Unary { op: _, right }) => {
+            visitor.visit_expr_mut(right);
+        }
+        crate::ExprKind::Function(Function {
+            type_params,
+            params,
+            body,
+            type_ann,
+            throws,
+            is_async: _,
+            is_gen: _,
+            captures: _,
+        }) => {
+            if let Some(type_params) = type_params {
+                for type_param in type_params {
+                    if let Some(bound) = &mut type_param.bound {
+                        visitor.visit_type_ann_mut(bound);
+                    }
+                    if let Some(default) = &mut type_param.default {
+                        visitor.visit_type_ann_mut(default);
+                    }
+                }
+            }
+
+            for param in params {
+                visitor.visit_pattern_mut(&mut param.pattern);
+                if let Some(type_ann) = &mut param.type_ann {
+                    visitor.visit_type_ann_mut(type_ann);
+                }
+            }
+
+            walk_block_or_expr_mut(visitor, body);
+
+            if let Some(type_ann) = type_ann {
+                visitor.visit_type_ann_mut(type_ann);
+            }
+
+            if let Some(throws) = throws {
+                visitor.visit_type_ann_mut(throws);
+            }
+        }
+        crate::ExprKind::Class(Class {
+            span: _,
+            type_params,
+            super_class: _,
+            super_type_args,
+            body,
+        }) => {
+            if let Some(type_params) = type_params {
+                for type_param in type_params {
+                    if let Some(bound) = &mut type_param.bound {
+                        visitor.visit_type_ann_mut(bound);
+                    }
+                    if let Some(default) = &mut type_param.default {
+                        visitor.visit_type_ann_mut(default);
+                    }
+                }
+            }
+
+            if let Some(super_type_args) = super_type_args {
+                for type_arg in super_type_args {
+                    visitor.visit_type_ann_mut(type_arg);
+                }
+            }
+
+            // TODO
+            for member in body {
+                match member {
+                    ClassMember::Method(_) => {}
+                    ClassMember::Getter(_) => {}
+                    ClassMember::Setter(_) => {}
+                    ClassMember::Field(_) => {}
+                }
+            }
+        }
+        crate::ExprKind::Call(Call {
+            callee,
+            type_args,
+            args,
+            opt_chain: _, // TODO
+            throws: _,    // TODO
+        }) => {
+            visitor.visit_expr_mut(callee);
+            if let Some(type_args) = type_args {
+                for type_arg in type_args {
+                    visitor.visit_type_ann_mut(type_arg);
+                }
+            }
+            for arg in args {
+                visitor.visit_expr_mut(arg);
+            }
+        }
+        crate::ExprKind::New(New {
+            callee,
+            type_args,
+            args,
+            throws: _, // TODO
+        }) => {
+            visitor.visit_expr_mut(callee);
+            if let Some(type_args) = type_args {
+                for type_arg in type_args {
+                    visitor.visit_type_ann_mut(type_arg);
+                }
+            }
+            for arg in args {
+                visitor.visit_expr_mut(arg);
+            }
+        }
+        crate::ExprKind::Member(Member {
+            object,
+            property,
+            opt_chain: _,
+        }) => {
+            visitor.visit_expr_mut(object);
+            match property {
+                MemberProp::Ident(_) => {}
+                MemberProp::Computed(ComputedPropName { span: _, expr }) => {
+                    visitor.visit_expr_mut(expr)
+                }
+            };
+        }
+        crate::ExprKind::IfElse(IfElse {
+            cond,
+            consequent,
+            alternate,
+        }) => {
+            visitor.visit_expr_mut(cond);
+            walk_block_mut(visitor, consequent);
+            if let Some(alternate) = alternate {
+                walk_block_or_expr_mut(visitor, alternate);
+            }
+        }
+        crate::ExprKind::IfLet(IfLet {
+            pattern,
+            expr,
+            consequent,
+            alternate,
+        }) => {
+            visitor.visit_pattern_mut(pattern);
+            visitor.visit_expr_mut(expr);
+            walk_block_mut(visitor, consequent);
+            if let Some(alternate) = alternate {
+                walk_block_or_expr_mut(visitor, alternate);
+            }
+        }
+        crate::ExprKind::Match(Match { expr, arms }) => {
+            visitor.visit_expr_mut(expr);
+            for MatchArm {
+                span: _,
+                pattern,
+                guard,
+                body,
+            } in arms
+            {
+                visitor.visit_pattern_mut(pattern);
+                if let Some(guard) = guard {
+                    visitor.visit_expr_mut(guard);
+                }
+                walk_block_or_expr_mut(visitor, body);
+            }
+        }
+        crate::ExprKind::Try(Try {
+            body,
+            catch,
+            finally,
+        }) => {
+            walk_block_mut(visitor, body);
+            if let Some(catch) = catch {
+                if let Some(param) = &mut catch.param {
+                    visitor.visit_pattern_mut(param);
+                }
+                walk_block_mut(visitor, &mut catch.body);
+            }
+            if let Some(finally) = finally {
+                walk_block_mut(visitor, finally);
+            }
+        }
+        crate::ExprKind::Do(Do { body }) => walk_block_mut(visitor, body),
+        crate::ExprKind::Await(Await { arg, throws: _ }) => visitor.visit_expr_mut(arg),
+        crate::ExprKind::Yield(Yield { arg }) => visitor.visit_expr_mut(arg),
+        crate::ExprKind::Throw(Throw { arg, throws: _ }) => visitor.visit_expr_mut(arg),
+        crate::ExprKind::JSXElement(_) => {}  // TODO
+        crate::ExprKind::JSXFragment(_) => {} // TODO
+    }
+}
+
+pub fn walk_pattern_mut<V: VisitorMut>(visitor: &mut V, pattern: &mut Pattern) {
+    match &mut pattern.kind {
+        crate::PatternKind::Ident(_) => {}
+        crate::PatternKind::Rest(RestPat { arg }) => visitor.visit_pattern_mut(arg),
+        crate::PatternKind::Object(ObjectPat { props, optional: _ }) => {
+            for prop in props {
+                match prop {
+                    ObjectPatProp::KeyValue(KeyValuePatProp {
+                        span: _,
+                        key: _,
+                        value,
+                        init,
+                    }) => {
+                        visitor.visit_pattern_mut(value);
+                        if let Some(init) = init {
+                            visitor.visit_expr_mut(init);
+                        }
+                    }
+                    ObjectPatProp::Shorthand(_) => {}
+                    ObjectPatProp::Rest(RestPat { arg }) => {
+                        visitor.visit_pattern_mut(arg);
+                    }
+                }
+            }
+        }
+        crate::PatternKind::Tuple(TuplePat { elems, optional: _ }) => {
+            for elem in elems.iter_mut().flatten() {
+                visitor.visit_pattern_mut(&mut elem.pattern);
+                if let Some(init) = &mut elem.init {
+                    visitor.visit_expr_mut(init);
+                }
+            }
+        }
+        crate::PatternKind::Lit(_) => {}
+        crate::PatternKind::Is(_) => {}
+        crate::PatternKind::Range(_) => {}
+        crate::PatternKind::Wildcard => {}
+    }
+}
+
+pub fn walk_decl_mut<V: VisitorMut>(visitor: &mut V, decl: &mut Decl) {
+    match &mut decl.kind {
+        DeclKind::VarDecl(crate::VarDecl {
+            is_declare: _,
+            is_var: _,
+            pattern,
+            expr,
+            type_ann,
+        }) => {
+            visitor.visit_pattern_mut(pattern);
+            if let Some(expr) = expr {
+                visitor.visit_expr_mut(expr);
+            }
+            if let Some(type_ann) = type_ann {
+                visitor.visit_type_ann_mut(type_ann);
+            }
+        }
+        DeclKind::TypeDecl(TypeDecl {
+            name: _,
+            name_span: _,
+            type_ann,
+            type_params,
+            is_opaque: _,
+        }) => {
+            if let Some(type_params) = type_params {
+                for type_param in type_params {
+                    if let Some(bound) = &mut type_param.bound {
+                        visitor.visit_type_ann_mut(bound);
+                    }
+                    if let Some(default) = &mut type_param.default {
+                        visitor.visit_type_ann_mut(default);
+                    }
+                }
+            }
+            visitor.visit_type_ann_mut(type_ann);
+        }
+        DeclKind::GlobalDecl(crate::GlobalDecl { decls }) => {
+            for decl in decls {
+                visitor.visit_decl_mut(decl);
+            }
+        }
+        DeclKind::FnDecl(crate::FnDecl {
+            name: _,
+            name_span: _,
+            type_ann,
+        }) => visitor.visit_type_ann_mut(type_ann),
+        DeclKind::ClassDecl(crate::ClassDecl {
+            name: _,
+            name_span: _,
+            class,
+        }) => {
+            if let Some(type_params) = &mut class.type_params {
+                for type_param in type_params {
+                    if let Some(bound) = &mut type_param.bound {
+                        visitor.visit_type_ann_mut(bound);
+                    }
+                    if let Some(default) = &mut type_param.default {
+                        visitor.visit_type_ann_mut(default);
+                    }
+                }
+            }
+        }
+        DeclKind::ModuleDecl(crate::ModuleDecl {
+            name: _,
+            name_span: _,
+            decls,
+        }) => {
+            for decl in decls {
+                visitor.visit_decl_mut(decl);
+            }
+        }
+    }
+}
+
+pub fn walk_stmt_mut<V: VisitorMut>(visitor: &mut V, stmt: &mut Stmt) {
+    match &mut stmt.kind {
+        StmtKind::Expr(ExprStmt { expr }) => visitor.visit_expr_mut(expr),
+        StmtKind::For(ForStmt { left, right, body }) => {
+            visitor.visit_pattern_mut(left);
+            visitor.visit_expr_mut(right);
+            walk_block_mut(visitor, body);
+        }
+        StmtKind::Return(ReturnStmt { arg }) => {
+            if let Some(arg) = arg {
+                visitor.visit_expr_mut(arg);
+            }
+        }
+        StmtKind::LetElse(LetElseStmt {
+            pattern,
+            expr,
+            alternate,
+        }) => {
+            visitor.visit_pattern_mut(pattern);
+            visitor.visit_expr_mut(expr);
+            walk_block_mut(visitor, alternate);
+        }
+        StmtKind::Decl(decl) => visitor.visit_decl_mut(decl),
+        StmtKind::Import(_) | StmtKind::Export(_) => {}
+    }
+}
+
+// TODO
+pub fn walk_type_ann_mut<V: VisitorMut>(_visitor: &mut V, type_ann: &mut TypeAnn) {
+    match &mut type_ann.kind {
+        crate::TypeAnnKind::BoolLit(_) => {}
+        crate::TypeAnnKind::Boolean => {}
+        crate::TypeAnnKind::NumLit(_) => {}
+        crate::TypeAnnKind::Number => {}
+        crate::TypeAnnKind::BigIntLit(_) => {}
+        crate::TypeAnnKind::BigInt => {}
+        crate::TypeAnnKind::StrLit(_) => {}
+        crate::TypeAnnKind::String => {}
+        crate::TypeAnnKind::Symbol => {}
+        crate::TypeAnnKind::UniqueSymbol => {}
+        crate::TypeAnnKind::Null => {}
+        crate::TypeAnnKind::Undefined => {}
+        crate::TypeAnnKind::Unknown => {}
+        crate::TypeAnnKind::Never => {}
+        crate::TypeAnnKind::Object(_) => {}
+        crate::TypeAnnKind::Tuple(_) => {}
+        crate::TypeAnnKind::Array(_) => {}
+        crate::TypeAnnKind::TypeRef(_, _) => {}
+        crate::TypeAnnKind::Function(_) => {}
+        crate::TypeAnnKind::Union(_) => {}
+        crate::TypeAnnKind::Intersection(_) => {}
+        crate::TypeAnnKind::IndexedAccess(_, _) => {}
+        crate::TypeAnnKind::KeyOf(_) => {}
+        crate::TypeAnnKind::Rest(_) => {}
+        crate::TypeAnnKind::Readonly(_) => {}
+        crate::TypeAnnKind::Labeled(_, _) => {}
+        crate::TypeAnnKind::TypeOf(_) => {}
+        crate::TypeAnnKind::Condition(_) => {}
+        crate::TypeAnnKind::Match(_) => {}
+        crate::TypeAnnKind::Wildcard => {}
+        crate::TypeAnnKind::Infer(_) => {}
+        crate::TypeAnnKind::Binary(_) => {}
+    }
+}
+
+pub fn walk_block_mut<V: VisitorMut>(visitor: &mut V, block: &mut Block) {
+    for stmt in &mut block.stmts {
+        visitor.visit_stmt_mut(stmt);
+    }
+}
+
+pub fn walk_block_or_expr_mut<V: VisitorMut>(visitor: &mut V, block_or_expr: &mut BlockOrExpr) {
+    match block_or_expr {
+        BlockOrExpr::Block(block) => walk_block_mut(visitor, block),
+        BlockOrExpr::Expr(expr) => visitor.visit_expr_mut(expr),
+    }
+}
@@ -1,9 +1,10 @@
+use escalier_ast::CompilerOptions;
 use escalier_codegen::js::*;
 use escalier_parser::parse;
 
 fn compile(input: &str) -> String {
-    let script = parse(input).unwrap();
-    let (js, _) = codegen_js(input, &script);
+    let script = parse(input, &CompilerOptions::default()).unwrap();
+    let (js, _) = codegen_js(input, &script, &CompilerOptions::default());
     js
 }
 
@@ -118,23 +119,23 @@ fn variable_declaration_with_number_literal() {
 #[test]
 fn let_in_inside_declaration() {
     insta::assert_snapshot!(compile("let foo = do {let x = 5\nx}"), @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const x = 5;
-        $temp_0 = x;
-    }export const foo = $temp_0;
+        $do_result_0 = x;
+    }export const foo = $do_result_0;
     "###);
 }
 
 #[test]
 fn nested_let_in_inside_declaration() {
     insta::assert_snapshot!(compile("let foo = do {let x = 5\nlet y = 10\nx + y}"), @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const x = 5;
         const y = 10;
-        $temp_0 = x + y;
-    }export const foo = $temp_0;
+        $do_result_0 = x + y;
+    }export const foo = $do_result_0;
     "###);
 }
 
@@ -154,12 +155,12 @@ fn js_print_let_in() {
     }
     "#;
     insta::assert_snapshot!(compile(input), @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const x = 5;
         const y = 10;
-        $temp_0 = x + y;
-    }export const foo = $temp_0;
+        $do_result_0 = x + y;
+    }export const foo = $do_result_0;
     "###);
 }
 
@@ -172,12 +173,12 @@ fn js_print_variable_shadowing() {
         x
     }"#;
     insta::assert_snapshot!(compile(input), @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const x = 5;
-        const x = 10;
-        $temp_0 = x;
-    }export const foo = $temp_0;
+        const x$1 = 10;
+        $do_result_0 = x$1;
+    }export const foo = $do_result_0;
     "###);
 }
 
@@ -224,17 +225,17 @@ fn js_print_nested_blocks() {
         }
         sum
     }"#), @r###"
-    let $temp_0;
+    let $do_result_0;
     {
-        let $temp_1;
+        let $do_result_1;
         {
             const x = 5;
             const y = 10;
-            $temp_1 = x + y;
+            $do_result_1 = x + y;
         }
-        const sum = $temp_1;
-        $temp_0 = sum;
-    }export const result = $temp_0;
+        const sum = $do_result_1;
+        $do_result_0 = sum;
+    }export const result = $do_result_0;
     "###);
 }
 
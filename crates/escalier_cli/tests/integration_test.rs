@@ -1,4 +1,4 @@
-use escalier_ast::{Script, StmtKind};
+use escalier_ast::{CompilerOptions, Script, StmtKind};
 use escalier_codegen::*;
 use escalier_hm::checker::Checker;
 use escalier_hm::context::Context;
@@ -16,7 +16,7 @@ pub fn current_report_message(checker: &Checker) -> String {
 }
 
 fn infer(input: &str) -> String {
-    let prog = parse(input).unwrap();
+    let prog = parse(input, &CompilerOptions::default()).unwrap();
     let mut stmt = prog.stmts.get(0).unwrap().to_owned();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
@@ -36,7 +36,7 @@ fn infer(input: &str) -> String {
 }
 
 fn infer_script(src: &str) -> (Script, (Context, Checker)) {
-    let result = parse(src);
+    let result = parse(src, &CompilerOptions::default());
     let mut script = match result {
         Ok(script) => script,
         Err(err) => {
@@ -47,7 +47,7 @@ fn infer_script(src: &str) -> (Script, (Context, Checker)) {
     let mut checker = Checker::default();
     let mut ctx = Context::default();
 
-    match checker.infer_script(&mut script, &mut ctx) {
+    match checker.infer_script(&mut script, &mut ctx, &CompilerOptions::default()) {
         Ok(()) => (script, (ctx, checker)),
         Err(error) => {
             let message = error.message;
@@ -168,7 +168,7 @@ fn infer_k_combinator_not_curried() -> Result<(), TypeError> {
     let result = checker.print_type(&ctx.values.get("K").unwrap().index);
     insta::assert_snapshot!(result, @"<B, A>(x: A, y: B) -> A");
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
     insta::assert_snapshot!(result, @"export declare const K: <B, A>(x: A, y: B) => A;
 ");
 
@@ -246,7 +246,7 @@ fn infer_decl() -> Result<(), TypeError> {
     let bar = "hello"
     "#;
     let (script, (ctx, checker)) = infer_script(src);
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const bar: "hello";
@@ -263,7 +263,7 @@ fn infer_with_subtyping() -> Result<(), TypeError> {
     let bar = foo(5, 10)
     "#;
     let (script, (ctx, checker)) = infer_script(src);
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const bar: number;
@@ -277,8 +277,7 @@ fn infer_with_subtyping() -> Result<(), TypeError> {
 fn infer_if_else_without_widening() {
     let (_, (ctx, checker)) = infer_script("let x = if (true) { 5 } else { 5 }");
     let result = checker.print_type(&ctx.values.get("x").unwrap().index);
-    // TODO: remove duplicate types from union types
-    assert_eq!(result, "5 | 5");
+    assert_eq!(result, "5");
 }
 
 #[test]
@@ -292,7 +291,7 @@ fn infer_if_else_with_widening() {
 fn infer_value_of_let_from_a_block_return_is_undefined() {
     let (_, (ctx, checker)) = infer_script("let x = if (true) { let a = 5 }");
     let result = checker.print_type(&ctx.values.get("x").unwrap().index);
-    assert_eq!(result, "undefined | undefined");
+    assert_eq!(result, "undefined");
 }
 
 #[test]
@@ -329,7 +328,7 @@ fn infer_if_else_with_multiple_widenings() -> Result<(), TypeError> {
     let result = checker.print_type(&ctx.values.get("x").unwrap().index);
     assert_eq!(result, "5 | 10 | 15");
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
     insta::assert_snapshot!(result, @"export declare const x: 5 | 10 | 15;\n");
 
     Ok(())
@@ -386,7 +385,7 @@ fn infer_let_rec_until() -> Result<(), TypeError> {
     let result = checker.print_type(&ctx.values.get("until").unwrap().index);
     insta::assert_snapshot!(result, @"<A>(p: (arg0: A) -> boolean, f: (arg0: A) -> A, x: A) -> A | A");
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
     insta::assert_snapshot!(result, @"export declare const until: <A>(p: (arg0: A) => boolean, f: (arg0: A) => A, x: A) => A | A;
 ");
 
@@ -409,9 +408,7 @@ fn infer_fib() {
     let fib = ctx.values.get("fib").unwrap();
     assert_eq!(
         format!("{}", checker.print_type(&fib.index)),
-        // TODO: unions of `number` and number literals should
-        // have `number` subsume the literals.
-        "(n: number) -> 0 | 1 | number"
+        "(n: number) -> number"
     );
 }
 
@@ -443,7 +440,7 @@ fn infer_async_math() -> Result<(), TypeError> {
         "<A, B>(a: () -> Promise<number, A>, b: () -> Promise<number, B>) -> Promise<number, A | B>"
     );
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
     insta::assert_snapshot!(result, @"export declare const add: <A, B>(a: () => Promise<number, A>, b: () => Promise<number, B>) => Promise<number, A | B>;
 ");
 
@@ -454,12 +451,12 @@ fn infer_async_math() -> Result<(), TypeError> {
 fn codegen_let_rec() -> Result<(), TypeError> {
     let src = "let f = fn () => f()";
     let (script, (ctx, checker)) = infer_script(src);
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
 
     insta::assert_snapshot!(js, @"export const f = ()=>f();
 ");
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const f: <A>() => A;\n");
 
@@ -474,24 +471,23 @@ fn codegen_if_else() -> Result<(), TypeError> {
     "#;
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const cond = true;
-    let $temp_0;
+    let $if_result_0;
     if (cond) {
-        $temp_0 = 5;
+        $if_result_0 = 5;
     } else {
-        $temp_0 = 5;
+        $if_result_0 = 5;
     }
-    export const result = $temp_0;
+    export const result = $if_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
-    // TODO: remove duplicates from union types
     insta::assert_snapshot!(result, @r###"
     export declare const cond: true;
-    export declare const result: 5 | 5;
+    export declare const result: 5;
     "###);
 
     Ok(())
@@ -501,7 +497,7 @@ fn codegen_if_else() -> Result<(), TypeError> {
 fn codegen_object() -> Result<(), TypeError> {
     let src = "let point = {x: 5, y: 10}";
     let (script, (ctx, checker)) = infer_script(src);
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
 
     insta::assert_snapshot!(js, @r###"
     export const point = {
@@ -510,7 +506,7 @@ fn codegen_object() -> Result<(), TypeError> {
     };
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: wrap types in `Readonly<>` if the binding is immutable
     insta::assert_snapshot!(result, @r###"
@@ -528,12 +524,12 @@ fn codegen_async_math() -> Result<(), TypeError> {
     let src = "let add = async fn (a, b) => await a() + await b()";
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
 
     insta::assert_snapshot!(js, @"export const add = async (a, b)=>await a() + await b();
 ");
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const add: <A, B>(a: () => Promise<number, A>, b: () => Promise<number, B>) => Promise<number, A | B>;
 ");
@@ -803,17 +799,16 @@ fn codegen_code_with_type_delcarations() -> Result<(), TypeError> {
     let point: Point = {x: 5, y: 10}
     "#;
     let (script, (ctx, checker)) = infer_script(src);
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
 
     insta::assert_snapshot!(js, @r###"
-    ;
     export const point = {
         x: 5,
         y: 10
     };
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     declare type Point = {
@@ -866,14 +861,12 @@ fn infer_assigning_to_obj_with_optional_props() {
 }
 
 #[test]
+#[should_panic = "'z' does not exist in {x: number, y: number}"]
 fn infer_assigning_an_obj_lit_with_extra_props() {
     let src = r#"
     let point: {x: number, y: number} = {x: 5, y: 10, z: 15}
     "#;
-    let (_, (ctx, checker)) = infer_script(src);
-
-    let point = checker.print_type(&ctx.values.get("point").unwrap().index);
-    assert_eq!(point, "{x: number, y: number}");
+    infer_script(src);
 }
 
 #[test]
@@ -908,16 +901,15 @@ fn codegen_object_type_with_optional_property() -> Result<(), TypeError> {
     let point: Point = {y: 10}
     "#;
     let (script, (ctx, checker)) = infer_script(src);
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
 
     insta::assert_snapshot!(js, @r###"
-    ;
     export const point = {
         y: 10
     };
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: wrap type annotations for immutable bindings in Readonly<>
     // TODO: use the type annotation for bindings when present instead of
@@ -982,18 +974,18 @@ fn codegen_block_with_multiple_non_let_lines() -> Result<(), TypeError> {
         x
     }"#;
     let (script, (ctx, checker)) = infer_script(src);
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const x = 5;
         x + 0;
-        $temp_0 = x;
-    }export const result = $temp_0;
+        $do_result_0 = x;
+    }export const result = $do_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const result: 5;
 ");
@@ -1455,21 +1447,21 @@ fn codegen_if_let() -> Result<(), TypeError> {
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const p = {
         x: 5,
         y: 10
     };
-    let $temp_0;
-    const $temp_1 = p;
+    let $if_let_result_0;
+    const $if_let_value_0 = p;
     {
-        const { x, y } = $temp_1;
-        $temp_0 = x + y;
-    }$temp_0;
+        const { x, y } = $if_let_value_0;
+        $if_let_result_0 = x + y;
+    }$if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const p: {
@@ -1494,21 +1486,21 @@ fn codegen_if_let_with_rename() -> Result<(), TypeError> {
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const p = {
         x: 5,
         y: 10
     };
-    let $temp_0;
-    const $temp_1 = p;
+    let $if_let_result_0;
+    const $if_let_value_0 = p;
     {
-        const { x: a, y: b } = $temp_1;
-        $temp_0 = a + b;
-    }$temp_0;
+        const { x: a, y: b } = $if_let_value_0;
+        $if_let_result_0 = a + b;
+    }$if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const p: {
@@ -1560,22 +1552,22 @@ fn infer_if_let_refutable_pattern_obj() -> Result<(), TypeError> {
         "{x: 5, y: 10}"
     );
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const p = {
         x: 5,
         y: 10
     };
-    let $temp_0;
-    const $temp_1 = p;
-    if ($temp_1.x === 5) {
-        const { y } = $temp_1;
-        $temp_0 = y;
+    let $if_let_result_0;
+    const $if_let_value_0 = p;
+    if ($if_let_value_0.x === 5) {
+        const { y } = $if_let_value_0;
+        $if_let_result_0 = y;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const p: {
@@ -1600,7 +1592,7 @@ fn infer_if_let_refutable_pattern_nested_obj() -> Result<(), TypeError> {
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const action = {
         type: "moveto",
@@ -1609,16 +1601,16 @@ fn infer_if_let_refutable_pattern_nested_obj() -> Result<(), TypeError> {
             y: 10
         }
     };
-    let $temp_0;
-    const $temp_1 = action;
-    if ($temp_1.type === "moveto") {
-        const { point: { x, y } } = $temp_1;
-        $temp_0 = x + y;
+    let $if_let_result_0;
+    const $if_let_value_0 = action;
+    if ($if_let_value_0.type === "moveto") {
+        const { point: { x, y } } = $if_let_value_0;
+        $if_let_result_0 = x + y;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const action: {
@@ -1648,21 +1640,18 @@ fn infer_if_let_refutable_pattern_with_disjoint_union() -> Result<(), TypeError>
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
-    ;
-    ;
-    ;
-    let $temp_0;
-    const $temp_1 = action;
-    if ($temp_1.type === "moveto") {
-        const { point: { x, y } } = $temp_1;
-        $temp_0 = x + y;
+    let $if_let_result_0;
+    const $if_let_value_0 = action;
+    if ($if_let_value_0.type === "moveto") {
+        const { point: { x, y } } = $if_let_value_0;
+        $if_let_result_0 = x + y;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     declare type Action = {
@@ -1700,22 +1689,22 @@ fn infer_if_let_refutable_pattern_array() -> Result<(), TypeError> {
         "[5, 10]"
     );
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const p = [
         5,
         10
     ];
-    let $temp_0;
-    const $temp_1 = p;
-    if ($temp_1[0] === 5) {
-        const [, y] = $temp_1;
-        $temp_0 = y;
+    let $if_let_result_0;
+    const $if_let_value_0 = p;
+    if ($if_let_value_0[0] === 5) {
+        const [, y] = $if_let_value_0;
+        $if_let_result_0 = y;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const p: readonly [5, 10];
 ");
@@ -1736,7 +1725,7 @@ fn infer_if_let_refutable_pattern_nested_array() -> Result<(), TypeError> {
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const action = [
         "moveto",
@@ -1745,16 +1734,16 @@ fn infer_if_let_refutable_pattern_nested_array() -> Result<(), TypeError> {
             10
         ]
     ];
-    let $temp_0;
-    const $temp_1 = action;
-    if ($temp_1[0] === "moveto") {
-        const [, [x, y]] = $temp_1;
-        $temp_0 = x + y;
+    let $if_let_result_0;
+    const $if_let_value_0 = action;
+    if ($if_let_value_0[0] === "moveto") {
+        const [, [x, y]] = $if_let_value_0;
+        $if_let_result_0 = x + y;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"export declare const action: readonly ["moveto", readonly [5, 10]];
 "###);
@@ -1775,19 +1764,18 @@ fn codegen_if_let_with_is_prim() -> Result<(), TypeError> {
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
-    ;
-    let $temp_0;
-    const $temp_1 = b;
-    if (typeof $temp_1 === "number") {
-        const a = $temp_1;
-        $temp_0 = a + 5;
+    let $if_let_result_0;
+    const $if_let_value_0 = b;
+    if (typeof $if_let_value_0 === "number") {
+        const a = $if_let_value_0;
+        $if_let_result_0 = a + 5;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const b: number | string;
 ");
@@ -1822,7 +1810,7 @@ fn codegen_if_let_with_is_class() {
     }
     "#;
 
-    let result = parse(src);
+    let result = parse(src, &CompilerOptions::default());
     let script = match result {
         Ok(prog) => prog,
         Err(err) => {
@@ -1831,26 +1819,21 @@ fn codegen_if_let_with_is_class() {
         }
     };
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
-    ;
-    ;
-    ;
     export const Foo = {
         constructor: ()=>foo
     };
-    ;
     export const Bar = {
         constructor: ()=>bar
     };
-    ;
-    let $temp_0;
-    const $temp_1 = b;
-    if ($temp_1 instanceof Foo) {
-        const a = $temp_1;
-        $temp_0 = a.getNum() + 5;
+    let $if_let_result_0;
+    const $if_let_value_0 = b;
+    if ($if_let_value_0 instanceof Foo) {
+        const a = $if_let_value_0;
+        $if_let_result_0 = a.getNum() + 5;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 }
 
@@ -1862,7 +1845,7 @@ fn codegen_array() -> Result<(), TypeError> {
 
     let (script, (ctx, checker)) = infer_script(src);
 
-    let (js, _) = codegen_js(src, &script);
+    let (js, _) = codegen_js(src, &script, &CompilerOptions::default());
     insta::assert_snapshot!(js, @r###"
     export const arr = [
         "hello",
@@ -1870,7 +1853,7 @@ fn codegen_array() -> Result<(), TypeError> {
     ];
     "###);
 
-    let result = codegen_d_ts(&script, &ctx, &checker)?;
+    let result = codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default())?;
     insta::assert_snapshot!(result, @"export declare const arr: readonly string[];
 ");
 
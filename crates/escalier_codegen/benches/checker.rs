@@ -0,0 +1,151 @@
+//! Benchmarks for parsing, inferring, and generating code for programs that
+//! stress different parts of the checker.  Each program is generated at a few
+//! sizes so that algorithmic regressions, e.g. expansions that grow
+//! exponentially, show up as a change in how the timings scale.
+//!
+//! Run `cargo bench -p escalier_codegen --bench checker` to measure them, or
+//! pass `-- --test` to run each benchmark once.  CI saves a baseline for the
+//! base of each pull request with `-- --save-baseline base`, compares against
+//! it with `-- --baseline-lenient base`, and fails if any benchmark's mean
+//! time regressed by more than the threshold in ci.yml.
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+use escalier_ast::CompilerOptions;
+use escalier_codegen::d_ts::codegen_d_ts;
+use escalier_codegen::js::codegen_js;
+use escalier_hm::checker::Checker;
+use escalier_hm::context::Context;
+use escalier_parser::parse;
+
+const SIZES: &[usize] = &[10, 50, 100];
+
+// Generates a program of the given size.
+type Program = fn(usize) -> String;
+
+const PROGRAMS: &[(&str, Program)] = &[
+    ("large_union", large_union),
+    ("deep_generics", deep_generics),
+    ("many_functions", many_functions),
+];
+
+// A union of `n` string literals that's narrowed and passed around.
+fn large_union(n: usize) -> String {
+    let keys: Vec<String> = (0..n).map(|i| format!("\"k{i}\"")).collect();
+    let mut src = format!("type Key = {}\n", keys.join(" | "));
+    src.push_str("type Rest = Exclude<Key, \"k0\">\n");
+    src.push_str("let id = fn (key: Key) -> Key => key\n");
+    for i in 0..n {
+        src.push_str(&format!("let key{i}: Key = id(\"k{i}\")\n"));
+    }
+    src.push_str(&format!("let rest: Rest = \"k{}\"\n", n - 1));
+    src
+}
+
+// A chain of `n` generic aliases, each nesting the one before it, and `n`
+// calls to a generic function.
+fn deep_generics(n: usize) -> String {
+    let mut src = String::from("type Box0<T> = {value: T}\n");
+    for i in 1..n {
+        src.push_str(&format!(
+            "type Box{i}<T> = {{value: T, inner: Box{}<T>}}\n",
+            i - 1
+        ));
+    }
+    src.push_str(&format!("declare let box: Box{}<number>\n", n - 1));
+    src.push_str(&format!(
+        "let value = box{}.value\n",
+        ".inner".repeat(n - 1)
+    ));
+    src.push_str("let wrap = fn <T>(value: T) -> Box0<T> => {value}\n");
+    for i in 0..n {
+        src.push_str(&format!("let wrapped{i} = wrap({i})\n"));
+    }
+    src
+}
+
+// `n` generic functions that each call the one before it.
+fn many_functions(n: usize) -> String {
+    let mut src = String::from("let f0 = fn (x: number, y) => y\n");
+    for i in 1..n {
+        src.push_str(&format!(
+            "let f{i} = fn (x: number, y) => if (x > 0) {{ f{}(x - 1, y) }} else {{ y }}\n",
+            i - 1
+        ));
+    }
+    for i in 0..n {
+        src.push_str(&format!("let obj{i} = {{index: {i}, f: f{i}}}\n"));
+    }
+    src
+}
+
+fn new_checker() -> (Checker, Context) {
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.load_prelude(&mut ctx).unwrap();
+    (checker, ctx)
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for (name, program) in PROGRAMS {
+        for size in SIZES {
+            let src = program(*size);
+            group.bench_with_input(BenchmarkId::new(*name, size), &src, |b, src| {
+                b.iter(|| parse(src, &CompilerOptions::default()).unwrap())
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_infer(c: &mut Criterion) {
+    let mut group = c.benchmark_group("infer");
+    for (name, program) in PROGRAMS {
+        for size in SIZES {
+            let script = parse(&program(*size), &CompilerOptions::default()).unwrap();
+            group.bench_with_input(BenchmarkId::new(*name, size), &script, |b, script| {
+                // Loading the prelude isn't part of what's being measured.
+                b.iter_batched(
+                    || (script.clone(), new_checker()),
+                    |(mut script, (mut checker, mut ctx))| {
+                        checker
+                            .infer_script(&mut script, &mut ctx, &CompilerOptions::default())
+                            .unwrap();
+                        checker
+                    },
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+    }
+    group.finish();
+}
+
+fn bench_codegen(c: &mut Criterion) {
+    let mut group = c.benchmark_group("codegen");
+    for (name, program) in PROGRAMS {
+        for size in SIZES {
+            let src = program(*size);
+            let mut script = parse(&src, &CompilerOptions::default()).unwrap();
+            let (mut checker, mut ctx) = new_checker();
+            checker
+                .infer_script(&mut script, &mut ctx, &CompilerOptions::default())
+                .unwrap();
+            assert!(checker.current_report.diagnostics.is_empty());
+
+            group.bench_function(BenchmarkId::new(*name, size), |b| {
+                b.iter(|| {
+                    let (js, _) = codegen_js(&src, &script, &CompilerOptions::default());
+                    let dts =
+                        codegen_d_ts(&script, &ctx, &checker, &CompilerOptions::default()).unwrap();
+                    (js, dts)
+                })
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_infer, bench_codegen);
+criterion_main!(benches);
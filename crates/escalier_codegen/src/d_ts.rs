@@ -1,27 +1,35 @@
 use generational_arena::Index;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 use swc_atoms::*;
-use swc_common::{SourceMap, DUMMY_SP};
+use swc_common::comments::{Comments, SingleThreadedComments};
+use swc_common::{BytePos, SourceMap, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::*;
 use swc_ecma_codegen::*;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
 
 use escalier_ast::visitor::*;
 use escalier_ast::{self as values};
 use escalier_hm::checker::Checker;
 use escalier_hm::context::Context;
+use escalier_hm::printer::TypeVarNames;
 use escalier_hm::type_error::TypeError;
 use escalier_hm::types;
 
+use crate::js::{build_jsdoc_comment, get_jsdoc};
+
 pub fn codegen_d_ts(
     program: &values::Script,
     ctx: &Context,
     checker: &Checker,
+    options: &values::CompilerOptions,
 ) -> core::result::Result<String, TypeError> {
-    Ok(print_d_ts(&build_d_ts(program, ctx, checker)?))
+    let comments = SingleThreadedComments::default();
+    let program = build_d_ts(program, ctx, checker, options, &comments)?;
+    Ok(print_d_ts(&program, &comments))
 }
 
-fn print_d_ts(program: &Program) -> String {
+fn print_d_ts(program: &Program, comments: &SingleThreadedComments) -> String {
     let mut buf = vec![];
     let cm = Rc::new(SourceMap::default());
 
@@ -30,19 +38,435 @@ fn print_d_ts(program: &Program) -> String {
             ..Default::default()
         },
         cm: cm.clone(),
-        comments: None,
-        wr: text_writer::JsWriter::new(cm, "\n", &mut buf, None),
+        comments: Some(comments),
+        wr: DtsWriter {
+            wr: text_writer::JsWriter::new(cm, "\n", &mut buf, None),
+        },
     };
 
-    emitter.emit_program(program).unwrap();
+    match program {
+        Program::Module(module) => {
+            for item in &module.body {
+                match item {
+                    ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                        emit_import(&mut emitter.wr, import)
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => {
+                        emit_named_export(&mut emitter.wr, export)
+                    }
+                    _ => emitter.emit_module_item(item),
+                }
+                .unwrap();
+            }
+            text_writer::WriteJs::commit_pending_semi(&mut emitter.wr).unwrap();
+        }
+        Program::Script(script) => emitter.emit_script(script).unwrap(),
+    }
 
     String::from_utf8_lossy(&buf).to_string()
 }
 
+// swc doesn't print the `type` modifiers of imports and exports, e.g.
+// `import type { A }` or `export { type B }`, so they're printed here instead.
+// The d.ts only contains named imports and exports.
+fn emit_import<W: text_writer::WriteJs>(wr: &mut W, import: &ImportDecl) -> Result {
+    wr.write_keyword(None, "import")?;
+    wr.write_space()?;
+    if import.type_only {
+        wr.write_keyword(None, "type")?;
+        wr.write_space()?;
+    }
+    let specifiers: Vec<_> = import
+        .specifiers
+        .iter()
+        .map(|specifier| match specifier {
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                local,
+                imported: Some(imported),
+                is_type_only,
+                ..
+            }) => (
+                *is_type_only,
+                imported.clone(),
+                Some(ModuleExportName::Ident(local.clone())),
+            ),
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                local,
+                imported: None,
+                is_type_only,
+                ..
+            }) => (*is_type_only, ModuleExportName::Ident(local.clone()), None),
+            _ => unreachable!("d.ts only emits named specifiers"),
+        })
+        .collect();
+    emit_specifiers(wr, &specifiers)?;
+    emit_from(wr, &import.src)?;
+    wr.write_semi(None)?;
+    wr.write_line()
+}
+
+fn emit_named_export<W: text_writer::WriteJs>(wr: &mut W, export: &NamedExport) -> Result {
+    wr.write_keyword(None, "export")?;
+    wr.write_space()?;
+    if export.type_only {
+        wr.write_keyword(None, "type")?;
+        wr.write_space()?;
+    }
+    let specifiers: Vec<_> = export
+        .specifiers
+        .iter()
+        .map(|specifier| match specifier {
+            ExportSpecifier::Named(ExportNamedSpecifier {
+                orig,
+                exported,
+                is_type_only,
+                ..
+            }) => (*is_type_only, orig.clone(), exported.clone()),
+            _ => unreachable!("d.ts only emits named specifiers"),
+        })
+        .collect();
+    emit_specifiers(wr, &specifiers)?;
+    if let Some(src) = &export.src {
+        emit_from(wr, src)?;
+    }
+    wr.write_semi(None)?;
+    wr.write_line()
+}
+
+// Emits `{ type A as B, C }` given whether each specifier is type-only, the
+// name being imported or exported, and its alias.
+fn emit_specifiers<W: text_writer::WriteJs>(
+    wr: &mut W,
+    specifiers: &[(bool, ModuleExportName, Option<ModuleExportName>)],
+) -> Result {
+    wr.write_punct(None, "{")?;
+    wr.write_space()?;
+    for (i, (is_type_only, name, alias)) in specifiers.iter().enumerate() {
+        if i > 0 {
+            wr.write_punct(None, ",")?;
+            wr.write_space()?;
+        }
+        if *is_type_only {
+            wr.write_keyword(None, "type")?;
+            wr.write_space()?;
+        }
+        emit_module_export_name(wr, name)?;
+        if let Some(alias) = alias {
+            wr.write_space()?;
+            wr.write_keyword(None, "as")?;
+            wr.write_space()?;
+            emit_module_export_name(wr, alias)?;
+        }
+    }
+    if !specifiers.is_empty() {
+        wr.write_space()?;
+    }
+    wr.write_punct(None, "}")
+}
+
+fn emit_module_export_name<W: text_writer::WriteJs>(wr: &mut W, name: &ModuleExportName) -> Result {
+    match name {
+        ModuleExportName::Ident(ident) => wr.write_symbol(DUMMY_SP, &ident.sym),
+        ModuleExportName::Str(str) => emit_str(wr, str),
+    }
+}
+
+fn emit_from<W: text_writer::WriteJs>(wr: &mut W, src: &Str) -> Result {
+    wr.write_space()?;
+    wr.write_keyword(None, "from")?;
+    wr.write_space()?;
+    emit_str(wr, src)
+}
+
+// JSON strings are also valid JS strings.
+fn emit_str<W: text_writer::WriteJs>(wr: &mut W, str: &Str) -> Result {
+    wr.write_str_lit(DUMMY_SP, &serde_json::to_string(&*str.value).unwrap())
+}
+
+// swc prints every `TsModuleDecl` that isn't `declare global` using the
+// `module` keyword, but `module` is deprecated for namespaces with identifier
+// names, which are the only kind we generate, so it's replaced with
+// `namespace`.
+struct DtsWriter<W: text_writer::WriteJs> {
+    wr: W,
+}
+
+impl<W: text_writer::WriteJs> text_writer::WriteJs for DtsWriter<W> {
+    fn increase_indent(&mut self) -> Result {
+        self.wr.increase_indent()
+    }
+
+    fn decrease_indent(&mut self) -> Result {
+        self.wr.decrease_indent()
+    }
+
+    fn write_semi(&mut self, span: Option<swc_common::Span>) -> Result {
+        self.wr.write_semi(span)
+    }
+
+    fn write_space(&mut self) -> Result {
+        self.wr.write_space()
+    }
+
+    fn write_keyword(&mut self, span: Option<swc_common::Span>, s: &'static str) -> Result {
+        match s {
+            "module" => self.wr.write_keyword(span, "namespace"),
+            _ => self.wr.write_keyword(span, s),
+        }
+    }
+
+    fn write_operator(&mut self, span: Option<swc_common::Span>, s: &str) -> Result {
+        self.wr.write_operator(span, s)
+    }
+
+    fn write_param(&mut self, s: &str) -> Result {
+        self.wr.write_param(s)
+    }
+
+    fn write_property(&mut self, s: &str) -> Result {
+        self.wr.write_property(s)
+    }
+
+    fn write_line(&mut self) -> Result {
+        self.wr.write_line()
+    }
+
+    fn write_lit(&mut self, span: swc_common::Span, s: &str) -> Result {
+        self.wr.write_lit(span, s)
+    }
+
+    fn write_comment(&mut self, s: &str) -> Result {
+        self.wr.write_comment(s)
+    }
+
+    fn write_str_lit(&mut self, span: swc_common::Span, s: &str) -> Result {
+        self.wr.write_str_lit(span, s)
+    }
+
+    fn write_str(&mut self, s: &str) -> Result {
+        self.wr.write_str(s)
+    }
+
+    fn write_symbol(&mut self, span: swc_common::Span, s: &str) -> Result {
+        self.wr.write_symbol(span, s)
+    }
+
+    fn write_punct(&mut self, span: Option<swc_common::Span>, s: &'static str) -> Result {
+        self.wr.write_punct(span, s)
+    }
+
+    fn care_about_srcmap(&self) -> bool {
+        self.wr.care_about_srcmap()
+    }
+
+    fn add_srcmap(&mut self, pos: BytePos) -> Result {
+        self.wr.add_srcmap(pos)
+    }
+
+    fn commit_pending_semi(&mut self) -> Result {
+        self.wr.commit_pending_semi()
+    }
+}
+
+fn build_module_item(decl: Decl, is_export: bool) -> ModuleItem {
+    match is_export {
+        true => ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl,
+        })),
+        false => ModuleItem::Stmt(Stmt::Decl(decl)),
+    }
+}
+
+// Attaches `doc` to `item` as a JSDoc comment.  The emitter looks up comments
+// by position so each documented item needs its own position, we use its
+// index in the module's body.
+fn attach_doc(item: &mut ModuleItem, index: usize, doc: &str, comments: &SingleThreadedComments) {
+    let pos = BytePos(index as u32 + 1);
+    let span = swc_common::Span {
+        lo: pos,
+        hi: pos,
+        ctxt: SyntaxContext::empty(),
+    };
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+            export_decl.span = span;
+        }
+        ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(type_alias))) => {
+            type_alias.span = span;
+        }
+        _ => return,
+    }
+
+    comments.add_leading(pos, build_jsdoc_comment(doc));
+}
+
+// Builds the type alias for the type `name` as well as its `Readonly` variant
+// if it's an object type with mutable properties and `with_readonly` is set.
+fn build_type_alias_decls(
+    name: &str,
+    scheme: &types::Scheme,
+    declare: bool,
+    with_readonly: bool,
+    ctx: &Context,
+    checker: &Checker,
+) -> Vec<Decl> {
+    // Type variables are named per decl so that they don't depend on the
+    // order in which the decls were checked.
+    let mut roots = vec![scheme.t];
+    for type_param in scheme.type_params.iter().flatten() {
+        roots.extend(type_param.constraint);
+    }
+    let names = &checker.type_var_names(&roots);
+
+    let type_params =
+        build_type_params_from_type_params(scheme.type_params.as_ref(), ctx, checker, names);
+
+    let mut decls = vec![];
+
+    if scheme.opaque_module.is_some() {
+        decls.push(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
+            span: DUMMY_SP,
+            declare,
+            id: build_ident(name),
+            type_params,
+            type_ann: Box::from(build_branded_type(
+                name,
+                build_type(&scheme.t, ctx, checker, names),
+            )),
+        })));
+    } else if let types::TypeKind::Object(obj) = &checker.arena[scheme.t].kind {
+        decls.push(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
+            span: DUMMY_SP,
+            declare,
+            id: build_ident(name),
+            type_params: type_params.clone(),
+            type_ann: Box::from(build_obj_type(obj, ctx, checker, names)),
+        })));
+
+        if with_readonly && !name.ends_with("Constructor") {
+            if let Some(obj) = immutable_obj_type(obj) {
+                decls.push(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
+                    span: DUMMY_SP,
+                    declare,
+                    id: build_ident(format!("Readonly{name}").as_str()),
+                    type_params,
+                    type_ann: Box::from(build_obj_type(&obj, ctx, checker, names)),
+                })));
+            }
+        }
+    } else {
+        decls.push(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
+            span: DUMMY_SP,
+            declare,
+            id: build_ident(name),
+            type_params,
+            type_ann: Box::from(build_type(&scheme.t, ctx, checker, names)),
+        })));
+    }
+
+    decls
+}
+
+// Opaque types are branded so that TypeScript also treats them as distinct
+// from their underlying types, e.g. `string & {readonly __brand: "UserId"}`.
+fn build_branded_type(name: &str, t: TsType) -> TsType {
+    let brand = TsTypeElement::TsPropertySignature(TsPropertySignature {
+        span: DUMMY_SP,
+        readonly: true,
+        key: Box::from(Expr::from(build_ident("__brand"))),
+        computed: false,
+        optional: false,
+        init: None,
+        params: vec![],
+        type_ann: Some(Box::from(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::from(TsType::TsLitType(TsLitType {
+                span: DUMMY_SP,
+                lit: TsLit::Str(Str {
+                    span: DUMMY_SP,
+                    value: JsWord::from(name),
+                    raw: None,
+                }),
+            })),
+        })),
+        type_params: None,
+    });
+
+    TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(
+        TsIntersectionType {
+            span: DUMMY_SP,
+            types: vec![
+                Box::from(t),
+                Box::from(TsType::TsTypeLit(TsTypeLit {
+                    span: DUMMY_SP,
+                    members: vec![brand],
+                })),
+            ],
+        },
+    ))
+}
+
+// `let mut` bindings are declared using `let` to match the generated JS.
+fn build_var_decl(
+    name: &str,
+    declare: bool,
+    ctx: &Context,
+    checker: &Checker,
+) -> core::result::Result<Decl, TypeError> {
+    let binding = ctx.get_binding(name)?;
+    let kind = match binding.is_mut {
+        true => VarDeclKind::Let,
+        false => VarDeclKind::Const,
+    };
+    Ok(build_var_decl_with_type(
+        name,
+        binding.index,
+        binding.is_mut,
+        kind,
+        declare,
+        ctx,
+        checker,
+    ))
+}
+
+fn build_var_decl_with_type(
+    name: &str,
+    t: Index,
+    is_mut: bool,
+    kind: VarDeclKind,
+    declare: bool,
+    ctx: &Context,
+    checker: &Checker,
+) -> Decl {
+    let names = &checker.type_var_names(&[t]);
+
+    let pat = Pat::Ident(BindingIdent {
+        id: build_ident(name),
+        type_ann: Some(Box::from(TsTypeAnn {
+            span: DUMMY_SP,
+            type_ann: Box::from(build_type_with_mutability(&t, is_mut, ctx, checker, names)),
+        })),
+    });
+
+    Decl::Var(Box::from(VarDecl {
+        span: DUMMY_SP,
+        kind,
+        declare,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name: pat,
+            init: None,
+            definite: false,
+        }],
+    }))
+}
+
 fn build_type_params_from_type_params(
     type_params: Option<&Vec<types::TypeParam>>,
     ctx: &Context,
     checker: &Checker,
+    names: &TypeVarNames,
 ) -> Option<Box<TsTypeParamDecl>> {
     type_params.as_ref().map(|type_params| {
         Box::from(TsTypeParamDecl {
@@ -53,7 +477,7 @@ fn build_type_params_from_type_params(
                     let constraint = type_param
                         .constraint
                         .as_ref()
-                        .map(|constraint| Box::from(build_type(constraint, ctx, checker)));
+                        .map(|constraint| Box::from(build_type(constraint, ctx, checker, names)));
                     TsTypeParam {
                         span: DUMMY_SP,
                         name: build_ident(&type_param.name),
@@ -69,14 +493,421 @@ fn build_type_params_from_type_params(
     })
 }
 
+// Builds the decls for a `declare fn`, `declare class`, or `declare module`
+// decl whose value has the type `t`.  `qualified_name` includes the names of
+// the modules that the decl is nested in, e.g. `Intl.Collator`.
+fn build_ambient_decls(
+    kind: &values::DeclKind,
+    qualified_name: &str,
+    t: Index,
+    declare: bool,
+    ctx: &Context,
+    checker: &Checker,
+) -> core::result::Result<Vec<Decl>, TypeError> {
+    let t = resolve_type_var(t, checker);
+    match kind {
+        values::DeclKind::FnDecl(values::FnDecl { name, .. }) => {
+            Ok(vec![build_fn_decl(name, t, declare, ctx, checker)?])
+        }
+        values::DeclKind::ClassDecl(values::ClassDecl { name, .. }) => {
+            let scheme = ctx.get_scheme(qualified_name)?;
+            Ok(build_class_decls(name, &scheme, t, declare, ctx, checker))
+        }
+        values::DeclKind::ModuleDecl(values::ModuleDecl { name, decls, .. }) => {
+            let mut items: Vec<ModuleItem> = vec![];
+            for decl in decls {
+                let member_decls = match &decl.kind {
+                    values::DeclKind::TypeDecl(values::TypeDecl { name, .. }) => {
+                        let scheme = ctx.get_scheme(&format!("{qualified_name}.{name}"))?;
+                        build_type_alias_decls(name, &scheme, false, true, ctx, checker)
+                    }
+                    values::DeclKind::VarDecl(values::VarDecl { pattern, .. }) => {
+                        let mut member_decls = vec![];
+                        for name in get_bindings(pattern) {
+                            let prop = get_prop(t, &name, checker)?;
+                            member_decls.push(build_var_decl_with_type(
+                                &name,
+                                prop.t,
+                                !prop.readonly,
+                                VarDeclKind::Const,
+                                false,
+                                ctx,
+                                checker,
+                            ));
+                        }
+                        member_decls
+                    }
+                    values::DeclKind::FnDecl(values::FnDecl { name, .. })
+                    | values::DeclKind::ClassDecl(values::ClassDecl { name, .. })
+                    | values::DeclKind::ModuleDecl(values::ModuleDecl { name, .. }) => {
+                        let prop = get_prop(t, name, checker)?;
+                        let qualified_name = format!("{qualified_name}.{name}");
+                        build_ambient_decls(
+                            &decl.kind,
+                            &qualified_name,
+                            prop.t,
+                            false,
+                            ctx,
+                            checker,
+                        )?
+                    }
+                    values::DeclKind::GlobalDecl(_) => vec![], // can't be nested
+                };
+                // Members are exported so that they can be accessed outside
+                // of the namespace.
+                for member_decl in member_decls {
+                    items.push(build_module_item(member_decl, true));
+                }
+            }
+
+            Ok(vec![Decl::TsModule(Box::from(TsModuleDecl {
+                span: DUMMY_SP,
+                declare,
+                global: false,
+                id: TsModuleName::Ident(build_ident(name)),
+                body: Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock {
+                    span: DUMMY_SP,
+                    body: items,
+                })),
+            }))])
+        }
+        values::DeclKind::TypeDecl(_)
+        | values::DeclKind::VarDecl(_)
+        | values::DeclKind::GlobalDecl(_) => {
+            unreachable!("only ambient decls are passed to `build_ambient_decls`")
+        }
+    }
+}
+
+fn build_fn_decl(
+    name: &str,
+    t: Index,
+    declare: bool,
+    ctx: &Context,
+    checker: &Checker,
+) -> core::result::Result<Decl, TypeError> {
+    let function = match &checker.arena[t].kind {
+        types::TypeKind::Function(function) => function,
+        _ => {
+            return Err(TypeError {
+                message: format!("expected {name} to be a function"),
+            })
+        }
+    };
+    let names = &checker.type_var_names(&[t]);
+
+    Ok(Decl::Fn(FnDecl {
+        ident: build_ident(name),
+        declare,
+        function: Box::from(build_function(function, ctx, checker, names)),
+    }))
+}
+
+// Builds the class for `declare class` along with the `Readonly` variant of
+// its instance type if it has mutable fields or mutating methods.  `scheme` is
+// the scheme for its instances and `static_t` is the type of the class itself.
+fn build_class_decls(
+    name: &str,
+    scheme: &types::Scheme,
+    static_t: Index,
+    declare: bool,
+    ctx: &Context,
+    checker: &Checker,
+) -> Vec<Decl> {
+    let names = &checker.type_var_names(&[scheme.t, static_t]);
+    let type_params =
+        build_type_params_from_type_params(scheme.type_params.as_ref(), ctx, checker, names);
+
+    let mut body: Vec<ClassMember> = vec![];
+
+    if let types::TypeKind::Object(obj) = &checker.arena[static_t].kind {
+        for elem in &obj.elems {
+            match elem {
+                // TypeScript constructors can't have type params, they use
+                // the class's instead.
+                types::TObjElem::Constructor(constructor) => {
+                    body.push(ClassMember::Constructor(Constructor {
+                        span: DUMMY_SP,
+                        key: PropName::Ident(build_ident("constructor")),
+                        params: build_params(&constructor.params, ctx, checker, names)
+                            .into_iter()
+                            .map(ParamOrTsParamProp::Param)
+                            .collect(),
+                        body: None,
+                        accessibility: None,
+                        is_optional: false,
+                    }));
+                }
+                types::TObjElem::Prop(prop) => {
+                    body.push(build_class_prop(prop, true, ctx, checker, names));
+                }
+                _ => (), // static members are either constructors or props
+            }
+        }
+    }
+
+    let mut decls = vec![];
+
+    if let types::TypeKind::Object(obj) = &checker.arena[scheme.t].kind {
+        for elem in &obj.elems {
+            match elem {
+                types::TObjElem::Prop(prop) => {
+                    body.push(build_class_prop(prop, false, ctx, checker, names));
+                }
+                types::TObjElem::Method(method) => {
+                    body.push(build_class_method(
+                        &method.name,
+                        &method.function,
+                        false,
+                        ctx,
+                        checker,
+                        names,
+                    ));
+                }
+                _ => (), // `declare class` can't have getters or setters
+            }
+        }
+
+        if let Some(obj) = immutable_obj_type(obj) {
+            decls.push(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
+                span: DUMMY_SP,
+                declare,
+                id: build_ident(format!("Readonly{name}").as_str()),
+                type_params: type_params.clone(),
+                type_ann: Box::from(build_obj_type(&obj, ctx, checker, names)),
+            })));
+        }
+    }
+
+    decls.insert(
+        0,
+        Decl::Class(ClassDecl {
+            ident: build_ident(name),
+            declare,
+            class: Box::from(Class {
+                span: DUMMY_SP,
+                decorators: vec![],
+                super_class: None,
+                is_abstract: false,
+                super_type_params: None,
+                type_params,
+                implements: vec![],
+                body,
+            }),
+        }),
+    );
+
+    decls
+}
+
+fn build_class_prop(
+    prop: &types::TProp,
+    is_static: bool,
+    ctx: &Context,
+    checker: &Checker,
+    names: &TypeVarNames,
+) -> ClassMember {
+    // Static methods are readonly props with function types.
+    if is_static && prop.readonly {
+        let t = resolve_type_var(prop.t, checker);
+        if let types::TypeKind::Function(function) = &checker.arena[t].kind {
+            return build_class_method(&prop.name, function, true, ctx, checker, names);
+        }
+    }
+
+    ClassMember::ClassProp(ClassProp {
+        span: DUMMY_SP,
+        value: None,
+        key: build_prop_name(&prop.name),
+        type_ann: Some(Box::from(build_type_ann(&prop.t, ctx, checker, names))),
+        is_static,
+        decorators: vec![],
+        accessibility: None,
+        is_abstract: false,
+        is_optional: prop.optional,
+        is_override: false,
+        readonly: prop.readonly,
+        declare: false,
+        definite: false,
+    })
+}
+
+fn build_class_method(
+    name: &types::TPropKey,
+    function: &types::Function,
+    is_static: bool,
+    ctx: &Context,
+    checker: &Checker,
+    names: &TypeVarNames,
+) -> ClassMember {
+    ClassMember::Method(ClassMethod {
+        span: DUMMY_SP,
+        key: build_prop_name(name),
+        function: Box::from(build_function(function, ctx, checker, names)),
+        kind: MethodKind::Method,
+        is_static,
+        accessibility: None,
+        is_abstract: false,
+        is_optional: false,
+        is_override: false,
+    })
+}
+
+// Builds a function without a body for use in ambient decls.
+fn build_function(
+    function: &types::Function,
+    ctx: &Context,
+    checker: &Checker,
+    names: &TypeVarNames,
+) -> Function {
+    Function {
+        params: build_params(&function.params, ctx, checker, names),
+        decorators: vec![],
+        span: DUMMY_SP,
+        body: None,
+        is_generator: false,
+        is_async: false,
+        type_params: build_type_params_from_type_params(
+            function.type_params.as_ref(),
+            ctx,
+            checker,
+            names,
+        ),
+        return_type: Some(Box::from(build_type_ann(
+            &function.ret,
+            ctx,
+            checker,
+            names,
+        ))),
+    }
+}
+
+// `self` params are skipped since `this` is implicit in TypeScript methods.
+fn build_params(
+    params: &[types::FuncParam],
+    ctx: &Context,
+    checker: &Checker,
+    names: &TypeVarNames,
+) -> Vec<Param> {
+    params
+        .iter()
+        .filter(|param| !param.is_self())
+        .map(|param| {
+            let type_ann = Some(Box::from(build_type_ann(&param.t, ctx, checker, names)));
+            let pat = match tpat_to_pat(&param.pattern, type_ann) {
+                Pat::Ident(bi) => Pat::Ident(BindingIdent {
+                    id: Ident {
+                        optional: param.optional,
+                        ..bi.id
+                    },
+                    ..bi
+                }),
+                pat => pat,
+            };
+            Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat,
+            }
+        })
+        .collect()
+}
+
+fn build_prop_name(key: &types::TPropKey) -> PropName {
+    match key {
+        types::TPropKey::StringKey(key) | types::TPropKey::NumberKey(key) => {
+            PropName::Ident(build_ident(key))
+        }
+        types::TPropKey::SymbolKey(types::TSymbolKey { name, .. }) => {
+            PropName::Computed(ComputedPropName {
+                span: DUMMY_SP,
+                expr: Box::from(build_symbol_key(name)),
+            })
+        }
+    }
+}
+
+// Returns the `name` prop of the object type `t`, this is used to look up the
+// members of `declare module` decls.
+fn get_prop(
+    t: Index,
+    name: &str,
+    checker: &Checker,
+) -> core::result::Result<types::TProp, TypeError> {
+    if let types::TypeKind::Object(obj) = &checker.arena[t].kind {
+        for elem in &obj.elems {
+            match elem {
+                types::TObjElem::Prop(prop)
+                    if prop.name == types::TPropKey::StringKey(name.to_owned()) =>
+                {
+                    return Ok(prop.to_owned());
+                }
+                _ => (),
+            }
+        }
+    }
+    Err(TypeError {
+        message: format!("{name} is not in scope"),
+    })
+}
+
+fn resolve_type_var(t: Index, checker: &Checker) -> Index {
+    match &checker.arena[t].kind {
+        types::TypeKind::TypeVar(types::TypeVar {
+            instance: Some(instance),
+            ..
+        }) => resolve_type_var(*instance, checker),
+        _ => t,
+    }
+}
+
 fn build_d_ts(
     program: &values::Script,
     ctx: &Context,
     checker: &Checker,
+    options: &values::CompilerOptions,
+    comments: &SingleThreadedComments,
 ) -> core::result::Result<Program, TypeError> {
     // TODO: Create a common `Export` type
     let mut type_exports: BTreeSet<String> = BTreeSet::new();
     let mut value_exports: BTreeSet<String> = BTreeSet::new();
+    // Types marked with `export`, this is only used when the script doesn't
+    // export all of its decls.
+    let mut explicit_type_exports: BTreeSet<String> = BTreeSet::new();
+    // The JSDoc for each decl that has doc comments or is deprecated.
+    let mut docs: BTreeMap<String, String> = BTreeMap::new();
+    // The types and values from `declare global` decls.
+    let mut global_types: BTreeSet<String> = BTreeSet::new();
+    let mut global_values: BTreeSet<String> = BTreeSet::new();
+    // `declare fn`, `declare class`, and `declare module` decls in the order
+    // they're declared.
+    let mut ambient_exports: Vec<(&str, &values::Decl)> = vec![];
+    let mut global_ambient_decls: Vec<(&str, &values::Decl)> = vec![];
+    // The local names referenced by `export {...}`.  Values that aren't
+    // otherwise exported are declared without `export` so that the named
+    // exports can refer to them.
+    let named_export_locals: BTreeSet<&str> = program
+        .stmts
+        .iter()
+        .flat_map(|stmt| match &stmt.kind {
+            values::StmtKind::Export(export) => export
+                .specifiers
+                .iter()
+                .map(|specifier| specifier.local.as_str())
+                .collect::<Vec<_>>(),
+            _ => vec![],
+        })
+        .collect();
+    let mut local_values: BTreeSet<String> = BTreeSet::new();
+    let mut local_ambient_decls: Vec<(&str, &values::Decl)> = vec![];
+    // Imports and named exports are kept as-is, including their type-only
+    // specifiers, since the types in the .d.ts file may reference them.
+    let mut imports: Vec<ModuleItem> = vec![];
+    let mut named_exports: Vec<ModuleItem> = vec![];
+    // The local names of everything that's imported.
+    let mut imported_names: BTreeSet<String> = BTreeSet::new();
+
+    let exports_all = program.exports_all();
 
     for stmt in &program.stmts {
         match &stmt.kind {
@@ -90,103 +921,318 @@ fn build_d_ts(
             //     // when we process `type_exports`.
             // }
             values::StmtKind::Decl(decl) => match &decl.kind {
+                // Types are always included since exported values may
+                // reference them.
                 values::DeclKind::TypeDecl(values::TypeDecl { name, .. }) => {
                     type_exports.insert(name.to_owned());
+                    if !exports_all && decl.is_export {
+                        explicit_type_exports.insert(name.to_owned());
+                    }
+                    if let Some(doc) = get_jsdoc(decl) {
+                        docs.insert(name.to_owned(), doc);
+                    }
+                }
+                values::DeclKind::VarDecl(values::VarDecl { pattern, .. })
+                    if !exports_all && !decl.is_export =>
+                {
+                    let bindings = get_bindings(pattern);
+                    local_values.extend(
+                        bindings
+                            .into_iter()
+                            .filter(|name| named_export_locals.contains(name.as_str())),
+                    );
+                }
+                values::DeclKind::FnDecl(values::FnDecl { name, .. })
+                | values::DeclKind::ClassDecl(values::ClassDecl { name, .. })
+                | values::DeclKind::ModuleDecl(values::ModuleDecl { name, .. })
+                    if !exports_all && !decl.is_export =>
+                {
+                    if named_export_locals.contains(name.as_str()) {
+                        local_ambient_decls.push((name.as_str(), decl));
+                    }
                 }
                 values::DeclKind::VarDecl(values::VarDecl { pattern, .. }) => {
                     let bindings = get_bindings(pattern);
                     for name in bindings {
+                        if let Some(doc) = get_jsdoc(decl) {
+                            docs.insert(name.to_owned(), doc);
+                        }
                         value_exports.insert(name);
                     }
                 }
+                values::DeclKind::FnDecl(values::FnDecl { name, .. })
+                | values::DeclKind::ClassDecl(values::ClassDecl { name, .. })
+                | values::DeclKind::ModuleDecl(values::ModuleDecl { name, .. }) => {
+                    ambient_exports.push((name.as_str(), decl));
+                }
+                values::DeclKind::GlobalDecl(values::GlobalDecl { decls }) => {
+                    for decl in decls {
+                        match &decl.kind {
+                            values::DeclKind::TypeDecl(values::TypeDecl { name, .. }) => {
+                                global_types.insert(name.to_owned());
+                            }
+                            values::DeclKind::VarDecl(values::VarDecl { pattern, .. }) => {
+                                global_values.extend(get_bindings(pattern));
+                            }
+                            values::DeclKind::FnDecl(values::FnDecl { name, .. })
+                            | values::DeclKind::ClassDecl(values::ClassDecl { name, .. })
+                            | values::DeclKind::ModuleDecl(values::ModuleDecl { name, .. }) => {
+                                global_ambient_decls.push((name.as_str(), decl));
+                            }
+                            values::DeclKind::GlobalDecl(_) => (), // can't be nested
+                        }
+                    }
+                }
             },
-            values::StmtKind::Expr(_) => (),   // nothing is exported
-            values::StmtKind::For(_) => (),    // nothing is exported
-            values::StmtKind::Return(_) => (), // nothing is exported
+            values::StmtKind::LetElse(values::LetElseStmt { pattern, .. }) if exports_all => {
+                let bindings = get_bindings(pattern);
+                for name in bindings {
+                    value_exports.insert(name);
+                }
+            }
+            values::StmtKind::LetElse(_) => (), // can't be marked with `export`
+            values::StmtKind::Expr(_) => (),    // nothing is exported
+            values::StmtKind::For(_) => (),     // nothing is exported
+            values::StmtKind::Return(_) => (),  // nothing is exported
+            values::StmtKind::Import(import) => {
+                imported_names.extend(
+                    import
+                        .specifiers
+                        .iter()
+                        .map(|specifier| specifier.local.to_owned()),
+                );
+                imports.push(build_import(import));
+            }
+            values::StmtKind::Export(export) => named_exports.push(build_named_export(export)),
         }
     }
 
-    let mut body: Vec<ModuleItem> = vec![];
+    // Scripts compiled to IIFEs don't export anything so only the decls
+    // inside of `declare global` are included.
+    if options.module_format == values::ModuleFormat::Iife {
+        type_exports.clear();
+        value_exports.clear();
+        ambient_exports.clear();
+        local_values.clear();
+        local_ambient_decls.clear();
+        imports.clear();
+        named_exports.clear();
+    }
+
+    let mut body: Vec<ModuleItem> = imports;
 
     for name in type_exports {
+        let is_export = explicit_type_exports.contains(&name);
+        let start = body.len();
+
         let scheme = ctx.get_scheme(&name)?;
+        for decl in build_type_alias_decls(&name, &scheme, true, true, ctx, checker) {
+            body.push(build_module_item(decl, is_export));
+        }
 
-        let type_params =
-            build_type_params_from_type_params(scheme.type_params.as_ref(), ctx, checker);
+        // Only the first decl is documented, the `Readonly` variant shares
+        // its docs.
+        if let Some(doc) = docs.get(&name) {
+            attach_doc(&mut body[start], start, doc, comments);
+        }
+    }
 
-        if let types::TypeKind::Object(obj) = &checker.arena[scheme.t].kind {
-            let mutable_decl =
-                ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
-                    span: DUMMY_SP,
-                    declare: true,
-                    id: build_ident(&name),
-                    type_params: type_params.clone(),
-                    type_ann: Box::from(build_obj_type(obj, ctx, checker)),
-                }))));
-            body.push(mutable_decl);
-
-            if !name.ends_with("Constructor") {
-                if let Some(obj) = immutable_obj_type(obj) {
-                    let immutable_decl = ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(
-                        Box::from(TsTypeAliasDecl {
-                            span: DUMMY_SP,
-                            declare: true,
-                            id: build_ident(format!("Readonly{name}").as_str()),
-                            type_params,
-                            type_ann: Box::from(build_obj_type(&obj, ctx, checker)),
-                        }),
-                    )));
+    for name in value_exports {
+        let decl = build_var_decl(&name, true, ctx, checker)?;
+        let mut item = build_module_item(decl, true);
 
-                    body.push(immutable_decl);
-                }
-            }
-        } else {
-            let decl =
-                ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(Box::from(TsTypeAliasDecl {
-                    span: DUMMY_SP,
-                    declare: true,
-                    id: build_ident(&name),
-                    type_params,
-                    type_ann: Box::from(build_type(&scheme.t, ctx, checker)),
-                }))));
+        if let Some(doc) = docs.get(&name) {
+            attach_doc(&mut item, body.len(), doc, comments);
+        }
 
-            body.push(decl);
+        body.push(item);
+    }
+
+    for (name, decl) in ambient_exports {
+        let binding = ctx.get_binding(name)?;
+        let start = body.len();
+
+        for ts_decl in build_ambient_decls(&decl.kind, name, binding.index, true, ctx, checker)? {
+            body.push(build_module_item(ts_decl, true));
+        }
+
+        if let Some(doc) = get_jsdoc(decl) {
+            attach_doc(&mut body[start], start, &doc, comments);
         }
     }
 
-    for name in value_exports {
-        let binding = ctx.get_binding(&name)?;
+    for name in local_values {
+        let decl = build_var_decl(&name, true, ctx, checker)?;
+        body.push(build_module_item(decl, false));
+    }
 
-        let pat = Pat::Ident(BindingIdent {
-            id: build_ident(&name),
-            type_ann: Some(Box::from(TsTypeAnn {
-                span: DUMMY_SP,
-                type_ann: Box::from(build_type(&binding.index, ctx, checker)),
-            })),
-        });
+    for (name, decl) in local_ambient_decls {
+        let binding = ctx.get_binding(name)?;
+        for ts_decl in build_ambient_decls(&decl.kind, name, binding.index, true, ctx, checker)? {
+            body.push(build_module_item(ts_decl, false));
+        }
+    }
 
-        let decl = ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-            span: DUMMY_SP,
-            decl: Decl::Var(Box::from(VarDecl {
+    body.extend(named_exports);
+
+    if !global_types.is_empty() || !global_values.is_empty() || !global_ambient_decls.is_empty() {
+        // Decls inside of `declare global` are already ambient so they can't
+        // use `declare`.  They describe values that are defined elsewhere so
+        // their types are used as is instead of adding `Readonly` variants to
+        // the global scope.
+        let mut items: Vec<ModuleItem> = vec![];
+        for name in global_types {
+            let scheme = ctx.get_scheme(&name)?;
+            for decl in build_type_alias_decls(&name, &scheme, false, false, ctx, checker) {
+                items.push(build_module_item(decl, false));
+            }
+        }
+        for name in global_values {
+            let binding = ctx.get_binding(&name)?;
+            let decl = build_var_decl_with_type(
+                &name,
+                binding.index,
+                true,
+                VarDeclKind::Var,
+                false,
+                ctx,
+                checker,
+            );
+            items.push(build_module_item(decl, false));
+        }
+        for (name, decl) in global_ambient_decls {
+            let binding = ctx.get_binding(name)?;
+            for ts_decl in
+                build_ambient_decls(&decl.kind, name, binding.index, false, ctx, checker)?
+            {
+                items.push(build_module_item(ts_decl, false));
+            }
+        }
+
+        // `declare global` can only be used in modules.
+        let is_module = body
+            .iter()
+            .any(|item| matches!(item, ModuleItem::ModuleDecl(_)));
+
+        body.push(ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(Box::from(
+            TsModuleDecl {
                 span: DUMMY_SP,
-                kind: VarDeclKind::Const,
                 declare: true,
-                decls: vec![VarDeclarator {
+                global: true,
+                id: TsModuleName::Ident(build_ident("global")),
+                body: Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock {
                     span: DUMMY_SP,
-                    name: pat,
-                    init: None,
-                    definite: false,
-                }],
-            })),
-        }));
+                    body: items,
+                })),
+            },
+        )))));
 
-        body.push(decl);
+        if !is_module {
+            body.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+                NamedExport {
+                    span: DUMMY_SP,
+                    specifiers: vec![],
+                    src: None,
+                    type_only: false,
+                    asserts: None,
+                },
+            )));
+        }
     }
 
-    Ok(Program::Module(Module {
+    let mut program = Program::Module(Module {
         span: DUMMY_SP,
         body,
         shebang: None,
+    });
+    program.visit_mut_with(&mut ImportedTypeRefs {
+        names: &imported_names,
+    });
+
+    Ok(program)
+}
+
+// Types are imported from other modules without their `Readonly` variants so
+// refs to them use the `Readonly` utility type instead, e.g. `ReadonlyPoint`
+// becomes `Readonly<Point>`.
+struct ImportedTypeRefs<'a> {
+    names: &'a BTreeSet<String>,
+}
+
+impl VisitMut for ImportedTypeRefs<'_> {
+    fn visit_mut_ts_type(&mut self, t: &mut TsType) {
+        t.visit_mut_children_with(self);
+        if let TsType::TsTypeRef(TsTypeRef {
+            type_name: TsEntityName::Ident(ident),
+            ..
+        }) = t
+        {
+            let name = match ident.sym.strip_prefix("Readonly") {
+                Some(name) if self.names.contains(name) => JsWord::from(name),
+                _ => return,
+            };
+            ident.sym = name;
+            *t = TsType::TsTypeRef(TsTypeRef {
+                span: DUMMY_SP,
+                type_name: TsEntityName::from(build_ident("Readonly")),
+                type_params: Some(Box::from(TsTypeParamInstantiation {
+                    span: DUMMY_SP,
+                    params: vec![Box::from(t.clone())],
+                })),
+            });
+        }
+    }
+}
+
+fn build_import(import: &values::Import) -> ModuleItem {
+    ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span: DUMMY_SP,
+        specifiers: import
+            .specifiers
+            .iter()
+            .map(|specifier| {
+                ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: DUMMY_SP,
+                    local: build_ident(&specifier.local),
+                    imported: specifier
+                        .imported
+                        .as_ref()
+                        .map(|imported| ModuleExportName::Ident(build_ident(imported))),
+                    is_type_only: specifier.is_type_only,
+                })
+            })
+            .collect(),
+        src: Box::from(Str {
+            span: DUMMY_SP,
+            value: JsWord::from(import.source.as_str()),
+            raw: None,
+        }),
+        type_only: import.is_type_only,
+        asserts: None,
+    }))
+}
+
+fn build_named_export(export: &values::NamedExport) -> ModuleItem {
+    ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+        span: DUMMY_SP,
+        specifiers: export
+            .specifiers
+            .iter()
+            .map(|specifier| {
+                ExportSpecifier::Named(ExportNamedSpecifier {
+                    span: DUMMY_SP,
+                    orig: ModuleExportName::Ident(build_ident(&specifier.local)),
+                    exported: specifier
+                        .exported
+                        .as_ref()
+                        .map(|exported| ModuleExportName::Ident(build_ident(exported))),
+                    is_type_only: specifier.is_type_only,
+                })
+            })
+            .collect(),
+        src: None,
+        type_only: export.is_type_only,
+        asserts: None,
     }))
 }
 
@@ -199,6 +1245,20 @@ pub fn build_ident(name: &str) -> Ident {
     }
 }
 
+// Symbol keys are stored using the path they were accessed through, e.g.
+// `Symbol.iterator`, which is converted back to a member expression.
+fn build_symbol_key(name: &str) -> Expr {
+    let mut parts = name.split('.');
+    let first = Expr::from(build_ident(parts.next().unwrap_or_default()));
+    parts.fold(first, |obj, prop| {
+        Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::from(obj),
+            prop: MemberProp::Ident(build_ident(prop)),
+        })
+    })
+}
+
 pub fn build_ts_pattern(pat: &types::TPat) -> Pat {
     match pat {
         types::TPat::Ident(bi) => Pat::Ident(BindingIdent {
@@ -211,6 +1271,11 @@ pub fn build_ts_pattern(pat: &types::TPat) -> Pat {
 
 fn tpat_to_pat(pat: &types::TPat, type_ann: Option<Box<TsTypeAnn>>) -> Pat {
     match pat {
+        // `self` params are `this` params in TypeScript.
+        types::TPat::Ident(bi) if bi.name == "self" => Pat::Ident(BindingIdent {
+            id: build_ident("this"),
+            type_ann,
+        }),
         types::TPat::Ident(bi) => Pat::Ident(BindingIdent {
             id: build_ident(&bi.name),
             type_ann,
@@ -226,7 +1291,11 @@ fn tpat_to_pat(pat: &types::TPat, type_ann: Option<Box<TsTypeAnn>>) -> Pat {
             elems: tuple
                 .elems
                 .iter()
-                .map(|elem| elem.as_ref().map(|elem| tpat_to_pat(elem, None)))
+                .map(|elem| match elem {
+                    Some(elem) if !is_binding(elem) => None,
+                    Some(elem) => Some(tpat_to_pat(elem, None)),
+                    None => None,
+                })
                 .collect(),
             optional: false,
             type_ann,
@@ -237,6 +1306,15 @@ fn tpat_to_pat(pat: &types::TPat, type_ann: Option<Box<TsTypeAnn>>) -> Pat {
                 .iter()
                 .map(|prop| {
                     match prop {
+                        // Refinements like `{kind: "click"}` don't bind
+                        // anything so we only include the key.
+                        types::TObjectPatProp::KeyValue(kv) if !is_binding(&kv.value) => {
+                            ObjectPatProp::Assign(AssignPatProp {
+                                span: DUMMY_SP,
+                                key: build_ident(&kv.key),
+                                value: None,
+                            })
+                        }
                         types::TObjectPatProp::KeyValue(kv) => {
                             ObjectPatProp::KeyValue(KeyValuePatProp {
                                 key: PropName::Ident(build_ident(&kv.key)),
@@ -267,12 +1345,27 @@ fn tpat_to_pat(pat: &types::TPat, type_ann: Option<Box<TsTypeAnn>>) -> Pat {
                 type_ann,
             })
         }
-        types::TPat::Lit(_) => todo!(),
-        types::TPat::Is(_) => todo!(),
-        types::TPat::Wildcard => todo!(),
+        types::TPat::Is(is) => Pat::Ident(BindingIdent {
+            id: build_ident(&is.ident),
+            type_ann,
+        }),
+        types::TPat::Lit(_) | types::TPat::Range(_) | types::TPat::Wildcard => {
+            Pat::Ident(BindingIdent {
+                id: build_ident("_"),
+                type_ann,
+            })
+        }
     }
 }
 
+// Returns false for patterns that only refine a value without binding it.
+fn is_binding(pat: &types::TPat) -> bool {
+    !matches!(
+        pat,
+        types::TPat::Lit(_) | types::TPat::Range(_) | types::TPat::Wildcard
+    )
+}
+
 pub fn pat_to_fn_param(param: &types::FuncParam, pat: Pat) -> TsFnParam {
     match pat {
         Pat::Ident(bi) => {
@@ -297,11 +1390,12 @@ pub fn build_ts_fn_type_with_params(
     type_params: Option<Box<TsTypeParamDecl>>,
     ctx: &Context,
     checker: &Checker,
+    names: &TypeVarNames,
 ) -> TsType {
     let params: Vec<TsFnParam> = params
         .iter()
         .map(|param| {
-            let type_ann = Some(Box::from(build_type_ann(&param.t, ctx, checker)));
+            let type_ann = Some(Box::from(build_type_ann(&param.t, ctx, checker, names)));
             let pat = tpat_to_pat(&param.pattern, type_ann);
             pat_to_fn_param(param, pat)
         })
@@ -311,7 +1405,7 @@ pub fn build_ts_fn_type_with_params(
         span: DUMMY_SP,
         params,
         type_params,
-        type_ann: Box::from(build_type_ann(ret, ctx, checker)),
+        type_ann: Box::from(build_type_ann(ret, ctx, checker, names)),
     }))
 }
 
@@ -324,10 +1418,21 @@ pub fn build_type(
     // type_params: Option<&TsTypeParamDecl>,
     ctx: &Context,
     checker: &Checker,
+    names: &TypeVarNames,
+) -> TsType {
+    build_type_with_mutability(t, false, ctx, checker, names)
+}
+
+/// Converts an internal Type to a TsType, `mutable` types, e.g. the type of a
+/// `let mut` binding, don't use `readonly` or the `Readonly` variants of types.
+pub fn build_type_with_mutability(
+    t: &Index,
+    mutable: bool,
+    ctx: &Context,
+    checker: &Checker,
+    names: &TypeVarNames,
 ) -> TsType {
     let t = &checker.arena[*t];
-    let mutable = false;
-    // let mutable = t.mutable;
     match &t.kind {
         types::TypeKind::TypeVar(types::TypeVar {
             id,
@@ -335,22 +1440,22 @@ pub fn build_type(
             instance,
         }) => {
             if let Some(instance) = instance {
-                return build_type(instance, ctx, checker);
+                return build_type_with_mutability(instance, mutable, ctx, checker, names);
             }
 
             // TODO: handle constraints on type variables
             // This will likely be easier if we stop using type variables for
             // type parameters.
-            let chars: Vec<_> = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz"
-                .chars()
-                .collect();
-            let id = chars.get(*id).unwrap();
+            let name = match names.get(id) {
+                Some(name) => name.to_owned(),
+                None => format!("T{id}"),
+            };
 
             TsType::TsTypeRef(TsTypeRef {
                 span: DUMMY_SP,
                 type_name: TsEntityName::from(Ident {
                     span: DUMMY_SP,
-                    sym: JsWord::from(format!("{id}")),
+                    sym: JsWord::from(name),
                     optional: false,
                 }),
                 type_params: None,
@@ -373,7 +1478,11 @@ pub fn build_type(
         }
         types::TypeKind::Primitive(primitive) => {
             let kind = match primitive {
-                types::Primitive::Number => TsKeywordTypeKind::TsNumberKeyword,
+                // `int` and `float` only exist at compile time.
+                types::Primitive::Number | types::Primitive::Int | types::Primitive::Float => {
+                    TsKeywordTypeKind::TsNumberKeyword
+                }
+                types::Primitive::BigInt => TsKeywordTypeKind::TsBigIntKeyword,
                 types::Primitive::Boolean => TsKeywordTypeKind::TsBooleanKeyword,
                 types::Primitive::String => TsKeywordTypeKind::TsStringKeyword,
                 types::Primitive::Symbol => TsKeywordTypeKind::TsSymbolKeyword,
@@ -384,6 +1493,14 @@ pub fn build_type(
                 kind,
             })
         }
+        types::TypeKind::UniqueSymbol(_) => TsType::TsTypeOperator(TsTypeOperator {
+            span: DUMMY_SP,
+            op: TsTypeOperatorOp::Unique,
+            type_ann: Box::from(TsType::TsKeywordType(TsKeywordType {
+                span: DUMMY_SP,
+                kind: TsKeywordTypeKind::TsSymbolKeyword,
+            })),
+        }),
         types::TypeKind::Literal(lit) => {
             let lit = match lit {
                 values::Literal::Number(n) => TsLit::Number(Number {
@@ -391,6 +1508,11 @@ pub fn build_type(
                     value: n.parse().unwrap(),
                     raw: Some(Atom::new(n.clone())),
                 }),
+                values::Literal::BigInt(n) => TsLit::BigInt(BigInt {
+                    span: DUMMY_SP,
+                    value: Box::new(n.parse().unwrap()),
+                    raw: None,
+                }),
                 values::Literal::Boolean(b) => TsLit::Bool(Bool {
                     span: DUMMY_SP,
                     value: b.to_owned(),
@@ -426,15 +1548,15 @@ pub fn build_type(
             throws: _,
         }) => {
             let type_params =
-                build_type_params_from_type_params(type_params.as_ref(), ctx, checker);
-            build_ts_fn_type_with_params(params, ret, type_params, ctx, checker)
+                build_type_params_from_type_params(type_params.as_ref(), ctx, checker, names);
+            build_ts_fn_type_with_params(params, ret, type_params, ctx, checker, names)
         }
         types::TypeKind::Union(types::Union { types }) => {
             TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(TsUnionType {
                 span: DUMMY_SP,
                 types: sort_types(types)
                     .iter()
-                    .map(|t| Box::from(build_type(t, ctx, checker)))
+                    .map(|t| Box::from(build_type(t, ctx, checker, names)))
                     .collect(),
             }))
         }
@@ -444,12 +1566,17 @@ pub fn build_type(
                     span: DUMMY_SP,
                     types: sort_types(types)
                         .iter()
-                        .map(|t| Box::from(build_type(t, ctx, checker)))
+                        .map(|t| Box::from(build_type(t, ctx, checker, names)))
                         .collect(),
                 },
             ))
         }
-        types::TypeKind::Object(obj) => build_obj_type(obj, ctx, checker),
+        types::TypeKind::Object(obj) => build_obj_type(obj, ctx, checker, names),
+        // The `Self` type of methods, it's the type of the object they're
+        // called on.
+        types::TypeKind::TypeRef(types::TypeRef {
+            name, scheme: None, ..
+        }) if name == "Self" => TsType::TsThisType(TsThisType { span: DUMMY_SP }),
         types::TypeKind::TypeRef(types::TypeRef {
             name, type_args, ..
         }) => {
@@ -469,7 +1596,14 @@ pub fn build_type(
                                 // TODO: keep track of which types are derived from
                                 // .d.ts files and whether or not they have Readonly
                                 // variants like ReadonlyArray, ReadonlySet, etc.
-                                sym = JsWord::from(format!("Readonly{name}"));
+                                // The variants of types declared in namespaces
+                                // are in the same namespace, e.g. `Intl.ReadonlyFormat`.
+                                sym = JsWord::from(match name.rsplit_once('.') {
+                                    Some((namespace, name)) => {
+                                        format!("{namespace}.Readonly{name}")
+                                    }
+                                    None => format!("Readonly{name}"),
+                                });
                             }
                         }
                     }
@@ -477,19 +1611,23 @@ pub fn build_type(
             }
 
             // Reverse overrides when exporting types
-            let type_args =
-                if type_args.is_empty() || name == "RegExpMatchArray" || name == "RegExp" {
-                    None
-                } else {
-                    // swc's AST calls these type params when really they're type args
-                    Some(Box::from(TsTypeParamInstantiation {
-                        span: DUMMY_SP,
-                        params: type_args
-                            .iter()
-                            .map(|t| Box::from(build_type(t, ctx, checker)))
-                            .collect(),
-                    }))
-                };
+            let type_args = if type_args.is_empty()
+                || name == "RegExpMatchArray"
+                || name == "RegExp"
+            {
+                None
+            } else {
+                // swc's AST calls these type params when really they're type args
+                Some(Box::from(TsTypeParamInstantiation {
+                    span: DUMMY_SP,
+                    params: type_args
+                        .iter()
+                        .map(|t| {
+                            Box::from(build_type_with_mutability(t, mutable, ctx, checker, names))
+                        })
+                        .collect(),
+                }))
+            };
 
             let t = TsType::TsTypeRef(TsTypeRef {
                 span: DUMMY_SP,
@@ -519,20 +1657,32 @@ pub fn build_type(
                 t
             }
         }
-        types::TypeKind::Tuple(types::Tuple { types }) => {
+        types::TypeKind::Tuple(types::Tuple {
+            types,
+            labels,
+            readonly,
+        }) => {
             let type_ann = TsType::TsTupleType(TsTupleType {
                 span: DUMMY_SP,
                 elem_types: types
                     .iter()
-                    .map(|t| TsTupleElement {
+                    .enumerate()
+                    .map(|(i, t)| TsTupleElement {
                         span: DUMMY_SP,
-                        label: None,
-                        ty: Box::from(build_type(t, ctx, checker)),
+                        label: labels.as_ref().map(|labels| {
+                            Pat::Ident(BindingIdent {
+                                id: build_ident(&labels[i]),
+                                type_ann: None,
+                            })
+                        }),
+                        ty: Box::from(build_type_with_mutability(t, mutable, ctx, checker, names)),
                     })
                     .collect(),
             });
 
-            if mutable {
+            // Arrays and tuples explicitly marked as `readonly` stay that way
+            // even when they're bound to mutable variables.
+            if mutable && !readonly {
                 type_ann
             } else {
                 TsType::TsTypeOperator(TsTypeOperator {
@@ -542,13 +1692,13 @@ pub fn build_type(
                 })
             }
         }
-        types::TypeKind::Array(types::Array { t }) => {
+        types::TypeKind::Array(types::Array { t, readonly }) => {
             let type_ann = TsType::TsArrayType(TsArrayType {
                 span: DUMMY_SP,
-                elem_type: Box::from(build_type(t, ctx, checker)),
+                elem_type: Box::from(build_type_with_mutability(t, mutable, ctx, checker, names)),
             });
 
-            if mutable {
+            if mutable && !readonly {
                 type_ann
             } else {
                 TsType::TsTypeOperator(TsTypeOperator {
@@ -563,14 +1713,14 @@ pub fn build_type(
         types::TypeKind::KeyOf(types::KeyOf { t }) => TsType::TsTypeOperator(TsTypeOperator {
             span: DUMMY_SP,
             op: TsTypeOperatorOp::KeyOf,
-            type_ann: Box::from(build_type(t, ctx, checker)),
+            type_ann: Box::from(build_type(t, ctx, checker, names)),
         }),
         types::TypeKind::IndexedAccess(types::IndexedAccess { obj: object, index }) => {
             TsType::TsIndexedAccessType(TsIndexedAccessType {
                 span: DUMMY_SP,
                 readonly: false,
-                obj_type: Box::from(build_type(object, ctx, checker)),
-                index_type: Box::from(build_type(index, ctx, checker)),
+                obj_type: Box::from(build_type(object, ctx, checker, names)),
+                index_type: Box::from(build_type(index, ctx, checker, names)),
             })
         }
         types::TypeKind::Conditional(types::Conditional {
@@ -580,10 +1730,10 @@ pub fn build_type(
             false_type,
         }) => TsType::TsConditionalType(TsConditionalType {
             span: DUMMY_SP,
-            check_type: Box::from(build_type(check_type, ctx, checker)),
-            extends_type: Box::from(build_type(extends_type, ctx, checker)),
-            true_type: Box::from(build_type(true_type, ctx, checker)),
-            false_type: Box::from(build_type(false_type, ctx, checker)),
+            check_type: Box::from(build_type(check_type, ctx, checker, names)),
+            extends_type: Box::from(build_type(extends_type, ctx, checker, names)),
+            true_type: Box::from(build_type(true_type, ctx, checker, names)),
+            false_type: Box::from(build_type(false_type, ctx, checker, names)),
         }),
         types::TypeKind::Infer(types::Infer { name }) => TsType::TsInferType(TsInferType {
             span: DUMMY_SP,
@@ -610,11 +1760,24 @@ pub fn build_type(
             // a `number` or `boolean` type.
             todo!()
         }
+        // TypeScript doesn't have anonymous recursive types, they'd need to
+        // be declared as type aliases.
+        types::TypeKind::Mu(_) | types::TypeKind::MuVar(_) => {
+            TsType::TsKeywordType(TsKeywordType {
+                span: DUMMY_SP,
+                kind: TsKeywordTypeKind::TsUnknownKeyword,
+            })
+        }
     }
 }
 
 // TODO: generate separate types for immutable and mutable object types
-fn build_obj_type(obj: &types::Object, ctx: &Context, checker: &Checker) -> TsType {
+fn build_obj_type(
+    obj: &types::Object,
+    ctx: &Context,
+    checker: &Checker,
+    names: &TypeVarNames,
+) -> TsType {
     let mut members: Vec<TsTypeElement> = vec![];
     let mut mapped_types: Vec<TsType> = vec![];
 
@@ -628,11 +1791,12 @@ fn build_obj_type(obj: &types::Object, ctx: &Context, checker: &Checker) -> TsTy
                 throws: _, // TODO
             }) => {
                 let type_params =
-                    build_type_params_from_type_params(type_params.as_ref(), ctx, checker);
+                    build_type_params_from_type_params(type_params.as_ref(), ctx, checker, names);
                 let params: Vec<TsFnParam> = params
                     .iter()
                     .map(|param| {
-                        let type_ann = Some(Box::from(build_type_ann(&param.t, ctx, checker)));
+                        let type_ann =
+                            Some(Box::from(build_type_ann(&param.t, ctx, checker, names)));
                         let pat = tpat_to_pat(&param.pattern, type_ann);
                         pat_to_fn_param(param, pat)
                     })
@@ -641,30 +1805,66 @@ fn build_obj_type(obj: &types::Object, ctx: &Context, checker: &Checker) -> TsTy
                 let type_elem = TsTypeElement::TsConstructSignatureDecl(TsConstructSignatureDecl {
                     span: DUMMY_SP,
                     params,
-                    type_ann: Some(Box::from(build_type_ann(ret, ctx, checker))),
+                    type_ann: Some(Box::from(build_type_ann(ret, ctx, checker, names))),
                     type_params,
                 });
 
                 members.push(type_elem);
             }
-            types::TObjElem::Method(_) => todo!(), // TODO
+            // Methods are emitted as readonly props with function types.
+            types::TObjElem::Method(types::TMethod { name, function, .. }) => {
+                let (key, computed) = build_prop_key(name);
+                let params: Vec<types::FuncParam> = function
+                    .params
+                    .iter()
+                    .filter(|param| !param.is_self())
+                    .cloned()
+                    .collect();
+                let type_params = build_type_params_from_type_params(
+                    function.type_params.as_ref(),
+                    ctx,
+                    checker,
+                    names,
+                );
+                let t = build_ts_fn_type_with_params(
+                    &params,
+                    &function.ret,
+                    type_params,
+                    ctx,
+                    checker,
+                    names,
+                );
+
+                let type_elem = TsTypeElement::TsPropertySignature(TsPropertySignature {
+                    span: DUMMY_SP,
+                    readonly: true,
+                    key: Box::from(key),
+                    computed,
+                    optional: false,
+                    init: None,
+                    params: vec![],
+                    type_ann: Some(Box::from(TsTypeAnn {
+                        span: DUMMY_SP,
+                        type_ann: Box::from(t),
+                    })),
+                    type_params: None,
+                });
+                members.push(type_elem);
+            }
             types::TObjElem::Getter(_) => todo!(), // TODO
             types::TObjElem::Setter(_) => todo!(), // TODO
             types::TObjElem::Prop(prop) => {
-                let key = match &prop.name {
-                    types::TPropKey::StringKey(key) => key.to_owned(),
-                    types::TPropKey::NumberKey(key) => key.to_owned(),
-                };
+                let (key, computed) = build_prop_key(&prop.name);
 
                 let type_elem = TsTypeElement::TsPropertySignature(TsPropertySignature {
                     span: DUMMY_SP,
                     readonly: prop.readonly,
-                    key: Box::from(Expr::from(build_ident(&key))),
-                    computed: false,
+                    key: Box::from(key),
+                    computed,
                     optional: prop.optional,
                     init: None,
                     params: vec![],
-                    type_ann: Some(Box::from(build_type_ann(&prop.t, ctx, checker))),
+                    type_ann: Some(Box::from(build_type_ann(&prop.t, ctx, checker, names))),
                     type_params: None,
                 });
                 members.push(type_elem);
@@ -683,8 +1883,8 @@ fn build_obj_type(obj: &types::Object, ctx: &Context, checker: &Checker) -> TsTy
                     span: DUMMY_SP,
                     readonly: None, // TODO
                     optional: None, // TODO
-                    name_type: Some(Box::new(build_type(key, ctx, checker))),
-                    type_ann: Some(Box::new(build_type(value, ctx, checker))),
+                    name_type: Some(Box::new(build_type(key, ctx, checker, names))),
+                    type_ann: Some(Box::new(build_type(value, ctx, checker, names))),
                     type_param: TsTypeParam {
                         span: DUMMY_SP,
                         name: Ident {
@@ -695,7 +1895,7 @@ fn build_obj_type(obj: &types::Object, ctx: &Context, checker: &Checker) -> TsTy
                         is_in: true,
                         is_out: false,
                         is_const: false,
-                        constraint: Some(Box::new(build_type(source, ctx, checker))),
+                        constraint: Some(Box::new(build_type(source, ctx, checker, names))),
                         default: None, // TODO
                     },
                 });
@@ -741,10 +1941,21 @@ fn build_obj_type(obj: &types::Object, ctx: &Context, checker: &Checker) -> TsTy
     }
 }
 
-fn build_type_ann(t: &Index, ctx: &Context, checker: &Checker) -> TsTypeAnn {
+// Returns the key of a property signature and whether it's computed.
+fn build_prop_key(key: &types::TPropKey) -> (Expr, bool) {
+    match key {
+        types::TPropKey::StringKey(key) => (Expr::from(build_ident(key)), false),
+        types::TPropKey::NumberKey(key) => (Expr::from(build_ident(key)), false),
+        types::TPropKey::SymbolKey(types::TSymbolKey { name, .. }) => {
+            (build_symbol_key(name), true)
+        }
+    }
+}
+
+fn build_type_ann(t: &Index, ctx: &Context, checker: &Checker, names: &TypeVarNames) -> TsTypeAnn {
     TsTypeAnn {
         span: DUMMY_SP,
-        type_ann: Box::from(build_type(t, ctx, checker)),
+        type_ann: Box::from(build_type(t, ctx, checker, names)),
     }
 }
 
@@ -792,6 +2003,7 @@ pub fn immutable_obj_type(obj: &types::Object) -> Option<types::Object> {
     if changed {
         Some(types::Object {
             elems,
+            is_fresh: obj.is_fresh,
             // is_interface: obj.is_interface,
         })
     } else {
@@ -805,14 +2017,29 @@ struct BindingsVisitor {
 
 impl Visitor for BindingsVisitor {
     fn visit_pattern(&mut self, pattern: &values::Pattern) {
-        if let values::PatternKind::Ident(ident) = &pattern.kind {
-            self.bindings.push(ident.name.to_owned())
+        match &pattern.kind {
+            values::PatternKind::Ident(ident) => self.bindings.push(ident.name.to_owned()),
+            values::PatternKind::Is(values::IsPat { ident, .. }) => {
+                self.bindings.push(ident.name.to_owned())
+            }
+            values::PatternKind::Object(values::ObjectPat { props, .. }) => {
+                for prop in props {
+                    if let values::ObjectPatProp::Shorthand(shorthand) = prop {
+                        self.bindings.push(shorthand.ident.name.to_owned())
+                    }
+                }
+            }
+            _ => (),
         }
         values::walk_pattern(self, pattern)
     }
+
+    // Default values can contain patterns, e.g. function params, but they
+    // don't introduce any bindings in the enclosing scope.
+    fn visit_expr(&mut self, _expr: &values::Expr) {}
 }
 
-fn get_bindings(pattern: &values::Pattern) -> Vec<String> {
+pub(crate) fn get_bindings(pattern: &values::Pattern) -> Vec<String> {
     let mut visitor = BindingsVisitor { bindings: vec![] };
     visitor.visit_pattern(pattern);
     visitor.bindings
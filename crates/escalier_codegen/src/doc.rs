@@ -0,0 +1,131 @@
+use serde::Serialize;
+
+use escalier_ast::{self as values};
+use escalier_hm::checker::Checker;
+use escalier_hm::context::Context;
+use escalier_hm::printer::PrintConfig;
+use escalier_hm::type_error::TypeError;
+
+use crate::d_ts::get_bindings;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum DocItemKind {
+    Type,
+    Value,
+}
+
+#[derive(Clone, Debug, Serialize)]
+struct DocItem {
+    name: String,
+    kind: DocItemKind,
+    signature: String,
+    doc: Option<String>,
+}
+
+/// Generates documentation for the decls exported by `program` in the order
+/// in which they're declared.  Each item includes its inferred signature along
+/// with the text of its `///` comments.
+pub fn codegen_doc(
+    program: &values::Script,
+    ctx: &Context,
+    checker: &Checker,
+    format: DocFormat,
+) -> core::result::Result<String, TypeError> {
+    let items = build_doc_items(program, ctx, checker)?;
+
+    Ok(match format {
+        DocFormat::Markdown => print_markdown(&items),
+        DocFormat::Json => serde_json::to_string_pretty(&items).unwrap(),
+    })
+}
+
+fn build_doc_items(
+    program: &values::Script,
+    ctx: &Context,
+    checker: &Checker,
+) -> core::result::Result<Vec<DocItem>, TypeError> {
+    let exports_all = program.exports_all();
+    let config = PrintConfig {
+        multiline: true,
+        stable_type_vars: true,
+        ..Default::default()
+    };
+    let mut items: Vec<DocItem> = vec![];
+
+    for stmt in &program.stmts {
+        let decl = match &stmt.kind {
+            values::StmtKind::Decl(decl) if exports_all || decl.is_export => decl,
+            _ => continue,
+        };
+
+        match &decl.kind {
+            values::DeclKind::TypeDecl(values::TypeDecl { name, .. }) => {
+                let scheme = ctx.get_scheme(name)?;
+                items.push(DocItem {
+                    name: name.to_owned(),
+                    kind: DocItemKind::Type,
+                    signature: checker.print_type_decl(name, &scheme, &config),
+                    doc: decl.doc.clone(),
+                });
+            }
+            values::DeclKind::VarDecl(values::VarDecl {
+                pattern, is_var, ..
+            }) => {
+                let keyword = if *is_var { "var" } else { "let" };
+                for name in get_bindings(pattern) {
+                    let binding = ctx.get_binding(&name)?;
+                    let t = checker.print_type_with_config(&binding.index, &config);
+                    items.push(DocItem {
+                        signature: format!("{keyword} {name}: {t}"),
+                        name,
+                        kind: DocItemKind::Value,
+                        doc: decl.doc.clone(),
+                    });
+                }
+            }
+            values::DeclKind::FnDecl(values::FnDecl { name, .. })
+            | values::DeclKind::ClassDecl(values::ClassDecl { name, .. })
+            | values::DeclKind::ModuleDecl(values::ModuleDecl { name, .. }) => {
+                let keyword = match &decl.kind {
+                    values::DeclKind::FnDecl(_) => "fn",
+                    values::DeclKind::ClassDecl(_) => "class",
+                    _ => "module",
+                };
+                let binding = ctx.get_binding(name)?;
+                let t = checker.print_type_with_config(&binding.index, &config);
+                items.push(DocItem {
+                    name: name.to_owned(),
+                    kind: DocItemKind::Value,
+                    signature: format!("declare {keyword} {name}: {t}"),
+                    doc: decl.doc.clone(),
+                });
+            }
+            // `declare global` decls aren't exported by the module.
+            values::DeclKind::GlobalDecl(_) => (),
+        }
+    }
+
+    Ok(items)
+}
+
+fn print_markdown(items: &[DocItem]) -> String {
+    let mut result = String::new();
+    for item in items {
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(&format!("## `{}`\n\n", item.name));
+        result.push_str(&format!("```escalier\n{}\n```\n", item.signature));
+        if let Some(doc) = &item.doc {
+            result.push_str(&format!("\n{doc}\n"));
+        }
+    }
+    result
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeSet;
+
+use swc_atoms::*;
+use swc_common::source_map::DUMMY_SP;
+use swc_ecma_ast::*;
+
+use crate::js::{build_ident, print_js};
+use crate::options::{RuntimeHelpers, Target};
+
+// Functions used by the generated code to implement features that don't map
+// directly to JavaScript.  Like `tslib`, these can either be defined in each
+// file that uses them or imported from a shared module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Helper {
+    // Allows `throw` to be used as an expression, e.g. `$throw(error)`.
+    Throw,
+    // The prelude's `absurd`, which is called with values that should be
+    // impossible, e.g. in the last arm of an exhaustive match.
+    Absurd,
+}
+
+const ALL_HELPERS: [Helper; 2] = [Helper::Throw, Helper::Absurd];
+
+impl Helper {
+    pub(crate) fn ident(&self) -> Ident {
+        let name = match self {
+            Helper::Throw => "$throw",
+            Helper::Absurd => "$absurd",
+        };
+        build_ident(name)
+    }
+
+    fn build_decl(&self) -> Decl {
+        let (params, stmts) = match self {
+            // function $throw(error) { throw error; }
+            Helper::Throw => {
+                let error = build_ident("error");
+                let stmts = vec![Stmt::Throw(ThrowStmt {
+                    span: DUMMY_SP,
+                    arg: Box::from(Expr::Ident(error.to_owned())),
+                })];
+                (vec![error], stmts)
+            }
+            // function $absurd(value) { throw new TypeError("Unexpected value"); }
+            Helper::Absurd => {
+                let error = Expr::New(NewExpr {
+                    span: DUMMY_SP,
+                    callee: Box::from(Expr::Ident(build_ident("TypeError"))),
+                    args: Some(vec![ExprOrSpread {
+                        spread: None,
+                        expr: Box::from(Expr::Lit(Lit::Str(Str {
+                            span: DUMMY_SP,
+                            value: JsWord::from("Unexpected value"),
+                            raw: None,
+                        }))),
+                    }]),
+                    type_args: None,
+                });
+                let stmts = vec![Stmt::Throw(ThrowStmt {
+                    span: DUMMY_SP,
+                    arg: Box::from(error),
+                })];
+                (vec![build_ident("value")], stmts)
+            }
+        };
+
+        Decl::Fn(FnDecl {
+            ident: self.ident(),
+            declare: false,
+            function: Box::from(Function {
+                params: params
+                    .into_iter()
+                    .map(|param| Param {
+                        span: DUMMY_SP,
+                        decorators: vec![],
+                        pat: Pat::Ident(BindingIdent::from(param)),
+                    })
+                    .collect(),
+                decorators: vec![],
+                span: DUMMY_SP,
+                body: Some(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                }),
+                is_generator: false,
+                is_async: false,
+                type_params: None,
+                return_type: None,
+            }),
+        })
+    }
+}
+
+// Adds the helpers in `helpers` to the start of `program`.  Since only the
+// helpers that are used are included, unused helpers are never emitted.
+pub(crate) fn add_helpers(
+    program: &mut Program,
+    helpers: &BTreeSet<Helper>,
+    strategy: &RuntimeHelpers,
+) {
+    let body = match program {
+        Program::Module(Module { body, .. }) => body,
+        Program::Script(_) => return,
+    };
+
+    if helpers.is_empty() {
+        return;
+    }
+
+    let items: Vec<ModuleItem> = match strategy {
+        RuntimeHelpers::Inline => helpers
+            .iter()
+            .map(|helper| ModuleItem::Stmt(Stmt::Decl(helper.build_decl())))
+            .collect(),
+        // import { $throw } from "<module>";
+        RuntimeHelpers::Import { module } => {
+            vec![ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                span: DUMMY_SP,
+                specifiers: helpers
+                    .iter()
+                    .map(|helper| {
+                        ImportSpecifier::Named(ImportNamedSpecifier {
+                            span: DUMMY_SP,
+                            local: helper.ident(),
+                            imported: None,
+                            is_type_only: false,
+                        })
+                    })
+                    .collect(),
+                src: Box::from(Str {
+                    span: DUMMY_SP,
+                    value: JsWord::from(module.as_str()),
+                    raw: None,
+                }),
+                type_only: false,
+                asserts: None,
+            }))]
+        }
+    };
+
+    body.splice(0..0, items);
+}
+
+/// Generates a module which exports all of the runtime helpers.  This is the
+/// module that's imported from when using `RuntimeHelpers::Import`.
+pub fn codegen_runtime_helpers() -> String {
+    let body = ALL_HELPERS
+        .iter()
+        .map(|helper| {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                span: DUMMY_SP,
+                decl: helper.build_decl(),
+            }))
+        })
+        .collect();
+
+    let program = Program::Module(Module {
+        span: DUMMY_SP,
+        body,
+        shebang: None,
+    });
+
+    let (js, _) = print_js("", &program, None, Target::default());
+    js
+}
@@ -1,7 +1,8 @@
+use std::collections::{BTreeMap, BTreeSet};
 use std::rc::Rc;
 
 use swc_atoms::*;
-use swc_common::comments::SingleThreadedComments;
+use swc_common::comments::{self as swc_comments, Comments, SingleThreadedComments};
 use swc_common::hygiene::Mark;
 use swc_common::source_map::{
     self, DefaultSourceMapGenConfig, FilePathMapping, Globals, DUMMY_SP, GLOBALS,
@@ -9,50 +10,113 @@ use swc_common::source_map::{
 use swc_common::{self, BytePos, FileName, SyntaxContext};
 use swc_ecma_ast::*;
 use swc_ecma_codegen::*;
-use swc_ecma_transforms_react::{react, Options, Runtime};
+use swc_ecma_transforms_react::react;
 use swc_ecma_visit::*;
 
 use escalier_ast::{self as values};
 
+use crate::helpers::{add_helpers, Helper};
+use crate::optimize::optimize_program;
+use crate::options::{to_es_version, to_swc_jsx_options, CodegenOptions, ModuleFormat, Target};
+use crate::rename::rename_shadowed_bindings;
+
 pub struct Context {
-    pub temp_id: u32,
+    // Temps are numbered separately for each kind of temp and each function so
+    // that changing one function doesn't rename the temps in other functions.
+    pub temp_ids: BTreeMap<&'static str, u32>,
+    pub freeze_immutable_values: bool,
+    // The runtime helpers used by the generated code.
+    pub(crate) helpers: BTreeSet<Helper>,
+    // Comments from the source, these are stored by position.
+    pub(crate) comments: SingleThreadedComments,
 }
 
 impl Context {
-    pub fn new_ident(&mut self) -> Ident {
-        let ident = Ident {
-            span: DUMMY_SP,
-            sym: JsWord::from(format!("$temp_{}", self.temp_id)),
-            optional: false,
-        };
-        self.temp_id += 1;
+    // Creates a temp named after what it's used for, e.g. `$if_result_0`.
+    // Escalier identifiers, including the ones in JSX, can't contain `$` so
+    // temps never collide with the bindings from the source, including the
+    // ones renamed to avoid shadowing.
+    // Temps in nested functions may shadow the enclosing function's temps, but
+    // temps are only ever used by the function that created them.
+    pub fn new_ident(&mut self, kind: &'static str) -> Ident {
+        let temp_id = self.temp_ids.entry(kind).or_insert(0);
+        let ident = build_ident(&format!("${kind}_{temp_id}"));
+        *temp_id += 1;
         ident
     }
+
+    // Temps created by `f` are numbered independently of the ones outside it.
+    fn with_temp_scope<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
+        let temp_ids = std::mem::take(&mut self.temp_ids);
+        let result = f(self);
+        self.temp_ids = temp_ids;
+        result
+    }
+
+    pub(crate) fn use_helper(&mut self, helper: Helper) -> Ident {
+        self.helpers.insert(helper);
+        helper.ident()
+    }
 }
 
-pub fn codegen_js(src: &str, program: &values::Script) -> (String, String) {
-    let mut ctx = Context { temp_id: 0 };
-    let program = build_js(program, &mut ctx);
+pub fn codegen_js(
+    src: &str,
+    program: &values::Script,
+    options: &values::CompilerOptions,
+) -> (String, String) {
+    codegen_js_with_options(src, program, options, &CodegenOptions::default())
+}
+
+pub fn codegen_js_with_options(
+    src: &str,
+    program: &values::Script,
+    options: &values::CompilerOptions,
+    codegen_options: &CodegenOptions,
+) -> (String, String) {
+    let codegen_options = &codegen_options.with_pragmas(&program.pragmas);
+    // The parser rejects JSX if `options.jsx` isn't set so the runtime isn't
+    // used in that case.
+    let jsx_runtime = options
+        .jsx
+        .to_owned()
+        .unwrap_or_default()
+        .with_pragmas(&program.pragmas);
+    let mut ctx = Context {
+        temp_ids: BTreeMap::new(),
+        freeze_immutable_values: codegen_options.freeze_immutable_values,
+        helpers: BTreeSet::new(),
+        comments: SingleThreadedComments::default(),
+    };
+    let script = rename_shadowed_bindings(program);
+    let mut program = build_js(&script, &mut ctx);
+    if codegen_options.optimize {
+        optimize_program(&mut program);
+    }
+    add_helpers(&mut program, &ctx.helpers, &codegen_options.runtime_helpers);
 
     let cm = Rc::new(source_map::SourceMap::default());
     let comments: Option<SingleThreadedComments> = None;
-    let options = Options {
-        runtime: Some(Runtime::Automatic),
-        ..Default::default()
-    };
+    let jsx_options = to_swc_jsx_options(&jsx_runtime);
 
     let globals = Globals::default();
     // The call to Mark::new() must be wrapped in a GLOBALS.set() closure
     GLOBALS.set(&globals, || {
         let top_level_mark = Mark::new();
         let unresolved_mark = Mark::new();
-        let mut v = react(cm, comments, options, top_level_mark, unresolved_mark);
+        let mut v = react(cm, comments, jsx_options, top_level_mark, unresolved_mark);
         let program = program.fold_with(&mut v);
-        print_js(src, &program)
+        // This happens after the JSX transform since it may add imports.
+        let program = build_module_format(program, &options.module_format);
+        print_js(src, &program, Some(&ctx.comments), options.target)
     })
 }
 
-fn print_js(src: &str, program: &Program) -> (String, String) {
+pub(crate) fn print_js(
+    src: &str,
+    program: &Program,
+    comments: Option<&dyn Comments>,
+    target: Target,
+) -> (String, String) {
     let mut buf = vec![];
     let mut src_map = vec![];
     let cm = Rc::new(source_map::SourceMap::new(FilePathMapping::empty()));
@@ -63,10 +127,11 @@ fn print_js(src: &str, program: &Program) -> (String, String) {
         let wr = text_writer::JsWriter::new(cm.clone(), "\n", &mut buf, Some(&mut src_map));
         let mut emitter = Emitter {
             cfg: swc_ecma_codegen::Config {
+                target: to_es_version(target),
                 ..Default::default()
             },
             cm: cm.clone(),
-            comments: None,
+            comments,
             wr,
         };
         emitter.emit_program(program).unwrap();
@@ -82,52 +147,58 @@ fn print_js(src: &str, program: &Program) -> (String, String) {
 }
 
 fn build_js(program: &values::Script, ctx: &mut Context) -> Program {
+    let exports_all = program.exports_all();
+    // Comments from statements that don't generate any code.
+    let mut pending_comments: Vec<values::Comment> = vec![];
+
     let body: Vec<ModuleItem> = program
         .stmts
         .iter()
         .flat_map(|child| {
             let mut stmts: Vec<Stmt> = vec![];
+            // Type-only decls, including `declare` decls, are erased without
+            // emitting anything.
             let result = match &child.kind {
                 values::StmtKind::Decl(decl) => match &decl.kind {
-                    values::DeclKind::TypeDecl(_) => {
-                        ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }))
-                    }
+                    values::DeclKind::TypeDecl(_) => None,
+                    values::DeclKind::GlobalDecl(_) => None,
+                    values::DeclKind::FnDecl(_) => None,
+                    values::DeclKind::ClassDecl(_) => None,
+                    values::DeclKind::ModuleDecl(_) => None,
                     values::DeclKind::VarDecl(values::VarDecl {
                         pattern,
                         expr: init,
                         is_declare: declare,
+                        is_var,
                         ..
                     }) => match declare {
-                        true => ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP })),
+                        true => None,
                         false => {
-                            // It should be okay to unwrap this here since any decl that isn't
-                            // using `declare` should have an initial value.
-                            let init = init.as_ref().unwrap();
-
-                            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
-                                span: DUMMY_SP,
-                                decl: Decl::Var(Box::from(build_var_decl(
-                                    pattern,
-                                    Some(init),
-                                    &mut stmts,
-                                    ctx,
-                                ))),
-                            }))
+                            // Bindings without an initializer are assigned
+                            // later on so they need to use `let`.
+                            let var_decl = Decl::Var(Box::from(build_var_decl(
+                                pattern,
+                                init.as_ref(),
+                                *is_var || init.is_none(),
+                                &mut stmts,
+                                ctx,
+                            )));
+                            Some(build_export_decl(var_decl, exports_all || decl.is_export))
                         }
                     },
                 },
                 values::StmtKind::Expr(values::ExprStmt { expr }) => {
-                    ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                    Some(ModuleItem::Stmt(Stmt::Expr(ExprStmt {
                         span: DUMMY_SP,
                         expr: Box::from(build_expr(expr, &mut stmts, ctx)),
-                    }))
+                    })))
                 }
                 values::StmtKind::For(values::ForStmt { left, right, body }) => {
                     let stmt = Stmt::ForOf(ForOfStmt {
                         span: DUMMY_SP,
                         is_await: false,
                         left: ForHead::VarDecl(Box::from(build_var_decl(
-                            left, None, &mut stmts, ctx,
+                            left, None, false, &mut stmts, ctx,
                         ))),
                         right: Box::from(build_expr(right, &mut stmts, ctx)),
                         body: Box::from(Stmt::Block(build_body_block_stmt(
@@ -136,7 +207,7 @@ fn build_js(program: &values::Script, ctx: &mut Context) -> Program {
                             ctx,
                         ))),
                     });
-                    ModuleItem::Stmt(stmt)
+                    Some(ModuleItem::Stmt(stmt))
                 }
                 // values::StmtKind::ClassDecl(values::ClassDecl { class, ident, .. }) => {
                 //     let ident = Ident::from(ident);
@@ -165,16 +236,28 @@ fn build_js(program: &values::Script, ctx: &mut Context) -> Program {
                 //         ctx,
                 //     ))),
                 // })),
+                values::StmtKind::LetElse(let_else) => {
+                    match build_let_else(let_else, &mut stmts, ctx) {
+                        Some(decl) => {
+                            Some(build_export_decl(Decl::Var(Box::from(decl)), exports_all))
+                        }
+                        None => Some(ModuleItem::Stmt(Stmt::Empty(EmptyStmt { span: DUMMY_SP }))),
+                    }
+                }
                 values::StmtKind::Return { .. } => {
                     panic!("return statements aren't allowed at the top level")
                 }
+                values::StmtKind::Import(import) => build_import(import),
+                values::StmtKind::Export(export) => build_named_export(export),
             };
 
             let mut items: Vec<ModuleItem> = stmts
                 .iter()
                 .map(|stmt| ModuleItem::Stmt(stmt.to_owned()))
                 .collect();
-            items.push(result);
+            items.extend(result);
+
+            attach_comments(child, &mut items, &mut pending_comments, &ctx.comments);
 
             items
         })
@@ -187,25 +270,493 @@ fn build_js(program: &values::Script, ctx: &mut Context) -> Program {
     })
 }
 
+fn build_export_decl(decl: Decl, is_export: bool) -> ModuleItem {
+    match is_export {
+        true => ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            span: DUMMY_SP,
+            decl,
+        })),
+        false => ModuleItem::Stmt(Stmt::Decl(decl)),
+    }
+}
+
+// Type-only specifiers are erased since the names they import don't exist at
+// runtime.  Imports that don't import any values are erased completely.
+fn build_import(import: &values::Import) -> Option<ModuleItem> {
+    let specifiers = import.value_specifiers();
+    if specifiers.is_empty() {
+        return None;
+    }
+
+    Some(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span: DUMMY_SP,
+        specifiers: specifiers
+            .into_iter()
+            .map(|specifier| {
+                ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: DUMMY_SP,
+                    local: build_ident(&specifier.local),
+                    imported: specifier
+                        .imported
+                        .as_ref()
+                        .map(|imported| ModuleExportName::Ident(build_ident(imported))),
+                    is_type_only: false,
+                })
+            })
+            .collect(),
+        src: Box::from(Str {
+            span: DUMMY_SP,
+            value: JsWord::from(import.source.as_str()),
+            raw: None,
+        }),
+        type_only: false,
+        asserts: None,
+    })))
+}
+
+// Like imports, type-only specifiers are erased and exports that don't export
+// any values are erased completely.
+fn build_named_export(export: &values::NamedExport) -> Option<ModuleItem> {
+    let specifiers = export.value_specifiers();
+    if specifiers.is_empty() {
+        return None;
+    }
+
+    Some(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+        NamedExport {
+            span: DUMMY_SP,
+            specifiers: specifiers
+                .into_iter()
+                .map(|specifier| {
+                    ExportSpecifier::Named(ExportNamedSpecifier {
+                        span: DUMMY_SP,
+                        orig: ModuleExportName::Ident(build_ident(&specifier.local)),
+                        exported: specifier
+                            .exported
+                            .as_ref()
+                            .map(|exported| ModuleExportName::Ident(build_ident(exported))),
+                        is_type_only: false,
+                    })
+                })
+                .collect(),
+            src: None,
+            type_only: false,
+            asserts: None,
+        },
+    )))
+}
+
+// Converts the ES module produced by `build_js` to `format`.
+fn build_module_format(program: Program, format: &ModuleFormat) -> Program {
+    let items = match (format, program) {
+        (ModuleFormat::Esm, program) => return program,
+        (_, Program::Module(Module { body, .. })) => body,
+        (_, program) => return program,
+    };
+
+    let mut stmts: Vec<Stmt> = vec![];
+    let mut exports: Vec<PropOrSpread> = vec![];
+
+    for item in items {
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => {
+                exports.extend(
+                    find_decl_ids(&decl)
+                        .into_iter()
+                        .map(|id| PropOrSpread::Prop(Box::from(Prop::Shorthand(id)))),
+                );
+                stmts.push(Stmt::Decl(decl));
+            }
+            // export { a, b as c };
+            // becomes
+            // module.exports = { a, c: b };
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport { specifiers, .. })) => {
+                for specifier in specifiers {
+                    if let ExportSpecifier::Named(ExportNamedSpecifier {
+                        orig: ModuleExportName::Ident(orig),
+                        exported,
+                        ..
+                    }) = specifier
+                    {
+                        exports.push(match exported {
+                            Some(ModuleExportName::Ident(exported)) => {
+                                build_export_prop(PropName::Ident(exported), Expr::Ident(orig))
+                            }
+                            _ => PropOrSpread::Prop(Box::from(Prop::Shorthand(orig))),
+                        });
+                    }
+                }
+            }
+            // export default expr;
+            // becomes
+            // module.exports = { default: expr };
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+                expr,
+                ..
+            })) => match format {
+                ModuleFormat::CommonJs => exports.push(build_export_prop(
+                    PropName::Ident(build_ident("default")),
+                    *expr,
+                )),
+                _ => stmts.push(Stmt::Expr(ExprStmt {
+                    span: DUMMY_SP,
+                    expr,
+                })),
+            },
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+                decl,
+                ..
+            })) => {
+                let value = match decl {
+                    DefaultDecl::Class(class) => Expr::Class(class),
+                    DefaultDecl::Fn(func) => Expr::Fn(func),
+                    DefaultDecl::TsInterfaceDecl(_) => continue,
+                };
+                match format {
+                    ModuleFormat::CommonJs => exports.push(build_export_prop(
+                        PropName::Ident(build_ident("default")),
+                        value,
+                    )),
+                    _ => stmts.push(Stmt::Expr(ExprStmt {
+                        span: DUMMY_SP,
+                        expr: Box::from(value),
+                    })),
+                }
+            }
+            // export * from "./foo";
+            // becomes
+            // module.exports = { ...require("./foo") };
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll { src, .. })) => {
+                if *format == ModuleFormat::CommonJs {
+                    exports.push(PropOrSpread::Spread(SpreadElement {
+                        dot3_token: DUMMY_SP,
+                        expr: Box::from(build_require_call(&src)),
+                    }));
+                }
+            }
+            // Scripts compiled to IIFEs can't import anything.  The checker
+            // reports these imports so they're dropped here.
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                if *format == ModuleFormat::CommonJs {
+                    stmts.append(&mut build_require(&import));
+                }
+            }
+            // The remaining module decls are TypeScript-only syntax which is
+            // never emitted.
+            ModuleItem::ModuleDecl(_) => {}
+            ModuleItem::Stmt(stmt) => stmts.push(stmt),
+        }
+    }
+
+    let body = match format {
+        // module.exports = { a, b, c };
+        ModuleFormat::CommonJs => {
+            if !exports.is_empty() {
+                stmts.push(Stmt::Expr(ExprStmt {
+                    span: DUMMY_SP,
+                    expr: Box::from(Expr::Assign(AssignExpr {
+                        span: DUMMY_SP,
+                        op: AssignOp::Assign,
+                        left: PatOrExpr::Expr(Box::from(Expr::Member(MemberExpr {
+                            span: DUMMY_SP,
+                            obj: Box::from(Expr::Ident(build_ident("module"))),
+                            prop: MemberProp::Ident(build_ident("exports")),
+                        }))),
+                        right: Box::from(Expr::Object(ObjectLit {
+                            span: DUMMY_SP,
+                            props: exports,
+                        })),
+                    })),
+                }));
+            }
+            stmts
+        }
+        // (()=>{ ... })();
+        _ => {
+            let func = Expr::Arrow(ArrowExpr {
+                span: DUMMY_SP,
+                params: vec![],
+                body: Box::new(BlockStmtOrExpr::BlockStmt(BlockStmt {
+                    span: DUMMY_SP,
+                    stmts,
+                })),
+                is_async: false,
+                is_generator: false,
+                type_params: None,
+                return_type: None,
+            });
+            vec![Stmt::Expr(ExprStmt {
+                span: DUMMY_SP,
+                expr: Box::from(Expr::Call(CallExpr {
+                    span: DUMMY_SP,
+                    callee: Callee::Expr(Box::from(Expr::Paren(ParenExpr {
+                        span: DUMMY_SP,
+                        expr: Box::from(func),
+                    }))),
+                    args: vec![],
+                    type_args: None,
+                })),
+            })]
+        }
+    };
+
+    Program::Script(Script {
+        span: DUMMY_SP,
+        body,
+        shebang: None,
+    })
+}
+
+// import { jsx as _jsx } from "react/jsx-runtime";
+// becomes
+// const { jsx: _jsx } = require("react/jsx-runtime");
+fn build_require(import: &ImportDecl) -> Vec<Stmt> {
+    let require = build_require_call(&import.src);
+
+    let mut stmts: Vec<Stmt> = vec![];
+    let mut props: Vec<ObjectPatProp> = vec![];
+
+    for specifier in &import.specifiers {
+        match specifier {
+            ImportSpecifier::Named(ImportNamedSpecifier {
+                local, imported, ..
+            }) => props.push(match imported {
+                Some(imported) => ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: match imported {
+                        ModuleExportName::Ident(ident) => PropName::Ident(ident.to_owned()),
+                        ModuleExportName::Str(str) => PropName::Str(str.to_owned()),
+                    },
+                    value: Box::from(Pat::Ident(BindingIdent::from(local.to_owned()))),
+                }),
+                None => ObjectPatProp::Assign(AssignPatProp {
+                    span: DUMMY_SP,
+                    key: local.to_owned(),
+                    value: None,
+                }),
+            }),
+            ImportSpecifier::Default(ImportDefaultSpecifier { local, .. }) => {
+                props.push(ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: PropName::Ident(build_ident("default")),
+                    value: Box::from(Pat::Ident(BindingIdent::from(local.to_owned()))),
+                }))
+            }
+            ImportSpecifier::Namespace(ImportStarAsSpecifier { local, .. }) => {
+                stmts.push(build_const_decl_stmt(local, require.to_owned()))
+            }
+        }
+    }
+
+    if !props.is_empty() {
+        let pat = Pat::Object(ObjectPat {
+            span: DUMMY_SP,
+            props,
+            optional: false,
+            type_ann: None,
+        });
+        stmts.push(build_const_decl_stmt_with_pat(pat, require));
+    }
+
+    stmts
+}
+
+fn build_require_call(src: &Str) -> Expr {
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::from(Expr::Ident(build_ident("require")))),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::from(Expr::Lit(Lit::Str(src.to_owned()))),
+        }],
+        type_args: None,
+    })
+}
+
+fn build_export_prop(key: PropName, value: Expr) -> PropOrSpread {
+    PropOrSpread::Prop(Box::from(Prop::KeyValue(KeyValueProp {
+        key,
+        value: Box::from(value),
+    })))
+}
+
+// Returns the bindings that a decl introduces at runtime.  Interfaces, type
+// aliases, and ambient decls don't introduce any.
+fn find_decl_ids(decl: &Decl) -> Vec<Ident> {
+    let mut finder = BindingIdFinder::default();
+    decl.visit_with(&mut finder);
+    finder.ids
+}
+
+// Collects the identifiers bound by decls and patterns.  Expressions, e.g.
+// default values, initializers, and function bodies, aren't visited since
+// the bindings in them aren't in the same scope.
+#[derive(Default)]
+struct BindingIdFinder {
+    ids: Vec<Ident>,
+}
+
+impl Visit for BindingIdFinder {
+    fn visit_class_decl(&mut self, decl: &ClassDecl) {
+        if !decl.declare {
+            self.ids.push(decl.ident.to_owned());
+        }
+    }
+
+    fn visit_fn_decl(&mut self, decl: &FnDecl) {
+        if !decl.declare {
+            self.ids.push(decl.ident.to_owned());
+        }
+    }
+
+    fn visit_var_decl(&mut self, decl: &VarDecl) {
+        if !decl.declare {
+            decl.visit_children_with(self);
+        }
+    }
+
+    fn visit_var_declarator(&mut self, decl: &VarDeclarator) {
+        decl.name.visit_with(self);
+    }
+
+    fn visit_ts_enum_decl(&mut self, decl: &TsEnumDecl) {
+        if !decl.declare && !decl.is_const {
+            self.ids.push(decl.id.to_owned());
+        }
+    }
+
+    fn visit_ts_module_decl(&mut self, decl: &TsModuleDecl) {
+        if let (false, TsModuleName::Ident(id)) = (decl.declare, &decl.id) {
+            self.ids.push(id.to_owned());
+        }
+    }
+
+    fn visit_ts_interface_decl(&mut self, _: &TsInterfaceDecl) {}
+
+    fn visit_ts_type_alias_decl(&mut self, _: &TsTypeAliasDecl) {}
+
+    fn visit_binding_ident(&mut self, ident: &BindingIdent) {
+        self.ids.push(ident.id.to_owned());
+    }
+
+    fn visit_assign_pat_prop(&mut self, prop: &AssignPatProp) {
+        self.ids.push(prop.key.to_owned());
+    }
+
+    fn visit_prop_name(&mut self, _: &PropName) {}
+
+    fn visit_ts_type_ann(&mut self, _: &TsTypeAnn) {}
+
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+pub(crate) fn build_ident(name: &str) -> Ident {
+    Ident {
+        span: DUMMY_SP,
+        sym: JsWord::from(name),
+        optional: false,
+    }
+}
+
 fn build_var_decl(
     pattern: &values::Pattern,
     init: Option<&values::Expr>,
+    is_var: bool,
     stmts: &mut Vec<Stmt>,
     ctx: &mut Context,
 ) -> VarDecl {
+    // `var` and `mut` bindings use `let` so that the JS reflects which
+    // bindings can be changed, everything else uses `const`.
+    let kind = match is_var || pattern.has_mut_bindings() {
+        true => VarDeclKind::Let,
+        false => VarDeclKind::Const,
+    };
+
+    let name = build_pattern(pattern, stmts, ctx).unwrap();
+    let init = init.map(|init| {
+        let init = build_expr(init, stmts, ctx);
+        // The type of a binding is only mutable if the binding is `mut`.
+        match ctx.freeze_immutable_values && !pattern.has_mut_bindings() {
+            true => build_freeze(init),
+            false => init,
+        }
+    });
+
     VarDecl {
         span: DUMMY_SP,
-        kind: VarDeclKind::Const,
+        kind,
         declare: false,
         decls: vec![VarDeclarator {
             span: DUMMY_SP,
-            name: build_pattern(pattern, stmts, ctx).unwrap(),
-            init: init.map(|init| Box::from(build_expr(init, stmts, ctx))),
+            name,
+            init: init.map(Box::from),
             definite: false,
         }],
     }
 }
 
+// Wraps object and array literals, including any nested inside of them, in
+// `Object.freeze()`, e.g. `{a: [1, 2]}` becomes
+// `Object.freeze({a: Object.freeze([1, 2])})`.
+fn build_freeze(expr: Expr) -> Expr {
+    let expr = match expr {
+        Expr::Object(ObjectLit { span, props }) => Expr::Object(ObjectLit {
+            span,
+            props: props
+                .into_iter()
+                .map(|prop| match prop {
+                    PropOrSpread::Prop(prop) => match *prop {
+                        Prop::KeyValue(KeyValueProp { key, value }) => {
+                            PropOrSpread::Prop(Box::from(Prop::KeyValue(KeyValueProp {
+                                key,
+                                value: Box::from(build_freeze(*value)),
+                            })))
+                        }
+                        prop => PropOrSpread::Prop(Box::from(prop)),
+                    },
+                    spread => spread,
+                })
+                .collect(),
+        }),
+        Expr::Array(ArrayLit { span, elems }) => Expr::Array(ArrayLit {
+            span,
+            elems: elems
+                .into_iter()
+                .map(|elem| {
+                    elem.map(|ExprOrSpread { spread, expr }| ExprOrSpread {
+                        spread,
+                        expr: match spread {
+                            Some(_) => expr,
+                            None => Box::from(build_freeze(*expr)),
+                        },
+                    })
+                })
+                .collect(),
+        }),
+        expr => return expr,
+    };
+
+    Expr::Call(CallExpr {
+        span: DUMMY_SP,
+        callee: Callee::Expr(Box::from(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::from(Expr::Ident(Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from("Object"),
+                optional: false,
+            })),
+            prop: MemberProp::Ident(Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from("freeze"),
+                optional: false,
+            }),
+        }))),
+        args: vec![ExprOrSpread {
+            spread: None,
+            expr: Box::from(expr),
+        }],
+        type_args: None,
+    })
+}
+
 // TODO: See if we can avoid returning an Option<> here so that we don't have
 // to unwrap() in when calling it from build_expr().
 fn build_pattern(
@@ -222,12 +773,13 @@ fn build_pattern(
     match &pattern.kind {
         // unassignable patterns
         values::PatternKind::Lit(_) => None,
+        values::PatternKind::Range(_) => None,
 
         // TODO: we need to have something we can assign `_` to when it appears
         // in object destructuring otherwise if there's a `...rest` that's also
         // in the pattern we'll end up with the wrong items assigned to `rest`.
         values::PatternKind::Wildcard => Some(Pat::Ident(BindingIdent {
-            id: ctx.new_ident(),
+            id: ctx.new_ident("unused"),
             type_ann: None,
         })),
 
@@ -251,16 +803,32 @@ fn build_pattern(
             }))
         }
         values::PatternKind::Object(values::ObjectPat { props, optional }) => {
+            let has_rest = props
+                .iter()
+                .any(|p| matches!(p, values::ObjectPatProp::Rest(_)));
             let props: Vec<ObjectPatProp> = props
                 .iter()
                 .filter_map(|p| match p {
                     values::ObjectPatProp::KeyValue(kvp) => {
-                        build_pattern(kvp.value.as_ref(), stmts, ctx).map(|value| {
-                            ObjectPatProp::KeyValue(KeyValuePatProp {
-                                key: PropName::Ident(Ident::from(&kvp.key)),
-                                value: Box::from(value),
-                            })
-                        })
+                        // If there's a `...rest` we still need to destructure
+                        // props whose values are unassignable, e.g. literals,
+                        // so that they're excluded from `rest`.
+                        let value = match build_pattern(kvp.value.as_ref(), stmts, ctx) {
+                            Some(value) => value,
+                            None if has_rest => Pat::Ident(BindingIdent {
+                                id: ctx.new_ident("unused"),
+                                type_ann: None,
+                            }),
+                            None => return None,
+                        };
+                        let value = match &kvp.init {
+                            Some(init) => build_assign_pat(value, init, stmts, ctx),
+                            None => value,
+                        };
+                        Some(ObjectPatProp::KeyValue(KeyValuePatProp {
+                            key: PropName::Ident(Ident::from(&kvp.key)),
+                            value: Box::from(value),
+                        }))
                     }
                     values::ObjectPatProp::Shorthand(values::ShorthandPatProp {
                         ident,
@@ -305,7 +873,13 @@ fn build_pattern(
             let elems: Vec<Option<Pat>> = elems
                 .iter()
                 .map(|elem| match elem {
-                    Some(elem) => build_pattern(&elem.pattern, stmts, ctx),
+                    Some(elem) => {
+                        let pat = build_pattern(&elem.pattern, stmts, ctx)?;
+                        match &elem.init {
+                            Some(init) => Some(build_assign_pat(pat, init, stmts, ctx)),
+                            None => Some(pat),
+                        }
+                    }
                     None => None,
                 })
                 .collect();
@@ -325,6 +899,20 @@ fn build_pattern(
     }
 }
 
+// e.g. `x = 5` in `const [x = 5] = tuple;`
+fn build_assign_pat(
+    left: Pat,
+    init: &values::Expr,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut Context,
+) -> Pat {
+    Pat::Assign(AssignPat {
+        span: DUMMY_SP,
+        left: Box::from(left),
+        right: Box::from(build_expr(init, stmts, ctx)),
+    })
+}
+
 fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) -> Expr {
     let span = swc_common::Span {
         lo: BytePos(expr.span.start as u32 + 1),
@@ -383,6 +971,11 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
         //         type_args: None,
         //     })
         // }
+        // The renamer replaces references to the prelude's `absurd` with its
+        // runtime helper.
+        values::ExprKind::Ident(ident) if ident.name == "$absurd" => {
+            Expr::Ident(ctx.use_helper(Helper::Absurd))
+        }
         values::ExprKind::Ident(ident) => Expr::from(Ident::from(ident)),
         values::ExprKind::Function(values::Function {
             params: args,
@@ -390,19 +983,99 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
             is_async,
             ..
         }) => {
-            let params: Vec<Pat> = args
-                .iter()
-                .map(|arg| build_pattern(&arg.pattern, stmts, ctx).unwrap())
-                .collect();
+            // Methods of object literals take `self` as their first param.  It's
+            // `this` in JavaScript so they're built as `function` expressions
+            // that bind `self` to `this`.
+            let (self_param, args) = match args.split_first() {
+                Some((first, rest)) if is_self_param(first) => (Some(first), rest),
+                _ => (None, &args[..]),
+            };
 
-            let body = match body {
-                values::BlockOrExpr::Block(body) => BlockStmtOrExpr::BlockStmt(
-                    build_body_block_stmt(body, &BlockFinalizer::ExprStmt, ctx),
-                ),
-                values::BlockOrExpr::Expr(expr) => {
-                    BlockStmtOrExpr::Expr(Box::from(build_expr(expr, stmts, ctx)))
+            // Each function numbers its temps from zero so that changes to one
+            // function don't affect the output of other functions.
+            let (params, body) = ctx.with_temp_scope(|ctx| {
+                // Statements that check and destructure refined params, these
+                // are inserted at the start of the function's body.
+                let mut prelude: Vec<Stmt> = vec![];
+                if let Some(self_param) = self_param {
+                    let name = build_pattern(&self_param.pattern, stmts, ctx).unwrap();
+                    prelude.push(Stmt::Decl(Decl::Var(Box::from(VarDecl {
+                        span: DUMMY_SP,
+                        kind: VarDeclKind::Const,
+                        declare: false,
+                        decls: vec![VarDeclarator {
+                            span: DUMMY_SP,
+                            name,
+                            init: Some(Box::from(Expr::This(ThisExpr { span: DUMMY_SP }))),
+                            definite: false,
+                        }],
+                    }))));
                 }
-            };
+                let params: Vec<Pat> = args
+                    .iter()
+                    .map(|arg| match arg.pattern.is_refinement() {
+                        true => build_refined_param(&arg.pattern, &mut prelude, ctx),
+                        false => build_pattern(&arg.pattern, stmts, ctx).unwrap(),
+                    })
+                    .collect();
+
+                let body = match body {
+                    values::BlockOrExpr::Block(body) => {
+                        let mut block = build_body_block_stmt(body, &BlockFinalizer::ExprStmt, ctx);
+                        prelude.append(&mut block.stmts);
+                        block.stmts = prelude;
+                        BlockStmtOrExpr::BlockStmt(block)
+                    }
+                    values::BlockOrExpr::Expr(expr) => {
+                        // Any temps needed by the expression are declared
+                        // inside the function so the body becomes a block.
+                        let expr = build_expr(expr, &mut prelude, ctx);
+                        match prelude.is_empty() {
+                            true => BlockStmtOrExpr::Expr(Box::from(expr)),
+                            false => {
+                                prelude.push(Stmt::Return(ReturnStmt {
+                                    span: DUMMY_SP,
+                                    arg: Some(Box::from(expr)),
+                                }));
+                                BlockStmtOrExpr::BlockStmt(BlockStmt {
+                                    span: DUMMY_SP,
+                                    stmts: prelude,
+                                })
+                            }
+                        }
+                    }
+                };
+
+                (params, body)
+            });
+
+            if self_param.is_some() {
+                let body = match body {
+                    BlockStmtOrExpr::BlockStmt(body) => body,
+                    // The binding of `self` is always added to the body.
+                    BlockStmtOrExpr::Expr(_) => unreachable!(),
+                };
+                return Expr::Fn(FnExpr {
+                    ident: None,
+                    function: Box::from(Function {
+                        params: params
+                            .into_iter()
+                            .map(|pat| Param {
+                                span: DUMMY_SP,
+                                decorators: vec![],
+                                pat,
+                            })
+                            .collect(),
+                        decorators: vec![],
+                        span,
+                        body: Some(body),
+                        is_generator: false,
+                        is_async: is_async.to_owned(),
+                        type_params: None,
+                        return_type: None,
+                    }),
+                });
+            }
 
             Expr::Arrow(ArrowExpr {
                 span,
@@ -429,9 +1102,14 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
             value: swc_atoms::JsWord::from(value.as_str()),
             raw: None,
         })),
-        values::ExprKind::Num(values::Num { value, .. }) => Expr::Lit(Lit::Num(Number {
+        values::ExprKind::Num(values::Num { value, raw }) => Expr::Lit(Lit::Num(Number {
             span,
             value: value.parse().unwrap(),
+            raw: raw.as_ref().map(|raw| Atom::new(raw.clone())),
+        })),
+        values::ExprKind::BigInt(values::BigInt { value }) => Expr::Lit(Lit::BigInt(BigInt {
+            span,
+            value: Box::new(value.parse().unwrap()),
             raw: None,
         })),
         values::ExprKind::Bool(values::Bool { value, .. }) => Expr::Lit(Lit::Bool(Bool {
@@ -471,28 +1149,55 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
                 values::BinaryOp::Minus => BinaryOp::Sub,
                 values::BinaryOp::Times => BinaryOp::Mul,
                 values::BinaryOp::Divide => BinaryOp::Div,
+                values::BinaryOp::Modulo => BinaryOp::Mod,
+                values::BinaryOp::Power => BinaryOp::Exp,
                 values::BinaryOp::Equals => BinaryOp::EqEqEq,
                 values::BinaryOp::NotEquals => BinaryOp::NotEqEq,
                 values::BinaryOp::LessThan => BinaryOp::Lt,
                 values::BinaryOp::LessThanOrEqual => BinaryOp::LtEq,
                 values::BinaryOp::GreaterThan => BinaryOp::Gt,
                 values::BinaryOp::GreaterThanOrEqual => BinaryOp::GtEq,
-                _ => todo!(),
+                values::BinaryOp::In => BinaryOp::In,
+                values::BinaryOp::And => BinaryOp::LogicalAnd,
+                values::BinaryOp::Or => BinaryOp::LogicalOr,
+                values::BinaryOp::BitwiseAnd => BinaryOp::BitAnd,
+                values::BinaryOp::BitwiseOr => BinaryOp::BitOr,
+                values::BinaryOp::BitwiseXor => BinaryOp::BitXor,
+                values::BinaryOp::LeftShift => BinaryOp::LShift,
+                values::BinaryOp::RightShift => BinaryOp::RShift,
+                values::BinaryOp::UnsignedRightShift => BinaryOp::ZeroFillRShift,
             };
 
             let left = Box::from(build_expr(left, stmts, ctx));
 
             let wrap_left = match left.as_ref() {
+                // `**` is right associative and JS doesn't allow unary
+                // operators on its left side without parens, e.g. `(-a) ** b`.
+                Expr::Bin(left) if matches!(op, BinaryOp::Exp) => {
+                    left.op.precedence() <= op.precedence()
+                }
+                Expr::Unary(_) => matches!(op, BinaryOp::Exp),
                 Expr::Bin(left) => left.op.precedence() < op.precedence(),
                 _ => false,
             };
 
-            let right = Box::from(build_expr(right, stmts, ctx));
+            let mut right_stmts = vec![];
+            let right = Box::from(build_expr(right, &mut right_stmts, ctx));
+
+            // Statements hoisted out of the right side of `&&` and `||` must
+            // only run if the left side doesn't short-circuit.
+            if matches!(op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr) && !right_stmts.is_empty() {
+                return build_short_circuit(op, *left, *right, right_stmts, stmts, ctx);
+            }
+            stmts.append(&mut right_stmts);
 
             let wrap_right = match right.as_ref() {
                 Expr::Bin(right) => match (op, right.op) {
                     (BinaryOp::Div, BinaryOp::Div) => true,
                     (BinaryOp::Sub, BinaryOp::Sub) => true,
+                    (BinaryOp::LShift | BinaryOp::RShift | BinaryOp::ZeroFillRShift, _) => {
+                        right.op.precedence() <= op.precedence()
+                    }
                     _ => right.op.precedence() < op.precedence(),
                 },
                 _ => false,
@@ -524,12 +1229,23 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
                 values::UnaryOp::Minus => UnaryOp::Minus,
                 values::UnaryOp::Not => todo!(),
                 values::UnaryOp::Plus => todo!(),
+                values::UnaryOp::BitwiseNot => UnaryOp::Tilde,
             };
 
+            let arg = Box::from(build_expr(arg, stmts, ctx));
+            let wrap_arg = matches!(arg.as_ref(), Expr::Bin(_));
+
             Expr::Unary(UnaryExpr {
                 span,
                 op,
-                arg: Box::from(build_expr(arg, stmts, ctx)),
+                arg: if wrap_arg {
+                    Box::from(Expr::Paren(ParenExpr {
+                        span: DUMMY_SP,
+                        expr: arg,
+                    }))
+                } else {
+                    arg
+                },
             })
         }
         values::ExprKind::IfElse(values::IfElse {
@@ -538,14 +1254,14 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
             alternate,
             ..
         }) => {
-            // let $temp_n;
-            let temp_id = ctx.new_ident();
+            // let $if_result_n;
+            let temp_id = ctx.new_ident("if_result");
             let temp_decl = build_let_decl_stmt(&temp_id);
             stmts.push(temp_decl);
 
             let finalizer = BlockFinalizer::Assign(temp_id.clone());
 
-            // if (cond) { ...; $temp_n = <cons_res> } else { ...; $temp_n = <alt_res> }
+            // if (cond) { ...; $if_result_n = <cons_res> } else { ...; $if_result_n = <alt_res> }
             let test = Box::from(build_expr(cond.as_ref(), stmts, ctx));
             let cons = Box::from(Stmt::Block(build_body_block_stmt(
                 consequent, &finalizer, ctx,
@@ -560,7 +1276,7 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
                 alt,
             }));
 
-            // $temp_n
+            // $if_result_n
             Expr::Ident(temp_id)
         }
         values::ExprKind::Object(values::Object { properties: props }) => {
@@ -591,7 +1307,9 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
         values::ExprKind::JSXElement(elem) => {
             Expr::JSXElement(Box::from(build_jsx_element(elem, stmts, ctx)))
         }
-        values::ExprKind::JSXFragment(_) => todo!(),
+        values::ExprKind::JSXFragment(fragment) => {
+            Expr::JSXFragment(build_jsx_fragment(fragment, stmts, ctx))
+        }
         values::ExprKind::Tuple(values::Tuple { elements: elems }) => Expr::Array(ArrayLit {
             span,
             elems: elems
@@ -649,67 +1367,9 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
                 tpl: Box::new(tpl),
             })
         }
+        values::ExprKind::IfLet(if_let) => build_if_let(if_let, stmts, ctx),
         values::ExprKind::Match(values::Match { expr, arms, .. }) => {
-            // let $temp_n;
-            let ret_temp_id = ctx.new_ident();
-            let ret_decl = build_let_decl_stmt(&ret_temp_id);
-            stmts.push(ret_decl);
-
-            // let $temp_m = <expr>
-            let temp_id = ctx.new_ident();
-            let temp_decl = build_const_decl_stmt(&temp_id, build_expr(expr, stmts, ctx));
-            stmts.push(temp_decl);
-
-            // TODO: we want to stop when we encounter the first
-            // irrefutable pattern since all subsequent patterns
-            // shouldn't be matched.
-            let mut has_catchall: bool = false;
-            let mut built_arms: Vec<(_, _)> = vec![];
-            for arm in arms {
-                if has_catchall {
-                    panic!("Catchall must appear last in match");
-                }
-
-                let (cond, block) = build_arm(arm, &temp_id, &ret_temp_id, stmts, ctx);
-
-                if cond.is_none() {
-                    has_catchall = true
-                }
-
-                built_arms.push((cond, block));
-            }
-
-            // We reverse the order of the arms because when building
-            // an if/else-if/else chain we need to start with the `else`
-            // and work our way back to the initial `if`.
-            built_arms.reverse();
-            let mut iter = built_arms.iter();
-            let first = match iter.next() {
-                Some((cond, block)) => match cond {
-                    Some(cond) => Stmt::If(IfStmt {
-                        span: DUMMY_SP,
-                        test: Box::from(cond.to_owned()),
-                        cons: Box::from(Stmt::Block(block.to_owned())),
-                        alt: None,
-                    }),
-                    None => Stmt::Block(block.to_owned()),
-                },
-                None => panic!("No arms in match"),
-            };
-
-            let if_else = iter.fold(first, |prev, (cond, block)| {
-                Stmt::If(IfStmt {
-                    span,
-                    test: Box::from(cond.to_owned().unwrap()),
-                    cons: Box::from(Stmt::Block(block.to_owned())),
-                    alt: Some(Box::from(prev)),
-                })
-            });
-
-            stmts.push(if_else);
-
-            // $temp_n
-            Expr::Ident(ret_temp_id)
+            build_match(expr, arms, span, stmts, ctx)
         }
         values::ExprKind::Class(class) => {
             let ident = Some(Ident::from(&values::Ident {
@@ -732,7 +1392,7 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
         //     },
         // })),
         values::ExprKind::Do(do_expr) => {
-            let temp_id = ctx.new_ident();
+            let temp_id = ctx.new_ident("do_result");
             let temp_decl = build_let_decl_stmt(&temp_id);
             stmts.push(temp_decl);
 
@@ -745,7 +1405,17 @@ fn build_expr(expr: &values::Expr, stmts: &mut Vec<Stmt>, ctx: &mut Context) ->
         }
         values::ExprKind::Try(_) => todo!(),
         values::ExprKind::Yield(_) => todo!(),
-        values::ExprKind::Throw(_) => todo!(),
+        // JavaScript only supports `throw` statements so a helper is used to
+        // allow it to be used as an expression, e.g. `$throw(error)`.
+        values::ExprKind::Throw(values::Throw { arg, .. }) => Expr::Call(CallExpr {
+            span,
+            callee: Callee::Expr(Box::from(Expr::Ident(ctx.use_helper(Helper::Throw)))),
+            args: vec![ExprOrSpread {
+                spread: None,
+                expr: Box::from(build_expr(arg, stmts, ctx)),
+            }],
+            type_args: None,
+        }),
     }
 }
 
@@ -795,6 +1465,16 @@ fn build_alt(
                         alt,
                     })
                 }
+                values::ExprKind::IfLet(_) => {
+                    let mut block_stmts = vec![];
+                    let expr = build_expr(expr, &mut block_stmts, ctx);
+                    block_stmts.push(build_finalizer(&expr, finalizer));
+
+                    Stmt::Block(BlockStmt {
+                        span,
+                        stmts: block_stmts,
+                    })
+                }
                 _ => panic!("Invalid alternate expression"),
             }
         }
@@ -847,6 +1527,145 @@ fn build_finalizer(expr: &Expr, finalizer: &BlockFinalizer) -> Stmt {
     }
 }
 
+// Allows comments to be attached to both module items and statements.
+trait SetSpan {
+    fn set_span(&mut self, span: swc_common::Span);
+}
+
+impl SetSpan for Stmt {
+    fn set_span(&mut self, span: swc_common::Span) {
+        match self {
+            Stmt::Decl(Decl::Var(var_decl)) => var_decl.span = span,
+            Stmt::Expr(expr_stmt) => expr_stmt.span = span,
+            Stmt::Return(return_stmt) => return_stmt.span = span,
+            Stmt::If(if_stmt) => if_stmt.span = span,
+            Stmt::Block(block_stmt) => block_stmt.span = span,
+            Stmt::ForOf(for_of_stmt) => for_of_stmt.span = span,
+            Stmt::Empty(empty_stmt) => empty_stmt.span = span,
+            _ => (),
+        }
+    }
+}
+
+impl SetSpan for ModuleItem {
+    fn set_span(&mut self, span: swc_common::Span) {
+        match self {
+            ModuleItem::Stmt(stmt) => stmt.set_span(span),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                export_decl.span = span;
+                // The `export` is removed when using CommonJS so the comments
+                // need to be attached to the decl as well.
+                if let Decl::Var(var_decl) = &mut export_decl.decl {
+                    var_decl.span = span;
+                }
+            }
+            ModuleItem::ModuleDecl(_) => (),
+        }
+    }
+}
+
+// Attaches the comments from `stmt` to `items`, the code generated for it.
+// The emitter looks up comments by position so the first item's `lo` is where
+// the leading comments are stored and the last item's `hi` is where the
+// trailing comment is stored.  If `stmt` doesn't generate any code, e.g. type
+// decls, its comments are attached to the next statement instead.
+fn attach_comments<T: SetSpan>(
+    stmt: &values::Stmt,
+    items: &mut [T],
+    pending_comments: &mut Vec<values::Comment>,
+    comments: &SingleThreadedComments,
+) {
+    // Doc comments are replaced by a single JSDoc comment so that they show up
+    // in editors.  They're dropped if `stmt` doesn't generate any code since
+    // they don't describe the next statement.
+    let doc = match &stmt.kind {
+        values::StmtKind::Decl(decl) => get_jsdoc(decl),
+        _ => None,
+    };
+    pending_comments.extend(
+        stmt.leading_comments
+            .iter()
+            .filter(|comment| doc.is_none() || !comment.is_doc())
+            .cloned(),
+    );
+
+    if items.is_empty() {
+        pending_comments.extend(stmt.trailing_comment.iter().cloned());
+        return;
+    }
+
+    if pending_comments.is_empty() && doc.is_none() && stmt.trailing_comment.is_none() {
+        return;
+    }
+
+    let lo = BytePos(stmt.span.start as u32 + 1);
+    // The trailing comment is stored at its own position instead of at the end
+    // of `stmt` so that it isn't emitted after an expression that ends in the
+    // same place.
+    let hi = match &stmt.trailing_comment {
+        Some(comment) => BytePos(comment.span.start as u32 + 1),
+        None => BytePos(stmt.span.end as u32 + 1),
+    };
+
+    for comment in pending_comments.drain(..) {
+        comments.add_leading(lo, build_comment(&comment));
+    }
+    if let Some(doc) = &doc {
+        comments.add_leading(lo, build_jsdoc_comment(doc));
+    }
+    if let Some(comment) = &stmt.trailing_comment {
+        comments.add_trailing(hi, build_comment(comment));
+    }
+
+    let ctxt = SyntaxContext::empty();
+    let last = items.len() - 1;
+    if last > 0 {
+        items[0].set_span(swc_common::Span { lo, hi: lo, ctxt });
+    }
+    items[last].set_span(swc_common::Span { lo, hi, ctxt });
+}
+
+fn build_comment(comment: &values::Comment) -> swc_comments::Comment {
+    swc_comments::Comment {
+        kind: swc_comments::CommentKind::Line,
+        span: DUMMY_SP,
+        text: Atom::from(comment.text.as_str()),
+    }
+}
+
+// Returns the contents of the JSDoc comment for `decl`, its doc comments are
+// followed by a `@deprecated` tag if it has a `@deprecated` attribute.
+pub(crate) fn get_jsdoc(decl: &values::Decl) -> Option<String> {
+    let deprecated = decl.deprecation().map(|reason| match reason.is_empty() {
+        true => "@deprecated".to_string(),
+        false => format!("@deprecated {reason}"),
+    });
+    match (&decl.doc, deprecated) {
+        (Some(doc), Some(deprecated)) => Some(format!("{doc}\n{deprecated}")),
+        (doc, deprecated) => doc.clone().or(deprecated),
+    }
+}
+
+// Builds a `/** ... */` comment containing `doc`.  Tags such as `@param` and
+// `@deprecated` are passed through as is since they use the same syntax in
+// JSDoc.
+pub(crate) fn build_jsdoc_comment(doc: &str) -> swc_comments::Comment {
+    let mut text = String::from("*\n");
+    for line in doc.lines() {
+        match line.is_empty() {
+            true => text.push_str(" *\n"),
+            false => text.push_str(&format!(" * {line}\n")),
+        }
+    }
+    text.push(' ');
+
+    swc_comments::Comment {
+        kind: swc_comments::CommentKind::Block,
+        span: DUMMY_SP,
+        text: Atom::from(text),
+    }
+}
+
 // NOTE: If an identifier has been specified in `assign_id` the last statement
 // in the block will assign the final expression to that identifier.  If it's
 // `None`, the last statement will be an actual return statement returning the
@@ -858,27 +1677,29 @@ fn build_body_block_stmt(
 ) -> BlockStmt {
     let mut new_stmts: Vec<Stmt> = vec![];
     let len = body.stmts.len();
+    // Comments from statements that don't generate any code.
+    let mut pending_comments: Vec<values::Comment> = vec![];
 
     for (i, stmt) in body.stmts.iter().enumerate() {
+        let start = new_stmts.len();
+
         match &stmt.kind {
             values::StmtKind::Decl(values::Decl {
                 kind:
                     values::DeclKind::VarDecl(values::VarDecl {
                         pattern,
                         type_ann: _,
-                        expr: Some(init),
+                        expr: init,
                         is_declare: _,
-                        ..
+                        is_var,
                     }),
                 ..
             }) => {
-                let stmt = match build_pattern(pattern, &mut new_stmts, ctx) {
-                    Some(name) => {
-                        build_const_decl_stmt_with_pat(name, build_expr(init, &mut new_stmts, ctx))
-                    }
-                    None => todo!(),
-                };
-                new_stmts.push(stmt);
+                // Bindings without an initializer are assigned later on so
+                // they need to use `let`.
+                let is_var = *is_var || init.is_none();
+                let decl = build_var_decl(pattern, init.as_ref(), is_var, &mut new_stmts, ctx);
+                new_stmts.push(Stmt::Decl(Decl::Var(Box::from(decl))));
             }
             values::StmtKind::Expr(values::ExprStmt { expr }) => {
                 let expr = build_expr(expr, &mut new_stmts, ctx);
@@ -899,6 +1720,7 @@ fn build_body_block_stmt(
                     left: ForHead::VarDecl(Box::from(build_var_decl(
                         left,
                         None,
+                        false,
                         &mut new_stmts,
                         ctx,
                     ))),
@@ -940,6 +1762,11 @@ fn build_body_block_stmt(
             //     });
             //     new_stmts.push(stmt);
             // }
+            values::StmtKind::LetElse(let_else) => {
+                if let Some(decl) = build_let_else(let_else, &mut new_stmts, ctx) {
+                    new_stmts.push(Stmt::Decl(Decl::Var(Box::from(decl))));
+                }
+            }
             values::StmtKind::Return(values::ReturnStmt { arg }) => {
                 let stmt = Stmt::Return(ReturnStmt {
                     span: DUMMY_SP,
@@ -952,7 +1779,17 @@ fn build_body_block_stmt(
 
             // Other decls are ignored when generating .js
             values::StmtKind::Decl(_) => (),
+            values::StmtKind::Import(_) | values::StmtKind::Export(_) => {
+                panic!("imports and exports are only allowed at the top level")
+            }
         }
+
+        attach_comments(
+            stmt,
+            &mut new_stmts[start..],
+            &mut pending_comments,
+            &ctx.comments,
+        );
     }
 
     if body.stmts.is_empty() {
@@ -973,62 +1810,282 @@ fn build_body_block_stmt(
     }
 }
 
-// fn build_let_expr(
-//     let_expr: &values::LetExpr,
-//     consequent: &values::Block,
-//     alternate: Option<&values::Block>,
-//     stmts: &mut Vec<Stmt>,
-//     ctx: &mut Context,
-// ) -> Expr {
-//     let LetExpr { pat, expr, .. } = let_expr;
+fn build_if_let(if_let: &values::IfLet, stmts: &mut Vec<Stmt>, ctx: &mut Context) -> Expr {
+    let values::IfLet {
+        pattern,
+        expr,
+        consequent,
+        alternate,
+    } = if_let;
 
-//     let ret_id = ctx.new_ident();
-//     let ret_decl = build_let_decl_stmt(&ret_id);
-//     stmts.push(ret_decl);
+    // let $if_let_result_n;
+    let ret_id = ctx.new_ident("if_let_result");
+    let ret_decl = build_let_decl_stmt(&ret_id);
+    stmts.push(ret_decl);
 
-//     let temp_id = ctx.new_ident();
-//     let temp_decl = build_const_decl_stmt(&temp_id, build_expr(expr, stmts, ctx));
-//     stmts.push(temp_decl);
+    // const $if_let_value_n = <expr>;
+    let temp_id = ctx.new_ident("if_let_value");
+    let temp_decl = build_const_decl_stmt(&temp_id, build_expr(expr, stmts, ctx));
+    stmts.push(temp_decl);
 
-//     let cond = build_cond_for_pat(pat, &temp_id);
+    let cond = build_cond_for_pat(pattern, &temp_id);
 
-//     let finalizer = BlockFinalizer::Assign(ret_id.clone());
-//     let mut block = build_body_block_stmt(consequent, &finalizer, ctx);
+    let finalizer = BlockFinalizer::Assign(ret_id.clone());
+    let mut block = build_body_block_stmt(consequent, &finalizer, ctx);
 
-//     if let Some(name) = build_pattern(pat, stmts, ctx) {
-//         // TODO: ignore the refutuable patterns when destructuring
-//         let destructure = build_const_decl_stmt_with_pat(name, Expr::from(temp_id));
-//         block.stmts.insert(0, destructure);
-//     }
+    if let Some(name) = build_pattern(pattern, stmts, ctx) {
+        let destructure = build_const_decl_stmt_with_pat(name, Expr::from(temp_id));
+        block.stmts.insert(0, destructure);
+    }
 
-//     match cond {
-//         Some(cond) => {
-//             let alt = alternate
-//                 .as_ref()
-//                 .map(|alt| Box::from(Stmt::Block(build_body_block_stmt(alt, &finalizer, ctx))));
-//             let if_else = Stmt::If(IfStmt {
-//                 span: DUMMY_SP,
-//                 test: Box::from(cond),
-//                 cons: Box::from(Stmt::Block(block)),
-//                 alt,
-//             });
-//             stmts.push(if_else);
-//         }
-//         None => {
-//             stmts.push(Stmt::Block(block));
-//         }
-//     };
+    match cond {
+        Some(cond) => {
+            let alt = alternate
+                .as_ref()
+                .map(|alt| Box::from(build_alt(alt, &finalizer, stmts, ctx)));
+            stmts.push(Stmt::If(IfStmt {
+                span: DUMMY_SP,
+                test: Box::from(cond),
+                cons: Box::from(Stmt::Block(block)),
+                alt,
+            }));
+        }
+        // The pattern is irrefutable so the alternate is never taken.
+        None => {
+            stmts.push(Stmt::Block(block));
+        }
+    };
+
+    Expr::Ident(ret_id)
+}
 
-//     Expr::Ident(ret_id)
+// `let <pattern> = <expr> else { ... }` is lowered to:
+// const $let_else_value_n = <expr>;
+// if (!(<cond>)) { ... }
+// const <pattern> = $let_else_value_n;
+fn build_let_else(
+    let_else: &values::LetElseStmt,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut Context,
+) -> Option<VarDecl> {
+    let values::LetElseStmt {
+        pattern,
+        expr,
+        alternate,
+    } = let_else;
+
+    let temp_id = ctx.new_ident("let_else_value");
+    let temp_decl = build_const_decl_stmt(&temp_id, build_expr(expr, stmts, ctx));
+    stmts.push(temp_decl);
+
+    if let Some(cond) = build_cond_for_pat(pattern, &temp_id) {
+        let alt = build_body_block_stmt(alternate, &BlockFinalizer::ExprStmt, ctx);
+        stmts.push(Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::from(build_not(cond)),
+            cons: Box::from(Stmt::Block(alt)),
+            alt: None,
+        }));
+    }
+
+    let name = build_pattern(pattern, stmts, ctx)?;
+
+    Some(VarDecl {
+        span: DUMMY_SP,
+        kind: VarDeclKind::Const,
+        declare: false,
+        decls: vec![VarDeclarator {
+            span: DUMMY_SP,
+            name,
+            init: Some(Box::from(Expr::from(temp_id))),
+            definite: false,
+        }],
+    })
+}
+
+// Params whose patterns are refinements, e.g. `fn ({type: "click", x}) => x`,
+// are replaced with a temp that's checked and then destructured:
+// ($param_n)=>{
+//     if (!(<cond>)) {
+//         throw new TypeError("Argument doesn't match the param's pattern");
+//     }
+//     const { x } = $param_n;
+//     ...
 // }
+// The checker already prevents mismatched arguments, the check is for callers
+// that aren't type checked.
+fn build_refined_param(
+    pattern: &values::Pattern,
+    prelude: &mut Vec<Stmt>,
+    ctx: &mut Context,
+) -> Pat {
+    let temp_id = ctx.new_ident("param");
+
+    if let Some(cond) = build_cond_for_pat(pattern, &temp_id) {
+        let error = Expr::New(NewExpr {
+            span: DUMMY_SP,
+            callee: Box::from(Expr::Ident(Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from("TypeError"),
+                optional: false,
+            })),
+            args: Some(vec![ExprOrSpread {
+                spread: None,
+                expr: Box::from(Expr::Lit(Lit::Str(Str {
+                    span: DUMMY_SP,
+                    value: JsWord::from("Argument doesn't match the param's pattern"),
+                    raw: None,
+                }))),
+            }]),
+            type_args: None,
+        });
+        prelude.push(Stmt::If(IfStmt {
+            span: DUMMY_SP,
+            test: Box::from(build_not(cond)),
+            cons: Box::from(Stmt::Block(BlockStmt {
+                span: DUMMY_SP,
+                stmts: vec![Stmt::Throw(ThrowStmt {
+                    span: DUMMY_SP,
+                    arg: Box::from(error),
+                })],
+            })),
+            alt: None,
+        }));
+    }
+
+    if let Some(name) = build_pattern(pattern, prelude, ctx) {
+        let destructure = build_const_decl_stmt_with_pat(name, Expr::from(temp_id.to_owned()));
+        prelude.push(destructure);
+    }
+
+    Pat::Ident(BindingIdent::from(temp_id))
+}
 
-fn build_arm(
-    arm: &values::MatchArm,
-    id: &Ident,
-    ret_id: &Ident,
+// let $logical_result_n;
+// $logical_result_n = <left>;
+// if ($logical_result_n) { ...<right_stmts>; $logical_result_n = <right> }
+//
+// For `||` the condition is negated.
+fn build_short_circuit(
+    op: BinaryOp,
+    left: Expr,
+    right: Expr,
+    mut right_stmts: Vec<Stmt>,
     stmts: &mut Vec<Stmt>,
     ctx: &mut Context,
-) -> (Option<Expr>, BlockStmt) {
+) -> Expr {
+    let temp_id = ctx.new_ident("logical_result");
+    let finalizer = BlockFinalizer::Assign(temp_id.clone());
+    stmts.push(build_let_decl_stmt(&temp_id));
+    stmts.push(build_finalizer(&left, &finalizer));
+
+    let test = match op {
+        BinaryOp::LogicalAnd => Expr::Ident(temp_id.clone()),
+        BinaryOp::LogicalOr => build_not(Expr::Ident(temp_id.clone())),
+        _ => unreachable!(),
+    };
+    right_stmts.push(build_finalizer(&right, &finalizer));
+    stmts.push(Stmt::If(IfStmt {
+        span: DUMMY_SP,
+        test: Box::from(test),
+        cons: Box::from(Stmt::Block(BlockStmt {
+            span: DUMMY_SP,
+            stmts: right_stmts,
+        })),
+        alt: None,
+    }));
+
+    Expr::Ident(temp_id)
+}
+
+// e.g. `!(<cond>)`
+fn build_not(cond: Expr) -> Expr {
+    Expr::Unary(UnaryExpr {
+        span: DUMMY_SP,
+        op: UnaryOp::Bang,
+        arg: Box::from(Expr::Paren(ParenExpr {
+            span: DUMMY_SP,
+            expr: Box::from(cond),
+        })),
+    })
+}
+
+// Lowers a `match` to a chain of `if` statements that assign the result of
+// the arm that matches to a temp, e.g.
+//
+// let $match_result_n;
+// const $match_value_n = <expr>;
+// if (<test>) {
+//     const <pattern> = $match_value_n;
+//     $match_result_n = <body>;
+// } else if ...
+//
+// Each arm is lowered in two phases: the first checks whether the arm's
+// pattern matches and declares its bindings and the second evaluates the
+// arm's guard, which can use those bindings, before its body.  When a guard
+// fails the next arm is tried which can't be done with an if/else-if chain so
+// matches with guards break out of a labeled block instead.
+fn build_match(
+    expr: &values::Expr,
+    arms: &[values::MatchArm],
+    span: swc_common::Span,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut Context,
+) -> Expr {
+    // let $match_result_n;
+    let ret_temp_id = ctx.new_ident("match_result");
+    let ret_decl = build_let_decl_stmt(&ret_temp_id);
+    stmts.push(ret_decl);
+
+    // const $match_value_n = <expr>
+    let temp_id = ctx.new_ident("match_value");
+    let temp_decl = build_const_decl_stmt(&temp_id, build_expr(expr, stmts, ctx));
+    stmts.push(temp_decl);
+
+    // $match_n: { ... }
+    let label = match arms.iter().any(|arm| arm.guard.is_some()) {
+        true => Some(ctx.new_ident("match")),
+        false => None,
+    };
+
+    // Arms after a catch-all arm can never be matched so they're skipped.  The
+    // checker reports them as errors.
+    let mut lowered_arms: Vec<LoweredArm> = vec![];
+    for arm in arms {
+        let lowered_arm = lower_arm(arm, &temp_id, &ret_temp_id, ctx);
+        let is_catchall = lowered_arm.test.is_none() && lowered_arm.guard.is_none();
+        lowered_arms.push(lowered_arm);
+        if is_catchall {
+            break;
+        }
+    }
+
+    // Matches without arms are also reported by the checker, in which case
+    // `$match_result_n` is left `undefined`.
+    if !lowered_arms.is_empty() {
+        let stmt = match label {
+            Some(label) => build_labeled_arms(lowered_arms, label, span),
+            None => build_if_else_arms(lowered_arms, span),
+        };
+        stmts.push(stmt);
+    }
+
+    // $match_result_n
+    Expr::Ident(ret_temp_id)
+}
+
+struct LoweredArm {
+    // Checks whether the arm's pattern matches, `None` if it always matches.
+    test: Option<Expr>,
+    // Declares the bindings from the arm's pattern.
+    bindings: Vec<Stmt>,
+    // The arm's guard along with the statements needed to evaluate it.
+    guard: Option<(Vec<Stmt>, Expr)>,
+    // Assigns the arm's result to `$match_result_n`.
+    body: BlockStmt,
+}
+
+fn lower_arm(arm: &values::MatchArm, id: &Ident, ret_id: &Ident, ctx: &mut Context) -> LoweredArm {
     let values::MatchArm {
         pattern: pat,
         body,
@@ -1036,9 +2093,9 @@ fn build_arm(
         ..
     } = arm;
 
-    let cond = build_cond_for_pat(pat, id);
+    let test = build_cond_for_pat(pat, id);
 
-    let mut block = match body {
+    let body = match body {
         values::BlockOrExpr::Block(body) => {
             build_body_block_stmt(body, &BlockFinalizer::Assign(ret_id.to_owned()), ctx)
         }
@@ -1058,28 +2115,124 @@ fn build_arm(
     };
 
     // If pattern has assignables, assign them
-    if let Some(name) = build_pattern(pat, stmts, ctx) {
+    let mut bindings: Vec<Stmt> = vec![];
+    if let Some(name) = build_pattern(pat, &mut bindings, ctx) {
         let destructure = build_const_decl_stmt_with_pat(name, Expr::from(id.to_owned()));
-        block.stmts.insert(0, destructure);
+        bindings.push(destructure);
     }
 
-    let cond = match (cond, guard) {
-        (Some(cond), Some(guard)) => {
-            // If the pattern was refutable and there's a guard then
-            // we return them logically AND-ed together.
-            Some(Expr::Bin(BinExpr {
+    let guard = guard.as_ref().map(|guard| {
+        let mut stmts: Vec<Stmt> = vec![];
+        let guard = build_expr(guard, &mut stmts, ctx);
+        (stmts, guard)
+    });
+
+    LoweredArm {
+        test,
+        bindings,
+        guard,
+        body,
+    }
+}
+
+// Used when none of the arms have guards.
+fn build_if_else_arms(arms: Vec<LoweredArm>, span: swc_common::Span) -> Stmt {
+    // We reverse the order of the arms because when building
+    // an if/else-if/else chain we need to start with the `else`
+    // and work our way back to the initial `if`.
+    let mut iter = arms.into_iter().rev().map(|arm| {
+        let mut stmts = arm.bindings;
+        stmts.extend(arm.body.stmts);
+        let block = BlockStmt {
+            span: arm.body.span,
+            stmts,
+        };
+        (arm.test, block)
+    });
+    let first = match iter.next() {
+        Some((test, block)) => match test {
+            Some(test) => Stmt::If(IfStmt {
                 span: DUMMY_SP,
-                op: BinaryOp::LogicalAnd,
-                left: Box::from(cond),
-                right: Box::from(build_expr(guard, stmts, ctx)),
-            }))
-        }
-        (Some(cond), None) => Some(cond),
-        (None, Some(guard)) => Some(build_expr(guard, stmts, ctx)),
-        (None, None) => None,
+                test: Box::from(test),
+                cons: Box::from(Stmt::Block(block)),
+                alt: None,
+            }),
+            None => Stmt::Block(block),
+        },
+        None => unreachable!("matches without arms aren't lowered"),
     };
 
-    (cond, block)
+    iter.fold(first, |prev, (test, block)| {
+        Stmt::If(IfStmt {
+            span,
+            test: Box::from(test.unwrap()),
+            cons: Box::from(Stmt::Block(block)),
+            alt: Some(Box::from(prev)),
+        })
+    })
+}
+
+// Used when some of the arms have guards.  Each arm breaks out of the block
+// labeled `label` if it matches, e.g.
+//
+// $match_n: {
+//     if (<test>) {
+//         const <pattern> = $match_value_n;
+//         if (<guard>) {
+//             $match_result_n = <body>;
+//             break $match_n;
+//         }
+//     }
+//     ...
+// }
+fn build_labeled_arms(arms: Vec<LoweredArm>, label: Ident, span: swc_common::Span) -> Stmt {
+    let stmts: Vec<Stmt> = arms
+        .into_iter()
+        .map(|arm| {
+            let mut body = arm.body;
+            body.stmts.push(Stmt::Break(BreakStmt {
+                span: DUMMY_SP,
+                label: Some(label.to_owned()),
+            }));
+
+            let mut stmts = arm.bindings;
+            match arm.guard {
+                Some((guard_stmts, guard)) => {
+                    stmts.extend(guard_stmts);
+                    stmts.push(Stmt::If(IfStmt {
+                        span: DUMMY_SP,
+                        test: Box::from(guard),
+                        cons: Box::from(Stmt::Block(body)),
+                        alt: None,
+                    }));
+                }
+                None => stmts.extend(body.stmts),
+            }
+
+            let block = BlockStmt {
+                span: DUMMY_SP,
+                stmts,
+            };
+            match arm.test {
+                Some(test) => Stmt::If(IfStmt {
+                    span: DUMMY_SP,
+                    test: Box::from(test),
+                    cons: Box::from(Stmt::Block(block)),
+                    alt: None,
+                }),
+                None => Stmt::Block(block),
+            }
+        })
+        .collect();
+
+    Stmt::Labeled(LabeledStmt {
+        span,
+        label,
+        body: Box::from(Stmt::Block(BlockStmt {
+            span: DUMMY_SP,
+            stmts,
+        })),
+    })
 }
 
 fn build_jsx_element(
@@ -1087,16 +2240,10 @@ fn build_jsx_element(
     stmts: &mut Vec<Stmt>,
     ctx: &mut Context,
 ) -> JSXElement {
-    let name = match &elem.opening.name {
-        values::JSXElementName::Ident(name) => JSXElementName::Ident(Ident {
-            span: DUMMY_SP,
-            sym: JsWord::from(name.name.to_owned()),
-            optional: false,
-        }),
-        values::JSXElementName::JSXMemberExpr(_) => todo!(),
-    };
+    let name = build_jsx_element_name(&elem.opening.name);
+    let self_closing = elem.opening.self_closing;
 
-    let elem = JSXElement {
+    JSXElement {
         span: DUMMY_SP,
         opening: JSXOpeningElement {
             span: DUMMY_SP,
@@ -1134,44 +2281,97 @@ fn build_jsx_element(
                     })
                 })
                 .collect(),
-            self_closing: false,
+            self_closing,
             type_args: None,
         },
-        children: elem
-            .children
-            .iter()
-            .map(|child| {
-                let result: JSXElementChild = match child {
-                    values::JSXElementChild::Text(values::JSXText { value, .. }) => {
-                        JSXElementChild::JSXText(JSXText {
-                            span: DUMMY_SP,
-                            value: Atom::new(value.clone()),
-                            raw: Atom::new(value.clone()),
-                        })
-                    }
-                    values::JSXElementChild::ExprContainer(values::JSXExprContainer {
-                        expr,
-                        ..
-                    }) => JSXElementChild::JSXExprContainer(JSXExprContainer {
-                        span: DUMMY_SP,
-                        expr: JSXExpr::Expr(Box::from(build_expr(expr, stmts, ctx))),
-                    }),
-                    values::JSXElementChild::Element(elem) => {
-                        JSXElementChild::JSXElement(Box::from(build_jsx_element(elem, stmts, ctx)))
-                    }
-                    values::JSXElementChild::SpreadChild(_) => todo!(),
-                    values::JSXElementChild::Fragment(_) => todo!(),
-                };
-                result
-            })
-            .collect(),
-        closing: Some(JSXClosingElement {
+        children: build_jsx_children(&elem.children, stmts, ctx),
+        closing: match self_closing {
+            true => None,
+            false => Some(JSXClosingElement {
+                span: DUMMY_SP,
+                name,
+            }),
+        },
+    }
+}
+
+fn build_jsx_fragment(
+    fragment: &values::JSXFragment,
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut Context,
+) -> JSXFragment {
+    JSXFragment {
+        span: DUMMY_SP,
+        opening: JSXOpeningFragment { span: DUMMY_SP },
+        children: build_jsx_children(&fragment.children, stmts, ctx),
+        closing: JSXClosingFragment { span: DUMMY_SP },
+    }
+}
+
+fn build_jsx_element_name(name: &values::JSXElementName) -> JSXElementName {
+    fn build_ident(ident: &values::Ident) -> Ident {
+        Ident {
             span: DUMMY_SP,
-            name,
-        }),
-    };
+            sym: JsWord::from(ident.name.to_owned()),
+            optional: false,
+        }
+    }
+
+    fn build_member_expr(member: &values::JSXMemberExpr) -> JSXMemberExpr {
+        JSXMemberExpr {
+            obj: match &member.obj {
+                values::JSXObject::Ident(ident) => JSXObject::Ident(build_ident(ident)),
+                values::JSXObject::JSXMemberExpr(member) => {
+                    JSXObject::JSXMemberExpr(Box::from(build_member_expr(member)))
+                }
+            },
+            prop: build_ident(&member.prop),
+        }
+    }
 
-    elem
+    match name {
+        values::JSXElementName::Ident(ident) => JSXElementName::Ident(build_ident(ident)),
+        values::JSXElementName::JSXMemberExpr(member) => {
+            JSXElementName::JSXMemberExpr(build_member_expr(member))
+        }
+    }
+}
+
+fn build_jsx_children(
+    children: &[values::JSXElementChild],
+    stmts: &mut Vec<Stmt>,
+    ctx: &mut Context,
+) -> Vec<JSXElementChild> {
+    children
+        .iter()
+        .map(|child| match child {
+            values::JSXElementChild::Text(values::JSXText { value, .. }) => {
+                JSXElementChild::JSXText(JSXText {
+                    span: DUMMY_SP,
+                    value: Atom::new(value.clone()),
+                    raw: Atom::new(value.clone()),
+                })
+            }
+            values::JSXElementChild::ExprContainer(values::JSXExprContainer { expr, .. }) => {
+                JSXElementChild::JSXExprContainer(JSXExprContainer {
+                    span: DUMMY_SP,
+                    expr: JSXExpr::Expr(Box::from(build_expr(expr, stmts, ctx))),
+                })
+            }
+            values::JSXElementChild::Element(elem) => {
+                JSXElementChild::JSXElement(Box::from(build_jsx_element(elem, stmts, ctx)))
+            }
+            values::JSXElementChild::SpreadChild(values::JSXSpreadChild { expr }) => {
+                JSXElementChild::JSXSpreadChild(JSXSpreadChild {
+                    span: DUMMY_SP,
+                    expr: Box::from(build_expr(expr, stmts, ctx)),
+                })
+            }
+            values::JSXElementChild::Fragment(fragment) => {
+                JSXElementChild::JSXFragment(build_jsx_fragment(fragment, stmts, ctx))
+            }
+        })
+        .collect()
 }
 
 fn build_class(class: &values::Class, stmts: &mut Vec<Stmt>, ctx: &mut Context) -> Class {
@@ -1181,12 +2381,12 @@ fn build_class(class: &values::Class, stmts: &mut Vec<Stmt>, ctx: &mut Context)
         .filter_map(|member| match member {
             values::ClassMember::Method(method) => {
                 // TODO: check if `name` is `constructor`
-                let body = match &method.function.body {
+                let body = ctx.with_temp_scope(|ctx| match &method.function.body {
                     values::BlockOrExpr::Block(block) => {
                         build_body_block_stmt(block, &BlockFinalizer::ExprStmt, ctx)
                     }
                     values::BlockOrExpr::Expr(_) => todo!(),
-                };
+                });
 
                 // In Escalier, `self` is always the first param in non-static
                 // methods, but it represents `this` in JavaScript which is
@@ -1282,9 +2482,8 @@ fn prop_name_from_prop_name(prop_name: &values::PropName, ctx: &mut Context) ->
 fn build_cond_for_pat(pat: &values::Pattern, id: &Ident) -> Option<Expr> {
     // TODO: implmenent `is_refutable`
     if is_refutable(pat) {
-        // Right now the only refutable pattern we support is LitPat.
-        // In the future there will be other refutable patterns such as
-        // array length, typeof, and instanceof checks.
+        // Each refutable sub-pattern adds a condition, all of which must
+        // be true for the pattern to match.
 
         let mut conds: Vec<Condition> = vec![];
 
@@ -1300,7 +2499,7 @@ fn build_cond_for_pat(pat: &values::Pattern, id: &Ident) -> Option<Expr> {
         Some(iter.fold(first, |prev, next| {
             Expr::Bin(BinExpr {
                 span: DUMMY_SP,
-                op: BinaryOp::LogicalOr,
+                op: BinaryOp::LogicalAnd,
                 left: Box::from(prev),
                 right: Box::from(cond_to_expr(next, id)),
             })
@@ -1310,6 +2509,13 @@ fn build_cond_for_pat(pat: &values::Pattern, id: &Ident) -> Option<Expr> {
     }
 }
 
+fn is_self_param(param: &values::FuncParam) -> bool {
+    matches!(
+        &param.pattern.kind,
+        values::PatternKind::Ident(values::BindingIdent { name, .. }) if name == "self"
+    )
+}
+
 fn is_refutable(pat: &values::Pattern) -> bool {
     match &pat.kind {
         // irrefutable
@@ -1320,6 +2526,7 @@ fn is_refutable(pat: &values::Pattern) -> bool {
         // refutable
         values::PatternKind::Lit(_) => true,
         values::PatternKind::Is(_) => true,
+        values::PatternKind::Range(_) => true,
 
         // refutable if at least one sub-pattern is refutable
         values::PatternKind::Object(values::ObjectPat { props, .. }) => {
@@ -1331,16 +2538,8 @@ fn is_refutable(pat: &values::Pattern) -> bool {
                 values::ObjectPatProp::Rest(values::RestPat { arg, .. }) => is_refutable(arg),
             })
         }
-        values::PatternKind::Tuple(values::TuplePat { elems, .. }) => {
-            elems.iter().any(|elem| {
-                match elem {
-                    Some(elem) => is_refutable(&elem.pattern),
-                    // FixMe: this should probably be true since it's equivalent
-                    // to having an element with the value `undefined`
-                    None => false,
-                }
-            })
-        }
+        // refutable since the length of the array is checked
+        values::PatternKind::Tuple(_) => true,
     }
 }
 
@@ -1371,7 +2570,7 @@ fn build_template_literal(
                 TplElement {
                     span: DUMMY_SP,
                     cooked: Some(Atom::new(quasi.value.clone())),
-                    raw: Atom::new(quasi.value.clone()),
+                    raw: Atom::new(escape_template_raw(&quasi.value)),
                     tail: false, // TODO: set this to `true` if it's the last quasi
                 }
             })
@@ -1379,6 +2578,24 @@ fn build_template_literal(
     }
 }
 
+// Template parts contain the string after escape sequences have been processed
+// so characters that would otherwise end the part or start an escape sequence
+// need to be escaped again when printing the template literal.
+fn escape_template_raw(value: &str) -> String {
+    let mut raw = String::new();
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => raw.push_str("\\\\"),
+            '`' => raw.push_str("\\`"),
+            '\r' => raw.push_str("\\r"),
+            '$' if chars.peek() == Some(&'{') => raw.push_str("\\$"),
+            c => raw.push(c),
+        }
+    }
+    raw
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 enum PathElem {
     ObjProp(String),
@@ -1390,7 +2607,9 @@ enum Check {
     EqualLit(values::Literal),
     Typeof(String), // limit this to primitives: "number", "string", "boolean"
     Instanceof(values::Ident),
-    // TODO: array length
+    Length(u32),
+    MinLength(u32), // used when the tuple pattern has a rest element
+    Compare(BinaryOp, values::Literal), // used for range patterns
 }
 
 type Path = Vec<PathElem>;
@@ -1425,6 +2644,26 @@ fn get_conds_for_pat(pat: &values::Pattern, conds: &mut Vec<Condition>, path: &m
             }
         }
         values::PatternKind::Tuple(values::TuplePat { elems, .. }) => {
+            let has_rest = elems.iter().any(|elem| {
+                matches!(
+                    elem,
+                    Some(values::TuplePatElem {
+                        pattern: values::Pattern {
+                            kind: values::PatternKind::Rest(_),
+                            ..
+                        },
+                        ..
+                    })
+                )
+            });
+            conds.push(Condition {
+                path: path.to_owned(),
+                check: match has_rest {
+                    true => Check::MinLength(elems.len() as u32 - 1),
+                    false => Check::Length(elems.len() as u32),
+                },
+            });
+
             for (index, elem) in elems.iter().enumerate() {
                 path.push(PathElem::ArrayIndex(index as u32));
                 if let Some(elem) = elem {
@@ -1454,6 +2693,32 @@ fn get_conds_for_pat(pat: &values::Pattern, conds: &mut Vec<Condition>, path: &m
                 });
             }
         },
+        // e.g. `0..10` is lowered to `x >= 0 && x < 10`
+        values::PatternKind::Range(values::RangePat {
+            start,
+            end,
+            inclusive,
+        }) => {
+            if let Some(start) = start {
+                conds.push(Condition {
+                    path: path.to_owned(),
+                    check: Check::Compare(
+                        BinaryOp::GtEq,
+                        values::Literal::Number(start.to_owned()),
+                    ),
+                });
+            }
+            if let Some(end) = end {
+                let op = match inclusive {
+                    true => BinaryOp::LtEq,
+                    false => BinaryOp::Lt,
+                };
+                conds.push(Condition {
+                    path: path.to_owned(),
+                    check: Check::Compare(op, values::Literal::Number(end.to_owned())),
+                });
+            }
+        }
     }
 }
 
@@ -1513,9 +2778,39 @@ fn cond_to_expr(cond: &Condition, id: &Ident) -> Expr {
             left: Box::from(left),
             right: Box::from(Expr::Ident(Ident::from(id))),
         }),
+        Check::Length(len) => build_length_check(left, BinaryOp::EqEqEq, *len),
+        Check::MinLength(len) => build_length_check(left, BinaryOp::GtEq, *len),
+        Check::Compare(op, lit) => Expr::Bin(BinExpr {
+            span: DUMMY_SP,
+            op: *op,
+            left: Box::from(left),
+            right: Box::from(Expr::from(lit)),
+        }),
     }
 }
 
+// e.g. `$match_value_0.length === 2`
+fn build_length_check(array: Expr, op: BinaryOp, len: u32) -> Expr {
+    Expr::Bin(BinExpr {
+        span: DUMMY_SP,
+        op,
+        left: Box::from(Expr::Member(MemberExpr {
+            span: DUMMY_SP,
+            obj: Box::from(array),
+            prop: MemberProp::Ident(Ident {
+                span: DUMMY_SP,
+                sym: JsWord::from("length"),
+                optional: false,
+            }),
+        })),
+        right: Box::from(Expr::Lit(Lit::Num(Number {
+            span: DUMMY_SP,
+            value: len as f64,
+            raw: None,
+        }))),
+    })
+}
+
 fn build_const_decl_stmt(id: &Ident, expr: Expr) -> Stmt {
     build_const_decl_stmt_with_pat(Pat::Ident(BindingIdent::from(id.to_owned())), expr)
 }
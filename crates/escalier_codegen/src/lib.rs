@@ -1,5 +1,13 @@
 pub mod d_ts;
+pub mod doc;
+mod helpers;
 pub mod js;
+mod optimize;
+pub mod options;
+mod rename;
 
 pub use d_ts::codegen_d_ts;
-pub use js::codegen_js;
+pub use doc::{codegen_doc, DocFormat};
+pub use helpers::codegen_runtime_helpers;
+pub use js::{codegen_js, codegen_js_with_options};
+pub use options::{CodegenOptions, JSXRuntime, ModuleFormat, RuntimeHelpers, Target};
@@ -0,0 +1,288 @@
+use swc_atoms::JsWord;
+use swc_ecma_ast::*;
+use swc_ecma_visit::*;
+
+// Cleans up some of the artifacts produced when lowering Escalier to JS:
+// - empty statements left behind by type-only decls are removed
+// - chains of temps, e.g. `let $if_result_1; ...; $if_result_0 = $if_result_1;`, which are
+//   produced when lowering nested if-else expressions are collapsed
+// - consts that are initialized with a literal and only used once are inlined
+pub(crate) fn optimize_program(program: &mut Program) {
+    program.visit_mut_with(&mut Optimizer);
+}
+
+struct Optimizer;
+
+impl VisitMut for Optimizer {
+    fn visit_mut_module_items(&mut self, items: &mut Vec<ModuleItem>) {
+        items.visit_mut_children_with(self);
+        optimize_stmts(items);
+    }
+
+    fn visit_mut_stmts(&mut self, stmts: &mut Vec<Stmt>) {
+        stmts.visit_mut_children_with(self);
+        optimize_stmts(stmts);
+    }
+}
+
+// Allows module items and statements to be optimized in the same way.  Only
+// module items that are statements are removed or inlined, exported decls are
+// left as is.
+trait StmtLike: VisitWith<UseCounter> + VisitMutWith<Renamer> + VisitMutWith<Inliner> {
+    fn as_stmt(&self) -> Option<&Stmt>;
+}
+
+impl StmtLike for Stmt {
+    fn as_stmt(&self) -> Option<&Stmt> {
+        Some(self)
+    }
+}
+
+impl StmtLike for ModuleItem {
+    fn as_stmt(&self) -> Option<&Stmt> {
+        match self {
+            ModuleItem::Stmt(stmt) => Some(stmt),
+            ModuleItem::ModuleDecl(_) => None,
+        }
+    }
+}
+
+fn optimize_stmts<T: StmtLike>(stmts: &mut Vec<T>) {
+    stmts.retain(|stmt| !matches!(stmt.as_stmt(), Some(Stmt::Empty(_))));
+    collapse_temps(stmts);
+    inline_consts(stmts);
+}
+
+// let $if_result_1;
+// if (cond) {
+//     $if_result_1 = 5;
+// } else {
+//     $if_result_1 = 10;
+// }
+// $if_result_0 = $if_result_1;
+//
+// becomes
+//
+// if (cond) {
+//     $if_result_0 = 5;
+// } else {
+//     $if_result_0 = 10;
+// }
+fn collapse_temps<T: StmtLike>(stmts: &mut Vec<T>) {
+    let mut i = 0;
+    while i < stmts.len() {
+        let (target, temp) = match stmts[i].as_stmt().and_then(as_temp_assign) {
+            Some((target, temp)) => (target.to_owned(), temp.to_owned()),
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+
+        let decl = stmts[..i]
+            .iter()
+            .position(|stmt| stmt.as_stmt().and_then(as_let_decl) == Some(&temp));
+
+        match decl {
+            Some(decl) if count_uses(stmts, &temp).reads == 1 => {
+                stmts.remove(i);
+                stmts.remove(decl);
+                i -= 1;
+
+                let mut renamer = Renamer {
+                    from: temp,
+                    to: target,
+                };
+                for stmt in stmts[decl..i].iter_mut() {
+                    stmt.visit_mut_with(&mut renamer);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+}
+
+// const message = "hello";
+// console.log(message);
+//
+// becomes
+//
+// console.log("hello");
+fn inline_consts<T: StmtLike>(stmts: &mut Vec<T>) {
+    let mut i = 0;
+    while i < stmts.len() {
+        let (name, lit) = match stmts[i].as_stmt().and_then(as_const_literal) {
+            Some((name, lit)) => (name.to_owned(), lit.to_owned()),
+            None => {
+                i += 1;
+                continue;
+            }
+        };
+
+        let counter = count_uses(stmts, &name);
+        // If the name is declared more than once, some of the uses could be
+        // referring to a different binding.
+        if counter.reads == 1 && counter.decls == 1 && !counter.is_shorthand {
+            stmts.remove(i);
+
+            let mut inliner = Inliner { name, lit };
+            for stmt in stmts[i..].iter_mut() {
+                stmt.visit_mut_with(&mut inliner);
+            }
+        } else {
+            i += 1;
+        }
+    }
+}
+
+// `$` isn't allowed in Escalier identifiers so only temps start with it.
+fn is_temp(ident: &Ident) -> bool {
+    ident.sym.starts_with('$')
+}
+
+// Matches `let $<temp>;`
+fn as_let_decl(stmt: &Stmt) -> Option<&JsWord> {
+    match stmt {
+        Stmt::Decl(Decl::Var(var_decl)) if var_decl.kind == VarDeclKind::Let => {
+            match var_decl.decls.as_slice() {
+                [VarDeclarator {
+                    name: Pat::Ident(BindingIdent { id, .. }),
+                    init: None,
+                    ..
+                }] if is_temp(id) => Some(&id.sym),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Matches `const <name> = <literal>;`
+fn as_const_literal(stmt: &Stmt) -> Option<(&JsWord, &Lit)> {
+    match stmt {
+        Stmt::Decl(Decl::Var(var_decl)) if var_decl.kind == VarDeclKind::Const => {
+            match var_decl.decls.as_slice() {
+                [VarDeclarator {
+                    name: Pat::Ident(BindingIdent { id, .. }),
+                    init: Some(init),
+                    ..
+                }] => match init.as_ref() {
+                    // Regexes aren't included since each evaluation of a regex
+                    // literal creates a new object.
+                    Expr::Lit(
+                        lit @ (Lit::Str(_)
+                        | Lit::Bool(_)
+                        | Lit::Null(_)
+                        | Lit::Num(_)
+                        | Lit::BigInt(_)),
+                    ) => Some((&id.sym, lit)),
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// Matches `$<temp> = $<other_temp>;`
+fn as_temp_assign(stmt: &Stmt) -> Option<(&JsWord, &JsWord)> {
+    let (left, right) = match stmt {
+        Stmt::Expr(ExprStmt { expr, .. }) => match expr.as_ref() {
+            Expr::Assign(AssignExpr {
+                op: AssignOp::Assign,
+                left,
+                right,
+                ..
+            }) => (left, right),
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    let left = match left {
+        PatOrExpr::Pat(pat) => match pat.as_ref() {
+            Pat::Ident(BindingIdent { id, .. }) => id,
+            _ => return None,
+        },
+        PatOrExpr::Expr(expr) => match expr.as_ref() {
+            Expr::Ident(id) => id,
+            _ => return None,
+        },
+    };
+
+    match right.as_ref() {
+        Expr::Ident(right) if is_temp(left) && is_temp(right) => Some((&left.sym, &right.sym)),
+        _ => None,
+    }
+}
+
+fn count_uses<T: StmtLike>(stmts: &[T], name: &JsWord) -> UseCounter {
+    let mut counter = UseCounter {
+        name: name.to_owned(),
+        reads: 0,
+        decls: 0,
+        is_shorthand: false,
+    };
+    for stmt in stmts {
+        stmt.visit_with(&mut counter);
+    }
+    counter
+}
+
+struct UseCounter {
+    name: JsWord,
+    reads: usize,
+    decls: usize,
+    // Set if the name is used as a shorthand property, e.g. `{x}`.
+    is_shorthand: bool,
+}
+
+impl Visit for UseCounter {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Ident(ident) if ident.sym == self.name => self.reads += 1,
+            _ => expr.visit_children_with(self),
+        }
+    }
+
+    fn visit_binding_ident(&mut self, ident: &BindingIdent) {
+        if ident.id.sym == self.name {
+            self.decls += 1;
+        }
+    }
+
+    fn visit_prop(&mut self, prop: &Prop) {
+        match prop {
+            Prop::Shorthand(ident) if ident.sym == self.name => self.is_shorthand = true,
+            _ => prop.visit_children_with(self),
+        }
+    }
+}
+
+struct Renamer {
+    from: JsWord,
+    to: JsWord,
+}
+
+impl VisitMut for Renamer {
+    fn visit_mut_ident(&mut self, ident: &mut Ident) {
+        if ident.sym == self.from {
+            ident.sym = self.to.to_owned();
+        }
+    }
+}
+
+struct Inliner {
+    name: JsWord,
+    lit: Lit,
+}
+
+impl VisitMut for Inliner {
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Ident(ident) if ident.sym == self.name => *expr = Expr::Lit(self.lit.to_owned()),
+            _ => expr.visit_mut_children_with(self),
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use swc_ecma_ast::EsVersion;
+use swc_ecma_transforms_react::{Options, Runtime};
+
+use escalier_ast::Pragma;
+
+pub use escalier_ast::{JSXRuntime, ModuleFormat, Target};
+
+pub(crate) fn to_swc_jsx_options(runtime: &JSXRuntime) -> Options {
+    match runtime {
+        JSXRuntime::Classic {
+            pragma,
+            pragma_frag,
+        } => Options {
+            runtime: Some(Runtime::Classic),
+            pragma: Some(pragma.to_owned()),
+            pragma_frag: Some(pragma_frag.to_owned()),
+            ..Default::default()
+        },
+        JSXRuntime::Automatic { import_source } => Options {
+            runtime: Some(Runtime::Automatic),
+            import_source: Some(import_source.to_owned()),
+            ..Default::default()
+        },
+    }
+}
+
+pub(crate) fn to_es_version(target: Target) -> EsVersion {
+    match target {
+        Target::Es2015 => EsVersion::Es2015,
+        Target::Es2020 => EsVersion::Es2020,
+        Target::EsNext => EsVersion::EsNext,
+    }
+}
+
+/// Determines how runtime helpers used by the generated code are included.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RuntimeHelpers {
+    /// Helpers are defined at the start of each file that uses them.
+    #[default]
+    Inline,
+    /// Helpers are imported from `module` which avoids duplicating them in
+    /// each file.  The module can be generated using
+    /// `codegen_runtime_helpers`.
+    Import { module: String },
+}
+
+/// Options that control how Escalier code is compiled to JavaScript.  These
+/// are specific to codegen, the options shared with the parser and checker,
+/// e.g. the module format and JSX runtime, are in `CompilerOptions`.
+#[derive(Clone, Debug, Default)]
+pub struct CodegenOptions {
+    /// Wraps object and tuple literals that are assigned to immutable bindings
+    /// in `Object.freeze()` so that mutations throw at runtime.  This is meant
+    /// for debug builds since it has a runtime cost.  It can be overridden in
+    /// each file using `// @freeze-immutable-values on` or `off`.
+    pub freeze_immutable_values: bool,
+    /// Runs a cleanup pass over the generated code which removes empty
+    /// statements, collapses chains of temp variables and inlines consts
+    /// that are initialized with literals and only used once.
+    pub optimize: bool,
+    /// How runtime helpers are included, only helpers that are used are
+    /// included in the output.
+    pub runtime_helpers: RuntimeHelpers,
+}
+
+impl CodegenOptions {
+    /// Overrides `self` with the pragmas at the start of a script.  Pragmas
+    /// that don't affect these options, e.g. `@strict-returns` and the JSX
+    /// pragmas handled by `JSXRuntime::with_pragmas`, and pragmas with invalid
+    /// values are ignored.
+    pub fn with_pragmas(&self, pragmas: &[Pragma]) -> Self {
+        let mut options = self.to_owned();
+        for Pragma { name, value, .. } in pragmas {
+            if name == "freeze-immutable-values" {
+                match value.as_deref() {
+                    Some("on") | None => options.freeze_immutable_values = true,
+                    Some("off") => options.freeze_immutable_values = false,
+                    Some(_) => (),
+                }
+            }
+        }
+        options
+    }
+}
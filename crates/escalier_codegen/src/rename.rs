@@ -0,0 +1,343 @@
+use std::collections::{HashMap, HashSet};
+
+use escalier_ast::*;
+
+use crate::d_ts::get_bindings;
+
+// JavaScript doesn't allow `let` and `const` bindings to be redeclared in the
+// same block and bindings can't be used anywhere in their block before they're
+// declared, e.g. `const x = parse(x)` throws.  Escalier allows both, so the
+// bindings that would cause these errors are renamed to `x$1`, `x$2`, etc.
+// Escalier identifiers can't contain `$` so the new names can't conflict with
+// other bindings.
+//
+// Top-level bindings are never renamed since they can be exported.  The type
+// checker doesn't allow them to be redeclared.
+pub(crate) fn rename_shadowed_bindings(script: &Script) -> Script {
+    let mut script = script.clone();
+    let mut renamer = Renamer {
+        scopes: vec![Scope::default()],
+        counts: HashMap::new(),
+    };
+    // Top-level bindings can be used before they're declared, e.g. inside of
+    // functions, so they're known ahead of time.
+    for stmt in &script.stmts {
+        let names = match &stmt.kind {
+            StmtKind::Decl(Decl { kind, .. }) => match kind {
+                DeclKind::VarDecl(VarDecl { pattern, .. }) => get_bindings(pattern),
+                DeclKind::FnDecl(FnDecl { name, .. })
+                | DeclKind::ClassDecl(ClassDecl { name, .. })
+                | DeclKind::ModuleDecl(ModuleDecl { name, .. }) => vec![name.to_owned()],
+                DeclKind::TypeDecl(_) | DeclKind::GlobalDecl(_) => vec![],
+            },
+            StmtKind::Import(import) => import
+                .value_specifiers()
+                .into_iter()
+                .map(|specifier| specifier.local.to_owned())
+                .collect(),
+            _ => vec![],
+        };
+        for name in names {
+            renamer.scopes[0].bindings.insert(name.to_owned(), name);
+        }
+    }
+    renamer.visit_program_mut(&mut script);
+    script
+}
+
+#[derive(Default)]
+struct Scope {
+    // Maps the names of the bindings declared in this scope to the names
+    // they're emitted as.
+    bindings: HashMap<String, String>,
+    // Names used in this scope, or in scopes nested inside of it, that refer
+    // to bindings declared outside of it.
+    outer_uses: HashSet<String>,
+}
+
+struct Renamer {
+    scopes: Vec<Scope>,
+    // The number of times each name has been renamed.  Renamed bindings are
+    // numbered across the whole script so that a binding in a nested function
+    // can't shadow a renamed binding from an enclosing function.
+    counts: HashMap<String, u32>,
+}
+
+impl Renamer {
+    fn declare(&mut self, name: &mut String) {
+        let is_top_level = self.scopes.len() == 1;
+        let scope = self.scopes.last_mut().unwrap();
+        let new_name = if !is_top_level
+            && (scope.bindings.contains_key(name.as_str())
+                || scope.outer_uses.contains(name.as_str()))
+        {
+            let count = self.counts.entry(name.to_owned()).or_insert(0);
+            *count += 1;
+            format!("{name}${count}")
+        } else {
+            name.to_owned()
+        };
+        scope.bindings.insert(name.to_owned(), new_name.to_owned());
+        *name = new_name;
+    }
+
+    fn resolve(&mut self, name: &mut String) {
+        for scope in self.scopes.iter_mut().rev() {
+            if let Some(new_name) = scope.bindings.get(name.as_str()) {
+                *name = new_name.to_owned();
+                return;
+            }
+            scope.outer_uses.insert(name.to_owned());
+        }
+        // `absurd` from the prelude isn't a JavaScript global so it's
+        // replaced with a runtime helper unless the script declares its own.
+        if name == "absurd" {
+            *name = "$absurd".to_string();
+        }
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(Scope::default());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn visit_jsx_element(&mut self, elem: &mut JSXElement) {
+        self.visit_jsx_element_name(&mut elem.opening.name);
+        if let Some(closing) = &mut elem.closing {
+            self.visit_jsx_element_name(&mut closing.name);
+        }
+        for attr in &mut elem.opening.attrs {
+            if let Some(JSXAttrValue::ExprContainer(JSXExprContainer { expr })) = &mut attr.value {
+                self.visit_expr_mut(expr);
+            }
+        }
+        self.visit_jsx_children(&mut elem.children);
+    }
+
+    fn visit_jsx_element_name(&mut self, name: &mut JSXElementName) {
+        let mut obj = match name {
+            JSXElementName::Ident(ident) => {
+                // Lowercase names are intrinsic elements, e.g. `<div>`, and
+                // don't refer to bindings.
+                if ident.name.starts_with(char::is_uppercase) {
+                    self.resolve(&mut ident.name);
+                }
+                return;
+            }
+            JSXElementName::JSXMemberExpr(member) => &mut member.obj,
+        };
+        loop {
+            match obj {
+                JSXObject::JSXMemberExpr(member) => obj = &mut member.obj,
+                JSXObject::Ident(ident) => {
+                    self.resolve(&mut ident.name);
+                    return;
+                }
+            }
+        }
+    }
+
+    fn visit_jsx_children(&mut self, children: &mut [JSXElementChild]) {
+        for child in children {
+            match child {
+                JSXElementChild::Text(_) => {}
+                JSXElementChild::ExprContainer(JSXExprContainer { expr }) => {
+                    self.visit_expr_mut(expr)
+                }
+                JSXElementChild::SpreadChild(JSXSpreadChild { expr }) => self.visit_expr_mut(expr),
+                JSXElementChild::Element(elem) => self.visit_jsx_element(elem),
+                JSXElementChild::Fragment(frag) => self.visit_jsx_children(&mut frag.children),
+            }
+        }
+    }
+}
+
+impl VisitorMut for Renamer {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match &mut expr.kind {
+            ExprKind::Ident(Ident { name, .. }) => self.resolve(name),
+            ExprKind::Object(Object { properties }) => {
+                for prop in properties {
+                    match prop {
+                        PropOrSpread::Prop(expr::Prop::Shorthand(ident)) => {
+                            let mut name = ident.name.to_owned();
+                            self.resolve(&mut name);
+                            if name != ident.name {
+                                // `{x}` becomes `{x: x$1}`.
+                                let span = ident.span;
+                                *prop = PropOrSpread::Prop(expr::Prop::Property {
+                                    key: ObjectKey::Ident(ident.to_owned()),
+                                    value: Expr {
+                                        kind: ExprKind::Ident(Ident { name, span }),
+                                        span,
+                                        inferred_type: None,
+                                    },
+                                });
+                            }
+                        }
+                        PropOrSpread::Prop(expr::Prop::Property { key, value }) => {
+                            if let ObjectKey::Computed(key) = key {
+                                self.visit_expr_mut(key);
+                            }
+                            self.visit_expr_mut(value);
+                        }
+                        PropOrSpread::Spread(expr) => self.visit_expr_mut(expr),
+                    }
+                }
+            }
+            ExprKind::Function(Function { params, body, .. }) => {
+                // Params can't be redeclared in the function's body either.
+                self.with_scope(|renamer| {
+                    for param in params.iter_mut() {
+                        renamer.visit_pattern_mut(&mut param.pattern);
+                    }
+                    walk_block_or_expr_mut(renamer, body);
+                });
+            }
+            ExprKind::IfElse(IfElse {
+                cond,
+                consequent,
+                alternate,
+            }) => {
+                self.visit_expr_mut(cond);
+                self.with_scope(|renamer| walk_block_mut(renamer, consequent));
+                if let Some(alternate) = alternate {
+                    self.with_scope(|renamer| walk_block_or_expr_mut(renamer, alternate));
+                }
+            }
+            ExprKind::IfLet(IfLet {
+                pattern,
+                expr,
+                consequent,
+                alternate,
+            }) => {
+                self.with_scope(|renamer| {
+                    renamer.visit_expr_mut(expr);
+                    renamer.visit_pattern_mut(pattern);
+                    walk_block_mut(renamer, consequent);
+                });
+                if let Some(alternate) = alternate {
+                    self.with_scope(|renamer| walk_block_or_expr_mut(renamer, alternate));
+                }
+            }
+            ExprKind::Match(Match { expr, arms }) => {
+                self.visit_expr_mut(expr);
+                for arm in arms {
+                    self.with_scope(|renamer| {
+                        renamer.visit_pattern_mut(&mut arm.pattern);
+                        if let Some(guard) = &mut arm.guard {
+                            renamer.visit_expr_mut(guard);
+                        }
+                        walk_block_or_expr_mut(renamer, &mut arm.body);
+                    });
+                }
+            }
+            ExprKind::Try(Try {
+                body,
+                catch,
+                finally,
+            }) => {
+                self.with_scope(|renamer| walk_block_mut(renamer, body));
+                if let Some(catch) = catch {
+                    // The catch param can't be redeclared in the catch block.
+                    self.with_scope(|renamer| {
+                        if let Some(param) = &mut catch.param {
+                            renamer.visit_pattern_mut(param);
+                        }
+                        walk_block_mut(renamer, &mut catch.body);
+                    });
+                }
+                if let Some(finally) = finally {
+                    self.with_scope(|renamer| walk_block_mut(renamer, finally));
+                }
+            }
+            ExprKind::Do(Do { body }) => self.with_scope(|renamer| walk_block_mut(renamer, body)),
+            ExprKind::JSXElement(elem) => self.visit_jsx_element(elem),
+            ExprKind::JSXFragment(frag) => self.visit_jsx_children(&mut frag.children),
+            _ => walk_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        match &mut pattern.kind {
+            PatternKind::Ident(BindingIdent { name, .. }) => self.declare(name),
+            PatternKind::Is(IsPat { ident, .. }) => self.declare(&mut ident.name),
+            PatternKind::Object(ObjectPat { props, .. }) => {
+                for prop in props {
+                    match prop {
+                        ObjectPatProp::Shorthand(ShorthandPatProp { span, ident, init }) => {
+                            if let Some(init) = init {
+                                self.visit_expr_mut(init);
+                            }
+                            let mut name = ident.name.to_owned();
+                            self.declare(&mut name);
+                            if name != ident.name {
+                                // `{x}` becomes `{x: x$1}`.
+                                *prop = ObjectPatProp::KeyValue(KeyValuePatProp {
+                                    span: *span,
+                                    key: Ident {
+                                        name: ident.name.to_owned(),
+                                        span: ident.span,
+                                    },
+                                    value: Box::from(Pattern {
+                                        kind: PatternKind::Ident(BindingIdent {
+                                            name,
+                                            span: ident.span,
+                                            mutable: ident.mutable,
+                                        }),
+                                        span: ident.span,
+                                        inferred_type: None,
+                                    }),
+                                    init: init.take(),
+                                });
+                            }
+                        }
+                        ObjectPatProp::KeyValue(KeyValuePatProp { value, init, .. }) => {
+                            if let Some(init) = init {
+                                self.visit_expr_mut(init);
+                            }
+                            self.visit_pattern_mut(value);
+                        }
+                        ObjectPatProp::Rest(RestPat { arg }) => self.visit_pattern_mut(arg),
+                    }
+                }
+            }
+            _ => walk_pattern_mut(self, pattern),
+        }
+    }
+
+    fn visit_decl_mut(&mut self, decl: &mut Decl) {
+        if let DeclKind::VarDecl(VarDecl { pattern, expr, .. }) = &mut decl.kind {
+            // The initializer can use bindings that the pattern shadows.
+            if let Some(expr) = expr {
+                self.visit_expr_mut(expr);
+            }
+            self.visit_pattern_mut(pattern);
+        }
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        match &mut stmt.kind {
+            StmtKind::For(ForStmt { left, right, body }) => {
+                // `for (const x of x)` throws so `right` is included in the
+                // loop's scope.
+                self.with_scope(|renamer| {
+                    renamer.visit_expr_mut(right);
+                    renamer.visit_pattern_mut(left);
+                    walk_block_mut(renamer, body);
+                });
+            }
+            StmtKind::LetElse(LetElseStmt {
+                pattern,
+                expr,
+                alternate,
+            }) => {
+                self.visit_expr_mut(expr);
+                self.with_scope(|renamer| walk_block_mut(renamer, alternate));
+                self.visit_pattern_mut(pattern);
+            }
+            _ => walk_stmt_mut(self, stmt),
+        }
+    }
+}
@@ -1,13 +1,17 @@
+use escalier_ast::{CompilerOptions, Strictness};
+use escalier_codegen::codegen_runtime_helpers;
 use escalier_codegen::d_ts::codegen_d_ts;
-use escalier_codegen::js::codegen_js;
+use escalier_codegen::doc::{codegen_doc, DocFormat};
+use escalier_codegen::js::{codegen_js, codegen_js_with_options};
+use escalier_codegen::options::{CodegenOptions, JSXRuntime, ModuleFormat, RuntimeHelpers};
 use escalier_hm::checker::Checker;
 use escalier_hm::context::Context;
 use escalier_hm::type_error::TypeError;
 use escalier_parser::parse;
 
 fn compile(input: &str) -> (String, String) {
-    let program = parse(input).unwrap();
-    codegen_js(input, &program)
+    let program = parse(input, &CompilerOptions::default()).unwrap();
+    codegen_js(input, &program, &CompilerOptions::default())
 }
 
 #[test]
@@ -32,6 +36,68 @@ fn unary_minus() {
 ");
 }
 
+#[test]
+fn bigint_and_numeric_separators() -> Result<(), TypeError> {
+    let src = r#"
+    let big = 9_007_199_254_740_993n
+    let million = 1_000_000
+    let sum = big + 1n
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const big = 9007199254740993n;
+    export const million = 1000000;
+    export const sum = big + 1n;
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const big: 9007199254740993n;
+    export declare const million: 1000000;
+    export declare const sum: 9007199254740994n;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn int_and_float_are_erased_to_number() -> Result<(), TypeError> {
+    let src = r#"
+    let add = fn (x: int, y: float) => x + y
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @"export const add = (x, y)=>x + y;
+");
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    let options = CompilerOptions {
+        strictness: Strictness {
+            numeric_refinements: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    checker.infer_script(&mut program, &mut ctx, &options)?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &options)?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const add: (x: number, y: number) => number;
+    "###);
+
+    Ok(())
+}
+
 #[test]
 fn fn_with_block_without_return() {
     let src = r#"
@@ -70,6 +136,71 @@ fn fn_with_block_with_return() {
     "###);
 }
 
+#[test]
+fn number_literal_formats() {
+    let src = r#"
+    let mask = 0xFF_FF
+    let perms = 0o755
+    let flags = 0b1010
+    let small = 1e-3
+    let big = 0xFFn
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const mask = 0xFFFF;
+    export const perms = 0o755;
+    export const flags = 0b1010;
+    export const small = 1e-3;
+    export const big = 255n;
+    "###);
+}
+
+#[test]
+fn exponentiation() {
+    let src = r#"
+    let a = x ** y ** 2
+    let b = (x ** y) ** 2
+    let c = -x ** 2
+    let d = (-x) ** 2
+    let e = 2 ** -x
+    let f = -(x + y)
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const a = x ** y ** 2;
+    export const b = (x ** y) ** 2;
+    export const c = -(x ** 2);
+    export const d = (-x) ** 2;
+    export const e = 2 ** -x;
+    export const f = -(x + y);
+    "###);
+}
+
+#[test]
+fn bitwise_operators() {
+    let src = r#"
+    let flags = (a | b) & ~c
+    let bits = x ^ y >>> 1
+    let shifted = a << (b << c)
+    let grouped = (a >> b) >> c
+    let rem = a % b
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const flags = (a | b) & ~c;
+    export const bits = x ^ y >>> 1;
+    export const shifted = a << (b << c);
+    export const grouped = a >> b >> c;
+    export const rem = a % b;
+    "###);
+}
+
 #[test]
 fn template_literals() {
     let src = r#"
@@ -89,6 +220,25 @@ fn template_literals() {
     "###);
 }
 
+#[test]
+fn raw_and_multiline_strings() {
+    let src = r##"
+    let digits = r"^\d+$"
+    let path = r"C:\Users\"
+    let msg = `a \`quoted\` \${x} with a \\`
+    let lines = "first \
+    second"
+    "##;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const digits = "^\\d+$";
+    export const path = "C:\\Users\\";
+    export const msg = `a \`quoted\` \${x} with a \\`;
+    export const lines = "first     second";
+    "###);
+}
+
 #[test]
 fn tagged_template_literals() {
     let src = r#"
@@ -123,25 +273,66 @@ fn pattern_matching() {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
-    const $temp_1 = count + 1;
-    if ($temp_1 === 0) {
-        $temp_0 = "none";
-    } else if ($temp_1 === 1) {
-        $temp_0 = "one";
-    } else if ($temp_1 === 2) {
-        $temp_0 = "a couple";
-    } else if (n < 5) {
-        const n = $temp_1;
-        console.log(`n = ${n}`);
-        $temp_0 = "a few";
-    } else {
-        const $temp_2 = $temp_1;
-        console.log("fallthrough");
-        $temp_0 = "many";
+    let $match_result_0;
+    const $match_value_0 = count + 1;
+    $match_0: {
+        if ($match_value_0 === 0) {
+            $match_result_0 = "none";
+            break $match_0;
+        }
+        if ($match_value_0 === 1) {
+            $match_result_0 = "one";
+            break $match_0;
+        }
+        if ($match_value_0 === 2) {
+            $match_result_0 = "a couple";
+            break $match_0;
+        }
+        {
+            const n = $match_value_0;
+            if (n < 5) {
+                console.log(`n = ${n}`);
+                $match_result_0 = "a few";
+                break $match_0;
+            }
+        }
+        {
+            const $unused_0 = $match_value_0;
+            console.log("fallthrough");
+            $match_result_0 = "many";
+            break $match_0;
+        }
     }
-    export const result = $temp_0;
+    export const result = $match_result_0;
+    "###);
+}
+
+#[test]
+fn overloaded_operators() -> Result<(), TypeError> {
+    let src = r#"
+    type Vector = {x: number, y: number, fn add(self, other: Vector) -> Vector, fn neg(self) -> Vector}
+    declare let p: Vector
+    declare let q: Vector
+    let sum = p + q
+    let neg = -p
+    let diff = p + -q
+    "#;
+
+    // Overloaded operators are desugared by the checker so codegen has to
+    // happen after type checking.
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    insta::assert_snapshot!(js, @r###"
+    export const sum = p.add(q);
+    export const neg = p.neg();
+    export const diff = p.add(q.neg());
     "###);
+
+    Ok(())
 }
 
 #[test]
@@ -157,25 +348,30 @@ fn pattern_matching_with_disjoint_union() -> Result<(), TypeError> {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    ;
-    ;
-    let $temp_0;
-    const $temp_1 = event;
-    if ($temp_1.type === "mousedown") {
-        const { x, y } = $temp_1;
-        $temp_0 = `mousedown: (${x}, ${y})`;
-    } else if ($temp_1.type === "keydown" && key !== "Escape") {
-        const { key } = $temp_1;
-        $temp_0 = key;
+    let $match_result_0;
+    const $match_value_0 = event;
+    $match_0: {
+        if ($match_value_0.type === "mousedown") {
+            const { x, y } = $match_value_0;
+            $match_result_0 = `mousedown: (${x}, ${y})`;
+            break $match_0;
+        }
+        if ($match_value_0.type === "keydown") {
+            const { key } = $match_value_0;
+            if (key !== "Escape") {
+                $match_result_0 = key;
+                break $match_0;
+            }
+        }
     }
-    export const result = $temp_0;
+    export const result = $match_result_0;
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     declare type Event = {
@@ -187,35 +383,129 @@ fn pattern_matching_with_disjoint_union() -> Result<(), TypeError> {
         key: string;
     };
     export declare const event: Event;
-    export declare const result: string | string;
+    export declare const result: string;
     "###);
 
     Ok(())
 }
 
 #[test]
-// TODO: Have a better error message when there's multiple catch-alls
-#[should_panic = "Catchall must appear last in match"]
-fn pattern_matching_multiple_catchall_panics() {
+fn pattern_matching_arrays_with_rest() {
+    let src = r#"
+    let result = match (array) {
+        [] => 0,
+        [a] => a,
+        [a, ...rest] => a + sum(rest)
+    }
+    "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    let $match_result_0;
+    const $match_value_0 = array;
+    if ($match_value_0.length === 0) {
+        const [] = $match_value_0;
+        $match_result_0 = 0;
+    } else if ($match_value_0.length === 1) {
+        const [a] = $match_value_0;
+        $match_result_0 = a;
+    } else if ($match_value_0.length >= 1) {
+        const [a, ...rest] = $match_value_0;
+        $match_result_0 = a + sum(rest);
+    }
+    export const result = $match_result_0;
+    "###);
+}
+
+#[test]
+fn pattern_matching_nested_rest() {
+    let src = r#"
+    let result = match (event) {
+        {type: "mousedown", pos: [x, ...others], ...rest} => x,
+        {type: "keydown", ...rest} => rest
+    }
+    "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    let $match_result_0;
+    const $match_value_0 = event;
+    if ($match_value_0.type === "mousedown" && $match_value_0.pos.length >= 1) {
+        const { type: $unused_0, pos: [x, ...others], ...rest } = $match_value_0;
+        $match_result_0 = x;
+    } else if ($match_value_0.type === "keydown") {
+        const { type: $unused_1, ...rest } = $match_value_0;
+        $match_result_0 = rest;
+    }
+    export const result = $match_result_0;
+    "###);
+}
+
+#[test]
+fn pattern_matching_skips_arms_after_catchall() {
     let src = r#"
-    let result = match (value) {
-        n => "foo",
-        _ => "bar"
+    let result = match (n) {
+        0 => "zero",
+        _ => "other",
+        1 => "one"
     }
     "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    let $match_result_0;
+    const $match_value_0 = n;
+    if ($match_value_0 === 0) {
+        $match_result_0 = "zero";
+    } else {
+        const $unused_0 = $match_value_0;
+        $match_result_0 = "other";
+    }
+    export const result = $match_result_0;
+    "###);
+}
+
+#[test]
+fn pattern_matching_without_arms() {
+    let src = r#"
+    let result = match (n) {}
+    "#;
+    let (js, _) = compile(src);
 
-    compile(src);
+    insta::assert_snapshot!(js, @r###"
+    let $match_result_0;
+    const $match_value_0 = n;
+    export const result = $match_result_0;
+    "###);
 }
 
 #[test]
-#[should_panic = "No arms in match"]
-fn pattern_matching_no_arms_panics() {
+fn pattern_matching_ranges() {
     let src = r#"
-    let result = match (value) {
+    let size = match (n) {
+        ..0 => "negative",
+        0..10 => "small",
+        10..=99 => "medium",
+        _ => "large"
     }
     "#;
+    let (js, _) = compile(src);
 
-    compile(src);
+    insta::assert_snapshot!(js, @r###"
+    let $match_result_0;
+    const $match_value_0 = n;
+    if ($match_value_0 < 0) {
+        $match_result_0 = "negative";
+    } else if ($match_value_0 >= 0 && $match_value_0 < 10) {
+        $match_result_0 = "small";
+    } else if ($match_value_0 >= 10 && $match_value_0 <= 99) {
+        $match_result_0 = "medium";
+    } else {
+        const $unused_0 = $match_value_0;
+        $match_result_0 = "large";
+    }
+    export const size = $match_result_0;
+    "###);
 }
 
 #[test]
@@ -232,15 +522,15 @@ fn simple_if_else() {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
+    let $if_result_0;
     if (cond) {
         console.log("true");
-        $temp_0 = 5;
+        $if_result_0 = 5;
     } else {
         console.log("false");
-        $temp_0 = 10;
+        $if_result_0 = 10;
     }
-    export const result = $temp_0;
+    export const result = $if_result_0;
     "###);
 }
 
@@ -262,15 +552,15 @@ fn simple_if_else_inside_fn() {
 
     insta::assert_snapshot!(js, @r###"
     export const foo = ()=>{
-        let $temp_0;
+        let $if_result_0;
         if (cond) {
             console.log("true");
-            $temp_0 = 5;
+            $if_result_0 = 5;
         } else {
             console.log("false");
-            $temp_0 = 10;
+            $if_result_0 = 10;
         }
-        const result = $temp_0;
+        const result = $if_result_0;
         return result;
     };
     "###);
@@ -289,18 +579,18 @@ fn simple_if_else_inside_fn_as_expr() {
     "#;
     let (js, _) = compile(src);
 
-    // TODO: all of this code should be inside the body of
-    // the function, not outside it
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
-    if (cond) {
-        console.log("true");
-        $temp_0 = 5;
-    } else {
-        console.log("false");
-        $temp_0 = 10;
-    }
-    export const foo = ()=>$temp_0;
+    export const foo = ()=>{
+        let $if_result_0;
+        if (cond) {
+            console.log("true");
+            $if_result_0 = 5;
+        } else {
+            console.log("false");
+            $if_result_0 = 10;
+        }
+        return $if_result_0;
+    };
     "###);
 }
 
@@ -324,25 +614,25 @@ fn nested_if_else() {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
+    let $if_result_0;
     if (c1) {
-        let $temp_1;
+        let $if_result_1;
         if (c2) {
-            $temp_1 = 5;
+            $if_result_1 = 5;
         } else {
-            $temp_1 = 10;
+            $if_result_1 = 10;
         }
-        $temp_0 = $temp_1;
+        $if_result_0 = $if_result_1;
     } else {
-        let $temp_2;
+        let $if_result_2;
         if (c3) {
-            $temp_2 = "hello";
+            $if_result_2 = "hello";
         } else {
-            $temp_2 = "world";
+            $if_result_2 = "world";
         }
-        $temp_0 = $temp_2;
+        $if_result_0 = $if_result_2;
     }
-    export const result = $temp_0;
+    export const result = $if_result_0;
     "###);
 }
 
@@ -368,9 +658,7 @@ fn multiple_lets_inside_a_function() {
     "###);
 }
 
-// TODO: do we want to support `if-let`?
 #[test]
-#[ignore]
 fn codegen_if_let_with_rename() {
     // TODO: don't allow irrefutable patterns to be used with if-let
     let src = r#"
@@ -381,21 +669,19 @@ fn codegen_if_let_with_rename() {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
-    const $temp_1 = {
+    let $if_let_result_0;
+    const $if_let_value_0 = {
         x: 5,
         y: 10
     };
     {
-        const { x: a, y: b } = $temp_1;
-        $temp_0 = a + b;
-    }export const result = $temp_0;
+        const { x: a, y: b } = $if_let_value_0;
+        $if_let_result_0 = a + b;
+    }export const result = $if_let_result_0;
     "###);
 }
 
-// TODO: do we want to support `if-let`?
 #[test]
-#[ignore]
 fn codegen_if_let_refutable_pattern_nested_obj() {
     let src = r#"
     let action = {type: "moveto", point: {x: 5, y: 10}}
@@ -413,19 +699,17 @@ fn codegen_if_let_refutable_pattern_nested_obj() {
             y: 10
         }
     };
-    let $temp_0;
-    const $temp_1 = action;
-    if ($temp_1.type === "moveto") {
-        const { point: { x, y } } = $temp_1;
-        $temp_0 = x + y;
+    let $if_let_result_0;
+    const $if_let_value_0 = action;
+    if ($if_let_value_0.type === "moveto") {
+        const { point: { x, y } } = $if_let_value_0;
+        $if_let_result_0 = x + y;
     }
-    $temp_0;
+    $if_let_result_0;
     "###);
 }
 
-// TODO: do we want to support `if-let`?
 #[test]
-#[ignore]
 fn codegen_if_let_with_else() {
     let src = r#"
     declare let a: string | number
@@ -440,25 +724,119 @@ fn codegen_if_let_with_else() {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    ;
-    let $temp_0;
-    const $temp_1 = a;
-    if (typeof $temp_1 === "number") {
-        const x = $temp_1;
-        $temp_0 = x + 5;
+    let $if_let_result_0;
+    const $if_let_value_0 = a;
+    if (typeof $if_let_value_0 === "number") {
+        const x = $if_let_value_0;
+        $if_let_result_0 = x + 5;
     } else {
-        let $temp_2;
-        const $temp_3 = a;
-        if (typeof $temp_3 === "string") {
-            const y = $temp_3;
-            $temp_2 = y;
+        let $if_let_result_1;
+        const $if_let_value_1 = a;
+        if (typeof $if_let_value_1 === "string") {
+            const y = $if_let_value_1;
+            $if_let_result_1 = y;
         } else {
-            $temp_2 = true;
+            $if_let_result_1 = true;
+        }
+        $if_let_result_0 = $if_let_result_1;
+    }
+    export const result = $if_let_result_0;
+    "###);
+}
+
+#[test]
+fn codegen_let_else() {
+    let src = r#"
+    let head = fn (items) {
+        let [first, ...rest] = items else {
+            return 0
         }
-        $temp_0 = $temp_2;
+        return first
     }
-    export const result = $temp_0;
+    "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const head = (items)=>{
+        const $let_else_value_0 = items;
+        if (!($let_else_value_0.length >= 1)) {
+            return 0;
+        }
+        const [first, ...rest] = $let_else_value_0;
+        return first;
+    };
+    "###);
+}
+
+#[test]
+fn codegen_refutable_func_params() -> Result<(), TypeError> {
+    let src = r#"
+    type Event = {type: "click", x: number} | {type: "key", key: string}
+    let handle = fn ({type: "click", x}: Event) => x
+    "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const handle = ($param_0)=>{
+        if (!($param_0.type === "click")) {
+            throw new TypeError("Argument doesn't match the param's pattern");
+        }
+        const { x } = $param_0;
+        return x;
+    };
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker
+        .infer_script(&mut program, &mut ctx, &CompilerOptions::default())
+        .unwrap();
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    declare type Event = {
+        type: "click";
+        x: number;
+    } | {
+        type: "key";
+        key: string;
+    };
+    export declare const handle: ({ type, x }: {
+        type: "click";
+        x: number;
+    }) => number;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn codegen_opaque_types_are_branded() -> Result<(), TypeError> {
+    let src = r#"
+    export opaque type UserId = string
+    export let toUserId = fn (id: string) -> UserId => id
+    "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const toUserId = (id)=>id;
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare type UserId = string & {
+        readonly __brand: "UserId";
+    };
+    export declare const toUserId: (id: string) => UserId;
     "###);
+
+    Ok(())
 }
 
 #[test]
@@ -471,12 +849,12 @@ fn codegen_block_with_multiple_non_let_lines() {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const x = 5;
         x + 0;
-        $temp_0 = x;
-    }export const result = $temp_0;
+        $do_result_0 = x;
+    }export const result = $do_result_0;
     "###);
 }
 
@@ -490,11 +868,13 @@ fn destructuring_function_object_params() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const foo = ({ x, y: b })=>x + b;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx).unwrap();
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker
+        .infer_script(&mut program, &mut ctx, &CompilerOptions::default())
+        .unwrap();
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const foo: ({ x, y: b }: {
@@ -516,11 +896,11 @@ fn destructuring_function_array_params() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const foo = ([a, b])=>a + b;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const foo: ([a, b]: readonly [number, number]) => number;
 ");
@@ -538,11 +918,11 @@ fn function_with_rest_param() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const foo = (x, ...y)=>x;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const foo: (x: number, ...y: readonly number[]) => number;
 ");
@@ -560,11 +940,11 @@ fn function_with_optional_param() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const foo = (x, y)=>x;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const foo: (x: number, y?: number) => number;
 ");
@@ -585,11 +965,11 @@ fn function_with_optional_param_and_rest_param() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const foo = (x, ...y)=>x;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const foo: (x?: number, ...y: Array<number>) => number;
 ");
@@ -607,11 +987,11 @@ fn generic_function() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const fst = (a, b)=>a;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: the return type should be `T` not `unknown`
     insta::assert_snapshot!(result, @"export declare const fst: <T>(a: T, b: T) => T;
@@ -630,11 +1010,11 @@ fn constrained_generic_function() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @"export const fst = (a, b)=>a;
 ");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: The type bound on `T` should be `number | string`, not `number | number`
     // TODO: The return type should be `T`, not `number | string`
@@ -658,12 +1038,11 @@ fn variable_declaration_with_destructuring() -> Result<(), TypeError> {
     ];
     "###);
 
-    // TODO: Support destructuring in top-level decls
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     export declare const x: 5;
@@ -673,6 +1052,43 @@ fn variable_declaration_with_destructuring() -> Result<(), TypeError> {
     Ok(())
 }
 
+#[test]
+fn variable_declaration_with_object_destructuring() -> Result<(), TypeError> {
+    let src = r#"
+    declare let obj: {a: number, b?: string, c: {d: boolean, e: [number, string, boolean]}}
+    let {a, b = "hi", c: {d, e: [f, ...g]}} = obj
+    "#;
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const { a, b = "hi", c: { d, e: [f, ...g] } } = obj;
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const a: number;
+    export declare const b: string;
+    export declare const d: boolean;
+    export declare const f: number;
+    export declare const g: readonly [string, boolean];
+    export declare const obj: {
+        a: number;
+        b?: string;
+        c: {
+            d: boolean;
+            e: readonly [number, string, boolean];
+        };
+    };
+    "###);
+
+    Ok(())
+}
+
 #[test]
 fn computed_property() {
     let src = r#"
@@ -724,21 +1140,20 @@ fn mutable_array() -> Result<(), TypeError> {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    export const arr = [
+    export let arr = [
         1,
         2,
         3
     ];
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
-    insta::assert_snapshot!(result, @"export declare const arr: readonly number[];
-");
+    insta::assert_snapshot!(result, @"export declare let arr: number[];");
 
     Ok(())
 }
@@ -749,11 +1164,11 @@ fn mutable_obj() -> Result<(), TypeError> {
     type Point = {x: number, y: number}
     "#;
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     // This should be:
     insta::assert_snapshot!(result, @r###"
@@ -778,11 +1193,11 @@ fn mutable_indexer() -> Result<(), TypeError> {
     type Dict = {[P]: string for P in string}
     "#;
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
     declare type Dict = {
@@ -843,7 +1258,7 @@ fn for_loop() -> Result<(), TypeError> {
 
     let (js, _) = compile(src);
     insta::assert_snapshot!(js, @r###"
-    export const sum = 0;
+    export let sum = 0;
     for (const num of [
         1,
         2,
@@ -853,14 +1268,13 @@ fn for_loop() -> Result<(), TypeError> {
     }
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
-    insta::assert_snapshot!(result, @r###"export declare const sum: number;
-    "###);
+    insta::assert_snapshot!(result, @"export declare let sum: number;");
 
     Ok(())
 }
@@ -880,7 +1294,7 @@ fn for_loop_inside_fn() -> Result<(), TypeError> {
     let (js, _) = compile(src);
     insta::assert_snapshot!(js, @r###"
     export const sum = (arr)=>{
-        const result = 0;
+        let result = 0;
         for (const num of arr){
             result = result + num;
         }
@@ -888,11 +1302,11 @@ fn for_loop_inside_fn() -> Result<(), TypeError> {
     };
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const sum: (arr: readonly number[]) => number;
 ");
@@ -912,21 +1326,21 @@ fn type_decl_inside_block() -> Result<(), TypeError> {
 
     let (js, _) = compile(src);
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const p = {
             x: 5,
             y: 10
         };
-        $temp_0 = p.x + p.y;
-    }export const result = $temp_0;
+        $do_result_0 = p.x + p.y;
+    }export const result = $do_result_0;
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const result: number;
 ");
@@ -946,21 +1360,21 @@ fn type_decl_inside_block_with_escape() -> Result<(), TypeError> {
 
     let (js, _) = compile(src);
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
+    let $do_result_0;
     {
         const p = {
             x: 5,
             y: 10
         };
-        $temp_0 = p;
-    }export const result = $temp_0;
+        $do_result_0 = p;
+    }export const result = $do_result_0;
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: How do we ensure that types defined within a block can't escape?
     insta::assert_snapshot!(result, @"export declare const result: Point;
@@ -1002,11 +1416,11 @@ fn class_inside_function() -> Result<(), TypeError> {
     };
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @"export declare const foo: () => Point;
 ");
@@ -1051,29 +1465,28 @@ fn multiple_returns_stress_test() -> Result<(), TypeError> {
     insta::assert_snapshot!(js, @r###"
     export const foo = (cond)=>{
         const bar = ()=>{
-            let $temp_0;
+            let $if_result_0;
             if (cond) {
                 return 5;
             }
-            $temp_0;
+            $if_result_0;
         };
-        let $temp_1;
+        let $if_result_0;
         if (cond) {
             return bar();
         }
-        $temp_1;
+        $if_result_0;
         return 10;
     };
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
-    // TODO: the return value should be `5 | 10 | undefined`
-    insta::assert_snapshot!(result, @"export declare const foo: (cond: boolean) => undefined | 10;
+    insta::assert_snapshot!(result, @"export declare const foo: (cond: boolean) => 5 | undefined | 10;
 ");
 
     Ok(())
@@ -1088,17 +1501,13 @@ fn partial_type() -> Result<(), TypeError> {
     "#;
 
     let (js, _) = compile(src);
-    insta::assert_snapshot!(js, @r###"
-    ;
-    ;
-    ;
-    "###);
+    insta::assert_snapshot!(js, @"");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: How do we ensure that types defined within a block can't escape?
     insta::assert_snapshot!(result, @r###"
@@ -1131,16 +1540,13 @@ fn mapped_type_with_additional_props() -> Result<(), TypeError> {
     "#;
 
     let (js, _) = compile(src);
-    insta::assert_snapshot!(js, @r###"
-    ;
-    ;
-    "###);
+    insta::assert_snapshot!(js, @"");
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     // TODO: How do we ensure that types defined within a block can't escape?
     insta::assert_snapshot!(result, @r###"
@@ -1179,55 +1585,1428 @@ fn compile_fib() -> Result<(), TypeError> {
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    let $temp_0;
-    if (n === 0) {
-        $temp_0 = 0;
-    } else if (n === 1) {
-        $temp_0 = 1;
-    } else {
-        $temp_0 = fib(n - 1) + fib(n - 2);
-    }
-    export const fib = (n)=>$temp_0;
+    // only self-recursive functions are supported, but support for
+    // mutual recursion will be added in the future
+    export const fib = (n)=>{
+        let $if_result_0;
+        if (n === 0) {
+            $if_result_0 = 0;
+        } else if (n === 1) {
+            $if_result_0 = 1;
+        } else {
+            $if_result_0 = fib(n - 1) + fib(n - 2);
+        }
+        return $if_result_0;
+    };
     "###);
 
-    let mut program = parse(src).unwrap();
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
 
     insta::assert_snapshot!(result, @r###"
-    export declare const fib: (n: number) => 0 | 1 | number;
+    export declare const fib: (n: number) => number;
     "###);
 
     Ok(())
 }
 
-// TODO: infer JSX
 #[test]
-#[ignore]
-fn compile_jsx() -> Result<(), TypeError> {
+fn preserve_comments() {
     let src = r#"
-    let button = <Button count={5} foo="bar" />
+    // Copyright (c) 2023 Escalier
+    type Point = {x: number, y: number}
+    // eslint-disable-next-line no-console
+    let origin: Point = {x: 0, y: 0} // the center
+    let make_point = fn (x: number, y: number) {
+        // returns a point
+        return {x, y}
+    }
     "#;
 
     let (js, _) = compile(src);
 
     insta::assert_snapshot!(js, @r###"
-    import { jsx as _jsx } from "react/jsx-runtime";
-    export const button = _jsx(Button, {
-        count: 5,
-        foo: "bar"
-    });
+    // Copyright (c) 2023 Escalier
+    // eslint-disable-next-line no-console
+    export const origin = {
+        x: 0,
+        y: 0
+    }; // the center
+    export const make_point = (x, y)=>{
+        // returns a point
+        return {
+            x,
+            y
+        };
+    };
     "###);
+}
 
-    let mut program = parse(src).unwrap();
+#[test]
+fn compile_jsx() -> Result<(), TypeError> {
+    let src = r#"
+    type JSXElement = {}
+    type Props = {count: number, foo: string}
+    declare let Button: fn (props: Props) -> JSXElement
+    let button = <Button count={5} foo="bar" />
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    import { jsx as _jsx } from "react/jsx-runtime";
+    export const button = _jsx(Button, {
+        count: 5,
+        foo: "bar"
+    });
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    declare type JSXElement = {
+    };
+    declare type Props = {
+        count: number;
+        foo: string;
+    };
+    declare type ReadonlyProps = {
+        readonly count: number;
+        readonly foo: string;
+    };
+    export declare const Button: (props: ReadonlyProps) => JSXElement;
+    export declare const button: JSXElement;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn compile_jsx_fragment_and_member_expr() {
+    let src = r#"
+    let list = <><UI.Item>one</UI.Item><UI.Item>{two}</UI.Item></>
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    import { jsx as _jsx, jsxs as _jsxs, Fragment as _Fragment } from "react/jsx-runtime";
+    export const list = _jsxs(_Fragment, {
+        children: [
+            _jsx(UI.Item, {
+                children: "one"
+            }),
+            _jsx(UI.Item, {
+                children: two
+            })
+        ]
+    });
+    "###);
+}
+
+fn compile_with_jsx_runtime(input: &str, jsx_runtime: JSXRuntime) -> String {
+    let options = CompilerOptions {
+        jsx: Some(jsx_runtime),
+        ..Default::default()
+    };
+    let program = parse(input, &options).unwrap();
+    let (js, _) = codegen_js(input, &program, &options);
+    js
+}
+
+#[test]
+fn compile_jsx_classic_runtime() {
+    let src = r#"
+    let elem = <div id="hello">Hello, {name}</div>
+    let list = <><li>one</li><li>two</li></>
+    "#;
+
+    let js = compile_with_jsx_runtime(src, JSXRuntime::classic());
+
+    insta::assert_snapshot!(js, @r###"
+    export const elem = React.createElement("div", {
+        id: "hello"
+    }, "Hello, ", name);
+    export const list = React.createElement(React.Fragment, null, React.createElement("li", null, "one"), React.createElement("li", null, "two"));
+    "###);
+}
+
+#[test]
+fn compile_jsx_custom_factory() {
+    let src = r#"
+    let elem = <div id="hello">Hello</div>
+    let list = <><li>one</li></>
+    "#;
+
+    let js = compile_with_jsx_runtime(
+        src,
+        JSXRuntime::Classic {
+            pragma: "h".to_string(),
+            pragma_frag: "Fragment".to_string(),
+        },
+    );
+
+    insta::assert_snapshot!(js, @r###"
+    export const elem = h("div", {
+        id: "hello"
+    }, "Hello");
+    export const list = h(Fragment, null, h("li", null, "one"));
+    "###);
+}
+
+#[test]
+fn compile_jsx_automatic_runtime_with_import_source() {
+    let src = r#"
+    let elem = <div id="hello">Hello</div>
+    "#;
+
+    let js = compile_with_jsx_runtime(
+        src,
+        JSXRuntime::Automatic {
+            import_source: "preact".to_string(),
+        },
+    );
+
+    insta::assert_snapshot!(js, @r###"
+    import { jsx as _jsx } from "preact/jsx-runtime";
+    export const elem = _jsx("div", {
+        id: "hello",
+        children: "Hello"
+    });
+    "###);
+}
+
+#[test]
+fn compile_jsx_with_pragma_comments() {
+    let src = r#"
+    // @jsx h
+    // @jsxFrag Fragment
+    let list = <><li>one</li></>
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    // @jsx h
+    // @jsxFrag Fragment
+    export const list = h(Fragment, null, h("li", null, "one"));
+    "###);
+
+    let src = r#"
+    // @jsxImportSource preact
+    let elem = <div>Hello</div>
+    "#;
+
+    let js = compile_with_jsx_runtime(src, JSXRuntime::classic());
+
+    insta::assert_snapshot!(js, @r###"
+    import { jsx as _jsx } from "preact/jsx-runtime";
+    // @jsxImportSource preact
+    export const elem = _jsx("div", {
+        children: "Hello"
+    });
+    "###);
+}
+
+#[test]
+fn jsx_runtime_with_pragmas() {
+    let pragmas = |src: &str| parse(src, &CompilerOptions::default()).unwrap().pragmas;
+
+    let runtime = JSXRuntime::default().with_pragmas(&pragmas("// @jsxRuntime classic"));
+    assert_eq!(runtime, JSXRuntime::classic());
+
+    let runtime = JSXRuntime::classic().with_pragmas(&pragmas("// @jsxRuntime automatic"));
+    assert_eq!(runtime, JSXRuntime::default());
+
+    let runtime = JSXRuntime::default().with_pragmas(&pragmas("// @jsxRuntime automatic @jsx h"));
+    assert_eq!(runtime, JSXRuntime::default());
+
+    let runtime = JSXRuntime::default().with_pragmas(&pragmas("let x = 5"));
+    assert_eq!(runtime, JSXRuntime::default());
+
+    // Pragmas after the first statement are ignored.
+    let runtime = JSXRuntime::default().with_pragmas(&pragmas("let x = 5\n// @jsx h"));
+    assert_eq!(runtime, JSXRuntime::default());
+}
+
+#[test]
+fn codegen_options_with_pragmas() {
+    let src = r#"// @freeze-immutable-values off
+    let point = {x: 5, y: 10}
+    "#;
+
+    let program = parse(src, &CompilerOptions::default()).unwrap();
+    let codegen_options = CodegenOptions {
+        freeze_immutable_values: true,
+        ..Default::default()
+    };
+    let (js, _) =
+        codegen_js_with_options(src, &program, &CompilerOptions::default(), &codegen_options);
+
+    insta::assert_snapshot!(js, @r###"
+    // @freeze-immutable-values off
+    export const point = {
+        x: 5,
+        y: 10
+    };
+    "###);
+}
+
+#[test]
+fn freeze_immutable_values() {
+    let src = r#"
+    let point = {x: 5, y: 10}
+    let line = {start: {x: 0, y: 0}, end: point, tags: ["a", "b"]}
+    let mut array = [1, 2, 3]
+    let [a, b] = [{x: 1}, {y: 2}]
+    let foo = fn () {
+        let tuple = [1, [2, 3]]
+        let mut obj = {x: 0}
+        return tuple
+    }
+    "#;
+
+    let program = parse(src, &CompilerOptions::default()).unwrap();
+    let codegen_options = CodegenOptions {
+        freeze_immutable_values: true,
+        ..Default::default()
+    };
+    let (js, _) =
+        codegen_js_with_options(src, &program, &CompilerOptions::default(), &codegen_options);
+
+    insta::assert_snapshot!(js, @r###"
+    export const point = Object.freeze({
+        x: 5,
+        y: 10
+    });
+    export const line = Object.freeze({
+        start: Object.freeze({
+            x: 0,
+            y: 0
+        }),
+        end: point,
+        tags: Object.freeze([
+            "a",
+            "b"
+        ])
+    });
+    export let array = [
+        1,
+        2,
+        3
+    ];
+    export const [a, b] = Object.freeze([
+        Object.freeze({
+            x: 1
+        }),
+        Object.freeze({
+            y: 2
+        })
+    ]);
+    export const foo = ()=>{
+        const tuple = Object.freeze([
+            1,
+            Object.freeze([
+                2,
+                3
+            ])
+        ]);
+        let obj = {
+            x: 0
+        };
+        return tuple;
+    };
+    "###);
+}
+
+#[test]
+fn optimize_output() {
+    let src = r#"
+    type Point = {x: number, y: number}
+    declare let log: fn (msg: string) -> undefined
+    let foo = fn (c1, c2) {
+        let message = "hello"
+        let result = if (c1) {
+            if (c2) {
+                5
+            } else {
+                10
+            }
+        } else {
+            15
+        }
+        log(message)
+        return result
+    }
+    "#;
+
+    let program = parse(src, &CompilerOptions::default()).unwrap();
+    let codegen_options = CodegenOptions {
+        optimize: true,
+        ..Default::default()
+    };
+    let (js, _) =
+        codegen_js_with_options(src, &program, &CompilerOptions::default(), &codegen_options);
+
+    insta::assert_snapshot!(js, @r###"
+    export const foo = (c1, c2)=>{
+        let $if_result_0;
+        if (c1) {
+            if (c2) {
+                $if_result_0 = 5;
+            } else {
+                $if_result_0 = 10;
+            }
+        } else {
+            $if_result_0 = 15;
+        }
+        const result = $if_result_0;
+        log("hello");
+        return result;
+    };
+    "###);
+}
+
+#[test]
+fn commonjs_module_format() {
+    let src = r#"
+    let add = fn (a, b) => a + b
+    let {x, y} = {x: 1, y: 2}
+    let elem = <div>Hello</div>
+    "#;
+
+    let options = CompilerOptions {
+        module_format: ModuleFormat::CommonJs,
+        ..Default::default()
+    };
+    let program = parse(src, &options).unwrap();
+    let (js, _) = codegen_js(src, &program, &options);
+
+    insta::assert_snapshot!(js, @r###"
+    const { jsx: _jsx } = require("react/jsx-runtime");
+    const add = (a, b)=>a + b;
+    const { x, y } = {
+        x: 1,
+        y: 2
+    };
+    const elem = _jsx("div", {
+        children: "Hello"
+    });
+    module.exports = {
+        add,
+        x,
+        y,
+        elem
+    };
+    "###);
+}
+
+#[test]
+fn iife_module_format() {
+    let src = r#"
+    let add = fn (a, b) => a + b
+    let sum = add(5, 10)
+    "#;
+
+    let options = CompilerOptions {
+        module_format: ModuleFormat::Iife,
+        ..Default::default()
+    };
+    let program = parse(src, &options).unwrap();
+    let (js, _) = codegen_js(src, &program, &options);
+
+    insta::assert_snapshot!(js, @r###"
+    (()=>{
+        const add = (a, b)=>a + b;
+        const sum = add(5, 10);
+    })();
+    "###);
+}
+
+#[test]
+fn iife_module_format_with_imports() -> Result<(), TypeError> {
+    let mut checker = Checker::default();
+    let ctx = Context::default();
+
+    let math_src = r#"
+    export let add = fn (a: number, b: number) -> number => a + b
+    "#;
+    let mut math = parse(math_src, &CompilerOptions::default()).unwrap();
+    let mut math_ctx = ctx.clone();
+    checker.infer_script(&mut math, &mut math_ctx, &CompilerOptions::default())?;
+    let exports = checker.get_exports(&math, &math_ctx)?;
+    checker.modules.insert("./math".to_string(), exports);
+
+    let src = r#"
+    import {add} from "./math"
+    type JSXElement = {}
+    let sum = add(5, 10)
+    let elem = <div>{sum}</div>
+    "#;
+
+    let options = CompilerOptions {
+        module_format: ModuleFormat::Iife,
+        ..Default::default()
+    };
+    let mut program = parse(src, &options).unwrap();
+    let mut ctx = ctx.clone();
+    checker.infer_script(&mut program, &mut ctx, &options)?;
+
+    insta::assert_snapshot!(checker.current_report, @r###"
+    ESC_1024 - Imports aren't supported by the IIFE module format:
+
+    ESC_1024 - JSX requires the classic runtime when using the IIFE module format:
+    "###);
+
+    // The imports are dropped since they've been reported.
+    let (js, _) = codegen_js(src, &program, &options);
+
+    insta::assert_snapshot!(js, @r###"
+    (()=>{
+        const sum = add(5, 10);
+        const elem = _jsx("div", {
+            children: sum
+        });
+    })();
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn iife_module_format_d_ts() -> Result<(), TypeError> {
+    let src = r#"
+    type Point = {x: number, y: number}
+    let origin: Point = {x: 0, y: 0}
+    declare global {
+        let VERSION: string
+    }
+    "#;
+
+    let options = CompilerOptions {
+        module_format: ModuleFormat::Iife,
+        ..Default::default()
+    };
+    let mut program = parse(src, &options).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &options)?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &options)?;
+
+    insta::assert_snapshot!(result, @r###"
+    declare global {
+        var VERSION: string;
+    }
+    export { };
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn explicit_exports() -> Result<(), TypeError> {
+    let src = r#"
+    type Point = {x: number, y: number}
+    export type Scale = number
+    let offset = 5
+    export let scale = fn (x: number, s: Scale) -> number => x * s + offset
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    const offset = 5;
+    export const scale = (x, s)=>x * s + offset;
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    declare type Point = {
+        x: number;
+        y: number;
+    };
+    declare type ReadonlyPoint = {
+        readonly x: number;
+        readonly y: number;
+    };
+    export declare type Scale = number;
+    export declare const scale: (x: number, s: Scale) => number;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn inline_runtime_helpers() {
+    let src = r#"
+    let fail = fn (msg: string) => throw msg
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    function $throw(error) {
+        throw error;
+    }
+    export const fail = (msg)=>$throw(msg);
+    "###);
+}
+
+#[test]
+fn imported_runtime_helpers() {
+    let src = r#"
+    let fail = fn (msg: string) => throw msg
+    "#;
+
+    let program = parse(src, &CompilerOptions::default()).unwrap();
+    let codegen_options = CodegenOptions {
+        runtime_helpers: RuntimeHelpers::Import {
+            module: "escalier_runtime".to_string(),
+        },
+        ..Default::default()
+    };
+    let (js, _) =
+        codegen_js_with_options(src, &program, &CompilerOptions::default(), &codegen_options);
+
+    insta::assert_snapshot!(js, @r###"
+    import { $throw } from "escalier_runtime";
+    export const fail = (msg)=>$throw(msg);
+    "###);
+
+    insta::assert_snapshot!(codegen_runtime_helpers(), @r###"
+    export function $throw(error) {
+        throw error;
+    }
+    export function $absurd(value) {
+        throw new TypeError("Unexpected value");
+    }
+    "###);
+}
+
+#[test]
+fn absurd_uses_runtime_helper() {
+    let src = r#"
+    let check = fn (value: never) => absurd(value)
+    let apply = fn (absurd: fn (x: number) -> number) => absurd(5)
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    function $absurd(value) {
+        throw new TypeError("Unexpected value");
+    }
+    export const check = (value)=>$absurd(value);
+    export const apply = (absurd)=>absurd(5);
+    "###);
+}
+
+#[test]
+fn user_defined_absurd_is_not_replaced() -> Result<(), TypeError> {
+    let src = r#"
+    let absurd = fn (x: number) => x + 1
+    let two = absurd(1)
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.load_prelude(&mut ctx)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    assert!(checker.current_report.diagnostics.is_empty());
+
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    insta::assert_snapshot!(js, @r###"
+    export const absurd = (x)=>x + 1;
+    export const two = absurd(1);
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn unused_runtime_helpers_are_not_included() {
+    let src = r#"
+    let add = fn (a: number, b: number) => a + b
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const add = (a, b)=>a + b;
+    "###);
+}
+
+#[test]
+fn methods_of_object_literals_bind_self() -> Result<(), TypeError> {
+    let src = r#"
+    declare let start: number
+    let counter = {
+        count: start,
+        inc: fn (mut self) -> Self {
+            self.count = self.count + 1
+            return self
+        },
+        value: fn (self) -> number => self.count,
+    }
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    insta::assert_snapshot!(js, @r###"
+    export const counter = {
+        count: start,
+        inc: function() {
+            const self = this;
+            self.count = self.count + 1;
+            return self;
+        },
+        value: function() {
+            const self = this;
+            return self.count;
+        }
+    };
+    "###);
+
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const counter: {
+        count: number;
+        inc: (this: this) => this;
+        value: (this: this) => number;
+    };
+    export declare const start: number;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn doc_comments() -> Result<(), TypeError> {
+    let src = r#"
+    /// A point in 2D space.
+    ///
+    /// Both coordinates are in pixels.
+    type Point = {x: number, y: number}
+    let origin: Point = {x: 0, y: 0}
+    /// Adds two numbers.
+    let add = fn (a: number, b: number) => a + b
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    /**
+     * A point in 2D space.
+     *
+     * Both coordinates are in pixels.
+     */ declare type Point = {
+        x: number;
+        y: number;
+    };
+    declare type ReadonlyPoint = {
+        readonly x: number;
+        readonly y: number;
+    };
+    /**
+     * Adds two numbers.
+     */ export declare const add: (a: number, b: number) => number;
+    export declare const origin: ReadonlyPoint;
+    "###);
+
+    let result = codegen_doc(&program, &ctx, &checker, DocFormat::Markdown)?;
+
+    insta::assert_snapshot!(result, @r###"
+    ## `Point`
+
+    ```escalier
+    type Point = {
+        x: number,
+        y: number,
+    }
+    ```
+
+    A point in 2D space.
+
+    Both coordinates are in pixels.
+
+    ## `origin`
+
+    ```escalier
+    let origin: Point
+    ```
+
+    ## `add`
+
+    ```escalier
+    let add: (a: number, b: number) -> number
+    ```
+
+    Adds two numbers.
+    "###);
+
+    let result = codegen_doc(&program, &ctx, &checker, DocFormat::Json)?;
+
+    insta::assert_snapshot!(result, @r###"
+    [
+      {
+        "name": "Point",
+        "kind": "type",
+        "signature": "type Point = {\n    x: number,\n    y: number,\n}",
+        "doc": "A point in 2D space.\n\nBoth coordinates are in pixels."
+      },
+      {
+        "name": "origin",
+        "kind": "value",
+        "signature": "let origin: Point",
+        "doc": null
+      },
+      {
+        "name": "add",
+        "kind": "value",
+        "signature": "let add: (a: number, b: number) -> number",
+        "doc": "Adds two numbers."
+      }
+    ]
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn doc_comments_are_emitted_as_jsdoc() -> Result<(), TypeError> {
+    let src = r#"
+    /// A point in 2D space.
+    type Point = {x: number, y: number}
+    // math helpers
+    /// Adds two numbers.
+    ///
+    /// @param a the first number
+    /// @param b the second number
+    /// @deprecated use `sum` instead
+    let add = fn (a: number, b: number) => a + b
+    //// not a doc comment
+    let origin: Point = {x: 0, y: 0}
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    // math helpers
+    /**
+     * Adds two numbers.
+     *
+     * @param a the first number
+     * @param b the second number
+     * @deprecated use `sum` instead
+     */ export const add = (a, b)=>a + b;
+    //// not a doc comment
+    export const origin = {
+        x: 0,
+        y: 0
+    };
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    /**
+     * A point in 2D space.
+     */ declare type Point = {
+        x: number;
+        y: number;
+    };
+    declare type ReadonlyPoint = {
+        readonly x: number;
+        readonly y: number;
+    };
+    /**
+     * Adds two numbers.
+     *
+     * @param a the first number
+     * @param b the second number
+     * @deprecated use `sum` instead
+     */ export declare const add: (a: number, b: number) => number;
+    export declare const origin: ReadonlyPoint;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn deprecated_decls_have_deprecated_tag() -> Result<(), TypeError> {
+    let src = r#"
+    /// Adds two numbers.
+    @deprecated("use `sum` instead")
+    let add = fn (a: number, b: number) => a + b
+    @deprecated
+    type Point = [number, number]
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    /**
+     * Adds two numbers.
+     * @deprecated use `sum` instead
+     */ export const add = (a, b)=>a + b;
+    "###);
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    /**
+     * @deprecated
+     */ declare type Point = readonly [number, number];
+    /**
+     * Adds two numbers.
+     * @deprecated use `sum` instead
+     */ export declare const add: (a: number, b: number) => number;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn cfg_gated_decls_are_omitted() -> Result<(), TypeError> {
+    let src = r#"
+    @cfg(target = "browser")
+    let platform = "browser"
+    @cfg(target = "node")
+    let platform = "node"
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    checker.cfg.insert("target".to_string(), "node".to_string());
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    insta::assert_snapshot!(js, @r###"
+    export const platform = "node";
+    "###);
+
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const platform: "node";
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn declare_global_d_ts() -> Result<(), TypeError> {
+    let src = r#"
+    declare global {
+        type Env = {mode: string}
+        let env: Env
+    }
+    let mode = env.mode
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    insta::assert_snapshot!(js, @r###"
+    export const mode = env.mode;
+    "###);
+
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const mode: string;
+    declare global {
+        type Env = {
+            mode: string;
+        };
+        var env: Env;
+    }
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn declare_ambient_decls_d_ts() -> Result<(), TypeError> {
+    let src = r#"
+    declare fn parseNumber(value: string, radix?: number) -> number
+    declare class Counter {
+        count: number
+        fn increment(mut self) -> undefined
+    }
+    declare module I18n {
+        type Locale = string
+        let defaultLocale: Locale
+    }
+    let n = parseNumber("5")
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    insta::assert_snapshot!(js, @r###"
+    export const n = parseNumber("5");
+    "###);
+
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const n: number;
+    export declare function parseNumber(value: string, radix?: number): number;
+    export declare class Counter {
+        constructor();
+        count: number;
+        increment(): undefined;
+    }
+    export declare type ReadonlyCounter = {
+        readonly count: number;
+    };
+    export declare namespace I18n {
+        export type Locale = string;
+        export const defaultLocale: I18n.Locale;
+    }
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn labeled_tuple_types_d_ts() -> Result<(), TypeError> {
+    let src = r#"
+    let scale = fn (p: [x: number, y: number], s: number) -> number => s
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut checker = Checker::default();
+    let mut ctx = Context::default();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare const scale: (p: readonly [x: number, y: number], s: number) => number;
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn readonly_arrays_d_ts() -> Result<(), TypeError> {
+    let src = r#"
+    let mut values: readonly number[] = [1, 2, 3]
+    let mut counts: number[] = [1, 2, 3]
+    "#;
+
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
     let mut checker = Checker::default();
     let mut ctx = Context::default();
-    checker.infer_script(&mut program, &mut ctx)?;
-    let result = codegen_d_ts(&program, &ctx, &checker)?;
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    export declare let counts: number[];
+    export declare let values: readonly number[];
+    "###);
+
+    Ok(())
+}
+
+#[test]
+fn in_operator() {
+    let src = r#"
+    let has_foo = "foo" in dict
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const has_foo = "foo" in dict;
+    "###);
+}
+
+#[test]
+fn equality_is_strict() {
+    let src = r#"
+    let eq = a == b
+    let neq = a != b
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const eq = a === b;
+    export const neq = a !== b;
+    "###);
+}
+
+#[test]
+fn logical_operators() {
+    let src = r#"
+    let a = x && y || z
+    let b = x && (y || z)
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const a = x && y || z;
+    export const b = x && (y || z);
+    "###);
+}
+
+#[test]
+fn logical_operators_short_circuit_hoisted_stmts() {
+    let src = r#"
+    let a = ready && if (cond) { 5 } else { 10 }
+    let b = fallback || if (cond) { 5 } else { 10 }
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    let $logical_result_0;
+    $logical_result_0 = ready;
+    if ($logical_result_0) {
+        let $if_result_0;
+        if (cond) {
+            $if_result_0 = 5;
+        } else {
+            $if_result_0 = 10;
+        }
+        $logical_result_0 = $if_result_0;
+    }
+    export const a = $logical_result_0;
+    let $logical_result_1;
+    $logical_result_1 = fallback;
+    if (!($logical_result_1)) {
+        let $if_result_1;
+        if (cond) {
+            $if_result_1 = 5;
+        } else {
+            $if_result_1 = 10;
+        }
+        $logical_result_1 = $if_result_1;
+    }
+    export const b = $logical_result_1;
+    "###);
+}
+
+#[test]
+fn decls_without_initializers() {
+    let src = r#"
+    let message: string
+    message = "hello"
+    let greet = fn () {
+        let name: string
+        name = "world"
+        return name
+    }
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export let message;
+    message = "hello";
+    export const greet = ()=>{
+        let name;
+        name = "world";
+        return name;
+    };
+    "###);
+}
+
+#[test]
+fn shadowed_bindings_are_renamed() {
+    let src = r#"
+    let input = "5"
+    let parse = fn (s: string) => s
+    let f = fn (input: string) {
+        let input = parse(input)
+        let input = parse(input)
+        let {value} = {value: input}
+        let value = value
+        return {input, value}
+    }
+    let g = fn () {
+        let result = parse(input)
+        let input = result
+        return input
+    }
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const input = "5";
+    export const parse = (s)=>s;
+    export const f = (input)=>{
+        const input$1 = parse(input);
+        const input$2 = parse(input$1);
+        const { value } = {
+            value: input$2
+        };
+        const value$1 = value;
+        return {
+            input: input$2,
+            value: value$1
+        };
+    };
+    export const g = ()=>{
+        const result = parse(input);
+        const input$3 = result;
+        return input$3;
+    };
+    "###);
+}
+
+#[test]
+fn match_guards_are_checked_after_bindings() {
+    let src = r#"
+    let describe = fn (value: number | string) => match (value) {
+        n is number if (n > 0) => "positive",
+        s is string if (s != "") => s,
+        _ => "other"
+    }
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const describe = (value)=>{
+        let $match_result_0;
+        const $match_value_0 = value;
+        $match_0: {
+            if (typeof $match_value_0 === "number") {
+                const n = $match_value_0;
+                if (n > 0) {
+                    $match_result_0 = "positive";
+                    break $match_0;
+                }
+            }
+            if (typeof $match_value_0 === "string") {
+                const s = $match_value_0;
+                if (s !== "") {
+                    $match_result_0 = s;
+                    break $match_0;
+                }
+            }
+            {
+                const $unused_0 = $match_value_0;
+                $match_result_0 = "other";
+                break $match_0;
+            }
+        }
+        return $match_result_0;
+    };
+    "###);
+}
+
+#[test]
+fn match_guards_using_destructured_bindings() {
+    let src = r#"
+    let classify = fn (point: [number, number]) => match (point) {
+        [x, y] if (x == y) => "diagonal",
+        [x, ...rest] if (x > 0) => "positive",
+        _ => "other"
+    }
+    "#;
+
+    let (js, _) = compile(src);
+
+    insta::assert_snapshot!(js, @r###"
+    export const classify = (point)=>{
+        let $match_result_0;
+        const $match_value_0 = point;
+        $match_0: {
+            if ($match_value_0.length === 2) {
+                const [x, y] = $match_value_0;
+                if (x === y) {
+                    $match_result_0 = "diagonal";
+                    break $match_0;
+                }
+            }
+            if ($match_value_0.length >= 1) {
+                const [x, ...rest] = $match_value_0;
+                if (x > 0) {
+                    $match_result_0 = "positive";
+                    break $match_0;
+                }
+            }
+            {
+                const $unused_0 = $match_value_0;
+                $match_result_0 = "other";
+                break $match_0;
+            }
+        }
+        return $match_result_0;
+    };
+    "###);
+}
+
+#[test]
+fn match_guards_with_hoisted_stmts() {
+    let src = r#"
+    let check = fn (event) => match (event) {
+        {type: "key", key} if (match (key) { "Escape" => false, _ => true }) => key,
+        _ => ""
+    }
+    "#;
+
+    let (js, _) = compile(src);
+
+    // The statements for the guard's `match` are only run after the arm's
+    // pattern has matched and `key` has been declared.
+    insta::assert_snapshot!(js, @r###"
+    export const check = (event)=>{
+        let $match_result_0;
+        const $match_value_0 = event;
+        $match_0: {
+            if ($match_value_0.type === "key") {
+                const { key } = $match_value_0;
+                let $match_result_1;
+                const $match_value_1 = key;
+                if ($match_value_1 === "Escape") {
+                    $match_result_1 = false;
+                } else {
+                    const $unused_0 = $match_value_1;
+                    $match_result_1 = true;
+                }
+                if ($match_result_1) {
+                    $match_result_0 = key;
+                    break $match_0;
+                }
+            }
+            {
+                const $unused_1 = $match_value_0;
+                $match_result_0 = "";
+                break $match_0;
+            }
+        }
+        return $match_result_0;
+    };
+    "###);
+}
+
+#[test]
+fn type_only_imports_and_exports() -> Result<(), TypeError> {
+    let mut checker = Checker::default();
+    let ctx = Context::default();
+
+    let geometry_src = r#"
+    export type Point = {x: number, y: number}
+    export let distance = fn (p: Point, q: Point) -> number => q.x - p.x
+    "#;
+    let mut geometry = parse(geometry_src, &CompilerOptions::default()).unwrap();
+    let mut geometry_ctx = ctx.clone();
+    checker.infer_script(
+        &mut geometry,
+        &mut geometry_ctx,
+        &CompilerOptions::default(),
+    )?;
+    let exports = checker.get_exports(&geometry, &geometry_ctx)?;
+    checker.modules.insert("./geometry".to_string(), exports);
+
+    let src = r#"
+    import type {Point as Vector} from "./geometry"
+    import {type Point, distance} from "./geometry"
+    let origin: Point = {x: 0, y: 0}
+    let isFar = fn (p: Vector) -> boolean => distance(origin, p) > 100
+    export {origin, isFar as far}
+    export type {Point}
+    "#;
+    let mut program = parse(src, &CompilerOptions::default()).unwrap();
+    let mut ctx = ctx.clone();
+    checker.infer_script(&mut program, &mut ctx, &CompilerOptions::default())?;
+
+    let (js, _) = codegen_js(src, &program, &CompilerOptions::default());
+
+    // Type-only imports, specifiers, and exports don't exist at runtime.
+    insta::assert_snapshot!(js, @r###"
+    import { distance } from "./geometry";
+    const origin = {
+        x: 0,
+        y: 0
+    };
+    const isFar = (p)=>distance(origin, p) > 100;
+    export { origin, isFar as far };
+    "###);
 
-    insta::assert_snapshot!(result, @r###"TODO"###);
+    let result = codegen_d_ts(&program, &ctx, &checker, &CompilerOptions::default())?;
+
+    insta::assert_snapshot!(result, @r###"
+    import type { Point as Vector } from "./geometry";
+    import { type Point, distance } from "./geometry";
+    declare const isFar: (p: Readonly<Vector>) => boolean;
+    declare const origin: Readonly<Point>;
+    export { origin, isFar as far };
+    export type { Point };
+    "###);
 
     Ok(())
 }
+
+#[test]
+fn commonjs_named_exports() {
+    let src = r#"
+    let add = fn (a, b) => a + b
+    let sub = fn (a, b) => a - b
+    export {add, sub as subtract}
+    "#;
+    let options = CompilerOptions {
+        module_format: ModuleFormat::CommonJs,
+        ..Default::default()
+    };
+    let program = parse(src, &options).unwrap();
+    let (js, _) = codegen_js(src, &program, &options);
+
+    insta::assert_snapshot!(js, @r###"
+    const add = (a, b)=>a + b;
+    const sub = (a, b)=>a - b;
+    module.exports = {
+        add,
+        subtract: sub
+    };
+    "###);
+}
@@ -114,3 +114,25 @@ pub fn find_throws_in_block(block: &Block) -> Vec<Index> {
 
     visitor.throws
 }
+
+struct JSXVisitor {
+    pub span: Option<Span>,
+}
+
+impl Visitor for JSXVisitor {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::JSXElement(_) | ExprKind::JSXFragment(_) => {
+                self.span.get_or_insert(expr.span);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+}
+
+// Returns the span of the first JSX element or fragment in the script.
+pub fn find_jsx(script: &Script) -> Option<Span> {
+    let mut visitor = JSXVisitor { span: None };
+    visitor.visit_program(script);
+    visitor.span
+}
@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+
+use escalier_ast::*;
+
+// Finds all of the bindings from enclosing scopes that are used by a function,
+// in the order in which they're first used.  Captures that are only ever read
+// are marked as read-only, these can be shared safely which allows codegen to
+// hoist functions whose captures never change.
+//
+// NOTE: bindings that are captured by nested functions are also captures of
+// the function containing them.
+pub fn find_captures(params: &[FuncParam], body: &BlockOrExpr) -> Vec<Capture> {
+    let mut analysis = CaptureAnalysis {
+        scopes: vec![HashSet::new()],
+        ..Default::default()
+    };
+
+    for param in params {
+        analysis.visit_pattern(&param.pattern);
+    }
+
+    walk_block_or_expr(&mut analysis, body);
+
+    analysis.captures
+}
+
+#[derive(Default)]
+struct CaptureAnalysis {
+    scopes: Vec<HashSet<String>>,
+    captures: Vec<Capture>,
+    // Maps the name of each capture to its position in `captures`.
+    lookup: HashMap<String, usize>,
+}
+
+impl CaptureAnalysis {
+    fn declare(&mut self, ident: &BindingIdent) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(ident.name.to_owned());
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(HashSet::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn use_ident(&mut self, name: &str, is_write: bool) {
+        if self.scopes.iter().any(|scope| scope.contains(name)) {
+            return;
+        }
+
+        let index = match self.lookup.get(name) {
+            Some(index) => *index,
+            None => {
+                let index = self.captures.len();
+                self.captures.push(Capture {
+                    name: name.to_owned(),
+                    is_read_only: true,
+                });
+                self.lookup.insert(name.to_owned(), index);
+                index
+            }
+        };
+
+        if is_write {
+            self.captures[index].is_read_only = false;
+        }
+    }
+
+    // Assigning to a property, e.g. `p.x = 5` or `a[0] = 5`, modifies the
+    // binding at the root of the member expression.
+    fn assign(&mut self, left: &Expr) {
+        match &left.kind {
+            ExprKind::Ident(Ident { name, .. }) => self.use_ident(name, true),
+            ExprKind::Member(Member { object, .. }) => self.assign(object),
+            _ => {}
+        }
+    }
+}
+
+impl Visitor for CaptureAnalysis {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Ident(Ident { name, .. }) => self.use_ident(name, false),
+            ExprKind::Function(_)
+            | ExprKind::IfElse(_)
+            | ExprKind::IfLet(_)
+            | ExprKind::Match(_)
+            | ExprKind::Try(_)
+            | ExprKind::Do(_) => self.with_scope(|analysis| walk_expr(analysis, expr)),
+            ExprKind::Object(Object { properties }) => {
+                for prop in properties {
+                    if let PropOrSpread::Prop(expr::Prop::Shorthand(Ident { name, .. })) = prop {
+                        self.use_ident(name, false);
+                    }
+                }
+                walk_expr(self, expr);
+            }
+            ExprKind::Assign(Assign { left, .. }) => {
+                self.assign(left);
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Ident(ident) => self.declare(ident),
+            PatternKind::Is(IsPat { ident, .. }) => self.declare(ident),
+            PatternKind::Object(ObjectPat { props, .. }) => {
+                for prop in props {
+                    if let ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) = prop {
+                        self.declare(ident);
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        match &decl.kind {
+            DeclKind::VarDecl(VarDecl {
+                pattern,
+                expr: Some(expr),
+                ..
+            }) => match &expr.kind {
+                // Functions and objects can refer to themselves so the
+                // bindings are declared before visiting the initializer.
+                ExprKind::Function(_) | ExprKind::Object(_) => {
+                    self.visit_pattern(pattern);
+                    self.visit_expr(expr);
+                }
+                _ => {
+                    self.visit_expr(expr);
+                    self.visit_pattern(pattern);
+                }
+            },
+            _ => walk_decl(self, decl),
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::For(ForStmt { left, right, body }) => {
+                self.visit_expr(right);
+                self.with_scope(|analysis| {
+                    analysis.visit_pattern(left);
+                    walk_block(analysis, body);
+                });
+            }
+            StmtKind::LetElse(LetElseStmt {
+                pattern,
+                expr,
+                alternate,
+            }) => {
+                self.visit_expr(expr);
+                self.with_scope(|analysis| walk_block(analysis, alternate));
+                self.visit_pattern(pattern);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::type_error::TypeError;
+
+impl Checker {
+    // Removes the top-level decls whose `@cfg(...)` attributes don't match
+    // `self.cfg`.  Since the checker runs before codegen, these decls are
+    // omitted from the generated code as well.
+    pub(crate) fn strip_cfg_from_script(&self, script: &mut Script) -> Result<(), TypeError> {
+        let mut stmts = vec![];
+        for stmt in script.stmts.drain(..) {
+            let is_enabled = match &stmt.kind {
+                StmtKind::Decl(decl) => self.is_cfg_enabled(&decl.attrs)?,
+                _ => true,
+            };
+            if is_enabled {
+                stmts.push(stmt);
+            }
+        }
+        script.stmts = stmts;
+        Ok(())
+    }
+
+    pub(crate) fn strip_cfg_from_module(&self, module: &mut Module) -> Result<(), TypeError> {
+        let mut items = vec![];
+        for item in module.items.drain(..) {
+            let is_enabled = match &item.kind {
+                ModuleItemKind::Decl(decl) | ModuleItemKind::Export(Export { decl }) => {
+                    self.is_cfg_enabled(&decl.attrs)?
+                }
+                ModuleItemKind::Import(_) => true,
+            };
+            if is_enabled {
+                items.push(item);
+            }
+        }
+        module.items = items;
+        Ok(())
+    }
+
+    // Each `@cfg(...)` attribute must match.  `@cfg(key = "value")` matches
+    // if `key` is set to `value` and `@cfg(key)` matches if `key` is set.
+    // Attributes with multiple args match if all of their args match.
+    fn is_cfg_enabled(&self, attrs: &[Attribute]) -> Result<bool, TypeError> {
+        for attr in attrs.iter().filter(|attr| attr.name == "cfg") {
+            for arg in &attr.args {
+                let is_match = match &arg.kind {
+                    ExprKind::Ident(Ident { name, .. }) => self.cfg.contains_key(name),
+                    ExprKind::Assign(Assign {
+                        left,
+                        op: AssignOp::Assign,
+                        right,
+                    }) => match (&left.kind, &right.kind) {
+                        (ExprKind::Ident(Ident { name, .. }), ExprKind::Str(Str { value, .. })) => {
+                            self.cfg.get(name) == Some(value)
+                        }
+                        _ => return Err(invalid_cfg_error()),
+                    },
+                    _ => return Err(invalid_cfg_error()),
+                };
+                if !is_match {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+}
+
+fn invalid_cfg_error() -> TypeError {
+    TypeError {
+        message: "Invalid `@cfg` attribute, expected `@cfg(key)` or `@cfg(key = \"value\")`"
+            .to_string(),
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::BTreeMap;
+use std::mem;
+
+use escalier_ast::{CompilerOptions, Script};
+
+use crate::checker::{Checker, ExpansionLimits, JSXTypes, Report, Subsumption};
+use crate::context::Context;
+use crate::type_error::TypeError;
+
+/// The options for `check_program` that are specific to the checker, the
+/// options shared with the parser and codegen are in `CompilerOptions`.  These
+/// are the same as the corresponding fields on `Checker`, see its docs for
+/// what each one does.
+#[derive(Clone, Debug, Default)]
+pub struct CheckOptions {
+    pub jsx_types: JSXTypes,
+    pub expansion_limits: ExpansionLimits,
+    pub subsumption: Subsumption,
+    pub custom_attributes: Vec<String>,
+    pub cfg: BTreeMap<String, String>,
+}
+
+/// The result of checking a program with `check_program`.
+#[derive(Debug)]
+pub struct CheckResult {
+    /// The checker used to check the program.  It owns the types referenced
+    /// by `ctx` and the program's `inferred_type`s, and can be used to print
+    /// them or to query the program, e.g. with `type_at` or `references`.
+    pub checker: Checker,
+    /// The types and values declared by the program and the libs it was
+    /// checked against.
+    pub ctx: Context,
+    pub report: Report,
+    /// The error that stopped the program from being checked, if any.  Most
+    /// problems are reported in `report` instead.
+    pub error: Option<TypeError>,
+}
+
+impl CheckResult {
+    /// Returns `true` if the program was checked without any errors.
+    /// Warnings don't count as errors.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none() && self.report.diagnostics.is_empty()
+    }
+}
+
+impl From<&CheckOptions> for Checker {
+    fn from(options: &CheckOptions) -> Self {
+        Checker {
+            jsx_types: options.jsx_types.to_owned(),
+            expansion_limits: options.expansion_limits,
+            subsumption: options.subsumption,
+            custom_attributes: options.custom_attributes.to_owned(),
+            cfg: options.cfg.to_owned(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Checks `program` against the prelude and `options.libs`.  This is the
+/// entry point for tools that only need to check a program, e.g. the
+/// compiler, and saves them from setting up a `Checker` and `Context`
+/// themselves.  The program's pragmas override `options`, see
+/// `Checker::apply_pragmas`.
+pub fn check_program(
+    program: &mut Script,
+    options: &CompilerOptions,
+    check_options: &CheckOptions,
+) -> CheckResult {
+    let mut checker = Checker::from(check_options);
+    checker.strictness = options.strictness.to_owned();
+    let mut ctx = Context::default();
+
+    let error = match checker.load_libs(&options.libs, &mut ctx) {
+        Ok(()) => checker.infer_script(program, &mut ctx, options).err(),
+        Err(error) => Some(error),
+    };
+    let report = mem::take(&mut checker.current_report);
+
+    CheckResult {
+        checker,
+        ctx,
+        report,
+        error,
+    }
+}
@@ -1,13 +1,26 @@
-use generational_arena::Arena;
+use generational_arena::{Arena, Index};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt;
 use std::mem;
 
+use escalier_ast::{Span, Strictness};
+
 use crate::diagnostic::Diagnostic;
+use crate::explain::Explanation;
+use crate::modules::ModuleExports;
+use crate::normalization_cache::NormalizationCache;
+use crate::references::SymbolIndex;
+use crate::span_index::SpanIndex;
 use crate::types::Type;
 
+pub use escalier_ast::{IndexAccess, InterpolationStrictness};
+
 #[derive(Default, Clone, Debug)]
 pub struct Report {
     pub diagnostics: Vec<Diagnostic>,
+    // Problems that don't prevent the program from being compiled, e.g. uses
+    // of deprecated bindings.
+    pub warnings: Vec<Diagnostic>,
 }
 
 impl fmt::Display for Report {
@@ -15,15 +28,148 @@ impl fmt::Display for Report {
         for diagnostic in &self.diagnostics {
             writeln!(fmt, "{}", diagnostic)?;
         }
+        for warning in &self.warnings {
+            writeln!(fmt, "warning: {}", warning)?;
+        }
         Ok(())
     }
 }
 
+/// The names of the types used to check JSX.  These can be changed to match
+/// the declarations provided by a particular framework.
+#[derive(Clone, Debug)]
+pub struct JSXTypes {
+    /// The type of every JSX element and fragment, e.g. `JSX.Element`.
+    pub element: String,
+    /// An object type mapping intrinsic element names, e.g. `div`, to the
+    /// type of their props, e.g. `JSX.IntrinsicElements`.
+    pub intrinsic_elements: String,
+}
+
+impl Default for JSXTypes {
+    fn default() -> Self {
+        JSXTypes {
+            element: "JSXElement".to_string(),
+            intrinsic_elements: "JSXIntrinsicElements".to_string(),
+        }
+    }
+}
+
+/// Limits on the work done when expanding type aliases.  Recursive aliases,
+/// e.g. `type Loop<T> = Loop<[T]>`, can otherwise be expanded forever.  When
+/// a limit is exceeded a diagnostic is reported for the alias being expanded.
+#[derive(Clone, Copy, Debug)]
+pub struct ExpansionLimits {
+    /// The maximum number of aliases that can be expanded inside each other.
+    pub max_depth: usize,
+    /// The maximum number of types in a union produced by expanding an alias.
+    pub max_union_size: usize,
+    /// The maximum number of times generic aliases can be instantiated while
+    /// checking a program.
+    pub max_instantiations: usize,
+}
+
+impl Default for ExpansionLimits {
+    fn default() -> Self {
+        ExpansionLimits {
+            max_depth: 100,
+            max_union_size: 10_000,
+            max_instantiations: 100_000,
+        }
+    }
+}
+
+/// Controls which members are removed from unions and intersections when
+/// they're created because they're subsumed by other members, e.g. `"a" |
+/// string` becomes `string` and `string & "a"` becomes `"a"`.
+#[derive(Clone, Copy, Debug)]
+pub struct Subsumption {
+    /// Also removes object types that are subsumed by other object types,
+    /// e.g. `{x: number} | {x: number, y: number}` becomes `{x: number}`.
+    /// This is disabled by default since the removed types can no longer be
+    /// narrowed to.
+    pub objects: bool,
+    /// The maximum number of members in unions and intersections whose
+    /// members are compared with each other.  Literals are removed from
+    /// larger unions only if their primitive types are also members.
+    pub max_size: usize,
+}
+
+impl Default for Subsumption {
+    fn default() -> Self {
+        Subsumption {
+            objects: false,
+            max_size: 32,
+        }
+    }
+}
+
+/// Counts how often the expansions of aliases and mapped and conditional
+/// types were reused instead of being computed again, see
+/// `Checker::normalization_stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NormalizationStats {
+    /// The number of expansions that were reused.
+    pub hits: usize,
+    /// The number of expansions that were computed and cached.
+    pub misses: usize,
+    /// The number of expansions that couldn't be cached, e.g. because they
+    /// contained type variables.
+    pub skipped: usize,
+}
+
 #[derive(Default, Debug)]
 pub struct Checker {
     pub arena: Arena<Type>,
     pub current_report: Report,
     pub parent_reports: Vec<Report>,
+    pub jsx_types: JSXTypes,
+    /// The optional checks that are enabled.  These are set from the options
+    /// passed to `infer_script` and the script's pragmas.
+    pub strictness: Strictness,
+    pub expansion_limits: ExpansionLimits,
+    pub subsumption: Subsumption,
+    /// The names of attributes, in addition to the built-in ones such as
+    /// `deprecated`, that can be used on decls.  Other attributes are
+    /// reported as warnings.
+    pub custom_attributes: Vec<String>,
+    /// The values that `@cfg(...)` attributes on top-level decls are checked
+    /// against, e.g. `target` => `browser` enables decls marked with
+    /// `@cfg(target = "browser")`.  Other gated decls are removed before the
+    /// program is checked.
+    pub cfg: BTreeMap<String, String>,
+    /// The modules that scripts can import, keyed by the sources they're
+    /// imported from, e.g. `"./geometry"`.  See `get_exports`.
+    pub modules: BTreeMap<String, ModuleExports>,
+    /// When set, the steps taken to infer expressions inside of its span are
+    /// recorded.
+    pub explanation: Option<Explanation>,
+    // The types of the nodes that have been inferred, used by `type_at`.
+    pub(crate) span_index: SpanIndex,
+    // The declarations of and references to symbols, used by `references`
+    // and `rename`.
+    pub(crate) symbols: SymbolIndex,
+    // The spans of the type references currently being expanded, if known.
+    pub(crate) expansion_spans: Vec<Option<Span>>,
+    // The spans of the nodes currently being inferred, innermost last.  Types
+    // created while inferring a node use its span as their provenance.
+    pub(crate) node_spans: Vec<Span>,
+    // The pairs of recursive types that are currently being unified.  They're
+    // assumed to unify if they're encountered again so that unifying them
+    // terminates.
+    pub(crate) mu_assumptions: HashSet<(Index, Index)>,
+    // The unions with members that are unbound type vars, keyed by the type
+    // vars.  Their members are compared again once the type vars are bound,
+    // see `remove_subsumed_members_after_binding`.
+    pub(crate) unions_by_type_var: HashMap<Index, Vec<Index>>,
+    pub(crate) normalization_cache: NormalizationCache,
+    pub(crate) instantiation_count: usize,
+    // The id of the module or script being checked.  Each call to
+    // `infer_module` or `infer_script` checks a different module.
+    pub(crate) module_id: usize,
+    // The smallest id that can be given to a new type var, unique symbol, or
+    // recursive type, see `new_type_id`.
+    pub(crate) next_type_id: usize,
 }
 
 impl Checker {
@@ -40,6 +186,7 @@ impl Checker {
         self.current_report
             .diagnostics
             .append(&mut report.diagnostics);
+        self.current_report.warnings.append(&mut report.warnings);
     }
 
     pub fn with_report<T>(&mut self, f: impl FnOnce(&mut Self) -> T) -> T {
@@ -48,4 +195,18 @@ impl Checker {
         self.pop_report();
         result
     }
+
+    // Calls `f` with `span` as the span of the node being inferred.
+    pub(crate) fn with_span<T>(&mut self, span: Span, f: impl FnOnce(&mut Self) -> T) -> T {
+        self.node_spans.push(span);
+        let result = f(self);
+        self.node_spans.pop();
+        result
+    }
+
+    // Returns the span of the innermost node being inferred, if any.  This is
+    // used as the location of diagnostics that aren't about a specific node.
+    pub(crate) fn current_span(&self) -> Option<Span> {
+        self.node_spans.last().copied()
+    }
 }
@@ -0,0 +1,290 @@
+use generational_arena::{Arena, Index};
+use std::collections::HashMap;
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::modules::ModuleExports;
+use crate::provenance::Provenance;
+use crate::types::*;
+
+/// The number of types that were kept and removed by `Checker::compact`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CompactionStats {
+    pub live: usize,
+    pub removed: usize,
+}
+
+impl Checker {
+    /// Removes the types that can't be reached from `contexts`, the exports of
+    /// `modules`, or from the nodes that have been inferred, e.g. those used by
+    /// `type_at`, and moves the rest into a new arena.  Long running sessions,
+    /// such as a language server re-checking a file after every edit, can call
+    /// this between checks so that the arena doesn't keep growing.
+    ///
+    /// The indexes of the remaining types change so every context that's
+    /// still in use must be passed in.  The `inferred_type`s in the ASTs of
+    /// programs that have already been checked aren't updated and the types'
+    /// provenance is reduced to spans.
+    pub fn compact(&mut self, contexts: &mut [&mut Context]) -> CompactionStats {
+        let mut stack: Vec<Index> = vec![];
+        for ctx in contexts.iter_mut() {
+            for_each_index_in_context(ctx, &mut |index| stack.push(*index));
+        }
+        for exports in self.modules.values_mut() {
+            for_each_index_in_module_exports(exports, &mut |index| stack.push(*index));
+        }
+        self.span_index.for_each_index(|index| stack.push(*index));
+        self.symbols.for_each_index(|index| stack.push(*index));
+
+        // Types are added to the new arena in the order they're found, their
+        // contents are moved over once all of them have been found.
+        let mut arena: Arena<Type> = Arena::new();
+        let mut mapping: HashMap<Index, Index> = HashMap::new();
+        let mut live: Vec<Index> = vec![];
+        while let Some(index) = stack.pop() {
+            if mapping.contains_key(&index) {
+                continue;
+            }
+            mapping.insert(index, arena.insert(Type::from(TypeKind::Wildcard)));
+            live.push(index);
+
+            let mut kind = self.arena[index].kind.clone();
+            for_each_index_in_kind(&mut kind, &mut |index| stack.push(*index));
+        }
+
+        for index in &live {
+            let mut t = self.arena[*index].clone();
+            for_each_index_in_kind(&mut t.kind, &mut |index| *index = mapping[index]);
+            t.provenance = t.provenance.and_then(reduce_to_span);
+            arena[mapping[index]] = t;
+        }
+
+        let stats = CompactionStats {
+            live: live.len(),
+            removed: self.arena.len() - live.len(),
+        };
+
+        // Ids of type vars, unique symbols, and recursive types are based on
+        // the size of the arena so they'd be reused once it shrinks.
+        self.next_type_id = self.next_type_id.max(self.arena.len());
+        self.arena = arena;
+
+        let mut remap = |index: &mut Index| *index = mapping[index];
+        for ctx in contexts.iter_mut() {
+            for_each_index_in_context(ctx, &mut remap);
+        }
+        for exports in self.modules.values_mut() {
+            for_each_index_in_module_exports(exports, &mut remap);
+        }
+        self.span_index.for_each_index(&mut remap);
+        self.symbols.for_each_index(&mut remap);
+
+        // Unions that were removed no longer need to be simplified.
+        self.unions_by_type_var = std::mem::take(&mut self.unions_by_type_var)
+            .into_iter()
+            .filter_map(|(type_var, unions)| {
+                let unions: Vec<Index> = unions
+                    .iter()
+                    .filter_map(|union| mapping.get(union).copied())
+                    .collect();
+                match (mapping.get(&type_var), unions.is_empty()) {
+                    (Some(type_var), false) => Some((*type_var, unions)),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // These only refer to types that are no longer needed or that are
+        // found again the next time they're needed.
+        self.clear_normalization_cache();
+        self.mu_assumptions.clear();
+
+        stats
+    }
+}
+
+fn reduce_to_span(provenance: Provenance) -> Option<Provenance> {
+    provenance.get_span().map(Provenance::Span)
+}
+
+fn for_each_index_in_context(ctx: &mut Context, f: &mut impl FnMut(&mut Index)) {
+    for (_, binding) in ctx.values.iter_mut() {
+        f(&mut binding.index);
+    }
+    for (_, scheme) in ctx.schemes.iter_mut() {
+        for_each_index_in_scheme(scheme, f);
+    }
+    ctx.non_generic = ctx
+        .non_generic
+        .iter()
+        .map(|index| {
+            let mut index = *index;
+            f(&mut index);
+            index
+        })
+        .collect();
+}
+
+fn for_each_index_in_module_exports(exports: &mut ModuleExports, f: &mut impl FnMut(&mut Index)) {
+    for binding in exports.values.values_mut() {
+        f(&mut binding.index);
+    }
+    for scheme in exports.schemes.values_mut() {
+        for_each_index_in_scheme(scheme, f);
+    }
+}
+
+fn for_each_index_in_scheme(scheme: &mut Scheme, f: &mut impl FnMut(&mut Index)) {
+    f(&mut scheme.t);
+    for_each_index_in_type_params(&mut scheme.type_params, f);
+}
+
+fn for_each_index_in_type_params(
+    type_params: &mut Option<Vec<TypeParam>>,
+    f: &mut impl FnMut(&mut Index),
+) {
+    for type_param in type_params.iter_mut().flatten() {
+        if let Some(constraint) = &mut type_param.constraint {
+            f(constraint);
+        }
+        if let Some(default) = &mut type_param.default {
+            f(default);
+        }
+    }
+}
+
+fn for_each_index_in_function(function: &mut Function, f: &mut impl FnMut(&mut Index)) {
+    for param in &mut function.params {
+        for_each_index_in_func_param(param, f);
+    }
+    f(&mut function.ret);
+    for_each_index_in_type_params(&mut function.type_params, f);
+    if let Some(throws) = &mut function.throws {
+        f(throws);
+    }
+}
+
+fn for_each_index_in_func_param(param: &mut FuncParam, f: &mut impl FnMut(&mut Index)) {
+    for_each_index_in_pattern(&mut param.pattern, f);
+    f(&mut param.t);
+}
+
+fn for_each_index_in_pattern(pattern: &mut TPat, f: &mut impl FnMut(&mut Index)) {
+    match pattern {
+        TPat::Rest(RestPat { arg }) => for_each_index_in_pattern(arg, f),
+        TPat::Tuple(TuplePat { elems }) => {
+            for elem in elems.iter_mut().flatten() {
+                for_each_index_in_pattern(elem, f);
+            }
+        }
+        TPat::Object(TObjectPat { props }) => {
+            for prop in props {
+                match prop {
+                    TObjectPatProp::KeyValue(TObjectKeyValuePatProp { value, .. }) => {
+                        for_each_index_in_pattern(value, f)
+                    }
+                    TObjectPatProp::Assign(TObjectAssignPatProp { value, .. }) => {
+                        if let Some(value) = value {
+                            f(value);
+                        }
+                    }
+                    TObjectPatProp::Rest(RestPat { arg }) => for_each_index_in_pattern(arg, f),
+                }
+            }
+        }
+        TPat::Ident(_) | TPat::Lit(_) | TPat::Is(_) | TPat::Range(_) | TPat::Wildcard => (),
+    }
+}
+
+fn for_each_index_in_kind(kind: &mut TypeKind, f: &mut impl FnMut(&mut Index)) {
+    match kind {
+        TypeKind::TypeVar(TypeVar {
+            instance,
+            constraint,
+            ..
+        }) => {
+            if let Some(instance) = instance {
+                f(instance);
+            }
+            if let Some(constraint) = constraint {
+                f(constraint);
+            }
+        }
+        TypeKind::TypeRef(TypeRef {
+            scheme, type_args, ..
+        }) => {
+            if let Some(scheme) = scheme {
+                for_each_index_in_scheme(scheme, f);
+            }
+            type_args.iter_mut().for_each(f);
+        }
+        TypeKind::Union(Union { types })
+        | TypeKind::Intersection(Intersection { types })
+        | TypeKind::Tuple(Tuple { types, .. }) => types.iter_mut().for_each(f),
+        TypeKind::Array(Array { t, .. }) => f(t),
+        TypeKind::Function(function) => for_each_index_in_function(function, f),
+        TypeKind::Object(Object { elems, .. }) => {
+            for elem in elems {
+                match elem {
+                    TObjElem::Call(function) | TObjElem::Constructor(function) => {
+                        for_each_index_in_function(function, f)
+                    }
+                    TObjElem::Method(method) => for_each_index_in_function(&mut method.function, f),
+                    TObjElem::Getter(TGetter { ret, throws, .. }) => {
+                        f(ret);
+                        if let Some(throws) = throws {
+                            f(throws);
+                        }
+                    }
+                    TObjElem::Setter(TSetter { param, throws, .. }) => {
+                        for_each_index_in_func_param(param, f);
+                        if let Some(throws) = throws {
+                            f(throws);
+                        }
+                    }
+                    TObjElem::Mapped(mapped) => {
+                        f(&mut mapped.key);
+                        f(&mut mapped.value);
+                        f(&mut mapped.source);
+                        if let Some(check) = &mut mapped.check {
+                            f(check);
+                        }
+                        if let Some(extends) = &mut mapped.extends {
+                            f(extends);
+                        }
+                    }
+                    TObjElem::Prop(prop) => f(&mut prop.t),
+                }
+            }
+        }
+        TypeKind::Rest(Rest { arg }) => f(arg),
+        TypeKind::KeyOf(KeyOf { t }) => f(t),
+        TypeKind::IndexedAccess(IndexedAccess { obj, index }) => {
+            f(obj);
+            f(index);
+        }
+        TypeKind::Conditional(Conditional {
+            check,
+            extends,
+            true_type,
+            false_type,
+        }) => {
+            f(check);
+            f(extends);
+            f(true_type);
+            f(false_type);
+        }
+        TypeKind::Binary(BinaryT { left, right, .. }) => {
+            f(left);
+            f(right);
+        }
+        TypeKind::Mu(Mu { t, .. }) => f(t),
+        TypeKind::Keyword(_)
+        | TypeKind::Primitive(_)
+        | TypeKind::UniqueSymbol(_)
+        | TypeKind::Literal(_)
+        | TypeKind::Infer(_)
+        | TypeKind::Wildcard
+        | TypeKind::MuVar(_) => (),
+    }
+}
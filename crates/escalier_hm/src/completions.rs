@@ -0,0 +1,356 @@
+use generational_arena::Index;
+use std::collections::HashMap;
+
+use escalier_ast::{self as syntax, *};
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::type_error::TypeError;
+use crate::types::{
+    self, Primitive, TGetter, TMethod, TObjElem, TProp, TPropKey, TSetter, TypeKind,
+};
+
+/// The completions for a position in a script, e.g. for an LSP completion
+/// provider.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completions {
+    /// The type whose members or values are being completed, e.g. the type of
+    /// `obj` in `obj.fo`, the type of the param that `f("")`'s arg is passed
+    /// to, or the type expected for the object literal in `let p: Point = {}`.
+    pub expected_type: Index,
+    pub items: Vec<Completion>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Completion {
+    /// The text to insert, e.g. `length` or `"left"`.
+    pub label: String,
+    pub kind: CompletionKind,
+    pub t: Index,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompletionKind {
+    Property,
+    Method,
+    /// A member of a union of literals, e.g. `"left" | "right"`.
+    Literal,
+}
+
+// What's being completed at a position.
+#[derive(Clone, Copy, Debug)]
+enum Target {
+    // A member of an object, e.g. `obj.fo`.
+    Member(Index),
+    // A key of an object literal whose type is expected to be the given type.
+    Key(Index),
+    // A value whose type is expected to be the given type.
+    Value(Index),
+}
+
+impl Checker {
+    /// Returns the completions for `span` in `script`, which must have already
+    /// been checked with `ctx`.  `span` is usually empty, i.e. the position of
+    /// the cursor.  Returns `None` if nothing is expected at `span`, e.g. if
+    /// it's inside of a binding's name.
+    pub fn get_completions(
+        &mut self,
+        ctx: &Context,
+        script: &Script,
+        span: Span,
+    ) -> Result<Option<Completions>, TypeError> {
+        let (expected_type, items) = match self.find_target(ctx, script, span) {
+            Some(Target::Member(t)) => (t, self.get_member_completions(ctx, t)?),
+            Some(Target::Key(t)) => (t, self.get_member_completions(ctx, t)?),
+            Some(Target::Value(t)) => (t, self.get_literal_completions(ctx, t)?),
+            None => return Ok(None),
+        };
+
+        Ok(Some(Completions {
+            expected_type,
+            items: items.unwrap_or_default(),
+        }))
+    }
+
+    /// Returns the type of the value expected at `span`, e.g. the type of
+    /// the param that an arg is passed to or the type annotation of a `let`
+    /// decl.
+    pub fn get_expected_type(
+        &mut self,
+        ctx: &Context,
+        script: &Script,
+        span: Span,
+    ) -> Option<Index> {
+        match self.find_target(ctx, script, span) {
+            Some(Target::Value(t)) => Some(t),
+            _ => None,
+        }
+    }
+
+    fn find_target(&mut self, ctx: &Context, script: &Script, span: Span) -> Option<Target> {
+        let mut visitor = TargetVisitor {
+            checker: self,
+            ctx,
+            span,
+            expected_types: HashMap::new(),
+            target: None,
+        };
+        visitor.visit_program(script);
+
+        let target = visitor.target?;
+        Some(match target {
+            Target::Member(t) => Target::Member(self.prune(t)),
+            Target::Key(t) => Target::Key(self.prune(t)),
+            Target::Value(t) => Target::Value(self.prune(t)),
+        })
+    }
+
+    // Returns the properties, methods, getters, and setters of `obj_idx`,
+    // including those of the wrapper objects for primitives, e.g. `String`.
+    // Returns `None` if the type doesn't have named members.
+    pub(crate) fn get_member_completions(
+        &mut self,
+        ctx: &Context,
+        obj_idx: Index,
+    ) -> Result<Option<Vec<Completion>>, TypeError> {
+        let obj_idx = self.prune(obj_idx);
+        let obj_idx = match &self.arena[obj_idx].kind.clone() {
+            TypeKind::Object(object) => {
+                let mut completions = vec![];
+                for elem in &object.elems {
+                    let (name, kind, t) = match elem {
+                        TObjElem::Method(TMethod { name, function, .. }) => {
+                            let t = self.new_func_type(
+                                &function.params,
+                                function.ret,
+                                &function.type_params,
+                                function.throws,
+                            );
+                            (name, CompletionKind::Method, t)
+                        }
+                        TObjElem::Getter(TGetter { name, ret, .. }) => {
+                            (name, CompletionKind::Property, *ret)
+                        }
+                        TObjElem::Setter(TSetter { name, param, .. }) => {
+                            (name, CompletionKind::Property, param.t)
+                        }
+                        TObjElem::Prop(TProp { name, t, .. }) => {
+                            (name, CompletionKind::Property, *t)
+                        }
+                        _ => continue,
+                    };
+                    let label = match name {
+                        TPropKey::StringKey(name) => name.to_owned(),
+                        TPropKey::NumberKey(name) => name.to_owned(),
+                        TPropKey::SymbolKey(_) => continue,
+                    };
+                    // Objects with both a getter and a setter for the same
+                    // property only list it once.
+                    if !completions.iter().any(|c: &Completion| c.label == label) {
+                        completions.push(Completion { label, kind, t });
+                    }
+                }
+                return Ok(Some(completions));
+            }
+            TypeKind::Union(union) => {
+                let mut completions: Vec<Completion> = vec![];
+                for idx in &union.types {
+                    if let Some(member_completions) = self.get_member_completions(ctx, *idx)? {
+                        completions.extend(member_completions);
+                    }
+                }
+                completions.sort_by(|a, b| a.label.cmp(&b.label));
+                completions.dedup_by(|a, b| a.label == b.label);
+                return Ok(Some(completions));
+            }
+            TypeKind::TypeRef(types::TypeRef {
+                name,
+                scheme,
+                type_args,
+                ..
+            }) => match scheme {
+                Some(scheme) => self.expand_scheme(ctx, scheme, type_args, name)?,
+                None => self.expand_alias(ctx, name, type_args)?,
+            },
+            TypeKind::Array(types::Array { t, .. }) => self.expand_alias(ctx, "Array", &[*t])?,
+            TypeKind::Tuple(types::Tuple { types, .. }) => {
+                let types = self.get_tuple_elem_types(types);
+                let t = self.new_union_type(&types);
+                self.expand_alias(ctx, "Array", &[t])?
+            }
+            TypeKind::Literal(Literal::String(_)) | TypeKind::Primitive(Primitive::String) => {
+                self.expand_alias(ctx, "String", &[])?
+            }
+            TypeKind::Literal(Literal::Number(_))
+            | TypeKind::Primitive(Primitive::Number | Primitive::Int | Primitive::Float) => {
+                self.expand_alias(ctx, "Number", &[])?
+            }
+            _ => return Ok(None),
+        };
+        self.get_member_completions(ctx, obj_idx)
+    }
+
+    // Returns the literals that a value of type `t` can be, e.g. `"left"` and
+    // `"right"` for `"left" | "right"`.  Returns `None` if `t` isn't made up of
+    // literals.
+    fn get_literal_completions(
+        &mut self,
+        ctx: &Context,
+        t: Index,
+    ) -> Result<Option<Vec<Completion>>, TypeError> {
+        let t = self.expand_type(ctx, t)?;
+        let types = match &self.arena[t].kind {
+            TypeKind::Union(union) => union.types.clone(),
+            TypeKind::Literal(_) => vec![t],
+            _ => return Ok(None),
+        };
+
+        let mut completions = vec![];
+        for t in types {
+            let t = self.expand_type(ctx, t)?;
+            if let TypeKind::Literal(_) = &self.arena[t].kind {
+                completions.push(Completion {
+                    label: self.print_type(&t),
+                    kind: CompletionKind::Literal,
+                    t,
+                });
+            }
+        }
+
+        Ok(Some(completions))
+    }
+
+    // Returns the type of the property `name` if `t` has one.
+    fn get_prop_type(&mut self, ctx: &Context, t: Index, name: &str) -> Option<Index> {
+        let t = self.expand_type(ctx, t).ok()?;
+        let completions = self.get_member_completions(ctx, t).ok()??;
+        completions
+            .into_iter()
+            .find(|completion| completion.label == name)
+            .map(|completion| completion.t)
+    }
+}
+
+// Finds the innermost node containing `span` and what can be completed there.
+// The types expected for exprs are recorded by their parents, keyed by the
+// exprs' spans, before the exprs are visited.
+struct TargetVisitor<'a> {
+    checker: &'a mut Checker,
+    ctx: &'a Context,
+    span: Span,
+    expected_types: HashMap<Span, Index>,
+    target: Option<Target>,
+}
+
+impl<'a> TargetVisitor<'a> {
+    fn contains(&self, span: &Span) -> bool {
+        span.start <= self.span.start && self.span.end <= span.end
+    }
+
+    fn expect(&mut self, expr: &Expr, t: Option<Index>) {
+        if let Some(t) = t {
+            self.expected_types.insert(expr.span, t);
+        }
+    }
+
+    fn get_param_type(&mut self, callee: &Expr, index: usize) -> Option<Index> {
+        let t = self.checker.prune(callee.inferred_type?);
+        match &self.checker.arena[t].kind {
+            TypeKind::Function(types::Function { params, .. }) => {
+                params.get(index).map(|param| param.t)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> Visitor for TargetVisitor<'a> {
+    fn visit_decl(&mut self, decl: &Decl) {
+        if let DeclKind::VarDecl(VarDecl {
+            expr: Some(expr),
+            type_ann: Some(type_ann),
+            ..
+        }) = &decl.kind
+        {
+            self.expect(expr, type_ann.inferred_type);
+        }
+        walk_decl(self, decl);
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        if !self.contains(&expr.span) {
+            return;
+        }
+
+        let expected = self.expected_types.get(&expr.span).copied();
+        self.target = expected.map(Target::Value);
+
+        match &expr.kind {
+            ExprKind::Member(Member {
+                object,
+                property: MemberProp::Ident(Ident { span, .. }),
+                ..
+            }) if self.contains(span) => {
+                self.target = object.inferred_type.map(Target::Member);
+                return;
+            }
+            ExprKind::Object(syntax::Object { properties }) if expected.is_some() => {
+                let expected = expected.unwrap();
+                // Inside of the braces, but not a value, e.g. `{a: 5, |}`.
+                self.target = Some(Target::Key(expected));
+                for prop in properties {
+                    match prop {
+                        PropOrSpread::Prop(expr::Prop::Shorthand(Ident { span, .. })) => {
+                            if self.contains(span) {
+                                return;
+                            }
+                        }
+                        PropOrSpread::Prop(expr::Prop::Property { key, value }) => {
+                            let name = match key {
+                                ObjectKey::Ident(Ident { span, .. }) if self.contains(span) => {
+                                    return;
+                                }
+                                ObjectKey::Ident(Ident { name, .. }) => name,
+                                ObjectKey::String(name) => name,
+                                _ => continue,
+                            };
+                            if !self.contains(&value.span) {
+                                continue;
+                            }
+                            let t = self.checker.get_prop_type(self.ctx, expected, name);
+                            self.expect(value, t);
+                        }
+                        PropOrSpread::Spread(_) => {}
+                    }
+                }
+            }
+            ExprKind::Call(Call { callee, args, .. }) => {
+                for (i, arg) in args.iter().enumerate() {
+                    let t = self.get_param_type(callee, i);
+                    self.expect(arg, t);
+                }
+            }
+            ExprKind::Assign(Assign { left, right, .. }) => {
+                self.expect(right, left.inferred_type);
+            }
+            ExprKind::Binary(Binary {
+                left,
+                op: BinaryOp::Equals | BinaryOp::NotEquals,
+                right,
+            }) => {
+                self.expect(left, right.inferred_type);
+                self.expect(right, left.inferred_type);
+            }
+            ExprKind::Function(syntax::Function {
+                body: BlockOrExpr::Expr(body),
+                type_ann: Some(type_ann),
+                ..
+            }) => {
+                self.expect(body, type_ann.inferred_type);
+            }
+            _ => {}
+        }
+
+        walk_expr(self, expr);
+    }
+}
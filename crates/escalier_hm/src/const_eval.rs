@@ -0,0 +1,151 @@
+use escalier_ast::{BinaryOp, Literal, UnaryOp};
+
+use crate::types::TBinaryOp;
+
+// Evaluates operators on literals at compile time so that, e.g. `2 + 3` has
+// the literal type `5`.  This is used both when inferring the types of
+// expressions and when expanding type-level arithmetic so that the two always
+// agree with each other and with JavaScript's semantics.
+//
+// Returns `None` if the result can't be computed at compile time, e.g. bigint
+// division by zero or overflow, or if the operator doesn't apply to literals
+// of these kinds, in which case the caller falls back to the operator's usual
+// typing rules.
+pub fn eval_binary_op(op: &BinaryOp, left: &Literal, right: &Literal) -> Option<Literal> {
+    match (left, right) {
+        (Literal::Number(left), Literal::Number(right)) => {
+            eval_number_op(op, left.parse().ok()?, right.parse().ok()?)
+        }
+        (Literal::BigInt(left), Literal::BigInt(right)) => {
+            eval_bigint_op(op, left.parse().ok()?, right.parse().ok()?)
+        }
+        (Literal::String(left), Literal::String(right)) => match op {
+            BinaryOp::Plus => Some(Literal::String(format!("{left}{right}"))),
+            BinaryOp::LessThan => Some(Literal::Boolean(left < right)),
+            BinaryOp::LessThanOrEqual => Some(Literal::Boolean(left <= right)),
+            BinaryOp::GreaterThan => Some(Literal::Boolean(left > right)),
+            BinaryOp::GreaterThanOrEqual => Some(Literal::Boolean(left >= right)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+pub fn eval_unary_op(op: &UnaryOp, arg: &Literal) -> Option<Literal> {
+    match (op, arg) {
+        (UnaryOp::Plus, Literal::Number(value)) => Some(number(value.parse().ok()?)),
+        (UnaryOp::Minus, Literal::Number(value)) => Some(number(-value.parse::<f64>().ok()?)),
+        (UnaryOp::Minus, Literal::BigInt(value)) => {
+            Some(bigint(value.parse::<i128>().ok()?.checked_neg()?))
+        }
+        (UnaryOp::BitwiseNot, Literal::Number(value)) => {
+            Some(number(!to_int32(value.parse().ok()?) as f64))
+        }
+        (UnaryOp::BitwiseNot, Literal::BigInt(value)) => Some(bigint(!value.parse::<i128>().ok()?)),
+        (UnaryOp::Not, Literal::Boolean(value)) => Some(Literal::Boolean(!value)),
+        _ => None,
+    }
+}
+
+// Type-level arithmetic, e.g. `type T = A + 1`, uses the same operators as
+// expressions.
+pub fn to_binary_op(op: &TBinaryOp) -> BinaryOp {
+    match op {
+        TBinaryOp::Add => BinaryOp::Plus,
+        TBinaryOp::Sub => BinaryOp::Minus,
+        TBinaryOp::Mul => BinaryOp::Times,
+        TBinaryOp::Div => BinaryOp::Divide,
+        TBinaryOp::Mod => BinaryOp::Modulo,
+    }
+}
+
+fn eval_number_op(op: &BinaryOp, left: f64, right: f64) -> Option<Literal> {
+    let result = match op {
+        BinaryOp::Plus => number(left + right),
+        BinaryOp::Minus => number(left - right),
+        BinaryOp::Times => number(left * right),
+        BinaryOp::Divide => number(left / right),
+        BinaryOp::Modulo => number(left % right),
+        BinaryOp::Power => number(pow(left, right)),
+        BinaryOp::LessThan => Literal::Boolean(left < right),
+        BinaryOp::LessThanOrEqual => Literal::Boolean(left <= right),
+        BinaryOp::GreaterThan => Literal::Boolean(left > right),
+        BinaryOp::GreaterThanOrEqual => Literal::Boolean(left >= right),
+        BinaryOp::BitwiseAnd
+        | BinaryOp::BitwiseOr
+        | BinaryOp::BitwiseXor
+        | BinaryOp::LeftShift
+        | BinaryOp::RightShift
+        | BinaryOp::UnsignedRightShift => {
+            number(eval_bitwise_op(op, to_int32(left), to_int32(right)))
+        }
+        _ => return None,
+    };
+    Some(result)
+}
+
+// BigInt division truncates towards zero and the remainder takes the sign of
+// the dividend which matches the behavior of i128.
+fn eval_bigint_op(op: &BinaryOp, left: i128, right: i128) -> Option<Literal> {
+    let result = match op {
+        BinaryOp::Plus => bigint(left.checked_add(right)?),
+        BinaryOp::Minus => bigint(left.checked_sub(right)?),
+        BinaryOp::Times => bigint(left.checked_mul(right)?),
+        BinaryOp::Divide => bigint(left.checked_div(right)?),
+        BinaryOp::Modulo => bigint(left.checked_rem(right)?),
+        // Negative exponents throw a RangeError.
+        BinaryOp::Power => bigint(left.checked_pow(u32::try_from(right).ok()?)?),
+        BinaryOp::BitwiseAnd => bigint(left & right),
+        BinaryOp::BitwiseOr => bigint(left | right),
+        BinaryOp::BitwiseXor => bigint(left ^ right),
+        BinaryOp::LessThan => Literal::Boolean(left < right),
+        BinaryOp::LessThanOrEqual => Literal::Boolean(left <= right),
+        BinaryOp::GreaterThan => Literal::Boolean(left > right),
+        BinaryOp::GreaterThanOrEqual => Literal::Boolean(left >= right),
+        _ => return None,
+    };
+    Some(result)
+}
+
+fn number(value: f64) -> Literal {
+    // `-0` and `0` are the same literal type.
+    let value = if value == 0.0 { 0.0 } else { value };
+    Literal::Number(value.to_string())
+}
+
+fn bigint(value: i128) -> Literal {
+    Literal::BigInt(value.to_string())
+}
+
+// `f64::powf` differs from `**` when the exponent is NaN or when raising ±1
+// to an infinite power, both of which are NaN in JavaScript.
+fn pow(base: f64, exponent: f64) -> f64 {
+    if exponent.is_nan() || (base.abs() == 1.0 && exponent.is_infinite()) {
+        return f64::NAN;
+    }
+    base.powf(exponent)
+}
+
+// Converts a number to a 32-bit int the same way that JavaScript's bitwise
+// operators do, i.e. truncating it and wrapping it modulo 2^32.
+fn to_int32(value: f64) -> i32 {
+    if !value.is_finite() {
+        return 0;
+    }
+    value.trunc().rem_euclid(4294967296.0) as u32 as i32
+}
+
+// Evaluates bitwise operators on numbers that have already been converted to
+// 32-bit ints.  Shift amounts only use the lowest five bits.
+fn eval_bitwise_op(op: &BinaryOp, left: i32, right: i32) -> f64 {
+    let shift = (right as u32) & 0x1f;
+    match op {
+        BinaryOp::BitwiseAnd => (left & right) as f64,
+        BinaryOp::BitwiseOr => (left | right) as f64,
+        BinaryOp::BitwiseXor => (left ^ right) as f64,
+        BinaryOp::LeftShift => left.wrapping_shl(shift) as f64,
+        BinaryOp::RightShift => (left >> shift) as f64,
+        BinaryOp::UnsignedRightShift => ((left as u32) >> shift) as f64,
+        _ => unreachable!(),
+    }
+}
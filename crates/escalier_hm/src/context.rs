@@ -1,10 +1,13 @@
+use escalier_ast::Span;
 use generational_arena::Index;
 use im::hashmap::HashMap;
 use im::hashset::HashSet;
+use itertools::Itertools;
 
 use crate::checker::Checker;
 use crate::folder::walk_index;
 use crate::folder::{self, Folder};
+use crate::infer::Generalize;
 use crate::key_value_store::KeyValueStore;
 use crate::type_error::TypeError;
 use crate::types::*;
@@ -13,6 +16,11 @@ use crate::types::*;
 pub struct Binding {
     pub index: Index,
     pub is_mut: bool,
+    // Where the binding was declared, `None` for bindings that don't come
+    // from source code, e.g. those from .d.ts files.
+    pub span: Option<Span>,
+    // The reason given by the `@deprecated` attribute of the binding's decl.
+    pub deprecated: Option<String>,
 }
 
 #[derive(Clone, Debug, Default)]
@@ -22,12 +30,27 @@ pub struct Context {
     // Maps type aliases to type types definitions.
     // TODO: figure out how we want to track types and schemes
     pub schemes: HashMap<String, Scheme>,
+    // Where the types in `schemes` were declared, for those declared in
+    // source code.
+    pub type_decls: HashMap<String, Span>,
     // A set of non-generic TypeVariables.
     // NOTE: The same type variable can be both generic and non-generic in
     // different contexts.
     pub non_generic: HashSet<Index>,
     // Whether we're in an async function body or not.
     pub is_async: bool,
+    // Index accesses that are known to be safe, e.g. `arr[i]` inside of
+    // `if (i < arr.length) { ... }`.  Each entry is the name of the object
+    // and the name of the key or a quoted string literal.
+    pub checked_accesses: HashSet<(String, String)>,
+    // The spans of immutable bindings declared without an initializer, e.g.
+    // `let x: number`.  These can be assigned once, which is checked by the
+    // definite assignment analysis.
+    pub uninitialized: HashSet<Span>,
+    // The names of the types and values from the prelude and libs.  Scripts
+    // can redeclare them at the top-level, e.g. to define their own `Partial`.
+    pub builtin_schemes: HashSet<String>,
+    pub builtin_values: HashSet<String>,
 }
 
 impl Context {
@@ -48,6 +71,42 @@ impl Context {
             }),
         }
     }
+
+    /// Adds a type that's declared at the top-level of a script.  Types from
+    /// the prelude and libs can be redeclared, other types can't.
+    pub fn insert_top_level_scheme(&mut self, name: &str, scheme: Scheme) -> Result<(), TypeError> {
+        let is_builtin = self.shadow_builtin_scheme(name);
+        match self.schemes.insert(name.to_owned(), scheme) {
+            Some(_) if !is_builtin => Err(redeclaration_error(name)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Adds a value that's declared at the top-level of a script.  Values
+    /// from the prelude and libs can be redeclared, other values can't.
+    pub fn insert_top_level_value(
+        &mut self,
+        name: &str,
+        binding: Binding,
+    ) -> Result<(), TypeError> {
+        let is_builtin = self.builtin_values.remove(name).is_some();
+        match self.values.insert(name.to_owned(), binding) {
+            Some(_) if !is_builtin => Err(redeclaration_error(name)),
+            _ => Ok(()),
+        }
+    }
+
+    // Returns whether `name` is a type from the prelude or libs.  It's no
+    // longer considered one afterwards since the script is redeclaring it.
+    pub(crate) fn shadow_builtin_scheme(&mut self, name: &str) -> bool {
+        self.builtin_schemes.remove(name).is_some()
+    }
+}
+
+pub(crate) fn redeclaration_error(name: &str) -> TypeError {
+    TypeError {
+        message: format!("{name} cannot be redeclared at the top-level"),
+    }
 }
 
 impl Checker {
@@ -89,11 +148,24 @@ impl Checker {
         fresh.fold_index(index)
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(t = %self.print_type(t)))
+    )]
     pub fn instantiate_type(
         &mut self,
         t: &Index,
         mapping: &std::collections::HashMap<String, Index>,
     ) -> Index {
+        self.explain(|checker| {
+            let args = mapping
+                .iter()
+                .sorted_by_key(|(name, _)| *name)
+                .map(|(name, arg)| format!("{name} = {}", checker.print_type(arg)))
+                .join(", ");
+            format!("instantiate {} with {args}", checker.print_type(t))
+        });
+
         let mut instantiate = Instantiate {
             checker: self,
             mapping,
@@ -155,6 +227,59 @@ impl Checker {
             throws,
         })
     }
+
+    /// Generalizes `t` into a scheme whose type params are the type variables
+    /// in `t` that don't occur in `ctx.non_generic`.  The type params are
+    /// named `A`, `B`, etc. in the order they first appear in `t`.  The
+    /// scheme has no type params if every type variable in `t` is
+    /// non-generic.
+    pub fn generalize(&mut self, t: Index, ctx: &Context) -> Scheme {
+        let mut mapping = std::collections::BTreeMap::default();
+        let mut generalize = Generalize {
+            checker: self,
+            mapping: &mut mapping,
+            non_generic: &ctx.non_generic,
+        };
+        let t = generalize.fold_index(&t);
+
+        let type_params = mapping
+            .into_values()
+            .sorted()
+            .map(|name| TypeParam {
+                name,
+                arity: 0,
+                constraint: None,
+                default: None,
+            })
+            .collect::<Vec<_>>();
+
+        Scheme {
+            t,
+            type_params: match type_params.is_empty() {
+                true => None,
+                false => Some(type_params),
+            },
+            is_type_param: false,
+            opaque_module: None,
+        }
+    }
+
+    /// Instantiates `scheme` by replacing each of its type params with a
+    /// fresh type variable constrained by the type param's constraint.  This
+    /// is the inverse of `generalize`.
+    pub fn instantiate(&mut self, scheme: &Scheme) -> Index {
+        let type_params = match &scheme.type_params {
+            Some(type_params) => type_params,
+            None => return scheme.t,
+        };
+
+        let mut mapping = std::collections::HashMap::default();
+        for tp in type_params {
+            mapping.insert(tp.name.to_owned(), self.new_type_var(tp.constraint));
+        }
+
+        self.instantiate_type(&scheme.t, &mapping)
+    }
 }
 
 struct Fresh<'a, 'b> {
@@ -233,11 +358,12 @@ impl<'a> Folder for Instantiate<'a> {
                 let new_types = folder::walk_indexes(self, types);
 
                 match self.mapping.get(name) {
-                    Some(index) => {
-                        // What does it mean to replace the constructor name
-                        // when it has type args?
-                        *index
+                    // Type params that are type constructors, e.g. `F` in
+                    // `F<T>`, are applied to the type args.
+                    Some(index) if !new_types.is_empty() => {
+                        self.checker.apply_type_constructor(*index, &new_types)
                     }
+                    Some(index) => *index,
                     None => {
                         if &new_types != types {
                             self.checker
@@ -248,6 +374,12 @@ impl<'a> Folder for Instantiate<'a> {
                     }
                 }
             }
+            TypeKind::Tuple(_) => {
+                // Type args in spread position, e.g. `T` in `[...T, number]`,
+                // are spliced into the tuple if they're tuples.
+                let index = walk_index(self, index);
+                self.checker.flatten_tuple(index)
+            }
             _ => walk_index(self, index),
         }
     }
@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::diagnostic::Diagnostic;
+use crate::type_error::TypeError;
+
+impl Checker {
+    // Bindings declared without an initializer, e.g. `let x: number`, must be
+    // assigned on all paths before they're used.  Immutable bindings can only
+    // be assigned once which means that they can't be assigned inside of loops
+    // or functions, e.g.
+    //
+    // let sign: string
+    // if (n < 0) {
+    //     sign = "-"
+    // } else {
+    //     sign = "+"
+    // }
+    pub fn check_definite_assignment(&mut self, body: &BlockOrExpr) {
+        self.run_definite_assignment(|analysis| walk_block_or_expr(analysis, body));
+    }
+
+    pub fn check_definite_assignment_in_script(&mut self, script: &Script) {
+        self.run_definite_assignment(|analysis| walk_program(analysis, script));
+    }
+
+    pub fn check_definite_assignment_in_module(&mut self, module: &Module) {
+        self.run_definite_assignment(|analysis| {
+            for item in &module.items {
+                match &item.kind {
+                    ModuleItemKind::Decl(decl) | ModuleItemKind::Export(Export { decl }) => {
+                        analysis.visit_decl(decl)
+                    }
+                    ModuleItemKind::Import(_) => {}
+                }
+            }
+        });
+    }
+
+    fn run_definite_assignment(&mut self, f: impl FnOnce(&mut DefiniteAssignment)) {
+        let mut analysis = DefiniteAssignment {
+            scopes: vec![HashMap::new()],
+            state: State {
+                assignments: vec![],
+                reachable: true,
+            },
+            ..Default::default()
+        };
+
+        f(&mut analysis);
+
+        self.current_report
+            .diagnostics
+            .append(&mut analysis.diagnostics);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum Assignment {
+    #[default]
+    Unassigned,
+    // Assigned on some paths but not others.
+    Maybe,
+    Definite,
+}
+
+#[derive(Clone, Debug, Default)]
+struct State {
+    // The assignment of each binding in `DefiniteAssignment::bindings`.
+    assignments: Vec<Assignment>,
+    // Whether the current point in the program can be reached, e.g. it's not
+    // after a `return` or `throw`.
+    reachable: bool,
+}
+
+struct UninitBinding {
+    mutable: bool,
+    // How many functions deep the binding was declared.
+    depth: usize,
+    // How many loops deep the binding was declared.
+    loop_depth: usize,
+    // Only the first use before the binding is assigned is reported.
+    reported: bool,
+}
+
+#[derive(Default)]
+struct DefiniteAssignment {
+    bindings: Vec<UninitBinding>,
+    // Bindings with initializers are `None`, they're tracked so that they
+    // shadow uninitialized bindings with the same name.
+    scopes: Vec<HashMap<String, Option<usize>>>,
+    state: State,
+    depth: usize,
+    loop_depth: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl DefiniteAssignment {
+    fn declare(&mut self, ident: &BindingIdent, is_uninit: bool) {
+        let id = match is_uninit {
+            true => {
+                let id = self.bindings.len();
+                self.bindings.push(UninitBinding {
+                    mutable: ident.mutable,
+                    depth: self.depth,
+                    loop_depth: self.loop_depth,
+                    reported: false,
+                });
+                Some(id)
+            }
+            false => None,
+        };
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(ident.name.to_owned(), id);
+    }
+
+    fn lookup(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name).copied())
+            .flatten()
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(HashMap::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn get_assignment(&self, id: usize) -> Assignment {
+        self.state.assignments.get(id).copied().unwrap_or_default()
+    }
+
+    fn set_assignment(&mut self, id: usize, assignment: Assignment) {
+        let assignments = &mut self.state.assignments;
+        if assignments.len() <= id {
+            assignments.resize(id + 1, Assignment::Unassigned);
+        }
+        assignments[id] = assignment;
+    }
+
+    // Replaces the current state with `state` and returns the old one.
+    fn restore(&mut self, state: State) -> State {
+        std::mem::replace(&mut self.state, state)
+    }
+
+    // Sets the current state to the combination of `states`, one for each
+    // path through the code.  Paths that can't be reached don't contribute.
+    fn merge(&mut self, states: Vec<State>) {
+        let mut reachable = states.into_iter().filter(|state| state.reachable);
+        let mut merged = match reachable.next() {
+            Some(state) => state,
+            None => {
+                self.state.reachable = false;
+                return;
+            }
+        };
+        for state in reachable {
+            let len = merged.assignments.len().max(state.assignments.len());
+            merged.assignments.resize(len, Assignment::Unassigned);
+            for (id, merged) in merged.assignments.iter_mut().enumerate() {
+                let other = state.assignments.get(id).copied().unwrap_or_default();
+                if *merged != other {
+                    *merged = Assignment::Maybe;
+                }
+            }
+        }
+        self.state = merged;
+    }
+
+    fn use_ident(&mut self, name: &str, span: Span) {
+        let id = match self.lookup(name) {
+            Some(id) => id,
+            None => return,
+        };
+        // Nested functions can be called after the binding has been assigned.
+        // Bindings declared inside of them are checked separately when the
+        // functions are inferred.
+        let binding = &self.bindings[id];
+        if binding.depth > 0 || self.depth > 0 || binding.reported || !self.state.reachable {
+            return;
+        }
+
+        let reason = match self.get_assignment(id) {
+            Assignment::Definite => return,
+            Assignment::Maybe => format!("`{name}` isn't assigned on all paths before this use"),
+            Assignment::Unassigned => format!("`{name}` hasn't been assigned yet"),
+        };
+        self.bindings[id].reported = true;
+        self.diagnostics.push(Diagnostic {
+            code: 1013,
+            message: format!("`{name}` is used before being assigned"),
+            reasons: vec![TypeError { message: reason }],
+            span: Some(span),
+            suggestion: None,
+            related: vec![],
+        });
+    }
+
+    fn assign(&mut self, name: &str, span: Span) {
+        let id = match self.lookup(name) {
+            Some(id) => id,
+            None => return,
+        };
+        let binding = &self.bindings[id];
+        if binding.depth > 0 {
+            return;
+        }
+
+        if !binding.mutable {
+            let reason = if self.depth > 0 {
+                Some(format!(
+                    "`{name}` is assigned inside of a function which can be called more than once"
+                ))
+            } else if binding.loop_depth < self.loop_depth {
+                Some(format!("`{name}` is assigned inside of a loop"))
+            } else {
+                match self.get_assignment(id) {
+                    Assignment::Unassigned => None,
+                    Assignment::Maybe => Some(format!("`{name}` may have already been assigned")),
+                    Assignment::Definite => Some(format!("`{name}` has already been assigned")),
+                }
+            };
+            if let Some(reason) = reason {
+                self.diagnostics.push(Diagnostic {
+                    code: 1014,
+                    message: format!("Cannot assign to `{name}` more than once"),
+                    reasons: vec![
+                        TypeError { message: reason },
+                        TypeError {
+                            message: format!(
+                                "`{name}` must be declared with `mut` to be assigned more than once"
+                            ),
+                        },
+                    ],
+                    span: Some(span),
+                    suggestion: None,
+                    related: vec![],
+                });
+            }
+        }
+
+        if self.depth == 0 {
+            self.set_assignment(id, Assignment::Definite);
+        }
+    }
+}
+
+impl Visitor for DefiniteAssignment {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Ident(Ident { name, span }) => self.use_ident(name, *span),
+            ExprKind::Assign(Assign { left, op, right }) => {
+                self.visit_expr(right);
+                match &left.kind {
+                    ExprKind::Ident(Ident { name, span }) => {
+                        // Compound assignments, e.g. `x += 1`, read `x` first.
+                        if *op != AssignOp::Assign {
+                            self.use_ident(name, *span);
+                        }
+                        self.assign(name, *span);
+                    }
+                    _ => self.visit_expr(left),
+                }
+            }
+            ExprKind::Binary(Binary {
+                left,
+                op: BinaryOp::And | BinaryOp::Or,
+                right,
+            }) => {
+                // The right side is only evaluated some of the time.
+                self.visit_expr(left);
+                let before = self.state.clone();
+                self.visit_expr(right);
+                let after = self.restore(before.clone());
+                self.merge(vec![before, after]);
+            }
+            ExprKind::Function(_) => {
+                let before = self.state.clone();
+                self.depth += 1;
+                self.with_scope(|analysis| walk_expr(analysis, expr));
+                self.depth -= 1;
+                self.restore(before);
+            }
+            ExprKind::IfElse(IfElse {
+                cond,
+                consequent,
+                alternate,
+            }) => {
+                self.visit_expr(cond);
+                let before = self.state.clone();
+                self.with_scope(|analysis| walk_block(analysis, consequent));
+                let after_consequent = self.restore(before);
+                if let Some(alternate) = alternate {
+                    self.with_scope(|analysis| walk_block_or_expr(analysis, alternate));
+                }
+                let after_alternate = self.state.clone();
+                self.merge(vec![after_consequent, after_alternate]);
+            }
+            ExprKind::IfLet(IfLet {
+                pattern,
+                expr,
+                consequent,
+                alternate,
+            }) => {
+                self.visit_expr(expr);
+                let before = self.state.clone();
+                self.with_scope(|analysis| {
+                    analysis.visit_pattern(pattern);
+                    walk_block(analysis, consequent);
+                });
+                let after_consequent = self.restore(before);
+                if let Some(alternate) = alternate {
+                    self.with_scope(|analysis| walk_block_or_expr(analysis, alternate));
+                }
+                let after_alternate = self.state.clone();
+                self.merge(vec![after_consequent, after_alternate]);
+            }
+            ExprKind::Match(Match { expr, arms }) => {
+                self.visit_expr(expr);
+                let before = self.state.clone();
+                let mut states = vec![];
+                for arm in arms {
+                    self.restore(before.clone());
+                    self.with_scope(|analysis| {
+                        analysis.visit_pattern(&arm.pattern);
+                        if let Some(guard) = &arm.guard {
+                            analysis.visit_expr(guard);
+                        }
+                        walk_block_or_expr(analysis, &arm.body);
+                    });
+                    states.push(self.state.clone());
+                }
+                if !states.is_empty() {
+                    self.merge(states);
+                }
+            }
+            ExprKind::Try(Try {
+                body,
+                catch,
+                finally,
+            }) => {
+                let before = self.state.clone();
+                self.with_scope(|analysis| walk_block(analysis, body));
+                if let Some(catch) = catch {
+                    // The body could throw before or after any of its
+                    // assignments.
+                    let after_body = self.state.clone();
+                    self.merge(vec![before, after_body.clone()]);
+                    self.state.reachable = true;
+                    self.with_scope(|analysis| {
+                        if let Some(param) = &catch.param {
+                            analysis.visit_pattern(param);
+                        }
+                        walk_block(analysis, &catch.body);
+                    });
+                    let after_catch = self.state.clone();
+                    self.merge(vec![after_body, after_catch]);
+                }
+                if let Some(finally) = finally {
+                    self.with_scope(|analysis| walk_block(analysis, finally));
+                }
+            }
+            ExprKind::Do(Do { body }) => self.with_scope(|analysis| walk_block(analysis, body)),
+            ExprKind::Throw(_) => {
+                walk_expr(self, expr);
+                self.state.reachable = false;
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Ident(ident) => self.declare(ident, false),
+            PatternKind::Is(IsPat { ident, .. }) => self.declare(ident, false),
+            PatternKind::Object(ObjectPat { props, .. }) => {
+                for prop in props {
+                    if let ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) = prop {
+                        self.declare(ident, false);
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        match &decl.kind {
+            DeclKind::VarDecl(VarDecl {
+                is_declare: false,
+                pattern:
+                    Pattern {
+                        kind: PatternKind::Ident(ident),
+                        ..
+                    },
+                expr: None,
+                ..
+            }) => self.declare(ident, true),
+            DeclKind::VarDecl(VarDecl {
+                pattern,
+                expr: Some(expr),
+                ..
+            }) => {
+                self.visit_expr(expr);
+                self.visit_pattern(pattern);
+            }
+            _ => walk_decl(self, decl),
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::For(ForStmt { left, right, body }) => {
+                // The body may not run at all.
+                self.visit_expr(right);
+                let before = self.state.clone();
+                self.loop_depth += 1;
+                self.with_scope(|analysis| {
+                    analysis.visit_pattern(left);
+                    walk_block(analysis, body);
+                });
+                self.loop_depth -= 1;
+                let after = self.restore(before.clone());
+                self.merge(vec![before, after]);
+            }
+            StmtKind::Return(_) => {
+                walk_stmt(self, stmt);
+                self.state.reachable = false;
+            }
+            StmtKind::LetElse(LetElseStmt {
+                pattern,
+                expr,
+                alternate,
+            }) => {
+                // The alternate can't complete so it doesn't affect the
+                // state afterwards.
+                self.visit_expr(expr);
+                let before = self.state.clone();
+                self.with_scope(|analysis| walk_block(analysis, alternate));
+                self.restore(before);
+                self.visit_pattern(pattern);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt;
 
+use escalier_ast::Span;
+
 use crate::type_error::TypeError;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -7,6 +9,14 @@ pub struct Diagnostic {
     pub code: u32,
     pub message: String,
     pub reasons: Vec<TypeError>,
+    // The location in the source that the diagnostic is about, if known.
+    pub span: Option<Span>,
+    // A likely fix for the problem, e.g. the property name that was probably
+    // meant when there's a typo.
+    pub suggestion: Option<String>,
+    // Other locations that explain the diagnostic, along with a note for
+    // each, e.g. where a constraint that wasn't satisfied was declared.
+    pub related: Vec<(Span, String)>,
 }
 
 impl fmt::Display for Diagnostic {
@@ -21,6 +31,12 @@ impl fmt::Display for Diagnostic {
             };
             writeln!(fmt, " {reason}")?;
         }
+        for (_, note) in &self.related {
+            writeln!(fmt, "note: {note}")?;
+        }
+        if let Some(suggestion) = &self.suggestion {
+            writeln!(fmt, "help: {suggestion}")?;
+        }
         Ok(())
     }
 }
@@ -0,0 +1,120 @@
+use generational_arena::Index;
+
+use escalier_ast::Literal as Lit;
+
+use crate::checker::Checker;
+use crate::types::*;
+
+// The kinds of values that a type can contain.  Types whose values are of
+// different kinds don't have any values in common.
+#[derive(Debug, PartialEq, Eq)]
+enum ValueKind {
+    Number,
+    BigInt,
+    Boolean,
+    String,
+    Symbol,
+    Null,
+    Undefined,
+    // Values that aren't primitives, e.g. arrays and functions.
+    NonPrimitive,
+    // Object types can describe primitives as well, e.g. `{length: number}`
+    // describes strings, which is what allows branded types such as
+    // `string & {__brand: "id"}`.
+    Object,
+}
+
+impl Checker {
+    // Returns true if some of the members of an intersection don't have any
+    // values in common, e.g. `number & string` or `"a" & "b"`, in which case
+    // the intersection is `never`.
+    pub(crate) fn has_disjoint_members(&mut self, types: &[Index]) -> bool {
+        types
+            .iter()
+            .enumerate()
+            .any(|(i, a)| types[i + 1..].iter().any(|b| self.are_disjoint(*a, *b)))
+    }
+
+    // This is conservative, types that can't be compared, e.g. type vars and
+    // type refs, are never disjoint.
+    fn are_disjoint(&mut self, a: Index, b: Index) -> bool {
+        let a = self.prune(a);
+        let b = self.prune(b);
+        let (a_kind, b_kind) = match (self.value_kind(a), self.value_kind(b)) {
+            (Some(a_kind), Some(b_kind)) => (a_kind, b_kind),
+            _ => return false,
+        };
+
+        match (&a_kind, &b_kind) {
+            (ValueKind::Object, ValueKind::Null | ValueKind::Undefined)
+            | (ValueKind::Null | ValueKind::Undefined, ValueKind::Object) => return true,
+            (ValueKind::Object, _) | (_, ValueKind::Object) => (),
+            _ if a_kind != b_kind => return true,
+            _ => (),
+        }
+
+        match (self.arena[a].kind.clone(), self.arena[b].kind.clone()) {
+            (TypeKind::Literal(Lit::Number(n1)), TypeKind::Literal(Lit::Number(n2))) => {
+                match (n1.parse::<f64>(), n2.parse::<f64>()) {
+                    (Ok(n1), Ok(n2)) => n1 != n2,
+                    _ => n1 != n2,
+                }
+            }
+            (TypeKind::Literal(l1), TypeKind::Literal(l2)) => l1 != l2,
+            (TypeKind::UniqueSymbol(s1), TypeKind::UniqueSymbol(s2)) => s1 != s2,
+            // Objects whose discriminants, i.e. props with literal types, are
+            // disjoint are disjoint, e.g. `{kind: "a"} & {kind: "b"}`.  Other
+            // props with disjoint types are `never` instead.
+            (TypeKind::Object(o1), TypeKind::Object(o2)) => o1.elems.iter().any(|e1| {
+                o2.elems.iter().any(|e2| match (e1, e2) {
+                    (TObjElem::Prop(p1), TObjElem::Prop(p2)) => {
+                        p1.name == p2.name
+                            && !p1.optional
+                            && !p2.optional
+                            && self.is_literal(p1.t)
+                            && self.is_literal(p2.t)
+                            && self.are_disjoint(p1.t, p2.t)
+                    }
+                    _ => false,
+                })
+            }),
+            _ => false,
+        }
+    }
+
+    fn is_literal(&mut self, t: Index) -> bool {
+        let t = self.prune(t);
+        matches!(
+            self.arena[t].kind,
+            TypeKind::Literal(_) | TypeKind::UniqueSymbol(_)
+        )
+    }
+
+    fn value_kind(&self, t: Index) -> Option<ValueKind> {
+        let kind = match &self.arena[t].kind {
+            TypeKind::Primitive(primitive) => match primitive {
+                Primitive::Number | Primitive::Int | Primitive::Float => ValueKind::Number,
+                Primitive::BigInt => ValueKind::BigInt,
+                Primitive::Boolean => ValueKind::Boolean,
+                Primitive::String => ValueKind::String,
+                Primitive::Symbol => ValueKind::Symbol,
+            },
+            TypeKind::Literal(lit) => match lit {
+                Lit::Number(_) => ValueKind::Number,
+                Lit::BigInt(_) => ValueKind::BigInt,
+                Lit::Boolean(_) => ValueKind::Boolean,
+                Lit::String(_) => ValueKind::String,
+                Lit::Null => ValueKind::Null,
+                Lit::Undefined => ValueKind::Undefined,
+            },
+            TypeKind::UniqueSymbol(_) => ValueKind::Symbol,
+            TypeKind::Keyword(Keyword::Object)
+            | TypeKind::Tuple(_)
+            | TypeKind::Array(_)
+            | TypeKind::Function(_) => ValueKind::NonPrimitive,
+            TypeKind::Object(_) => ValueKind::Object,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
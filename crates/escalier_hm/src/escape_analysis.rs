@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::diagnostic::Diagnostic;
+use crate::type_error::TypeError;
+
+impl Checker {
+    // Mutable bindings can be used as immutable values, e.g. by returning them
+    // or by assigning them to an immutable binding, as long as there's no other
+    // way to mutate the value afterwards.  This allows values to be built up
+    // using local mutation before being handed off as immutable values, e.g.
+    //
+    // let range = fn (n: number) -> number[] {
+    //     let mut result: number[] = []
+    //     for (i in 0..n) {
+    //         result.push(i)
+    //     }
+    //     return result
+    // }
+    pub fn check_escapes(&mut self, params: &[FuncParam], body: &BlockOrExpr) {
+        let mut analysis = EscapeAnalysis {
+            scopes: vec![HashMap::new()],
+            ..Default::default()
+        };
+
+        // NOTE: `mut` params can be returned as immutable values since it's
+        // up to the caller to decide whether it still needs to mutate them.
+        for param in params {
+            analysis.visit_pattern(&param.pattern);
+        }
+
+        match body {
+            BlockOrExpr::Block(block) => walk_block(&mut analysis, block),
+            BlockOrExpr::Expr(expr) => {
+                analysis.visit_expr(expr);
+                analysis.freeze(expr, None);
+            }
+        }
+
+        self.current_report
+            .diagnostics
+            .append(&mut analysis.diagnostics);
+    }
+}
+
+#[derive(Default)]
+struct LocalBinding {
+    name: String,
+    mutable: bool,
+    // How many functions deep the binding was declared.
+    depth: usize,
+    // Set if the binding is used by a nested function which could mutate it
+    // at any point in the future.
+    captured: bool,
+    // Set if the value is stored somewhere else, e.g. in another mutable
+    // binding or inside of an object or tuple.
+    aliased: bool,
+    // The immutable binding that the value was assigned to and where.
+    frozen: Option<(String, Span)>,
+}
+
+#[derive(Default)]
+struct EscapeAnalysis {
+    bindings: Vec<LocalBinding>,
+    scopes: Vec<HashMap<String, usize>>,
+    depth: usize,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl EscapeAnalysis {
+    fn declare(&mut self, ident: &BindingIdent) {
+        let id = self.bindings.len();
+        self.bindings.push(LocalBinding {
+            name: ident.name.to_owned(),
+            mutable: ident.mutable,
+            depth: self.depth,
+            ..Default::default()
+        });
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(ident.name.to_owned(), id);
+    }
+
+    // Bindings that aren't found are declared outside of the function being
+    // checked and are ignored.
+    fn lookup(&self, expr: &Expr) -> Option<usize> {
+        match &expr.kind {
+            ExprKind::Ident(Ident { name, .. }) => self
+                .scopes
+                .iter()
+                .rev()
+                .find_map(|scope| scope.get(name).copied()),
+            _ => None,
+        }
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(HashMap::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn report(&mut self, id: usize, reason: String, span: Span) {
+        let name = &self.bindings[id].name;
+        self.diagnostics.push(Diagnostic {
+            code: 1003,
+            message: format!(
+                "`{name}` can't be used as an immutable value because it can still be mutated"
+            ),
+            reasons: vec![TypeError { message: reason }],
+            span: Some(span),
+            suggestion: None,
+            related: vec![],
+        });
+    }
+
+    fn use_ident(&mut self, expr: &Expr) {
+        if let Some(id) = self.lookup(expr) {
+            let depth = self.depth;
+            let binding = &mut self.bindings[id];
+            if binding.depth < depth {
+                binding.captured = true;
+            }
+            // Only the first use after the value has been frozen is reported.
+            if let Some((into, span)) = binding.frozen.take() {
+                let reason = format!(
+                    "`{}` is used after being assigned to `{into}`",
+                    binding.name
+                );
+                self.report(id, reason, span);
+            }
+        }
+    }
+
+    fn alias(&mut self, expr: &Expr) {
+        if let Some(id) = self.lookup(expr) {
+            let binding = &mut self.bindings[id];
+            if binding.mutable {
+                binding.aliased = true;
+            }
+        }
+    }
+
+    // Called whenever a value is used as an immutable value, `into` is the
+    // name of the immutable binding it's being assigned to, if any.
+    fn freeze(&mut self, expr: &Expr, into: Option<&str>) {
+        // Nested functions are checked separately when they're inferred.
+        if self.depth > 0 {
+            return;
+        }
+        let id = match self.lookup(expr) {
+            Some(id) => id,
+            None => return,
+        };
+        let binding = &mut self.bindings[id];
+        if !binding.mutable {
+            return;
+        }
+
+        let name = &binding.name;
+        let reason = if binding.captured {
+            format!("`{name}` is captured by a function that can mutate it")
+        } else if binding.aliased {
+            format!("`{name}` may be aliased by another reference")
+        } else {
+            if let Some(into) = into {
+                binding.frozen = Some((into.to_owned(), expr.span));
+            }
+            return;
+        };
+
+        self.report(id, reason, expr.span);
+    }
+}
+
+impl Visitor for EscapeAnalysis {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Ident(_) => self.use_ident(expr),
+            ExprKind::Function(_) => {
+                self.depth += 1;
+                self.with_scope(|analysis| walk_expr(analysis, expr));
+                self.depth -= 1;
+            }
+            ExprKind::IfElse(_)
+            | ExprKind::IfLet(_)
+            | ExprKind::Match(_)
+            | ExprKind::Try(_)
+            | ExprKind::Do(_) => self.with_scope(|analysis| walk_expr(analysis, expr)),
+            ExprKind::Object(Object { properties }) => {
+                for prop in properties {
+                    match prop {
+                        PropOrSpread::Prop(expr::Prop::Shorthand(ident)) => {
+                            let value = Expr {
+                                kind: ExprKind::Ident(ident.to_owned()),
+                                span: ident.span,
+                                inferred_type: None,
+                            };
+                            self.use_ident(&value);
+                            self.alias(&value);
+                        }
+                        PropOrSpread::Prop(expr::Prop::Property { value, .. }) => self.alias(value),
+                        PropOrSpread::Spread(_) => {}
+                    }
+                }
+                walk_expr(self, expr);
+            }
+            ExprKind::Tuple(Tuple { elements }) => {
+                for elem in elements {
+                    if let ExprOrSpread::Expr(elem) = elem {
+                        self.alias(elem);
+                    }
+                }
+                walk_expr(self, expr);
+            }
+            ExprKind::Assign(Assign { right, .. }) => {
+                self.alias(right);
+                walk_expr(self, expr);
+            }
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Ident(ident) => self.declare(ident),
+            PatternKind::Is(IsPat { ident, .. }) => self.declare(ident),
+            PatternKind::Object(ObjectPat { props, .. }) => {
+                for prop in props {
+                    if let ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) = prop {
+                        self.declare(ident);
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        match &decl.kind {
+            DeclKind::VarDecl(VarDecl {
+                pattern,
+                expr: Some(expr),
+                ..
+            }) => {
+                self.visit_expr(expr);
+
+                let aliased = pattern.has_mut_bindings()
+                    && matches!(self.lookup(expr), Some(id) if self.bindings[id].mutable);
+                match &pattern.kind {
+                    PatternKind::Ident(BindingIdent {
+                        name,
+                        mutable: false,
+                        ..
+                    }) => self.freeze(expr, Some(name)),
+                    _ if aliased => self.alias(expr),
+                    _ => {}
+                }
+
+                let start = self.bindings.len();
+                self.visit_pattern(pattern);
+                if aliased {
+                    for binding in self.bindings[start..].iter_mut() {
+                        binding.aliased = binding.mutable;
+                    }
+                }
+            }
+            _ => walk_decl(self, decl),
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::For(_) => self.with_scope(|analysis| walk_stmt(analysis, stmt)),
+            StmtKind::Return(ReturnStmt { arg: Some(arg) }) => {
+                self.visit_expr(arg);
+                self.freeze(arg, None);
+            }
+            StmtKind::LetElse(LetElseStmt {
+                pattern,
+                expr,
+                alternate,
+            }) => {
+                self.visit_expr(expr);
+                self.with_scope(|analysis| walk_block(analysis, alternate));
+                self.visit_pattern(pattern);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+}
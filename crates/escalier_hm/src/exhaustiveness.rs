@@ -0,0 +1,600 @@
+use generational_arena::Index;
+
+use escalier_ast::{
+    IsPat, KeyValuePatProp, LitPat, Literal, MatchArm, ObjectPat, ObjectPatProp, Pattern,
+    PatternKind, RangePat, Span, TuplePat, TuplePatElem,
+};
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::diagnostic::Diagnostic;
+use crate::infer::literals_equal;
+use crate::infer_pattern::{parse_number, range_contains};
+use crate::type_error::TypeError;
+use crate::types::{self, Array, Keyword, Primitive, Rest, TObjElem, TProp, TPropKey, TypeKind};
+
+// Match arms are simplified before they're checked since bindings, wildcards,
+// and rest patterns all match any value.
+#[derive(Clone, Debug)]
+enum Pat {
+    Wild,
+    Lit(Literal),
+    Range(RangePat),
+    Is(String),
+    // The elements before the rest element, if there is one, and whether
+    // there's a rest element.
+    Tuple(Vec<Pat>, bool),
+    Object(Vec<(String, Pat)>),
+}
+
+impl From<&Pattern> for Pat {
+    fn from(pattern: &Pattern) -> Self {
+        match &pattern.kind {
+            PatternKind::Ident(_) | PatternKind::Rest(_) | PatternKind::Wildcard => Pat::Wild,
+            PatternKind::Lit(LitPat { lit }) => Pat::Lit(lit.to_owned()),
+            PatternKind::Range(range) => Pat::Range(range.to_owned()),
+            PatternKind::Is(IsPat { is_id, .. }) => Pat::Is(is_id.name.to_owned()),
+            PatternKind::Tuple(TuplePat { elems, .. }) => {
+                let mut pats = vec![];
+                let mut has_rest = false;
+                for elem in elems {
+                    match elem {
+                        Some(TuplePatElem { pattern, .. }) => match &pattern.kind {
+                            PatternKind::Rest(_) => has_rest = true,
+                            _ => pats.push(Pat::from(pattern)),
+                        },
+                        None => pats.push(Pat::Wild),
+                    }
+                }
+                Pat::Tuple(pats, has_rest)
+            }
+            PatternKind::Object(ObjectPat { props, .. }) => Pat::Object(
+                props
+                    .iter()
+                    .filter_map(|prop| match prop {
+                        ObjectPatProp::KeyValue(KeyValuePatProp { key, value, .. }) => {
+                            Some((key.name.to_owned(), Pat::from(value.as_ref())))
+                        }
+                        // Shorthand and rest props match any value.
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+}
+
+// The kinds of values that a type is split into.  Each one is matched by
+// different patterns.
+#[derive(Clone, Debug)]
+enum Ctor {
+    Lit(Literal),
+    // Tuples with the given element types.  Open tuples also include longer
+    // values, they're used for arrays and tuples with rest elements.
+    Tuple(Vec<Index>, bool),
+    // `t` is the expanded object type and `orig` is the type it was expanded
+    // from which is used by `is` patterns.
+    Object { t: Index, orig: Index },
+    // Values that can only be matched by wildcards and `is` patterns.
+    Other { t: Index, orig: Index },
+}
+
+// A value that isn't matched by any of the arms.
+enum Witness {
+    Wild,
+    Lit(Literal),
+    Tuple(Vec<Witness>, bool),
+    Object(Vec<(String, Witness)>),
+    Type(Index),
+}
+
+impl Checker {
+    // Reports matches without arms and arms that come after a catch-all arm,
+    // i.e. an arm without a guard whose pattern matches any value, since they
+    // can never be matched.  Otherwise the match is checked for exhaustiveness.
+    pub(crate) fn check_match_arms(
+        &mut self,
+        ctx: &Context,
+        arms: &[MatchArm],
+        expr_t: Index,
+        span: Span,
+    ) {
+        if arms.is_empty() {
+            self.current_report.diagnostics.push(Diagnostic {
+                code: 1017,
+                message: "Match has no arms".to_string(),
+                reasons: vec![],
+                span: Some(span),
+                suggestion: Some("add an arm, e.g. `_ => ...`".to_string()),
+                related: vec![],
+            });
+            return;
+        }
+
+        let catchall = arms.iter().position(|arm| self.is_catchall_arm(arm));
+        if let Some(index) = catchall {
+            let count = arms.len() - index - 1;
+            if count > 0 {
+                self.current_report.diagnostics.push(Diagnostic {
+                    code: 1018,
+                    message: "Catch-all arm must be the last arm in a match".to_string(),
+                    reasons: vec![TypeError {
+                        message: match count {
+                            1 => "the arm after it can never be matched".to_string(),
+                            _ => format!("the {count} arms after it can never be matched"),
+                        },
+                    }],
+                    span: Some(arms[index].span),
+                    suggestion: Some(
+                        "move the catch-all arm to the end of the match or remove the arms after it"
+                            .to_string(),
+                    ),
+                    related: vec![],
+                });
+            }
+            return;
+        }
+
+        self.check_match_exhaustiveness(ctx, arms, expr_t, span);
+    }
+
+    // Catch-all arms don't have guards and their patterns match any value.
+    pub(crate) fn is_catchall_arm(&self, arm: &MatchArm) -> bool {
+        arm.guard.is_none() && is_irrefutable(&Pat::from(&arm.pattern))
+    }
+
+    // Warns about matches that don't handle every value of the expression
+    // being matched.  Arms with guards are ignored since they might not match.
+    // Matches with multiple scrutinees, e.g. `match (a, b)`, are matched as
+    // tuples so every combination of their values is checked.
+    fn check_match_exhaustiveness(
+        &mut self,
+        ctx: &Context,
+        arms: &[MatchArm],
+        expr_t: Index,
+        span: Span,
+    ) {
+        let rows: Vec<Vec<Pat>> = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .map(|arm| vec![Pat::from(&arm.pattern)])
+            .collect();
+
+        let witness = match self.find_witness(ctx, &rows, &[expr_t]) {
+            Some(witness) => witness,
+            None => return,
+        };
+        let missing = self.print_witness(&witness[0]);
+
+        self.current_report.warnings.push(Diagnostic {
+            code: 1016,
+            message: "Match isn't exhaustive".to_string(),
+            reasons: vec![TypeError {
+                message: format!("`{missing}` isn't matched by any of the arms"),
+            }],
+            span: Some(span),
+            suggestion: Some(format!(
+                "add an arm for `{missing}` or a `_` arm to match the remaining values"
+            )),
+            related: vec![],
+        });
+    }
+
+    // Returns the type of the values that aren't matched by `arms`, i.e. the
+    // members of `expr_t` that some values of aren't matched.  This is the
+    // type of the value being matched inside of a catch-all arm after `arms`,
+    // which is `never` if the other arms match every value.
+    pub(crate) fn unmatched_type(
+        &mut self,
+        ctx: &Context,
+        arms: &[MatchArm],
+        expr_t: Index,
+    ) -> Index {
+        let rows: Vec<Vec<Pat>> = arms
+            .iter()
+            .filter(|arm| arm.guard.is_none())
+            .map(|arm| vec![Pat::from(&arm.pattern)])
+            .collect();
+        if rows.is_empty() {
+            return expr_t;
+        }
+
+        let types = match self.expand_type(ctx, expr_t) {
+            Ok(t) => match &self.arena[t].kind {
+                TypeKind::Union(types::Union { types }) => types.to_owned(),
+                _ => vec![t],
+            },
+            Err(_) => return expr_t,
+        };
+        // Booleans are split so that `true` and `false` can be handled
+        // separately.
+        let types: Vec<Index> = types
+            .into_iter()
+            .flat_map(|t| match &self.arena[t].kind {
+                TypeKind::Primitive(Primitive::Boolean) => vec![
+                    self.new_lit_type(&Literal::Boolean(true)),
+                    self.new_lit_type(&Literal::Boolean(false)),
+                ],
+                _ => vec![t],
+            })
+            .collect();
+        let unmatched: Vec<Index> = types
+            .iter()
+            .filter(|t| self.find_witness(ctx, &rows, &[**t]).is_some())
+            .cloned()
+            .collect();
+
+        match unmatched.len() == types.len() {
+            // Nothing was narrowed so the original type, which might be an
+            // alias, is kept.
+            true => expr_t,
+            false => self.new_union_type(&unmatched),
+        }
+    }
+
+    // Reports values that are passed to `never` params, e.g. the argument of
+    // `absurd(x)` in the last arm of a match, that haven't been narrowed to
+    // `never`.  Each of the variants that wasn't handled is listed.
+    pub(crate) fn report_unhandled_variants(&mut self, ctx: &Context, arg_t: Index, span: Span) {
+        let variants = match self.expand_type(ctx, arg_t) {
+            Ok(t) => match &self.arena[t].kind {
+                TypeKind::Union(types::Union { types }) => types.to_owned(),
+                _ => vec![t],
+            },
+            Err(_) => vec![arg_t],
+        };
+
+        self.current_report.diagnostics.push(Diagnostic {
+            code: 1021,
+            message: format!("Expected `never` but got `{}`", self.print_type(&arg_t)),
+            reasons: variants
+                .iter()
+                .map(|t| TypeError {
+                    message: format!("`{}` isn't handled", self.print_type(t)),
+                })
+                .collect(),
+            span: Some(span),
+            suggestion: Some("handle the remaining variants before this point".to_string()),
+            related: vec![],
+        });
+    }
+
+    // Finds values that aren't matched by any of the rows where each row
+    // contains a pattern for each of `types`.  This is the usefulness check
+    // from "Warnings for pattern matching" by Luc Maranget.
+    fn find_witness(
+        &mut self,
+        ctx: &Context,
+        rows: &[Vec<Pat>],
+        types: &[Index],
+    ) -> Option<Vec<Witness>> {
+        let (t, types) = match types.split_first() {
+            Some(split) => split,
+            None => return rows.is_empty().then(Vec::new),
+        };
+
+        // The first value only needs to be split if there are patterns that
+        // match some of its values.
+        if rows.iter().all(|row| matches!(row[0], Pat::Wild)) {
+            let rows: Vec<Vec<Pat>> = rows.iter().map(|row| row[1..].to_vec()).collect();
+            let mut witness = self.find_witness(ctx, &rows, types)?;
+            witness.insert(0, Witness::Wild);
+            return Some(witness);
+        }
+
+        for ctor in self.split_type(ctx, *t, rows) {
+            let keys = object_keys(&ctor, rows);
+            let fields = self.ctor_fields(&ctor, &keys);
+            let rows: Vec<Vec<Pat>> = rows
+                .iter()
+                .filter_map(|row| {
+                    let mut pats = self.specialize(&row[0], &ctor, &keys, fields.len())?;
+                    pats.extend_from_slice(&row[1..]);
+                    Some(pats)
+                })
+                .collect();
+
+            let field_count = fields.len();
+            if let Some(mut witness) =
+                self.find_witness(ctx, &rows, &[fields, types.to_vec()].concat())
+            {
+                let rest = witness.split_off(field_count);
+                let first = self.ctor_witness(ctor, keys, witness);
+                return Some([vec![first], rest].into_iter().flatten().collect());
+            }
+        }
+
+        None
+    }
+
+    fn split_type(&mut self, ctx: &Context, t: Index, rows: &[Vec<Pat>]) -> Vec<Ctor> {
+        let orig = t;
+        let t = match self.expand_type(ctx, t) {
+            Ok(t) => t,
+            Err(_) => return vec![Ctor::Other { t, orig }],
+        };
+
+        match self.arena[t].kind.clone() {
+            TypeKind::Union(types::Union { types }) => types
+                .iter()
+                .flat_map(|t| self.split_type(ctx, *t, rows))
+                .collect(),
+            TypeKind::Literal(lit) => vec![Ctor::Lit(lit)],
+            TypeKind::Primitive(Primitive::Boolean) => vec![
+                Ctor::Lit(Literal::Boolean(true)),
+                Ctor::Lit(Literal::Boolean(false)),
+            ],
+            TypeKind::Primitive(Primitive::Number | Primitive::Int | Primitive::Float)
+                if rows.iter().any(|row| matches!(row[0], Pat::Range(_))) =>
+            {
+                split_number(rows)
+            }
+            TypeKind::Keyword(Keyword::Never) => vec![],
+            TypeKind::Tuple(types::Tuple { mut types, .. }) => {
+                let rest = types.last().and_then(|last| match &self.arena[*last].kind {
+                    TypeKind::Rest(Rest { arg }) => Some(*arg),
+                    _ => None,
+                });
+                match rest {
+                    Some(arg) => {
+                        types.pop();
+                        match self.expand_type(ctx, arg).map(|arg| &self.arena[arg].kind) {
+                            Ok(TypeKind::Array(Array { t: elem, .. })) => {
+                                split_seq(types, Some(*elem), rows)
+                            }
+                            _ => vec![Ctor::Other { t, orig }],
+                        }
+                    }
+                    None => split_seq(types, None, rows),
+                }
+            }
+            TypeKind::Array(Array { t: elem, .. }) => split_seq(vec![], Some(elem), rows),
+            TypeKind::Object(_) => vec![Ctor::Object { t, orig }],
+            _ => vec![Ctor::Other { t, orig }],
+        }
+    }
+
+    // The types of the values inside of values of the given kind that patterns
+    // can match.
+    fn ctor_fields(&mut self, ctor: &Ctor, keys: &[String]) -> Vec<Index> {
+        match ctor {
+            Ctor::Lit(_) | Ctor::Other { .. } => vec![],
+            Ctor::Tuple(types, _) => types.to_owned(),
+            Ctor::Object { t, .. } => {
+                let elems = match &self.arena[*t].kind {
+                    TypeKind::Object(types::Object { elems, .. }) => elems.to_owned(),
+                    _ => vec![],
+                };
+                keys.iter()
+                    .map(|key| {
+                        let prop = elems.iter().find_map(|elem| match elem {
+                            TObjElem::Prop(
+                                prop @ TProp {
+                                    name: TPropKey::StringKey(name),
+                                    ..
+                                },
+                            ) if name == key => Some(prop),
+                            _ => None,
+                        });
+                        match prop {
+                            Some(prop) => prop.get_type(self),
+                            None => self.new_keyword(Keyword::Unknown),
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    // Returns the patterns for the fields of values of the given kind if the
+    // pattern matches them.
+    fn specialize(
+        &self,
+        pat: &Pat,
+        ctor: &Ctor,
+        keys: &[String],
+        arity: usize,
+    ) -> Option<Vec<Pat>> {
+        match (pat, ctor) {
+            (Pat::Wild, _) => Some(vec![Pat::Wild; arity]),
+            (Pat::Is(name), _) if self.is_pattern_matches(name, ctor) => {
+                Some(vec![Pat::Wild; arity])
+            }
+            (Pat::Lit(lit), Ctor::Lit(value)) if literals_equal(lit, value) => Some(vec![]),
+            (Pat::Range(range), Ctor::Lit(Literal::Number(value)))
+                if range_contains(range, value).unwrap_or(false) =>
+            {
+                Some(vec![])
+            }
+            (Pat::Tuple(pats, has_rest), Ctor::Tuple(types, is_open)) => {
+                let is_match = match (has_rest, is_open) {
+                    (true, _) => pats.len() <= types.len(),
+                    (false, false) => pats.len() == types.len(),
+                    (false, true) => false,
+                };
+                if !is_match {
+                    return None;
+                }
+                let mut pats = pats.to_owned();
+                pats.resize(types.len(), Pat::Wild);
+                Some(pats)
+            }
+            (Pat::Object(props), Ctor::Object { .. }) => Some(
+                keys.iter()
+                    .map(|key| match props.iter().find(|(name, _)| name == key) {
+                        Some((_, pat)) => pat.to_owned(),
+                        None => Pat::Wild,
+                    })
+                    .collect(),
+            ),
+            _ => None,
+        }
+    }
+
+    fn is_pattern_matches(&self, name: &str, ctor: &Ctor) -> bool {
+        let (t, orig) = match ctor {
+            Ctor::Lit(lit) => {
+                return matches!(
+                    (name, lit),
+                    ("number", Literal::Number(_))
+                        | ("bigint", Literal::BigInt(_))
+                        | ("string", Literal::String(_))
+                        | ("boolean", Literal::Boolean(_))
+                )
+            }
+            Ctor::Tuple(_, _) => return false,
+            Ctor::Object { t, orig } | Ctor::Other { t, orig } => (t, orig),
+        };
+
+        if let TypeKind::TypeRef(types::TypeRef {
+            name: orig_name, ..
+        }) = &self.arena[*orig].kind
+        {
+            if orig_name == name {
+                return true;
+            }
+        }
+
+        matches!(
+            (name, &self.arena[*t].kind),
+            (
+                "number",
+                TypeKind::Primitive(Primitive::Number | Primitive::Int | Primitive::Float)
+            ) | ("bigint", TypeKind::Primitive(Primitive::BigInt))
+                | ("string", TypeKind::Primitive(Primitive::String))
+                | ("boolean", TypeKind::Primitive(Primitive::Boolean))
+        )
+    }
+
+    fn ctor_witness(&self, ctor: Ctor, keys: Vec<String>, fields: Vec<Witness>) -> Witness {
+        match ctor {
+            Ctor::Lit(lit) => Witness::Lit(lit),
+            Ctor::Tuple(_, is_open) => Witness::Tuple(fields, is_open),
+            Ctor::Object { orig, .. } => {
+                let props: Vec<(String, Witness)> = keys
+                    .into_iter()
+                    .zip(fields)
+                    .filter(|(_, witness)| !matches!(witness, Witness::Wild))
+                    .collect();
+                match props.is_empty() {
+                    true => Witness::Type(orig),
+                    false => Witness::Object(props),
+                }
+            }
+            Ctor::Other { t, orig } => match &self.arena[t].kind {
+                TypeKind::TypeVar(_) | TypeKind::Keyword(Keyword::Unknown) => Witness::Wild,
+                _ => Witness::Type(orig),
+            },
+        }
+    }
+
+    fn print_witness(&self, witness: &Witness) -> String {
+        match witness {
+            Witness::Wild => "_".to_string(),
+            Witness::Lit(lit) => lit.to_string(),
+            Witness::Tuple(elems, is_open) => {
+                let mut elems: Vec<String> =
+                    elems.iter().map(|elem| self.print_witness(elem)).collect();
+                if *is_open {
+                    elems.push("...".to_string());
+                }
+                format!("[{}]", elems.join(", "))
+            }
+            Witness::Object(props) => {
+                let props: Vec<String> = props
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {}", self.print_witness(value)))
+                    .collect();
+                format!("{{{}}}", props.join(", "))
+            }
+            Witness::Type(t) => self.print_type(t),
+        }
+    }
+}
+
+// Tuple patterns are refutable since they check the length of the value even
+// if all of their elements are irrefutable.
+fn is_irrefutable(pat: &Pat) -> bool {
+    match pat {
+        Pat::Wild => true,
+        Pat::Object(props) => props.iter().all(|(_, pat)| is_irrefutable(pat)),
+        Pat::Lit(_) | Pat::Range(_) | Pat::Is(_) | Pat::Tuple(_, _) => false,
+    }
+}
+
+// Arrays and tuples with rest elements are split by length.  The lengths up
+// to the longest tuple pattern are checked individually and longer values can
+// only be matched by patterns with rest elements.
+fn split_seq(fixed: Vec<Index>, rest: Option<Index>, rows: &[Vec<Pat>]) -> Vec<Ctor> {
+    let elem = match rest {
+        Some(elem) => elem,
+        None => return vec![Ctor::Tuple(fixed, false)],
+    };
+
+    let max_len = rows
+        .iter()
+        .filter_map(|row| match &row[0] {
+            Pat::Tuple(pats, _) => Some(pats.len()),
+            _ => None,
+        })
+        .fold(fixed.len(), usize::max);
+
+    (fixed.len()..=max_len + 1)
+        .map(|len| {
+            let mut types = fixed.to_owned();
+            types.resize(len, elem);
+            Ctor::Tuple(types, len > max_len)
+        })
+        .collect()
+}
+
+// Numbers are split into the values that range and literal patterns start and
+// end at along with the intervals between them.  Each interval is either
+// matched by a pattern or it isn't so it's checked using a value inside of it.
+fn split_number(rows: &[Vec<Pat>]) -> Vec<Ctor> {
+    let mut points: Vec<f64> = rows
+        .iter()
+        .flat_map(|row| match &row[0] {
+            Pat::Lit(Literal::Number(value)) => vec![value],
+            Pat::Range(RangePat { start, end, .. }) => start.iter().chain(end).collect(),
+            _ => vec![],
+        })
+        .filter_map(|value| parse_number(value).ok())
+        .collect();
+    points.sort_by(f64::total_cmp);
+    points.dedup();
+
+    let mut values: Vec<f64> = vec![];
+    for (i, point) in points.iter().enumerate() {
+        if i == 0 {
+            values.push(point - 1.0);
+        }
+        values.push(*point);
+        match points.get(i + 1) {
+            Some(next) => values.push((point + next) / 2.0),
+            None => values.push(point + 1.0),
+        }
+    }
+
+    values
+        .into_iter()
+        .map(|value| Ctor::Lit(Literal::Number(value.to_string())))
+        .collect()
+}
+
+// The keys used by the object patterns at the start of the rows.
+fn object_keys(ctor: &Ctor, rows: &[Vec<Pat>]) -> Vec<String> {
+    let mut keys: Vec<String> = vec![];
+    if let Ctor::Object { .. } = ctor {
+        for row in rows {
+            if let Pat::Object(props) = &row[0] {
+                for (key, _) in props {
+                    if !keys.contains(key) {
+                        keys.push(key.to_owned());
+                    }
+                }
+            }
+        }
+    }
+    keys
+}
@@ -0,0 +1,88 @@
+use generational_arena::Index;
+use std::fmt;
+
+use escalier_ast::Span;
+
+use crate::checker::Checker;
+use crate::type_error::TypeError;
+
+/// A trace of the steps taken while inferring the types of the expressions
+/// inside of `span`.  Set `Checker::explanation` before checking a program to
+/// record one, this is much quicker than adding `eprintln!`s when debugging
+/// why inference failed.
+#[derive(Clone, Debug)]
+pub struct Explanation {
+    pub span: Span,
+    /// Each step is indented by how deeply nested it is.
+    pub steps: Vec<String>,
+    // The number of expressions inside of `span` currently being inferred.
+    depth: usize,
+}
+
+impl Explanation {
+    pub fn new(span: Span) -> Self {
+        Explanation {
+            span,
+            steps: vec![],
+            depth: 0,
+        }
+    }
+
+    fn contains(&self, span: &Span) -> bool {
+        self.span.start <= span.start && span.end <= self.span.end
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(fmt, "{step}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Checker {
+    // Records a step if an expression inside the explained span is being
+    // inferred.  `step` is only called in that case since printing types is
+    // expensive.
+    pub(crate) fn explain(&mut self, step: impl FnOnce(&Self) -> String) {
+        let is_explaining = matches!(&self.explanation, Some(e) if e.depth > 0);
+        if !is_explaining {
+            return;
+        }
+        let step = step(self);
+        if let Some(explanation) = &mut self.explanation {
+            let indent = "  ".repeat(explanation.depth);
+            explanation.steps.push(format!("{indent}{step}"));
+        }
+    }
+
+    // Returns whether the expression at `span` is being explained, in which
+    // case `end_explain_expr` must be called once it's been inferred.
+    pub(crate) fn start_explain_expr(&mut self, span: &Span) -> bool {
+        match &mut self.explanation {
+            Some(explanation) if explanation.contains(span) => {
+                explanation.depth += 1;
+                let indent = "  ".repeat(explanation.depth - 1);
+                explanation
+                    .steps
+                    .push(format!("{indent}infer expression at {span:?}"));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub(crate) fn end_explain_expr(&mut self, result: &Result<Index, TypeError>) {
+        let step = match result {
+            Ok(t) => format!("=> {}", self.print_type(t)),
+            Err(error) => format!("=> {error}"),
+        };
+        if let Some(explanation) = &mut self.explanation {
+            let indent = "  ".repeat(explanation.depth);
+            explanation.steps.push(format!("{indent}{step}"));
+            explanation.depth -= 1;
+        }
+    }
+}
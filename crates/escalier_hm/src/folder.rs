@@ -77,29 +77,41 @@ pub fn walk_index<F: Folder>(folder: &mut F, index: &Index) -> Index {
 
             TypeKind::Intersection(Intersection { types: new_types })
         }
-        TypeKind::Tuple(Tuple { types }) => {
+        TypeKind::Tuple(Tuple {
+            types,
+            labels,
+            readonly,
+        }) => {
             let new_types = walk_indexes(folder, types);
 
             if new_types == *types {
                 return *index;
             }
 
-            TypeKind::Tuple(Tuple { types: new_types })
+            TypeKind::Tuple(Tuple {
+                types: new_types,
+                labels: labels.to_owned(),
+                readonly: *readonly,
+            })
         }
-        TypeKind::Array(Array { t }) => {
+        TypeKind::Array(Array { t, readonly }) => {
             let new_t = folder.fold_index(t);
 
             if new_t == *t {
                 return *index;
             }
 
-            TypeKind::Array(Array { t: new_t })
+            TypeKind::Array(Array {
+                t: new_t,
+                readonly: *readonly,
+            })
         }
         TypeKind::Keyword(_) => return *index,
         TypeKind::Primitive(_) => return *index,
+        TypeKind::UniqueSymbol(_) => return *index,
         TypeKind::Literal(_) => return *index,
         TypeKind::Function(function) => TypeKind::Function(walk_function(folder, function)),
-        TypeKind::Object(Object { elems }) => {
+        TypeKind::Object(Object { elems, is_fresh }) => {
             let elems: Vec<_> = elems
                 .iter()
                 .map(|elem| match elem {
@@ -163,7 +175,10 @@ pub fn walk_index<F: Folder>(folder: &mut F, index: &Index) -> Index {
                 })
                 .collect();
 
-            TypeKind::Object(Object { elems })
+            TypeKind::Object(Object {
+                elems,
+                is_fresh: *is_fresh,
+            })
         }
         TypeKind::Rest(Rest { arg }) => {
             let new_arg = folder.fold_index(arg);
@@ -238,6 +253,16 @@ pub fn walk_index<F: Folder>(folder: &mut F, index: &Index) -> Index {
                 right: new_right,
             })
         }
+        TypeKind::Mu(Mu { id, t }) => {
+            let new_t = folder.fold_index(t);
+
+            if new_t == *t {
+                return *index;
+            }
+
+            TypeKind::Mu(Mu { id: *id, t: new_t })
+        }
+        TypeKind::MuVar(_) => return *index,
     };
 
     folder.put_type(Type {
@@ -282,6 +307,7 @@ fn walk_type_params<F: Folder>(
 fn walk_type_param<F: Folder>(folder: &mut F, type_param: &TypeParam) -> TypeParam {
     TypeParam {
         name: type_param.name.to_owned(),
+        arity: type_param.arity,
         constraint: type_param
             .constraint
             .as_ref()
@@ -5,11 +5,16 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use escalier_ast::{self as syntax, *};
 
 use crate::ast_utils::{find_returns, find_throws, find_throws_in_block};
-use crate::checker::Checker;
+use crate::capture_analysis::find_captures;
+use crate::checker::{Checker, IndexAccess, InterpolationStrictness};
+use crate::const_eval::{eval_binary_op, eval_unary_op};
 use crate::context::*;
+use crate::diagnostic::Diagnostic;
 use crate::folder::{self, Folder};
+use crate::infer_ambient::ambient_keyword;
 use crate::infer_pattern::*;
 use crate::key_value_store::KeyValueStore;
+use crate::modules::get_local_names;
 use crate::provenance::Provenance;
 use crate::type_error::TypeError;
 use crate::types::{self, *};
@@ -42,25 +47,35 @@ impl Checker {
         node: &mut Expr,
         ctx: &mut Context,
     ) -> Result<Index, TypeError> {
-        self.with_report(|checker| -> Result<Index, TypeError> {
+        let is_explained = self.start_explain_expr(&node.span);
+
+        self.node_spans.push(node.span);
+        let result = self.with_report(|checker| -> Result<Index, TypeError> {
+            // Set when an operator is overloaded by a user type in which case
+            // `node` is replaced with a call to the overloading method.
+            let mut desugared: Option<ExprKind> = None;
+
             let idx: Index =
                 match &mut node.kind {
-                    ExprKind::Ident(Ident { name, .. }) => checker.get_type(name, ctx)?,
-                    ExprKind::Str(str) => checker.arena.insert(Type::from(TypeKind::Literal(
+                    ExprKind::Ident(Ident { name, span }) => {
+                        checker.check_deprecated_use(name, span, ctx);
+                        checker.add_value_reference(name, *span, false, ctx);
+                        checker.get_type(name, ctx)?
+                    }
+                    ExprKind::Str(str) => checker.from_type_kind(TypeKind::Literal(
                         syntax::Literal::String(str.value.to_owned()),
-                    ))),
-                    ExprKind::Num(num) => checker.arena.insert(Type::from(TypeKind::Literal(
+                    )),
+                    ExprKind::Num(num) => checker.from_type_kind(TypeKind::Literal(
                         syntax::Literal::Number(num.value.to_owned()),
-                    ))),
-                    ExprKind::Bool(bool) => checker.arena.insert(Type::from(TypeKind::Literal(
+                    )),
+                    ExprKind::BigInt(bigint) => checker.from_type_kind(
+                        TypeKind::Literal(syntax::Literal::BigInt(bigint.value.to_owned())),
+                    ),
+                    ExprKind::Bool(bool) => checker.from_type_kind(TypeKind::Literal(
                         syntax::Literal::Boolean(bool.value),
-                    ))),
-                    ExprKind::Null(_) => checker
-                        .arena
-                        .insert(Type::from(TypeKind::Literal(syntax::Literal::Null))),
-                    ExprKind::Undefined(_) => checker
-                        .arena
-                        .insert(Type::from(TypeKind::Literal(syntax::Literal::Undefined))),
+                    )),
+                    ExprKind::Null(_) => checker.from_type_kind(TypeKind::Literal(syntax::Literal::Null)),
+                    ExprKind::Undefined(_) => checker.from_type_kind(TypeKind::Literal(syntax::Literal::Undefined)),
                     ExprKind::Tuple(syntax::Tuple {
                         elements: elems, ..
                     }) => {
@@ -77,18 +92,65 @@ impl Checker {
                     ExprKind::Object(syntax::Object {
                         properties: props, ..
                     }) => {
-                        let mut prop_types: Vec<types::TObjElem> = vec![];
-                        for prop_or_spread in props.iter_mut() {
+                        let mut prop_types: Vec<(usize, types::TObjElem)> = vec![];
+                        let mut indexer_keys: Vec<Primitive> = vec![];
+                        let mut indexer_values: Vec<Index> = vec![];
+
+                        // Methods, i.e. functions whose first param is `self`,
+                        // are inferred after the other props with `Self` bound
+                        // to a provisional type for the object so that they
+                        // can use its props and call each other.
+                        let method_types: Vec<(TPropKey, Index)> = props
+                            .iter()
+                            .filter(|prop| is_method_prop(prop))
+                            .filter_map(|prop| match prop {
+                                PropOrSpread::Prop(expr::Prop::Property { key, .. }) => {
+                                    get_static_prop_key(key)
+                                }
+                                _ => None,
+                            })
+                            .map(|key| (key, checker.new_type_var(None)))
+                            .collect();
+                        let mut self_t: Option<Index> = None;
+                        let mut method_ctx = ctx.clone();
+
+                        for (i, prop_or_spread) in props
+                            .iter_mut()
+                            .enumerate()
+                            .sorted_by_key(|(_, prop)| is_method_prop(prop))
+                        {
+                            let is_method = is_method_prop(prop_or_spread);
+                            let ctx = match is_method {
+                                true => {
+                                    if self_t.is_none() {
+                                        let t = checker.new_self_type(&prop_types, &method_types);
+                                        method_ctx.schemes.insert(
+                                            "Self".to_string(),
+                                            Scheme {
+                                                type_params: None,
+                                                t,
+                                                is_type_param: true,
+                                                opaque_module: None,
+                                            },
+                                        );
+                                        self_t = Some(t);
+                                    }
+                                    &mut method_ctx
+                                }
+                                false => &mut *ctx,
+                            };
                             match prop_or_spread {
                                 PropOrSpread::Spread(_) => todo!(),
                                 PropOrSpread::Prop(prop) => match prop {
-                                    expr::Prop::Shorthand(Ident { name, span: _ }) => {
-                                        prop_types.push(types::TObjElem::Prop(types::TProp {
+                                    expr::Prop::Shorthand(Ident { name, span }) => {
+                                        checker.add_value_reference(name, *span, true, ctx);
+                                        let prop = types::TProp {
                                             name: TPropKey::StringKey(name.to_owned()),
                                             readonly: false,
                                             optional: false,
                                             t: checker.get_type(name, ctx)?,
-                                        }));
+                                        };
+                                        prop_types.push((i, types::TObjElem::Prop(prop)));
                                     }
                                     expr::Prop::Property { key, value } => {
                                         let prop = match key {
@@ -105,19 +167,92 @@ impl Checker {
                                                 t: checker.infer_expression(value, ctx)?,
                                             },
                                             ObjectKey::Number(name) => types::TProp {
-                                                name: TPropKey::StringKey(name.to_owned()),
+                                                name: TPropKey::NumberKey(name.to_owned()),
                                                 readonly: false,
                                                 optional: false,
                                                 t: checker.infer_expression(value, ctx)?,
                                             },
-                                            ObjectKey::Computed(_) => todo!(),
+                                            ObjectKey::Computed(expr) => {
+                                                let key_t = checker.infer_expression(expr, ctx)?;
+                                                let t = checker.infer_expression(value, ctx)?;
+
+                                                // Keys that aren't known statically, e.g.
+                                                // `[key]` where `key` is a `string`, are
+                                                // covered by an indexer instead.
+                                                let key_t = checker.prune(key_t);
+                                                if let TypeKind::Primitive(
+                                                    primitive @ (Primitive::String
+                                                    | Primitive::Number
+                                                    | Primitive::Symbol),
+                                                ) = &checker.arena[key_t].kind
+                                                {
+                                                    if !indexer_keys.contains(primitive) {
+                                                        indexer_keys.push(primitive.to_owned());
+                                                    }
+                                                    indexer_values.push(t);
+                                                    continue;
+                                                }
+
+                                                types::TProp {
+                                                    name: checker.get_computed_key(key_t, expr)?,
+                                                    readonly: false,
+                                                    optional: false,
+                                                    t,
+                                                }
+                                            }
                                         };
-                                        prop_types.push(types::TObjElem::Prop(prop));
+                                        // Methods that come after this one can
+                                        // use its type.
+                                        if is_method {
+                                            if let Some((_, t)) = method_types
+                                                .iter()
+                                                .find(|(name, _)| *name == prop.name)
+                                            {
+                                                checker.unify(ctx, prop.t, *t)?;
+                                            }
+                                        }
+                                        prop_types.push((i, types::TObjElem::Prop(prop)));
                                     }
                                 },
                             }
                         }
-                        checker.new_object_type(&prop_types)
+                        // Props are listed in the order they were written.
+                        prop_types.sort_by_key(|(i, _)| *i);
+                        let mut prop_types: Vec<types::TObjElem> =
+                            prop_types.into_iter().map(|(_, elem)| elem).collect();
+                        if !indexer_keys.is_empty() {
+                            let keys = indexer_keys
+                                .into_iter()
+                                .map(|primitive| checker.new_primitive(primitive))
+                                .collect::<Vec<_>>();
+                            prop_types.push(types::TObjElem::Mapped(types::MappedType {
+                                key: checker.new_type_ref("P", None, &[]),
+                                value: checker.new_union_type(&indexer_values),
+                                target: "P".to_string(),
+                                source: checker.new_union_type(&keys),
+                                optional: None,
+                                check: None,
+                                extends: None,
+                            }));
+                        }
+                        checker.from_type_kind(TypeKind::Object(types::Object {
+                            elems: prop_types,
+                            is_fresh: true,
+                        }))
+                    }
+                    ExprKind::Call(syntax::Call {
+                        callee,
+                        args,
+                        type_args: None,
+                        opt_chain: false,
+                        throws: _,
+                    }) if is_promise_all_with_tuple(callee, args) => {
+                        // The signature of `Promise.all` can't describe that
+                        // each element of the tuple is resolved separately so
+                        // it's special-cased here.
+                        checker.infer_expression(callee, ctx)?;
+                        let tuple_t = checker.infer_expression(&mut args[0], ctx)?;
+                        checker.infer_promise_all(tuple_t)
                     }
                     ExprKind::Call(syntax::Call {
                         callee,
@@ -141,7 +276,7 @@ impl Checker {
                             Some(type_args) => {
                                 let type_args = type_args
                                     .iter_mut()
-                                    .map(|type_arg| checker.infer_type_ann(type_arg, ctx))
+                                    .map(|type_arg| checker.infer_type_arg(type_arg, ctx))
                                     .collect::<Result<Vec<_>, _>>()?;
 
                                 checker.unify_call(ctx, args, Some(&type_args), false, func_idx)?
@@ -191,7 +326,7 @@ impl Checker {
                             Some(type_args) => {
                                 let type_args = type_args
                                     .iter_mut()
-                                    .map(|type_arg| checker.infer_type_ann(type_arg, ctx))
+                                    .map(|type_arg| checker.infer_type_arg(type_arg, ctx))
                                     .collect::<Result<Vec<_>, _>>()?;
 
                                 checker.unify_call(ctx, args, Some(&type_args), true, func_idx)?
@@ -213,6 +348,7 @@ impl Checker {
                         type_params,
                         type_ann: return_type,
                         throws: sig_throws,
+                        captures,
                     }) => {
                         let mut sig_ctx = ctx.clone();
 
@@ -228,55 +364,55 @@ impl Checker {
                         {
                             let type_ann_t = match type_ann {
                                 Some(type_ann) => checker.infer_type_ann(type_ann, &mut sig_ctx)?,
+                                // The `self` params of methods are the type of
+                                // the object they're called on.
+                                None if is_self_pattern(pattern)
+                                    && sig_ctx.schemes.contains_key("Self") =>
+                                {
+                                    checker.new_type_ref("Self", None, &[])
+                                }
                                 None => checker.new_type_var(None),
                             };
-                            pattern.inferred_type = Some(type_ann_t);
 
                             let (assumps, param_t) = checker.infer_pattern(pattern, &sig_ctx)?;
                             checker.unify(&sig_ctx, param_t, type_ann_t)?;
 
+                            // Patterns that are refinements, e.g. `{type: "click", x}`, narrow
+                            // the param's type so that callers can only pass matching values.
+                            let t = match pattern.is_refinement() {
+                                true => param_t,
+                                false => type_ann_t,
+                            };
+                            pattern.inferred_type = Some(t);
+
                             for (name, binding) in assumps {
                                 sig_ctx.non_generic.insert(binding.index);
                                 sig_ctx.values.insert(name.to_owned(), binding);
                             }
 
                             func_params.push(types::FuncParam {
-                                pattern: pattern_to_tpat(pattern, true),
-                                t: type_ann_t,
+                                pattern: pattern_to_tpat(pattern),
+                                t,
                                 optional: *optional,
                             });
                         }
 
+                        let ret_t = return_type
+                            .as_mut()
+                            .map(|return_type| checker.infer_type_ann(return_type, &mut sig_ctx))
+                            .transpose()?;
+
                         let mut body_ctx = sig_ctx.clone();
                         body_ctx.is_async = *is_async;
 
-                        let mut body_t = 'outer: {
-                            match body {
-                                BlockOrExpr::Block(Block { stmts, .. }) => {
-                                    for stmt in stmts.iter_mut() {
-                                        body_ctx = body_ctx.clone();
-                                        checker.infer_statement(stmt, &mut body_ctx)?;
-                                        if let StmtKind::Return(_) = stmt.kind {
-                                            let ret_types: Vec<Index> = find_returns(body)
-                                                .iter()
-                                                .filter_map(|ret| ret.inferred_type)
-                                                .collect();
-
-                                            // TODO: warn about unreachable code.
-                                            break 'outer checker.new_union_type(&ret_types);
-                                        }
-                                    }
+                        // The return type of async functions is a `Promise` so
+                        // it can't be compared with the body's return values.
+                        let body_ret_t = if *is_async { None } else { ret_t };
+                        let mut body_t = checker.infer_func_body(body, &mut body_ctx, body_ret_t)?;
 
-                                    // If we don't encounter a return statement, we assume
-                                    // the return type is `undefined`.
-                                    checker.new_lit_type(&Literal::Undefined)
-                                }
-                                BlockOrExpr::Expr(expr) => {
-                                    // TODO: use `find_returns` here as well
-                                    checker.infer_expression(expr, &mut body_ctx)?
-                                }
-                            }
-                        };
+                        checker.check_escapes(params, body);
+                        checker.check_definite_assignment(body);
+                        *captures = Some(find_captures(params, body));
 
                         let body_throws = find_throws(body);
                         let body_throws = if body_throws.is_empty() {
@@ -305,11 +441,14 @@ impl Checker {
                             (None, None) => None,
                         };
 
-                        let ret_t = match return_type {
-                            Some(return_type) => {
-                                checker.infer_type_ann(return_type, &mut sig_ctx)?
+                        let ret_t = match ret_t {
+                            Some(ret_t) => ret_t,
+                            None => {
+                                // Object literals aren't fresh once they've been
+                                // returned from a function.
+                                body_t = checker.remove_freshness(body_t);
+                                checker.new_type_var(None)
                             }
-                            None => checker.new_type_var(None),
                         };
 
                         // TODO: Make the return type `Promise<body_t, throws>` if the function
@@ -341,9 +480,47 @@ impl Checker {
                         alternate,
                     }) => {
                         let cond_type = checker.infer_expression(cond, ctx)?;
-                        let bool_type = checker.new_primitive(Primitive::Boolean);
-                        checker.unify(ctx, cond_type, bool_type)?;
-                        let consequent_type = checker.infer_block(consequent, ctx)?;
+                        checker.check_condition(ctx, cond, cond_type)?;
+
+                        let mut new_ctx = ctx.clone();
+                        checker.refine_ctx_with_cond(cond, true, &mut new_ctx);
+
+                        let consequent_type = checker.infer_block(consequent, &mut new_ctx)?;
+                        let alternate_type = match alternate {
+                            Some(alternate) => {
+                                let mut alt_ctx = ctx.clone();
+                                checker.refine_ctx_with_cond(cond, false, &mut alt_ctx);
+                                match alternate {
+                                    BlockOrExpr::Block(block) => {
+                                        checker.infer_block(block, &mut alt_ctx)?
+                                    }
+                                    BlockOrExpr::Expr(expr) => {
+                                        checker.infer_expression(expr, &mut alt_ctx)?
+                                    }
+                                }
+                            }
+                            None => checker.new_lit_type(&Literal::Undefined),
+                        };
+                        // checker.unify(ctx, consequent_type, alternate_type)?;
+                        // consequent_type
+                        checker.new_union_type(&[consequent_type, alternate_type])
+                    }
+                    ExprKind::IfLet(IfLet {
+                        pattern,
+                        expr,
+                        consequent,
+                        alternate,
+                    }) => {
+                        let expr_type = checker.infer_expression(expr, ctx)?;
+                        let pat_bindings =
+                            checker.infer_refutable_pattern(ctx, pattern, expr, expr_type)?;
+
+                        let mut new_ctx = ctx.clone();
+                        for (name, binding) in pat_bindings {
+                            new_ctx.values.insert(name, binding);
+                        }
+
+                        let consequent_type = checker.infer_block(consequent, &mut new_ctx)?;
                         let alternate_type = match alternate {
                             Some(alternate) => match alternate {
                                 BlockOrExpr::Block(block) => checker.infer_block(block, ctx)?,
@@ -351,8 +528,6 @@ impl Checker {
                             },
                             None => checker.new_lit_type(&Literal::Undefined),
                         };
-                        // checker.unify(ctx, consequent_type, alternate_type)?;
-                        // consequent_type
                         checker.new_union_type(&[consequent_type, alternate_type])
                     }
                     ExprKind::Member(Member {
@@ -361,7 +536,7 @@ impl Checker {
                         opt_chain,
                     }) => {
                         let mut obj_idx = checker.infer_expression(obj, ctx)?;
-                        let is_mut = is_expr_mutable(ctx, obj)?;
+                        let is_mut = is_expr_mutable(checker, ctx, obj)?;
                         let mut has_undefined = false;
                         if *opt_chain {
                             if let TypeKind::Union(union) = &checker.arena[obj_idx].kind {
@@ -372,16 +547,89 @@ impl Checker {
                         }
 
                         let result = match prop {
-                            MemberProp::Ident(Ident { name, .. }) => {
+                            MemberProp::Ident(Ident { name, span }) => {
                                 let key_idx =
                                     checker.new_lit_type(&Literal::String(name.to_owned()));
-                                checker.get_ident_member(ctx, obj_idx, key_idx, is_mut)?
+                                let index_access = checker.strictness.index_access;
+                                if is_checked_access(ctx, obj, Some(format!("{name:?}"))) {
+                                    checker.strictness.index_access = IndexAccess::Unchecked;
+                                }
+                                let result =
+                                    match checker.get_ident_member(ctx, obj_idx, key_idx, is_mut) {
+                                        // Mutating methods, e.g. `push`, can only be
+                                        // accessed on mutable receivers.
+                                        Err(error) if !is_mut => match checker
+                                            .get_ident_member(ctx, obj_idx, key_idx, true)
+                                        {
+                                            Ok(t) => {
+                                                checker.report_immutable_receiver(ctx, obj, name)?;
+                                                Ok(t)
+                                            }
+                                            Err(_) => Err(error),
+                                        },
+                                        result => result,
+                                    };
+                                checker.strictness.index_access = index_access;
+                                match result {
+                                    Ok(t) => t,
+                                    Err(error) => {
+                                        let names =
+                                            match checker.get_ident_member_names(ctx, obj_idx)? {
+                                                Some(names) if !names.contains(name) => names,
+                                                _ => return Err(error),
+                                            };
+                                        let suggestion = find_similar_name(name, names.iter())
+                                            .map(|similar| format!("did you mean `{similar}`?"));
+                                        // The type of the missing property is
+                                        // unknown so we use a fresh type variable
+                                        // to avoid reporting follow-on errors.
+                                        checker.current_report.diagnostics.push(Diagnostic {
+                                            code: 1004,
+                                            message: format!(
+                                                "Property `{name}` does not exist on {}",
+                                                checker.print_type(&obj_idx)
+                                            ),
+                                            reasons: vec![error],
+                                            span: Some(*span),
+                                            suggestion,
+                                            related: vec![],
+                                        });
+                                        checker.new_type_var(None)
+                                    }
+                                }
                             }
                             MemberProp::Computed(ComputedPropName { expr, .. }) => {
                                 let prop_type = checker.infer_expression(expr, ctx)?;
-                                checker.get_computed_member(ctx, obj_idx, prop_type, is_mut)?
+                                // Accesses that have been checked, e.g. `arr[i]`
+                                // inside of `if (i < arr.length) { ... }`, can't
+                                // be `undefined`.
+                                let index_access = checker.strictness.index_access;
+                                if is_checked_access(ctx, obj, get_access_key(expr)) {
+                                    checker.strictness.index_access = IndexAccess::Unchecked;
+                                }
+                                let result = match checker
+                                    .get_computed_member(ctx, obj_idx, prop_type, is_mut)
+                                {
+                                    Err(error) if !is_mut => match checker
+                                        .get_computed_member(ctx, obj_idx, prop_type, true)
+                                    {
+                                        Ok(t) => {
+                                            let name = match &expr.kind {
+                                                ExprKind::Str(Str { value, .. }) => value.to_owned(),
+                                                _ => format!("[{}]", get_computed_key_name(expr)),
+                                            };
+                                            checker.report_immutable_receiver(ctx, obj, &name)?;
+                                            Ok(t)
+                                        }
+                                        Err(_) => Err(error),
+                                    },
+                                    result => result,
+                                };
+                                checker.strictness.index_access = index_access;
+                                result?
                             }
                         };
+                        let result = checker.bind_self_type(result, obj_idx);
 
                         match *opt_chain && has_undefined {
                             true => {
@@ -406,11 +654,47 @@ impl Checker {
                             false => result,
                         }
                     }
-                    ExprKind::JSXElement(_) => todo!(),
+                    ExprKind::JSXElement(elem) => checker.infer_jsx_element(elem, ctx)?,
                     ExprKind::Assign(Assign { left, op: _, right }) => {
-                        if !is_expr_mutable(ctx, left)? {
-                            return Err(TypeError {
-                                message: "Cannot assign to immutable lvalue".to_string(),
+                        // Immutable bindings without an initializer can be
+                        // assigned once, see `check_definite_assignment`.
+                        let is_uninitialized = match &left.kind {
+                            ExprKind::Ident(Ident { name, .. }) => matches!(
+                                ctx.values.get(name),
+                                Some(Binding { span: Some(span), .. })
+                                    if ctx.uninitialized.contains(span)
+                            ),
+                            _ => false,
+                        };
+                        if !is_uninitialized && !is_expr_mutable(checker, ctx, left)? {
+                            let name = match get_lvalue_root(left) {
+                                Some(Ident { name, .. }) => name,
+                                None => {
+                                    return Err(TypeError {
+                                        message: "Cannot assign to immutable lvalue".to_string(),
+                                    })
+                                }
+                            };
+                            let binding = ctx.get_binding(name)?;
+                            let message = match &left.kind {
+                                ExprKind::Ident(_) => {
+                                    format!("Cannot assign to `{name}` because it isn't mutable")
+                                }
+                                _ => format!(
+                                    "Cannot assign to a property of `{name}` because it isn't mutable"
+                                ),
+                            };
+                            // The diagnostic points to the binding's declaration
+                            // since that's where `mut` needs to be added.
+                            checker.current_report.diagnostics.push(Diagnostic {
+                                code: 1002,
+                                message,
+                                reasons: vec![TypeError {
+                                    message: format!("`{name}` must be declared with `mut`"),
+                                }],
+                                span: binding.span.or(Some(left.span)),
+                                suggestion: None,
+                                related: vec![],
                             });
                         }
 
@@ -418,46 +702,160 @@ impl Checker {
                         let r_t = checker.infer_expression(right, ctx)?;
                         checker.unify(ctx, r_t, l_t)?;
 
+                        if let ExprKind::Member(Member { object, .. }) = &left.kind {
+                            if let Some(obj_t) = object.inferred_type {
+                                let obj_t = checker.prune(obj_t);
+                                let readonly = match &checker.arena[obj_t].kind {
+                                    TypeKind::Array(array) if array.readonly => Some("array"),
+                                    TypeKind::Tuple(tuple) if tuple.readonly => Some("tuple"),
+                                    _ => None,
+                                };
+                                if let Some(kind) = readonly {
+                                    checker.current_report.diagnostics.push(Diagnostic {
+                                        code: 1010,
+                                        message: format!(
+                                            "Cannot assign to an element of a readonly {kind}"
+                                        ),
+                                        reasons: vec![TypeError {
+                                            message: format!(
+                                                "`{}` can't be modified",
+                                                checker.print_type(&obj_t)
+                                            ),
+                                        }],
+                                        span: Some(left.span),
+                                        suggestion: None,
+                                        related: vec![],
+                                    });
+                                }
+                            }
+                        }
+
                         r_t
                     }
                     ExprKind::Binary(Binary { op, left, right }) => {
                         let number = checker.new_primitive(Primitive::Number);
+                        let bigint = checker.new_primitive(Primitive::BigInt);
                         let boolean = checker.new_primitive(Primitive::Boolean);
                         let left_type = checker.infer_expression(left, ctx)?;
-                        let right_type = checker.infer_expression(right, ctx)?;
+                        let right_type = match op {
+                            // The right side of `&&` and `||` is only evaluated
+                            // if the left side doesn't short-circuit.
+                            BinaryOp::And | BinaryOp::Or => {
+                                let mut right_ctx = ctx.clone();
+                                let is_true = matches!(op, BinaryOp::And);
+                                checker.refine_ctx_with_cond(left, is_true, &mut right_ctx);
+                                checker.infer_expression(right, &mut right_ctx)?
+                            }
+                            _ => checker.infer_expression(right, ctx)?,
+                        };
+                        let is_bigint =
+                            checker.is_bigint(left_type) || checker.is_bigint(right_type);
+
+                        let overload = match get_binary_op_method(op) {
+                            Some(method) => checker
+                                .infer_operator_overload(ctx, left_type, method, &[right_type])?
+                                .map(|t| (method, t)),
+                            None => None,
+                        };
+
+                        let const_result = match (
+                            &checker.arena[left_type].kind,
+                            &checker.arena[right_type].kind,
+                        ) {
+                            (TypeKind::Literal(left), TypeKind::Literal(right)) => {
+                                eval_binary_op(op, left, right)
+                            }
+                            _ => None,
+                        };
 
                         match op {
+                            _ if overload.is_some() => {
+                                let (method, t) = overload.unwrap();
+                                desugared =
+                                    Some(desugar_operator(method, left, vec![*right.clone()]));
+                                t
+                            }
+                            _ if const_result.is_some() => {
+                                checker.new_lit_type(&const_result.unwrap())
+                            }
+                            BinaryOp::Plus
+                                if checker.is_string(left_type) || checker.is_string(right_type) =>
+                            {
+                                let string = checker.new_primitive(Primitive::String);
+                                checker.unify(ctx, left_type, string)?;
+                                checker.unify(ctx, right_type, string)?;
+                                string
+                            }
                             BinaryOp::Plus
                             | BinaryOp::Minus
                             | BinaryOp::Times
                             | BinaryOp::Divide
-                            | BinaryOp::Modulo => {
+                            | BinaryOp::Modulo
+                            | BinaryOp::Power => {
                                 match (
                                     &checker.arena[left_type].kind,
                                     &checker.arena[right_type].kind,
                                 ) {
-                                    (
-                                        TypeKind::Literal(Literal::Number(left)),
-                                        TypeKind::Literal(Literal::Number(right)),
-                                    ) => {
-                                        let left = left.parse::<f64>().unwrap();
-                                        let right = right.parse::<f64>().unwrap();
-
-                                        let result = match op {
-                                            BinaryOp::Plus => left + right,
-                                            BinaryOp::Minus => left - right,
-                                            BinaryOp::Times => left * right,
-                                            BinaryOp::Divide => left / right,
-                                            BinaryOp::Modulo => left % right,
-                                            _ => unreachable!(),
-                                        };
-
-                                        checker.new_lit_type(&Literal::Number(result.to_string()))
+                                    (_, _) if is_bigint => {
+                                        if checker.is_number(left_type)
+                                            || checker.is_number(right_type)
+                                        {
+                                            return Err(TypeError {
+                                                message: "Cannot mix bigint and number in arithmetic, use BigInt() or Number() to convert between them".to_string(),
+                                            });
+                                        }
+                                        checker.unify(ctx, left_type, bigint)?;
+                                        checker.unify(ctx, right_type, bigint)?;
+                                        bigint
+                                    }
+                                    (_, _) => {
+                                        checker.unify(ctx, left_type, number)?;
+                                        checker.unify(ctx, right_type, number)?;
+                                        match checker.strictness.numeric_refinements {
+                                            true => checker.refine_arithmetic(
+                                                op, left_type, right_type,
+                                            ),
+                                            false => number,
+                                        }
+                                    }
+                                }
+                            }
+                            BinaryOp::BitwiseAnd
+                            | BinaryOp::BitwiseOr
+                            | BinaryOp::BitwiseXor
+                            | BinaryOp::LeftShift
+                            | BinaryOp::RightShift
+                            | BinaryOp::UnsignedRightShift => {
+                                match (
+                                    &checker.arena[left_type].kind,
+                                    &checker.arena[right_type].kind,
+                                ) {
+                                    (_, _) if is_bigint => {
+                                        if matches!(op, BinaryOp::UnsignedRightShift) {
+                                            return Err(TypeError {
+                                                message: "bigints have no unsigned right shift, use >> instead".to_string(),
+                                            });
+                                        }
+                                        if checker.is_number(left_type)
+                                            || checker.is_number(right_type)
+                                        {
+                                            return Err(TypeError {
+                                                message: "Cannot mix bigint and number in bitwise operations, use BigInt() or Number() to convert between them".to_string(),
+                                            });
+                                        }
+                                        checker.unify(ctx, left_type, bigint)?;
+                                        checker.unify(ctx, right_type, bigint)?;
+                                        bigint
                                     }
                                     (_, _) => {
                                         checker.unify(ctx, left_type, number)?;
                                         checker.unify(ctx, right_type, number)?;
-                                        number
+                                        // Bitwise operators convert their
+                                        // operands to 32-bit ints.
+                                        match checker.strictness.numeric_refinements {
+                                            true => checker.new_primitive(Primitive::Int),
+                                            false => number,
+                                        }
                                     }
                                 }
                             }
@@ -469,22 +867,13 @@ impl Checker {
                                     &checker.arena[left_type].kind,
                                     &checker.arena[right_type].kind,
                                 ) {
-                                    (
-                                        TypeKind::Literal(Literal::Number(left)),
-                                        TypeKind::Literal(Literal::Number(right)),
-                                    ) => {
-                                        let left = left.parse::<f64>().unwrap();
-                                        let right = right.parse::<f64>().unwrap();
-
-                                        let result = match op {
-                                            BinaryOp::GreaterThan => left > right,
-                                            BinaryOp::GreaterThanOrEqual => left >= right,
-                                            BinaryOp::LessThan => left < right,
-                                            BinaryOp::LessThanOrEqual => left <= right,
-                                            _ => unreachable!(),
-                                        };
-
-                                        checker.new_lit_type(&Literal::Boolean(result))
+                                    // Unlike arithmetic, comparing a bigint with
+                                    // a number is allowed.
+                                    (_, _) if is_bigint => {
+                                        let numeric = checker.new_union_type(&[number, bigint]);
+                                        checker.unify(ctx, left_type, numeric)?;
+                                        checker.unify(ctx, right_type, numeric)?;
+                                        boolean
                                     }
                                     (_, _) => {
                                         checker.unify(ctx, left_type, number)?;
@@ -494,40 +883,75 @@ impl Checker {
                                 }
                             }
                             BinaryOp::And | BinaryOp::Or => {
-                                checker.unify(ctx, left_type, boolean)?;
-                                checker.unify(ctx, right_type, boolean)?;
+                                // Operands whose types aren't known yet are
+                                // assumed to be booleans.
+                                for t in [left_type, right_type] {
+                                    let t = checker.prune(t);
+                                    if matches!(checker.arena[t].kind, TypeKind::TypeVar(_)) {
+                                        checker.unify(ctx, t, boolean)?;
+                                    }
+                                }
+
+                                // `a && b` is `a` if `a` is falsy and `a || b`
+                                // is `a` if `a` is truthy, otherwise they're `b`.
+                                let falsy = checker.get_falsy_type(left_type);
+                                let truthy = checker.get_truthy_type(left_type);
+                                let (short_circuit, evaluates_right) = match op {
+                                    BinaryOp::And => (falsy, truthy),
+                                    BinaryOp::Or => (truthy, falsy),
+                                    _ => unreachable!(),
+                                };
+                                match checker.arena[evaluates_right].kind {
+                                    TypeKind::Keyword(Keyword::Never) => short_circuit,
+                                    _ => checker
+                                        .new_simplified_union_type(&[short_circuit, right_type]),
+                                }
+                            }
+                            BinaryOp::In => {
+                                let string = checker.new_primitive(Primitive::String);
+                                let symbol = checker.new_primitive(Primitive::Symbol);
+                                let key = checker.new_union_type(&[string, number, symbol]);
+                                checker.unify(ctx, left_type, key)?;
+
+                                let obj = checker.expand_type(ctx, right_type)?;
+                                if matches!(
+                                    checker.arena[obj].kind,
+                                    TypeKind::Primitive(_)
+                                        | TypeKind::Literal(_)
+                                        | TypeKind::Keyword(_)
+                                ) {
+                                    return Err(TypeError {
+                                        message: format!(
+                                            "The right side of `in` must be an object, got {}",
+                                            checker.print_type(&right_type)
+                                        ),
+                                    });
+                                }
                                 boolean
                             }
                             BinaryOp::Equals | BinaryOp::NotEquals => {
-                                match (
-                                    &checker.arena[left_type].kind,
-                                    &checker.arena[right_type].kind,
-                                ) {
-                                    (
-                                        TypeKind::Literal(Literal::Number(left)),
-                                        TypeKind::Literal(Literal::Number(right)),
-                                    ) => {
+                                match checker.get_equality_result(left_type, right_type) {
+                                    Some((result, reason)) => {
                                         let result = match op {
-                                            BinaryOp::Equals => left == right,
-                                            BinaryOp::NotEquals => left != right,
+                                            BinaryOp::Equals => result,
+                                            BinaryOp::NotEquals => !result,
                                             _ => unreachable!(),
                                         };
 
-                                        checker.new_lit_type(&Literal::Boolean(result))
-                                    }
-                                    (
-                                        TypeKind::Literal(Literal::String(left)),
-                                        TypeKind::Literal(Literal::String(right)),
-                                    ) => {
-                                        let result = match op {
-                                            BinaryOp::Equals => left == right,
-                                            BinaryOp::NotEquals => left != right,
-                                            _ => unreachable!(),
-                                        };
+                                        checker.current_report.diagnostics.push(Diagnostic {
+                                            code: 1011,
+                                            message: format!(
+                                                "This comparison is always {result}"
+                                            ),
+                                            reasons: vec![TypeError { message: reason }],
+                                            span: Some(merge_spans(&left.span, &right.span)),
+                                            suggestion: None,
+                                            related: vec![],
+                                        });
 
                                         checker.new_lit_type(&Literal::Boolean(result))
                                     }
-                                    (_, _) => {
+                                    None => {
                                         let var_a = checker.new_type_var(None);
                                         let var_b = checker.new_type_var(None);
                                         checker.unify(ctx, left_type, var_a)?;
@@ -546,10 +970,42 @@ impl Checker {
                         let boolean = checker.new_primitive(Primitive::Boolean);
                         let arg_type = checker.infer_expression(arg, ctx)?;
 
+                        let overload = match op {
+                            UnaryOp::Minus => {
+                                checker.infer_operator_overload(ctx, arg_type, "neg", &[])?
+                            }
+                            _ => None,
+                        };
+
+                        let const_result = match &checker.arena[arg_type].kind {
+                            TypeKind::Literal(arg) => eval_unary_op(op, arg),
+                            _ => None,
+                        };
+
                         match op {
+                            UnaryOp::Minus if overload.is_some() => {
+                                desugared = Some(desugar_operator("neg", arg, vec![]));
+                                overload.unwrap()
+                            }
+                            _ if const_result.is_some() => {
+                                checker.new_lit_type(&const_result.unwrap())
+                            }
+                            UnaryOp::Minus if checker.is_bigint(arg_type) => {
+                                checker.new_primitive(Primitive::BigInt)
+                            }
                             UnaryOp::Minus => {
                                 checker.unify(ctx, arg_type, number)?;
-                                number
+                                match checker.get_numeric_refinement(arg_type) {
+                                    Some(primitive) if checker.strictness.numeric_refinements => {
+                                        checker.new_primitive(primitive)
+                                    }
+                                    _ => number,
+                                }
+                            }
+                            UnaryOp::Plus if checker.is_bigint(arg_type) => {
+                                return Err(TypeError {
+                                    message: "Unary '+' can't be used with bigint".to_string(),
+                                });
                             }
                             UnaryOp::Plus => {
                                 checker.unify(ctx, arg_type, number)?;
@@ -559,6 +1015,16 @@ impl Checker {
                                 checker.unify(ctx, arg_type, boolean)?;
                                 boolean
                             }
+                            UnaryOp::BitwiseNot if checker.is_bigint(arg_type) => {
+                                checker.new_primitive(Primitive::BigInt)
+                            }
+                            UnaryOp::BitwiseNot => {
+                                checker.unify(ctx, arg_type, number)?;
+                                match checker.strictness.numeric_refinements {
+                                    true => checker.new_primitive(Primitive::Int),
+                                    false => number,
+                                }
+                            }
                         }
                     }
                     ExprKind::Await(Await { arg: expr, throws }) => {
@@ -578,13 +1044,21 @@ impl Checker {
                         // type whenever it's used.
                         let promise_t = checker.new_type_ref("Promise", None, &[inner_t, throws_t]);
                         checker.unify(ctx, expr_t, promise_t)?;
+
+                        // Awaiting a promise that resolves to another promise
+                        // waits for both, e.g. `await p` where `p` is
+                        // `Promise<Promise<T, E1>, E2>` results in `T` and can
+                        // throw `E1 | E2`.
+                        let (inner_t, throws_t) = checker.unwrap_promise(inner_t, throws_t);
                         *throws = Some(throws_t);
 
                         inner_t
                     }
-                    ExprKind::TemplateLiteral(TemplateLiteral { parts: _, exprs: _ }) => {
-                        // QUESTION: Do we want to require that each expr in
-                        // exprs has a .toString() method?
+                    ExprKind::TemplateLiteral(TemplateLiteral { parts: _, exprs }) => {
+                        for expr in exprs.iter_mut() {
+                            let t = checker.infer_expression(expr, ctx)?;
+                            checker.check_interpolation(ctx, expr, t)?;
+                        }
                         checker.new_primitive(Primitive::String)
                     }
                     ExprKind::TaggedTemplateLiteral(TaggedTemplateLiteral {
@@ -594,30 +1068,59 @@ impl Checker {
                     }) => {
                         let tag = checker.infer_expression(tag, ctx)?;
 
-                        let mut args = vec![Expr {
-                            kind: ExprKind::Tuple(syntax::Tuple {
-                                elements: parts
+                        // The tag is called with the string parts of the
+                        // template followed by each of the interpolated exprs.
+                        // If `TemplateStringsArray` is in scope we use it as
+                        // the type of the string parts, otherwise we fallback
+                        // to a tuple of string literals.
+                        let strings_t = match ctx.schemes.get("TemplateStringsArray") {
+                            Some(scheme) => checker.new_type_ref(
+                                "TemplateStringsArray",
+                                Some(scheme.to_owned()),
+                                &[],
+                            ),
+                            None => {
+                                let types = parts
                                     .iter()
                                     .map(|part| {
-                                        let part = Expr {
-                                            kind: ExprKind::Str(Str {
-                                                value: part.value.to_owned(),
-                                                span: Span { start: 0, end: 0 },
-                                            }),
-                                            span: Span { start: 0, end: 0 },
-                                            inferred_type: None,
-                                        };
-                                        ExprOrSpread::Expr(part)
+                                        checker
+                                            .new_lit_type(&Literal::String(part.value.to_owned()))
                                     })
-                                    .collect(),
+                                    .collect::<Vec<_>>();
+                                checker.new_tuple_type(&types)
+                            }
+                        };
+
+                        // `$strings` can't be written by users so it won't
+                        // shadow any of the bindings used by `exprs`.
+                        let strings_name = "$strings";
+                        let mut call_ctx = ctx.clone();
+                        call_ctx.values.insert(
+                            strings_name.to_string(),
+                            Binding {
+                                index: strings_t,
+                                is_mut: false,
+                                span: None,
+                                deprecated: None,
+                            },
+                        );
+
+                        let mut args = vec![Expr {
+                            kind: ExprKind::Ident(Ident {
+                                name: strings_name.to_string(),
+                                span: node.span,
                             }),
-                            span: Span { start: 0, end: 0 },
+                            span: node.span,
                             inferred_type: None,
                         }];
-                        args.extend(exprs.clone());
+                        // The interpolated exprs are moved into `args` and then
+                        // back out again so that they retain their inferred
+                        // types.
+                        args.append(exprs);
 
-                        let (call_result, call_throws) =
-                            checker.unify_call(ctx, &mut args, None, false, tag)?;
+                        let result = checker.unify_call(&mut call_ctx, &mut args, None, false, tag);
+                        *exprs = args.split_off(1);
+                        let (call_result, call_throws) = result?;
 
                         if let Some(call_throws) = call_throws {
                             throws.replace(call_throws);
@@ -630,14 +1133,41 @@ impl Checker {
                         let expr_idx = checker.infer_expression(expr, ctx)?;
                         let mut body_types: Vec<Index> = vec![];
 
-                        for arm in arms.iter_mut() {
-                            let (pat_bindings, pat_idx) =
-                                checker.infer_pattern(&mut arm.pattern, ctx)?;
+                        for i in 0..arms.len() {
+                            // Catch-all arms only match the values that the
+                            // arms before them don't, so the value being
+                            // matched is narrowed to those values, e.g. to
+                            // `never` in `_ => absurd(x)` if the other arms
+                            // are exhaustive.
+                            let unmatched_t = match checker.is_catchall_arm(&arms[i]) {
+                                true => Some(checker.unmatched_type(ctx, &arms[..i], expr_idx)),
+                                false => None,
+                            };
 
-                            // Checks that the pattern is a sub-type of expr
-                            checker.unify(ctx, pat_idx, expr_idx)?;
+                            let arm = &mut arms[i];
+                            let pat_bindings = checker.infer_refutable_pattern(
+                                ctx,
+                                &mut arm.pattern,
+                                expr,
+                                unmatched_t.unwrap_or(expr_idx),
+                            )?;
 
                             let mut new_ctx = ctx.clone();
+                            if let (Some(unmatched_t), ExprKind::Ident(Ident { name, .. })) =
+                                (unmatched_t, &expr.kind)
+                            {
+                                if let Some(binding) = ctx.values.get(name) {
+                                    if !binding.is_mut {
+                                        new_ctx.values.insert(
+                                            name.to_owned(),
+                                            Binding {
+                                                index: unmatched_t,
+                                                ..binding.clone()
+                                            },
+                                        );
+                                    }
+                                }
+                            }
                             for (name, binding) in pat_bindings {
                                 // TODO: Update .env to store bindings so that we can handle
                                 // mutability correctly
@@ -655,11 +1185,7 @@ impl Checker {
                             body_types.push(body_type);
                         }
 
-                        let t0 = checker.prune(body_types[0]);
-                        eprintln!("t0 = {}", checker.print_type(&t0));
-
-                        let t1 = checker.prune(body_types[1]);
-                        eprintln!("t1 = {}", checker.print_type(&t1));
+                        checker.check_match_arms(ctx, arms, expr_idx, node.span);
 
                         checker.new_union_type(&body_types)
                     }
@@ -702,16 +1228,28 @@ impl Checker {
                         throws.replace(checker.infer_expression(arg, ctx)?);
                         checker.new_keyword(Keyword::Never)
                     }
-                    ExprKind::JSXFragment(_) => todo!(),
+                    ExprKind::JSXFragment(fragment) => checker.infer_jsx_fragment(fragment, ctx)?,
                 };
 
+            if let Some(kind) = desugared {
+                node.kind = kind;
+            }
+
             let t = &mut checker.arena[idx];
             t.provenance = Some(Provenance::Expr(Box::new(node.to_owned())));
 
             node.inferred_type = Some(idx);
+            checker.span_index.insert(node.span, idx);
 
             Ok(idx)
-        })
+        });
+        self.node_spans.pop();
+
+        if is_explained {
+            self.end_explain_expr(&result);
+        }
+
+        result
     }
 
     pub fn infer_block(
@@ -722,47 +1260,199 @@ impl Checker {
         let mut new_ctx = ctx.clone();
         let mut result_t = self.new_lit_type(&Literal::Undefined);
 
-        for stmt in &mut block.stmts.iter_mut() {
+        let mut stmts = block.stmts.iter_mut();
+        while let Some(stmt) = stmts.next() {
             result_t = self.infer_statement(stmt, &mut new_ctx)?;
+            // The remaining statements are unreachable so we skip them and
+            // don't include them in the block's type.
+            if self.check_completion(stmt, result_t, stmts.as_slice()) {
+                return Ok(self.new_keyword(Keyword::Never));
+            }
         }
 
         Ok(result_t)
     }
 
+    // Infers the type of the values returned from a function's `body`.  If
+    // the end of a block body is reachable the function implicitly returns
+    // `undefined`.  When `strict_returns` is enabled, this is reported if the
+    // function returns values elsewhere or its return type, `ret_t`, doesn't
+    // include `undefined`.
+    pub fn infer_func_body(
+        &mut self,
+        body: &mut BlockOrExpr,
+        ctx: &mut Context,
+        ret_t: Option<Index>,
+    ) -> Result<Index, TypeError> {
+        let block = match body {
+            BlockOrExpr::Block(block) => block,
+            BlockOrExpr::Expr(expr) => {
+                // TODO: use `find_returns` here as well
+                return self.infer_expression(expr, ctx);
+            }
+        };
+
+        let span = block.span;
+        let mut completes = false;
+        let mut stmts = block.stmts.iter_mut();
+        while let Some(stmt) = stmts.next() {
+            let t = self.infer_statement(stmt, ctx)?;
+            if self.check_completion(stmt, t, stmts.as_slice()) {
+                completes = true;
+                break;
+            }
+        }
+
+        let mut ret_types: Vec<Index> = find_returns(body)
+            .iter()
+            .filter_map(|ret| ret.inferred_type)
+            .collect();
+
+        if !completes {
+            let undefined = self.new_lit_type(&Literal::Undefined);
+            let is_reported = self.strictness.strict_returns
+                && match ret_t {
+                    Some(ret_t) => self.unify(ctx, undefined, ret_t).is_err(),
+                    None => !ret_types.is_empty(),
+                };
+
+            if is_reported {
+                self.current_report.diagnostics.push(Diagnostic {
+                    code: 1006,
+                    message: "Not all code paths return a value".to_string(),
+                    reasons: vec![TypeError {
+                        message: "The end of the function is reachable and implicitly returns `undefined`".to_string(),
+                    }],
+                    span: Some(span),
+                    suggestion: None,
+                    related: vec![],
+                });
+            } else {
+                ret_types.push(undefined);
+            }
+        }
+
+        Ok(self.new_union_type(&ret_types))
+    }
+
+    // Returns true if control flow can't continue past `stmt`, which is the
+    // case for `return` statements and expressions of type `never`, e.g.
+    // `throw`.  If that's the case, the statements after it, `rest`, are
+    // reported as unreachable.
+    pub fn check_completion(&mut self, stmt: &Stmt, t: Index, rest: &[Stmt]) -> bool {
+        let t = self.prune(t);
+        let is_never = matches!(self.arena[t].kind, TypeKind::Keyword(Keyword::Never));
+        let reason = match &stmt.kind {
+            StmtKind::Return(_) => "a `return` statement",
+            StmtKind::Expr(ExprStmt {
+                expr:
+                    Expr {
+                        kind: ExprKind::Throw(_),
+                        ..
+                    },
+            }) => "a `throw` expression",
+            StmtKind::Expr(_) if is_never => "an expression of type `never`",
+            _ => return false,
+        };
+
+        if let (Some(first), Some(last)) = (rest.first(), rest.last()) {
+            self.current_report.diagnostics.push(Diagnostic {
+                code: 1005,
+                message: "Unreachable code".to_string(),
+                reasons: vec![TypeError {
+                    message: format!("Statements after {reason} will never be executed"),
+                }],
+                span: Some(Span {
+                    start: first.span.start,
+                    end: last.span.end,
+                }),
+                suggestion: None,
+                related: vec![],
+            });
+        }
+
+        true
+    }
+
+    pub fn infer_prop_name(
+        &mut self,
+        name: &mut PropName,
+        ctx: &mut Context,
+    ) -> Result<TPropKey, TypeError> {
+        match name {
+            PropName::Ident(ident) => Ok(TPropKey::StringKey(ident.name.to_owned())),
+            PropName::Computed(expr) => self.infer_computed_key(expr, ctx),
+        }
+    }
+
+    // Computed keys must have a type that's known statically, e.g. a unique
+    // symbol like `Symbol.iterator` or a string/number literal.
+    pub fn infer_computed_key(
+        &mut self,
+        expr: &mut Expr,
+        ctx: &mut Context,
+    ) -> Result<TPropKey, TypeError> {
+        let t = self.infer_expression(expr, ctx)?;
+        self.get_computed_key(t, expr)
+    }
+
+    fn get_computed_key(&mut self, t: Index, expr: &Expr) -> Result<TPropKey, TypeError> {
+        let t = self.prune(t);
+        match &self.arena[t].kind {
+            TypeKind::UniqueSymbol(UniqueSymbol { id }) => Ok(TPropKey::SymbolKey(TSymbolKey {
+                id: *id,
+                name: get_computed_key_name(expr),
+            })),
+            TypeKind::Literal(Literal::String(name)) => Ok(TPropKey::StringKey(name.to_owned())),
+            TypeKind::Literal(Literal::Number(name)) => Ok(TPropKey::NumberKey(name.to_owned())),
+            _ => Err(TypeError {
+                message: format!(
+                    "{} can't be used as a computed key, computed keys must be unique symbols or string/number literals",
+                    self.print_type(&t)
+                ),
+            }),
+        }
+    }
+
     pub fn infer_type_ann(
         &mut self,
         type_ann: &mut TypeAnn,
         ctx: &mut Context,
     ) -> Result<Index, TypeError> {
+        self.with_span(type_ann.span, |checker| {
+            checker.infer_type_ann_with_span(type_ann, ctx)
+        })
+    }
+
+    fn infer_type_ann_with_span(
+        &mut self,
+        type_ann: &mut TypeAnn,
+        ctx: &mut Context,
+    ) -> Result<Index, TypeError> {
+        let span = type_ann.span;
         let idx = match &mut type_ann.kind {
             TypeAnnKind::Function(func_type) => {
                 let function = self.infer_function_type(func_type, ctx)?;
-                self.arena.insert(Type::from(TypeKind::Function(function)))
+                self.from_type_kind(TypeKind::Function(function))
             }
 
             TypeAnnKind::NumLit(value) => {
-                self.arena
-                    .insert(Type::from(TypeKind::Literal(syntax::Literal::Number(
-                        value.to_owned(),
-                    ))))
+                self.from_type_kind(TypeKind::Literal(syntax::Literal::Number(value.to_owned())))
             }
             TypeAnnKind::StrLit(value) => {
-                self.arena
-                    .insert(Type::from(TypeKind::Literal(syntax::Literal::String(
-                        value.to_owned(),
-                    ))))
-            }
-            TypeAnnKind::BoolLit(value) => {
-                self.arena
-                    .insert(Type::from(TypeKind::Literal(syntax::Literal::Boolean(
-                        value.to_owned(),
-                    ))))
+                self.from_type_kind(TypeKind::Literal(syntax::Literal::String(value.to_owned())))
             }
+            TypeAnnKind::BigIntLit(value) => self.new_lit_type(&Literal::BigInt(value.to_owned())),
+            TypeAnnKind::BoolLit(value) => self.from_type_kind(TypeKind::Literal(
+                syntax::Literal::Boolean(value.to_owned()),
+            )),
 
             TypeAnnKind::Number => self.new_primitive(Primitive::Number),
+            TypeAnnKind::BigInt => self.new_primitive(Primitive::BigInt),
             TypeAnnKind::Boolean => self.new_primitive(Primitive::Boolean),
             TypeAnnKind::String => self.new_primitive(Primitive::String),
             TypeAnnKind::Symbol => self.new_primitive(Primitive::Symbol),
+            TypeAnnKind::UniqueSymbol => self.new_unique_symbol(),
 
             TypeAnnKind::Null => self.new_lit_type(&Literal::Null),
             TypeAnnKind::Undefined => self.new_lit_type(&Literal::Undefined),
@@ -779,13 +1469,16 @@ impl Checker {
             TypeAnnKind::Object(obj) => {
                 let mut props: Vec<types::TObjElem> = Vec::new();
                 let mut obj_ctx = ctx.clone();
+                // `Self` is bound to the type of the receiver when the
+                // object's members are accessed, see `bind_self_type`.
                 let self_idx = self.new_type_var(None);
                 obj_ctx.schemes.insert(
                     "Self".to_string(),
                     Scheme {
                         type_params: None,
                         t: self_idx,
-                        is_type_param: false,
+                        is_type_param: true,
+                        opaque_module: None,
                     },
                 );
                 for elem in obj.iter_mut() {
@@ -806,6 +1499,7 @@ impl Checker {
                                 type_params: None,
                                 t: source,
                                 is_type_param: false,
+                                opaque_module: None,
                             };
                             type_ctx.schemes.insert(target.to_owned(), scheme);
 
@@ -837,32 +1531,37 @@ impl Checker {
                             }));
                         }
                         ObjectProp::Method(method) => {
+                            // The method's type params have to be in scope
+                            // before its params and return type are inferred.
+                            let mut method_ctx = obj_ctx.clone();
+                            let type_params =
+                                self.infer_type_params(&mut method.type_params, &mut method_ctx)?;
+
                             let params = method
                                 .params
                                 .iter_mut()
                                 .map(|param| {
                                     let t =
-                                        self.infer_type_ann(&mut param.type_ann, &mut obj_ctx)?;
+                                        self.infer_type_ann(&mut param.type_ann, &mut method_ctx)?;
                                     Ok(types::FuncParam {
-                                        pattern: pattern_to_tpat(&param.pattern, true),
+                                        pattern: pattern_to_tpat(&param.pattern),
                                         t,
                                         optional: param.optional,
                                     })
                                 })
                                 .collect::<Result<Vec<_>, _>>()?;
 
-                            let type_params =
-                                self.infer_type_params(&mut method.type_params, &mut obj_ctx)?;
-
-                            let ret = self.infer_type_ann(&mut method.ret, &mut obj_ctx)?;
+                            let ret = self.infer_type_ann(&mut method.ret, &mut method_ctx)?;
 
                             let throws = match &mut method.throws {
-                                Some(throws) => Some(self.infer_type_ann(throws, &mut obj_ctx)?),
+                                Some(throws) => Some(self.infer_type_ann(throws, &mut method_ctx)?),
                                 None => None,
                             };
 
+                            let name = self.infer_prop_name(&mut method.name, &mut obj_ctx)?;
+
                             props.push(types::TObjElem::Method(types::TMethod {
-                                name: TPropKey::StringKey(method.name.to_owned()),
+                                name,
                                 mutates: method.mutates,
                                 function: types::Function {
                                     params,
@@ -887,7 +1586,7 @@ impl Checker {
                             // TODO: create an `infer_func_param` function
                             let t = self.infer_type_ann(&mut param.type_ann, &mut obj_ctx)?;
                             let param = types::FuncParam {
-                                pattern: pattern_to_tpat(&param.pattern, true),
+                                pattern: pattern_to_tpat(&param.pattern),
                                 t,
                                 optional: param.optional,
                             };
@@ -899,7 +1598,7 @@ impl Checker {
                         }
                         ObjectProp::Prop(prop) => {
                             props.push(types::TObjElem::Prop(types::TProp {
-                                name: TPropKey::StringKey(prop.name.to_owned()),
+                                name: self.infer_prop_name(&mut prop.name, &mut obj_ctx)?,
                                 readonly: prop.readonly,
                                 optional: prop.optional,
                                 t: self.infer_type_ann(&mut prop.type_ann, &mut obj_ctx)?,
@@ -915,6 +1614,15 @@ impl Checker {
                 }
                 self.new_object_type(&props)
             }
+            TypeAnnKind::TypeRef(name, None)
+                if (name == "int" || name == "float") && !ctx.schemes.contains_key(name) =>
+            {
+                match (self.strictness.numeric_refinements, name.as_str()) {
+                    (true, "int") => self.new_primitive(Primitive::Int),
+                    (true, _) => self.new_primitive(Primitive::Float),
+                    (false, _) => self.new_primitive(Primitive::Number),
+                }
+            }
             TypeAnnKind::TypeRef(name, type_args) if name == "Array" => match type_args {
                 Some(type_args) => {
                     let t = self.infer_type_ann(&mut type_args[0], ctx)?;
@@ -927,11 +1635,18 @@ impl Checker {
                 }
             },
             TypeAnnKind::TypeRef(name, type_args) => {
+                // The name is always at the start of the type ref.
+                let name_span = Span {
+                    start: span.start,
+                    end: span.start + name.len(),
+                };
+                self.add_type_reference(name, name_span, ctx);
+
                 let type_args = match type_args {
                     Some(type_args) => {
                         let mut type_args_idxs = Vec::new();
                         for type_arg in type_args.iter_mut() {
-                            type_args_idxs.push(self.infer_type_ann(type_arg, ctx)?);
+                            type_args_idxs.push(self.infer_type_arg(type_arg, ctx)?);
                         }
                         type_args_idxs
                     }
@@ -955,26 +1670,8 @@ impl Checker {
                     });
                 }
 
-                // Contraints can reference other type params so we need make
-                // sure that definitions for each type param are in scope where
-                // each type param is defined to be the corresponding type arg.
-                let mut sig_ctx = ctx.clone();
-                for (param, arg) in type_params.iter().zip(type_args.iter()) {
-                    sig_ctx.schemes.insert(
-                        param.name.clone(),
-                        Scheme {
-                            type_params: None,
-                            t: *arg,
-                            is_type_param: false,
-                        },
-                    );
-                }
-
-                for (param, arg) in type_params.iter().zip(type_args.iter()) {
-                    if let Some(constraint) = param.constraint {
-                        self.unify(&sig_ctx, *arg, constraint)?;
-                    }
-                }
+                self.check_type_arg_kinds(ctx, &type_params, &type_args)?;
+                self.check_type_arg_constraints(ctx, &type_params, &type_args);
 
                 // NOTE: If the scheme we get was created from a type param
                 // we can't use it as the new type ref's scheme because it
@@ -998,14 +1695,30 @@ impl Checker {
                 for type_ann in types.iter_mut() {
                     idxs.push(self.infer_type_ann(type_ann, ctx)?);
                 }
-                self.new_intersection_type(&idxs)
+                let t = self.new_intersection_type(&idxs);
+                if self.strictness.warn_on_empty_intersections {
+                    self.check_empty_intersection(t, &idxs, span);
+                }
+                t
             }
             TypeAnnKind::Tuple(types) => {
                 let mut idxs = Vec::new();
+                let mut labels = Vec::new();
                 for type_ann in types.iter_mut() {
+                    if let TypeAnnKind::Labeled(label, _) = &type_ann.kind {
+                        labels.push(label.to_owned());
+                    }
                     idxs.push(self.infer_type_ann(type_ann, ctx)?);
                 }
-                self.new_tuple_type(&idxs)
+                match labels.is_empty() {
+                    true => self.new_tuple_type(&idxs),
+                    false => self.new_labeled_tuple_type(&idxs, &labels),
+                }
+            }
+            TypeAnnKind::Labeled(_, type_ann) => self.infer_type_ann(type_ann, ctx)?,
+            TypeAnnKind::Readonly(type_ann) => {
+                let idx = self.infer_type_ann(type_ann, ctx)?;
+                self.new_readonly_type(idx)
             }
             TypeAnnKind::Rest(rest) => {
                 let idx = self.infer_type_ann(rest, ctx)?;
@@ -1020,10 +1733,18 @@ impl Checker {
                 let index_idx = self.infer_type_ann(index_type, ctx)?;
                 self.new_indexed_access_type(obj_idx, index_idx)
             }
-            TypeAnnKind::TypeOf(arg) => {
-                let arg = ctx.values.get(&arg.name).unwrap();
-                arg.index
-            }
+            TypeAnnKind::TypeOf(expr) => match &expr.kind {
+                ExprKind::Ident(Ident { name, .. }) => ctx.get_binding(name)?.index,
+                // Qualified identifiers are resolved by looking up each
+                // member in turn, e.g. `typeof foo.bar.baz`.
+                ExprKind::Member(_) => self.infer_expression(expr, ctx)?,
+                _ => {
+                    return Err(TypeError {
+                        message: "typeof can only be used with identifiers and member paths"
+                            .to_string(),
+                    })
+                }
+            },
             // TODO: Create types for all of these
             TypeAnnKind::KeyOf(type_ann) => {
                 let t = self.infer_type_ann(type_ann, ctx)?;
@@ -1052,6 +1773,7 @@ impl Checker {
                         type_params: None,
                         t: tp,
                         is_type_param: false,
+                        opaque_module: None,
                     };
                     cond_ctx.schemes.insert(infer.name, scheme);
                     // QUESTION: Do we need to do something with ctx.non_generic here?
@@ -1099,18 +1821,30 @@ impl Checker {
                     BinaryOp::Times => TBinaryOp::Mul,
                     BinaryOp::Divide => TBinaryOp::Div,
                     BinaryOp::Modulo => TBinaryOp::Mod,
-                    BinaryOp::Equals => todo!(),
-                    BinaryOp::NotEquals => todo!(),
-                    BinaryOp::LessThan => todo!(),
-                    BinaryOp::LessThanOrEqual => todo!(),
-                    BinaryOp::GreaterThan => todo!(),
-                    BinaryOp::GreaterThanOrEqual => todo!(),
-                    BinaryOp::Or => todo!(),
-                    BinaryOp::And => todo!(),
+                    // The parser only produces the operators above in types.
+                    BinaryOp::Power
+                    | BinaryOp::Equals
+                    | BinaryOp::NotEquals
+                    | BinaryOp::LessThan
+                    | BinaryOp::LessThanOrEqual
+                    | BinaryOp::GreaterThan
+                    | BinaryOp::GreaterThanOrEqual
+                    | BinaryOp::In
+                    | BinaryOp::Or
+                    | BinaryOp::And
+                    | BinaryOp::BitwiseAnd
+                    | BinaryOp::BitwiseOr
+                    | BinaryOp::BitwiseXor
+                    | BinaryOp::LeftShift
+                    | BinaryOp::RightShift
+                    | BinaryOp::UnsignedRightShift => {
+                        return Err(TypeError {
+                            message: format!("operator {op:?} not supported in types"),
+                        })
+                    }
                 };
 
-                self.arena
-                    .insert(Type::from(TypeKind::Binary(BinaryT { op, left, right })))
+                self.from_type_kind(TypeKind::Binary(BinaryT { op, left, right }))
             }
         };
 
@@ -1118,6 +1852,14 @@ impl Checker {
         t.provenance = Some(Provenance::TypeAnn(Box::new(type_ann.to_owned())));
 
         type_ann.inferred_type = Some(idx);
+        self.span_index.insert(type_ann.span, idx);
+        if let TypeAnnKind::TypeRef(name, _) = &type_ann.kind {
+            let name_span = Span {
+                start: type_ann.span.start,
+                end: type_ann.span.start + name.len(),
+            };
+            self.add_type_ref_type(idx, name_span);
+        }
 
         Ok(idx)
     }
@@ -1146,7 +1888,7 @@ impl Checker {
                 let t = self.infer_type_ann(&mut param.type_ann, &mut sig_ctx)?;
 
                 Ok(types::FuncParam {
-                    pattern: pattern_to_tpat(&param.pattern, true),
+                    pattern: pattern_to_tpat(&param.pattern),
                     t,
                     optional: param.optional,
                 })
@@ -1173,7 +1915,8 @@ impl Checker {
         statement: &mut Stmt,
         ctx: &mut Context,
     ) -> Result<Index, TypeError> {
-        self.with_report(|checker| -> Result<Index, TypeError> {
+        self.node_spans.push(statement.span);
+        let result = self.with_report(|checker| -> Result<Index, TypeError> {
             let t = match &mut statement.kind {
                 StmtKind::Expr(ExprStmt { expr }) => checker.infer_expression(expr, ctx)?,
                 StmtKind::For(ForStmt { left, right, body }) => {
@@ -1194,7 +1937,6 @@ impl Checker {
                 }
                 StmtKind::Return(ReturnStmt { arg: expr }) => {
                     // TODO: handle multiple return statements
-                    // TODO: warn about unreachable code after a return statement
                     match expr {
                         Some(expr) => checker.infer_expression(expr, ctx)?,
                         None => {
@@ -1203,21 +1945,237 @@ impl Checker {
                         }
                     }
                 }
-                StmtKind::Decl(decl) => match &mut decl.kind {
-                    DeclKind::TypeDecl(decl) => checker.infer_type_decl(decl, ctx)?,
-                    DeclKind::VarDecl(decl) => {
-                        checker.infer_var_decl(decl, ctx)?;
-                        checker.new_lit_type(&Literal::Undefined)
+                StmtKind::LetElse(LetElseStmt {
+                    pattern,
+                    expr,
+                    alternate,
+                }) => {
+                    let expr_type = checker.infer_expression(expr, ctx)?;
+                    let pat_bindings =
+                        checker.infer_refutable_pattern(ctx, pattern, expr, expr_type)?;
+
+                    // The bindings aren't in scope in the `else` block and
+                    // it must not fall through since they'd be uninitialized.
+                    let alternate_type = checker.infer_block(alternate, ctx)?;
+                    let alternate_type = checker.prune(alternate_type);
+                    let returns = matches!(
+                        alternate.stmts.last(),
+                        Some(Stmt {
+                            kind: StmtKind::Return(_),
+                            ..
+                        })
+                    );
+                    let throws = matches!(
+                        checker.arena[alternate_type].kind,
+                        TypeKind::Keyword(Keyword::Never)
+                    );
+                    if !returns && !throws {
+                        return Err(TypeError {
+                            message: "The else block of a let-else statement must return or throw"
+                                .to_string(),
+                        });
                     }
-                    // DeclKind::ClassDecl(_) => todo!(),
-                    // DeclKind::StructDecl(_) => todo!(),
-                },
-            };
 
-            statement.inferred_type = Some(t);
+                    for (name, binding) in pat_bindings {
+                        ctx.values.insert(name, binding);
+                    }
+
+                    checker.new_lit_type(&Literal::Undefined)
+                }
+                StmtKind::Import(_) => {
+                    return Err(TypeError {
+                        message: "`import` can only be used at the top-level".to_string(),
+                    });
+                }
+                StmtKind::Export(_) => {
+                    return Err(TypeError {
+                        message: "`export` can only be used at the top-level".to_string(),
+                    });
+                }
+                StmtKind::Decl(Decl { kind, attrs, .. }) => match kind {
+                    DeclKind::GlobalDecl(_) => {
+                        return Err(TypeError {
+                            message: "`declare global` can only be used at the top-level"
+                                .to_string(),
+                        });
+                    }
+                    DeclKind::FnDecl(_) | DeclKind::ClassDecl(_) | DeclKind::ModuleDecl(_) => {
+                        return Err(TypeError {
+                            message: format!(
+                                "`declare {}` can only be used at the top-level",
+                                ambient_keyword(kind)
+                            ),
+                        });
+                    }
+                    DeclKind::TypeDecl(decl) => {
+                        checker.check_attrs(attrs);
+                        checker.infer_type_decl(decl, ctx)?
+                    }
+                    DeclKind::VarDecl(decl) => {
+                        checker.check_attrs(attrs);
+                        let bindings = checker.infer_local_var_decl(decl, ctx)?;
+                        mark_deprecated(ctx, &bindings, attrs);
+                        checker.new_lit_type(&Literal::Undefined)
+                    } // DeclKind::ClassDecl(_) => todo!(),
+                      // DeclKind::StructDecl(_) => todo!(),
+                },
+            };
+
+            statement.inferred_type = Some(t);
 
             Ok(t)
-        })
+        });
+        self.node_spans.pop();
+
+        result
+    }
+
+    // Infers the bindings introduced by `decl` and gives them provisional
+    // types so that they can be referenced before `decl` has been inferred,
+    // e.g. by recursive functions.
+    fn infer_provisional_bindings(
+        &mut self,
+        decl: &mut VarDecl,
+        ctx: &Context,
+    ) -> Result<Assump, TypeError> {
+        let (bindings, _) = self.infer_pattern(&mut decl.pattern, ctx)?;
+
+        // The type annotation, if there is one, is checked against the
+        // initializer's type so there's no need for a provisional type.
+        if let (PatternKind::Ident(BindingIdent { name, .. }), Some(init), None) =
+            (&decl.pattern.kind, &decl.expr, &decl.type_ann)
+        {
+            if let (Some(binding), Some(t)) = (bindings.get(name), self.new_provisional_type(init))
+            {
+                self.unify(ctx, binding.index, t)?;
+            }
+        }
+
+        Ok(bindings)
+    }
+
+    // Generalizes the functions in `bindings`, including those inside of
+    // objects and tuples.  Bindings whose types contain type vars that occur in
+    // `non_generic` are left in `bindings` to be generalized later.
+    fn generalize_bindings(
+        &mut self,
+        ctx: &Context,
+        bindings: &mut Vec<Binding>,
+        non_generic: &im::HashSet<Index>,
+    ) -> Result<(), TypeError> {
+        let mut deferred = vec![];
+        for binding in bindings.drain(..) {
+            let pruned_index = self.prune(binding.index);
+            let type_vars = find_type_vars(&mut self.arena, &pruned_index);
+            if type_vars.iter().any(|t| self.occurs_in(*t, non_generic)) {
+                deferred.push(binding);
+                continue;
+            }
+            let gen_index = generalize_type(self, pruned_index);
+            if gen_index != pruned_index {
+                self.bind(ctx, binding.index, gen_index)?;
+            }
+        }
+        *bindings = deferred;
+
+        Ok(())
+    }
+
+    // Object literals are given a provisional type with the same keys as the
+    // literal so that its methods can call one another through the binding
+    // it's assigned to, e.g. `obj.fact(n - 1)`.
+    fn new_provisional_type(&mut self, expr: &Expr) -> Option<Index> {
+        let props = match &expr.kind {
+            ExprKind::Object(syntax::Object { properties }) => properties,
+            _ => return None,
+        };
+
+        let mut elems: Vec<TObjElem> = vec![];
+        for prop in props {
+            let (name, t) = match prop {
+                PropOrSpread::Prop(expr::Prop::Shorthand(Ident { name, .. })) => (
+                    TPropKey::StringKey(name.to_owned()),
+                    self.new_type_var(None),
+                ),
+                PropOrSpread::Prop(expr::Prop::Property { key, value }) => {
+                    // Computed keys aren't known until they've been inferred.
+                    let name = get_static_prop_key(key)?;
+                    let t = match self.new_provisional_type(value) {
+                        Some(t) => t,
+                        None => self.new_type_var(None),
+                    };
+                    (name, t)
+                }
+                PropOrSpread::Spread(_) => return None,
+            };
+            elems.push(TObjElem::Prop(TProp {
+                name,
+                t,
+                optional: false,
+                readonly: false,
+            }));
+        }
+
+        Some(self.new_object_type(&elems))
+    }
+
+    // The provisional type of an object literal's `self` while its methods
+    // are inferred.  Its other props have already been inferred so only the
+    // methods' types are unknown until they're inferred.
+    fn new_self_type(
+        &mut self,
+        prop_types: &[(usize, TObjElem)],
+        method_types: &[(TPropKey, Index)],
+    ) -> Index {
+        let mut elems: Vec<TObjElem> = prop_types.iter().map(|(_, elem)| elem.to_owned()).collect();
+        for (name, t) in method_types {
+            elems.push(TObjElem::Prop(TProp {
+                name: name.to_owned(),
+                t: *t,
+                optional: false,
+                readonly: false,
+            }));
+        }
+        self.new_object_type(&elems)
+    }
+
+    // Variable declarations inside of blocks whose initializers are functions
+    // or objects can refer to their own bindings, e.g.
+    // `let fact = fn (n) => if (n == 0) { 1 } else { n * fact(n - 1) }`.
+    // The provisional types for these bindings are non-generic while the
+    // initializer is being inferred.  Afterwards they're removed from
+    // `non_generic` so that the bindings are generalized when they're used.
+    fn infer_local_var_decl(
+        &mut self,
+        decl: &mut VarDecl,
+        ctx: &mut Context,
+    ) -> Result<Assump, TypeError> {
+        let is_recursive = matches!(
+            &decl.expr,
+            Some(Expr {
+                kind: ExprKind::Function(_) | ExprKind::Object(_),
+                ..
+            })
+        );
+        if decl.is_declare || !is_recursive {
+            return self.infer_var_decl(decl, ctx);
+        }
+
+        let prebindings = self.infer_provisional_bindings(decl, ctx)?;
+        for (name, binding) in &prebindings {
+            ctx.non_generic.insert(binding.index);
+            ctx.values.insert(name.to_owned(), binding.clone());
+        }
+
+        let bindings = self.infer_var_decl(decl, ctx)?;
+
+        for (name, binding) in &bindings {
+            let prebinding = &prebindings[name];
+            self.unify(ctx, binding.index, prebinding.index)?;
+            ctx.non_generic.remove(&prebinding.index);
+        }
+
+        Ok(bindings)
     }
 
     pub fn infer_var_decl(
@@ -1239,7 +2197,19 @@ impl Checker {
         match (is_declare, init, type_ann) {
             (false, Some(init), type_ann) => {
                 let init_idx = self.infer_expression(init, ctx)?;
-                let tpat = pattern_to_tpat(pattern, false);
+
+                // Classes live in both the value and type namespaces, e.g.
+                // `let Foo = class {...}` can be used as `let foo: Foo = new Foo()`.
+                if let (PatternKind::Ident(BindingIdent { name, span, .. }), ExprKind::Class(_)) =
+                    (&pattern.kind, &init.kind)
+                {
+                    if let Some(scheme) = self.get_instance_scheme(init_idx) {
+                        ctx.schemes.insert(name.to_owned(), scheme);
+                        self.add_type_decl(name, *span, ctx);
+                    }
+                }
+
+                let tpat = pattern_to_tpat(pattern);
                 let mutability = check_mutability(ctx, &tpat, init)?;
 
                 let idx = match type_ann {
@@ -1264,6 +2234,11 @@ impl Checker {
                         type_ann_idx
                     }
                     None => {
+                        // Object literals aren't fresh once they've been
+                        // assigned to a binding, e.g. `let p = {x: 5, y: 10}`
+                        // can be assigned to `{x: number}` later on.
+                        let init_idx = self.remove_freshness(init_idx);
+
                         // Results in bindings introduced by the LHS pattern
                         // having their types inferred.
                         // It's okay for pat_type to be the super type here
@@ -1286,9 +2261,26 @@ impl Checker {
 
                 Ok(pat_bindings)
             }
+            // The binding must be assigned before it's used, see
+            // `check_definite_assignment`.
+            (false, None, Some(type_ann)) if matches!(pattern.kind, PatternKind::Ident(_)) => {
+                let idx = self.infer_type_ann(type_ann, ctx)?;
+
+                self.unify(ctx, idx, pat_type)?;
+
+                for (name, binding) in &pat_bindings {
+                    if !binding.is_mut {
+                        ctx.uninitialized.extend(binding.span);
+                    }
+                    ctx.values.insert(name.clone(), binding.clone());
+                }
+
+                pattern.inferred_type = Some(idx);
+
+                Ok(pat_bindings)
+            }
             (false, None, _) => Err(TypeError {
-                message: "Variable declarations not using `declare` must have an initializer"
-                    .to_string(),
+                message: "Variable declarations without an initializer must be a single identifier with a type annotation".to_string(),
             }),
             (true, None, Some(type_ann)) => {
                 let idx = self.infer_type_ann(type_ann, ctx)?;
@@ -1312,6 +2304,45 @@ impl Checker {
         }
     }
 
+    // Adds the types and values from a `declare global` block to `ctx`.  Types
+    // are inferred first so that values can reference them.
+    fn infer_global_decl(
+        &mut self,
+        decl: &mut GlobalDecl,
+        ctx: &mut Context,
+    ) -> Result<(), TypeError> {
+        for decl in &mut decl.decls {
+            match &mut decl.kind {
+                DeclKind::TypeDecl(type_decl) => {
+                    self.check_attrs(&decl.attrs);
+                    self.infer_type_decl(type_decl, ctx)?;
+                }
+                DeclKind::ClassDecl(class_decl) => {
+                    self.add_class_placeholder(class_decl, ctx)?;
+                }
+                _ => (),
+            }
+        }
+
+        for decl in &mut decl.decls {
+            let Decl { kind, attrs, .. } = decl;
+            let bindings = match kind {
+                DeclKind::TypeDecl(_) | DeclKind::GlobalDecl(_) => continue,
+                DeclKind::VarDecl(var_decl) => {
+                    self.check_attrs(attrs);
+                    self.infer_var_decl(var_decl, ctx)?
+                }
+                DeclKind::FnDecl(_) | DeclKind::ClassDecl(_) | DeclKind::ModuleDecl(_) => {
+                    self.check_attrs(attrs);
+                    self.infer_ambient_decl(kind, ctx)?
+                }
+            };
+            mark_deprecated(ctx, &bindings, attrs);
+        }
+
+        Ok(())
+    }
+
     pub fn infer_type_decl(
         &mut self,
         decl: &mut TypeDecl,
@@ -1319,14 +2350,30 @@ impl Checker {
     ) -> Result<Index, TypeError> {
         let TypeDecl {
             name,
+            name_span,
             type_ann,
             type_params,
+            is_opaque,
         } = decl;
 
+        self.add_type_decl(name, *name_span, ctx);
+
         // NOTE: We clone `ctx` so that type params don't escape the signature
         let mut sig_ctx = ctx.clone();
 
         let type_params = self.infer_type_params(type_params, &mut sig_ctx)?;
+
+        // Recursive references, e.g. `Promise<U, E>` in the return type of
+        // `Promise`'s `then` method, are looked up lazily since the scheme
+        // isn't complete yet.
+        let placeholder_scheme = Scheme {
+            t: self.new_keyword(Keyword::Unknown),
+            type_params: type_params.clone(),
+            is_type_param: true,
+            opaque_module: None,
+        };
+        sig_ctx.schemes.insert(name.to_owned(), placeholder_scheme);
+
         let t = self.infer_type_ann(type_ann, &mut sig_ctx)?;
 
         // TODO: generalize type `t` into a scheme
@@ -1334,6 +2381,7 @@ impl Checker {
             t,
             type_params,
             is_type_param: false,
+            opaque_module: is_opaque.then_some(self.module_id),
         };
 
         ctx.schemes.insert(name.to_owned(), scheme);
@@ -1343,49 +2391,62 @@ impl Checker {
 
     // TODO: write tests for this
     pub fn infer_module(&mut self, node: &mut Module, ctx: &mut Context) -> Result<(), TypeError> {
+        self.strip_cfg_from_module(node)?;
+        self.clear_normalization_cache();
+        self.module_id += 1;
+
+        // `declare global` decls can be used anywhere so they're inferred first.
+        for item in &mut node.items {
+            if let ModuleItemKind::Decl(Decl {
+                kind: DeclKind::GlobalDecl(decl),
+                ..
+            }) = &mut item.kind
+            {
+                self.infer_global_decl(decl, ctx)?;
+            }
+        }
+
         // Prebindings are used to handle recursive and mutually recursive
         // function declarations.
         let mut prebindings: HashMap<String, Binding> = HashMap::new();
 
         for item in &mut node.items {
             match &mut item.kind {
-                ModuleItemKind::Import(_) => {
-                    // TODO: handle imports
-                }
+                ModuleItemKind::Import(import) => self.infer_import(import, ctx)?,
                 ModuleItemKind::Export(_) => (),
-                ModuleItemKind::Decl(decl) => match &mut decl.kind {
-                    DeclKind::TypeDecl(TypeDecl { name, .. }) => {
+                ModuleItemKind::Decl(Decl { kind, attrs, .. }) => match kind {
+                    DeclKind::TypeDecl(TypeDecl {
+                        name,
+                        name_span,
+                        is_opaque,
+                        ..
+                    }) => {
+                        self.add_type_decl(name, *name_span, ctx);
                         let placeholder_scheme = Scheme {
                             t: self.new_keyword(Keyword::Unknown),
                             type_params: None,
                             is_type_param: false,
+                            opaque_module: is_opaque.then_some(self.module_id),
                         };
-                        let name = name.to_owned();
-                        if ctx
-                            .schemes
-                            .insert(name.clone(), placeholder_scheme)
-                            .is_some()
-                        {
-                            return Err(TypeError {
-                                message: format!("{name} cannot be redeclared at the top-level"),
-                            });
-                        }
+                        ctx.insert_top_level_scheme(name, placeholder_scheme)?;
                     }
-                    DeclKind::VarDecl(VarDecl { pattern, .. }) => {
-                        let (bindings, _) = self.infer_pattern(pattern, ctx)?;
+                    DeclKind::VarDecl(decl) => {
+                        let bindings = self.infer_provisional_bindings(decl, ctx)?;
 
-                        for (name, binding) in bindings {
+                        for (name, mut binding) in bindings {
+                            binding.deprecated = get_deprecation(attrs);
                             prebindings.insert(name.to_owned(), binding.clone());
                             ctx.non_generic.insert(binding.index);
-                            if ctx.values.insert(name.to_owned(), binding).is_some() {
-                                return Err(TypeError {
-                                    message: format!(
-                                        "{name} cannot be redeclared at the top-level"
-                                    ),
-                                });
-                            }
+                            ctx.insert_top_level_value(&name, binding)?;
                         }
                     }
+                    DeclKind::GlobalDecl(_) => (),
+                    DeclKind::FnDecl(_) | DeclKind::ClassDecl(_) | DeclKind::ModuleDecl(_) => {
+                        let (name, binding) = self.infer_ambient_prebinding(kind, attrs, ctx)?;
+                        prebindings.insert(name.to_owned(), binding.clone());
+                        ctx.non_generic.insert(binding.index);
+                        ctx.insert_top_level_value(&name, binding)?;
+                    }
                 },
             }
         }
@@ -1394,15 +2455,24 @@ impl Checker {
 
         for item in &mut node.items.iter_mut() {
             // TODO: handle imports and exports
-            if let ModuleItemKind::Decl(decl) = &mut item.kind {
-                match &mut decl.kind {
+            if let ModuleItemKind::Decl(Decl { kind, attrs, .. }) = &mut item.kind {
+                self.check_attrs(attrs);
+                match kind {
                     DeclKind::TypeDecl(decl) => {
                         // NOTE: This updates ctx.schemes.
                         self.infer_type_decl(decl, ctx)?;
                     }
                     DeclKind::VarDecl(decl) => {
                         // TODO: figure out how to avoid parsing patterns twice
-                        bindings.append(&mut self.infer_var_decl(decl, ctx)?);
+                        let mut decl_bindings = self.infer_var_decl(decl, ctx)?;
+                        mark_deprecated(ctx, &decl_bindings, attrs);
+                        bindings.append(&mut decl_bindings);
+                    }
+                    DeclKind::GlobalDecl(_) => (),
+                    DeclKind::FnDecl(_) | DeclKind::ClassDecl(_) | DeclKind::ModuleDecl(_) => {
+                        let mut decl_bindings = self.infer_ambient_decl(kind, ctx)?;
+                        mark_deprecated(ctx, &decl_bindings, attrs);
+                        bindings.append(&mut decl_bindings);
                     }
                 }
             };
@@ -1415,8 +2485,10 @@ impl Checker {
         // Unify each binding with its prebinding
         for (name, binding) in &bindings {
             let prebinding = prebindings.get_mut(name).unwrap();
-            // QUESTION: Which direction should we unify in?
-            self.unify(ctx, prebinding.index, binding.index)?;
+            // The inferred binding must be usable everywhere the prebinding
+            // was used, e.g. a class calling its own constructor from a
+            // static method.
+            self.unify(ctx, binding.index, prebinding.index)?;
         }
 
         // Prune any functions before generalizing, this avoids
@@ -1427,24 +2499,56 @@ impl Checker {
             self.bind(ctx, binding.index, pruned_index)?;
         }
 
-        // Generalize any functions.
+        // Generalize any functions, including those inside of objects and
+        // tuples.
         for binding in bindings.values() {
             let pruned_index = self.prune(binding.index);
-            if let TypeKind::Function(func) = &self.arena[pruned_index].kind.clone() {
-                let func = generalize_func(self, func);
-                let gen_func_index = self.arena.insert(Type::from(TypeKind::Function(func)));
-                self.bind(ctx, binding.index, gen_func_index)?;
+            let gen_index = generalize_type(self, pruned_index);
+            if gen_index != pruned_index {
+                self.bind(ctx, binding.index, gen_index)?;
             }
         }
 
+        self.check_definite_assignment_in_module(node);
+        if self.strictness.warn_on_shadowing {
+            self.check_shadowing_in_module(node);
+        }
+
         Ok(())
     }
 
+    /// Infers the types of the decls in `node` and adds them to `ctx`.  The
+    /// checks that are done are determined by `options.strictness` and the
+    /// script's pragmas, see `apply_pragmas`.  `options.libs` aren't loaded by
+    /// this method, use `load_libs` to add them to `ctx` first.
     // TODO: split this into `infer_script` and `infer_module`.  `infer_script`
     // shouldn't allow mutually recursion between statements while `infer_module`
     // should.  `infer_script` can still allow mutual recursion that occurs within
     // a single statment (variable declaration).
-    pub fn infer_script(&mut self, node: &mut Script, ctx: &mut Context) -> Result<(), TypeError> {
+    pub fn infer_script(
+        &mut self,
+        node: &mut Script,
+        ctx: &mut Context,
+        options: &CompilerOptions,
+    ) -> Result<(), TypeError> {
+        self.strictness = options.strictness.to_owned();
+        self.apply_pragmas(&node.pragmas);
+        self.check_module_format(node, options);
+        self.strip_cfg_from_script(node)?;
+        self.clear_normalization_cache();
+        self.module_id += 1;
+
+        // `declare global` decls can be used anywhere so they're inferred first.
+        for stmt in &mut node.stmts {
+            if let StmtKind::Decl(Decl {
+                kind: DeclKind::GlobalDecl(decl),
+                ..
+            }) = &mut stmt.kind
+            {
+                self.infer_global_decl(decl, ctx)?;
+            }
+        }
+
         // Prebindings are used to handle recursive and mutually recursive
         // function declarations.
         let mut prebindings: HashMap<String, Binding> = HashMap::new();
@@ -1456,57 +2560,80 @@ impl Checker {
                 StmtKind::Expr(_) => (),
                 StmtKind::For(_) => (),
                 StmtKind::Return(_) => (),
-                StmtKind::Decl(decl) => match &mut decl.kind {
-                    DeclKind::TypeDecl(TypeDecl { name, .. }) => {
+                StmtKind::LetElse(_) => (),
+                // Imports are hoisted so their names can be used anywhere.
+                StmtKind::Import(import) => self.infer_import(import, ctx)?,
+                StmtKind::Export(_) => (),
+                StmtKind::Decl(Decl { kind, attrs, .. }) => match kind {
+                    DeclKind::TypeDecl(TypeDecl {
+                        name,
+                        name_span,
+                        is_opaque,
+                        ..
+                    }) => {
+                        self.add_type_decl(name, *name_span, ctx);
                         let placeholder_scheme = Scheme {
                             t: self.new_keyword(Keyword::Unknown),
                             type_params: None,
                             is_type_param: false,
+                            opaque_module: is_opaque.then_some(self.module_id),
                         };
-                        let name = name.to_owned();
-                        if ctx
-                            .schemes
-                            .insert(name.clone(), placeholder_scheme)
-                            .is_some()
-                        {
-                            return Err(TypeError {
-                                message: format!("{name} cannot be redeclared at the top-level"),
-                            });
-                        }
+                        ctx.insert_top_level_scheme(name, placeholder_scheme)?;
                     }
-                    DeclKind::VarDecl(VarDecl { pattern, .. }) => {
-                        let (bindings, _) = self.infer_pattern(pattern, ctx)?;
+                    DeclKind::VarDecl(decl) => {
+                        let bindings = self.infer_provisional_bindings(decl, ctx)?;
 
-                        for (name, binding) in bindings {
+                        for (name, mut binding) in bindings {
+                            binding.deprecated = get_deprecation(attrs);
                             prebindings.insert(name.to_owned(), binding.clone());
                             ctx.non_generic.insert(binding.index);
-                            if ctx.values.insert(name.to_owned(), binding).is_some() {
-                                return Err(TypeError {
-                                    message: format!(
-                                        "{name} cannot be redeclared at the top-level"
-                                    ),
-                                });
-                            }
+                            ctx.insert_top_level_value(&name, binding)?;
                         }
                     }
+                    DeclKind::GlobalDecl(_) => (),
+                    DeclKind::FnDecl(_) | DeclKind::ClassDecl(_) | DeclKind::ModuleDecl(_) => {
+                        let (name, binding) = self.infer_ambient_prebinding(kind, attrs, ctx)?;
+                        prebindings.insert(name.to_owned(), binding.clone());
+                        ctx.non_generic.insert(binding.index);
+                        ctx.insert_top_level_value(&name, binding)?;
+                    }
                 },
             }
         }
 
+        // The decls that haven't been inferred yet and the bindings that can't
+        // be generalized until they have.
+        let mut uninferred: HashSet<String> = prebindings.keys().cloned().collect();
+        let mut ungeneralized: Vec<Binding> = vec![];
+
+        let locals = get_local_names(node);
+
         for stmt in &mut node.stmts.iter_mut() {
             match &mut stmt.kind {
                 StmtKind::Decl(Decl {
-                    kind: DeclKind::VarDecl(decl),
+                    kind:
+                        kind @ (DeclKind::VarDecl(_)
+                        | DeclKind::FnDecl(_)
+                        | DeclKind::ClassDecl(_)
+                        | DeclKind::ModuleDecl(_)),
+                    attrs,
                     ..
                 }) => {
-                    // TODO: figure out how to avoid parsing patterns twice
-                    let bindings = self.infer_var_decl(decl, ctx)?;
+                    self.check_attrs(attrs);
+                    let bindings = match kind {
+                        // TODO: figure out how to avoid parsing patterns twice
+                        DeclKind::VarDecl(decl) => self.infer_var_decl(decl, ctx)?,
+                        _ => self.infer_ambient_decl(kind, ctx)?,
+                    };
+                    mark_deprecated(ctx, &bindings, attrs);
 
                     // Unify each binding with its prebinding
                     for (name, binding) in &bindings {
                         let prebinding = prebindings.get_mut(name).unwrap();
-                        // QUESTION: Which direction should we unify in?
-                        self.unify(ctx, prebinding.index, binding.index)?;
+                        // The inferred binding must be usable everywhere the
+                        // prebinding was used, e.g. a class calling its own
+                        // constructor from a static method.
+                        self.unify(ctx, binding.index, prebinding.index)?;
                     }
 
                     // Prune any functions before generalizing, this avoids
@@ -1517,26 +2644,759 @@ impl Checker {
                         self.bind(ctx, binding.index, pruned_index)?;
                     }
 
-                    // Generalize any functions.
-                    for binding in bindings.values() {
-                        let pruned_index = self.prune(binding.index);
-                        if let TypeKind::Function(func) = &self.arena[pruned_index].kind.clone() {
-                            let func = generalize_func(self, func);
-                            let gen_func_index =
-                                self.arena.insert(Type::from(TypeKind::Function(func)));
-                            self.bind(ctx, binding.index, gen_func_index)?;
-                        }
+                    // Type vars that are shared with the provisional types of
+                    // later decls, e.g. the return type of a function that's
+                    // called before it's declared, can't be generalized until
+                    // those decls have been inferred.
+                    for name in bindings.keys() {
+                        uninferred.remove(name);
                     }
+                    let non_generic: im::HashSet<Index> = uninferred
+                        .iter()
+                        .map(|name| prebindings[name].index)
+                        .collect();
+                    ungeneralized.extend(bindings.into_values());
+                    self.generalize_bindings(ctx, &mut ungeneralized, &non_generic)?;
                 }
+                StmtKind::Decl(Decl {
+                    kind: DeclKind::GlobalDecl(_),
+                    attrs,
+                    ..
+                }) => self.check_attrs(attrs),
+                StmtKind::Import(_) => (),
+                StmtKind::Export(export) => self.check_named_export(export, &locals, ctx)?,
                 _ => {
                     self.infer_statement(stmt, ctx)?;
                 }
             };
         }
 
+        self.check_definite_assignment_in_script(node);
+        if self.strictness.warn_on_shadowing {
+            self.check_shadowing_in_script(node);
+        }
+
+        Ok(())
+    }
+
+    fn is_boolean(&mut self, t: Index) -> bool {
+        let t = self.prune(t);
+        match &self.arena[t].kind.clone() {
+            TypeKind::Primitive(Primitive::Boolean) => true,
+            TypeKind::Literal(Literal::Boolean(_)) => true,
+            TypeKind::Union(union) => union.types.iter().all(|t| self.is_boolean(*t)),
+            _ => false,
+        }
+    }
+
+    fn is_bigint(&mut self, t: Index) -> bool {
+        let t = self.prune(t);
+        matches!(
+            self.arena[t].kind,
+            TypeKind::Literal(Literal::BigInt(_)) | TypeKind::Primitive(Primitive::BigInt)
+        )
+    }
+
+    fn is_string(&mut self, t: Index) -> bool {
+        let t = self.prune(t);
+        matches!(
+            self.arena[t].kind,
+            TypeKind::Literal(Literal::String(_)) | TypeKind::Primitive(Primitive::String)
+        )
+    }
+
+    fn is_number(&mut self, t: Index) -> bool {
+        let t = self.prune(t);
+        matches!(
+            self.arena[t].kind,
+            TypeKind::Literal(Literal::Number(_))
+                | TypeKind::Primitive(Primitive::Number | Primitive::Int | Primitive::Float)
+        )
+    }
+
+    // Checks an operator applied to a user type that overloads it by declaring
+    // the corresponding method, e.g. `a + b` is checked as `a.add(b)`.  Returns
+    // the type of the result or `None` if `obj` doesn't overload the operator.
+    fn infer_operator_overload(
+        &mut self,
+        ctx: &mut Context,
+        obj: Index,
+        method: &str,
+        args: &[Index],
+    ) -> Result<Option<Index>, TypeError> {
+        let obj = self.prune(obj);
+        if !matches!(
+            self.arena[obj].kind,
+            TypeKind::Object(_) | TypeKind::TypeRef(_)
+        ) {
+            return Ok(None);
+        }
+
+        // The operators overloaded by opaque types can be used outside of the
+        // modules that they're declared in, unlike their other members.
+        let obj = match self.arena[obj].kind.clone() {
+            TypeKind::TypeRef(tref) => match self.get_opaque_scheme(ctx, &tref) {
+                Some(scheme) => match self.expand_opaque_type_ref(ctx, &tref, &scheme) {
+                    Ok(expanded) => expanded,
+                    Err(_) => return Ok(None),
+                },
+                None => obj,
+            },
+            _ => obj,
+        };
+
+        let key = self.new_lit_type(&Literal::String(method.to_owned()));
+        let method_t = match self.get_ident_member(ctx, obj, key, false) {
+            Ok(method_t) => method_t,
+            Err(_) => return Ok(None),
+        };
+
+        let params = args
+            .iter()
+            .enumerate()
+            .map(|(i, t)| types::FuncParam {
+                pattern: TPat::Ident(BindingIdent {
+                    name: format!("arg{i}"),
+                    mutable: false,
+                    span: Span { start: 0, end: 0 },
+                }),
+                t: *t,
+                optional: false,
+            })
+            .collect::<Vec<_>>();
+        let ret = self.new_type_var(None);
+        let call_t = self.new_func_type(&params, ret, &None, None);
+
+        self.unify(ctx, method_t, call_t)?;
+
+        Ok(Some(ret))
+    }
+
+    // Conditions whose types aren't known yet are assumed to be booleans.
+    // Other conditions are checked for truthiness unless
+    // `strict_boolean_expressions` is enabled, in which case conditions that
+    // aren't booleans are reported.
+    fn check_condition(&mut self, ctx: &Context, cond: &Expr, t: Index) -> Result<(), TypeError> {
+        let t = self.prune(t);
+        if let TypeKind::TypeVar(_) = &self.arena[t].kind {
+            let boolean = self.new_primitive(Primitive::Boolean);
+            return self.unify(ctx, t, boolean);
+        }
+
+        if self.strictness.strict_boolean_expressions && !self.is_boolean(t) {
+            self.current_report.diagnostics.push(Diagnostic {
+                code: 1012,
+                message: "Conditions must be booleans".to_string(),
+                reasons: vec![TypeError {
+                    message: format!("`{}` is not a boolean", self.print_type(&t)),
+                }],
+                span: Some(cond.span),
+                suggestion: None,
+                related: vec![],
+            });
+        }
+
+        Ok(())
+    }
+
+    // Updates `ctx` with what's known when `cond` evaluates to `is_true`.
+    // Checking that an array is non-empty, e.g. `arr.length > 0`, narrows
+    // `arr` from `T[]` to `[T, ...T[]]`.  Bounds checks, e.g. `i < arr.length`,
+    // and `in` checks, e.g. `key in dict`, mark `arr[i]` and `dict[key]` as
+    // checked accesses.  `"key" in obj` also narrows union-typed objects to
+    // the members which can (or can't) have a `key` property.  Mutable
+    // bindings aren't refined since the array or object could be modified or
+    // reassigned in the meantime.
+    fn refine_ctx_with_cond(&mut self, cond: &Expr, is_true: bool, ctx: &mut Context) {
+        if let ExprKind::Binary(Binary {
+            op: BinaryOp::And,
+            left,
+            right,
+        }) = &cond.kind
+        {
+            // If `a && b` is false, we don't know which of them was false.
+            if is_true {
+                self.refine_ctx_with_cond(left, is_true, ctx);
+                self.refine_ctx_with_cond(right, is_true, ctx);
+            }
+            return;
+        }
+
+        if let ExprKind::Binary(Binary {
+            op: BinaryOp::Or,
+            left,
+            right,
+        }) = &cond.kind
+        {
+            // If `a || b` is true, we don't know which of them was true.
+            if !is_true {
+                self.refine_ctx_with_cond(left, is_true, ctx);
+                self.refine_ctx_with_cond(right, is_true, ctx);
+            }
+            return;
+        }
+
+        if let ExprKind::Unary(Unary {
+            op: UnaryOp::Not,
+            right: arg,
+        }) = &cond.kind
+        {
+            self.refine_ctx_with_cond(arg, !is_true, ctx);
+            return;
+        }
+
+        if let ExprKind::Ident(Ident { name, .. }) = &cond.kind {
+            if is_immutable(ctx, name) {
+                self.narrow_by_truthiness(ctx, name, is_true);
+            }
+            return;
+        }
+
+        if let ExprKind::Binary(Binary {
+            op: BinaryOp::In,
+            left,
+            right,
+        }) = &cond.kind
+        {
+            if let (ExprKind::Str(Str { value: key, .. }), ExprKind::Ident(Ident { name, .. })) =
+                (&left.kind, &right.kind)
+            {
+                if is_immutable(ctx, name) {
+                    self.narrow_by_prop(ctx, name, key, is_true);
+                }
+            }
+        }
+
+        if !is_true {
+            return;
+        }
+
+        if let Some((obj, key)) = get_checked_access(cond) {
+            if is_immutable(ctx, obj) && (key.starts_with('"') || is_immutable(ctx, &key)) {
+                ctx.checked_accesses.insert((obj.to_owned(), key));
+            }
+            return;
+        }
+
+        let name = match get_non_empty_check(cond) {
+            Some(name) if is_immutable(ctx, name) => name,
+            _ => return,
+        };
+        let binding = ctx.values.get(name).unwrap().to_owned();
+
+        let t = self.prune(binding.index);
+        let non_empty = match &self.arena[t].kind {
+            TypeKind::Array(types::Array { t: elem, readonly }) => {
+                let (elem, readonly) = (*elem, *readonly);
+                let rest = self.new_rest_type(t);
+                let tuple = self.new_tuple_type(&[elem, rest]);
+                match readonly {
+                    true => self.new_readonly_type(tuple),
+                    false => tuple,
+                }
+            }
+            _ => return,
+        };
+
+        ctx.values.insert(
+            name.to_owned(),
+            Binding {
+                index: non_empty,
+                ..binding
+            },
+        );
+    }
+
+    // Narrows `name`'s union type to the members which can be truthy if
+    // `is_true` is true or which can be falsy otherwise.
+    fn narrow_by_truthiness(&mut self, ctx: &mut Context, name: &str, is_true: bool) {
+        let binding = ctx.values.get(name).unwrap().to_owned();
+        let t = self.prune(binding.index);
+        if !matches!(&self.arena[t].kind, TypeKind::Union(_)) {
+            return;
+        }
+
+        let index = match is_true {
+            true => self.get_truthy_type(t),
+            false => self.get_falsy_type(t),
+        };
+        ctx.values
+            .insert(name.to_owned(), Binding { index, ..binding });
+    }
+
+    // Returns the parts of `t` that are truthy, e.g. `string | undefined`
+    // becomes `string` and `boolean` becomes `true`.
+    fn get_truthy_type(&mut self, t: Index) -> Index {
+        let t = self.prune(t);
+        match &self.arena[t].kind.clone() {
+            TypeKind::Union(union) => {
+                let types: Vec<Index> = union
+                    .types
+                    .iter()
+                    .map(|t| self.get_truthy_type(*t))
+                    .collect();
+                self.new_union_type(&types)
+            }
+            TypeKind::Literal(lit) if is_falsy_literal(lit) => self.new_keyword(Keyword::Never),
+            TypeKind::Primitive(Primitive::Boolean) => self.new_lit_type(&Literal::Boolean(true)),
+            _ => t,
+        }
+    }
+
+    // Returns the parts of `t` that are falsy, e.g. `string | undefined`
+    // becomes `"" | undefined` and objects become `never`.
+    fn get_falsy_type(&mut self, t: Index) -> Index {
+        let t = self.prune(t);
+        match &self.arena[t].kind.clone() {
+            TypeKind::Union(union) => {
+                let types: Vec<Index> = union
+                    .types
+                    .iter()
+                    .map(|t| self.get_falsy_type(*t))
+                    .collect();
+                self.new_union_type(&types)
+            }
+            TypeKind::Literal(lit) if is_falsy_literal(lit) => t,
+            TypeKind::Literal(_) => self.new_keyword(Keyword::Never),
+            TypeKind::Primitive(prim) => match prim {
+                Primitive::Boolean => self.new_lit_type(&Literal::Boolean(false)),
+                Primitive::Number | Primitive::Int | Primitive::Float => {
+                    self.new_lit_type(&Literal::Number("0".to_string()))
+                }
+                Primitive::BigInt => self.new_lit_type(&Literal::BigInt("0".to_string())),
+                Primitive::String => self.new_lit_type(&Literal::String("".to_string())),
+                Primitive::Symbol => self.new_keyword(Keyword::Never),
+            },
+            TypeKind::Object(_)
+            | TypeKind::Function(_)
+            | TypeKind::Array(_)
+            | TypeKind::Tuple(_)
+            | TypeKind::Keyword(Keyword::Object) => self.new_keyword(Keyword::Never),
+            _ => t,
+        }
+    }
+
+    // Flattens nested unions and removes redundant members, e.g. literals
+    // whose primitive type is also a member.  `true | false` is replaced with
+    // `boolean`.
+    fn new_simplified_union_type(&mut self, types: &[Index]) -> Index {
+        let mut flattened = vec![];
+        for t in types {
+            let t = self.prune(*t);
+            match &self.arena[t].kind {
+                TypeKind::Union(union) => flattened.extend(union.types.iter().cloned()),
+                _ => flattened.push(t),
+            }
+        }
+
+        let is_bool_lit = |checker: &Checker, t: &Index, value: bool| {
+            matches!(
+                checker.arena[*t].kind,
+                TypeKind::Literal(Literal::Boolean(b)) if b == value
+            )
+        };
+        if flattened.iter().any(|t| is_bool_lit(self, t, true))
+            && flattened.iter().any(|t| is_bool_lit(self, t, false))
+        {
+            flattened.retain(|t| !is_bool_lit(self, t, true) && !is_bool_lit(self, t, false));
+            flattened.push(self.new_primitive(Primitive::Boolean));
+        }
+
+        let prims: Vec<Primitive> = flattened
+            .iter()
+            .filter_map(|t| match &self.arena[*t].kind {
+                TypeKind::Primitive(prim) => Some(prim.to_owned()),
+                _ => None,
+            })
+            .collect();
+
+        let mut result: Vec<Index> = vec![];
+        for t in flattened {
+            if let TypeKind::Literal(lit) = &self.arena[t].kind {
+                if matches!(get_literal_primitive(lit), Some(prim) if prims.contains(&prim)) {
+                    continue;
+                }
+            }
+            if !result.iter().any(|r| self.equals(r, &t)) {
+                result.push(t);
+            }
+        }
+
+        self.new_union_type(&result)
+    }
+
+    // Removes the members of `name`'s union type which can't have a `key`
+    // property if `has_key` is true or which must have one otherwise.
+    fn narrow_by_prop(&mut self, ctx: &mut Context, name: &str, key: &str, has_key: bool) {
+        let binding = ctx.values.get(name).unwrap().to_owned();
+        let t = self.prune(binding.index);
+        let types = match &self.arena[t].kind {
+            TypeKind::Union(union) => union.types.to_owned(),
+            _ => return,
+        };
+
+        let mut narrowed = vec![];
+        for t in types {
+            match self.has_prop(ctx, t, key) {
+                Some(has_prop) if has_prop != has_key => (),
+                _ => narrowed.push(t),
+            }
+        }
+
+        let index = self.new_union_type(&narrowed);
+        ctx.values
+            .insert(name.to_owned(), Binding { index, ..binding });
+    }
+
+    // Returns `Some(true)` if values of type `t` always have a `key` property,
+    // `Some(false)` if they never do, and `None` if they might, e.g. because
+    // the property is optional or `t` has an indexer.
+    fn has_prop(&mut self, ctx: &Context, t: Index, key: &str) -> Option<bool> {
+        let t = self.expand_type(ctx, t).ok()?;
+        let elems = match &self.arena[t].kind {
+            TypeKind::Object(object) => object.elems.to_owned(),
+            _ => return None,
+        };
+
+        let mut has_indexer = false;
+        for elem in &elems {
+            let (name, optional) = match elem {
+                TObjElem::Prop(TProp { name, optional, .. }) => (name, *optional),
+                TObjElem::Method(TMethod { name, .. })
+                | TObjElem::Getter(TGetter { name, .. })
+                | TObjElem::Setter(TSetter { name, .. }) => (name, false),
+                TObjElem::Mapped(_) => {
+                    has_indexer = true;
+                    continue;
+                }
+                _ => continue,
+            };
+            match name {
+                TPropKey::StringKey(name) | TPropKey::NumberKey(name) if name == key => {
+                    return match optional {
+                        true => None,
+                        false => Some(true),
+                    };
+                }
+                _ => (),
+            }
+        }
+
+        match has_indexer {
+            true => None,
+            false => Some(false),
+        }
+    }
+
+    // Returns whether `left == right` is always true or always false along
+    // with the reason why, or `None` if it depends on the values being
+    // compared.  Both sides having the same literal type means the comparison
+    // is always true and types which have no values in common means that it's
+    // always false.
+    fn get_equality_result(&mut self, left: Index, right: Index) -> Option<(bool, String)> {
+        let left = self.prune(left);
+        let right = self.prune(right);
+
+        if let (TypeKind::Literal(left_lit), TypeKind::Literal(right_lit)) =
+            (&self.arena[left].kind, &self.arena[right].kind)
+        {
+            if literals_equal(left_lit, right_lit) {
+                let message = format!(
+                    "Both sides have the literal type `{}`",
+                    self.print_type(&left)
+                );
+                return Some((true, message));
+            }
+        }
+
+        match self.types_overlap(left, right) {
+            true => None,
+            false => {
+                let message = format!(
+                    "`{}` and `{}` have no overlap",
+                    self.print_type(&left),
+                    self.print_type(&right)
+                );
+                Some((false, message))
+            }
+        }
+    }
+
+    // Returns `false` if no value can have both types.  Only primitives,
+    // literals, and unions of them are checked, other types are assumed to
+    // overlap.
+    fn types_overlap(&mut self, left: Index, right: Index) -> bool {
+        let left = self.prune(left);
+        let right = self.prune(right);
+
+        match (
+            self.arena[left].kind.clone(),
+            self.arena[right].kind.clone(),
+        ) {
+            (TypeKind::Union(union), _) => {
+                for t in union.types {
+                    if self.types_overlap(t, right) {
+                        return true;
+                    }
+                }
+                false
+            }
+            (_, TypeKind::Union(union)) => {
+                for t in union.types {
+                    if self.types_overlap(left, t) {
+                        return true;
+                    }
+                }
+                false
+            }
+            (TypeKind::Literal(left), TypeKind::Literal(right)) => literals_equal(&left, &right),
+            (TypeKind::Literal(lit), TypeKind::Primitive(prim))
+            | (TypeKind::Primitive(prim), TypeKind::Literal(lit)) => {
+                match get_literal_primitive(&lit) {
+                    Some(lit_prim) => primitives_overlap(&lit_prim, &prim),
+                    None => false,
+                }
+            }
+            (TypeKind::Primitive(left), TypeKind::Primitive(right)) => {
+                primitives_overlap(&left, &right)
+            }
+            _ => true,
+        }
+    }
+
+    // Returns `Int` or `Float` if `t` is an `int` or `float` or a number
+    // literal that would be inferred as one.
+    fn get_numeric_refinement(&mut self, t: Index) -> Option<Primitive> {
+        let t = self.prune(t);
+        match &self.arena[t].kind {
+            TypeKind::Primitive(Primitive::Int) => Some(Primitive::Int),
+            TypeKind::Primitive(Primitive::Float) => Some(Primitive::Float),
+            TypeKind::Literal(Literal::Number(value)) => match is_int_literal(value) {
+                true => Some(Primitive::Int),
+                false => Some(Primitive::Float),
+            },
+            _ => None,
+        }
+    }
+
+    // Returns the type of an arithmetic operation involving `int`s and
+    // `float`s, e.g. `int + int` is an `int`, but `int / int` is a `float`.
+    // If either operand is a plain `number` then so is the result.
+    fn refine_arithmetic(&mut self, op: &BinaryOp, left: Index, right: Index) -> Index {
+        let left = self.get_numeric_refinement(left);
+        let right = self.get_numeric_refinement(right);
+
+        let primitive = match (left, right) {
+            (Some(Primitive::Int), Some(Primitive::Int)) => match op {
+                // Negative exponents result in fractions, e.g. `2 ** -1`.
+                BinaryOp::Divide | BinaryOp::Power => Primitive::Float,
+                _ => Primitive::Int,
+            },
+            (Some(_), Some(_)) => Primitive::Float,
+            _ => Primitive::Number,
+        };
+
+        self.new_primitive(primitive)
+    }
+
+    // Arrays and tuples can't be indexed by `float`s when numeric refinements
+    // are enabled since doing so is almost always a bug.
+    pub fn check_index(&mut self, key: Index) -> Result<(), TypeError> {
+        if !self.strictness.numeric_refinements {
+            return Ok(());
+        }
+        if let Some(Primitive::Float) = self.get_numeric_refinement(key) {
+            return Err(TypeError {
+                message: format!(
+                    "{} can't be used as an index, expected int",
+                    self.print_type(&key)
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    // Reports a diagnostic if `t`, the type of `expr`, isn't allowed to be
+    // interpolated in a template literal.
+    fn check_interpolation(
+        &mut self,
+        ctx: &mut Context,
+        expr: &Expr,
+        t: Index,
+    ) -> Result<(), TypeError> {
+        let mut primitives = vec![Primitive::String, Primitive::Number];
+        match self.strictness.interpolation_strictness {
+            InterpolationStrictness::Off => return Ok(()),
+            InterpolationStrictness::Loose => primitives.push(Primitive::Boolean),
+            InterpolationStrictness::Strict => (),
+        }
+
+        let types = primitives
+            .into_iter()
+            .map(|primitive| self.new_primitive(primitive))
+            .collect::<Vec<_>>();
+        let expected_t = self.new_union_type(&types);
+
+        if self.unify(ctx, t, expected_t).is_ok() {
+            return Ok(());
+        }
+
+        let mut expected = self.print_type(&expected_t);
+        if self.strictness.interpolation_strictness == InterpolationStrictness::Loose {
+            if self.has_to_string(ctx, t) {
+                return Ok(());
+            }
+            expected = format!("{expected} or a type with a toString method");
+        }
+
+        self.current_report.diagnostics.push(Diagnostic {
+            code: 1001,
+            message: "Invalid interpolation in template literal".to_string(),
+            reasons: vec![TypeError {
+                message: format!(
+                    "{} can't be interpolated, expected {expected}",
+                    self.print_type(&t)
+                ),
+            }],
+            span: Some(expr.span),
+            suggestion: None,
+            related: vec![],
+        });
+
         Ok(())
     }
 
+    // Returns the type that `t` resolves to along with the union of the errors
+    // it can reject with.  Nested promises are unwrapped until a non-promise
+    // type is reached.
+    fn unwrap_promise(&mut self, t: Index, throws: Index) -> (Index, Index) {
+        let mut t = t;
+        let mut throws = vec![throws];
+        loop {
+            let pruned = self.prune(t);
+            match &self.arena[pruned].kind {
+                TypeKind::TypeRef(types::TypeRef {
+                    name, type_args, ..
+                }) if name == "Promise" && type_args.len() == 2 => {
+                    t = type_args[0];
+                    throws.push(type_args[1]);
+                }
+                _ => break,
+            }
+        }
+        let mut unique_throws: Vec<Index> = vec![];
+        for throws_t in throws {
+            let throws_t = self.prune(throws_t);
+            if !unique_throws.iter().any(|t| self.equals(t, &throws_t)) {
+                unique_throws.push(throws_t);
+            }
+        }
+        (t, self.new_union_type(&unique_throws))
+    }
+
+    // Infers `Promise.all([a, b, ...])` as a promise that resolves to a tuple
+    // of the types each element resolves to.  Elements that aren't promises
+    // resolve to themselves.
+    fn infer_promise_all(&mut self, tuple_t: Index) -> Index {
+        let tuple_t = self.prune(tuple_t);
+        let elem_types = match &self.arena[tuple_t].kind {
+            TypeKind::Tuple(types::Tuple { types, .. }) => types.to_owned(),
+            _ => unreachable!("the arg to `Promise.all` should be a tuple"),
+        };
+
+        let never = self.new_keyword(Keyword::Never);
+        let mut resolved_types = vec![];
+        let mut throws_types = vec![];
+        for elem_t in elem_types {
+            let (resolved_t, throws_t) = self.unwrap_promise(elem_t, never);
+            resolved_types.push(resolved_t);
+            throws_types.push(throws_t);
+        }
+
+        let resolved_t = self.new_tuple_type(&resolved_types);
+        let throws_t = self.new_union_type(&throws_types);
+        self.new_type_ref("Promise", None, &[resolved_t, throws_t])
+    }
+
+    // Warns about attributes that the checker doesn't know about since they're
+    // likely typos, e.g. `@depreciated`.
+    pub(crate) fn check_attrs(&mut self, attrs: &[Attribute]) {
+        for attr in attrs {
+            if BUILTIN_ATTRIBUTES.contains(&attr.name.as_str())
+                || self.custom_attributes.contains(&attr.name)
+            {
+                continue;
+            }
+            self.current_report.warnings.push(Diagnostic {
+                code: 1009,
+                message: format!("Unknown attribute `@{}`", attr.name),
+                reasons: vec![],
+                span: Some(attr.span),
+                suggestion: None,
+                related: vec![],
+            });
+        }
+    }
+
+    // Warns about intersections that were reduced to `never` even though none
+    // of their members are `never`.
+    fn check_empty_intersection(&mut self, t: Index, types: &[Index], span: Span) {
+        let is_never = |checker: &Self, t: &Index| {
+            matches!(checker.arena[*t].kind, TypeKind::Keyword(Keyword::Never))
+        };
+        if !is_never(self, &t) || types.iter().any(|t| is_never(self, t)) {
+            return;
+        }
+
+        let types = types.iter().map(|t| self.print_type(t)).join(" & ");
+        self.current_report.warnings.push(Diagnostic {
+            code: 1020,
+            message: format!(
+                "The intersection `{types}` is `never` since its members don't have any values in common"
+            ),
+            reasons: vec![],
+            span: Some(span),
+            suggestion: None,
+            related: vec![],
+        });
+    }
+
+    // Warns about uses of bindings whose decls are marked with `@deprecated`.
+    fn check_deprecated_use(&mut self, name: &str, span: &Span, ctx: &Context) {
+        let reason = match ctx.values.get(name) {
+            Some(Binding {
+                deprecated: Some(reason),
+                ..
+            }) => reason,
+            _ => return,
+        };
+        let reasons = match reason.is_empty() {
+            true => vec![],
+            false => vec![TypeError {
+                message: reason.to_owned(),
+            }],
+        };
+
+        self.current_report.warnings.push(Diagnostic {
+            code: 1008,
+            message: format!("`{name}` is deprecated"),
+            reasons,
+            span: Some(*span),
+            suggestion: None,
+            related: vec![],
+        });
+    }
+
+    fn has_to_string(&mut self, ctx: &mut Context, t: Index) -> bool {
+        let t = self.prune(t);
+        if let TypeKind::Union(union) = &self.arena[t].kind.clone() {
+            return union.types.iter().all(|t| self.has_to_string(ctx, *t));
+        }
+
+        let key = self.new_lit_type(&Literal::String("toString".to_string()));
+        self.get_ident_member(ctx, t, key, false).is_ok()
+    }
+
     fn get_ident_member(
         &mut self,
         ctx: &mut Context,
@@ -1588,14 +3448,19 @@ impl Checker {
                 };
                 self.get_ident_member(ctx, obj_idx, key_idx, is_mut)
             }
-            TypeKind::Array(types::Array { t }) => {
+            // Mutating methods can't be called on `readonly` arrays and tuples
+            // even if they're bound to mutable variables.
+            TypeKind::Array(types::Array { t, readonly }) => {
                 let obj_idx = self.expand_alias(ctx, "Array", &[*t])?;
-                self.get_ident_member(ctx, obj_idx, key_idx, is_mut)
+                self.get_ident_member(ctx, obj_idx, key_idx, is_mut && !readonly)
             }
-            TypeKind::Tuple(types::Tuple { types }) => {
-                let t = self.new_union_type(types);
+            TypeKind::Tuple(types::Tuple {
+                types, readonly, ..
+            }) => {
+                let types = self.get_tuple_elem_types(types);
+                let t = self.new_union_type(&types);
                 let obj_idx = self.expand_alias(ctx, "Array", &[t])?;
-                self.get_ident_member(ctx, obj_idx, key_idx, is_mut)
+                self.get_ident_member(ctx, obj_idx, key_idx, is_mut && !readonly)
             }
             TypeKind::Literal(Literal::String(_)) => {
                 let obj_idx = self.expand_alias(ctx, "String", &[])?;
@@ -1609,7 +3474,7 @@ impl Checker {
                 let obj_idx = self.expand_alias(ctx, "String", &[])?;
                 self.get_ident_member(ctx, obj_idx, key_idx, is_mut)
             }
-            TypeKind::Primitive(Primitive::Number) => {
+            TypeKind::Primitive(Primitive::Number | Primitive::Int | Primitive::Float) => {
                 let obj_idx = self.expand_alias(ctx, "Number", &[])?;
                 self.get_ident_member(ctx, obj_idx, key_idx, is_mut)
             }
@@ -1619,6 +3484,78 @@ impl Checker {
         }
     }
 
+    // Returns the names of the properties that can be accessed on `obj_idx`
+    // using dot notation or `None` if `obj_idx` doesn't have any properties.
+    fn get_ident_member_names(
+        &mut self,
+        ctx: &Context,
+        obj_idx: Index,
+    ) -> Result<Option<Vec<String>>, TypeError> {
+        let completions = self.get_member_completions(ctx, obj_idx)?;
+        Ok(completions.map(|completions| {
+            completions
+                .into_iter()
+                .map(|completion| completion.label)
+                .collect()
+        }))
+    }
+
+    // Reports accesses of mutating methods on receivers that aren't mutable,
+    // e.g. `arr.push(4)` where `arr` isn't declared with `mut`.
+    fn report_immutable_receiver(
+        &mut self,
+        ctx: &Context,
+        obj: &Expr,
+        method: &str,
+    ) -> Result<(), TypeError> {
+        // Chained calls, e.g. `builder.add(1).add(2)`, aren't mutable because
+        // their receiver isn't, which has already been reported.
+        if get_chained_receiver(self, ctx, obj).is_some() {
+            return Ok(());
+        }
+        let diagnostic = match get_lvalue_root(obj) {
+            Some(Ident { name, .. }) => {
+                let binding = ctx.get_binding(name)?;
+                let message = match &obj.kind {
+                    ExprKind::Ident(_) => format!(
+                        "Cannot call mutating method `{method}` on `{name}` because it isn't mutable"
+                    ),
+                    _ => format!(
+                        "Cannot call mutating method `{method}` on a property of `{name}` because it isn't mutable"
+                    ),
+                };
+                Diagnostic {
+                    code: 1022,
+                    message,
+                    reasons: vec![TypeError {
+                        message: format!("`{name}` must be declared with `mut`"),
+                    }],
+                    span: Some(obj.span),
+                    suggestion: None,
+                    related: binding
+                        .span
+                        .map(|span| (span, format!("`{name}` is declared here")))
+                        .into_iter()
+                        .collect(),
+                }
+            }
+            // Values that aren't reached through a binding, e.g. the results
+            // of function calls, are never mutable.
+            None => Diagnostic {
+                code: 1022,
+                message: format!(
+                    "Cannot call mutating method `{method}` on a value that isn't mutable"
+                ),
+                reasons: vec![],
+                span: Some(obj.span),
+                suggestion: None,
+                related: vec![],
+            },
+        };
+        self.current_report.diagnostics.push(diagnostic);
+        Ok(())
+    }
+
     pub fn infer_type_params(
         &mut self,
         type_params: &mut Option<Vec<syntax::TypeParam>>,
@@ -1637,15 +3574,37 @@ impl Checker {
 
                 // Adds the param to the context and set its type to the constraint
                 // or `unknown` if there is no constraint.
+                // Type params that are type constructors, e.g. `F<_>`, have a
+                // placeholder type param for each type arg they take so that
+                // their type refs are checked for the right number of type args.
                 let scheme = Scheme {
                     t: match constraint {
                         Some(constraint) => constraint,
                         None => self.new_keyword(Keyword::Unknown),
                     },
-                    type_params: None,
+                    type_params: match tp.arity {
+                        0 => None,
+                        arity => Some(
+                            (0..arity)
+                                .map(|_| types::TypeParam {
+                                    name: "_".to_string(),
+                                    arity: 0,
+                                    constraint: None,
+                                    default: None,
+                                })
+                                .collect(),
+                        ),
+                    },
                     is_type_param: true,
+                    opaque_module: None,
                 };
                 sig_ctx.schemes.insert(tp.name.to_owned(), scheme);
+                // The name is always at the start of the type param.
+                let name_span = Span {
+                    start: tp.span.start,
+                    end: tp.span.start + tp.name.len(),
+                };
+                self.add_type_decl(&tp.name, name_span, sig_ctx);
             }
         }
 
@@ -1664,6 +3623,7 @@ impl Checker {
                         }
                         Ok(types::TypeParam {
                             name: tp.name.to_owned(),
+                            arity: tp.arity,
                             constraint: match &mut tp.bound {
                                 Some(constraint) => Some(self.infer_type_ann(constraint, sig_ctx)?),
                                 None => None,
@@ -1683,6 +3643,55 @@ impl Checker {
     }
 }
 
+fn is_promise_all_with_tuple(callee: &Expr, args: &[Expr]) -> bool {
+    match (&callee.kind, args) {
+        (
+            ExprKind::Member(Member {
+                object,
+                property: MemberProp::Ident(prop),
+                opt_chain: false,
+            }),
+            [arg],
+        ) => {
+            prop.name == "all"
+                && matches!(&object.kind, ExprKind::Ident(Ident { name, .. }) if name == "Promise")
+                && matches!(arg.kind, ExprKind::Tuple(_))
+        }
+        _ => false,
+    }
+}
+
+// Methods in object literals are functions whose first param is `self`, e.g.
+// `{count: 0, get: fn (self) => self.count}`.
+fn is_method_prop(prop: &PropOrSpread) -> bool {
+    match prop {
+        PropOrSpread::Prop(expr::Prop::Property {
+            value:
+                Expr {
+                    kind: ExprKind::Function(syntax::Function { params, .. }),
+                    ..
+                },
+            ..
+        }) => params
+            .first()
+            .is_some_and(|param| is_self_pattern(&param.pattern)),
+        _ => false,
+    }
+}
+
+fn get_static_prop_key(key: &ObjectKey) -> Option<TPropKey> {
+    match key {
+        ObjectKey::Ident(Ident { name, .. }) => Some(TPropKey::StringKey(name.to_owned())),
+        ObjectKey::String(name) => Some(TPropKey::StringKey(name.to_owned())),
+        ObjectKey::Number(name) => Some(TPropKey::NumberKey(name.to_owned())),
+        ObjectKey::Computed(_) => None,
+    }
+}
+
+fn is_self_pattern(pattern: &Pattern) -> bool {
+    matches!(&pattern.kind, PatternKind::Ident(BindingIdent { name, .. }) if name == "self")
+}
+
 fn is_promise(t: &Type) -> bool {
     matches!(
         t,
@@ -1726,6 +3735,151 @@ pub fn check_mutability(ctx: &Context, tpat: &TPat, init: &Expr) -> Result<bool,
     Ok(lhs_mutable && rhs_mutable)
 }
 
+// Returns the names of the object and the key in conditions that check that
+// an index access is safe, e.g. `i < arr.length` or `key in dict`.
+fn get_checked_access(cond: &Expr) -> Option<(&str, String)> {
+    let (op, left, right) = match &cond.kind {
+        ExprKind::Binary(Binary { op, left, right }) => (op, left, right),
+        _ => return None,
+    };
+
+    match op {
+        BinaryOp::In => match &right.kind {
+            ExprKind::Ident(Ident { name, .. }) => Some((name, get_access_key(left)?)),
+            _ => None,
+        },
+        BinaryOp::LessThan => match (&left.kind, get_length_object(right)) {
+            (ExprKind::Ident(Ident { name: key, .. }), Some(name)) => Some((name, key.to_owned())),
+            _ => None,
+        },
+        BinaryOp::GreaterThan => match (get_length_object(left), &right.kind) {
+            (Some(name), ExprKind::Ident(Ident { name: key, .. })) => Some((name, key.to_owned())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Keys of checked accesses are either the names of identifiers or quoted
+// string literals.
+fn get_access_key(expr: &Expr) -> Option<String> {
+    match &expr.kind {
+        ExprKind::Ident(Ident { name, .. }) => Some(name.to_owned()),
+        ExprKind::Str(Str { value, .. }) => Some(format!("{value:?}")),
+        _ => None,
+    }
+}
+
+// Number literals are compared by value so that `1` and `1.0` are equal.
+pub(crate) fn literals_equal(left: &Literal, right: &Literal) -> bool {
+    match (left, right) {
+        (Literal::Number(left), Literal::Number(right)) => {
+            match (left.parse::<f64>(), right.parse::<f64>()) {
+                (Ok(left), Ok(right)) => left == right,
+                _ => left == right,
+            }
+        }
+        _ => left == right,
+    }
+}
+
+fn is_falsy_literal(lit: &Literal) -> bool {
+    match lit {
+        Literal::Number(value) => matches!(value.parse::<f64>(), Ok(n) if n == 0.0 || n.is_nan()),
+        Literal::BigInt(value) => value == "0",
+        Literal::String(value) => value.is_empty(),
+        Literal::Boolean(value) => !value,
+        Literal::Null | Literal::Undefined => true,
+    }
+}
+
+fn get_literal_primitive(lit: &Literal) -> Option<Primitive> {
+    match lit {
+        Literal::Number(_) => Some(Primitive::Number),
+        Literal::BigInt(_) => Some(Primitive::BigInt),
+        Literal::String(_) => Some(Primitive::String),
+        Literal::Boolean(_) => Some(Primitive::Boolean),
+        Literal::Null | Literal::Undefined => None,
+    }
+}
+
+// `int` and `float` are both refinements of `number`.
+fn primitives_overlap(left: &Primitive, right: &Primitive) -> bool {
+    let is_numeric =
+        |prim: &Primitive| matches!(prim, Primitive::Number | Primitive::Int | Primitive::Float);
+    left == right || is_numeric(left) && is_numeric(right)
+}
+
+fn is_immutable(ctx: &Context, name: &str) -> bool {
+    matches!(ctx.values.get(name), Some(binding) if !binding.is_mut)
+}
+
+fn is_checked_access(ctx: &Context, obj: &Expr, key: Option<String>) -> bool {
+    match (&obj.kind, key) {
+        (ExprKind::Ident(Ident { name, .. }), Some(key)) => {
+            ctx.checked_accesses.contains(&(name.to_owned(), key))
+        }
+        _ => false,
+    }
+}
+
+// Returns the name of the array in conditions that check that it's
+// non-empty, e.g. `arr.length > 0`, `arr.length >= 1`, or `arr.length != 0`.
+fn get_non_empty_check(cond: &Expr) -> Option<&str> {
+    let (op, left, right) = match &cond.kind {
+        ExprKind::Binary(Binary { op, left, right }) => (op, left, right),
+        _ => return None,
+    };
+
+    // `0 < arr.length` is treated the same as `arr.length > 0`.
+    let (name, op, value) = match (get_length_object(left), get_length_object(right)) {
+        (Some(name), None) => (name, op.to_owned(), &right.kind),
+        (None, Some(name)) => {
+            let op = match op {
+                BinaryOp::LessThan => BinaryOp::GreaterThan,
+                BinaryOp::LessThanOrEqual => BinaryOp::GreaterThanOrEqual,
+                BinaryOp::GreaterThan => BinaryOp::LessThan,
+                BinaryOp::GreaterThanOrEqual => BinaryOp::LessThanOrEqual,
+                op => op.to_owned(),
+            };
+            (name, op, &left.kind)
+        }
+        _ => return None,
+    };
+
+    let value: f64 = match value {
+        ExprKind::Num(Num { value, .. }) => value.parse().ok()?,
+        _ => return None,
+    };
+
+    let is_non_empty = match op {
+        BinaryOp::GreaterThan => value >= 0.0,
+        BinaryOp::GreaterThanOrEqual => value > 0.0,
+        BinaryOp::NotEquals => value == 0.0,
+        _ => false,
+    };
+
+    match is_non_empty {
+        true => Some(name),
+        false => None,
+    }
+}
+
+// Returns the name of `arr` if `expr` is `arr.length`.
+fn get_length_object(expr: &Expr) -> Option<&str> {
+    match &expr.kind {
+        ExprKind::Member(Member {
+            object,
+            property: MemberProp::Ident(Ident { name: prop, .. }),
+            opt_chain: false,
+        }) if prop == "length" => match &object.kind {
+            ExprKind::Ident(Ident { name, .. }) => Some(name),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 // TODO: find the rest of the identifiers in the expression
 fn find_identifiers(expr: &Expr) -> Result<Vec<Ident>, TypeError> {
     let mut idents = vec![];
@@ -1738,22 +3892,75 @@ fn find_identifiers(expr: &Expr) -> Result<Vec<Ident>, TypeError> {
 }
 
 // TODO: separate mutability checks from lvalue checks
-fn is_expr_mutable(ctx: &Context, expr: &Expr) -> Result<bool, TypeError> {
+fn is_expr_mutable(checker: &mut Checker, ctx: &Context, expr: &Expr) -> Result<bool, TypeError> {
     match &expr.kind {
         ExprKind::Ident(ident) => {
             let binding = ctx.values.get(&ident.name).unwrap();
             Ok(binding.is_mut)
         }
-        ExprKind::Member(member) => is_expr_mutable(ctx, &member.object),
+        ExprKind::Member(member) => is_expr_mutable(checker, ctx, &member.object),
         ExprKind::Tuple(_) => Ok(true),
         ExprKind::Object(_) => Ok(true),
+        ExprKind::Call(_) => match get_chained_receiver(checker, ctx, expr) {
+            Some(receiver) => is_expr_mutable(checker, ctx, receiver),
+            None => Ok(false),
+        },
         _ => Ok(false),
     }
 }
 
-struct Generalize<'a, 'b> {
-    checker: &'a mut Checker,
-    mapping: &'b mut BTreeMap<Index, String>,
+// Methods that take `mut self` and return `Self` return their receiver so
+// calls to them can be chained, e.g. `builder.add(1).add(2)`.  Returns the
+// receiver if `expr` is a call to one of these methods.
+fn get_chained_receiver<'a>(
+    checker: &mut Checker,
+    ctx: &Context,
+    expr: &'a Expr,
+) -> Option<&'a Expr> {
+    if let ExprKind::Call(syntax::Call { callee, .. }) = &expr.kind {
+        if let ExprKind::Member(Member {
+            object,
+            property: MemberProp::Ident(Ident { name, .. }),
+            ..
+        }) = &callee.kind
+        {
+            let obj_t = object.inferred_type?;
+            if checker.returns_mut_self(ctx, obj_t, name) {
+                return Some(object);
+            }
+        }
+    }
+    None
+}
+
+// Returns the identifier that an lvalue is reached through, e.g. `p` in `p.x`
+// or `arr` in `arr[i]`.
+fn get_lvalue_root(expr: &Expr) -> Option<&Ident> {
+    match &expr.kind {
+        ExprKind::Ident(ident) => Some(ident),
+        ExprKind::Member(member) => get_lvalue_root(&member.object),
+        _ => None,
+    }
+}
+
+// Returns the text used to display a symbol key, e.g. `Symbol.iterator`.
+fn get_computed_key_name(expr: &Expr) -> String {
+    match &expr.kind {
+        ExprKind::Ident(Ident { name, .. }) => name.to_owned(),
+        ExprKind::Member(Member {
+            object,
+            property: MemberProp::Ident(Ident { name, .. }),
+            ..
+        }) => format!("{}.{name}", get_computed_key_name(object)),
+        _ => "symbol".to_string(),
+    }
+}
+
+pub(crate) struct Generalize<'a, 'b> {
+    pub checker: &'a mut Checker,
+    pub mapping: &'b mut BTreeMap<Index, String>,
+    // Type variables that occur in these types are left as is.
+    pub non_generic: &'b im::HashSet<Index>,
 }
 
 // TODO: have `Checker` implement this trait
@@ -1779,6 +3986,9 @@ impl<'a, 'b> Folder for Generalize<'a, 'b> {
                 instance: _,
                 constraint: _,
             }) => {
+                if self.checker.occurs_in(index, self.non_generic) {
+                    return index;
+                }
                 let name = match self.mapping.get(&index) {
                     Some(name) => name.clone(),
                     None => {
@@ -1801,12 +4011,70 @@ impl<'a, 'b> Folder for Generalize<'a, 'b> {
     }
 }
 
+// Generalizes the functions in `t`, e.g. the functions in a record of
+// functions, so that each of them can be called with different types.  Each
+// function gets its own type params.  Returns `t` if it doesn't contain any
+// functions to generalize.
+pub fn generalize_type(checker: &mut Checker, t: Index) -> Index {
+    let t = checker.prune(t);
+    match checker.arena[t].kind.clone() {
+        TypeKind::Function(func) => {
+            let func = generalize_func(checker, &func);
+            checker.from_type_kind(TypeKind::Function(func))
+        }
+        TypeKind::Object(object) => {
+            let mut changed = false;
+            let elems: Vec<TObjElem> = object
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    TObjElem::Prop(prop) => {
+                        let prop_t = generalize_type(checker, prop.t);
+                        changed |= prop_t != checker.prune(prop.t);
+                        TObjElem::Prop(TProp {
+                            t: prop_t,
+                            ..prop.to_owned()
+                        })
+                    }
+                    TObjElem::Method(method) => {
+                        let function = generalize_func(checker, &method.function);
+                        changed |= function.type_params != method.function.type_params;
+                        TObjElem::Method(TMethod {
+                            function,
+                            ..method.to_owned()
+                        })
+                    }
+                    _ => elem.to_owned(),
+                })
+                .collect();
+            match changed {
+                true => checker.from_type_kind(TypeKind::Object(types::Object { elems, ..object })),
+                false => t,
+            }
+        }
+        TypeKind::Tuple(tuple) => {
+            let types: Vec<Index> = tuple
+                .types
+                .iter()
+                .map(|t| generalize_type(checker, *t))
+                .collect();
+            let pruned: Vec<Index> = tuple.types.iter().map(|t| checker.prune(*t)).collect();
+            match types != pruned {
+                true => checker.from_type_kind(TypeKind::Tuple(types::Tuple { types, ..tuple })),
+                false => t,
+            }
+        }
+        _ => t,
+    }
+}
+
 pub fn generalize_func(checker: &mut Checker, func: &types::Function) -> types::Function {
     // A mapping of TypeVariables to TypeVariables
     let mut mapping = BTreeMap::default();
     let mut generalize = Generalize {
         checker,
         mapping: &mut mapping,
+        non_generic: &im::HashSet::new(),
     };
 
     let params = func
@@ -1829,6 +4097,7 @@ pub fn generalize_func(checker: &mut Checker, func: &types::Function) -> types::
     for (_, name) in mapping {
         type_params.push(types::TypeParam {
             name: name.clone(),
+            arity: 0,
             constraint: None,
             default: None,
         });
@@ -1847,3 +4116,57 @@ pub fn generalize_func(checker: &mut Checker, func: &types::Function) -> types::
         throws,
     }
 }
+
+// Returns the name of the method that user types can declare to overload a
+// binary operator, e.g. `fn add(self, other: Self) -> Self` overloads `+`.
+fn get_binary_op_method(op: &BinaryOp) -> Option<&'static str> {
+    match op {
+        BinaryOp::Plus => Some("add"),
+        BinaryOp::Minus => Some("sub"),
+        BinaryOp::Times => Some("mul"),
+        BinaryOp::Divide => Some("div"),
+        BinaryOp::Modulo => Some("mod"),
+        _ => None,
+    }
+}
+
+// Rewrites an overloaded operator as a call to the method that overloads it,
+// e.g. `a + b` becomes `a.add(b)`, so that codegen emits the method call.
+fn desugar_operator(method: &str, obj: &Expr, args: Vec<Expr>) -> ExprKind {
+    let callee = Expr {
+        kind: ExprKind::Member(Member {
+            object: Box::new(obj.to_owned()),
+            property: MemberProp::Ident(Ident {
+                name: method.to_owned(),
+                span: obj.span,
+            }),
+            opt_chain: false,
+        }),
+        span: obj.span,
+        inferred_type: None,
+    };
+
+    ExprKind::Call(syntax::Call {
+        callee: Box::new(callee),
+        type_args: None,
+        args,
+        opt_chain: false,
+        throws: None,
+    })
+}
+
+// Attributes that can be used on decls without being listed in
+// `Checker::custom_attributes`.
+const BUILTIN_ATTRIBUTES: [&str; 2] = ["cfg", "deprecated"];
+
+// Marks the bindings introduced by a decl with a `@deprecated` attribute so
+// that uses of them are reported.
+fn mark_deprecated(ctx: &mut Context, bindings: &Assump, attrs: &[Attribute]) {
+    if let Some(reason) = get_deprecation(attrs) {
+        for name in bindings.keys() {
+            if let Some(binding) = ctx.values.get_mut(name) {
+                binding.deprecated = Some(reason.to_owned());
+            }
+        }
+    }
+}
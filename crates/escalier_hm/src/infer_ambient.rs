@@ -0,0 +1,273 @@
+use generational_arena::Index;
+use std::collections::HashMap;
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::context::{redeclaration_error, Binding, Context};
+use crate::folder::{walk_index, Folder};
+use crate::infer_pattern::Assump;
+use crate::key_value_store::KeyValueStore;
+use crate::type_error::TypeError;
+use crate::types::{Keyword, Scheme, TObjElem, TProp, TPropKey, Type, TypeKind, TypeRef};
+
+impl Checker {
+    // Gives the value declared by a `declare fn`, `declare class`, or
+    // `declare module` decl a provisional type so that it can be referenced
+    // before the decl has been inferred.  Classes also get a placeholder
+    // scheme for the type of their instances.
+    pub(crate) fn infer_ambient_prebinding(
+        &mut self,
+        kind: &DeclKind,
+        attrs: &[Attribute],
+        ctx: &mut Context,
+    ) -> Result<(String, Binding), TypeError> {
+        let (name, name_span) = ambient_decl_name(kind);
+
+        if let DeclKind::ClassDecl(decl) = kind {
+            self.add_type_decl(name, name_span, ctx);
+            let is_builtin = ctx.shadow_builtin_scheme(name);
+            if self.add_class_placeholder(decl, ctx)? && !is_builtin {
+                return Err(redeclaration_error(name));
+            }
+        }
+
+        let binding = Binding {
+            index: self.new_type_var(None),
+            is_mut: false,
+            span: Some(name_span),
+            deprecated: get_deprecation(attrs),
+        };
+
+        Ok((name.to_owned(), binding))
+    }
+
+    // Adds a placeholder scheme for the instances of a declared class so that
+    // the class can be referenced before it's inferred.  Returns true if the
+    // placeholder replaced another type with the same name.
+    pub(crate) fn add_class_placeholder(
+        &mut self,
+        decl: &ClassDecl,
+        ctx: &mut Context,
+    ) -> Result<bool, TypeError> {
+        // Only the number of type params is needed to check references.
+        let type_params =
+            self.infer_type_params(&mut decl.class.type_params.clone(), &mut ctx.clone())?;
+        // References to the placeholder are looked up lazily, like those to
+        // type params, so that they resolve to the class once it's inferred.
+        let placeholder_scheme = Scheme {
+            t: self.new_keyword(Keyword::Unknown),
+            type_params,
+            is_type_param: true,
+            opaque_module: None,
+        };
+        Ok(ctx
+            .schemes
+            .insert(decl.name.to_owned(), placeholder_scheme)
+            .is_some())
+    }
+
+    // Infers the value declared by a `declare fn`, `declare class`, or
+    // `declare module` decl and adds it to `ctx`.
+    pub(crate) fn infer_ambient_decl(
+        &mut self,
+        kind: &mut DeclKind,
+        ctx: &mut Context,
+    ) -> Result<Assump, TypeError> {
+        let t = match kind {
+            DeclKind::FnDecl(FnDecl { type_ann, .. }) => self.infer_type_ann(type_ann, ctx)?,
+            DeclKind::ClassDecl(decl) => self.infer_class_decl(decl, ctx)?,
+            DeclKind::ModuleDecl(decl) => self.infer_module_decl(decl, ctx)?,
+            DeclKind::TypeDecl(_) | DeclKind::VarDecl(_) | DeclKind::GlobalDecl(_) => {
+                return Err(TypeError {
+                    message: "expected `declare fn`, `declare class`, or `declare module`"
+                        .to_string(),
+                })
+            }
+        };
+
+        let (name, name_span) = ambient_decl_name(kind);
+        self.add_value_decl(name, name_span, t);
+
+        // Bindings are type vars, like those introduced by patterns, so that
+        // they can be generalized in place.
+        let index = self.new_type_var(None);
+        self.bind(ctx, index, t)?;
+
+        let binding = Binding {
+            index,
+            is_mut: false,
+            span: Some(name_span),
+            deprecated: None,
+        };
+        ctx.values.insert(name.to_owned(), binding.clone());
+
+        Ok(Assump::from([(name.to_owned(), binding)]))
+    }
+
+    // Infers the type of a `declare module` decl, an object whose properties
+    // are the values declared inside of it.  The types declared inside of it
+    // are added to `ctx` with qualified names, e.g. `Intl.Locale`.
+    fn infer_module_decl(
+        &mut self,
+        decl: &mut ModuleDecl,
+        ctx: &mut Context,
+    ) -> Result<Index, TypeError> {
+        let ModuleDecl { name, decls, .. } = decl;
+
+        // Members can reference each other without qualifying their names.
+        let mut mod_ctx = ctx.clone();
+
+        // Types are inferred first so that values can reference them.
+        for decl in decls.iter_mut() {
+            match &mut decl.kind {
+                DeclKind::TypeDecl(type_decl) => {
+                    self.check_attrs(&decl.attrs);
+                    self.infer_type_decl(type_decl, &mut mod_ctx)?;
+                }
+                DeclKind::ClassDecl(class_decl) => {
+                    self.add_class_placeholder(class_decl, &mut mod_ctx)?;
+                }
+                _ => (),
+            }
+        }
+
+        let mut elems: Vec<TObjElem> = vec![];
+        for decl in decls.iter_mut() {
+            let Decl { kind, attrs, .. } = decl;
+            let bindings = match kind {
+                DeclKind::TypeDecl(_) => continue,
+                DeclKind::GlobalDecl(_) => {
+                    return Err(TypeError {
+                        message: "`declare global` can only be used at the top-level".to_string(),
+                    })
+                }
+                DeclKind::VarDecl(var_decl) => {
+                    self.check_attrs(attrs);
+                    self.infer_var_decl(var_decl, &mut mod_ctx)?
+                }
+                DeclKind::FnDecl(_) | DeclKind::ClassDecl(_) | DeclKind::ModuleDecl(_) => {
+                    self.check_attrs(attrs);
+                    self.infer_ambient_decl(kind, &mut mod_ctx)?
+                }
+            };
+            for (name, binding) in bindings {
+                elems.push(TObjElem::Prop(TProp {
+                    name: TPropKey::StringKey(name),
+                    t: binding.index,
+                    optional: false,
+                    readonly: !binding.is_mut,
+                }));
+            }
+        }
+
+        // References to the module's types are qualified so that they can be
+        // used outside of it.
+        let names: HashMap<String, String> = get_module_type_names(decls)
+            .into_iter()
+            .map(|type_name| (type_name.to_owned(), format!("{name}.{type_name}")))
+            .collect();
+
+        let mut qualify = QualifyTypeRefs {
+            checker: self,
+            names: &names,
+        };
+        for elem in elems.iter_mut() {
+            if let TObjElem::Prop(prop) = elem {
+                prop.t = qualify.fold_index(&prop.t);
+            }
+        }
+        for (type_name, qualified_name) in &names {
+            let scheme = mod_ctx.get_scheme(type_name)?;
+            let scheme = Scheme {
+                t: qualify.fold_index(&scheme.t),
+                ..scheme
+            };
+            ctx.schemes.insert(qualified_name.to_owned(), scheme);
+        }
+
+        Ok(self.new_object_type(&elems))
+    }
+}
+
+// Returns the name of the value declared by an ambient decl and its span.
+fn ambient_decl_name(kind: &DeclKind) -> (&str, Span) {
+    match kind {
+        DeclKind::FnDecl(FnDecl {
+            name, name_span, ..
+        })
+        | DeclKind::ClassDecl(ClassDecl {
+            name, name_span, ..
+        })
+        | DeclKind::ModuleDecl(ModuleDecl {
+            name, name_span, ..
+        }) => (name, *name_span),
+        DeclKind::TypeDecl(_) | DeclKind::VarDecl(_) | DeclKind::GlobalDecl(_) => {
+            unreachable!("only ambient decls are passed to `ambient_decl_name`")
+        }
+    }
+}
+
+// Returns the keyword after `declare` for ambient decls, used in errors.
+pub(crate) fn ambient_keyword(kind: &DeclKind) -> &'static str {
+    match kind {
+        DeclKind::FnDecl(_) => "fn",
+        DeclKind::ClassDecl(_) => "class",
+        DeclKind::ModuleDecl(_) => "module",
+        DeclKind::TypeDecl(_) => "type",
+        DeclKind::VarDecl(_) => "let",
+        DeclKind::GlobalDecl(_) => "global",
+    }
+}
+
+// Returns the names of the types declared inside of a `declare module`,
+// including those of nested modules, e.g. `Format.Options`.
+pub(crate) fn get_module_type_names(decls: &[Decl]) -> Vec<String> {
+    let mut names = vec![];
+    for decl in decls {
+        match &decl.kind {
+            DeclKind::TypeDecl(TypeDecl { name, .. }) => names.push(name.to_owned()),
+            DeclKind::ClassDecl(ClassDecl { name, .. }) => names.push(name.to_owned()),
+            DeclKind::ModuleDecl(ModuleDecl { name, decls, .. }) => {
+                for type_name in get_module_type_names(decls) {
+                    names.push(format!("{name}.{type_name}"));
+                }
+            }
+            DeclKind::VarDecl(_) | DeclKind::FnDecl(_) | DeclKind::GlobalDecl(_) => (),
+        }
+    }
+    names
+}
+
+// Replaces references to the types in `names` with references to their
+// qualified names.  The new references are looked up by name since the
+// schemes they had were for the unqualified names.
+struct QualifyTypeRefs<'a> {
+    checker: &'a mut Checker,
+    names: &'a HashMap<String, String>,
+}
+
+impl<'a> KeyValueStore<Index, Type> for QualifyTypeRefs<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.checker.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.checker.arena.insert(t)
+    }
+}
+
+impl<'a> Folder for QualifyTypeRefs<'a> {
+    fn fold_index(&mut self, index: &Index) -> Index {
+        let index = self.checker.prune(*index);
+        match &self.checker.arena[index].kind.clone() {
+            TypeKind::TypeRef(TypeRef {
+                name, type_args, ..
+            }) if self.names.contains_key(name) => {
+                let type_args: Vec<Index> = type_args.iter().map(|t| self.fold_index(t)).collect();
+                self.checker
+                    .new_type_ref(&self.names[name], None, &type_args)
+            }
+            _ => walk_index(self, &index),
+        }
+    }
+}
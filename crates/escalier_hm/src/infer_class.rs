@@ -4,12 +4,13 @@ use std::collections::HashMap;
 
 use escalier_ast::{self as syntax, *};
 
-use crate::ast_utils::{find_returns, find_throws};
+use crate::ast_utils::find_throws;
 use crate::checker::Checker;
 use crate::context::*;
 use crate::infer::generalize_func;
 use crate::infer_pattern::pattern_to_tpat;
 use crate::key_value_store::KeyValueStore;
+use crate::provenance::Provenance;
 use crate::type_error::TypeError;
 use crate::types::{self, *};
 use crate::visitor::{walk_index, Visitor};
@@ -25,7 +26,9 @@ impl Checker {
         // TODO: mutate the instance_scheme since only the methods need
         // further type checking.
         // TODO: unify _static_type with the static type of the class
-        let (instance_scheme, _static_type) = self.infer_class_interface(class, &mut cls_ctx)?;
+        let self_type = self.new_type_var(None);
+        let (instance_scheme, _static_type) =
+            self.infer_class_interface(class, self_type, &mut cls_ctx)?;
 
         cls_ctx
             .schemes
@@ -51,6 +54,7 @@ impl Checker {
                             throws: sig_throws,
                             is_async,
                             is_gen: _,
+                            captures: _,
                         },
                 }) => {
                     let mut sig_ctx = cls_ctx.clone();
@@ -63,6 +67,8 @@ impl Checker {
                         let binding = Binding {
                             index: self.new_type_ref("Self", Some(instance_scheme.clone()), &[]),
                             is_mut: *is_mutating,
+                            span: None,
+                            deprecated: None,
                         };
                         sig_ctx.values.insert("self".to_string(), binding);
                     }
@@ -77,54 +83,42 @@ impl Checker {
                             Some(type_ann) => self.infer_type_ann(type_ann, &mut sig_ctx)?,
                             None => self.new_type_var(None),
                         };
-                        pattern.inferred_type = Some(type_ann_t);
 
                         let (assumps, param_t) = self.infer_pattern(pattern, &sig_ctx)?;
                         self.unify(&sig_ctx, param_t, type_ann_t)?;
 
+                        // Patterns that are refinements, e.g. `{type: "click", x}`, narrow
+                        // the param's type so that callers can only pass matching values.
+                        let t = match pattern.is_refinement() {
+                            true => param_t,
+                            false => type_ann_t,
+                        };
+                        pattern.inferred_type = Some(t);
+
                         for (name, binding) in assumps {
                             sig_ctx.non_generic.insert(binding.index);
                             sig_ctx.values.insert(name.to_owned(), binding);
                         }
 
                         func_params.push(types::FuncParam {
-                            pattern: pattern_to_tpat(pattern, true),
-                            t: type_ann_t,
+                            pattern: pattern_to_tpat(pattern),
+                            t,
                             optional: *optional,
                         });
                     }
 
+                    let ret_t = return_type
+                        .as_mut()
+                        .map(|return_type| self.infer_type_ann(return_type, &mut sig_ctx))
+                        .transpose()?;
+
                     let mut body_ctx = sig_ctx.clone();
                     body_ctx.is_async = *is_async;
 
-                    // TODO: dedupe with infer_expression
-                    let body_t = 'outer: {
-                        match body {
-                            BlockOrExpr::Block(Block { stmts, .. }) => {
-                                for stmt in stmts.iter_mut() {
-                                    body_ctx = body_ctx.clone();
-                                    self.infer_statement(stmt, &mut body_ctx)?;
-                                    if let StmtKind::Return(_) = stmt.kind {
-                                        let ret_types: Vec<Index> = find_returns(body)
-                                            .iter()
-                                            .filter_map(|ret| ret.inferred_type)
-                                            .collect();
-
-                                        // TODO: warn about unreachable code.
-                                        break 'outer self.new_union_type(&ret_types);
-                                    }
-                                }
-
-                                // If we don't encounter a return statement, we assume
-                                // the return type is `undefined`.
-                                self.new_lit_type(&Literal::Undefined)
-                            }
-                            BlockOrExpr::Expr(expr) => {
-                                // TODO: use `find_returns` here as well
-                                self.infer_expression(expr, &mut body_ctx)?
-                            }
-                        }
-                    };
+                    // The return type of async methods is a `Promise` so it
+                    // can't be compared with the body's return values.
+                    let body_ret_t = if *is_async { None } else { ret_t };
+                    let body_t = self.infer_func_body(body, &mut body_ctx, body_ret_t)?;
 
                     let body_throws = find_throws(body);
                     let body_throws = if body_throws.is_empty() {
@@ -153,12 +147,12 @@ impl Checker {
                         (None, None) => None,
                     };
 
-                    let name = match name {
-                        PropName::Ident(Ident { name, span: _ }) => name.to_owned(),
-                        PropName::Computed(_) => todo!(),
-                    };
+                    let is_constructor = matches!(
+                        name,
+                        PropName::Ident(Ident { name, span: _ }) if name == "constructor"
+                    );
 
-                    if &name == "constructor" {
+                    if is_constructor {
                         static_elems.push(TObjElem::Constructor(types::Function {
                             params: func_params,
                             ret: self.new_type_ref("Self", Some(instance_scheme.clone()), &[]),
@@ -168,31 +162,38 @@ impl Checker {
                         continue;
                     }
 
-                    let ret_t = match return_type {
-                        Some(return_type) => self.infer_type_ann(return_type, &mut sig_ctx)?,
+                    let ret_t = match ret_t {
+                        Some(ret_t) => ret_t,
                         None => self.new_type_var(None),
                     };
 
                     self.unify(&sig_ctx, body_t, ret_t)?;
 
-                    let method = TObjElem::Method(TMethod {
-                        name: TPropKey::StringKey(name.clone()),
-                        mutates: *is_mutating,
-                        function: types::Function {
-                            type_params,
-                            params: func_params,
-                            ret: ret_t,
-                            throws,
-                        },
-                    });
+                    let name = self.infer_prop_name(name, &mut sig_ctx)?;
 
                     // if let Some(m) = map.get(&name) {
                     //     // TODO
                     // }
 
                     match is_static {
-                        true => static_elems.push(method),
-                        false => instance_elems.push(method),
+                        // Static methods don't have a `self` param so they're
+                        // modeled as properties whose values are functions.
+                        true => static_elems.push(TObjElem::Prop(TProp {
+                            name,
+                            t: self.new_func_type(&func_params, ret_t, &type_params, throws),
+                            optional: false,
+                            readonly: true,
+                        })),
+                        false => instance_elems.push(TObjElem::Method(TMethod {
+                            name,
+                            mutates: *is_mutating,
+                            function: types::Function {
+                                type_params,
+                                params: func_params,
+                                ret: ret_t,
+                                throws,
+                            },
+                        })),
                     };
                 }
                 ClassMember::Getter(_) => todo!(),
@@ -271,6 +272,21 @@ impl Checker {
             }
         }
 
+        // Classes without an explicit constructor get a default one that
+        // doesn't take any params.
+        if !static_elems
+            .iter()
+            .any(|elem| matches!(elem, TObjElem::Constructor(_)))
+        {
+            static_elems.push(TObjElem::Constructor(types::Function {
+                params: vec![],
+                ret: self.new_type_ref("Self", Some(instance_scheme.clone()), &[]),
+                type_params: None,
+                throws: None,
+            }));
+        }
+
+        instance_type.provenance = Some(Provenance::Class(Box::new(class.to_owned())));
         let instance_type = self.arena.insert(instance_type);
         let static_type = self.new_object_type(&static_elems);
 
@@ -278,6 +294,7 @@ impl Checker {
             type_params: None,
             t: instance_type,
             is_type_param: false,
+            opaque_module: None,
         };
 
         replace_self_type_refs(&mut self.arena, &instance_type, &self_scheme);
@@ -286,16 +303,124 @@ impl Checker {
         Ok(static_type)
     }
 
+    // Infers the type of `declare class Name<T> {...}` from the signatures of
+    // its members.  This is the type of the class's constructor and static
+    // members, its instances have the type `Name<T>` which is added to `ctx`.
+    pub(crate) fn infer_class_decl(
+        &mut self,
+        decl: &mut ClassDecl,
+        ctx: &mut Context,
+    ) -> Result<Index, TypeError> {
+        let ClassDecl {
+            name,
+            name_span,
+            class,
+        } = decl;
+
+        if class.super_class.is_some() {
+            return Err(TypeError {
+                message: format!("`declare class {name}` can't extend another class"),
+            });
+        }
+
+        self.add_type_decl(name, *name_span, ctx);
+
+        // NOTE: We clone `ctx` so that type params don't escape the class.
+        let mut sig_ctx = ctx.clone();
+        let type_params = self.infer_type_params(&mut class.type_params, &mut sig_ctx)?;
+
+        // References to the class inside of its members are looked up lazily
+        // since its scheme isn't complete yet, see `infer_type_decl`.
+        let placeholder_scheme = Scheme {
+            t: self.new_keyword(Keyword::Unknown),
+            type_params: type_params.clone(),
+            is_type_param: true,
+            opaque_module: None,
+        };
+        sig_ctx.schemes.insert(name.to_owned(), placeholder_scheme);
+
+        let self_type = self.new_type_var(None);
+        let (instance_scheme, static_type) =
+            self.infer_class_interface(class, self_type, &mut sig_ctx)?;
+
+        let scheme = Scheme {
+            type_params: type_params.clone(),
+            ..instance_scheme
+        };
+        let type_args: Vec<Index> = type_params
+            .iter()
+            .flatten()
+            .map(|type_param| self.new_type_ref(&type_param.name, None, &[]))
+            .collect();
+        let instance_t = self.new_type_ref(name, Some(scheme.clone()), &type_args);
+        self.bind(ctx, self_type, instance_t)?;
+
+        // Constructors return instances of the class so they're generic over
+        // the class's type params.
+        let mut static_elems = match &self.arena[static_type].kind {
+            TypeKind::Object(obj) => obj.elems.clone(),
+            _ => vec![],
+        };
+        let mut has_constructor = false;
+        for elem in static_elems.iter_mut() {
+            if let TObjElem::Constructor(constructor) = elem {
+                let ctor_type_params: Vec<types::TypeParam> = type_params
+                    .iter()
+                    .chain(constructor.type_params.iter())
+                    .flatten()
+                    .cloned()
+                    .collect();
+                constructor.type_params =
+                    (!ctor_type_params.is_empty()).then_some(ctor_type_params);
+                constructor.ret = instance_t;
+                has_constructor = true;
+            }
+        }
+
+        // Classes without a constructor can be created without any args.
+        if !has_constructor {
+            static_elems.push(TObjElem::Constructor(types::Function {
+                params: vec![],
+                ret: instance_t,
+                type_params: type_params.clone(),
+                throws: None,
+            }));
+        }
+
+        ctx.schemes.insert(name.to_owned(), scheme);
+
+        Ok(self.new_object_type(&static_elems))
+    }
+
+    /// Returns the scheme for instances of a class given its static type.
+    pub fn get_instance_scheme(&self, static_type: Index) -> Option<Scheme> {
+        if let TypeKind::Object(types::Object { elems, .. }) = &self.arena[static_type].kind {
+            for elem in elems {
+                if let TObjElem::Constructor(constructor) = elem {
+                    if let TypeKind::TypeRef(TypeRef {
+                        scheme: Some(scheme),
+                        ..
+                    }) = &self.arena[constructor.ret].kind
+                    {
+                        return Some(scheme.to_owned());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    // Infers the types of the members of `class` from their signatures.
+    // `self_type` is the type of `Self` inside of them.
     fn infer_class_interface(
         &mut self,
         class: &mut Class,
+        self_type: Index,
         ctx: &mut Context,
     ) -> Result<(Scheme, Index), TypeError> {
         let mut instance_elems: Vec<TObjElem> = vec![];
         let mut static_elems: Vec<TObjElem> = vec![];
 
-        let self_type = self.new_type_var(None);
-
         let mut cls_ctx = ctx.clone();
         cls_ctx.schemes.insert(
             "Self".to_string(),
@@ -303,6 +428,7 @@ impl Checker {
                 t: self_type,
                 type_params: None,
                 is_type_param: false,
+                opaque_module: None,
             },
         );
 
@@ -325,6 +451,7 @@ impl Checker {
                             throws,      // TODO: include in signature
                             is_async: _, // return type is a promise
                             is_gen: _,   // return type is a generator
+                            captures: _,
                         },
                 }) => {
                     let mut sig_ctx = cls_ctx.clone();
@@ -338,7 +465,7 @@ impl Checker {
                                 None => self.new_type_var(None),
                             };
                             Ok(types::FuncParam {
-                                pattern: pattern_to_tpat(&param.pattern, true),
+                                pattern: pattern_to_tpat(&param.pattern),
                                 t,
                                 optional: param.optional,
                             })
@@ -363,7 +490,7 @@ impl Checker {
                             }
                             TPropKey::StringKey(name.to_string())
                         }
-                        PropName::Computed(_) => todo!(),
+                        PropName::Computed(expr) => self.infer_computed_key(expr, &mut sig_ctx)?,
                     };
 
                     if is_constructor {
@@ -376,20 +503,23 @@ impl Checker {
                         continue;
                     }
 
-                    let method = TObjElem::Method(TMethod {
-                        name,
-                        mutates: *is_mutating,
-                        function: types::Function {
-                            type_params,
-                            params: func_params,
-                            ret,
-                            throws,
-                        },
-                    });
-
                     match is_static {
-                        true => static_elems.push(method),
-                        false => instance_elems.push(method),
+                        true => static_elems.push(TObjElem::Prop(TProp {
+                            name,
+                            t: self.new_func_type(&func_params, ret, &type_params, throws),
+                            optional: false,
+                            readonly: true,
+                        })),
+                        false => instance_elems.push(TObjElem::Method(TMethod {
+                            name,
+                            mutates: *is_mutating,
+                            function: types::Function {
+                                type_params,
+                                params: func_params,
+                                ret,
+                                throws,
+                            },
+                        })),
                     };
                 }
                 ClassMember::Getter(Getter {
@@ -411,7 +541,7 @@ impl Checker {
                         PropName::Ident(Ident { name, span: _ }) => {
                             TPropKey::StringKey(name.to_string())
                         }
-                        PropName::Computed(_) => todo!(),
+                        PropName::Computed(expr) => self.infer_computed_key(expr, &mut sig_ctx)?,
                     };
 
                     let getter = TObjElem::Getter(TGetter {
@@ -435,7 +565,7 @@ impl Checker {
                         PropName::Ident(Ident { name, span: _ }) => {
                             TPropKey::StringKey(name.to_string())
                         }
-                        PropName::Computed(_) => todo!(),
+                        PropName::Computed(expr) => self.infer_computed_key(expr, &mut sig_ctx)?,
                     };
 
                     let setter = TObjElem::Setter(TSetter {
@@ -482,6 +612,7 @@ impl Checker {
             // default, each function gets its own type params
             type_params: None,
             is_type_param: false,
+            opaque_module: None,
         };
 
         let static_type = self.new_object_type(&static_elems);
@@ -510,7 +641,7 @@ impl Checker {
         }
 
         Ok(types::FuncParam {
-            pattern: pattern_to_tpat(&param.pattern, true),
+            pattern: pattern_to_tpat(&param.pattern),
             t: type_ann_t,
             optional: param.optional,
         })
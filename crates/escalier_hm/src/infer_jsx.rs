@@ -0,0 +1,326 @@
+use generational_arena::Index;
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::context::*;
+use crate::diagnostic::Diagnostic;
+use crate::type_error::TypeError;
+
+impl Checker {
+    pub fn infer_jsx_element(
+        &mut self,
+        elem: &mut JSXElement,
+        ctx: &mut Context,
+    ) -> Result<Index, TypeError> {
+        // JSX elements are checked as if they were calls to the component
+        // with a single props object, e.g. `<Foo bar={baz}>qux</Foo>` is
+        // checked as `Foo({bar: baz, children: "qux"})`.  The props object is
+        // built from copies of the attribute values and children which are
+        // moved back into the element once they've been inferred.
+        let mut props = build_props(&elem.opening.attrs, &elem.children, elem.span);
+
+        match &elem.opening.name {
+            JSXElementName::Ident(Ident { name, .. }) if is_intrinsic(name) => {
+                let props_t = self.infer_expression(&mut props, ctx)?;
+                // Intrinsic element types don't usually declare `children` so
+                // extra props aren't reported for them.
+                let props_t = self.remove_freshness(props_t);
+                let expected_t = self.get_intrinsic_props(name, ctx)?;
+
+                if let Some(expected_t) = expected_t {
+                    if let Err(error) = self.unify(ctx, props_t, expected_t) {
+                        self.current_report.diagnostics.push(Diagnostic {
+                            code: 1000,
+                            message: format!("Props for <{name}> are incorrect"),
+                            reasons: vec![error],
+                            span: Some(elem.span),
+                            suggestion: None,
+                            related: vec![],
+                        });
+                    }
+                }
+            }
+            name => {
+                let mut callee = jsx_element_name_to_expr(name, elem.span);
+                let callee_t = self.infer_expression(&mut callee, ctx)?;
+                let mut args = [props];
+                self.unify_call(ctx, &mut args, None, false, callee_t)?;
+                [props] = args;
+            }
+        }
+
+        restore_props(props, &mut elem.opening.attrs, &mut elem.children);
+
+        self.new_jsx_element_type(ctx)
+    }
+
+    pub fn infer_jsx_fragment(
+        &mut self,
+        fragment: &mut JSXFragment,
+        ctx: &mut Context,
+    ) -> Result<Index, TypeError> {
+        if let Some(mut children) = build_children(&fragment.children, fragment.span) {
+            self.infer_expression(&mut children, ctx)?;
+            restore_children(children, &mut fragment.children);
+        }
+
+        self.new_jsx_element_type(ctx)
+    }
+
+    // Returns the type of the props for an intrinsic element, e.g. `div`, or
+    // `None` if no intrinsic elements type is in scope in which case any props
+    // are allowed.
+    fn get_intrinsic_props(
+        &mut self,
+        name: &str,
+        ctx: &Context,
+    ) -> Result<Option<Index>, TypeError> {
+        let intrinsics_name = self.jsx_types.intrinsic_elements.to_owned();
+        let scheme = match ctx.schemes.get(&intrinsics_name) {
+            Some(scheme) => scheme.to_owned(),
+            None => return Ok(None),
+        };
+
+        let intrinsics = self.new_type_ref(&intrinsics_name, Some(scheme), &[]);
+        let intrinsics = self.expand_type(ctx, intrinsics)?;
+        let key = self.new_lit_type(&Literal::String(name.to_owned()));
+
+        match self.get_prop_value(ctx, intrinsics, key, false) {
+            Ok(t) => Ok(Some(t)),
+            Err(_) => Err(TypeError {
+                message: format!("<{name}> is not a valid intrinsic element"),
+            }),
+        }
+    }
+
+    fn new_jsx_element_type(&mut self, ctx: &Context) -> Result<Index, TypeError> {
+        let name = self.jsx_types.element.to_owned();
+        let scheme = ctx.get_scheme(&name)?;
+        Ok(self.new_type_ref(&name, Some(scheme), &[]))
+    }
+}
+
+// Following React's convention, lowercase names refer to built-in elements
+// while capitalized names and member expressions refer to components.
+fn is_intrinsic(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_lowercase())
+}
+
+fn jsx_element_name_to_expr(name: &JSXElementName, span: Span) -> Expr {
+    fn member_to_expr(member: &JSXMemberExpr, span: Span) -> Expr {
+        let object = match &member.obj {
+            JSXObject::Ident(ident) => ident_to_expr(ident),
+            JSXObject::JSXMemberExpr(member) => member_to_expr(member, span),
+        };
+        Expr {
+            kind: ExprKind::Member(Member {
+                object: Box::new(object),
+                property: MemberProp::Ident(member.prop.to_owned()),
+                opt_chain: false,
+            }),
+            span,
+            inferred_type: None,
+        }
+    }
+
+    match name {
+        JSXElementName::Ident(ident) => ident_to_expr(ident),
+        JSXElementName::JSXMemberExpr(member) => member_to_expr(member, span),
+    }
+}
+
+fn ident_to_expr(ident: &Ident) -> Expr {
+    Expr {
+        kind: ExprKind::Ident(ident.to_owned()),
+        span: ident.span,
+        inferred_type: None,
+    }
+}
+
+fn build_props(attrs: &[JSXAttr], children: &[JSXElementChild], span: Span) -> Expr {
+    let mut properties: Vec<PropOrSpread> = attrs
+        .iter()
+        .map(|attr| {
+            let value = match &attr.value {
+                // `<input disabled />` is short for `<input disabled={true} />`
+                None => Expr {
+                    kind: ExprKind::Bool(Bool { value: true }),
+                    span,
+                    inferred_type: None,
+                },
+                Some(JSXAttrValue::Str(value)) => Expr {
+                    kind: ExprKind::Str(Str {
+                        span,
+                        value: value.to_owned(),
+                    }),
+                    span,
+                    inferred_type: None,
+                },
+                Some(JSXAttrValue::ExprContainer(JSXExprContainer { expr })) => {
+                    expr.as_ref().to_owned()
+                }
+            };
+            PropOrSpread::Prop(expr::Prop::Property {
+                key: ObjectKey::String(attr.name.to_owned()),
+                value,
+            })
+        })
+        .collect();
+
+    if let Some(children) = build_children(children, span) {
+        properties.push(PropOrSpread::Prop(expr::Prop::Property {
+            key: ObjectKey::String("children".to_string()),
+            value: children,
+        }));
+    }
+
+    Expr {
+        kind: ExprKind::Object(Object { properties }),
+        span,
+        inferred_type: None,
+    }
+}
+
+// A single child is passed as is while multiple children are passed as a
+// tuple, matching the shape of `props.children` at runtime.
+fn build_children(children: &[JSXElementChild], span: Span) -> Option<Expr> {
+    let mut exprs: Vec<Expr> = children
+        .iter()
+        .filter_map(|child| match child {
+            JSXElementChild::Text(JSXText { value, span }) => Some(Expr {
+                kind: ExprKind::Str(Str {
+                    span: *span,
+                    value: clean_jsx_text(value)?,
+                }),
+                span: *span,
+                inferred_type: None,
+            }),
+            JSXElementChild::ExprContainer(JSXExprContainer { expr }) => {
+                Some(expr.as_ref().to_owned())
+            }
+            JSXElementChild::SpreadChild(JSXSpreadChild { expr }) => Some(expr.as_ref().to_owned()),
+            JSXElementChild::Element(elem) => Some(Expr {
+                kind: ExprKind::JSXElement(elem.as_ref().to_owned()),
+                span: elem.span,
+                inferred_type: None,
+            }),
+            JSXElementChild::Fragment(fragment) => Some(Expr {
+                kind: ExprKind::JSXFragment(fragment.as_ref().to_owned()),
+                span: fragment.span,
+                inferred_type: None,
+            }),
+        })
+        .collect();
+
+    match exprs.len() {
+        0 => None,
+        1 => exprs.pop(),
+        _ => Some(Expr {
+            kind: ExprKind::Tuple(Tuple {
+                elements: exprs.into_iter().map(ExprOrSpread::Expr).collect(),
+            }),
+            span,
+            inferred_type: None,
+        }),
+    }
+}
+
+// Moves the inferred copies of the attribute values and children from `props`
+// back into the element so that their `inferred_type`s are kept.
+fn restore_props(props: Expr, attrs: &mut [JSXAttr], children: &mut [JSXElementChild]) {
+    let ExprKind::Object(Object { properties }) = props.kind else {
+        return;
+    };
+    let mut values = properties.into_iter().map(|prop| match prop {
+        PropOrSpread::Prop(expr::Prop::Property { value, .. }) => value,
+        _ => unreachable!("build_props only creates properties"),
+    });
+
+    for (attr, value) in attrs.iter_mut().zip(values.by_ref()) {
+        if let Some(JSXAttrValue::ExprContainer(JSXExprContainer { expr })) = &mut attr.value {
+            **expr = value;
+        }
+    }
+
+    if let Some(value) = values.next() {
+        restore_children(value, children);
+    }
+}
+
+// The inverse of `build_children`.
+fn restore_children(value: Expr, children: &mut [JSXElementChild]) {
+    let exprs = match value.kind {
+        ExprKind::Tuple(Tuple { elements })
+            if children.iter().filter(|child| has_expr(child)).count() > 1 =>
+        {
+            elements
+                .into_iter()
+                .map(|elem| match elem {
+                    ExprOrSpread::Expr(expr) => expr,
+                    ExprOrSpread::Spread(expr) => expr,
+                })
+                .collect()
+        }
+        _ => vec![value],
+    };
+
+    let children = children.iter_mut().filter(|child| has_expr(child));
+    for (child, expr) in children.zip(exprs) {
+        match child {
+            JSXElementChild::ExprContainer(JSXExprContainer { expr: child })
+            | JSXElementChild::SpreadChild(JSXSpreadChild { expr: child }) => **child = expr,
+            JSXElementChild::Element(child) => {
+                if let ExprKind::JSXElement(elem) = expr.kind {
+                    **child = elem;
+                }
+            }
+            JSXElementChild::Fragment(child) => {
+                if let ExprKind::JSXFragment(frag) = expr.kind {
+                    **child = frag;
+                }
+            }
+            JSXElementChild::Text(_) => {}
+        }
+    }
+}
+
+// Whether `build_children` creates an expression for the child.
+fn has_expr(child: &JSXElementChild) -> bool {
+    match child {
+        JSXElementChild::Text(JSXText { value, .. }) => clean_jsx_text(value).is_some(),
+        _ => true,
+    }
+}
+
+// Collapses whitespace in JSX text the same way that Babel and SWC do: lines
+// are trimmed, blank lines are dropped, and the remaining lines are joined
+// with a single space.  Returns `None` if the text is only whitespace that
+// spans multiple lines.
+fn clean_jsx_text(value: &str) -> Option<String> {
+    let lines: Vec<&str> = value.lines().collect();
+    if lines.len() <= 1 {
+        return Some(value.to_owned());
+    }
+
+    let last = lines.len() - 1;
+    let cleaned = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = if i == 0 { *line } else { line.trim_start() };
+            if i == last {
+                line
+            } else {
+                line.trim_end()
+            }
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    match cleaned.is_empty() {
+        true => None,
+        false => Some(cleaned),
+    }
+}
@@ -5,6 +5,8 @@ use escalier_ast::{self as ast, *};
 
 use crate::checker::Checker;
 use crate::context::{Binding, Context};
+use crate::infer::literals_equal;
+use crate::references::{find_shorthand_bindings, Namespace, Occurrence};
 use crate::type_error::TypeError;
 use crate::types::{self, *};
 
@@ -24,7 +26,11 @@ impl Checker {
             ctx: &Context,
         ) -> Result<Index, TypeError> {
             let t = match &mut pattern.kind {
-                PatternKind::Ident(BindingIdent { name, mutable, .. }) => {
+                PatternKind::Ident(BindingIdent {
+                    name,
+                    mutable,
+                    span,
+                }) => {
                     let t = checker.new_type_var(None);
                     if assump
                         .insert(
@@ -32,6 +38,8 @@ impl Checker {
                             Binding {
                                 index: t,
                                 is_mut: *mutable,
+                                span: Some(*span),
+                                deprecated: None,
                             },
                         )
                         .is_some()
@@ -51,46 +59,72 @@ impl Checker {
                     let mut elems: Vec<types::TObjElem> = vec![];
 
                     for prop in props.iter_mut() {
+                        if rest_opt_ty.is_some() && !matches!(prop, ObjectPatProp::Rest(_)) {
+                            return Err(TypeError {
+                                message:
+                                    "Rest pattern must be the last property in an object pattern"
+                                        .to_string(),
+                            });
+                        }
                         match prop {
                             // re-assignment, e.g. {x: new_x, y: new_y} = point
-                            ObjectPatProp::KeyValue(KeyValuePatProp { key, value, .. }) => {
-                                // We ignore `init` for now, we can come back later to handle
-                                // default values.
-                                // TODO: handle default values
-
-                                // TODO: bubble the error up from infer_patter_rec() if there is one.
-                                let value_type =
-                                    infer_pattern_rec(checker, value.as_mut(), assump, ctx)?;
+                            ObjectPatProp::KeyValue(KeyValuePatProp {
+                                key, value, init, ..
+                            }) => {
+                                let (value_type, optional) = match init {
+                                    Some(init) => {
+                                        let ident = match &value.kind {
+                                            PatternKind::Ident(ident) => ident,
+                                            _ => return Err(TypeError {
+                                                message: "Default values are only supported for identifiers".to_string(),
+                                            }),
+                                        };
+                                        let t = infer_default(checker, ident, init, assump, ctx)?;
+                                        (t, true)
+                                    }
+                                    // TODO: bubble the error up from infer_patter_rec() if there is one.
+                                    None => (
+                                        infer_pattern_rec(checker, value.as_mut(), assump, ctx)?,
+                                        false,
+                                    ),
+                                };
 
                                 elems.push(types::TObjElem::Prop(types::TProp {
                                     name: TPropKey::StringKey(key.name.to_owned()),
-                                    optional: false,
+                                    optional,
                                     readonly: false,
                                     t: value_type,
                                 }))
                             }
-                            ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) => {
-                                // We ignore `init` for now, we can come back later to handle
-                                // default values.
-                                // TODO: handle default values
-
-                                let t = checker.new_type_var(None);
-                                if assump
-                                    .insert(
-                                        ident.name.to_owned(),
-                                        Binding {
-                                            index: t,
-                                            is_mut: false,
-                                        },
-                                    )
-                                    .is_some()
-                                {
-                                    todo!("return an error");
-                                }
+                            ObjectPatProp::Shorthand(ShorthandPatProp { ident, init, .. }) => {
+                                let t = match init {
+                                    Some(init) => infer_default(checker, ident, init, assump, ctx)?,
+                                    None => {
+                                        let t = checker.new_type_var(None);
+                                        if assump
+                                            .insert(
+                                                ident.name.to_owned(),
+                                                Binding {
+                                                    index: t,
+                                                    is_mut: ident.mutable,
+                                                    span: Some(ident.span),
+                                                    deprecated: None,
+                                                },
+                                            )
+                                            .is_some()
+                                        {
+                                            return Err(TypeError {
+                                                message: "Duplicate identifier in pattern"
+                                                    .to_string(),
+                                            });
+                                        }
+                                        t
+                                    }
+                                };
 
                                 elems.push(types::TObjElem::Prop(types::TProp {
                                     name: TPropKey::StringKey(ident.name.to_owned()),
-                                    optional: false,
+                                    optional: init.is_some(),
                                     readonly: false,
                                     t,
                                 }))
@@ -124,13 +158,36 @@ impl Checker {
                 }
                 PatternKind::Tuple(ast::TuplePat { elems, optional: _ }) => {
                     let mut elem_types = vec![];
-                    for elem in elems.iter_mut() {
+                    let last = elems.len().saturating_sub(1);
+                    for (i, elem) in elems.iter_mut().enumerate() {
                         let t = match elem {
                             Some(elem) => {
-                                // TODO:
-                                // - handle elem.init
-                                // - check for multiple rest patterns
-                                infer_pattern_rec(checker, &mut elem.pattern, assump, ctx)?
+                                if i != last {
+                                    if let PatternKind::Rest(_) = elem.pattern.kind {
+                                        return Err(TypeError {
+                                            message: "Rest pattern must be the last element in a tuple pattern".to_string(),
+                                        });
+                                    }
+                                }
+                                match (&elem.pattern.kind, &mut elem.init) {
+                                    (PatternKind::Ident(ident), Some(init)) => {
+                                        let t = infer_default(checker, ident, init, assump, ctx)?;
+                                        // `undefined` comes first so that it isn't
+                                        // bound to `t` when unifying.
+                                        let undefined = checker.new_lit_type(&Literal::Undefined);
+                                        checker.new_union_type(&[undefined, t])
+                                    }
+                                    (_, Some(_)) => {
+                                        return Err(TypeError {
+                                            message:
+                                                "Default values are only supported for identifiers"
+                                                    .to_string(),
+                                        })
+                                    }
+                                    (_, None) => {
+                                        infer_pattern_rec(checker, &mut elem.pattern, assump, ctx)?
+                                    }
+                                }
                             }
                             None => checker.new_lit_type(&Literal::Undefined),
                         };
@@ -143,6 +200,7 @@ impl Checker {
                 PatternKind::Is(IsPat { ident, is_id }) => {
                     let t = match is_id.name.as_str() {
                         "number" => checker.new_primitive(Primitive::Number),
+                        "bigint" => checker.new_primitive(Primitive::BigInt),
                         "string" => checker.new_primitive(Primitive::String),
                         "boolean" => checker.new_primitive(Primitive::Boolean),
                         name => checker.get_type(name, ctx)?,
@@ -153,25 +211,394 @@ impl Checker {
                         Binding {
                             index: t,
                             is_mut: false,
+                            span: Some(ident.span),
+                            deprecated: None,
                         },
                     );
 
                     t
                 }
+                // When a range pattern is the top-level pattern of a match arm
+                // it's narrowed using `infer_range_pattern` instead.
+                PatternKind::Range(_) => checker.new_primitive(Primitive::Number),
                 PatternKind::Wildcard => checker.new_type_var(None),
             };
 
             Ok(t)
         }
 
+        // Infers the type of an identifier with a default value, e.g. `x = 0`
+        // in `{x = 0}` or `[x = 0]`.  The binding's type is the union of the
+        // type being matched, which is returned, and the default's type.
+        fn infer_default(
+            checker: &mut Checker,
+            ident: &BindingIdent,
+            init: &mut Expr,
+            assump: &mut Assump,
+            ctx: &Context,
+        ) -> Result<Index, TypeError> {
+            let mut init_ctx = ctx.clone();
+            let init_t = checker.infer_expression(init, &mut init_ctx)?;
+
+            let t = checker.new_type_var(None);
+            let binding_t = checker.new_union_type(&[t, init_t]);
+            if assump
+                .insert(
+                    ident.name.to_owned(),
+                    Binding {
+                        index: binding_t,
+                        is_mut: ident.mutable,
+                        span: Some(ident.span),
+                        deprecated: None,
+                    },
+                )
+                .is_some()
+            {
+                return Err(TypeError {
+                    message: "Duplicate identifier in pattern".to_string(),
+                });
+            }
+
+            Ok(t)
+        }
+
         let mut assump = Assump::default();
-        let pat_type = infer_pattern_rec(self, pattern, &mut assump, ctx)?;
+        let pat_type = self.with_span(pattern.span, |checker| {
+            infer_pattern_rec(checker, pattern, &mut assump, ctx)
+        })?;
+
+        let shorthands = find_shorthand_bindings(pattern);
+        for (name, binding) in &assump {
+            if let Some(span) = binding.span {
+                self.span_index.insert(span, binding.index);
+                self.symbols.insert(
+                    span,
+                    Occurrence {
+                        namespace: Namespace::Value,
+                        name: name.to_owned(),
+                        decl: span,
+                        is_shorthand: shorthands.contains(&span),
+                    },
+                );
+            }
+        }
 
         Ok((assump, pat_type))
     }
+
+    // Infers the type of a refutable pattern, e.g. in a match arm or an if-let,
+    // and checks that it's a sub-type of `expr_t`.  Range patterns also narrow
+    // the value being matched if it's an immutable variable.
+    pub fn infer_refutable_pattern(
+        &mut self,
+        ctx: &Context,
+        pattern: &mut Pattern,
+        expr: &Expr,
+        expr_t: Index,
+    ) -> Result<Assump, TypeError> {
+        let (mut assump, pat_t) = match &pattern.kind {
+            PatternKind::Range(range) => (
+                Assump::default(),
+                self.infer_range_pattern(ctx, range, expr_t)?,
+            ),
+            _ => self.infer_pattern(pattern, ctx)?,
+        };
+
+        // The type of the value being matched inside of the arm, if the
+        // pattern narrows it.
+        let narrowed_t = match &pattern.kind {
+            PatternKind::Range(_) => Some(pat_t),
+            PatternKind::Tuple(tuple) => Some(self.narrow_by_tuple_pattern(ctx, tuple, expr_t)?),
+            _ => None,
+        };
+
+        // Checks that the pattern is a sub-type of expr
+        match &pattern.kind {
+            PatternKind::Tuple(tuple) => {
+                let narrowed_t = narrowed_t.unwrap();
+                let merged_t = self.merge_tuple_members(ctx, tuple, narrowed_t)?;
+                self.unify(ctx, pat_t, merged_t.unwrap_or(narrowed_t))?
+            }
+            _ => self.unify(ctx, pat_t, expr_t)?,
+        };
+
+        if let (Some(narrowed_t), ExprKind::Ident(Ident { name, .. })) = (narrowed_t, &expr.kind) {
+            if let Some(binding) = ctx.values.get(name) {
+                if !binding.is_mut {
+                    assump.insert(
+                        name.to_owned(),
+                        Binding {
+                            index: narrowed_t,
+                            is_mut: false,
+                            span: binding.span,
+                            deprecated: binding.deprecated.clone(),
+                        },
+                    );
+                }
+            }
+        }
+
+        Ok(assump)
+    }
+
+    // Range patterns only match numbers.  If the value being matched is a
+    // union of number literals then the pattern's type is narrowed to the
+    // literals that are within the range, e.g. `0..5` matching a value of type
+    // `1 | 5 | 10` has type `1`.
+    pub fn infer_range_pattern(
+        &mut self,
+        ctx: &Context,
+        range: &RangePat,
+        expr_t: Index,
+    ) -> Result<Index, TypeError> {
+        let expr_t = self.expand_type(ctx, expr_t)?;
+        let types = match &self.arena[expr_t].kind {
+            TypeKind::Union(Union { types }) => types.to_owned(),
+            _ => vec![expr_t],
+        };
+
+        let mut narrowed_types: Vec<Index> = vec![];
+        for t in types {
+            match self.arena[t].kind.clone() {
+                TypeKind::Literal(Literal::Number(value)) if range_contains(range, &value)? => {
+                    narrowed_types.push(t);
+                }
+                TypeKind::Primitive(Primitive::Number | Primitive::Int | Primitive::Float) => {
+                    narrowed_types.push(t);
+                }
+                TypeKind::TypeVar(_) => {
+                    narrowed_types.push(self.new_primitive(Primitive::Number));
+                }
+                _ => (),
+            }
+        }
+
+        if narrowed_types.is_empty() {
+            return Err(TypeError {
+                message: format!(
+                    "Range pattern {range} can't match any value of type {}",
+                    self.print_type(&expr_t)
+                ),
+            });
+        }
+
+        Ok(self.new_union_type(&narrowed_types))
+    }
+
+    // Tuple patterns only match arrays and tuples whose lengths are compatible
+    // with the pattern.  If the value being matched is a union then the
+    // pattern's type is narrowed to those members, e.g. `[a, b]` matching a
+    // value of type `[number] | [string, string]` has type `[string, string]`.
+    // Tuples with literal elements that don't match the pattern's literals
+    // are excluded as well.
+    pub fn narrow_by_tuple_pattern(
+        &mut self,
+        ctx: &Context,
+        tuple: &ast::TuplePat,
+        expr_t: Index,
+    ) -> Result<Index, TypeError> {
+        let expanded_t = self.expand_type(ctx, expr_t)?;
+        let types = match &self.arena[expanded_t].kind {
+            TypeKind::Union(Union { types }) => types.to_owned(),
+            _ => return Ok(expr_t),
+        };
+
+        let mut narrowed_types: Vec<Index> = vec![];
+        for t in types {
+            let expanded_t = self.expand_type(ctx, t)?;
+            if self.tuple_pattern_can_match(tuple, expanded_t) {
+                narrowed_types.push(t);
+            }
+        }
+
+        if narrowed_types.is_empty() {
+            return Err(TypeError {
+                message: format!(
+                    "Tuple pattern with {} elements can't match any value of type {}",
+                    tuple.elems.len(),
+                    self.print_type(&expanded_t)
+                ),
+            });
+        }
+
+        Ok(self.new_union_type(&narrowed_types))
+    }
+
+    // Combines the members of a union of tuples into a single tuple so that
+    // each binding from a tuple pattern without a rest element gets the union
+    // of the types of its element in each member, e.g. `[a, b]` matching a
+    // value of type `[string, string] | [boolean, ...number[]]` binds `a` to
+    // `string | boolean` and `b` to `string | number`.
+    fn merge_tuple_members(
+        &mut self,
+        ctx: &Context,
+        tuple: &ast::TuplePat,
+        t: Index,
+    ) -> Result<Option<Index>, TypeError> {
+        let has_rest = tuple
+            .elems
+            .iter()
+            .flatten()
+            .any(|elem| matches!(elem.pattern.kind, PatternKind::Rest(_)));
+        if has_rest {
+            return Ok(None);
+        }
+
+        let t = self.expand_type(ctx, t)?;
+        let types = match &self.arena[t].kind {
+            TypeKind::Union(Union { types }) => types.to_owned(),
+            _ => return Ok(None),
+        };
+
+        let len = tuple.elems.len();
+        let mut columns: Vec<Vec<Index>> = vec![vec![]; len];
+        for t in types {
+            let t = self.expand_type(ctx, t)?;
+            let elems = match self.arena[t].kind.clone() {
+                TypeKind::Tuple(types::Tuple { types, .. }) => types,
+                TypeKind::Array(Array { t, .. }) => vec![t; len],
+                _ => return Ok(None),
+            };
+            for (i, column) in columns.iter_mut().enumerate() {
+                let elem = match elems.get(i) {
+                    Some(elem) => *elem,
+                    None => match elems.last() {
+                        Some(elem) => *elem,
+                        None => return Ok(None),
+                    },
+                };
+                let elem = match self.arena[elem].kind.clone() {
+                    // Elements after the rest element aren't supported so the
+                    // rest element's type is used for the remaining elements.
+                    TypeKind::Rest(Rest { arg }) => {
+                        let arg = self.expand_type(ctx, arg)?;
+                        match &self.arena[arg].kind {
+                            TypeKind::Array(Array { t, .. }) => *t,
+                            _ => return Ok(None),
+                        }
+                    }
+                    _ => elem,
+                };
+                column.push(elem);
+            }
+        }
+
+        let elems: Vec<Index> = columns
+            .iter()
+            .map(|column| self.new_union_type(column))
+            .collect();
+        Ok(Some(self.new_tuple_type(&elems)))
+    }
+
+    fn tuple_pattern_can_match(&self, tuple: &ast::TuplePat, t: Index) -> bool {
+        let types = match &self.arena[t].kind {
+            TypeKind::Tuple(types::Tuple { types, .. }) => types,
+            TypeKind::Array(_) | TypeKind::TypeVar(_) => return true,
+            _ => return false,
+        };
+
+        let is_rest_pat = |elem: &Option<TuplePatElem>| {
+            matches!(
+                elem,
+                Some(TuplePatElem {
+                    pattern: Pattern {
+                        kind: PatternKind::Rest(_),
+                        ..
+                    },
+                    ..
+                })
+            )
+        };
+        let pat_has_rest = tuple.elems.iter().any(is_rest_pat);
+        let pat_len = tuple.elems.iter().filter(|elem| !is_rest_pat(elem)).count();
+
+        let is_rest_type = |t: &Index| matches!(self.arena[*t].kind, TypeKind::Rest(_));
+        let type_has_rest = types.iter().any(is_rest_type);
+        let type_len = types.iter().filter(|t| !is_rest_type(t)).count();
+
+        let lengths_overlap = match (pat_has_rest, type_has_rest) {
+            (false, false) => pat_len == type_len,
+            (false, true) => pat_len >= type_len,
+            (true, false) => pat_len <= type_len,
+            (true, true) => true,
+        };
+        if !lengths_overlap {
+            return false;
+        }
+
+        // Elements before any rest elements must match the literals in the
+        // pattern.
+        tuple
+            .elems
+            .iter()
+            .zip(types.iter())
+            .take_while(|(elem, t)| !is_rest_pat(elem) && !is_rest_type(t))
+            .all(|(elem, t)| match (elem, &self.arena[*t].kind) {
+                (
+                    Some(TuplePatElem {
+                        pattern:
+                            Pattern {
+                                kind: PatternKind::Lit(LitPat { lit }),
+                                ..
+                            },
+                        ..
+                    }),
+                    TypeKind::Literal(t_lit),
+                ) => literals_equal(lit, t_lit),
+                _ => true,
+            })
+    }
 }
 
-pub fn pattern_to_tpat(pattern: &Pattern, is_func_param: bool) -> TPat {
+pub(crate) fn range_contains(range: &RangePat, value: &str) -> Result<bool, TypeError> {
+    let value = parse_number(value)?;
+
+    if let Some(start) = &range.start {
+        if value < parse_number(start)? {
+            return Ok(false);
+        }
+    }
+
+    if let Some(end) = &range.end {
+        let end = parse_number(end)?;
+        if value > end || (value == end && !range.inclusive) {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+pub(crate) fn parse_number(value: &str) -> Result<f64, TypeError> {
+    let (negative, value) = match value.strip_prefix('-') {
+        Some(value) => (true, value),
+        None => (false, value),
+    };
+
+    let radix = match value.get(0..2) {
+        Some("0x" | "0X") => Some(16),
+        Some("0o" | "0O") => Some(8),
+        Some("0b" | "0B") => Some(2),
+        _ => None,
+    };
+
+    let result = match radix {
+        Some(radix) => i64::from_str_radix(&value[2..], radix)
+            .map(|n| n as f64)
+            .ok(),
+        None => value.parse::<f64>().ok(),
+    };
+
+    match result {
+        Some(n) if negative => Ok(-n),
+        Some(n) => Ok(n),
+        None => Err(TypeError {
+            message: format!("{value} is not a valid number"),
+        }),
+    }
+}
+
+pub fn pattern_to_tpat(pattern: &Pattern) -> TPat {
     match &pattern.kind {
         PatternKind::Ident(binding_ident) => TPat::Ident(ast::BindingIdent {
             name: binding_ident.name.to_owned(),
@@ -179,7 +606,7 @@ pub fn pattern_to_tpat(pattern: &Pattern, is_func_param: bool) -> TPat {
             span: Span { start: 0, end: 0 },
         }),
         PatternKind::Rest(e_rest) => TPat::Rest(types::RestPat {
-            arg: Box::from(pattern_to_tpat(e_rest.arg.as_ref(), is_func_param)),
+            arg: Box::from(pattern_to_tpat(e_rest.arg.as_ref())),
         }),
         PatternKind::Object(e_obj) => {
             // TODO: replace TProp with the type equivalent of EFnParamObjectPatProp
@@ -191,7 +618,7 @@ pub fn pattern_to_tpat(pattern: &Pattern, is_func_param: bool) -> TPat {
                         ObjectPatProp::KeyValue(kv) => {
                             types::TObjectPatProp::KeyValue(types::TObjectKeyValuePatProp {
                                 key: kv.key.name.to_owned(),
-                                value: pattern_to_tpat(&kv.value, is_func_param),
+                                value: pattern_to_tpat(&kv.value),
                             })
                         }
                         ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) => {
@@ -202,7 +629,7 @@ pub fn pattern_to_tpat(pattern: &Pattern, is_func_param: bool) -> TPat {
                             })
                         }
                         ObjectPatProp::Rest(rest) => types::TObjectPatProp::Rest(types::RestPat {
-                            arg: Box::from(pattern_to_tpat(rest.arg.as_ref(), is_func_param)),
+                            arg: Box::from(pattern_to_tpat(rest.arg.as_ref())),
                         }),
                     }
                 })
@@ -216,38 +643,22 @@ pub fn pattern_to_tpat(pattern: &Pattern, is_func_param: bool) -> TPat {
                 elems: e_array
                     .elems
                     .iter()
-                    .map(|elem| {
-                        elem.as_ref()
-                            .map(|elem| pattern_to_tpat(&elem.pattern, is_func_param))
-                    })
+                    .map(|elem| elem.as_ref().map(|elem| pattern_to_tpat(&elem.pattern)))
                     .collect(),
             })
         }
-        PatternKind::Lit(LitPat { lit }) => {
-            if is_func_param {
-                panic!("Literal patterns not allowed in function params")
-            } else {
-                TPat::Lit(TLitPat {
-                    lit: lit.to_owned(),
-                })
-            }
-        }
-        PatternKind::Is(IsPat { ident, is_id }) => {
-            if is_func_param {
-                panic!("'is' patterns not allowed in function params")
-            } else {
-                TPat::Is(TIsPat {
-                    ident: ident.name.to_owned(),
-                    is_id: is_id.name.to_owned(),
-                })
-            }
-        }
-        PatternKind::Wildcard => {
-            if is_func_param {
-                panic!("Wildcard patterns not allowed in function params")
-            } else {
-                TPat::Wildcard
-            }
-        }
+        PatternKind::Lit(LitPat { lit }) => TPat::Lit(TLitPat {
+            lit: lit.to_owned(),
+        }),
+        PatternKind::Is(IsPat { ident, is_id }) => TPat::Is(TIsPat {
+            ident: ident.name.to_owned(),
+            is_id: is_id.name.to_owned(),
+        }),
+        PatternKind::Range(range) => TPat::Range(TRangePat {
+            start: range.start.to_owned(),
+            end: range.end.to_owned(),
+            inclusive: range.inclusive,
+        }),
+        PatternKind::Wildcard => TPat::Wildcard,
     }
 }
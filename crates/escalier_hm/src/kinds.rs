@@ -0,0 +1,115 @@
+use generational_arena::Index;
+
+use escalier_ast::{TypeAnn, TypeAnnKind};
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::type_error::TypeError;
+use crate::types::{TypeKind, TypeParam, TypeRef};
+
+impl Checker {
+    /// Infers a type arg passed to a generic type or function.  Generic types
+    /// that are passed without any type args, e.g. `Array` in
+    /// `MapOver<Array, number>`, are inferred as type constructors which are
+    /// type refs without type args.
+    pub(crate) fn infer_type_arg(
+        &mut self,
+        type_arg: &mut TypeAnn,
+        ctx: &mut Context,
+    ) -> Result<Index, TypeError> {
+        if let TypeAnnKind::TypeRef(name, None) = &type_arg.kind {
+            if self.get_type_constructor_arity(ctx, name) > 0 {
+                let name = name.to_owned();
+                let span = type_arg.span;
+                self.add_type_reference(&name, span, ctx);
+
+                // Type params don't have a scheme of their own, see the
+                // `TypeRef` case in `infer_type_ann`.
+                let scheme = match ctx.get_scheme(&name) {
+                    Ok(scheme) if !scheme.is_type_param => Some(scheme),
+                    _ => None,
+                };
+                let t = self.with_span(span, |checker| checker.new_type_ref(&name, scheme, &[]));
+
+                type_arg.inferred_type = Some(t);
+                self.span_index.insert(span, t);
+                self.add_type_ref_type(t, span);
+                return Ok(t);
+            }
+        }
+
+        self.infer_type_ann(type_arg, ctx)
+    }
+
+    /// Checks that each type arg is a type constructor that takes as many type
+    /// args as its type param if the type param is a type constructor, e.g.
+    /// `F<_>`, or a type if it isn't.
+    pub(crate) fn check_type_arg_kinds(
+        &mut self,
+        ctx: &Context,
+        type_params: &[TypeParam],
+        type_args: &[Index],
+    ) -> Result<(), TypeError> {
+        for (type_param, type_arg) in type_params.iter().zip(type_args.iter()) {
+            let type_arg = self.prune(*type_arg);
+            let arity = match &self.arena[type_arg].kind {
+                TypeKind::TypeRef(TypeRef {
+                    name, type_args, ..
+                }) if type_args.is_empty() => self.get_type_constructor_arity(ctx, name),
+                _ => 0,
+            };
+
+            if arity != type_param.arity {
+                let name = &type_param.name;
+                let type_arg = self.print_type(&type_arg);
+                return Err(TypeError {
+                    message: match (type_param.arity, arity) {
+                        (0, _) => format!(
+                            "{name} expects a type, but was passed the type constructor {type_arg}"
+                        ),
+                        (expected, 0) => format!(
+                            "{name} expects a type constructor that takes {expected} type args, but was passed {type_arg}"
+                        ),
+                        (expected, actual) => format!(
+                            "{name} expects a type constructor that takes {expected} type args, but {type_arg} takes {actual}"
+                        ),
+                    },
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Applies the type constructor `t` to `type_args`, e.g. `Array` to
+    /// `[number]` results in `Array<number>`.  Types that aren't type
+    /// constructors are returned as is.
+    pub(crate) fn apply_type_constructor(&mut self, t: Index, type_args: &[Index]) -> Index {
+        let t = self.prune(t);
+        match self.arena[t].kind.clone() {
+            TypeKind::TypeRef(TypeRef {
+                name,
+                scheme,
+                type_args: constructor_args,
+            }) if constructor_args.is_empty() => match (name.as_str(), type_args) {
+                ("Array", [elem]) => self.new_array_type(*elem),
+                _ => self.new_type_ref(&name, scheme, type_args),
+            },
+            _ => t,
+        }
+    }
+
+    // Returns the number of type args that the type named `name` takes when
+    // it's used as a type constructor.
+    fn get_type_constructor_arity(&self, ctx: &Context, name: &str) -> usize {
+        if name == "Array" {
+            return 1;
+        }
+        match ctx.get_scheme(name) {
+            Ok(scheme) => scheme
+                .type_params
+                .map_or(0, |type_params| type_params.len()),
+            Err(_) => 0,
+        }
+    }
+}
@@ -1,17 +1,46 @@
 // Based on https://github.com/tcr/rust-hindley-milner/blob/master/src/lib.rs
 mod ast_utils;
+mod capture_analysis;
+mod cfg;
+mod const_eval;
+mod definite_assignment;
+mod disjointness;
+mod escape_analysis;
+mod exhaustiveness;
 mod folder;
+mod infer_ambient;
 mod infer_class;
+mod infer_jsx;
 mod infer_pattern;
 mod key_value_store;
+mod kinds;
+mod module_format;
+mod normalization_cache;
+mod pragmas;
 mod provenance;
+mod recursive_types;
+mod self_type;
+mod shadowing;
+mod span_index;
+mod subsumption;
+mod type_definition;
 mod unify;
 mod visitor;
 
+pub mod check;
 pub mod checker;
+pub mod compaction;
+pub mod completions;
 pub mod context;
 pub mod diagnostic;
+pub mod explain;
 pub mod infer;
+pub mod libs;
+pub mod modules;
+pub mod prelude;
+pub mod printer;
+pub mod references;
 pub mod type_error;
+pub mod typed_ast;
 pub mod types;
 pub mod util;
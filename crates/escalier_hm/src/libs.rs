@@ -0,0 +1,303 @@
+use std::collections::BTreeSet;
+
+pub use escalier_ast::Lib;
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::type_error::TypeError;
+
+// The libs that `lib` references types from.
+fn deps(lib: Lib) -> &'static [Lib] {
+    match lib {
+        Lib::Es2015 => &[],
+        Lib::Es2020 => &[Lib::Es2015],
+        Lib::Dom => &[Lib::Es2015],
+        Lib::Node => &[Lib::Es2015],
+    }
+}
+
+fn source(lib: Lib) -> &'static str {
+    match lib {
+        Lib::Es2015 => ES2015,
+        Lib::Es2020 => ES2020,
+        Lib::Dom => DOM,
+        Lib::Node => NODE,
+    }
+}
+
+// Newer es libs are loaded first so that their definitions take precedence,
+// e.g. es2020's `Array` is used instead of es2015's.  The dom and node libs
+// reference types from the es libs so they're loaded last.
+const LOAD_ORDER: [Lib; 4] = [Lib::Es2020, Lib::Es2015, Lib::Dom, Lib::Node];
+
+impl Checker {
+    /// Adds the types and values defined by `libs`, along with the libs they
+    /// depend on, to `ctx`.
+    ///
+    /// Types and values that already have a definition in `ctx` are left
+    /// untouched.
+    pub fn load_libs(&mut self, libs: &[Lib], ctx: &mut Context) -> Result<(), TypeError> {
+        // The libs reference types from the prelude, e.g. `Promise`.
+        self.load_prelude(ctx)?;
+
+        let mut all_libs: BTreeSet<Lib> = BTreeSet::new();
+        let mut stack: Vec<Lib> = libs.to_vec();
+        while let Some(lib) = stack.pop() {
+            if all_libs.insert(lib) {
+                stack.extend(deps(lib));
+            }
+        }
+
+        for lib in LOAD_ORDER.iter().filter(|lib| all_libs.contains(lib)) {
+            self.load_source(lib.name(), source(*lib), ctx)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub static ES2015: &str = r#"
+type Array<T> = {
+    [P]: T for P in number,
+    length: number,
+    fn push(mut self, ...items: T[]) -> number,
+    fn pop(mut self) -> T | undefined,
+    fn shift(mut self) -> T | undefined,
+    fn unshift(mut self, ...items: T[]) -> number,
+    fn reverse(mut self) -> T[],
+    fn sort(mut self, compareFn?: fn (a: T, b: T) -> number) -> T[],
+    fn splice(mut self, start: number, deleteCount?: number) -> T[],
+    fn fill(mut self, value: T, start?: number, end?: number) -> T[],
+    fn concat(self, ...items: T[]) -> T[],
+    fn join(self, separator?: string) -> string,
+    fn slice(self, start?: number, end?: number) -> T[],
+    fn indexOf(self, searchElement: T, fromIndex?: number) -> number,
+    fn lastIndexOf(self, searchElement: T, fromIndex?: number) -> number,
+    fn every(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> boolean,
+    fn some(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> boolean,
+    fn forEach(self, callbackfn: fn (value: T, index: number, array: T[]) -> undefined) -> undefined,
+    fn map<U>(self, callbackfn: fn (value: T, index: number, array: T[]) -> U) -> U[],
+    fn filter(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> T[],
+    fn reduce<U>(self, callbackfn: fn (acc: U, value: T, index: number, array: T[]) -> U, initialValue: U) -> U,
+    fn find(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> T | undefined,
+    fn findIndex(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> number,
+}
+type String = {
+    length: number,
+    fn charAt(self, pos: number) -> string,
+    fn charCodeAt(self, index: number) -> number,
+    fn codePointAt(self, pos: number) -> number | undefined,
+    fn concat(self, ...strings: string[]) -> string,
+    fn includes(self, searchString: string, position?: number) -> boolean,
+    fn startsWith(self, searchString: string, position?: number) -> boolean,
+    fn endsWith(self, searchString: string, endPosition?: number) -> boolean,
+    fn indexOf(self, searchString: string, position?: number) -> number,
+    fn lastIndexOf(self, searchString: string, position?: number) -> number,
+    fn repeat(self, count: number) -> string,
+    fn slice(self, start?: number, end?: number) -> string,
+    fn substring(self, start: number, end?: number) -> string,
+    fn split(self, separator: string, limit?: number) -> string[],
+    fn toLowerCase(self) -> string,
+    fn toUpperCase(self) -> string,
+    fn trim(self) -> string,
+}
+type Number = {
+    fn toFixed(self, fractionDigits?: number) -> string,
+    fn toPrecision(self, precision?: number) -> string,
+    fn toString(self, radix?: number) -> string,
+}
+type Boolean = {
+    fn valueOf(self) -> boolean,
+}
+type PromiseConstructor = {
+    fn resolve<T>(self, value: T) -> Promise<T, never>,
+    fn reject<E>(self, reason: E) -> Promise<never, E>,
+    fn all<T, E>(self, values: Promise<T, E>[]) -> Promise<T[], E>,
+    fn race<T, E>(self, values: Promise<T, E>[]) -> Promise<T, E>,
+}
+declare let Promise: PromiseConstructor
+type Math = {
+    E: number,
+    PI: number,
+    fn abs(self, x: number) -> number,
+    fn ceil(self, x: number) -> number,
+    fn floor(self, x: number) -> number,
+    fn round(self, x: number) -> number,
+    fn trunc(self, x: number) -> number,
+    fn sign(self, x: number) -> number,
+    fn sqrt(self, x: number) -> number,
+    fn pow(self, x: number, y: number) -> number,
+    fn min(self, ...values: number[]) -> number,
+    fn max(self, ...values: number[]) -> number,
+    fn random(self) -> number,
+}
+declare let Math: Math
+type JSON = {
+    fn parse(self, text: string) -> unknown,
+    fn stringify(self, value: unknown) -> string,
+}
+declare let JSON: JSON
+declare let parseInt: fn (str: string, radix?: number) -> number
+declare let parseFloat: fn (str: string) -> number
+declare let isNaN: fn (value: number) -> boolean
+declare let isFinite: fn (value: number) -> boolean
+"#;
+
+pub static ES2020: &str = r#"
+type Array<T> = {
+    [P]: T for P in number,
+    length: number,
+    fn push(mut self, ...items: T[]) -> number,
+    fn pop(mut self) -> T | undefined,
+    fn shift(mut self) -> T | undefined,
+    fn unshift(mut self, ...items: T[]) -> number,
+    fn reverse(mut self) -> T[],
+    fn sort(mut self, compareFn?: fn (a: T, b: T) -> number) -> T[],
+    fn splice(mut self, start: number, deleteCount?: number) -> T[],
+    fn fill(mut self, value: T, start?: number, end?: number) -> T[],
+    fn concat(self, ...items: T[]) -> T[],
+    fn join(self, separator?: string) -> string,
+    fn slice(self, start?: number, end?: number) -> T[],
+    fn includes(self, searchElement: T, fromIndex?: number) -> boolean,
+    fn indexOf(self, searchElement: T, fromIndex?: number) -> number,
+    fn lastIndexOf(self, searchElement: T, fromIndex?: number) -> number,
+    fn every(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> boolean,
+    fn some(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> boolean,
+    fn forEach(self, callbackfn: fn (value: T, index: number, array: T[]) -> undefined) -> undefined,
+    fn map<U>(self, callbackfn: fn (value: T, index: number, array: T[]) -> U) -> U[],
+    fn flatMap<U>(self, callbackfn: fn (value: T, index: number, array: T[]) -> U[]) -> U[],
+    fn filter(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> T[],
+    fn reduce<U>(self, callbackfn: fn (acc: U, value: T, index: number, array: T[]) -> U, initialValue: U) -> U,
+    fn find(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> T | undefined,
+    fn findIndex(self, predicate: fn (value: T, index: number, array: T[]) -> boolean) -> number,
+}
+type String = {
+    length: number,
+    fn charAt(self, pos: number) -> string,
+    fn charCodeAt(self, index: number) -> number,
+    fn codePointAt(self, pos: number) -> number | undefined,
+    fn concat(self, ...strings: string[]) -> string,
+    fn includes(self, searchString: string, position?: number) -> boolean,
+    fn startsWith(self, searchString: string, position?: number) -> boolean,
+    fn endsWith(self, searchString: string, endPosition?: number) -> boolean,
+    fn indexOf(self, searchString: string, position?: number) -> number,
+    fn lastIndexOf(self, searchString: string, position?: number) -> number,
+    fn padStart(self, maxLength: number, fillString?: string) -> string,
+    fn padEnd(self, maxLength: number, fillString?: string) -> string,
+    fn repeat(self, count: number) -> string,
+    fn slice(self, start?: number, end?: number) -> string,
+    fn substring(self, start: number, end?: number) -> string,
+    fn split(self, separator: string, limit?: number) -> string[],
+    fn toLowerCase(self) -> string,
+    fn toUpperCase(self) -> string,
+    fn trim(self) -> string,
+    fn trimStart(self) -> string,
+    fn trimEnd(self) -> string,
+}
+type PromiseSettledResult<T, E> = {status: "fulfilled", value: T} | {status: "rejected", reason: E}
+type PromiseConstructor = {
+    fn resolve<T>(self, value: T) -> Promise<T, never>,
+    fn reject<E>(self, reason: E) -> Promise<never, E>,
+    fn all<T, E>(self, values: Promise<T, E>[]) -> Promise<T[], E>,
+    fn allSettled<T, E>(self, values: Promise<T, E>[]) -> Promise<PromiseSettledResult<T, E>[], never>,
+    fn race<T, E>(self, values: Promise<T, E>[]) -> Promise<T, E>,
+}
+type BigIntConstructor = {
+    fn (value: number | string) -> bigint,
+}
+declare let BigInt: BigIntConstructor
+"#;
+
+pub static DOM: &str = r#"
+type Console = {
+    fn log(self, ...data: unknown[]) -> undefined,
+    fn info(self, ...data: unknown[]) -> undefined,
+    fn warn(self, ...data: unknown[]) -> undefined,
+    fn error(self, ...data: unknown[]) -> undefined,
+    fn debug(self, ...data: unknown[]) -> undefined,
+}
+declare let console: Console
+type Event = {
+    type: string,
+    fn preventDefault(mut self) -> undefined,
+    fn stopPropagation(mut self) -> undefined,
+}
+type HTMLElement = {
+    id: string,
+    className: string,
+    innerHTML: string,
+    textContent: string | null,
+    tagName: string,
+    fn getAttribute(self, qualifiedName: string) -> string | null,
+    fn setAttribute(mut self, qualifiedName: string, value: string) -> undefined,
+    fn appendChild(mut self, node: HTMLElement) -> HTMLElement,
+    fn removeChild(mut self, child: HTMLElement) -> HTMLElement,
+    fn addEventListener(mut self, eventType: string, listener: fn (event: Event) -> undefined) -> undefined,
+    fn removeEventListener(mut self, eventType: string, listener: fn (event: Event) -> undefined) -> undefined,
+}
+type Document = {
+    title: string,
+    body: HTMLElement,
+    fn getElementById(self, elementId: string) -> HTMLElement | null,
+    fn querySelector(self, selectors: string) -> HTMLElement | null,
+    fn querySelectorAll(self, selectors: string) -> HTMLElement[],
+    fn createElement(self, tagName: string) -> HTMLElement,
+}
+declare let document: Document
+type Location = {
+    href: string,
+    pathname: string,
+    search: string,
+    hash: string,
+    fn reload(self) -> undefined,
+}
+type Response = {
+    ok: boolean,
+    status: number,
+    statusText: string,
+    fn text(self) -> Promise<string, unknown>,
+    fn json(self) -> Promise<unknown, unknown>,
+}
+declare let fetch: fn (input: string) -> Promise<Response, unknown>
+declare let setTimeout: fn (handler: fn () -> undefined, timeout?: number) -> number
+declare let clearTimeout: fn (id: number) -> undefined
+declare let setInterval: fn (handler: fn () -> undefined, timeout?: number) -> number
+declare let clearInterval: fn (id: number) -> undefined
+type Window = {
+    document: Document,
+    location: Location,
+    innerWidth: number,
+    innerHeight: number,
+    fn alert(self, message?: string) -> undefined,
+    fn addEventListener(mut self, eventType: string, listener: fn (event: Event) -> undefined) -> undefined,
+}
+declare let window: Window
+"#;
+
+pub static NODE: &str = r#"
+type Console = {
+    fn log(self, ...data: unknown[]) -> undefined,
+    fn info(self, ...data: unknown[]) -> undefined,
+    fn warn(self, ...data: unknown[]) -> undefined,
+    fn error(self, ...data: unknown[]) -> undefined,
+    fn debug(self, ...data: unknown[]) -> undefined,
+}
+declare let console: Console
+type Process = {
+    argv: string[],
+    env: {[P]: string | undefined for P in string},
+    platform: string,
+    exitCode: number | undefined,
+    fn cwd(self) -> string,
+    fn exit(self, code?: number) -> never,
+    fn nextTick(self, callback: fn () -> undefined) -> undefined,
+}
+declare let process: Process
+declare let __dirname: string
+declare let __filename: string
+declare let require: fn (id: string) -> unknown
+declare let setTimeout: fn (callback: fn () -> undefined, ms?: number) -> number
+declare let clearTimeout: fn (timeoutId: number) -> undefined
+declare let setInterval: fn (callback: fn () -> undefined, ms?: number) -> number
+declare let clearInterval: fn (intervalId: number) -> undefined
+"#;
@@ -0,0 +1,53 @@
+use escalier_ast::*;
+
+use crate::ast_utils::find_jsx;
+use crate::checker::Checker;
+use crate::diagnostic::Diagnostic;
+
+impl Checker {
+    /// Scripts compiled to IIFEs are loaded using `<script>` tags so they
+    /// can't import anything.  This includes the JSX runtime that's imported
+    /// by the automatic runtime so JSX requires the classic runtime.
+    pub(crate) fn check_module_format(&mut self, script: &Script, options: &CompilerOptions) {
+        if options.module_format != ModuleFormat::Iife {
+            return;
+        }
+
+        for stmt in &script.stmts {
+            if let StmtKind::Import(import) = &stmt.kind {
+                // Type-only imports are erased so they're allowed.
+                if !import.value_specifiers().is_empty() {
+                    self.report_module_format_error(
+                        "Imports aren't supported by the IIFE module format",
+                        stmt.span,
+                    );
+                }
+            }
+        }
+
+        let runtime = options
+            .jsx
+            .to_owned()
+            .unwrap_or_default()
+            .with_pragmas(&script.pragmas);
+        if let JSXRuntime::Automatic { .. } = runtime {
+            if let Some(span) = find_jsx(script) {
+                self.report_module_format_error(
+                    "JSX requires the classic runtime when using the IIFE module format",
+                    span,
+                );
+            }
+        }
+    }
+
+    fn report_module_format_error(&mut self, message: &str, span: Span) {
+        self.current_report.diagnostics.push(Diagnostic {
+            code: 1024,
+            message: message.to_string(),
+            reasons: vec![],
+            span: Some(span),
+            suggestion: None,
+            related: vec![],
+        });
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::context::{Binding, Context};
+use crate::infer_ambient::get_module_type_names;
+use crate::shadowing::find_pattern_bindings;
+use crate::type_error::TypeError;
+use crate::types::Scheme;
+
+/// The values and types exported by a module, keyed by the names they're
+/// exported as.  Scripts can import the modules in `Checker::modules`.
+#[derive(Clone, Debug, Default)]
+pub struct ModuleExports {
+    pub values: BTreeMap<String, Binding>,
+    pub schemes: BTreeMap<String, Scheme>,
+}
+
+impl Checker {
+    /// Returns the values and types exported by `script`, `ctx` must be the
+    /// context that the script was inferred in.  The result can be added to
+    /// `modules` so that other scripts can import it.
+    pub fn get_exports(&self, script: &Script, ctx: &Context) -> Result<ModuleExports, TypeError> {
+        let exports_all = script.exports_all();
+
+        // Each export is the name it's exported as and its local name.
+        let mut values: Vec<(String, String)> = vec![];
+        let mut types: Vec<(String, String)> = vec![];
+
+        for stmt in &script.stmts {
+            match &stmt.kind {
+                StmtKind::Decl(decl) if exports_all || decl.is_export => {
+                    let (value_names, type_names) = get_decl_names(decl);
+                    values.extend(value_names.into_iter().map(|name| (name.clone(), name)));
+                    types.extend(type_names.into_iter().map(|name| (name.clone(), name)));
+                }
+                StmtKind::LetElse(LetElseStmt { pattern, .. }) if exports_all => {
+                    for ident in find_pattern_bindings(pattern) {
+                        values.push((ident.name.clone(), ident.name));
+                    }
+                }
+                StmtKind::Export(export) => {
+                    for specifier in &export.specifiers {
+                        let ExportSpecifier {
+                            local,
+                            exported,
+                            is_type_only,
+                            ..
+                        } = specifier;
+                        let exported = exported.as_ref().unwrap_or(local);
+                        let is_type_only = export.is_type_only || *is_type_only;
+                        if !is_type_only && ctx.values.contains_key(local) {
+                            values.push((exported.to_owned(), local.to_owned()));
+                        }
+                        if ctx.schemes.contains_key(local) {
+                            types.push((exported.to_owned(), local.to_owned()));
+                        }
+                        let prefix = format!("{local}.");
+                        for key in ctx.schemes.keys() {
+                            if let Some(rest) = key.strip_prefix(&prefix) {
+                                types.push((format!("{exported}.{rest}"), key.to_owned()));
+                            }
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        let mut exports = ModuleExports::default();
+        for (exported, local) in values {
+            exports.values.insert(exported, ctx.get_binding(&local)?);
+        }
+        for (exported, local) in types {
+            exports.schemes.insert(exported, ctx.get_scheme(&local)?);
+        }
+
+        Ok(exports)
+    }
+
+    // Adds the values and types imported by `import` to `ctx`.  Type-only
+    // specifiers only import types since they're erased from the generated JS.
+    pub(crate) fn infer_import(
+        &mut self,
+        import: &Import,
+        ctx: &mut Context,
+    ) -> Result<(), TypeError> {
+        let exports = match self.modules.get(&import.source) {
+            Some(exports) => exports.to_owned(),
+            None => {
+                return Err(TypeError {
+                    message: format!("Cannot find module \"{}\"", import.source),
+                })
+            }
+        };
+
+        for specifier in &import.specifiers {
+            let ImportSpecifier {
+                local,
+                imported,
+                is_type_only,
+                span,
+            } = specifier;
+            let name = imported.as_ref().unwrap_or(local);
+            let is_type_only = import.is_type_only || *is_type_only;
+
+            // The types declared inside of `declare module` decls are
+            // imported along with them, e.g. `Intl.Locale` with `Intl`.
+            let prefix = format!("{name}.");
+            let mut schemes: Vec<(String, Scheme)> = exports
+                .schemes
+                .iter()
+                .filter_map(|(key, scheme)| {
+                    let rest = key.strip_prefix(&prefix)?;
+                    Some((format!("{local}.{rest}"), scheme.to_owned()))
+                })
+                .collect();
+            if let Some(scheme) = exports.schemes.get(name) {
+                schemes.push((local.to_owned(), scheme.to_owned()));
+            }
+
+            let binding = match is_type_only {
+                true => None,
+                false => exports.values.get(name),
+            };
+
+            if schemes.is_empty() && binding.is_none() {
+                let message = match exports.values.contains_key(name) {
+                    true => {
+                        format!("{name} is a value so it can't be imported using `import type`")
+                    }
+                    false => format!("Module \"{}\" doesn't export {name}", import.source),
+                };
+                return Err(TypeError { message });
+            }
+
+            for (local, scheme) in schemes {
+                self.add_type_decl(&local, *span, ctx);
+                ctx.insert_top_level_scheme(&local, scheme)?;
+            }
+
+            if let Some(binding) = binding {
+                // Imported bindings can't be reassigned.
+                let binding = Binding {
+                    is_mut: false,
+                    span: Some(*span),
+                    ..binding.to_owned()
+                };
+                self.add_value_decl(local, *span, binding.index);
+                ctx.insert_top_level_value(local, binding)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Checks that the names in `export {...}` are declared or imported at the
+    // top-level of the script.  Types must be exported using `export type`
+    // since the JS that's generated for the export doesn't include them.
+    pub(crate) fn check_named_export(
+        &self,
+        export: &NamedExport,
+        locals: &LocalNames,
+        ctx: &Context,
+    ) -> Result<(), TypeError> {
+        for specifier in &export.specifiers {
+            let name = &specifier.local;
+            let is_type_only = export.is_type_only || specifier.is_type_only;
+            let is_value = locals.values.contains(name) && ctx.values.contains_key(name);
+            let is_type = locals.types.contains(name) && ctx.schemes.contains_key(name);
+
+            let message = match (is_value, is_type) {
+                (false, false) => format!("{name} is not declared in this script"),
+                (true, false) if is_type_only => {
+                    format!("{name} is a value so it can't be exported using `export type`")
+                }
+                (false, true) if !is_type_only => {
+                    format!("{name} is a type so it must be exported using `export type`")
+                }
+                _ => continue,
+            };
+            return Err(TypeError { message });
+        }
+
+        Ok(())
+    }
+}
+
+// The names of the values and types declared or imported at the top-level of
+// a script.  Imported names are included in both since whether they're values
+// or types depends on what they were imported from.
+#[derive(Default)]
+pub(crate) struct LocalNames {
+    pub(crate) values: BTreeSet<String>,
+    pub(crate) types: BTreeSet<String>,
+}
+
+pub(crate) fn get_local_names(script: &Script) -> LocalNames {
+    let mut locals = LocalNames::default();
+    for stmt in &script.stmts {
+        match &stmt.kind {
+            StmtKind::Decl(decl) => {
+                let (value_names, type_names) = get_decl_names(decl);
+                locals.values.extend(value_names);
+                locals.types.extend(type_names);
+            }
+            StmtKind::LetElse(LetElseStmt { pattern, .. }) => {
+                let idents = find_pattern_bindings(pattern);
+                locals
+                    .values
+                    .extend(idents.into_iter().map(|ident| ident.name));
+            }
+            StmtKind::Import(import) => {
+                for specifier in &import.specifiers {
+                    locals.values.insert(specifier.local.to_owned());
+                    locals.types.insert(specifier.local.to_owned());
+                }
+            }
+            StmtKind::Expr(_) | StmtKind::For(_) | StmtKind::Return(_) | StmtKind::Export(_) => (),
+        }
+    }
+    locals
+}
+
+// Returns the names of the values and types declared by a top-level decl.
+// The types declared inside of `declare module` decls use qualified names,
+// e.g. `Intl.Locale`.
+fn get_decl_names(decl: &Decl) -> (Vec<String>, Vec<String>) {
+    match &decl.kind {
+        DeclKind::TypeDecl(TypeDecl { name, .. }) => (vec![], vec![name.to_owned()]),
+        DeclKind::VarDecl(VarDecl { pattern, .. }) => {
+            let idents = find_pattern_bindings(pattern);
+            (idents.into_iter().map(|ident| ident.name).collect(), vec![])
+        }
+        DeclKind::FnDecl(FnDecl { name, .. }) => (vec![name.to_owned()], vec![]),
+        DeclKind::ClassDecl(ClassDecl { name, .. }) => {
+            (vec![name.to_owned()], vec![name.to_owned()])
+        }
+        DeclKind::ModuleDecl(ModuleDecl { name, decls, .. }) => {
+            let type_names = get_module_type_names(decls)
+                .into_iter()
+                .map(|type_name| format!("{name}.{type_name}"))
+                .collect();
+            (vec![name.to_owned()], type_names)
+        }
+        DeclKind::GlobalDecl(_) => (vec![], vec![]),
+    }
+}
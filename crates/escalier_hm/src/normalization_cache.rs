@@ -0,0 +1,183 @@
+use generational_arena::{Arena, Index};
+use std::collections::{HashMap, HashSet};
+
+use crate::checker::{Checker, NormalizationStats};
+use crate::key_value_store::KeyValueStore;
+use crate::printer::PrintConfig;
+use crate::type_error::TypeError;
+use crate::types::*;
+use crate::util::{find_infer_types, find_type_vars};
+use crate::visitor::{walk_index, Visitor};
+
+/// The results of expanding aliases and evaluating mapped and conditional
+/// types, keyed by the canonical keys of the types that were expanded.
+#[derive(Debug, Default)]
+pub(crate) struct NormalizationCache {
+    entries: HashMap<String, Index>,
+    stats: NormalizationStats,
+}
+
+impl Checker {
+    /// Returns the number of expansions that were reused, computed, or
+    /// couldn't be cached since the checker was created.
+    pub fn normalization_stats(&self) -> NormalizationStats {
+        self.normalization_cache.stats
+    }
+
+    // Forgets the cached expansions.  This is done before checking each
+    // module since aliases with the same names may be declared differently.
+    pub(crate) fn clear_normalization_cache(&mut self) {
+        self.normalization_cache.entries.clear();
+    }
+
+    // Expands `t` with `expand` unless `t` was already expanded.  Only types
+    // whose expansion doesn't depend on the context they're expanded in, or
+    // on type variables that may be bound later, are cached.
+    pub(crate) fn expand_cached(
+        &mut self,
+        t: Index,
+        expand: impl FnOnce(&mut Self) -> Result<Index, TypeError>,
+    ) -> Result<Index, TypeError> {
+        let key = match self.normalization_key(t) {
+            Some(key) => key,
+            None => {
+                self.normalization_cache.stats.skipped += 1;
+                return expand(self);
+            }
+        };
+
+        if let Some(expanded) = self.normalization_cache.entries.get(&key) {
+            self.normalization_cache.stats.hits += 1;
+            return Ok(*expanded);
+        }
+
+        let expanded = expand(self)?;
+        self.normalization_cache.stats.misses += 1;
+        if find_type_vars(&mut self.arena, &expanded).is_empty() {
+            self.normalization_cache.entries.insert(key, expanded);
+        }
+
+        Ok(expanded)
+    }
+
+    // Returns a key that's the same for types that expand the same way, or
+    // `None` if `t` contains unbound type variables or references to types
+    // that have to be looked up in the context, e.g. type params.
+    fn normalization_key(&mut self, t: Index) -> Option<String> {
+        let mut visitor = NormalizationKeyVisitor {
+            arena: &mut self.arena,
+            bound_names: vec![],
+            visited_schemes: HashSet::new(),
+            annotations: vec![],
+            is_cacheable: true,
+        };
+        visitor.visit_index(&t);
+
+        if !visitor.is_cacheable {
+            return None;
+        }
+
+        // The printed type doesn't include which alias each type ref refers
+        // to or whether objects are fresh so these are appended to it.
+        let annotations = visitor.annotations.join(",");
+        let config = PrintConfig {
+            stable_type_vars: true,
+            ..PrintConfig::default()
+        };
+        let t = self.print_type_with_config(&t, &config);
+        Some(format!("{t}|{annotations}"))
+    }
+}
+
+struct NormalizationKeyVisitor<'a> {
+    arena: &'a mut Arena<Type>,
+    // The names introduced by the types enclosing the current one, e.g. the
+    // type params of generic functions and the targets of mapped types.
+    bound_names: Vec<String>,
+    // The aliases whose definitions have been checked, this avoids looping
+    // on recursive aliases.
+    visited_schemes: HashSet<Index>,
+    annotations: Vec<String>,
+    is_cacheable: bool,
+}
+
+impl<'a> KeyValueStore<Index, Type> for NormalizationKeyVisitor<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.arena.insert(t)
+    }
+}
+
+impl<'a> Visitor for NormalizationKeyVisitor<'a> {
+    fn visit_index(&mut self, index: &Index) {
+        if !self.is_cacheable {
+            return;
+        }
+
+        let mut names: Vec<String> = vec![];
+        match self.arena[*index].kind.clone() {
+            TypeKind::TypeVar(TypeVar { instance: None, .. }) => {
+                self.is_cacheable = false;
+                return;
+            }
+            TypeKind::TypeRef(TypeRef {
+                name, scheme: None, ..
+            }) if !self.bound_names.contains(&name) => {
+                self.is_cacheable = false;
+                return;
+            }
+            TypeKind::TypeRef(TypeRef {
+                scheme: Some(scheme),
+                ..
+            }) => {
+                let (idx, generation) = scheme.t.into_raw_parts();
+                self.annotations.push(format!("{idx}:{generation}"));
+
+                // Aliases can refer to types in the scope they're declared
+                // in which would make their expansion depend on it.
+                if self.visited_schemes.insert(scheme.t) {
+                    let type_params = scheme.type_params.unwrap_or_default();
+                    let scheme_names = type_params.into_iter().map(|param| param.name);
+                    let len = self.bound_names.len();
+                    self.bound_names.extend(scheme_names);
+                    self.visit_index(&scheme.t);
+                    self.bound_names.truncate(len);
+                }
+            }
+            TypeKind::Function(Function { type_params, .. }) => {
+                names.extend(type_params.unwrap_or_default().into_iter().map(|p| p.name));
+            }
+            TypeKind::Conditional(Conditional { extends, .. }) => {
+                let infer_types = find_infer_types(self.arena, &extends);
+                names.extend(infer_types.into_iter().map(|infer| infer.name));
+            }
+            TypeKind::Object(Object { elems, is_fresh }) => {
+                if is_fresh {
+                    self.annotations.push("fresh".to_string());
+                }
+                for elem in elems {
+                    match elem {
+                        TObjElem::Mapped(mapped) => names.push(mapped.target),
+                        TObjElem::Method(method) => names.extend(
+                            method
+                                .function
+                                .type_params
+                                .unwrap_or_default()
+                                .into_iter()
+                                .map(|p| p.name),
+                        ),
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+
+        let len = self.bound_names.len();
+        self.bound_names.extend(names);
+        walk_index(self, index);
+        self.bound_names.truncate(len);
+    }
+}
@@ -0,0 +1,80 @@
+use escalier_ast::*;
+
+use crate::checker::{Checker, IndexAccess, InterpolationStrictness};
+use crate::diagnostic::Diagnostic;
+
+impl Checker {
+    /// Overrides the checker's options with the pragmas at the start of a
+    /// script, e.g. `// @strict-returns off`, so that files can opt in or out
+    /// of checks while a codebase is migrated.  The following are supported:
+    /// - `@strict-returns`, `@strict-boolean-expressions`,
+    ///   `@numeric-refinements`, `@warn-on-shadowing`, and
+    ///   `@warn-on-empty-intersections` which take `on` or `off`, `on` is
+    ///   assumed if there's no value
+    /// - `@interpolation-strictness` which takes `off`, `loose`, or `strict`
+    /// - `@index-access` which takes `checked` or `unchecked`
+    ///
+    /// Other pragmas, e.g. `@jsx`, are left for codegen.  Pragmas with invalid
+    /// values are reported as warnings and ignored.
+    pub fn apply_pragmas(&mut self, pragmas: &[Pragma]) {
+        for pragma in pragmas {
+            let value = pragma.value.as_deref();
+            let result = match pragma.name.as_str() {
+                "strict-returns" => set_flag(&mut self.strictness.strict_returns, value),
+                "strict-boolean-expressions" => {
+                    set_flag(&mut self.strictness.strict_boolean_expressions, value)
+                }
+                "numeric-refinements" => set_flag(&mut self.strictness.numeric_refinements, value),
+                "warn-on-shadowing" => set_flag(&mut self.strictness.warn_on_shadowing, value),
+                "warn-on-empty-intersections" => {
+                    set_flag(&mut self.strictness.warn_on_empty_intersections, value)
+                }
+                "interpolation-strictness" => match value {
+                    Some("off") => Ok(InterpolationStrictness::Off),
+                    Some("loose") => Ok(InterpolationStrictness::Loose),
+                    Some("strict") => Ok(InterpolationStrictness::Strict),
+                    _ => Err("`off`, `loose`, or `strict`"),
+                }
+                .map(|strictness| self.strictness.interpolation_strictness = strictness),
+                "index-access" => match value {
+                    Some("checked") => Ok(IndexAccess::Checked),
+                    Some("unchecked") => Ok(IndexAccess::Unchecked),
+                    _ => Err("`checked` or `unchecked`"),
+                }
+                .map(|index_access| self.strictness.index_access = index_access),
+                _ => Ok(()),
+            };
+            if let Err(expected) = result {
+                self.report_invalid_pragma(pragma, expected);
+            }
+        }
+    }
+
+    fn report_invalid_pragma(&mut self, pragma: &Pragma, expected: &str) {
+        let message = match &pragma.value {
+            Some(value) => format!(
+                "Invalid value `{value}` for `@{}`, expected {expected}",
+                pragma.name
+            ),
+            None => format!("Missing value for `@{}`, expected {expected}", pragma.name),
+        };
+        self.current_report.warnings.push(Diagnostic {
+            code: 1023,
+            message,
+            reasons: vec![],
+            span: Some(pragma.span),
+            suggestion: None,
+            related: vec![],
+        });
+    }
+}
+
+// Sets `flag` from the value of a pragma, the value must be `on` or `off`.
+fn set_flag(flag: &mut bool, value: Option<&str>) -> Result<(), &'static str> {
+    match value {
+        Some("on") | None => *flag = true,
+        Some("off") => *flag = false,
+        Some(_) => return Err("`on` or `off`"),
+    }
+    Ok(())
+}
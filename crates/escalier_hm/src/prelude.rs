@@ -0,0 +1,98 @@
+use escalier_ast::{
+    BindingIdent, ClassDecl, CompilerOptions, DeclKind, FnDecl, ModuleDecl, PatternKind, StmtKind,
+    VarDecl,
+};
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::modules::get_local_names;
+use crate::type_error::TypeError;
+
+/// Utility types and values that are available in every program without
+/// having to be defined by hand.  They're written in Escalier itself using
+/// mapped and conditional types.
+pub static PRELUDE: &str = r#"
+type Partial<T> = {[P]+?: T[P] for P in keyof T}
+type Required<T> = {[P]-?: T[P] for P in keyof T}
+type Pick<T, K> = {[P]: T[P] for P in K}
+type Record<K, V> = {[P]: V for P in K}
+type Exclude<T, U> = if (T : U) { never } else { T }
+type Extract<T, U> = if (T : U) { T } else { never }
+type Omit<T, K> = Pick<T, Exclude<keyof T, K>>
+type ReturnType<T : fn (...args: _) -> _> = if (T : fn (...args: _) -> infer R) {
+    R
+} else {
+    never
+}
+type Parameters<T : fn (...args: _) -> _> = if (T : fn (...args: infer P) -> _) {
+    P
+} else {
+    never
+}
+type NonEmptyArray<T> = [T, ...T[]]
+type Promise<T, E> = {
+    fn then<U>(self, onfulfilled: fn (value: T) -> U) -> Promise<U, E>,
+    fn catch<U>(self, onrejected: fn (reason: E) -> U) -> Promise<T | U, never>,
+    fn finally(self, onfinally: fn () -> undefined) -> Promise<T, E>,
+}
+type SymbolConstructor = {
+    fn (description?: string) -> symbol,
+    asyncIterator: unique symbol,
+    iterator: unique symbol,
+}
+declare let Symbol: SymbolConstructor
+declare fn absurd(value: never) -> never
+"#;
+
+impl Checker {
+    /// Adds the types and values defined in `PRELUDE` to `ctx`.
+    ///
+    /// Types and values that already have a definition in `ctx`, e.g. ones
+    /// that were loaded from a lib.d.ts file, are left untouched.
+    pub fn load_prelude(&mut self, ctx: &mut Context) -> Result<(), TypeError> {
+        self.load_source("prelude", PRELUDE, ctx)
+    }
+
+    // Infers the decls in `src` skipping any types and values that are
+    // already defined in `ctx`.
+    pub(crate) fn load_source(
+        &mut self,
+        name: &str,
+        src: &str,
+        ctx: &mut Context,
+    ) -> Result<(), TypeError> {
+        // Sources are checked using the same checks as the scripts that use
+        // them.
+        let options = CompilerOptions {
+            strictness: self.strictness.to_owned(),
+            ..Default::default()
+        };
+        let mut script = escalier_parser::parse(src, &options).map_err(|error| TypeError {
+            message: format!("failed to parse {name}: {}", error.message),
+        })?;
+
+        script.stmts.retain(|stmt| match &stmt.kind {
+            StmtKind::Decl(decl) => match &decl.kind {
+                DeclKind::TypeDecl(type_decl) => !ctx.schemes.contains_key(&type_decl.name),
+                DeclKind::VarDecl(VarDecl { pattern, .. }) => match &pattern.kind {
+                    PatternKind::Ident(BindingIdent { name, .. }) => !ctx.values.contains_key(name),
+                    _ => true,
+                },
+                DeclKind::GlobalDecl(_) => true,
+                DeclKind::FnDecl(FnDecl { name, .. })
+                | DeclKind::ClassDecl(ClassDecl { name, .. })
+                | DeclKind::ModuleDecl(ModuleDecl { name, .. }) => !ctx.values.contains_key(name),
+            },
+            _ => true,
+        });
+
+        let locals = get_local_names(&script);
+        self.infer_script(&mut script, ctx, &options)?;
+
+        // Scripts can redeclare these names, e.g. to use their own `Partial`.
+        ctx.builtin_schemes.extend(locals.types);
+        ctx.builtin_values.extend(locals.values);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,588 @@
+use generational_arena::Index;
+use std::collections::{HashMap, HashSet};
+
+use escalier_ast::BindingIdent;
+
+use crate::checker::Checker;
+use crate::types::*;
+
+/// Controls how types are printed by `Checker::print_type_with_config`.
+#[derive(Debug, Clone, Default)]
+pub struct PrintConfig {
+    /// Prints each element of an object type on its own line.
+    pub multiline: bool,
+    /// The number of levels of type aliases to replace with their definitions.
+    /// Only aliases whose scheme is attached to the type reference can be
+    /// expanded, other aliases are printed by name.
+    pub alias_depth: usize,
+    /// Names type variables `A`, `B`, ... in the order in which they're
+    /// printed instead of using their ids, skipping names that are already
+    /// used by the type, e.g. by its type params.  Types that only differ in
+    /// which type variables they were assigned print the same way so the
+    /// output doesn't change when declarations are checked in another order.
+    pub stable_type_vars: bool,
+}
+
+/// Maps the ids of the unbound type variables in some types to the names
+/// they're given when printed with `stable_type_vars`, see
+/// `Checker::type_var_names`.
+pub type TypeVarNames = HashMap<usize, String>;
+
+impl Checker {
+    pub fn print_scheme(&self, scheme: &Scheme) -> String {
+        self.print_with_names(&PrintConfig::default(), |printer| {
+            printer.print_scheme(scheme)
+        })
+    }
+
+    pub fn print_type(&self, index: &Index) -> String {
+        self.print_type_with_config(index, &PrintConfig::default())
+    }
+
+    /// Prints the declaration of the type alias `name`, e.g.
+    /// `type Pair<T> = [T, T]`.
+    pub fn print_type_decl(&self, name: &str, scheme: &Scheme, config: &PrintConfig) -> String {
+        self.print_with_names(config, |printer| {
+            let type_params = match &scheme.type_params {
+                Some(type_params) => printer.print_type_params(type_params),
+                None => "".to_string(),
+            };
+            let t = printer.print_type(&scheme.t);
+            let opaque = match scheme.opaque_module {
+                Some(_) => "opaque ",
+                None => "",
+            };
+            format!("{opaque}type {name}{type_params} = {t}")
+        })
+    }
+
+    pub fn print_type_with_config(&self, index: &Index, config: &PrintConfig) -> String {
+        self.print_with_names(config, |printer| printer.print_type(index))
+    }
+
+    /// Returns the names that the unbound type variables in `indexes` are
+    /// given when the types are printed together with `stable_type_vars`.
+    /// This is used to name them consistently in output that isn't produced
+    /// by the printer, e.g. .d.ts files.
+    pub fn type_var_names(&self, indexes: &[Index]) -> TypeVarNames {
+        let config = PrintConfig {
+            stable_type_vars: true,
+            ..PrintConfig::default()
+        };
+        let mut names = TypeVarNames::new();
+        self.print_with_names(&config, |printer| {
+            printer.print_types(indexes);
+            names = printer.type_vars.clone();
+            String::new()
+        });
+        names
+    }
+
+    // Calls `print` with a new printer.  When type vars are named by the
+    // order in which they're printed it's called again if one of them got
+    // the same name as a type that's referenced by name, e.g. a type param,
+    // with those names reserved.
+    fn print_with_names(
+        &self,
+        config: &PrintConfig,
+        mut print: impl FnMut(&mut Printer) -> String,
+    ) -> String {
+        let mut printer = Printer::new(self, config, HashSet::new());
+        let result = print(&mut printer);
+        if !printer
+            .type_vars
+            .values()
+            .any(|name| printer.used_names.contains(name))
+        {
+            return result;
+        }
+
+        let reserved = printer.used_names;
+        print(&mut Printer::new(self, config, reserved))
+    }
+}
+
+struct Printer<'a> {
+    checker: &'a Checker,
+    config: &'a PrintConfig,
+    indent: usize,
+    // The number of aliases that are currently being expanded.
+    alias_depth: usize,
+    // Maps the ids of type variables to their names when `stable_type_vars`
+    // is enabled.
+    type_vars: TypeVarNames,
+    // The number of names that have been tried for type variables.
+    next_type_var: usize,
+    // Names that type variables can't be given since they're used by types
+    // that are printed by name.
+    reserved_names: HashSet<String>,
+    // The names of the type refs, type params, etc. that have been printed.
+    used_names: HashSet<String>,
+    // Maps the type params of each alias being expanded to their type args.
+    type_args: Vec<HashMap<String, Index>>,
+    // The ids of the recursive types being printed, innermost last.
+    mu_ids: Vec<usize>,
+}
+
+impl<'a> Printer<'a> {
+    fn new(checker: &'a Checker, config: &'a PrintConfig, reserved_names: HashSet<String>) -> Self {
+        Printer {
+            checker,
+            config,
+            indent: 0,
+            alias_depth: 0,
+            type_vars: TypeVarNames::new(),
+            next_type_var: 0,
+            reserved_names,
+            used_names: HashSet::new(),
+            type_args: vec![],
+            mu_ids: vec![],
+        }
+    }
+
+    fn print_scheme(&mut self, scheme: &Scheme) -> String {
+        let mut result = String::default();
+        if let Some(type_params) = &scheme.type_params {
+            result.push_str(&self.print_type_params(type_params));
+        }
+        result.push_str(&self.print_type(&scheme.t));
+        result
+    }
+
+    fn print_type(&mut self, index: &Index) -> String {
+        let checker = self.checker;
+        match &checker.arena[*index].kind {
+            TypeKind::TypeVar(TypeVar {
+                instance: Some(inst),
+                ..
+            }) => self.print_type(inst),
+            TypeKind::TypeVar(TypeVar { id, constraint, .. }) => {
+                let name = self.print_type_var(*id);
+                match constraint {
+                    Some(constraint) => format!("{name}:{}", self.print_type(constraint)),
+                    None => name,
+                }
+            }
+            TypeKind::Union(Union { types }) => self.print_types(types).join(" | "),
+            TypeKind::Intersection(Intersection { types }) => self.print_types(types).join(" & "),
+            TypeKind::Tuple(Tuple {
+                types,
+                labels,
+                readonly,
+            }) => {
+                let elems = match labels {
+                    Some(labels) => labels
+                        .iter()
+                        .zip(types.iter())
+                        .map(|(label, t)| match &checker.arena[*t].kind {
+                            TypeKind::Rest(rest) => {
+                                format!("...{label}: {}", self.print_type(&rest.arg))
+                            }
+                            _ => format!("{label}: {}", self.print_type(t)),
+                        })
+                        .collect(),
+                    None => self.print_types(types),
+                };
+                let modifier = if *readonly { "readonly " } else { "" };
+                format!("{modifier}[{}]", elems.join(", "))
+            }
+            TypeKind::Array(Array { t, readonly }) => {
+                let modifier = if *readonly { "readonly " } else { "" };
+                format!("{modifier}{}[]", self.print_type(t))
+            }
+            TypeKind::TypeRef(TypeRef {
+                name,
+                scheme,
+                type_args,
+            }) => {
+                if let Some(result) = self.print_type_arg(name, type_args) {
+                    return result;
+                }
+                self.used_names.insert(name.to_owned());
+                if let Some(scheme) = scheme {
+                    if self.alias_depth < self.config.alias_depth {
+                        return self.print_alias(scheme, type_args);
+                    }
+                }
+                if type_args.is_empty() {
+                    name.to_string()
+                } else {
+                    format!("{}<{}>", name, self.print_types(type_args).join(", "))
+                }
+            }
+            TypeKind::Keyword(keyword) => keyword.to_string(),
+            TypeKind::Primitive(primitive) => primitive.to_string(),
+            TypeKind::UniqueSymbol(_) => "unique symbol".to_string(),
+            TypeKind::Literal(lit) => lit.to_string(),
+            TypeKind::Object(object) => {
+                self.indent += 1;
+                let mut fields = vec![];
+                for prop in &object.elems {
+                    fields.push(self.print_obj_elem(prop));
+                }
+                self.indent -= 1;
+
+                if self.config.multiline && !fields.is_empty() {
+                    let indent = "    ".repeat(self.indent + 1);
+                    let fields = fields
+                        .iter()
+                        .map(|field| format!("{indent}{field},\n"))
+                        .collect::<String>();
+                    format!("{{\n{fields}{}}}", "    ".repeat(self.indent))
+                } else {
+                    format!("{{{}}}", fields.join(", "))
+                }
+            }
+            TypeKind::Rest(rest) => {
+                format!("...{}", self.print_type(&rest.arg))
+            }
+            TypeKind::Function(func) => {
+                let type_params = match &func.type_params {
+                    Some(type_params) => self.print_type_params(type_params),
+                    None => "".to_string(),
+                };
+                let params = self.print_params(&func.params).join(", ");
+                let ret = self.print_type(&func.ret);
+                let throws = match func.throws {
+                    Some(throws) => format!(" throws {}", self.print_type(&throws)),
+                    None => "".to_string(),
+                };
+                format!("{type_params}({params}) -> {ret}{throws}")
+            }
+            TypeKind::KeyOf(KeyOf { t }) => format!("keyof {}", self.print_type(t)),
+            TypeKind::IndexedAccess(IndexedAccess { obj, index }) => {
+                let obj = self.print_type(obj);
+                let index = self.print_type(index);
+                format!("{obj}[{index}]")
+            }
+            TypeKind::Conditional(Conditional {
+                check,
+                extends,
+                true_type,
+                false_type,
+            }) => {
+                let check = self.print_type(check);
+                let extends = self.print_type(extends);
+                let true_type = self.print_type(true_type);
+                let false_type = self.print_type(false_type);
+                format!("{check} extends {extends} ? {true_type} : {false_type}")
+            }
+            TypeKind::Infer(Infer { name }) => {
+                self.used_names.insert(name.to_owned());
+                format!("infer {}", name)
+            }
+            TypeKind::Wildcard => "_".to_string(),
+            TypeKind::Binary(BinaryT { op, left, right }) => {
+                let op = match op {
+                    TBinaryOp::Add => "+",
+                    TBinaryOp::Sub => "-",
+                    TBinaryOp::Mul => "*",
+                    TBinaryOp::Div => "/",
+                    TBinaryOp::Mod => "%",
+                };
+                let left = self.print_type(left);
+                let right = self.print_type(right);
+                format!("{left} {op} {right}")
+            }
+            TypeKind::Mu(Mu { id, t }) => {
+                self.mu_ids.push(*id);
+                let name = self.print_mu_var(*id);
+                let t = self.print_type(t);
+                self.mu_ids.pop();
+                format!("μ{name}. {t}")
+            }
+            TypeKind::MuVar(MuVar { id }) => self.print_mu_var(*id),
+        }
+    }
+
+    // Recursive types are named `X`, `Y`, and `Z` by how deeply they're
+    // nested with a number added when there are more than three of them.
+    fn print_mu_var(&self, id: usize) -> String {
+        match self.mu_ids.iter().rposition(|mu_id| *mu_id == id) {
+            Some(depth) => {
+                let name = ["X", "Y", "Z"][depth % 3];
+                match depth / 3 {
+                    0 => name.to_string(),
+                    n => format!("{name}{n}"),
+                }
+            }
+            None => format!("μ{id}"),
+        }
+    }
+
+    // Type variables are named `A`, ..., `Z`, `A1`, ..., `Z1`, etc. in the
+    // order in which they're printed when `stable_type_vars` is enabled.
+    fn print_type_var(&mut self, id: usize) -> String {
+        if !self.config.stable_type_vars {
+            return format!("t{id}");
+        }
+        if let Some(name) = self.type_vars.get(&id) {
+            return name.to_owned();
+        }
+        loop {
+            let n = self.next_type_var;
+            self.next_type_var += 1;
+            let letter = char::from(b'A' + (n % 26) as u8);
+            let name = match n / 26 {
+                0 => letter.to_string(),
+                i => format!("{letter}{i}"),
+            };
+            if !self.reserved_names.contains(&name) {
+                self.type_vars.insert(id, name.to_owned());
+                return name;
+            }
+        }
+    }
+
+    // If `name` is one of the type params of the alias currently being
+    // expanded, prints the type arg that was passed for it.  The type arg
+    // comes from outside of the alias so it's printed without the alias'
+    // type params in scope.
+    fn print_type_arg(&mut self, name: &str, type_args: &[Index]) -> Option<String> {
+        if !type_args.is_empty() {
+            return None;
+        }
+        let type_arg = *self.type_args.last()?.get(name)?;
+        let scope = self.type_args.pop().unwrap();
+        let result = self.print_type(&type_arg);
+        self.type_args.push(scope);
+        Some(result)
+    }
+
+    fn print_alias(&mut self, scheme: &Scheme, type_args: &[Index]) -> String {
+        let type_params = scheme.type_params.as_deref().unwrap_or_default();
+        let scope = type_params
+            .iter()
+            .zip(type_args)
+            .map(|(tp, arg)| (tp.name.to_owned(), *arg))
+            .collect();
+
+        self.type_args.push(scope);
+        self.alias_depth += 1;
+        let result = self.print_type(&scheme.t);
+        self.alias_depth -= 1;
+        self.type_args.pop();
+
+        result
+    }
+
+    fn print_obj_elem(&mut self, elem: &TObjElem) -> String {
+        match elem {
+            TObjElem::Getter(TGetter {
+                ret,
+                name,
+                throws: _,
+            }) => {
+                let ret_type = self.print_type(ret);
+                format!("get {name}(self) -> {ret_type}")
+            }
+            TObjElem::Setter(TSetter {
+                param,
+                name,
+                throws: _, // TODO
+            }) => {
+                let param = self.print_type(&param.t);
+                format!("set {name}(mut self, {param})")
+            }
+            TObjElem::Constructor(Function {
+                params,
+                ret,
+                type_params,
+                throws: _, // TODO
+            }) => {
+                let mut result = "new fn".to_string();
+                if let Some(type_params) = type_params {
+                    result.push_str(&self.print_type_params(type_params));
+                }
+                let params = self.print_params(params).join(", ");
+                let ret = self.print_type(ret);
+                result.push_str(&format!("({params}) -> {ret}"));
+                result
+            }
+            TObjElem::Call(Function {
+                params,
+                ret,
+                type_params,
+                throws: _, // TODO
+            }) => {
+                let mut result = "fn".to_string();
+                if let Some(type_params) = type_params {
+                    result.push_str(&self.print_type_params(type_params));
+                }
+                let params = self.print_params(params).join(", ");
+                let ret = self.print_type(ret);
+                result.push_str(&format!("({params}) -> {ret}"));
+                result
+            }
+            TObjElem::Mapped(MappedType {
+                key,
+                value,
+                target,
+                source,
+                optional: _, // TODO
+                // TODO: handle `if`-clause
+                check: _,
+                extends: _,
+            }) => {
+                let key = self.print_type(key);
+                let value = self.print_type(value);
+                let source = self.print_type(source);
+                self.used_names.insert(target.to_owned());
+
+                format!("[{key}]: {value} for {target} in {source}")
+            }
+            TObjElem::Method(TMethod {
+                name,
+                mutates,
+                function:
+                    Function {
+                        type_params,
+                        params,
+                        ret,
+                        throws,
+                    },
+            }) => {
+                let type_params = match type_params {
+                    Some(type_params) => self.print_type_params(type_params),
+                    None => "".to_string(),
+                };
+
+                let mut params = self.print_params(params);
+                match mutates {
+                    true => params.insert(0, "mut self".to_string()),
+                    false => params.insert(0, "self".to_string()),
+                }
+                let params = params.join(", ");
+
+                let ret = self.print_type(ret);
+                let throws = match throws {
+                    Some(throws) => format!(" throws {}", self.print_type(throws)),
+                    None => "".to_string(),
+                };
+                format!("{name}{type_params}({params}) -> {ret}{throws}")
+            }
+            TObjElem::Prop(TProp {
+                name,
+                optional,
+                readonly,
+                t,
+            }) => {
+                let t = self.print_type(t);
+                let mut str = "".to_string();
+                if *readonly {
+                    str += "readonly ";
+                }
+
+                str += &name.to_string();
+                if *optional {
+                    str += "?";
+                }
+                str += &format!(": {t}");
+
+                str
+            }
+        }
+    }
+
+    fn print_type_params(&mut self, type_params: &[TypeParam]) -> String {
+        if type_params.is_empty() {
+            return "".to_string();
+        }
+        let type_params = type_params
+            .iter()
+            .map(|tp| {
+                self.used_names.insert(tp.name.to_owned());
+                let name = match tp.arity {
+                    0 => tp.name.clone(),
+                    arity => format!("{}<{}>", tp.name, vec!["_"; arity].join(", ")),
+                };
+                match &tp.constraint {
+                    Some(constraint) => format!("{name}:{}", self.print_type(constraint)),
+                    None => name,
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("<{}>", type_params.join(", "))
+    }
+
+    fn print_types(&mut self, indexes: &[Index]) -> Vec<String> {
+        let mut result = vec![];
+        for index in indexes {
+            result.push(self.print_type(index));
+        }
+        result
+    }
+
+    fn print_params(&mut self, params: &[FuncParam]) -> Vec<String> {
+        let mut strings = vec![];
+        for param in params {
+            strings.push(self.print_param(param))
+        }
+        strings
+    }
+
+    fn print_param(&mut self, param: &FuncParam) -> String {
+        let name = tpat_to_string(&param.pattern);
+        match param.optional {
+            true => format!("{name}?: {}", self.print_type(&param.t)),
+            false => format!("{name}: {}", self.print_type(&param.t)),
+        }
+    }
+}
+
+fn tpat_to_string(pattern: &TPat) -> String {
+    match pattern {
+        TPat::Ident(BindingIdent { name, mutable, .. }) => match mutable {
+            true => format!("mut {}", name),
+            false => name.to_owned(),
+        },
+        TPat::Rest(RestPat { arg }) => format!("...{}", tpat_to_string(arg.as_ref())),
+        TPat::Tuple(TuplePat { elems }) => format!(
+            "[{}]",
+            elems
+                .iter()
+                .map(|elem| match elem {
+                    Some(elem) => tpat_to_string(elem),
+                    None => " ".to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        TPat::Object(TObjectPat { props }) => {
+            let props: Vec<String> = props
+                .iter()
+                .map(|prop| match prop {
+                    TObjectPatProp::KeyValue(TObjectKeyValuePatProp { key, value }) => {
+                        match value {
+                            TPat::Ident(_) => key.to_string(),
+                            _ => format!("{}: {}", key, tpat_to_string(value)),
+                        }
+                    }
+                    // TODO: handle assignments in object patterns
+                    TObjectPatProp::Assign(TObjectAssignPatProp { key, value: _ }) => {
+                        key.to_string()
+                    }
+                    TObjectPatProp::Rest(RestPat { arg }) => {
+                        format!("...{}", tpat_to_string(arg.as_ref()))
+                    }
+                })
+                .collect();
+            format!("{{{}}}", props.join(", "))
+        }
+        TPat::Lit(TLitPat { lit }) => lit.to_string(),
+        TPat::Is(TIsPat { ident, is_id }) => {
+            format!("{ident} is {is_id}")
+        }
+        TPat::Range(TRangePat {
+            start,
+            end,
+            inclusive,
+        }) => {
+            let op = if *inclusive { "..=" } else { ".." };
+            let start = start.as_deref().unwrap_or("");
+            let end = end.as_deref().unwrap_or("");
+            format!("{start}{op}{end}")
+        }
+        TPat::Wildcard => "_".to_string(),
+    }
+}
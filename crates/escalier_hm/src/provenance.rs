@@ -1,4 +1,4 @@
-use escalier_ast::{Expr, Span, TypeAnn};
+use escalier_ast::{Class, Expr, Span, TypeAnn};
 
 use crate::types::Type;
 
@@ -7,6 +7,10 @@ pub enum Provenance {
     // from AST
     Expr(Box<Expr>),
     TypeAnn(Box<TypeAnn>),
+    // The instance type of a class
+    Class(Box<Class>),
+    // The node that was being inferred when the type was created
+    Span(Span),
 
     // from other types
     Type(Box<Type>),
@@ -35,6 +39,8 @@ impl Provenance {
             },
             // Provenance::TObjElem(_) => None, // TODO: add provenance to TObjElem
             Provenance::TypeAnn(type_ann) => Some(type_ann.span.to_owned()),
+            Provenance::Class(class) => Some(class.span.to_owned()),
+            Provenance::Span(span) => Some(*span),
         }
     }
 
@@ -60,6 +66,8 @@ impl Provenance {
             },
             // Provenance::TObjElem(_) => None,
             Provenance::TypeAnn(_) => None,
+            Provenance::Class(_) => None,
+            Provenance::Span(_) => None,
         }
     }
 }
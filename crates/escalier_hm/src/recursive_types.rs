@@ -0,0 +1,92 @@
+use generational_arena::Index;
+
+use crate::checker::Checker;
+use crate::folder::{walk_index, Folder};
+use crate::key_value_store::KeyValueStore;
+use crate::types::{Mu, MuVar, Type, TypeKind};
+
+impl Checker {
+    /// Creates the recursive type `μX. t` where `X` replaces the occurrences
+    /// of the type variable `var` in `t`, e.g. binding `a` to `null | {next:
+    /// a}` binds it to `μX. null | {next: X}` instead.
+    pub fn new_mu_type(&mut self, var: Index, t: Index) -> Index {
+        let id = self.new_type_id();
+        let mu_var = self.from_type_kind(TypeKind::MuVar(MuVar { id }));
+        let mut replace = ReplaceTypeVar {
+            checker: self,
+            var,
+            replacement: mu_var,
+        };
+        let t = replace.fold_index(&t);
+        self.from_type_kind(TypeKind::Mu(Mu { id, t }))
+    }
+
+    /// Unfolds the recursive type `mu` one level by replacing the `MuVar`s in
+    /// its body with `mu` itself, e.g. `μX. null | {next: X}` unfolds to
+    /// `null | {next: μX. null | {next: X}}`.  Other types are returned as is.
+    pub fn unfold_mu(&mut self, mu: Index) -> Index {
+        let mu = self.prune(mu);
+        match self.arena[mu].kind.clone() {
+            TypeKind::Mu(Mu { id, t }) => {
+                let mut unfold = UnfoldMu {
+                    checker: self,
+                    id,
+                    mu,
+                };
+                unfold.fold_index(&t)
+            }
+            _ => mu,
+        }
+    }
+}
+
+struct ReplaceTypeVar<'a> {
+    checker: &'a mut Checker,
+    var: Index,
+    replacement: Index,
+}
+
+impl<'a> KeyValueStore<Index, Type> for ReplaceTypeVar<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.checker.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.checker.arena.insert(t)
+    }
+}
+
+impl<'a> Folder for ReplaceTypeVar<'a> {
+    fn fold_index(&mut self, index: &Index) -> Index {
+        let index = self.checker.prune(*index);
+        match index == self.var {
+            true => self.replacement,
+            false => walk_index(self, &index),
+        }
+    }
+}
+
+struct UnfoldMu<'a> {
+    checker: &'a mut Checker,
+    id: usize,
+    mu: Index,
+}
+
+impl<'a> KeyValueStore<Index, Type> for UnfoldMu<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.checker.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.checker.arena.insert(t)
+    }
+}
+
+impl<'a> Folder for UnfoldMu<'a> {
+    fn fold_index(&mut self, index: &Index) -> Index {
+        match &self.checker.arena[*index].kind {
+            TypeKind::MuVar(MuVar { id }) if *id == self.id => self.mu,
+            // Nested recursive types with the same id shadow this one.
+            TypeKind::Mu(Mu { id, .. }) if *id == self.id => *index,
+            _ => walk_index(self, index),
+        }
+    }
+}
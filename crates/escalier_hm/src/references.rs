@@ -0,0 +1,210 @@
+use generational_arena::Index;
+use std::collections::{BTreeMap, HashMap};
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::context::{Binding, Context};
+
+/// Values and types are declared separately so a value and a type can have
+/// the same name without referring to each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Namespace {
+    Value,
+    Type,
+}
+
+/// Replaces the text at `span` with `new_text`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub new_text: String,
+}
+
+// A declaration of a symbol or a reference to one.
+#[derive(Clone, Debug)]
+pub(crate) struct Occurrence {
+    pub namespace: Namespace,
+    pub name: String,
+    // The span of the name in the symbol's declaration.
+    pub decl: Span,
+    // Whether the occurrence is also the key of an object, e.g. `x` in
+    // `{x, y: 5}` or `let {x} = point`, which must be kept when renaming.
+    pub is_shorthand: bool,
+}
+
+/// Maps the spans of identifiers that declare or reference symbols to the
+/// declarations they resolve to.  It's filled in while checking a program.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct SymbolIndex {
+    occurrences: BTreeMap<Span, Occurrence>,
+    // The declarations that the type refs inferred from type annotations
+    // resolve to.  These are kept separately from the types' provenance since
+    // that gets replaced when a binding with an annotation is referenced.
+    type_refs: HashMap<Index, Span>,
+}
+
+impl SymbolIndex {
+    pub fn insert(&mut self, span: Span, occurrence: Occurrence) {
+        self.occurrences.insert(span, occurrence);
+    }
+
+    // Identifiers don't overlap so the only occurrence that can contain
+    // `span` is the last one starting at or before it.
+    pub fn get(&self, span: &Span) -> Option<&Occurrence> {
+        let end = Span {
+            start: span.start,
+            end: usize::MAX,
+        };
+        match self.occurrences.range(..=end).next_back() {
+            Some((occurrence_span, occurrence)) if span.end <= occurrence_span.end => {
+                Some(occurrence)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn get_type_ref_decl(&self, t: Index) -> Option<Span> {
+        self.type_refs.get(&t).copied()
+    }
+
+    pub(crate) fn for_each_index(&mut self, mut f: impl FnMut(&mut Index)) {
+        self.type_refs = self
+            .type_refs
+            .drain()
+            .map(|(mut t, span)| {
+                f(&mut t);
+                (t, span)
+            })
+            .collect();
+    }
+}
+
+impl Checker {
+    /// Returns the spans of the declaration of the symbol at `span` and every
+    /// reference to it in the order they appear.  `span` can be either the
+    /// declaration or any of its references.
+    pub fn references(&self, span: Span) -> Vec<Span> {
+        self.occurrences_of(span)
+            .into_iter()
+            .map(|(span, _)| span)
+            .collect()
+    }
+
+    /// Returns the edits needed to rename the symbol at `span` to `new_name`.
+    /// Shorthand properties are expanded so that the objects' keys don't
+    /// change, e.g. renaming `x` in `{x}` to `y` results in `{x: y}`.
+    pub fn rename(&self, span: Span, new_name: &str) -> Vec<TextEdit> {
+        self.occurrences_of(span)
+            .into_iter()
+            .map(|(span, occurrence)| TextEdit {
+                span,
+                new_text: match occurrence.is_shorthand {
+                    true => format!("{}: {new_name}", occurrence.name),
+                    false => new_name.to_string(),
+                },
+            })
+            .collect()
+    }
+
+    pub(crate) fn add_value_reference(
+        &mut self,
+        name: &str,
+        span: Span,
+        is_shorthand: bool,
+        ctx: &Context,
+    ) {
+        if let Some(Binding {
+            span: Some(decl), ..
+        }) = ctx.values.get(name)
+        {
+            let occurrence = Occurrence {
+                namespace: Namespace::Value,
+                name: name.to_owned(),
+                decl: *decl,
+                is_shorthand,
+            };
+            self.symbols.insert(span, occurrence);
+        }
+    }
+
+    pub(crate) fn add_type_reference(&mut self, name: &str, span: Span, ctx: &Context) {
+        if let Some(decl) = ctx.type_decls.get(name) {
+            let occurrence = Occurrence {
+                namespace: Namespace::Type,
+                name: name.to_owned(),
+                decl: *decl,
+                is_shorthand: false,
+            };
+            self.symbols.insert(span, occurrence);
+        }
+    }
+
+    // Records the declaration of a value that isn't declared by a pattern,
+    // e.g. `declare fn parseInt(value: string) -> number`.
+    pub(crate) fn add_value_decl(&mut self, name: &str, span: Span, t: Index) {
+        self.span_index.insert(span, t);
+        self.symbols.insert(
+            span,
+            Occurrence {
+                namespace: Namespace::Value,
+                name: name.to_owned(),
+                decl: span,
+                is_shorthand: false,
+            },
+        );
+    }
+
+    pub(crate) fn add_type_decl(&mut self, name: &str, span: Span, ctx: &mut Context) {
+        ctx.type_decls.insert(name.to_owned(), span);
+        self.add_type_reference(name, span, ctx);
+    }
+
+    // Records the declaration that the type ref `t`, whose name is at
+    // `name_span`, resolves to.
+    pub(crate) fn add_type_ref_type(&mut self, t: Index, name_span: Span) {
+        if let Some(occurrence) = self.symbols.get(&name_span) {
+            let decl = occurrence.decl;
+            self.symbols.type_refs.insert(t, decl);
+        }
+    }
+
+    fn occurrences_of(&self, span: Span) -> Vec<(Span, &Occurrence)> {
+        let (namespace, decl) = match self.symbols.get(&span) {
+            Some(occurrence) => (occurrence.namespace, occurrence.decl),
+            None => return vec![],
+        };
+
+        self.symbols
+            .occurrences
+            .iter()
+            .filter(|(_, occurrence)| occurrence.namespace == namespace && occurrence.decl == decl)
+            .map(|(span, occurrence)| (*span, occurrence))
+            .collect()
+    }
+}
+
+struct ShorthandVisitor {
+    spans: Vec<Span>,
+}
+
+impl Visitor for ShorthandVisitor {
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        if let PatternKind::Object(ObjectPat { props, .. }) = &pattern.kind {
+            for prop in props {
+                if let ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) = prop {
+                    self.spans.push(ident.span);
+                }
+            }
+        }
+        walk_pattern(self, pattern);
+    }
+}
+
+// Returns the spans of the bindings in `pattern` that are also the keys of
+// object patterns, e.g. `x` in `{x, y: z}`.
+pub(crate) fn find_shorthand_bindings(pattern: &Pattern) -> Vec<Span> {
+    let mut visitor = ShorthandVisitor { spans: vec![] };
+    visitor.visit_pattern(pattern);
+    visitor.spans
+}
@@ -0,0 +1,114 @@
+use generational_arena::Index;
+
+use crate::checker::Checker;
+use crate::context::Context;
+use crate::folder::{walk_index, Folder};
+use crate::key_value_store::KeyValueStore;
+use crate::types::{Function, Object, TMethod, TObjElem, TProp, TPropKey, Type, TypeKind, TypeRef};
+
+impl Checker {
+    // Replaces the `Self` types in `t`, the type of a member of `receiver`,
+    // with `receiver` so that methods that return `self`, e.g.
+    // `fn (mut self) -> Self`, return the type of the object they were
+    // called on.
+    pub(crate) fn bind_self_type(&mut self, t: Index, receiver: Index) -> Index {
+        let mut replace = ReplaceSelfType {
+            checker: self,
+            receiver,
+        };
+        replace.fold_index(&t)
+    }
+
+    // Returns true if the `key` method of `obj_t` takes `mut self` and returns
+    // `Self`, in which case calling it on a mutable receiver returns a mutable
+    // value.
+    pub(crate) fn returns_mut_self(&mut self, ctx: &Context, obj_t: Index, key: &str) -> bool {
+        let obj_t = match self.expand_type(ctx, obj_t) {
+            Ok(t) => t,
+            Err(_) => return false,
+        };
+        let elems = match &self.arena[obj_t].kind {
+            TypeKind::Object(object) => object.elems.clone(),
+            _ => return false,
+        };
+        for elem in elems {
+            match elem {
+                TObjElem::Method(TMethod {
+                    name: TPropKey::StringKey(name),
+                    mutates,
+                    function,
+                }) if name == key => return mutates && self.is_self_type(function.ret),
+                TObjElem::Prop(TProp {
+                    name: TPropKey::StringKey(name),
+                    t,
+                    ..
+                }) if name == key => {
+                    let t = self.prune(t);
+                    return match self.arena[t].kind.clone() {
+                        TypeKind::Function(Function { params, ret, .. }) => {
+                            params.first().is_some_and(|param| param.is_mut_self())
+                                && self.is_self_type(ret)
+                        }
+                        _ => false,
+                    };
+                }
+                _ => (),
+            }
+        }
+        false
+    }
+
+    fn is_self_type(&mut self, t: Index) -> bool {
+        let t = self.prune(t);
+        matches!(&self.arena[t].kind, TypeKind::TypeRef(tref) if is_unbound_self_type(tref))
+    }
+
+    // Objects with methods have their own `Self` type so the `Self` types
+    // inside of them don't refer to the receiver.
+    fn has_own_self_type(&mut self, object: &Object) -> bool {
+        object.elems.iter().any(|elem| match elem {
+            TObjElem::Method(_) => true,
+            TObjElem::Prop(prop) => {
+                let t = self.prune(prop.t);
+                match &self.arena[t].kind {
+                    TypeKind::Function(Function { params, .. }) => {
+                        params.first().is_some_and(|param| param.is_self())
+                    }
+                    _ => false,
+                }
+            }
+            _ => false,
+        })
+    }
+}
+
+// `Self` types that haven't been bound are type refs without a scheme, see
+// the `Self` scheme that's added when inferring object types and literals.
+pub(crate) fn is_unbound_self_type(tref: &TypeRef) -> bool {
+    tref.name == "Self" && tref.scheme.is_none()
+}
+
+struct ReplaceSelfType<'a> {
+    checker: &'a mut Checker,
+    receiver: Index,
+}
+
+impl<'a> KeyValueStore<Index, Type> for ReplaceSelfType<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.checker.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.checker.arena.insert(t)
+    }
+}
+
+impl<'a> Folder for ReplaceSelfType<'a> {
+    fn fold_index(&mut self, index: &Index) -> Index {
+        let index = self.checker.prune(*index);
+        match &self.checker.arena[index].kind.clone() {
+            TypeKind::TypeRef(tref) if is_unbound_self_type(tref) => self.receiver,
+            TypeKind::Object(object) if self.checker.has_own_self_type(object) => index,
+            _ => walk_index(self, &index),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::diagnostic::Diagnostic;
+use crate::type_error::TypeError;
+
+impl Checker {
+    // Warns about `let` decls that shadow other bindings.  Shadowing is only
+    // considered to be intentional if the initializer uses the binding that's
+    // being shadowed, e.g.
+    //
+    // let input = "5"
+    // if (cond) {
+    //     let input = parse(input)
+    // }
+    pub fn check_shadowing_in_script(&mut self, script: &Script) {
+        self.run_shadowing(|analysis| walk_program(analysis, script));
+    }
+
+    pub fn check_shadowing_in_module(&mut self, module: &Module) {
+        self.run_shadowing(|analysis| {
+            for item in &module.items {
+                match &item.kind {
+                    ModuleItemKind::Decl(decl) | ModuleItemKind::Export(Export { decl }) => {
+                        analysis.visit_decl(decl)
+                    }
+                    ModuleItemKind::Import(_) => {}
+                }
+            }
+        });
+    }
+
+    fn run_shadowing(&mut self, f: impl FnOnce(&mut Shadowing)) {
+        let mut analysis = Shadowing {
+            scopes: vec![HashSet::new()],
+            warnings: vec![],
+        };
+
+        f(&mut analysis);
+
+        self.current_report.warnings.append(&mut analysis.warnings);
+    }
+}
+
+struct Shadowing {
+    scopes: Vec<HashSet<String>>,
+    warnings: Vec<Diagnostic>,
+}
+
+impl Shadowing {
+    fn declare(&mut self, ident: &BindingIdent) {
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .insert(ident.name.to_owned());
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.contains(name))
+    }
+
+    fn with_scope(&mut self, f: impl FnOnce(&mut Self)) {
+        self.scopes.push(HashSet::new());
+        f(self);
+        self.scopes.pop();
+    }
+
+    fn check_let(&mut self, pattern: &Pattern, init: Option<&Expr>) {
+        let mut refs = References::default();
+        if let Some(init) = init {
+            refs.visit_expr(init);
+        }
+        for ident in find_pattern_bindings(pattern) {
+            if !self.is_declared(&ident.name) || refs.names.contains(&ident.name) {
+                continue;
+            }
+            let name = &ident.name;
+            self.warnings.push(Diagnostic {
+                code: 1015,
+                message: format!("`{name}` shadows another binding"),
+                reasons: vec![TypeError {
+                    message: format!(
+                        "`{name}` is already declared in this scope or an outer scope"
+                    ),
+                }],
+                span: Some(ident.span),
+                suggestion: Some(format!(
+                    "rename `{name}` or use the previous value in the initializer, e.g. `let {name} = f({name})`"
+                )),
+                related: vec![],
+            });
+        }
+    }
+}
+
+impl Visitor for Shadowing {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Function(_) => self.with_scope(|analysis| walk_expr(analysis, expr)),
+            ExprKind::IfElse(IfElse {
+                cond,
+                consequent,
+                alternate,
+            }) => {
+                self.visit_expr(cond);
+                self.with_scope(|analysis| walk_block(analysis, consequent));
+                if let Some(alternate) = alternate {
+                    self.with_scope(|analysis| walk_block_or_expr(analysis, alternate));
+                }
+            }
+            ExprKind::IfLet(IfLet {
+                pattern,
+                expr,
+                consequent,
+                alternate,
+            }) => {
+                self.visit_expr(expr);
+                self.with_scope(|analysis| {
+                    analysis.visit_pattern(pattern);
+                    walk_block(analysis, consequent);
+                });
+                if let Some(alternate) = alternate {
+                    self.with_scope(|analysis| walk_block_or_expr(analysis, alternate));
+                }
+            }
+            ExprKind::Match(Match { expr, arms }) => {
+                self.visit_expr(expr);
+                for arm in arms {
+                    self.with_scope(|analysis| {
+                        analysis.visit_pattern(&arm.pattern);
+                        if let Some(guard) = &arm.guard {
+                            analysis.visit_expr(guard);
+                        }
+                        walk_block_or_expr(analysis, &arm.body);
+                    });
+                }
+            }
+            ExprKind::Try(Try {
+                body,
+                catch,
+                finally,
+            }) => {
+                self.with_scope(|analysis| walk_block(analysis, body));
+                if let Some(catch) = catch {
+                    self.with_scope(|analysis| {
+                        if let Some(param) = &catch.param {
+                            analysis.visit_pattern(param);
+                        }
+                        walk_block(analysis, &catch.body);
+                    });
+                }
+                if let Some(finally) = finally {
+                    self.with_scope(|analysis| walk_block(analysis, finally));
+                }
+            }
+            ExprKind::Do(Do { body }) => self.with_scope(|analysis| walk_block(analysis, body)),
+            _ => walk_expr(self, expr),
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Ident(ident) | PatternKind::Is(IsPat { ident, .. }) => self.declare(ident),
+            PatternKind::Object(ObjectPat { props, .. }) => {
+                for prop in props {
+                    if let ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) = prop {
+                        self.declare(ident);
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_pattern(self, pattern);
+    }
+
+    fn visit_decl(&mut self, decl: &Decl) {
+        match &decl.kind {
+            DeclKind::VarDecl(VarDecl {
+                is_declare: false,
+                pattern,
+                expr,
+                ..
+            }) => {
+                if let Some(expr) = expr {
+                    self.visit_expr(expr);
+                }
+                self.check_let(pattern, expr.as_ref());
+                self.visit_pattern(pattern);
+            }
+            DeclKind::VarDecl(_) => {}
+            _ => walk_decl(self, decl),
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &Stmt) {
+        match &stmt.kind {
+            StmtKind::For(ForStmt { left, right, body }) => {
+                self.visit_expr(right);
+                self.with_scope(|analysis| {
+                    analysis.visit_pattern(left);
+                    walk_block(analysis, body);
+                });
+            }
+            StmtKind::LetElse(LetElseStmt {
+                pattern,
+                expr,
+                alternate,
+            }) => {
+                self.visit_expr(expr);
+                self.with_scope(|analysis| walk_block(analysis, alternate));
+                self.check_let(pattern, Some(expr));
+                self.visit_pattern(pattern);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+}
+
+// The names used by an expression.
+#[derive(Default)]
+struct References {
+    names: HashSet<String>,
+}
+
+impl Visitor for References {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let ExprKind::Ident(Ident { name, .. }) = &expr.kind {
+            self.names.insert(name.to_owned());
+        }
+        walk_expr(self, expr);
+    }
+}
+
+// Returns the bindings introduced by `pattern`.
+pub(crate) fn find_pattern_bindings(pattern: &Pattern) -> Vec<BindingIdent> {
+    let mut bindings = PatternBindings::default();
+    bindings.visit_pattern(pattern);
+    bindings.idents
+}
+
+// The bindings introduced by a pattern.
+#[derive(Default)]
+struct PatternBindings {
+    idents: Vec<BindingIdent>,
+}
+
+impl Visitor for PatternBindings {
+    // Default values can contain functions whose params aren't bindings of
+    // the pattern.
+    fn visit_expr(&mut self, _expr: &Expr) {}
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        match &pattern.kind {
+            PatternKind::Ident(ident) | PatternKind::Is(IsPat { ident, .. }) => {
+                self.idents.push(ident.to_owned())
+            }
+            PatternKind::Object(ObjectPat { props, .. }) => {
+                for prop in props {
+                    if let ObjectPatProp::Shorthand(ShorthandPatProp { ident, .. }) = prop {
+                        self.idents.push(ident.to_owned());
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_pattern(self, pattern);
+    }
+}
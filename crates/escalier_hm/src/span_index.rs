@@ -0,0 +1,48 @@
+use generational_arena::Index;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use escalier_ast::Span;
+
+use crate::checker::Checker;
+
+/// Maps the spans of exprs, bindings, and type annotations to their types so
+/// that the type of the innermost node at a position can be looked up without
+/// traversing the AST.
+#[derive(Clone, Debug, Default)]
+pub struct SpanIndex {
+    // Spans that start at the same position are ordered from shortest to
+    // longest when iterating in reverse.
+    types: BTreeMap<(usize, Reverse<usize>), Index>,
+}
+
+impl SpanIndex {
+    pub fn insert(&mut self, span: Span, t: Index) {
+        self.types.insert((span.start, Reverse(span.end)), t);
+    }
+
+    // Nodes' spans are either nested or disjoint so the first span containing
+    // `span` when iterating backwards from its start is the innermost one.
+    pub fn get(&self, span: &Span) -> Option<Index> {
+        self.types
+            .range(..=(span.start, Reverse(span.start)))
+            .rev()
+            .find(|((_, Reverse(end)), _)| span.end <= *end)
+            .map(|(_, t)| *t)
+    }
+
+    pub(crate) fn for_each_index(&mut self, f: impl FnMut(&mut Index)) {
+        self.types.values_mut().for_each(f);
+    }
+}
+
+impl Checker {
+    /// Returns the type of the innermost expr, binding, or type annotation
+    /// containing `span`, e.g. to show the type under the cursor when
+    /// hovering.  `span` can be empty, in which case the node at that offset
+    /// is used.  Only nodes that have been inferred are indexed.
+    pub fn type_at(&mut self, span: Span) -> Option<Index> {
+        let t = self.span_index.get(&span)?;
+        Some(self.prune(t))
+    }
+}
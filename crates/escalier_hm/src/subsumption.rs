@@ -0,0 +1,265 @@
+use generational_arena::Index;
+use std::collections::HashSet;
+
+use escalier_ast::Literal as Lit;
+
+use crate::checker::Checker;
+use crate::types::*;
+
+impl Checker {
+    // Removes the members of a union that are subtypes of other members,
+    // e.g. `"a" | string` becomes `string` and `1 | 2 | 1` becomes `1 | 2`.
+    pub(crate) fn remove_subsumed_union_members(&mut self, types: Vec<Index>) -> Vec<Index> {
+        if types.len() < 2 {
+            return types;
+        }
+        let pruned: Vec<Index> = types.iter().map(|t| self.prune(*t)).collect();
+
+        let mut primitives: HashSet<Primitive> = HashSet::new();
+        for t in &pruned {
+            match &self.arena[*t].kind {
+                TypeKind::Keyword(Keyword::Unknown) => return vec![*t],
+                TypeKind::Primitive(primitive) => {
+                    primitives.insert(primitive.to_owned());
+                }
+                _ => (),
+            }
+        }
+
+        // Comparing every pair of members is quadratic so it's skipped for
+        // large unions, literals are still removed if their primitive types
+        // are members.
+        let compare_pairs = pruned.len() <= self.subsumption.max_size;
+
+        let mut result = vec![];
+        for (i, t) in pruned.iter().enumerate() {
+            let widened = self.widen_to_primitive(*t);
+            if widened.is_some_and(|primitive| primitives.contains(&primitive)) {
+                continue;
+            }
+            if compare_pairs && self.is_subsumed_by_another(&pruned, i, |sub, sup| (sub, sup)) {
+                continue;
+            }
+            result.push(types[i]);
+        }
+        result
+    }
+
+    // Records which members of `union` are unbound type vars so that the
+    // union can be simplified once they're bound, e.g. `T | 0` becomes
+    // `number` when `T` is bound to `number`.  The members of nested unions
+    // are included since they're compared with the other members too.
+    pub(crate) fn track_type_var_members(&mut self, union: Index, types: &[Index]) {
+        for t in types {
+            let t = self.prune(*t);
+            match &self.arena[t].kind {
+                TypeKind::TypeVar(TypeVar { instance: None, .. }) => {
+                    self.unions_by_type_var.entry(t).or_default().push(union);
+                }
+                TypeKind::Union(Union { types }) => {
+                    let types = types.to_owned();
+                    self.track_type_var_members(union, &types);
+                }
+                _ => (),
+            }
+        }
+    }
+
+    // Removes the members of the unions containing `type_var` that are
+    // subsumed by other members now that it's been bound.  The unions are
+    // updated in place since other types may already refer to them.
+    pub(crate) fn remove_subsumed_members_after_binding(&mut self, type_var: Index) {
+        let unions = match self.unions_by_type_var.remove(&type_var) {
+            Some(unions) => unions,
+            None => return,
+        };
+        for union in unions {
+            let types = match &self.arena[union].kind {
+                TypeKind::Union(Union { types }) => types.to_owned(),
+                _ => continue,
+            };
+            let len = types.len();
+            let types = self.remove_subsumed_union_members(types);
+            match types.as_slice() {
+                _ if types.len() == len => (),
+                // A union with a single member is replaced by that member
+                // unless it's a type var, which can't be copied.
+                [t] => {
+                    let t = self.prune(*t);
+                    if !matches!(self.arena[t].kind, TypeKind::TypeVar(_)) {
+                        self.arena[union].kind = self.arena[t].kind.clone();
+                        continue;
+                    }
+                }
+                _ => {
+                    self.arena[union].kind = TypeKind::Union(Union {
+                        types: types.clone(),
+                    })
+                }
+            }
+            self.track_type_var_members(union, &types);
+        }
+    }
+
+    // Removes the members of an intersection that are supertypes of other
+    // members, e.g. `string & "a"` becomes `"a"`.
+    pub(crate) fn remove_subsumed_intersection_members(&mut self, types: Vec<Index>) -> Vec<Index> {
+        if types.len() < 2 {
+            return types;
+        }
+        let pruned: Vec<Index> = types.iter().map(|t| self.prune(*t)).collect();
+
+        let mut primitives: HashSet<Primitive> = HashSet::new();
+        for t in &pruned {
+            if let Some(primitive) = self.widen_to_primitive(*t) {
+                primitives.insert(primitive);
+            }
+        }
+
+        let compare_pairs = pruned.len() <= self.subsumption.max_size;
+
+        let mut result = vec![];
+        for (i, t) in pruned.iter().enumerate() {
+            match &self.arena[*t].kind {
+                TypeKind::Keyword(Keyword::Unknown) => continue,
+                TypeKind::Primitive(primitive) if primitives.contains(primitive) => continue,
+                _ => (),
+            }
+            if compare_pairs && self.is_subsumed_by_another(&pruned, i, |sub, sup| (sup, sub)) {
+                continue;
+            }
+            result.push(types[i]);
+        }
+
+        match result.is_empty() {
+            // Every member was `unknown`.
+            true => vec![types[0]],
+            false => result,
+        }
+    }
+
+    // Returns true if `types[i]` is subsumed by another member of `types`.
+    // `order` swaps the types being compared for intersections.  When two
+    // members subsume each other the first one is kept.
+    fn is_subsumed_by_another(
+        &mut self,
+        types: &[Index],
+        i: usize,
+        order: impl Fn(Index, Index) -> (Index, Index),
+    ) -> bool {
+        (0..types.len()).any(|j| {
+            if i == j {
+                return false;
+            }
+            let (sub, sup) = order(types[i], types[j]);
+            if !self.is_subtype_of(sub, sup) {
+                return false;
+            }
+            j < i || !self.is_subtype_of(sup, sub)
+        })
+    }
+
+    // Returns the primitive type that a literal or a refinement of a
+    // primitive type belongs to, e.g. `"a"` belongs to `string`.
+    fn widen_to_primitive(&self, t: Index) -> Option<Primitive> {
+        match &self.arena[t].kind {
+            TypeKind::Literal(Lit::Number(_)) => Some(Primitive::Number),
+            TypeKind::Literal(Lit::BigInt(_)) => Some(Primitive::BigInt),
+            TypeKind::Literal(Lit::Boolean(_)) => Some(Primitive::Boolean),
+            TypeKind::Literal(Lit::String(_)) => Some(Primitive::String),
+            TypeKind::Primitive(Primitive::Int | Primitive::Float) => Some(Primitive::Number),
+            TypeKind::UniqueSymbol(_) => Some(Primitive::Symbol),
+            _ => None,
+        }
+    }
+
+    // Returns true if `sub` is known to be a subtype of `sup` without binding
+    // any type variables.  This is conservative, types that can't be compared
+    // structurally, e.g. functions, are only subtypes of themselves.
+    fn is_subtype_of(&mut self, sub: Index, sup: Index) -> bool {
+        let sub = self.prune(sub);
+        let sup = self.prune(sup);
+        if sub == sup {
+            return true;
+        }
+
+        match (self.arena[sub].kind.clone(), self.arena[sup].kind.clone()) {
+            (_, TypeKind::Keyword(Keyword::Unknown)) => true,
+            (TypeKind::Keyword(Keyword::Never), _) => true,
+            (TypeKind::Union(Union { types }), _) => {
+                types.iter().all(|t| self.is_subtype_of(*t, sup))
+            }
+            (_, TypeKind::Union(Union { types })) => {
+                types.iter().any(|t| self.is_subtype_of(sub, *t))
+            }
+            // The body of a recursive type is compared without unfolding it,
+            // e.g. `null` is a subtype of `μX. null | {next: X}`.
+            (_, TypeKind::Mu(Mu { t, .. })) => self.is_subtype_of(sub, t),
+            (TypeKind::Keyword(k1), TypeKind::Keyword(k2)) => k1 == k2,
+            (TypeKind::Literal(l1), TypeKind::Literal(l2)) => l1 == l2,
+            (TypeKind::Primitive(p1), TypeKind::Primitive(p2)) if p1 == p2 => true,
+            (_, TypeKind::Primitive(primitive)) => self.widen_to_primitive(sub) == Some(primitive),
+            (TypeKind::Tuple(t1), TypeKind::Tuple(t2)) => {
+                (t2.readonly || !t1.readonly)
+                    && t1.types.len() == t2.types.len()
+                    && t1
+                        .types
+                        .iter()
+                        .zip(t2.types.iter())
+                        .all(|(e1, e2)| self.is_elem_subtype_of(*e1, *e2, t2.readonly))
+            }
+            (TypeKind::Array(a1), TypeKind::Array(a2)) => {
+                (a2.readonly || !a1.readonly) && self.is_elem_subtype_of(a1.t, a2.t, a2.readonly)
+            }
+            (TypeKind::TypeRef(r1), TypeKind::TypeRef(r2)) => {
+                r1.name == r2.name
+                    && r1.type_args.len() == r2.type_args.len()
+                    && r1
+                        .type_args
+                        .iter()
+                        .zip(r2.type_args.iter())
+                        .all(|(a1, a2)| self.is_elem_subtype_of(*a1, *a2, false))
+            }
+            (TypeKind::Object(o1), TypeKind::Object(o2)) if self.subsumption.objects => {
+                self.is_obj_subtype_of(&o1, &o2)
+            }
+            _ => false,
+        }
+    }
+
+    // Elements that can be mutated, e.g. those of mutable arrays, have to be
+    // the same type in both, otherwise they're covariant.
+    fn is_elem_subtype_of(&mut self, sub: Index, sup: Index, covariant: bool) -> bool {
+        self.is_subtype_of(sub, sup) && (covariant || self.is_subtype_of(sup, sub))
+    }
+
+    // Only objects with nothing but props are compared.  `sub` can have props
+    // that `sup` doesn't.
+    fn is_obj_subtype_of(&mut self, sub: &Object, sup: &Object) -> bool {
+        let props = |object: &Object| -> Option<Vec<TProp>> {
+            object
+                .elems
+                .iter()
+                .map(|elem| match elem {
+                    TObjElem::Prop(prop) => Some(prop.to_owned()),
+                    _ => None,
+                })
+                .collect()
+        };
+        let (sub_props, sup_props) = match (props(sub), props(sup)) {
+            (Some(sub_props), Some(sup_props)) => (sub_props, sup_props),
+            _ => return false,
+        };
+
+        sup_props.iter().all(|sup_prop| {
+            match sub_props.iter().find(|prop| prop.name == sup_prop.name) {
+                Some(sub_prop) => {
+                    (sup_prop.optional || !sub_prop.optional)
+                        && (!sub_prop.readonly || sup_prop.readonly)
+                        && self.is_elem_subtype_of(sub_prop.t, sup_prop.t, sup_prop.readonly)
+                }
+                None => sup_prop.optional,
+            }
+        })
+    }
+}
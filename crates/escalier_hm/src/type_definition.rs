@@ -0,0 +1,54 @@
+use generational_arena::Index;
+
+use escalier_ast::Span;
+
+use crate::checker::Checker;
+use crate::provenance::Provenance;
+use crate::types::{self, TypeKind};
+
+impl Checker {
+    /// Returns the spans of the declarations of the type of the innermost
+    /// node containing `span`, e.g. for an LSP "go to type definition"
+    /// request.  Aliases are followed back to the `type` decl that declares
+    /// them and instances of classes to the class.  Unions have a definition
+    /// for each of their members that has one.  Types that aren't declared
+    /// anywhere, e.g. primitives and object types, return an empty `Vec`.
+    pub fn type_definition_at(&mut self, span: Span) -> Vec<Span> {
+        let mut spans = vec![];
+        if let Some(t) = self.type_at(span) {
+            self.collect_type_definitions(t, &mut spans);
+        }
+        spans
+    }
+
+    fn collect_type_definitions(&mut self, t: Index, spans: &mut Vec<Span>) {
+        let t = self.prune(t);
+        match self.arena[t].kind.clone() {
+            TypeKind::TypeRef(types::TypeRef { scheme, .. }) => {
+                if let Some(decl) = self.symbols.get_type_ref_decl(t) {
+                    if !spans.contains(&decl) {
+                        spans.push(decl);
+                    }
+                } else if let Some(scheme) = scheme {
+                    // References to `Self` within classes aren't written in
+                    // the source, but their scheme is the class's instance.
+                    self.collect_type_definitions(scheme.t, spans);
+                }
+            }
+            TypeKind::Object(_) => {
+                if let Some(Provenance::Class(class)) = &self.arena[t].provenance {
+                    if !spans.contains(&class.span) {
+                        spans.push(class.span);
+                    }
+                }
+            }
+            TypeKind::Union(types::Union { types }) => {
+                for t in types {
+                    self.collect_type_definitions(t, spans);
+                }
+            }
+            TypeKind::Array(types::Array { t, .. }) => self.collect_type_definitions(t, spans),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use generational_arena::{Arena, Index};
+use serde::{Deserialize, Serialize};
+
+use escalier_ast::*;
+
+use crate::checker::Checker;
+use crate::types::{Type, TypeKind, TypeVar};
+
+/// The version of the schema used by `TypedScript`.  This is incremented
+/// whenever the AST or types change in a way that breaks existing consumers.
+pub const SCHEMA_VERSION: u32 = 3;
+
+/// A checked script along with the types that were inferred for it.  This
+/// allows tools like linters, doc generators, and editors to consume the
+/// compiler's output as JSON without linking against it.
+///
+/// Each `inferred_type` in `script` is an index into `types`.  Type variables
+/// that have been resolved are replaced by the type they were resolved to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypedScript {
+    pub version: u32,
+    pub script: Script,
+    pub types: Arena<Type>,
+}
+
+impl TypedScript {
+    /// `script` must have been checked by `checker`.
+    pub fn new(script: &Script, checker: &Checker) -> Self {
+        let mut script = script.clone();
+        Resolver { checker }.visit_program_mut(&mut script);
+
+        TypedScript {
+            version: SCHEMA_VERSION,
+            script,
+            types: checker.arena.clone(),
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        let typed_script: TypedScript = serde_json::from_str(json)?;
+        if typed_script.version != SCHEMA_VERSION {
+            return Err(serde::de::Error::custom(format!(
+                "unsupported schema version {}, expected {SCHEMA_VERSION}",
+                typed_script.version
+            )));
+        }
+        Ok(typed_script)
+    }
+}
+
+struct Resolver<'a> {
+    checker: &'a Checker,
+}
+
+impl<'a> Resolver<'a> {
+    // Like `Checker::prune` but doesn't shorten the chain of instances since
+    // the checker isn't mutable.
+    fn resolve(&self, t: &mut Option<Index>) {
+        if let Some(index) = t {
+            while let TypeKind::TypeVar(TypeVar {
+                instance: Some(instance),
+                ..
+            }) = &self.checker.arena[*index].kind
+            {
+                *index = *instance;
+            }
+        }
+    }
+}
+
+impl<'a> VisitorMut for Resolver<'a> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        self.resolve(&mut expr.inferred_type);
+        walk_expr_mut(self, expr);
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        self.resolve(&mut pattern.inferred_type);
+        walk_pattern_mut(self, pattern);
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        self.resolve(&mut stmt.inferred_type);
+        walk_stmt_mut(self, stmt);
+    }
+
+    fn visit_type_ann_mut(&mut self, type_ann: &mut TypeAnn) {
+        self.resolve(&mut type_ann.inferred_type);
+        walk_type_ann_mut(self, type_ann);
+    }
+}
@@ -6,11 +6,12 @@ use std::fmt;
 // TODO: create type versions of these so that we don't have to bother
 // with source locations when doing type-level stuff.
 use escalier_ast::{BindingIdent, Literal as Lit};
+use serde::{Deserialize, Serialize};
 
 use crate::checker::Checker;
 use crate::provenance::Provenance;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TypeVar {
     pub id: usize,
     pub instance: Option<Index>,
@@ -18,7 +19,7 @@ pub struct TypeVar {
 }
 
 // TODO: rename this TypeRef
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct TypeRef {
     pub name: String,
     // NOTE: if `scheme` is `None` then we need to look up the type in the
@@ -27,7 +28,7 @@ pub struct TypeRef {
     pub type_args: Vec<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Keyword {
     Never,
     Object,
@@ -45,9 +46,15 @@ impl fmt::Display for Keyword {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Primitive {
     Number,
+    // `int` and `float` are refinements of `number` which are only used when
+    // `Strictness::numeric_refinements` is enabled.  Both are erased to
+    // `number` in the generated output.
+    Int,
+    Float,
+    BigInt,
     Boolean,
     String,
     Symbol,
@@ -57,6 +64,9 @@ impl Primitive {
     pub fn get_scheme_name(&self) -> &'static str {
         match self {
             Self::Number => "Number",
+            Self::Int => "Number",
+            Self::Float => "Number",
+            Self::BigInt => "BigInt",
             Self::Boolean => "Boolean",
             Self::String => "String",
             Self::Symbol => "Symbol",
@@ -68,6 +78,9 @@ impl fmt::Display for Primitive {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let result = match self {
             Self::Number => "number",
+            Self::Int => "int",
+            Self::Float => "float",
+            Self::BigInt => "bigint",
             Self::Boolean => "boolean",
             Self::String => "string",
             Self::Symbol => "symbol",
@@ -76,7 +89,7 @@ impl fmt::Display for Primitive {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Function {
     pub params: Vec<FuncParam>,
     pub ret: Index,
@@ -86,7 +99,7 @@ pub struct Function {
     pub throws: Option<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct FuncParam {
     pub pattern: TPat,
     pub t: Index,
@@ -109,7 +122,7 @@ impl FuncParam {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TPat {
     Ident(BindingIdent),
     Rest(RestPat),
@@ -117,100 +130,149 @@ pub enum TPat {
     Object(TObjectPat),
     Lit(TLitPat),
     Is(TIsPat),
+    Range(TRangePat),
     Wildcard,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct RestPat {
     pub arg: Box<TPat>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TuplePat {
     pub elems: Vec<Option<TPat>>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TObjectPat {
     pub props: Vec<TObjectPatProp>,
 }
 
 // TODO: update this to match AST changes to ObjectPatProp
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum TObjectPatProp {
     KeyValue(TObjectKeyValuePatProp),
     Assign(TObjectAssignPatProp),
     Rest(RestPat),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TObjectKeyValuePatProp {
     pub key: String,
     pub value: TPat,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TObjectAssignPatProp {
     pub key: String,
     pub value: Option<Index>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TLitPat {
     pub lit: Lit,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TRangePat {
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub inclusive: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TIsPat {
     pub ident: String,
     pub is_id: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct TypeParam {
     pub name: String,
+    /// The number of type args the param takes if it's a type constructor,
+    /// e.g. 1 for `F` in `type MapOver<F<_>, T> = F<T>`, otherwise 0.
+    pub arity: usize,
     pub constraint: Option<Index>,
     pub default: Option<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Call {
     pub args: Vec<Index>,
     pub ret: Index,
 }
 
 // TODO: update this struct to use `Function` internally
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TMethod {
     pub name: TPropKey,
     pub mutates: bool,
     pub function: Function,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TGetter {
     pub name: TPropKey,
     pub ret: Index,
     pub throws: Option<Index>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TSetter {
     pub name: TPropKey,
     pub param: FuncParam,
     pub throws: Option<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TIndexKey {
     pub name: String,
     pub t: Index,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum TPropKey {
     StringKey(String),
     NumberKey(String),
+    SymbolKey(TSymbolKey),
+}
+
+// A key whose type is a `unique symbol`, e.g. `[Symbol.iterator]`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TSymbolKey {
+    // The `id` of the `UniqueSymbol`.
+    pub id: usize,
+    // How the key was written, this is only used when printing types.
+    pub name: String,
+}
+
+// The same symbol can be referenced in different ways so keys are compared
+// using only their `id`.
+impl PartialEq for TSymbolKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for TSymbolKey {}
+
+impl PartialOrd for TSymbolKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TSymbolKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl std::hash::Hash for TSymbolKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
 }
 
 impl fmt::Display for TPropKey {
@@ -218,11 +280,12 @@ impl fmt::Display for TPropKey {
         match self {
             TPropKey::StringKey(key) => write!(f, "{key}"),
             TPropKey::NumberKey(key) => write!(f, "{key}"),
+            TPropKey::SymbolKey(TSymbolKey { name, .. }) => write!(f, "[{name}]"),
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TProp {
     pub name: TPropKey,
     pub optional: bool,
@@ -252,13 +315,13 @@ impl TProp {
 //     pub throws: Option<Index>,
 // }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum MappedModifier {
     Add,
     Remove,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct MappedType {
     pub key: Index,
     pub value: Index,
@@ -271,7 +334,7 @@ pub struct MappedType {
     pub extends: Option<Index>,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum TObjElem {
     Call(Function),
     // NOTE: type_params on constructors should be a subset of type_params on
@@ -285,68 +348,77 @@ pub enum TObjElem {
     // RestSpread - we can use this instead of converting {a, ...x} to {a} & tvar
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Object {
     pub elems: Vec<TObjElem>,
+    // Set on the types inferred from object literals.  Fresh object types
+    // can't have properties that are missing from the type they're being
+    // assigned to.
+    pub is_fresh: bool,
 }
 
 // NOTE: this is only used for the rest element in array patterns since we
 // treat `{a, ...x}` as `{a} & x` where `x` is a type variable.
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Rest {
     pub arg: Index,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum UtilityKind {
     KeyOf,
     Index,
     Cond,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Utility {
     pub kind: UtilityKind,
     pub types: Vec<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Mutable {
     pub t: Index,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Union {
     pub types: Vec<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Intersection {
     pub types: Vec<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Tuple {
     pub types: Vec<Index>,
+    // Labels are only used when printing types, e.g. `[x: number, y: number]`
+    // and are ignored when comparing or unifying tuples.
+    pub labels: Option<Vec<String>>,
+    pub readonly: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Array {
     pub t: Index,
+    pub readonly: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct KeyOf {
     pub t: Index,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct IndexedAccess {
     pub obj: Index,
     pub index: Index,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Conditional {
     pub check: Index,
     pub extends: Index,
@@ -354,17 +426,23 @@ pub struct Conditional {
     pub false_type: Index,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Infer {
     pub name: String,
     // TODO
     // pub constraint: Option<Index>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Wildcard {}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+// Every `unique symbol` is only assignable to itself and `symbol`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct UniqueSymbol {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum TBinaryOp {
     Add,
     Sub,
@@ -374,14 +452,27 @@ pub enum TBinaryOp {
     // TODO: fill this out with more operators
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BinaryT {
     pub op: TBinaryOp,
     pub left: Index,
     pub right: Index,
 }
 
-#[derive(Debug, Clone, Hash)]
+// A recursive type, e.g. `μX. null | {next: X}` for a linked list.  The
+// `MuVar`s in `t` with the same `id` stand for the whole type.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Mu {
+    pub id: usize,
+    pub t: Index,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MuVar {
+    pub id: usize,
+}
+
+#[derive(Debug, Clone, Hash, Serialize, Deserialize)]
 pub enum TypeKind {
     TypeVar(TypeVar),
     TypeRef(TypeRef),
@@ -391,6 +482,7 @@ pub enum TypeKind {
     Tuple(Tuple),
     Keyword(Keyword),
     Primitive(Primitive),
+    UniqueSymbol(UniqueSymbol),
     Literal(Lit),
     Function(Function),
     Object(Object),
@@ -401,11 +493,16 @@ pub enum TypeKind {
     Infer(Infer),
     Wildcard,
     Binary(BinaryT),
+    Mu(Mu),
+    MuVar(MuVar),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Type {
     pub kind: TypeKind,
+    // Provenance is only used for reporting errors and would otherwise
+    // include a copy of the AST node for every type when serialized.
+    #[serde(skip)]
     pub provenance: Option<Provenance>,
 }
 
@@ -418,11 +515,15 @@ impl From<TypeKind> for Type {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Scheme {
     pub t: Index,
     pub type_params: Option<Vec<TypeParam>>,
     pub is_type_param: bool,
+    /// The id of the module that an opaque type, e.g. `opaque type UserId =
+    /// string`, was declared in.  Opaque types can only be used as `t` inside
+    /// of that module, see `Checker::module_id`.
+    pub opaque_module: Option<usize>,
 }
 
 /// A type variable standing for an arbitrary type.
@@ -431,371 +532,6 @@ pub struct Scheme {
 /// only assigned lazily, when required.
 
 impl Checker {
-    pub fn print_scheme(&self, scheme: &Scheme) -> String {
-        let mut result = String::default();
-        if let Some(type_params) = &scheme.type_params {
-            let type_params = type_params
-                .iter()
-                .map(|tp| match &tp.constraint {
-                    Some(constraint) => {
-                        format!("{}:{}", tp.name.clone(), self.print_type(constraint))
-                    }
-                    None => tp.name.clone(),
-                })
-                .collect::<Vec<_>>();
-            result.push_str(&format!("<{}>", type_params.join(", ")))
-        }
-        result.push_str(&self.print_type(&scheme.t));
-        result
-    }
-
-    // TODO: support pretty printing of types
-    pub fn print_type(&self, index: &Index) -> String {
-        match &self.arena[*index].kind {
-            TypeKind::TypeVar(TypeVar {
-                instance: Some(inst),
-                ..
-            }) => self.print_type(inst),
-            TypeKind::TypeVar(TypeVar { id, constraint, .. }) => match constraint {
-                Some(constraint) => format!("t{id}:{}", self.print_type(constraint)),
-                None => format!("t{id}"),
-            },
-            TypeKind::Union(Union { types }) => self.print_types(types).join(" | "),
-            TypeKind::Intersection(Intersection { types }) => self.print_types(types).join(" & "),
-            TypeKind::Tuple(Tuple { types }) => {
-                format!("[{}]", self.print_types(types).join(", "))
-            }
-            TypeKind::Array(Array { t }) => format!("{}[]", self.print_type(t)),
-            TypeKind::TypeRef(TypeRef {
-                name,
-                scheme: _, // TODO
-                type_args,
-            }) => {
-                if type_args.is_empty() {
-                    name.to_string()
-                } else {
-                    format!("{}<{}>", name, self.print_types(type_args).join(", "))
-                }
-            }
-            TypeKind::Keyword(keyword) => keyword.to_string(),
-            TypeKind::Primitive(primitive) => primitive.to_string(),
-            TypeKind::Literal(lit) => lit.to_string(),
-            TypeKind::Object(object) => {
-                let mut fields = vec![];
-                for prop in &object.elems {
-                    match prop {
-                        TObjElem::Getter(TGetter {
-                            ret,
-                            name,
-                            throws: _,
-                        }) => {
-                            let ret_type = self.print_type(ret);
-                            fields.push(format!("get {name}(self) -> {ret_type}"));
-                        }
-                        TObjElem::Setter(TSetter {
-                            param,
-                            name,
-                            throws: _, // TODO
-                        }) => {
-                            let param = self.print_type(&param.t);
-                            fields.push(format!("set {name}(mut self, {param})"))
-                        }
-                        TObjElem::Constructor(Function {
-                            params,
-                            ret,
-                            type_params,
-                            throws: _, // TODO
-                        }) => {
-                            let mut result = "new fn".to_string();
-                            match type_params {
-                                Some(type_params) if !type_params.is_empty() => {
-                                    let type_params = type_params
-                                        .iter()
-                                        .map(|tp| match &tp.constraint {
-                                            Some(constraint) => format!(
-                                                "{}:{}",
-                                                tp.name.clone(),
-                                                self.print_type(constraint)
-                                            ),
-                                            None => tp.name.clone(),
-                                        })
-                                        .collect::<Vec<_>>();
-                                    result.push_str(&format!("<{}>", type_params.join(", ")))
-                                }
-                                _ => (),
-                            };
-                            result.push_str(&format!(
-                                "({}) -> {}",
-                                self.print_params(params).join(", "),
-                                self.print_type(ret)
-                            ));
-                            fields.push(result);
-                        }
-                        TObjElem::Call(Function {
-                            params,
-                            ret,
-                            type_params,
-                            throws: _, // TODO
-                        }) => {
-                            let mut result = "fn".to_string();
-                            match type_params {
-                                Some(type_params) if !type_params.is_empty() => {
-                                    let type_params = type_params
-                                        .iter()
-                                        .map(|tp| match &tp.constraint {
-                                            Some(constraint) => format!(
-                                                "{}:{}",
-                                                tp.name.clone(),
-                                                self.print_type(constraint)
-                                            ),
-                                            None => tp.name.clone(),
-                                        })
-                                        .collect::<Vec<_>>();
-                                    result.push_str(&format!("<{}>", type_params.join(", ")))
-                                }
-                                _ => (),
-                            };
-                            result.push_str(&format!(
-                                "({}) -> {}",
-                                self.print_params(params).join(", "),
-                                self.print_type(ret)
-                            ));
-                            fields.push(result);
-                        }
-                        TObjElem::Mapped(MappedType {
-                            key,
-                            value,
-                            target,
-                            source,
-                            optional: _, // TODO
-                            // TODO: handle `if`-clause
-                            check: _,
-                            extends: _,
-                        }) => {
-                            let key = self.print_type(key);
-                            let value = self.print_type(value);
-                            let source = self.print_type(source);
-
-                            let result = format!("[{key}]: {value} for {target} in {source}",);
-                            fields.push(result);
-                        }
-                        TObjElem::Method(TMethod {
-                            name,
-                            mutates,
-                            function:
-                                Function {
-                                    type_params,
-                                    params,
-                                    ret,
-                                    throws,
-                                },
-                        }) => {
-                            let name = match name {
-                                TPropKey::StringKey(s) => s,
-                                TPropKey::NumberKey(n) => n,
-                            };
-                            let type_params = match type_params {
-                                Some(type_params) if !type_params.is_empty() => {
-                                    let type_params = type_params
-                                        .iter()
-                                        .map(|tp| match &tp.constraint {
-                                            Some(constraint) => {
-                                                format!(
-                                                    "{}:{}",
-                                                    tp.name.clone(),
-                                                    self.print_type(constraint)
-                                                )
-                                            }
-                                            None => tp.name.clone(),
-                                        })
-                                        .collect::<Vec<_>>();
-                                    format!("<{}>", type_params.join(", "))
-                                }
-                                _ => "".to_string(),
-                            };
-
-                            let throws = match throws {
-                                Some(throws) => format!(" throws {}", self.print_type(throws)),
-                                None => "".to_string(),
-                            };
-
-                            let mut params = self.print_params(params);
-                            match mutates {
-                                true => params.insert(0, "mut self".to_string()),
-                                false => params.insert(0, "self".to_string()),
-                            }
-                            let params = params.join(", ");
-
-                            let ret = self.print_type(ret);
-                            let field = format!("{name}{type_params}({params}) -> {ret}{throws}",);
-                            fields.push(field);
-                        }
-                        TObjElem::Prop(TProp {
-                            name,
-                            optional,
-                            readonly,
-                            t,
-                        }) => {
-                            let name = match name {
-                                TPropKey::StringKey(s) => s,
-                                TPropKey::NumberKey(n) => n,
-                            };
-                            let t = self.print_type(t);
-                            let mut str = "".to_string();
-                            if *readonly {
-                                str += "readonly ";
-                            }
-
-                            str += name;
-                            if *optional {
-                                str += "?";
-                            }
-                            str += &format!(": {t}");
-
-                            fields.push(str);
-                        }
-                    }
-                }
-                format!("{{{}}}", fields.join(", "))
-            }
-            TypeKind::Rest(rest) => {
-                format!("...{}", self.print_type(&rest.arg))
-            }
-            TypeKind::Function(func) => {
-                let type_params = match &func.type_params {
-                    Some(type_params) if !type_params.is_empty() => {
-                        let type_params = type_params
-                            .iter()
-                            .map(|tp| match &tp.constraint {
-                                Some(constraint) => {
-                                    format!("{}:{}", tp.name.clone(), self.print_type(constraint))
-                                }
-                                None => tp.name.clone(),
-                            })
-                            .collect::<Vec<_>>();
-                        format!("<{}>", type_params.join(", "))
-                    }
-                    _ => "".to_string(),
-                };
-                let throws = match func.throws {
-                    Some(throws) => format!(" throws {}", self.print_type(&throws)),
-                    None => "".to_string(),
-                };
-                format!(
-                    "{type_params}({}) -> {}{throws}",
-                    self.print_params(&func.params).join(", "),
-                    self.print_type(&func.ret),
-                )
-            }
-            TypeKind::KeyOf(KeyOf { t }) => format!("keyof {}", self.print_type(t)),
-            TypeKind::IndexedAccess(IndexedAccess { obj, index }) => {
-                format!("{}[{}]", self.print_type(obj), self.print_type(index))
-            }
-            TypeKind::Conditional(Conditional {
-                check,
-                extends,
-                true_type,
-                false_type,
-            }) => {
-                format!(
-                    "{} extends {} ? {} : {}",
-                    self.print_type(check),
-                    self.print_type(extends),
-                    self.print_type(true_type),
-                    self.print_type(false_type),
-                )
-            }
-            TypeKind::Infer(Infer { name }) => format!("infer {}", name),
-            TypeKind::Wildcard => "_".to_string(),
-            TypeKind::Binary(BinaryT { op, left, right }) => {
-                let op = match op {
-                    TBinaryOp::Add => "+",
-                    TBinaryOp::Sub => "-",
-                    TBinaryOp::Mul => "*",
-                    TBinaryOp::Div => "/",
-                    TBinaryOp::Mod => "%",
-                };
-                format!(
-                    "{} {} {}",
-                    self.print_type(left),
-                    op,
-                    self.print_type(right),
-                )
-            }
-        }
-    }
-
-    fn print_types(&self, indexes: &[Index]) -> Vec<String> {
-        let mut result = vec![];
-        for index in indexes {
-            result.push(self.print_type(index));
-        }
-        result
-    }
-
-    fn print_params(&self, params: &[FuncParam]) -> Vec<String> {
-        let mut strings = vec![];
-        for param in params {
-            strings.push(self.print_param(param))
-        }
-        strings
-    }
-
-    fn print_param(&self, param: &FuncParam) -> String {
-        let name = Self::tpat_to_string(&param.pattern);
-        match param.optional {
-            true => format!("{name}?: {}", self.print_type(&param.t)),
-            false => format!("{name}: {}", self.print_type(&param.t)),
-        }
-    }
-
-    fn tpat_to_string(pattern: &TPat) -> String {
-        match pattern {
-            TPat::Ident(BindingIdent { name, mutable, .. }) => match mutable {
-                true => format!("mut {}", name),
-                false => name.to_owned(),
-            },
-            TPat::Rest(RestPat { arg }) => format!("...{}", Self::tpat_to_string(arg.as_ref())),
-            TPat::Tuple(TuplePat { elems }) => format!(
-                "[{}]",
-                elems
-                    .iter()
-                    .map(|elem| match elem {
-                        Some(elem) => Self::tpat_to_string(elem),
-                        None => " ".to_string(),
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", ")
-            ),
-            TPat::Object(TObjectPat { props }) => {
-                let props: Vec<String> = props
-                    .iter()
-                    .map(|prop| match prop {
-                        TObjectPatProp::KeyValue(TObjectKeyValuePatProp { key, value }) => {
-                            match value {
-                                TPat::Ident(_) => key.to_string(),
-                                _ => format!("{}: {}", key, Self::tpat_to_string(value)),
-                            }
-                        }
-                        // TODO: handle assignments in object patterns
-                        TObjectPatProp::Assign(TObjectAssignPatProp { key, value: _ }) => {
-                            key.to_string()
-                        }
-                        TObjectPatProp::Rest(RestPat { arg }) => {
-                            format!("...{}", Self::tpat_to_string(arg.as_ref()))
-                        }
-                    })
-                    .collect();
-                format!("{{{}}}", props.join(", "))
-            }
-            TPat::Lit(TLitPat { lit }) => lit.to_string(),
-            TPat::Is(TIsPat { ident, is_id }) => {
-                format!("{ident} is {is_id}")
-            }
-            TPat::Wildcard => "_".to_string(),
-        }
-    }
-
     pub fn equals(&self, a: &Index, b: &Index) -> bool {
         match (&self.arena[*a].kind, &self.arena[*b].kind) {
             (TypeKind::TypeVar(v1), TypeKind::TypeVar(v2)) => match (v1.instance, v2.instance) {
@@ -814,7 +550,7 @@ impl Checker {
                 self.types_equal(&int1.types, &int2.types)
             }
             (TypeKind::Tuple(tuple1), TypeKind::Tuple(tuple2)) => {
-                self.types_equal(&tuple1.types, &tuple2.types)
+                tuple1.readonly == tuple2.readonly && self.types_equal(&tuple1.types, &tuple2.types)
             }
             (TypeKind::Keyword(kw1), TypeKind::Keyword(kw2)) => kw1 == kw2,
             (TypeKind::Primitive(prim1), TypeKind::Primitive(prim2)) => prim1 == prim2,
@@ -870,98 +606,154 @@ impl Checker {
         type_params: &Option<Vec<TypeParam>>,
         throws: Option<Index>,
     ) -> Index {
-        self.arena.insert(Type::from(TypeKind::Function(Function {
+        self.from_type_kind(TypeKind::Function(Function {
             params: params.to_vec(),
             ret: ret.to_owned(),
             type_params: type_params.to_owned(),
             throws,
-        })))
+        }))
     }
 
     // TODO: flatten union types
     pub fn new_union_type(&mut self, types: &[Index]) -> Index {
+        let types: Vec<Index> = types
+            .iter()
+            .filter(|t| !matches!(self.arena[**t].kind, TypeKind::Keyword(Keyword::Never)))
+            .cloned()
+            .collect();
+        let types = self.remove_subsumed_union_members(types);
         match types.len() {
             0 => self.new_keyword(Keyword::Never),
             1 => types[0],
-            _ => self.arena.insert(Type::from(TypeKind::Union(Union {
-                types: types
-                    .to_owned()
-                    .iter()
-                    .filter(|t| !matches!(self.arena[**t].kind, TypeKind::Keyword(Keyword::Never)))
-                    .cloned()
-                    .collect(),
-            }))),
+            _ => {
+                let union = self.from_type_kind(TypeKind::Union(Union {
+                    types: types.clone(),
+                }));
+                self.track_type_var_members(union, &types);
+                union
+            }
         }
     }
 
     pub fn new_intersection_type(&mut self, types: &[Index]) -> Index {
-        self.arena
-            .insert(Type::from(TypeKind::Intersection(Intersection {
-                types: types.to_owned(),
-            })))
+        let types = self.remove_subsumed_intersection_members(types.to_owned());
+        if self.has_disjoint_members(&types) {
+            return self.new_keyword(Keyword::Never);
+        }
+        match types.len() {
+            1 => types[0],
+            _ => self.from_type_kind(TypeKind::Intersection(Intersection { types })),
+        }
     }
 
     pub fn new_tuple_type(&mut self, types: &[Index]) -> Index {
-        self.arena.insert(Type::from(TypeKind::Tuple(Tuple {
+        self.from_type_kind(TypeKind::Tuple(Tuple {
             types: types.to_owned(),
-        })))
+            labels: None,
+            readonly: false,
+        }))
+    }
+
+    pub fn new_labeled_tuple_type(&mut self, types: &[Index], labels: &[String]) -> Index {
+        self.from_type_kind(TypeKind::Tuple(Tuple {
+            types: types.to_owned(),
+            labels: Some(labels.to_owned()),
+            readonly: false,
+        }))
     }
 
     pub fn new_object_type(&mut self, elems: &[TObjElem]) -> Index {
-        self.arena.insert(Type::from(TypeKind::Object(Object {
+        self.from_type_kind(TypeKind::Object(Object {
             elems: elems.to_vec(),
-        })))
+            is_fresh: false,
+        }))
+    }
+
+    // Returns a new id for a type var, unique symbol, or recursive type.  The
+    // ids match the size of the arena unless it's been compacted.
+    pub(crate) fn new_type_id(&mut self) -> usize {
+        let id = self.next_type_id.max(self.arena.len());
+        self.next_type_id = id + 1;
+        id
     }
 
     pub fn new_type_var(&mut self, constraint: Option<Index>) -> Index {
-        self.arena.insert(Type::from(TypeKind::TypeVar(TypeVar {
-            id: self.arena.len(), // use for debugging purposes only
+        let id = self.new_type_id();
+        self.from_type_kind(TypeKind::TypeVar(TypeVar {
+            id, // use for debugging purposes only
             instance: None,
             constraint,
-        })))
+        }))
+    }
+
+    pub fn new_unique_symbol(&mut self) -> Index {
+        let id = self.new_type_id();
+        self.from_type_kind(TypeKind::UniqueSymbol(UniqueSymbol { id }))
+    }
+
+    // Returns the type of the values that can be used to access a property
+    // with the given key, e.g. `"foo"` for `foo`.
+    pub fn new_prop_key_type(&mut self, key: &TPropKey) -> Index {
+        match key {
+            TPropKey::StringKey(name) => self.new_lit_type(&Lit::String(name.to_owned())),
+            TPropKey::NumberKey(name) => self.new_lit_type(&Lit::Number(name.to_owned())),
+            TPropKey::SymbolKey(TSymbolKey { id, .. }) => {
+                self.from_type_kind(TypeKind::UniqueSymbol(UniqueSymbol { id: *id }))
+            }
+        }
     }
 
     pub fn new_type_ref(&mut self, name: &str, scheme: Option<Scheme>, types: &[Index]) -> Index {
-        self.arena.insert(Type::from(TypeKind::TypeRef(TypeRef {
+        self.from_type_kind(TypeKind::TypeRef(TypeRef {
             name: name.to_string(),
             scheme,
             type_args: types.to_vec(),
-        })))
+        }))
     }
 
     pub fn new_array_type(&mut self, t: Index) -> Index {
-        self.arena.insert(Type::from(TypeKind::Array(Array { t })))
+        self.from_type_kind(TypeKind::Array(Array { t, readonly: false }))
+    }
+
+    // Returns a copy of `t` marked as `readonly` if it's an array or tuple,
+    // otherwise `t` is returned as is.
+    pub fn new_readonly_type(&mut self, t: Index) -> Index {
+        let kind = match &self.arena[t].kind {
+            TypeKind::Array(array) => TypeKind::Array(Array {
+                readonly: true,
+                ..array.to_owned()
+            }),
+            TypeKind::Tuple(tuple) => TypeKind::Tuple(Tuple {
+                readonly: true,
+                ..tuple.to_owned()
+            }),
+            _ => return t,
+        };
+        self.from_type_kind(kind)
     }
 
     pub fn new_keyword(&mut self, keyword: Keyword) -> Index {
-        self.arena.insert(Type::from(TypeKind::Keyword(keyword)))
+        self.from_type_kind(TypeKind::Keyword(keyword))
     }
 
     pub fn new_primitive(&mut self, primitive: Primitive) -> Index {
-        self.arena
-            .insert(Type::from(TypeKind::Primitive(primitive)))
+        self.from_type_kind(TypeKind::Primitive(primitive))
     }
 
     pub fn new_rest_type(&mut self, t: Index) -> Index {
-        self.arena
-            .insert(Type::from(TypeKind::Rest(Rest { arg: t })))
+        self.from_type_kind(TypeKind::Rest(Rest { arg: t }))
     }
 
     pub fn new_lit_type(&mut self, lit: &Lit) -> Index {
-        self.arena
-            .insert(Type::from(TypeKind::Literal(lit.clone())))
+        self.from_type_kind(TypeKind::Literal(lit.clone()))
     }
 
     pub fn new_keyof_type(&mut self, t: Index) -> Index {
-        self.arena.insert(Type::from(TypeKind::KeyOf(KeyOf { t })))
+        self.from_type_kind(TypeKind::KeyOf(KeyOf { t }))
     }
 
     pub fn new_indexed_access_type(&mut self, obj: Index, index: Index) -> Index {
-        self.arena
-            .insert(Type::from(TypeKind::IndexedAccess(IndexedAccess {
-                obj,
-                index,
-            })))
+        self.from_type_kind(TypeKind::IndexedAccess(IndexedAccess { obj, index }))
     }
 
     pub fn new_conditional_type(
@@ -971,26 +763,30 @@ impl Checker {
         true_type: Index,
         false_type: Index,
     ) -> Index {
-        self.arena
-            .insert(Type::from(TypeKind::Conditional(Conditional {
-                check,
-                extends,
-                true_type,
-                false_type,
-            })))
+        self.from_type_kind(TypeKind::Conditional(Conditional {
+            check,
+            extends,
+            true_type,
+            false_type,
+        }))
     }
 
     pub fn new_infer_type(&mut self, name: &str) -> Index {
-        self.arena.insert(Type::from(TypeKind::Infer(Infer {
+        self.from_type_kind(TypeKind::Infer(Infer {
             name: name.to_string(),
-        })))
+        }))
     }
 
     pub fn new_wildcard_type(&mut self) -> Index {
-        self.arena.insert(Type::from(TypeKind::Wildcard))
+        self.from_type_kind(TypeKind::Wildcard)
     }
 
+    // All of the constructors above go through here so that types always
+    // point to the node that was being inferred when they were created.
     pub fn from_type_kind(&mut self, kind: TypeKind) -> Index {
-        self.arena.insert(Type::from(kind))
+        self.arena.insert(Type {
+            kind,
+            provenance: self.current_span().map(Provenance::Span),
+        })
     }
 }
@@ -12,6 +12,7 @@ use crate::diagnostic::Diagnostic;
 use crate::infer::check_mutability;
 use crate::type_error::TypeError;
 use crate::types::*;
+use crate::util::{find_similar_name, is_int_literal};
 
 impl Checker {
     /// Unify the two types t1 and t2.
@@ -27,10 +28,24 @@ impl Checker {
     ///
     /// Raises:
     ///     InferenceError: Raised if the types cannot be unified.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "trace",
+            skip_all,
+            fields(t1 = %self.print_type(&t1), t2 = %self.print_type(&t2)),
+        )
+    )]
     pub fn unify(&mut self, ctx: &Context, t1: Index, t2: Index) -> Result<(), TypeError> {
         let a = self.prune(t1);
         let b = self.prune(t2);
 
+        self.explain(|checker| {
+            let a = checker.print_type(&a);
+            let b = checker.print_type(&b);
+            format!("unify {a} with {b}")
+        });
+
         // TODO: only expand if unification fails since it's expensive
 
         let a_t = self.arena[a].clone();
@@ -44,6 +59,21 @@ impl Checker {
             (TypeKind::Wildcard, _) => Ok(()),
             (_, TypeKind::Wildcard) => Ok(()),
 
+            // `never` is assignable to all types
+            (TypeKind::Keyword(Keyword::Never), _) => Ok(()),
+
+            // Recursive types are unfolded one level at a time.
+            (TypeKind::Mu(_), _) | (_, TypeKind::Mu(_)) => {
+                if !self.mu_assumptions.insert((a, b)) {
+                    return Ok(());
+                }
+                let a_unfolded = self.unfold_mu(a);
+                let b_unfolded = self.unfold_mu(b);
+                let result = self.unify(ctx, a_unfolded, b_unfolded);
+                self.mu_assumptions.remove(&(a, b));
+                result
+            }
+
             (TypeKind::Keyword(kw1), TypeKind::Keyword(kw2)) => {
                 if kw1 == kw2 {
                     Ok(())
@@ -88,46 +118,7 @@ impl Checker {
                 })
             }
             (TypeKind::Tuple(tuple1), TypeKind::Tuple(tuple2)) => {
-                'outer: {
-                    if tuple1.types.len() < tuple2.types.len() {
-                        // If there's a rest pattern in tuple1, then it can unify
-                        // with the reamining elements of tuple2.
-                        if let Some(last) = tuple1.types.last() {
-                            if let TypeKind::Rest(_) = self.arena[*last].kind {
-                                break 'outer;
-                            }
-                        }
-
-                        return Err(TypeError {
-                            message: format!(
-                                "Expected tuple of length {}, got tuple of length {}",
-                                tuple2.types.len(),
-                                tuple1.types.len()
-                            ),
-                        });
-                    }
-                }
-
-                for (i, (p, q)) in tuple1.types.iter().zip(tuple2.types.iter()).enumerate() {
-                    // let q_t = arena[*q];
-                    match (&self.arena[*p].kind, &self.arena[*q].kind) {
-                        (TypeKind::Rest(_), TypeKind::Rest(_)) => {
-                            return Err(TypeError {
-                                message: "Can't unify two rest elements".to_string(),
-                            })
-                        }
-                        (TypeKind::Rest(_), _) => {
-                            let rest_q = self.new_tuple_type(&tuple2.types[i..]);
-                            self.unify(ctx, *p, rest_q)?;
-                        }
-                        (_, TypeKind::Rest(_)) => {
-                            let rest_p = self.new_tuple_type(&tuple1.types[i..]);
-                            self.unify(ctx, rest_p, *q)?;
-                        }
-                        (_, _) => self.unify(ctx, *p, *q)?,
-                    }
-                }
-                Ok(())
+                self.unify_tuples(ctx, tuple1, tuple2)
             }
             (TypeKind::Tuple(tuple), TypeKind::Array(array)) => {
                 // TODO: handle rest elements in the tuple
@@ -167,9 +158,11 @@ impl Checker {
             (TypeKind::Array(array_a), TypeKind::Array(array_b)) => {
                 self.unify(ctx, array_a.t, array_b.t)
             }
-            (TypeKind::TypeRef(con_a), TypeKind::TypeRef(con_b)) => {
+            // Type refs with different names may still be aliases for the same
+            // type so we leave those to the expansion fallback below.
+            (TypeKind::TypeRef(con_a), TypeKind::TypeRef(con_b)) if con_a.name == con_b.name => {
                 // TODO: support type constructors with optional and default type params
-                if con_a.name != con_b.name || con_a.type_args.len() != con_b.type_args.len() {
+                if con_a.type_args.len() != con_b.type_args.len() {
                     return Err(TypeError {
                         message: format!(
                             "type mismatch: {} != {}",
@@ -332,6 +325,7 @@ impl Checker {
                 let equal = match (&lit1, &lit2) {
                     (Lit::Boolean(value1), Lit::Boolean(value2)) => value1 == value2,
                     (Lit::Number(value1), Lit::Number(value2)) => value1 == value2,
+                    (Lit::BigInt(value1), Lit::BigInt(value2)) => value1 == value2,
                     (Lit::String(value1), Lit::String(value2)) => value1 == value2,
                     (Lit::Undefined, Lit::Undefined) => true,
                     (Lit::Null, Lit::Null) => true,
@@ -349,10 +343,23 @@ impl Checker {
                 Ok(())
             }
             (TypeKind::Literal(Lit::Number(_)), TypeKind::Primitive(Primitive::Number)) => Ok(()),
+            (TypeKind::Literal(Lit::Number(value)), TypeKind::Primitive(Primitive::Int))
+                if is_int_literal(value) =>
+            {
+                Ok(())
+            }
+            (TypeKind::Literal(Lit::Number(_)), TypeKind::Primitive(Primitive::Float)) => Ok(()),
+            (TypeKind::Literal(Lit::BigInt(_)), TypeKind::Primitive(Primitive::BigInt)) => Ok(()),
             (TypeKind::Literal(Lit::String(_)), TypeKind::Primitive(Primitive::String)) => Ok(()),
             (TypeKind::Literal(Lit::Boolean(_)), TypeKind::Primitive(Primitive::Boolean)) => Ok(()),
+            (TypeKind::UniqueSymbol(sym1), TypeKind::UniqueSymbol(sym2)) if sym1 == sym2 => Ok(()),
+            (TypeKind::UniqueSymbol(_), TypeKind::Primitive(Primitive::Symbol)) => Ok(()),
             (TypeKind::Primitive(prim1), TypeKind::Primitive(prim2)) => match (prim1, prim2) {
                 (Primitive::Number, Primitive::Number) => Ok(()),
+                // Every `int` is also a valid `float`.
+                (Primitive::Int, Primitive::Int | Primitive::Float | Primitive::Number) => Ok(()),
+                (Primitive::Float, Primitive::Float | Primitive::Number) => Ok(()),
+                (Primitive::BigInt, Primitive::BigInt) => Ok(()),
                 (Primitive::String, Primitive::String) => Ok(()),
                 (Primitive::Boolean, Primitive::Boolean) => Ok(()),
                 (Primitive::Symbol, Primitive::Symbol) => Ok(()),
@@ -413,7 +420,7 @@ impl Checker {
                             Some((
                                 name.to_string(),
                                 TProp {
-                                    name: TPropKey::StringKey(name.to_string()),
+                                    name: name.to_owned(),
                                     t: func_type,
                                     optional: false,
                                     readonly: false,
@@ -454,7 +461,7 @@ impl Checker {
                             Some((
                                 name.to_string(),
                                 TProp {
-                                    name: TPropKey::StringKey(name.to_string()),
+                                    name: name.to_owned(),
                                     t: func_type,
                                     optional: false,
                                     readonly: false,
@@ -478,6 +485,36 @@ impl Checker {
                     })
                     .collect();
 
+                // Fresh object literals can't have properties that don't exist
+                // in object2 unless object2 has an indexer that covers them.
+                if object1.is_fresh
+                    && !object2
+                        .elems
+                        .iter()
+                        .any(|elem| matches!(elem, TObjElem::Mapped(_)))
+                {
+                    for elem in &object1.elems {
+                        let name = match elem {
+                            TObjElem::Method(TMethod { name, .. }) => name,
+                            TObjElem::Getter(TGetter { name, .. }) => name,
+                            TObjElem::Setter(TSetter { name, .. }) => name,
+                            TObjElem::Prop(TProp { name, .. }) => name,
+                            _ => continue,
+                        };
+                        let name = name.to_string();
+                        if named_props_2.contains_key(&name) {
+                            continue;
+                        }
+
+                        let mut message =
+                            format!("'{}' does not exist in {}", name, self.print_type(&b),);
+                        if let Some(similar) = find_similar_name(&name, named_props_2.keys()) {
+                            message.push_str(&format!(", did you mean '{similar}'?"));
+                        }
+                        return Err(TypeError { message });
+                    }
+                }
+
                 // object1 must have at least as the same named elements as object2
                 // TODO: handle the case where object1 has an indexer that covers
                 // some of the named elements of object2
@@ -527,10 +564,33 @@ impl Checker {
                     1 => {
                         match mapped_1.len() {
                             0 => {
-                                for (_, prop_1) in named_props_1 {
+                                let mapped = mapped_2[0];
+                                let mut mapping: HashMap<String, Index> = HashMap::new();
+                                mapping.insert(mapped.target.to_owned(), mapped.source);
+                                let mapped_key = self.instantiate_type(&mapped.key, &mapping);
+
+                                // Each property must have a key that's covered by
+                                // the indexer and a value that matches the indexer's
+                                // value for that key.
+                                for (name, prop_1) in named_props_1 {
+                                    let key = self.new_prop_key_type(&prop_1.name);
+                                    if self.unify(ctx, key, mapped_key).is_err() {
+                                        return Err(TypeError {
+                                            message: format!(
+                                                "'{}' is not a valid key for {}",
+                                                name,
+                                                self.print_type(&b),
+                                            ),
+                                        });
+                                    }
+
+                                    let mut mapping: HashMap<String, Index> = HashMap::new();
+                                    mapping.insert(mapped.target.to_owned(), key);
+                                    let value = self.instantiate_type(&mapped.value, &mapping);
+
                                     let undefined = self.new_lit_type(&Lit::Undefined);
                                     let t1 = prop_1.get_type(self);
-                                    let t2 = self.new_union_type(&[mapped_2[0].value, undefined]);
+                                    let t2 = self.new_union_type(&[value, undefined]);
                                     self.unify(ctx, t1, t2)?;
                                 }
                             }
@@ -674,6 +734,10 @@ impl Checker {
                 }
             }
             _ => {
+                if let Some((alias_a, alias_b)) = self.find_common_alias(ctx, a, b) {
+                    return self.unify(ctx, alias_a, alias_b);
+                }
+
                 let expanded_a = self.expand(ctx, a)?;
                 let expanded_b = self.expand(ctx, b)?;
 
@@ -713,6 +777,114 @@ impl Checker {
         }
     }
 
+    // A rest element, e.g. `...T` in `[...T, number]`, matches the elements of
+    // the other tuple that aren't matched by the elements before and after it.
+    fn unify_tuples(
+        &mut self,
+        ctx: &Context,
+        tuple1: &Tuple,
+        tuple2: &Tuple,
+    ) -> Result<(), TypeError> {
+        let types1 = &tuple1.types;
+        let types2 = &tuple2.types;
+        let is_rest =
+            |checker: &Self, t: &Index| matches!(checker.arena[*t].kind, TypeKind::Rest(_));
+        let rest1 = types1.iter().position(|t| is_rest(self, t));
+        let rest2 = types2.iter().position(|t| is_rest(self, t));
+
+        match (rest1, rest2) {
+            (Some(i), Some(j)) => {
+                if i != j || types1.len() != types2.len() {
+                    return Err(TypeError {
+                        message: "Can't unify two rest elements".to_string(),
+                    });
+                }
+                for (p, q) in types1.iter().zip(types2.iter()) {
+                    match (&self.arena[*p].kind, &self.arena[*q].kind) {
+                        (TypeKind::Rest(rest1), TypeKind::Rest(rest2)) => {
+                            self.unify(ctx, rest1.arg, rest2.arg)?
+                        }
+                        (_, _) => self.unify(ctx, *p, *q)?,
+                    }
+                }
+            }
+            (Some(i), None) => {
+                let after = types1.len() - i - 1;
+                if types2.len() < i + after {
+                    // Tuples with extra elements are subtypes of shorter
+                    // tuples.
+                    if after == 0 {
+                        for (p, q) in types1.iter().zip(types2.iter()) {
+                            self.unify(ctx, *p, *q)?;
+                        }
+                        return Ok(());
+                    }
+                    return Err(TypeError {
+                        message: format!(
+                            "Expected tuple of length {}, got tuple of length {}",
+                            types2.len(),
+                            types1.len()
+                        ),
+                    });
+                }
+                let end = types2.len() - after;
+                for (p, q) in types1[..i].iter().zip(types2[..i].iter()) {
+                    self.unify(ctx, *p, *q)?;
+                }
+                let rest_q = self.new_tuple_type(&types2[i..end]);
+                self.unify(ctx, types1[i], rest_q)?;
+                for (p, q) in types1[i + 1..].iter().zip(types2[end..].iter()) {
+                    self.unify(ctx, *p, *q)?;
+                }
+            }
+            (None, Some(j)) => {
+                let after = types2.len() - j - 1;
+                if types1.len() < j + after {
+                    return Err(TypeError {
+                        message: format!(
+                            "Expected tuple of at least length {}, got tuple of length {}",
+                            j + after,
+                            types1.len()
+                        ),
+                    });
+                }
+                let end = types1.len() - after;
+                for (p, q) in types1[..j].iter().zip(types2[..j].iter()) {
+                    self.unify(ctx, *p, *q)?;
+                }
+                let rest_p = self.new_tuple_type(&types1[j..end]);
+                self.unify(ctx, rest_p, types2[j])?;
+                for (p, q) in types1[end..].iter().zip(types2[j + 1..].iter()) {
+                    self.unify(ctx, *p, *q)?;
+                }
+            }
+            (None, None) => {
+                if types1.len() < types2.len() {
+                    return Err(TypeError {
+                        message: format!(
+                            "Expected tuple of length {}, got tuple of length {}",
+                            types2.len(),
+                            types1.len()
+                        ),
+                    });
+                }
+                // Labels aren't part of a tuple's type, but they're included
+                // in error messages to help identify the mismatched element.
+                let labels = tuple2.labels.as_ref().or(tuple1.labels.as_ref());
+                for (i, (p, q)) in types1.iter().zip(types2.iter()).enumerate() {
+                    self.unify(ctx, *p, *q).map_err(|err| match labels {
+                        Some(labels) => TypeError {
+                            message: format!("{} for tuple element `{}`", err.message, labels[i]),
+                        },
+                        None => err,
+                    })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     // This function unifies and infers the return type of a function call.
     pub fn unify_call(
         &mut self,
@@ -750,7 +922,16 @@ impl Checker {
                         Ok(param)
                     })
                     .collect::<Result<Vec<_>, _>>()?;
-                let call_type = self.new_func_type(&arg_types, ret_type, &None, None);
+                let call_type = match newable {
+                    // e.g. a class referencing itself from within its own body
+                    true => self.new_object_type(&[TObjElem::Constructor(Function {
+                        params: arg_types,
+                        ret: ret_type,
+                        type_params: None,
+                        throws: None,
+                    })]),
+                    false => self.new_func_type(&arg_types, ret_type, &None, None),
+                };
                 self.bind(ctx, b, call_type)?
             }
             TypeKind::Union(Union { types }) => {
@@ -848,12 +1029,17 @@ impl Checker {
                     message: format!("Primitive {primitive:#?} is not callable"),
                 });
             }
+            TypeKind::UniqueSymbol(_) => {
+                return Err(TypeError {
+                    message: "unique symbol is not callable".to_string(),
+                });
+            }
             TypeKind::Keyword(keyword) => {
                 return Err(TypeError {
                     message: format!("{keyword} is not callable"),
                 })
             }
-            TypeKind::Object(Object { elems }) => {
+            TypeKind::Object(Object { elems, .. }) => {
                 let mut newables = vec![];
                 let mut callables = vec![];
 
@@ -951,10 +1137,21 @@ impl Checker {
                 left: _,
                 right: _,
             }) => todo!(),
+            TypeKind::Mu(_) => {
+                let t = self.unfold_mu(b);
+                return self.unify_call(ctx, args, type_args, newable, t);
+            }
+            TypeKind::MuVar(_) => {
+                return Err(TypeError {
+                    message: format!("{} is not callable", self.print_type(&b)),
+                });
+            }
         }
 
         // We need to prune the return type, because it might be a type variable.
-        let ret_type = self.prune(ret_type);
+        // Spreads of tuples in the return type, e.g. `[...T, U]`, are flattened
+        // now that `T` is known.
+        let ret_type = self.flatten_tuple(ret_type);
 
         Ok((ret_type, maybe_throws_type))
     }
@@ -967,10 +1164,30 @@ impl Checker {
         ret_type: Index,
         func: Function,
     ) -> Result<Option<Index>, TypeError> {
-        let func = if func.type_params.is_some() {
-            self.instantiate_func(&func, type_args)?
-        } else {
-            func
+        // Type args that aren't passed explicitly are inferred from the args
+        // so the type args' constraints are checked after unifying the args.
+        let (func, type_params, type_args) = match func.type_params.to_owned() {
+            Some(type_params) => {
+                let type_args = match type_args {
+                    Some(type_args) => type_args.to_vec(),
+                    // Type vars can't be applied to type args so type args
+                    // for type params that are type constructors can't be
+                    // inferred.
+                    None if type_params.iter().any(|tp| tp.arity > 0) => {
+                        return Err(TypeError {
+                            message: "Type args must be passed explicitly to functions with type constructor params".to_string(),
+                        });
+                    }
+                    None => type_params
+                        .iter()
+                        .map(|_| self.new_type_var(None))
+                        .collect(),
+                };
+                let func = self.instantiate_func(&func, Some(&type_args))?;
+                self.check_type_arg_kinds(ctx, &type_params, &type_args)?;
+                (func, type_params, type_args)
+            }
+            None => (func, vec![], vec![]),
         };
 
         let func_params = match func.params.get(0) {
@@ -1023,6 +1240,19 @@ impl Checker {
                 }
             }
 
+            // Passing a value to a `never` param asserts that every variant
+            // of the value has been handled, e.g. `_ => absurd(x)`.
+            let (param_t, arg_t) = (self.prune(param.t), self.prune(*p));
+            if matches!(self.arena[param_t].kind, TypeKind::Keyword(Keyword::Never))
+                && !matches!(
+                    self.arena[arg_t].kind,
+                    TypeKind::Keyword(Keyword::Never) | TypeKind::TypeVar(_)
+                )
+            {
+                self.report_unhandled_variants(ctx, arg_t, arg.span);
+                continue;
+            }
+
             match check_mutability(ctx, &param.pattern, arg)? {
                 true => self.unify_mut(ctx, *p, param.t)?,
                 false => match self.unify(ctx, *p, param.t) {
@@ -1083,9 +1313,14 @@ impl Checker {
                 code: 1000,
                 message: "Function arguments are incorrect".to_string(),
                 reasons,
+                span: self.current_span(),
+                suggestion: None,
+                related: vec![],
             });
         }
 
+        self.check_type_arg_constraints(ctx, &type_params, &type_args);
+
         self.unify(ctx, ret_type, func.ret)?;
 
         let mut maybe_throws_type = None;
@@ -1104,6 +1339,66 @@ impl Checker {
         Ok(maybe_throws_type)
     }
 
+    // Reports each type arg that isn't assignable to its type param's
+    // constraint.  The diagnostic points at the type arg, or whatever it was
+    // inferred from, and notes where the constraint was declared.
+    pub(crate) fn check_type_arg_constraints(
+        &mut self,
+        ctx: &Context,
+        type_params: &[TypeParam],
+        type_args: &[Index],
+    ) {
+        // Constraints can reference other type params, e.g. `<K: keyof T>`.
+        let mapping: HashMap<String, Index> = type_params
+            .iter()
+            .zip(type_args.iter())
+            .map(|(param, arg)| (param.name.to_owned(), *arg))
+            .collect();
+
+        for (param, arg) in type_params.iter().zip(type_args.iter()) {
+            let constraint = match param.constraint {
+                Some(constraint) => constraint,
+                None => continue,
+            };
+            let constraint_t = self.instantiate_type(&constraint, &mapping);
+            if let Err(error) = self.unify(ctx, *arg, constraint_t) {
+                let arg = self.prune(*arg);
+                let span = self.get_span(arg).or(self.current_span());
+                let related = match self.get_span(constraint) {
+                    Some(span) => vec![(
+                        span,
+                        format!(
+                            "`{}` is constrained to be a subtype of `{}`",
+                            param.name,
+                            self.print_type(&constraint)
+                        ),
+                    )],
+                    None => vec![],
+                };
+                self.current_report.diagnostics.push(Diagnostic {
+                    code: 1019,
+                    message: format!(
+                        "Type argument `{}` does not satisfy the constraint of `{}`",
+                        self.print_type(&arg),
+                        param.name
+                    ),
+                    reasons: vec![error],
+                    span,
+                    suggestion: None,
+                    related,
+                });
+            }
+        }
+    }
+
+    // Returns the span of the source that `t` came from, if it's known.
+    fn get_span(&self, t: Index) -> Option<Span> {
+        self.arena[t]
+            .provenance
+            .as_ref()
+            .and_then(|provenance| provenance.get_span())
+    }
+
     pub fn bind(&mut self, ctx: &Context, a: Index, b: Index) -> Result<(), TypeError> {
         // eprint!("bind(");
         // eprint!("{:#?}", arena[a].as_string(arena));
@@ -1122,10 +1417,13 @@ impl Checker {
                 // the types that contain 'a' and then check if the remaining.
                 if let TypeKind::Union(Union { types }) = &self.arena[b].kind.clone() {
                     let types = self.flatten_types(types);
-                    let types: Vec<Index> =
+                    let filtered_types: Vec<Index> =
                         types.iter().filter(|t| a != **t).cloned().collect_vec();
 
-                    match types.as_slice() {
+                    // If 'a' only occurs within the union's members, e.g.
+                    // `null | {child: a}`, it's bound to a recursive type.
+                    match filtered_types.as_slice() {
+                        _ if filtered_types.len() == types.len() => {}
                         [] => return Ok(()),
                         [t] => {
                             return self.bind(ctx, a, *t);
@@ -1137,6 +1435,14 @@ impl Checker {
                     }
                 }
 
+                // Data structures that contain themselves, e.g. linked lists,
+                // are given recursive types.  Functions that take themselves
+                // as params, e.g. `f` in `f(f)`, aren't.
+                if !matches!(self.arena[b].kind, TypeKind::Function(_)) {
+                    let mu = self.new_mu_type(a, b);
+                    return self.bind(ctx, a, mu);
+                }
+
                 return Err(TypeError {
                     message: format!(
                         "recursive unification - {} occurs in {}",
@@ -1153,6 +1459,7 @@ impl Checker {
                         if let Some(constraint) = avar.constraint {
                             self.unify(ctx, b, constraint)?;
                         }
+                        self.remove_subsumed_members_after_binding(a);
                     }
                     _ => {
                         unimplemented!("bind not implemented for {:#?}", t.kind);
@@ -1169,10 +1476,76 @@ impl Checker {
 
         match &a_t.kind {
             TypeKind::TypeRef(TypeRef { name, .. }) if name == "Promise" => Ok(a),
+            // Outside of the module they're declared in opaque types are only
+            // compatible with themselves.
+            TypeKind::TypeRef(tref) if self.get_opaque_scheme(ctx, tref).is_some() => Ok(a),
             _ => self.expand_type(ctx, a),
         }
     }
 
+    // Finds the first alias that both `a` and `b` expand to, e.g.
+    // `Phantom<"m">` for `PM` and `Phantom<"s">` when `type PM =
+    // Phantom<"m">`.  Its type args are unified instead of the types that it
+    // expands to, which may not use all of them, e.g. `type Phantom<U> =
+    // {value: number}`.
+    fn find_common_alias(&mut self, ctx: &Context, a: Index, b: Index) -> Option<(Index, Index)> {
+        if !matches!(
+            (&self.arena[a].kind, &self.arena[b].kind),
+            (TypeKind::TypeRef(_), TypeKind::TypeRef(_))
+        ) {
+            return None;
+        }
+
+        let aliases_a = self.expand_aliases(ctx, a);
+        let aliases_b = self.expand_aliases(ctx, b);
+        aliases_a.iter().find_map(|alias_a| {
+            aliases_b
+                .iter()
+                .find(|alias_b| self.get_alias_name(**alias_b) == self.get_alias_name(*alias_a))
+                .map(|alias_b| (*alias_a, *alias_b))
+        })
+    }
+
+    // Returns `t` followed by the aliases that it's an alias of, e.g. `PM`
+    // and `Phantom<"m">` for `type PM = Phantom<"m">`.  The number of aliases
+    // is limited since they may be recursive.
+    fn expand_aliases(&mut self, ctx: &Context, t: Index) -> Vec<Index> {
+        let mut aliases = vec![];
+        let mut t = self.prune(t);
+        while let TypeKind::TypeRef(tref) = self.arena[t].kind.clone() {
+            aliases.push(t);
+            if aliases.len() >= self.expansion_limits.max_depth || tref.name == "Promise" {
+                break;
+            }
+
+            let scheme = match tref.scheme {
+                Some(scheme) => scheme,
+                None => match ctx.get_scheme(&tref.name) {
+                    Ok(scheme) => scheme,
+                    Err(_) => break,
+                },
+            };
+            if scheme.is_type_param || self.is_opaque(&scheme) {
+                break;
+            }
+
+            let mut mapping: HashMap<String, Index> = HashMap::new();
+            for (param, arg) in scheme.type_params.iter().flatten().zip(&tref.type_args) {
+                mapping.insert(param.name.clone(), *arg);
+            }
+            t = self.instantiate_type(&scheme.t, &mapping);
+            t = self.prune(t);
+        }
+        aliases
+    }
+
+    fn get_alias_name(&self, t: Index) -> Option<&str> {
+        match &self.arena[t].kind {
+            TypeKind::TypeRef(tref) => Some(&tref.name),
+            _ => None,
+        }
+    }
+
     fn flatten_types(&mut self, types: &[Index]) -> Vec<Index> {
         let mut out_types: Vec<Index> = vec![];
         for t in types {
@@ -1202,7 +1575,9 @@ pub fn simplify_intersection(checker: &mut Checker, in_types: &[Index]) -> Index
         .collect();
 
     // The use of HashSet<Type> here is to avoid duplicate types
-    let mut props_map: DefaultHashMap<String, BTreeSet<Index>> = defaulthashmap!();
+    let mut props_map: DefaultHashMap<TPropKey, BTreeSet<Index>> = defaulthashmap!();
+    // A prop is only optional if all of the TProps with its name are optional.
+    let mut optional_map: DefaultHashMap<TPropKey, bool> = defaulthashmap!(true);
     for obj in obj_types {
         for elem in &obj.elems {
             match elem {
@@ -1215,9 +1590,10 @@ pub fn simplify_intersection(checker: &mut Checker, in_types: &[Index]) -> Index
                 TObjElem::Setter(_) => todo!(),
                 TObjElem::Prop(prop) => {
                     let key = match &prop.name {
-                        TPropKey::StringKey(key) => key.to_owned(),
-                        TPropKey::NumberKey(key) => key.to_owned(),
+                        TPropKey::NumberKey(key) => TPropKey::StringKey(key.to_owned()),
+                        key => key.to_owned(),
                     };
+                    optional_map[key.to_owned()] &= prop.optional;
                     props_map[key].insert(prop.t);
                 }
             }
@@ -1236,11 +1612,8 @@ pub fn simplify_intersection(checker: &mut Checker, in_types: &[Index]) -> Index
                 // checker.from_type_kind(TypeKind::Intersection(types))
             };
             TObjElem::Prop(TProp {
-                name: TPropKey::StringKey(name.to_owned()),
-                // TODO: determine this field from all of the TProps with
-                // the same name.  This should only be optional if all of
-                // the TProps with the current name are optional.
-                optional: false,
+                name: name.to_owned(),
+                optional: optional_map[name.to_owned()],
                 readonly: false,
                 t,
             })
@@ -1,12 +1,14 @@
 use generational_arena::{Arena, Index};
 use itertools::Itertools;
 use std::collections::{BTreeMap, HashMap};
-use std::mem::transmute;
+use std::mem::{self, transmute};
 
 use escalier_ast::Literal;
 
-use crate::checker::Checker;
+use crate::checker::{Checker, IndexAccess};
+use crate::const_eval::{eval_binary_op, to_binary_op};
 use crate::context::*;
+use crate::diagnostic::Diagnostic;
 use crate::folder::walk_index;
 use crate::folder::Folder;
 use crate::key_value_store::KeyValueStore;
@@ -34,13 +36,14 @@ impl Checker {
         // We clone here because we can't move out of a shared reference.
         // TODO: Consider using Rc<RefCell<Type>> to avoid unnecessary cloning.
         match self.arena.get(pruned_type2).unwrap().clone().kind {
-            TypeKind::TypeVar(_) => false,   // leaf node
-            TypeKind::Literal(_) => false,   // leaf node
-            TypeKind::Primitive(_) => false, // leaf node
-            TypeKind::Keyword(_) => false,   // leaf node
-            TypeKind::Infer(_) => false,     // leaf node
-            TypeKind::Wildcard => false,     // leaf node
-            TypeKind::Object(Object { elems }) => elems.iter().any(|elem| match elem {
+            TypeKind::TypeVar(_) => false,      // leaf node
+            TypeKind::Literal(_) => false,      // leaf node
+            TypeKind::Primitive(_) => false,    // leaf node
+            TypeKind::UniqueSymbol(_) => false, // leaf node
+            TypeKind::Keyword(_) => false,      // leaf node
+            TypeKind::Infer(_) => false,        // leaf node
+            TypeKind::Wildcard => false,        // leaf node
+            TypeKind::Object(Object { elems, .. }) => elems.iter().any(|elem| match elem {
                 TObjElem::Constructor(constructor) => {
                     // TODO: check constraints and default on type_params
                     let param_types: Vec<_> =
@@ -115,8 +118,8 @@ impl Checker {
             }
             TypeKind::Union(Union { types }) => self.occurs_in(v, &types),
             TypeKind::Intersection(Intersection { types }) => self.occurs_in(v, &types),
-            TypeKind::Tuple(Tuple { types }) => self.occurs_in(v, &types),
-            TypeKind::Array(Array { t }) => self.occurs_in_type(v, t),
+            TypeKind::Tuple(Tuple { types, .. }) => self.occurs_in(v, &types),
+            TypeKind::Array(Array { t, .. }) => self.occurs_in_type(v, t),
             TypeKind::TypeRef(TypeRef {
                 type_args: types, ..
             }) => self.occurs_in(v, &types),
@@ -138,6 +141,8 @@ impl Checker {
             TypeKind::Binary(BinaryT { op: _, left, right }) => {
                 self.occurs_in_type(v, left) || self.occurs_in_type(v, right)
             }
+            TypeKind::Mu(Mu { id: _, t }) => self.occurs_in_type(v, t),
+            TypeKind::MuVar(_) => false, // leaf node
         }
     }
 
@@ -203,6 +208,71 @@ impl Checker {
         value
     }
 
+    // The result of an index access whose key may not exist, e.g. `arr[i]` or
+    // `dict[key]`, includes `undefined` unless `index_access` is `Unchecked`.
+    pub(crate) fn index_access_result(&mut self, types: &[Index]) -> Index {
+        let mut types = types.to_vec();
+        if self.strictness.index_access == IndexAccess::Checked {
+            types.push(self.new_lit_type(&Literal::Undefined));
+        }
+        self.new_union_type(&types)
+    }
+
+    // Returns the types of the elements in a tuple.  The elements of rest
+    // elements, e.g. `...T[]`, are included instead of the rest elements
+    // themselves.
+    pub fn get_tuple_elem_types(&mut self, types: &[Index]) -> Vec<Index> {
+        let mut elem_types = vec![];
+        for t in types {
+            match &self.arena[*t].kind {
+                TypeKind::Rest(Rest { arg }) => {
+                    let arg = self.prune(*arg);
+                    match &self.arena[arg].kind {
+                        TypeKind::Array(Array { t, .. }) => elem_types.push(*t),
+                        TypeKind::Tuple(Tuple { types, .. }) => {
+                            let types = types.to_owned();
+                            elem_types.extend(self.get_tuple_elem_types(&types));
+                        }
+                        _ => elem_types.push(arg),
+                    }
+                }
+                _ => elem_types.push(*t),
+            }
+        }
+        elem_types
+    }
+
+    // Splices the elements of tuples in spread position into the tuple that
+    // contains them, e.g. `[...[string, boolean], number]` becomes
+    // `[string, boolean, number]`.  Spreads of arrays and unbound type
+    // variables are left as is.
+    pub fn flatten_tuple(&mut self, t: Index) -> Index {
+        let t = self.prune(t);
+        let types = match &self.arena[t].kind {
+            TypeKind::Tuple(Tuple { types, .. }) => types.to_owned(),
+            _ => return t,
+        };
+
+        let mut changed = false;
+        let mut new_types = vec![];
+        for elem in types {
+            if let TypeKind::Rest(Rest { arg }) = self.arena[elem].kind {
+                let arg = self.flatten_tuple(arg);
+                if let TypeKind::Tuple(Tuple { types, .. }) = &self.arena[arg].kind {
+                    new_types.extend(types.iter().copied());
+                    changed = true;
+                    continue;
+                }
+            }
+            new_types.push(elem);
+        }
+
+        match changed {
+            true => self.new_tuple_type(&new_types),
+            false => t,
+        }
+    }
+
     pub fn expand_alias(
         &mut self,
         ctx: &Context,
@@ -213,6 +283,46 @@ impl Checker {
         self.expand_scheme(ctx, &scheme, type_args, name)
     }
 
+    // Returns true if `scheme` is an opaque type that was declared in a
+    // different module than the one being checked.
+    pub(crate) fn is_opaque(&self, scheme: &Scheme) -> bool {
+        scheme
+            .opaque_module
+            .is_some_and(|module_id| module_id != self.module_id)
+    }
+
+    // Returns the scheme of `tref` if it's an opaque type that was declared
+    // in a different module than the one being checked.
+    pub(crate) fn get_opaque_scheme(&self, ctx: &Context, tref: &TypeRef) -> Option<Scheme> {
+        let scheme = match &tref.scheme {
+            Some(scheme) => scheme.to_owned(),
+            None => ctx.get_scheme(&tref.name).ok()?,
+        };
+        match self.is_opaque(&scheme) {
+            true => Some(scheme),
+            false => None,
+        }
+    }
+
+    // Expands `tref` as if the module that it was declared in were being
+    // checked.  This is used to look up the operators overloaded by opaque
+    // types, which can be used outside of that module, e.g. `+` on
+    // `Quantity<"m">` can still require both sides to have the same units.
+    pub(crate) fn expand_opaque_type_ref(
+        &mut self,
+        ctx: &Context,
+        tref: &TypeRef,
+        scheme: &Scheme,
+    ) -> Result<Index, TypeError> {
+        let module_id = match scheme.opaque_module {
+            Some(opaque_module) => mem::replace(&mut self.module_id, opaque_module),
+            None => self.module_id,
+        };
+        let result = self.expand_scheme(ctx, scheme, &tref.type_args, &tref.name);
+        self.module_id = module_id;
+        result
+    }
+
     pub fn expand_scheme(
         &mut self,
         ctx: &Context,
@@ -220,8 +330,30 @@ impl Checker {
         type_args: &[Index],
         name: &str,
     ) -> Result<Index, TypeError> {
+        if self.is_opaque(scheme) {
+            return Err(TypeError {
+                message: format!("{name} is opaque outside of the module it's declared in"),
+            });
+        }
+
         match &scheme.type_params {
             Some(type_params) => {
+                self.instantiation_count += 1;
+                let max_instantiations = self.expansion_limits.max_instantiations;
+                if self.instantiation_count > max_instantiations {
+                    let error = TypeError {
+                        message: format!(
+                            "Generic aliases were instantiated more than {max_instantiations} times"
+                        ),
+                    };
+                    // Only the first instantiation past the limit is reported
+                    // to avoid a diagnostic for every alias after it.
+                    if self.instantiation_count == max_instantiations + 1 {
+                        self.report_expansion_limit(name, &error);
+                    }
+                    return Err(error);
+                }
+
                 if type_params.len() != type_args.len() {
                     return Err(TypeError {
                         message: format!(
@@ -277,6 +409,18 @@ impl Checker {
                                     })
                                     .collect::<Vec<_>>();
 
+                                let max_union_size = self.expansion_limits.max_union_size;
+                                if filtered_types.len() > max_union_size {
+                                    let error = TypeError {
+                                        message: format!(
+                                            "Expanding {name} produced a union of {} types, the maximum is {max_union_size}",
+                                            filtered_types.len()
+                                        ),
+                                    };
+                                    self.report_expansion_limit(name, &error);
+                                    return Err(error);
+                                }
+
                                 let t = self.new_union_type(&filtered_types);
                                 return self.expand_type(ctx, t);
                             }
@@ -284,9 +428,8 @@ impl Checker {
                     }
                 }
 
-                // Contraints can reference other type params so we need make
-                // sure that definitions for each type param are in scope where
-                // each type param is defined to be the corresponding type arg.
+                // The type args' constraints are checked where the type args
+                // are written or inferred so that they're only reported once.
                 let mut sig_ctx = ctx.clone();
                 for (param, arg) in type_params.iter().zip(type_args.iter()) {
                     sig_ctx.schemes.insert(
@@ -295,15 +438,13 @@ impl Checker {
                             type_params: None,
                             t: *arg,
                             is_type_param: false,
+                            opaque_module: None,
                         },
                     );
                 }
 
                 let mut mapping: HashMap<String, Index> = HashMap::new();
                 for (param, arg) in type_params.iter().zip(type_args.iter()) {
-                    if let Some(constraint) = param.constraint {
-                        self.unify(&sig_ctx, *arg, constraint)?;
-                    }
                     mapping.insert(param.name.clone(), arg.to_owned());
                 }
 
@@ -323,11 +464,15 @@ impl Checker {
         }
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "trace", skip_all, fields(t = %self.print_type(&t)))
+    )]
     pub fn expand_type(&mut self, ctx: &Context, t: Index) -> Result<Index, TypeError> {
         let t = self.prune(t);
 
         // It's okay to clone here because we aren't mutating the type
-        let t = match &self.arena[t].clone().kind {
+        let expanded = match &self.arena[t].clone().kind {
             TypeKind::KeyOf(KeyOf { t }) => self.expand_keyof(ctx, *t)?,
             // TODO: Readonly<T> should remove mutating methods
             // TODO: IndexedAccess["key"] should remove the `self` param from methods
@@ -335,21 +480,96 @@ impl Checker {
                 let is_mut = true;
                 self.get_computed_member(ctx, *obj, *index, is_mut)?
             }
-            TypeKind::Conditional(conditional) => self.expand_conditional(ctx, conditional)?,
-            TypeKind::TypeRef(TypeRef {
-                name,
-                scheme,
-                type_args,
-            }) => match scheme {
-                Some(scheme) => self.expand_scheme(ctx, scheme, type_args, name)?,
-                None => self.expand_alias(ctx, name, type_args)?,
-            },
+            TypeKind::Conditional(conditional) => {
+                self.expand_cached(t, |checker| checker.expand_conditional(ctx, conditional))?
+            }
+            TypeKind::TypeRef(tref) => {
+                self.expand_cached(t, |checker| checker.expand_type_ref(ctx, t, tref))?
+            }
             TypeKind::Binary(binary) => self.expand_binary(ctx, binary)?,
-            TypeKind::Object(object) => return self.expand_object(ctx, object),
+            TypeKind::Mu(_) => self.unfold_mu(t),
+            // Only objects containing mapped types need to be expanded.
+            TypeKind::Object(object)
+                if object
+                    .elems
+                    .iter()
+                    .any(|elem| matches!(elem, TObjElem::Mapped(_))) =>
+            {
+                let expanded =
+                    self.expand_cached(t, |checker| checker.expand_object(ctx, object))?;
+                self.explain_expansion(t, expanded);
+                return Ok(expanded);
+            }
             _ => return Ok(t), // Early return to avoid infinite loop
         };
 
-        self.expand_type(ctx, t)
+        self.explain_expansion(t, expanded);
+        self.expand_type(ctx, expanded)
+    }
+
+    fn explain_expansion(&mut self, t: Index, expanded: Index) {
+        self.explain(|checker| {
+            let t = checker.print_type(&t);
+            let expanded = checker.print_type(&expanded);
+            format!("expand {t} to {expanded}")
+        });
+    }
+
+    fn expand_type_ref(
+        &mut self,
+        ctx: &Context,
+        t: Index,
+        tref: &TypeRef,
+    ) -> Result<Index, TypeError> {
+        let TypeRef {
+            name,
+            scheme,
+            type_args,
+        } = tref;
+
+        let max_depth = self.expansion_limits.max_depth;
+        if self.expansion_spans.len() >= max_depth {
+            let error = TypeError {
+                message: format!("Expanding {name} exceeded the maximum depth of {max_depth}"),
+            };
+            self.report_expansion_limit(name, &error);
+            return Err(error);
+        }
+
+        let span = self.arena[t]
+            .provenance
+            .as_ref()
+            .and_then(|provenance| provenance.get_span());
+        self.expansion_spans.push(span);
+        let result = match scheme {
+            Some(scheme) => self.expand_scheme(ctx, scheme, type_args, name),
+            None => self.expand_alias(ctx, name, type_args),
+        };
+        self.expansion_spans.pop();
+
+        result
+    }
+
+    // Reports that expanding `name` exceeded one of the `ExpansionLimits`.
+    // The error is also returned by the caller, but it's reported here since
+    // some callers, e.g. unifying with each member of a union, discard errors.
+    // The diagnostic points at the innermost reference being expanded that
+    // has a span, or the node being inferred if none of them do.
+    fn report_expansion_limit(&mut self, name: &str, error: &TypeError) {
+        let span = self
+            .expansion_spans
+            .iter()
+            .rev()
+            .find_map(|span| *span)
+            .or(self.current_span());
+        self.current_report.diagnostics.push(Diagnostic {
+            code: 1007,
+            message: format!("Type `{name}` is too complex to expand"),
+            reasons: vec![error.to_owned()],
+            span,
+            suggestion: None,
+            related: vec![],
+        });
     }
 
     // Expands `keyof` types into one of the followwing:
@@ -362,9 +582,10 @@ impl Checker {
         // We're not mutating `kind` so this should be safe.
         let obj_kind: &TypeKind = unsafe { transmute(&self.arena[obj].kind) };
         match obj_kind {
-            TypeKind::Object(Object { elems }) => {
+            TypeKind::Object(Object { elems, .. }) => {
                 let mut string_keys: Vec<Index> = Vec::new();
                 let mut number_keys: Vec<Index> = Vec::new();
+                let mut symbol_keys: Vec<Index> = Vec::new();
                 let mut maybe_string: Option<Index> = None;
                 let mut maybe_number: Option<Index> = None;
                 let mut maybe_symbol: Option<Index> = None;
@@ -398,6 +619,11 @@ impl Checker {
                                 number_keys
                                     .push(self.new_lit_type(&Literal::Number(name.to_owned())));
                             }
+                            TPropKey::SymbolKey(TSymbolKey { id, .. }) => {
+                                symbol_keys.push(self.from_type_kind(TypeKind::UniqueSymbol(
+                                    UniqueSymbol { id: *id },
+                                )));
+                            }
                         },
                         TObjElem::Getter(TGetter { name, .. }) => match name {
                             TPropKey::StringKey(name) => {
@@ -408,6 +634,11 @@ impl Checker {
                                 number_keys
                                     .push(self.new_lit_type(&Literal::Number(name.to_owned())));
                             }
+                            TPropKey::SymbolKey(TSymbolKey { id, .. }) => {
+                                symbol_keys.push(self.from_type_kind(TypeKind::UniqueSymbol(
+                                    UniqueSymbol { id: *id },
+                                )));
+                            }
                         },
                         TObjElem::Setter(TSetter { name, .. }) => match name {
                             TPropKey::StringKey(name) => {
@@ -418,6 +649,11 @@ impl Checker {
                                 number_keys
                                     .push(self.new_lit_type(&Literal::Number(name.to_owned())));
                             }
+                            TPropKey::SymbolKey(TSymbolKey { id, .. }) => {
+                                symbol_keys.push(self.from_type_kind(TypeKind::UniqueSymbol(
+                                    UniqueSymbol { id: *id },
+                                )));
+                            }
                         },
                         TObjElem::Prop(TProp { name, .. }) => match name {
                             TPropKey::StringKey(name) => {
@@ -428,6 +664,11 @@ impl Checker {
                                 number_keys
                                     .push(self.new_lit_type(&Literal::Number(name.to_owned())));
                             }
+                            TPropKey::SymbolKey(TSymbolKey { id, .. }) => {
+                                symbol_keys.push(self.from_type_kind(TypeKind::UniqueSymbol(
+                                    UniqueSymbol { id: *id },
+                                )));
+                            }
                         },
                     }
                 }
@@ -444,8 +685,9 @@ impl Checker {
                     None => all_keys.append(&mut string_keys),
                 }
 
-                if let Some(symbol) = maybe_symbol {
-                    all_keys.push(symbol);
+                match maybe_symbol {
+                    Some(symbol) => all_keys.push(symbol),
+                    None => all_keys.append(&mut symbol_keys),
                 }
 
                 Ok(self.new_union_type(&all_keys))
@@ -617,27 +859,21 @@ impl Checker {
     }
 
     pub fn expand_binary(&mut self, _ctx: &Context, binary: &BinaryT) -> Result<Index, TypeError> {
-        let t = match (
+        let const_result = match (
             &self.arena[binary.left].kind,
             &self.arena[binary.right].kind,
         ) {
-            (
-                TypeKind::Literal(Literal::Number(left)),
-                TypeKind::Literal(Literal::Number(right)),
-            ) => {
-                let left = left.parse::<f64>().unwrap();
-                let right = right.parse::<f64>().unwrap();
-
-                let result = match binary.op {
-                    TBinaryOp::Add => left + right,
-                    TBinaryOp::Sub => left - right,
-                    TBinaryOp::Mul => left * right,
-                    TBinaryOp::Div => left / right,
-                    TBinaryOp::Mod => left % right,
-                };
-
-                self.new_lit_type(&Literal::Number(result.to_string()))
+            (TypeKind::Literal(left), TypeKind::Literal(right)) => {
+                eval_binary_op(&to_binary_op(&binary.op), left, right)
             }
+            _ => None,
+        };
+
+        let t = match (
+            &self.arena[binary.left].kind,
+            &self.arena[binary.right].kind,
+        ) {
+            _ if const_result.is_some() => self.new_lit_type(&const_result.unwrap()),
             (TypeKind::Literal(Literal::Number(_)), TypeKind::Primitive(Primitive::Number)) => {
                 self.new_primitive(Primitive::Number)
             }
@@ -710,7 +946,7 @@ impl Checker {
 
                                     let obj = self.expand_type(ctx, *obj)?;
 
-                                    if let TypeKind::Object(Object { elems }) =
+                                    if let TypeKind::Object(Object { elems, .. }) =
                                         &self.arena[obj].kind
                                     {
                                         for elem in elems {
@@ -776,10 +1012,10 @@ impl Checker {
             }
         }
 
-        Ok(self.arena.insert(Type {
-            kind: TypeKind::Object(Object { elems: new_elems }),
-            provenance: None, // TODO
-        }))
+        Ok(self.from_type_kind(TypeKind::Object(Object {
+            elems: new_elems,
+            is_fresh: false,
+        })))
     }
 
     pub fn get_computed_member(
@@ -797,11 +1033,11 @@ impl Checker {
         match &obj_type.kind {
             TypeKind::Object(_) => self.get_prop_value(ctx, obj_idx, key_idx, is_mut),
             TypeKind::Array(array) => {
+                self.check_index(key_idx)?;
                 match &key_type.kind {
                     TypeKind::Literal(Literal::Number(_)) => {
                         // TODO: update AST with the inferred type
-                        let types = vec![array.t, self.new_lit_type(&Literal::Undefined)];
-                        Ok(self.new_union_type(&types))
+                        Ok(self.index_access_result(&[array.t]))
                     }
                     TypeKind::Literal(Literal::String(_)) => {
                         // TODO: look up methods on the `Array` interface
@@ -809,9 +1045,8 @@ impl Checker {
                         // to the union of all types in the tuple
                         self.get_prop_value(ctx, obj_idx, key_idx, is_mut)
                     }
-                    TypeKind::Primitive(Primitive::Number) => {
-                        let types = vec![array.t, self.new_lit_type(&Literal::Undefined)];
-                        Ok(self.new_union_type(&types))
+                    TypeKind::Primitive(Primitive::Number | Primitive::Int) => {
+                        Ok(self.index_access_result(&[array.t]))
                     }
                     _ => Err(TypeError {
                         message: "Can only access tuple properties with a number".to_string(),
@@ -819,11 +1054,25 @@ impl Checker {
                 }
             }
             TypeKind::Tuple(tuple) => {
+                self.check_index(key_idx)?;
                 match &key_type.kind {
                     TypeKind::Literal(Literal::Number(value)) => {
                         let index: usize = str::parse(value).map_err(|_| TypeError {
                             message: format!("{} isn't a valid index", value),
                         })?;
+                        // Elements at or after a rest element, e.g. `...T[]` in
+                        // `[T, ...T[]]`, may not exist.
+                        let rest = tuple
+                            .types
+                            .iter()
+                            .position(|t| matches!(self.arena[*t].kind, TypeKind::Rest(_)));
+                        if let Some(rest) = rest {
+                            if index < rest {
+                                return Ok(tuple.types[index]);
+                            }
+                            let types = self.get_tuple_elem_types(&tuple.types[rest..]);
+                            return Ok(self.index_access_result(&types));
+                        }
                         if index < tuple.types.len() {
                             // TODO: update AST with the inferred type
                             return Ok(tuple.types[index]);
@@ -841,10 +1090,9 @@ impl Checker {
                         // to the union of all types in the tuple
                         self.get_prop_value(ctx, obj_idx, key_idx, is_mut)
                     }
-                    TypeKind::Primitive(Primitive::Number) => {
-                        let mut types = tuple.types.clone();
-                        types.push(self.new_lit_type(&Literal::Undefined));
-                        Ok(self.new_union_type(&types))
+                    TypeKind::Primitive(Primitive::Number | Primitive::Int) => {
+                        let types = self.get_tuple_elem_types(&tuple.types);
+                        Ok(self.index_access_result(&types))
                     }
                     _ => Err(TypeError {
                         message: "Can only access tuple properties with a number".to_string(),
@@ -950,6 +1198,7 @@ impl Checker {
                                 match &name {
                                     TPropKey::StringKey(_) if primitive == &Primitive::String => (),
                                     TPropKey::NumberKey(_) if primitive == &Primitive::Number => (),
+                                    TPropKey::SymbolKey(_) if primitive == &Primitive::Symbol => (),
                                     _ => continue,
                                 };
                                 values.push(*ret);
@@ -962,6 +1211,7 @@ impl Checker {
                                 match &name {
                                     TPropKey::StringKey(_) if primitive == &Primitive::String => (),
                                     TPropKey::NumberKey(_) if primitive == &Primitive::Number => (),
+                                    TPropKey::SymbolKey(_) if primitive == &Primitive::Symbol => (),
                                     _ => continue,
                                 };
                                 values.push(param.t);
@@ -970,6 +1220,7 @@ impl Checker {
                                 match &prop.name {
                                     TPropKey::StringKey(_) if primitive == &Primitive::String => (),
                                     TPropKey::NumberKey(_) if primitive == &Primitive::Number => (),
+                                    TPropKey::SymbolKey(_) if primitive == &Primitive::Symbol => (),
                                     _ => continue,
                                 };
 
@@ -987,10 +1238,7 @@ impl Checker {
                         let mapped_key = get_mapped_key(self, mapped);
 
                         match self.unify(ctx, key_idx, mapped_key) {
-                            Ok(_) => {
-                                let undefined = self.new_lit_type(&Literal::Undefined);
-                                Ok(self.new_union_type(&[mapped.value, undefined]))
-                            }
+                            Ok(_) => Ok(self.index_access_result(&[mapped.value])),
                             Err(_) => Err(TypeError {
                                 message: format!(
                                     "{} is not a valid indexer for {}",
@@ -1000,8 +1248,7 @@ impl Checker {
                             }),
                         }
                     } else if !values.is_empty() {
-                        values.push(undefined);
-                        Ok(self.new_union_type(&values))
+                        Ok(self.index_access_result(&values))
                     } else {
                         Err(TypeError {
                             message: format!("{} has no indexer", self.print_type(&obj_idx),),
@@ -1029,6 +1276,7 @@ impl Checker {
                                 let key = match &method.name {
                                     TPropKey::StringKey(key) => key,
                                     TPropKey::NumberKey(key) => key,
+                                    TPropKey::SymbolKey(_) => continue,
                                 };
                                 if key == name {
                                     let TMethod {
@@ -1076,6 +1324,7 @@ impl Checker {
                                 let key = match &getter.name {
                                     TPropKey::StringKey(key) => key,
                                     TPropKey::NumberKey(key) => key,
+                                    TPropKey::SymbolKey(_) => continue,
                                 };
 
                                 if key == name {
@@ -1086,6 +1335,7 @@ impl Checker {
                                 let key = match &setter.name {
                                     TPropKey::StringKey(key) => key,
                                     TPropKey::NumberKey(key) => key,
+                                    TPropKey::SymbolKey(_) => continue,
                                 };
 
                                 if key == name {
@@ -1096,10 +1346,14 @@ impl Checker {
                                 let key = match &prop.name {
                                     TPropKey::StringKey(key) => key,
                                     TPropKey::NumberKey(key) => key,
+                                    TPropKey::SymbolKey(_) => continue,
                                 };
                                 if key == name {
+                                    // Methods of object literals are type vars
+                                    // while the literal is being inferred.
+                                    let t = self.prune(prop.t);
                                     if let TypeKind::Function(Function { params, .. }) =
-                                        &self.arena[prop.t].kind
+                                        &self.arena[t].kind
                                     {
                                         if let Some(param) = params.first() {
                                             if param.is_mut_self() && !is_mut {
@@ -1127,10 +1381,7 @@ impl Checker {
                         let mapped_key = get_mapped_key(self, mapped);
 
                         match self.unify(ctx, key_idx, mapped_key) {
-                            Ok(_) => {
-                                let undefined = self.new_lit_type(&Literal::Undefined);
-                                Ok(self.new_union_type(&[mapped.value, undefined]))
-                            }
+                            Ok(_) => Ok(self.index_access_result(&[mapped.value])),
                             Err(_) => Err(TypeError {
                                 message: format!("Couldn't find property {} in object", name,),
                             }),
@@ -1160,10 +1411,7 @@ impl Checker {
                     if let Some(mapped) = maybe_mapped {
                         let mapped_key = get_mapped_key(self, mapped);
                         match self.unify(ctx, key_idx, mapped_key) {
-                            Ok(_) => {
-                                let undefined = self.new_lit_type(&Literal::Undefined);
-                                Ok(self.new_union_type(&[mapped.value, undefined]))
-                            }
+                            Ok(_) => Ok(self.index_access_result(&[mapped.value])),
                             Err(_) => Err(TypeError {
                                 message: format!("Couldn't find property {} in object", name,),
                             }),
@@ -1174,6 +1422,62 @@ impl Checker {
                         })
                     }
                 }
+                TypeKind::UniqueSymbol(UniqueSymbol { id }) => {
+                    for elem in &object.elems {
+                        match elem {
+                            TObjElem::Method(TMethod {
+                                name: TPropKey::SymbolKey(TSymbolKey { id: key, name }),
+                                mutates,
+                                function:
+                                    Function {
+                                        params,
+                                        ret,
+                                        type_params,
+                                        throws,
+                                    },
+                            }) if key == id => {
+                                if *mutates && !is_mut {
+                                    return Err(TypeError {
+                                        message: format!(
+                                            "Cannot call mutating method [{}] on a non-mutable object",
+                                            name,
+                                        ),
+                                    });
+                                }
+                                return Ok(self.new_func_type(params, *ret, type_params, *throws));
+                            }
+                            TObjElem::Getter(TGetter {
+                                name: TPropKey::SymbolKey(TSymbolKey { id: key, .. }),
+                                ret,
+                                ..
+                            }) if key == id => return Ok(*ret),
+                            TObjElem::Setter(TSetter {
+                                name: TPropKey::SymbolKey(TSymbolKey { id: key, .. }),
+                                param,
+                                ..
+                            }) if key == id => return Ok(param.t),
+                            TObjElem::Prop(TProp {
+                                name: TPropKey::SymbolKey(TSymbolKey { id: key, .. }),
+                                t,
+                                optional,
+                                ..
+                            }) if key == id => {
+                                return Ok(match optional {
+                                    true => self.new_union_type(&[*t, undefined]),
+                                    false => *t,
+                                });
+                            }
+                            _ => continue,
+                        }
+                    }
+
+                    Err(TypeError {
+                        message: format!(
+                            "Couldn't find property {} on object",
+                            self.print_type(&key_idx)
+                        ),
+                    })
+                }
                 _ => Err(TypeError {
                     message: format!("{} is not a valid key", self.print_type(&key_idx)),
                 }),
@@ -1184,6 +1488,74 @@ impl Checker {
             })
         }
     }
+
+    // Returns a copy of `t` where none of the object types are fresh.
+    pub fn remove_freshness(&mut self, t: Index) -> Index {
+        let mut visitor = FindFreshVisitor {
+            arena: &mut self.arena,
+            is_fresh: false,
+        };
+        visitor.visit_index(&t);
+
+        // Avoids copying types that don't contain any fresh object types.
+        if !visitor.is_fresh {
+            return t;
+        }
+
+        let mut folder = RemoveFreshnessFolder {
+            arena: &mut self.arena,
+        };
+
+        folder.fold_index(&t)
+    }
+}
+
+// Number literals without a decimal point or exponent are `int`s, e.g. `5` is
+// an `int` while `5.0` and `5e3` are `float`s.
+pub fn is_int_literal(value: &str) -> bool {
+    let is_radix = ["0x", "0X", "0b", "0B", "0o", "0O"]
+        .iter()
+        .any(|prefix| value.trim_start_matches('-').starts_with(prefix));
+    is_radix || !value.contains(['.', 'e', 'E'])
+}
+
+// Returns the name that's closest to `name`, if there's one that's close enough
+// that `name` is likely a typo of it.
+pub fn find_similar_name<'a>(
+    name: &str,
+    candidates: impl Iterator<Item = &'a String>,
+) -> Option<&'a String> {
+    let len = name.chars().count();
+    let max_distance = (len / 3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance && *distance < len)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+// Levenshtein distance between `a` and `b` where swapping two adjacent
+// characters counts as a single edit.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    dist[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    dist[a.len()][b.len()]
 }
 
 pub fn filter_nullables(arena: &Arena<Type>, types: &[Index]) -> Vec<Index> {
@@ -1246,6 +1618,43 @@ pub fn find_infer_types(arena: &mut Arena<Type>, t: &Index) -> Vec<Infer> {
     replace_visitor.infer_types
 }
 
+pub struct FindTypeVarsVisitor<'a> {
+    pub arena: &'a mut Arena<Type>,
+    pub type_vars: Vec<Index>,
+}
+
+impl<'a> KeyValueStore<Index, Type> for FindTypeVarsVisitor<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.arena.insert(t)
+    }
+}
+
+impl<'a> Visitor for FindTypeVarsVisitor<'a> {
+    fn visit_index(&mut self, index: &Index) {
+        if let TypeKind::TypeVar(TypeVar { instance: None, .. }) = &self.arena[*index].kind {
+            if !self.type_vars.contains(index) {
+                self.type_vars.push(*index);
+            }
+        }
+        visitor::walk_index(self, index);
+    }
+}
+
+// Returns the type vars in `t` that haven't been bound to a type.
+pub fn find_type_vars(arena: &mut Arena<Type>, t: &Index) -> Vec<Index> {
+    let mut visitor = FindTypeVarsVisitor {
+        arena,
+        type_vars: vec![],
+    };
+
+    visitor.visit_index(t);
+
+    visitor.type_vars
+}
+
 pub struct ReplaceVisitor<'a> {
     pub arena: &'a mut Arena<Type>,
     pub mapping: &'a std::collections::HashMap<String, Index>,
@@ -1283,3 +1692,52 @@ pub fn replace_infer_types(
 
     replace_visitor.fold_index(t)
 }
+
+pub struct FindFreshVisitor<'a> {
+    pub arena: &'a mut Arena<Type>,
+    pub is_fresh: bool,
+}
+
+impl<'a> KeyValueStore<Index, Type> for FindFreshVisitor<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.arena.insert(t)
+    }
+}
+
+impl<'a> Visitor for FindFreshVisitor<'a> {
+    fn visit_index(&mut self, index: &Index) {
+        if let TypeKind::Object(Object { is_fresh: true, .. }) = &self.arena[*index].kind {
+            self.is_fresh = true;
+            return;
+        }
+        visitor::walk_index(self, index);
+    }
+}
+
+pub struct RemoveFreshnessFolder<'a> {
+    pub arena: &'a mut Arena<Type>,
+}
+
+impl<'a> KeyValueStore<Index, Type> for RemoveFreshnessFolder<'a> {
+    fn get_type(&mut self, idx: &Index) -> Type {
+        self.arena[*idx].clone()
+    }
+    fn put_type(&mut self, t: Type) -> Index {
+        self.arena.insert(t)
+    }
+}
+
+impl<'a> Folder for RemoveFreshnessFolder<'a> {
+    fn fold_index(&mut self, index: &Index) -> Index {
+        let index = walk_index(self, index);
+        // `walk_index` always returns a copy of object types so it's safe to
+        // update them in place.
+        if let TypeKind::Object(object) = &mut self.arena[index].kind {
+            object.is_fresh = false;
+        }
+        index
+    }
+}
@@ -34,17 +34,18 @@ pub fn walk_index<V: Visitor>(visitor: &mut V, index: &Index) {
         TypeKind::Intersection(Intersection { types }) => {
             walk_indexes(visitor, types);
         }
-        TypeKind::Tuple(Tuple { types }) => {
+        TypeKind::Tuple(Tuple { types, .. }) => {
             walk_indexes(visitor, types);
         }
-        TypeKind::Array(Array { t }) => {
+        TypeKind::Array(Array { t, .. }) => {
             visitor.visit_index(t);
         }
         TypeKind::Keyword(_) => (),
         TypeKind::Primitive(_) => (),
+        TypeKind::UniqueSymbol(_) => (),
         TypeKind::Literal(_) => (),
         TypeKind::Function(function) => walk_function(visitor, function),
-        TypeKind::Object(Object { elems }) => {
+        TypeKind::Object(Object { elems, .. }) => {
             elems.iter().for_each(|elem| match elem {
                 TObjElem::Constructor(function) => walk_function(visitor, function),
                 TObjElem::Call(function) => walk_function(visitor, function),
@@ -108,6 +109,8 @@ pub fn walk_index<V: Visitor>(visitor: &mut V, index: &Index) {
             visitor.visit_index(left);
             visitor.visit_index(right);
         }
+        TypeKind::Mu(Mu { id: _, t }) => visitor.visit_index(t),
+        TypeKind::MuVar(_) => (),
     }
 }
 
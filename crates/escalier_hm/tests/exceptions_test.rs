@@ -59,11 +59,13 @@ fn test_env() -> (Checker, Context) {
     let array_scheme = Scheme {
         type_params: Some(vec![types::TypeParam {
             name: "T".to_string(),
+            arity: 0,
             constraint: None,
             default: None,
         }]),
         t: array_interface,
         is_type_param: false,
+        opaque_module: None,
     };
 
     context.schemes.insert("Array".to_string(), array_scheme);
@@ -85,9 +87,9 @@ fn basic_throws_test() -> Result<(), TypeError> {
         return add(a, b)
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("div").unwrap();
     assert_eq!(
@@ -120,9 +122,9 @@ fn constrained_throws() -> Result<(), TypeError> {
         return div(a, b)
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("foo").unwrap();
     assert_eq!(
@@ -143,9 +145,9 @@ fn constrained_throws_type_mismatch() -> Result<(), TypeError> {
         return div(a, b)
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    let result = checker.infer_script(&mut script, &mut my_ctx);
+    let result = checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default());
 
     assert_eq!(
         result,
@@ -168,9 +170,9 @@ fn throws_multiple_exceptions() -> Result<(), TypeError> {
         return sqrt(div(a, b))
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("foo").unwrap();
     assert_eq!(
@@ -192,9 +194,9 @@ fn unify_call_throws_with_func_sig_throws() -> Result<(), TypeError> {
         return sqrt(div(a, b))
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("foo").unwrap();
     assert_eq!(
@@ -216,9 +218,9 @@ fn unify_call_throws_with_func_sig_throws_failure() -> Result<(), TypeError> {
         return sqrt(div(a, b))
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    let result = checker.infer_script(&mut script, &mut my_ctx);
+    let result = checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default());
 
     assert_eq!(
         result,
@@ -241,9 +243,9 @@ fn scoped_throws() -> Result<(), TypeError> {
         return a + b
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("foo").unwrap();
     assert_eq!(
@@ -264,9 +266,9 @@ fn throws_coalesces_duplicate_exceptions() -> Result<(), TypeError> {
         return div(1, a) + div(1, b)
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("foo").unwrap();
     assert_eq!(
@@ -299,9 +301,9 @@ fn callback_with_throws() -> Result<(), TypeError> {
         return result
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("foo").unwrap();
     assert_eq!(
@@ -327,9 +329,9 @@ fn infer_throws_from_throw() -> Result<(), TypeError> {
         return a / b
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("div").unwrap();
     assert_eq!(
@@ -356,14 +358,14 @@ fn try_catches_throw() -> Result<(), TypeError> {
         0
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("div").unwrap();
     assert_eq!(
         checker.print_type(&binding.index),
-        r#"(a: number, b: number) -> number | 0"#
+        r#"(a: number, b: number) -> number"#
     );
 
     Ok(())
@@ -385,9 +387,9 @@ fn try_catches_throw_and_rethrows() -> Result<(), TypeError> {
         throw "RETHROWN_ERROR"
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("div").unwrap();
     assert_eq!(
@@ -416,17 +418,14 @@ fn try_catches_throw_return_inside_try_catch() -> Result<(), TypeError> {
         }
     }
     "#;
-    let mut script = parse(src).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
 
-    checker.infer_script(&mut script, &mut my_ctx)?;
+    checker.infer_script(&mut script, &mut my_ctx, &CompilerOptions::default())?;
 
     let binding = my_ctx.values.get("div").unwrap();
     assert_eq!(
         checker.print_type(&binding.index),
-        // TODO: the return type should be `number` because all `return` statements
-        // return numbers and code appearing after the `try-catch` is
-        // unreachable.
-        r#"(a: number, b: number) -> undefined"#
+        r#"(a: number, b: number) -> number"#
     );
 
     Ok(())
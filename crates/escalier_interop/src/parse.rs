@@ -39,7 +39,9 @@ pub fn infer_ts_type_ann(
             TsKeywordTypeKind::TsBooleanKeyword => {
                 Ok(checker.from_type_kind(TypeKind::Primitive(Primitive::Boolean)))
             }
-            TsKeywordTypeKind::TsBigIntKeyword => Err(String::from("can't parse BigInt yet")),
+            TsKeywordTypeKind::TsBigIntKeyword => {
+                Ok(checker.from_type_kind(TypeKind::Primitive(Primitive::BigInt)))
+            }
             TsKeywordTypeKind::TsStringKeyword => {
                 Ok(checker.from_type_kind(TypeKind::Primitive(Primitive::String)))
             }
@@ -103,6 +105,7 @@ pub fn infer_ts_type_ann(
 
                                 Ok(TypeParam {
                                     name: type_param.name.sym.to_string(),
+                                    arity: 0,
                                     constraint,
                                     default,
                                 })
@@ -182,6 +185,7 @@ pub fn infer_ts_type_ann(
 
             let t = checker.from_type_kind(TypeKind::Object(Object {
                 elems,
+                is_fresh: false,
                 // is_interface: false,
             }));
 
@@ -260,10 +264,10 @@ pub fn infer_ts_type_ann(
                         Ok(checker.new_keyof_type(type_ann))
                     }
                 },
-                TsTypeOperatorOp::Unique => todo!(),
+                TsTypeOperatorOp::Unique => Ok(checker.new_unique_symbol()),
                 TsTypeOperatorOp::ReadOnly => {
                     let type_ann = infer_ts_type_ann(checker, ctx, type_ann)?;
-                    Ok(type_ann)
+                    Ok(checker.new_readonly_type(type_ann))
                 }
             }
         }
@@ -330,7 +334,9 @@ pub fn infer_ts_type_ann(
             TsLit::Number(num) => Ok(checker.new_lit_type(&Lit::Number(format!("{}", num.value)))),
             TsLit::Str(str) => Ok(checker.new_lit_type(&Lit::String(str.value.to_string()))),
             TsLit::Bool(b) => Ok(checker.new_lit_type(&Lit::Boolean(b.value))),
-            TsLit::BigInt(_) => Err(String::from("can't parse BigInt literal yet")),
+            TsLit::BigInt(bigint) => {
+                Ok(checker.new_lit_type(&Lit::BigInt(bigint.value.to_string())))
+            }
             TsLit::Tpl(_) => Err(String::from("can't parse Tpl literal yet")),
         },
         TsType::TsTypePredicate(_) => Err(String::from("can't parse type predicate yet")),
@@ -450,6 +456,7 @@ fn infer_method_sig(
 
                 Ok(TypeParam {
                     name: type_param.name.sym.to_string(),
+                    arity: 0,
                     constraint,
                     default,
                 })
@@ -524,6 +531,7 @@ fn get_type_params(
 
                 Ok(TypeParam {
                     name: type_param.name.sym.to_string(),
+                    arity: 0,
                     constraint,
                     default,
                 })
@@ -663,6 +671,7 @@ fn infer_type_alias_decl(
 
                     Ok(TypeParam {
                         name: type_param.name.sym.to_string(),
+                        arity: 0,
                         constraint,
                         default,
                     })
@@ -676,6 +685,7 @@ fn infer_type_alias_decl(
         t,
         type_params,
         is_type_param: false,
+        opaque_module: None,
     };
 
     Ok(scheme)
@@ -703,6 +713,7 @@ fn infer_interface_decl(
             t: self_type,
             type_params: None,
             is_type_param: false,
+            opaque_module: None,
         },
     );
 
@@ -724,7 +735,7 @@ fn infer_interface_decl(
         })
         .collect();
 
-    let t = checker.from_type_kind(TypeKind::Object(Object { elems }));
+    let t = checker.new_object_type(&elems);
 
     let mut type_params = match &decl.type_params {
         Some(type_params) => Some(
@@ -750,6 +761,7 @@ fn infer_interface_decl(
 
                     Ok(TypeParam {
                         name: type_param.name.sym.to_string(),
+                        arity: 0,
                         constraint,
                         default,
                     })
@@ -769,11 +781,13 @@ fn infer_interface_decl(
         type_params = Some(vec![
             TypeParam {
                 name: "TPattern".to_string(),
+                arity: 0,
                 constraint: None,
                 default: None,
             },
             TypeParam {
                 name: "TFlags".to_string(),
+                arity: 0,
                 constraint: None,
                 default: None,
             },
@@ -784,6 +798,7 @@ fn infer_interface_decl(
         t,
         type_params,
         is_type_param: false,
+        opaque_module: None,
     };
 
     Ok(scheme)
@@ -868,6 +883,8 @@ impl Visit for InterfaceCollector {
                             let binding = Binding {
                                 index: t.to_owned(),
                                 is_mut: false,
+                                span: None,
+                                deprecated: None,
                             };
                             self.ctx.values.insert(name, binding);
                         }
@@ -946,5 +963,9 @@ pub fn parse_dts(d_ts_source: &str) -> Result<(Checker, Context), Error> {
     // TODO: maintain a list of standard library methods that mutate and update
     // those methods here.
 
+    if let Err(error) = collector.checker.load_prelude(&mut collector.ctx) {
+        eprintln!("couldn't load prelude: {error}");
+    }
+
     Ok((collector.checker, collector.ctx))
 }
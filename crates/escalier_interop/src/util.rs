@@ -4,7 +4,7 @@ use std::collections::HashSet;
 use escalier_hm::checker::Checker;
 use escalier_hm::types::{
     Function, MappedType, Object as TObject, Scheme, TGetter, TMethod, TObjElem, TProp, TPropKey,
-    TSetter, TypeKind,
+    TSetter, TSymbolKey, TypeKind,
 };
 
 pub fn new_merge_schemes(schemes: &[Scheme], checker: &mut Checker) -> Scheme {
@@ -25,12 +25,13 @@ pub fn new_merge_schemes(schemes: &[Scheme], checker: &mut Checker) -> Scheme {
         }
     }
 
-    let t = checker.from_type_kind(TypeKind::Object(TObject { elems }));
+    let t = checker.new_object_type(&elems);
 
     Scheme {
         t,
         type_params,
         is_type_param: false,
+        opaque_module: None,
     }
 }
 
@@ -58,7 +59,7 @@ pub fn merge_readonly_and_mutable_schemes(
     // if there's a property in both scheme's that's marked as `readonly` then
     // we should mark in the merged scheme as `readonly`
 
-    if let TypeKind::Object(TObject { elems }) = &checker.arena[readonly_scheme.t].kind {
+    if let TypeKind::Object(TObject { elems, .. }) = &checker.arena[readonly_scheme.t].kind {
         for elem in elems {
             match elem {
                 TObjElem::Call(callable) => {
@@ -74,6 +75,7 @@ pub fn merge_readonly_and_mutable_schemes(
                     let key = match &method.name {
                         TPropKey::StringKey(key) => key,
                         TPropKey::NumberKey(key) => key,
+                        TPropKey::SymbolKey(TSymbolKey { name, .. }) => name,
                     };
                     methods.insert(key.to_owned(), method.to_owned());
                 }
@@ -83,6 +85,7 @@ pub fn merge_readonly_and_mutable_schemes(
                     let key = match &getter.name {
                         TPropKey::StringKey(key) => key,
                         TPropKey::NumberKey(key) => key,
+                        TPropKey::SymbolKey(TSymbolKey { name, .. }) => name,
                     };
                     getters.insert(key.to_owned(), getter.to_owned());
                 }
@@ -92,6 +95,7 @@ pub fn merge_readonly_and_mutable_schemes(
                     let key = match &setter.name {
                         TPropKey::StringKey(key) => key,
                         TPropKey::NumberKey(key) => key,
+                        TPropKey::SymbolKey(TSymbolKey { name, .. }) => name,
                     };
                     setters.insert(key.to_owned(), setter.to_owned());
                 }
@@ -104,12 +108,13 @@ pub fn merge_readonly_and_mutable_schemes(
         }
     }
 
-    if let TypeKind::Object(TObject { elems }) = &checker.arena[mutable_scheme.t].kind {
+    if let TypeKind::Object(TObject { elems, .. }) = &checker.arena[mutable_scheme.t].kind {
         for elem in elems {
             if let TObjElem::Method(method) = elem {
                 let key = match &method.name {
                     TPropKey::StringKey(key) => key,
                     TPropKey::NumberKey(key) => key,
+                    TPropKey::SymbolKey(TSymbolKey { name, .. }) => name,
                 };
 
                 if !methods.contains_key(key) {
@@ -160,6 +165,7 @@ pub fn merge_readonly_and_mutable_schemes(
 
     let t = checker.from_type_kind(TypeKind::Object(TObject {
         elems,
+        is_fresh: false,
         // NOTE: This function is called from the code parsing interfaces
         // so this should always be true.
         // TODO: add a check to make sure since we shouldn't be trying to
@@ -171,6 +177,7 @@ pub fn merge_readonly_and_mutable_schemes(
         t,
         type_params,
         is_type_param: false,
+        opaque_module: None,
     }
 }
 
@@ -179,6 +186,7 @@ mod tests {
     use super::*;
 
     use escalier_ast::script::Script;
+    use escalier_ast::CompilerOptions;
     use escalier_hm::context::Context;
     use escalier_parser::*;
 
@@ -200,7 +208,9 @@ mod tests {
         let mut checker = Checker::default();
         let mut ctx = Context::default();
         let mut program = parse(src).unwrap();
-        checker.infer_script(&mut program, &mut ctx).unwrap();
+        checker
+            .infer_script(&mut program, &mut ctx, &CompilerOptions::default())
+            .unwrap();
 
         let scheme_a = ctx.schemes.get("A").unwrap();
         let scheme_b = ctx.schemes.get("B").unwrap();
@@ -226,7 +236,9 @@ mod tests {
         let mut checker = Checker::default();
         let mut ctx = Context::default();
         let mut program = parse(src).unwrap();
-        checker.infer_script(&mut program, &mut ctx).unwrap();
+        checker
+            .infer_script(&mut program, &mut ctx, &CompilerOptions::default())
+            .unwrap();
 
         let scheme_a = ctx.schemes.get("A").unwrap().to_owned();
         let scheme_b = ctx.schemes.get("B").unwrap().to_owned();
@@ -252,7 +264,9 @@ mod tests {
         let mut checker = Checker::default();
         let mut ctx = Context::default();
         let mut program = parse(src).unwrap();
-        checker.infer_script(&mut program, &mut ctx).unwrap();
+        checker
+            .infer_script(&mut program, &mut ctx, &CompilerOptions::default())
+            .unwrap();
 
         let scheme_a = ctx.schemes.get("A").unwrap().to_owned();
         let scheme_b = ctx.schemes.get("B").unwrap().to_owned();
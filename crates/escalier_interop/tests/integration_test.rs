@@ -1,5 +1,6 @@
 use std::fs;
 
+use escalier_ast::CompilerOptions;
 use escalier_hm::checker::Checker;
 use escalier_hm::context::Context;
 use escalier_hm::type_error::TypeError;
@@ -16,7 +17,7 @@ fn infer_prog(src: &str) -> (Checker, Context) {
     let lib = fs::read_to_string(LIB_ES5_D_TS).unwrap();
     let (mut checker, mut ctx) = parse_dts(&lib).unwrap();
 
-    let result = parse(src);
+    let result = parse(src, &CompilerOptions::default());
     let mut script = match result {
         Ok(script) => script,
         Err(err) => {
@@ -24,7 +25,7 @@ fn infer_prog(src: &str) -> (Checker, Context) {
             panic!("Error parsing expression");
         }
     };
-    match checker.infer_script(&mut script, &mut ctx) {
+    match checker.infer_script(&mut script, &mut ctx, &CompilerOptions::default()) {
         Ok(_) => {
             if !checker.current_report.diagnostics.is_empty() {
                 panic!("was expecting infer_prog() to return no errors");
@@ -43,12 +44,12 @@ fn infer_script_with_checker(
     checker: &mut Checker,
     ctx: &mut Context,
 ) -> Result<(), String> {
-    let result = parse(src);
+    let result = parse(src, &CompilerOptions::default());
     let mut script = match result {
         Ok(script) => script,
         Err(_) => return Err("Error parsing expression".to_string()),
     };
-    match checker.infer_script(&mut script, ctx) {
+    match checker.infer_script(&mut script, ctx, &CompilerOptions::default()) {
         Ok(_) => {
             if !checker.current_report.diagnostics.is_empty() {
                 Err("was expecting infer_prog() to return no errors".to_string())
@@ -88,19 +89,29 @@ fn infer_method_on_readonly_array() {
 }
 
 #[test]
-#[should_panic = "TypeError: Cannot call mutating method splice on a non-mutable object"]
 fn infer_mutable_method_on_readonly_array_errors() {
     let src = r#"
     declare let arr: string[]
     let splice = arr.splice
     "#;
 
-    let (mut checker, ctx) = infer_prog(src);
-
-    let scheme = ctx.get_scheme("Array").unwrap();
-    let t = checker.expand_type(&ctx, scheme.t).unwrap();
-    let Array = checker.print_type(&t);
-    eprintln!("Array = {}", Array);
+    let lib = fs::read_to_string(LIB_ES5_D_TS).unwrap();
+    let (mut checker, mut ctx) = parse_dts(&lib).unwrap();
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
+    checker
+        .infer_script(&mut script, &mut ctx, &CompilerOptions::default())
+        .unwrap();
+
+    let messages: Vec<String> = checker
+        .current_report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.to_owned())
+        .collect();
+    assert_eq!(
+        messages,
+        vec!["Cannot call mutating method `splice` on `arr` because it isn't mutable"]
+    );
 }
 
 #[test]
@@ -202,7 +213,7 @@ fn infer_static_properties() {
 //     let str = foo("hello");
 //     let bool = foo.bar;
 //     "#;
-//     let result = parse(src);
+//     let result = parse(src, &CompilerOptions::default());
 //     let mut prog = match result {
 //         Ok(prog) => prog,
 //         Err(err) => {
@@ -6,6 +6,8 @@ use swc_common::source_map::SourceFile;
 use lsp_server::Connection;
 use lsp_types::*;
 
+use escalier_ast::CompilerOptions;
+
 mod semantic_tokens;
 mod server;
 mod util;
@@ -61,7 +63,11 @@ fn main() -> Result<(), Box<dyn Error + Sync + Send>> {
 
     let lib = fs::read_to_string(LIB_ES5_D_TS).unwrap();
     let file_cache: HashMap<Url, SourceFile> = HashMap::new();
-    let mut server = LanguageServer { lib, file_cache };
+    let mut server = LanguageServer {
+        lib,
+        options: CompilerOptions::default(),
+        file_cache,
+    };
 
     server.main_loop(&connection)?;
 
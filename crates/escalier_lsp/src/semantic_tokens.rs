@@ -87,11 +87,13 @@ impl<'a> Visitor for SemanticTokenVisitor<'a> {
             // purposes: e.g. parameters, varaibles, properties, etc.
             ExprKind::Ident(_) => Some(4),
             ExprKind::IfElse(_) => None,
+            ExprKind::IfLet(_) => None,
             ExprKind::JSXElement(_) => None,
             ExprKind::JSXFragment(_) => None,
             ExprKind::Function(_) => None,
             ExprKind::Assign(_) => None,
             ExprKind::Num(_) => Some(11),
+            ExprKind::BigInt(_) => Some(11),
             ExprKind::Bool(_) => None,
             ExprKind::Str(_) => Some(10),
             ExprKind::Null(_) => None,
@@ -182,14 +184,19 @@ impl<'a> Visitor for SemanticTokenVisitor<'a> {
             TypeAnnKind::Boolean => Some(0),
             TypeAnnKind::NumLit(_) => Some(11),
             TypeAnnKind::Number => Some(0),
+            TypeAnnKind::BigIntLit(_) => Some(11),
+            TypeAnnKind::BigInt => Some(0),
             TypeAnnKind::StrLit(_) => Some(10),
             TypeAnnKind::String => Some(0),
             TypeAnnKind::Symbol => None,
+            TypeAnnKind::UniqueSymbol => None,
             TypeAnnKind::Null => None,
             TypeAnnKind::Undefined => None,
             TypeAnnKind::Unknown => Some(0),
             TypeAnnKind::Never => Some(0),
             TypeAnnKind::Rest(_) => None,
+            TypeAnnKind::Readonly(_) => None,
+            TypeAnnKind::Labeled(_, _) => None,
             TypeAnnKind::TypeOf(_) => None,
             TypeAnnKind::Match(_) => None,
             TypeAnnKind::Wildcard => None,
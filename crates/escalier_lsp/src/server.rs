@@ -13,9 +13,10 @@ use lsp_types::request::{HoverRequest, SemanticTokensFullRequest};
 use lsp_types::*;
 
 use escalier_ast::{
-    walk_expr, walk_pattern, walk_stmt, walk_type_ann, Expr, Pattern, Script, Stmt, TypeAnn,
-    Visitor,
+    walk_expr, walk_pattern, walk_stmt, walk_type_ann, CompilerOptions, Expr, Pattern, Script,
+    Stmt, TypeAnn, Visitor,
 };
+use escalier_hm::printer::PrintConfig;
 use escalier_interop::parse::parse_dts;
 use escalier_parser::parse;
 
@@ -24,6 +25,7 @@ use crate::util;
 
 pub struct LanguageServer {
     pub lib: String,
+    pub options: CompilerOptions,
     pub file_cache: HashMap<Url, SourceFile>,
 }
 
@@ -89,11 +91,13 @@ impl LanguageServer {
 
                 // Update ParseError to implement Error + Sync + Send
                 eprintln!("parsing input");
-                let mut program = parse(&file.src).unwrap();
+                let mut program = parse(&file.src, &self.options).unwrap();
 
                 // Update TypeError to implement Error + Sync + Send
                 eprintln!("inferring types");
-                checker.infer_script(&mut program, &mut ctx).unwrap();
+                checker
+                    .infer_script(&mut program, &mut ctx, &self.options)
+                    .unwrap();
 
                 eprintln!("program = {program:#?}");
 
@@ -106,8 +110,15 @@ impl LanguageServer {
                 // TODO: create a From impl to convert from one Position to another.
                 let cursor_loc = params.text_document_position_params.position;
 
+                // Type variables are named by where they appear in the type
+                // instead of by their ids which depend on the order in which
+                // the program was checked.
+                let config = PrintConfig {
+                    stable_type_vars: true,
+                    ..PrintConfig::default()
+                };
                 let message = match get_type_at_location(file, &program, &cursor_loc) {
-                    Some(t) => checker.print_type(&t),
+                    Some(t) => checker.print_type_with_config(&t, &config),
                     None => String::from("no type info"),
                 };
 
@@ -202,7 +213,7 @@ impl LanguageServer {
             }
         };
 
-        let mut prog = match parse(&file.src) {
+        let mut prog = match parse(&file.src, &self.options) {
             Ok(prog) => prog,
             Err(_) => {
                 return Response {
@@ -345,6 +356,7 @@ mod tests {
         let mut server = LanguageServer {
             file_cache,
             lib: String::from(""),
+            options: CompilerOptions::default(),
         };
 
         let uri = Url::from_str("file://path/to/file.esc").unwrap();
@@ -385,6 +397,7 @@ mod tests {
         let mut server = LanguageServer {
             file_cache,
             lib: String::from(""),
+            options: CompilerOptions::default(),
         };
 
         let params = DidChangeTextDocumentParams {
@@ -427,6 +440,7 @@ mod tests {
         let server = LanguageServer {
             file_cache,
             lib: String::from(""),
+            options: CompilerOptions::default(),
         };
 
         let params = HoverParams {
@@ -0,0 +1,12 @@
+#![no_main]
+
+use escalier_ast::CompilerOptions;
+use libfuzzer_sys::fuzz_target;
+
+// Run with `cargo fuzz run parse -- -timeout=5` from crates/escalier_parser.
+// The timeout catches inputs that cause the parser to hang.
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = escalier_parser::parse(input, &CompilerOptions::default());
+    }
+});
@@ -6,9 +6,24 @@ use crate::token::*;
 
 impl<'a> Parser<'a> {
     pub fn parse_class(&mut self) -> Result<Expr, ParseError> {
-        let token = self.next().unwrap_or(EOF.clone());
-        assert_eq!(token.kind, TokenKind::Class);
+        let token = self.expect(TokenKind::Class)?;
+        let class = self.parse_class_def(token.span.start, false)?;
+        let span = class.span;
 
+        Ok(Expr {
+            kind: ExprKind::Class(class),
+            span,
+            inferred_type: None,
+        })
+    }
+
+    // Parses a class from its type params onward.  The methods of ambient
+    // classes, e.g. `declare class Foo {...}`, don't have bodies.
+    pub(crate) fn parse_class_def(
+        &mut self,
+        start: usize,
+        is_ambient: bool,
+    ) -> Result<Class, ParseError> {
         let type_params = self.maybe_parse_type_params()?;
 
         let super_class = if self.peek().unwrap_or(&EOF).kind == TokenKind::Extends {
@@ -20,50 +35,37 @@ impl<'a> Parser<'a> {
                     name,
                 })
             } else {
-                panic!("expected identifier");
+                return Err(ParseError {
+                    message: "expected identifier".to_string(),
+                });
             }
         } else {
             None
         };
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftBrace
-        );
+        self.expect(TokenKind::LeftBrace)?;
 
         let mut body = vec![];
 
         while self.peek().unwrap_or(&EOF).kind != TokenKind::RightBrace {
-            let member = self.parse_class_member()?;
+            let member = self.parse_class_member(is_ambient)?;
             body.push(member);
         }
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightBrace
-        );
+        self.expect(TokenKind::RightBrace)?;
 
         let end = self.scanner.cursor();
-        let span = Span {
-            start: token.span.start,
-            end,
-        };
-        let kind = ExprKind::Class(Class {
-            span,
+
+        Ok(Class {
+            span: Span { start, end },
             type_params,
             super_class,
             super_type_args: None, // TODO
             body,
-        });
-
-        Ok(Expr {
-            kind,
-            span,
-            inferred_type: None,
         })
     }
 
-    fn parse_class_member(&mut self) -> Result<ClassMember, ParseError> {
+    fn parse_class_member(&mut self, is_ambient: bool) -> Result<ClassMember, ParseError> {
         let is_public = if self.peek().unwrap_or(&EOF).kind == TokenKind::Pub {
             self.next(); // consumes 'pub'
             true
@@ -80,10 +82,14 @@ impl<'a> Parser<'a> {
 
         let token = self.peek().unwrap_or(&EOF);
         match token.kind {
-            TokenKind::Identifier(_) => self.parse_field(is_public, is_static),
-            TokenKind::Fn => self.parse_method(is_public, is_static),
-            TokenKind::Gen => self.parse_method(is_public, is_static),
-            TokenKind::Async => self.parse_method(is_public, is_static),
+            TokenKind::Identifier(_) => self.parse_field(is_public, is_static, is_ambient),
+            TokenKind::Fn => self.parse_method(is_public, is_static, is_ambient),
+            TokenKind::Gen => self.parse_method(is_public, is_static, is_ambient),
+            TokenKind::Async => self.parse_method(is_public, is_static, is_ambient),
+            TokenKind::Get | TokenKind::Set if is_ambient => Err(ParseError {
+                message: "getters and setters can't be used in `declare class`, use fields instead"
+                    .to_string(),
+            }),
             TokenKind::Get => match is_static {
                 true => Err(ParseError {
                     message: "static getters are not allowed".to_string(),
@@ -102,7 +108,12 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn parse_field(&mut self, is_public: bool, is_static: bool) -> Result<ClassMember, ParseError> {
+    fn parse_field(
+        &mut self,
+        is_public: bool,
+        is_static: bool,
+        is_ambient: bool,
+    ) -> Result<ClassMember, ParseError> {
         // TODO: how do we include `pub` and `static` in the span?
         let token = self.next().unwrap_or(EOF.clone());
         let start = token.span.start;
@@ -113,7 +124,9 @@ impl<'a> Parser<'a> {
                 name: name.to_owned(),
             }
         } else {
-            panic!("expected identifier");
+            return Err(ParseError {
+                message: "expected identifier".to_string(),
+            });
         };
 
         let field = match self.peek().unwrap_or(&EOF).kind {
@@ -133,6 +146,11 @@ impl<'a> Parser<'a> {
                     type_ann: Some(type_ann),
                 })
             }
+            TokenKind::Assign if is_ambient => {
+                return Err(ParseError {
+                    message: "fields in `declare class` can't have initializers".to_string(),
+                })
+            }
             TokenKind::Assign => {
                 self.next(); // consumes '='
                 let init = self.parse_expr()?;
@@ -149,15 +167,18 @@ impl<'a> Parser<'a> {
                     type_ann: None,
                 })
             }
-            _ => panic!("expected ':' or '='"),
+            _ => {
+                return Err(ParseError {
+                    message: "expected ':' or '='".to_string(),
+                })
+            }
         };
 
         Ok(field)
     }
 
     fn parse_getter(&mut self, is_public: bool) -> Result<ClassMember, ParseError> {
-        let token = self.next().unwrap_or(EOF.clone());
-        assert_eq!(token.kind, TokenKind::Get);
+        let token = self.expect(TokenKind::Get)?;
         let start = token.span.start;
 
         let name = self.parse_name()?;
@@ -181,8 +202,7 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_setter(&mut self, is_public: bool) -> Result<ClassMember, ParseError> {
-        let token = self.next().unwrap_or(EOF.clone());
-        assert_eq!(token.kind, TokenKind::Set);
+        let token = self.expect(TokenKind::Set)?;
         let start = token.span.start;
 
         let name = self.parse_name()?;
@@ -209,6 +229,7 @@ impl<'a> Parser<'a> {
         &mut self,
         is_public: bool,
         is_static: bool,
+        is_ambient: bool,
     ) -> Result<ClassMember, ParseError> {
         // TODO: how do we include `pub` and `static` in the span?
         let start = self.peek().unwrap_or(&EOF).span.start;
@@ -227,7 +248,7 @@ impl<'a> Parser<'a> {
             false
         };
 
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Fn);
+        self.expect(TokenKind::Fn)?;
 
         let name = self.parse_name()?;
         let type_params = self.maybe_parse_type_params()?;
@@ -250,7 +271,29 @@ impl<'a> Parser<'a> {
             _ => None,
         };
 
-        let body = self.parse_block()?;
+        let body = match is_ambient {
+            true => {
+                let is_constructor =
+                    matches!(&name, PropName::Ident(Ident { name, .. }) if name == "constructor");
+                if !is_constructor && type_ann.is_none() {
+                    return Err(ParseError {
+                        message: "methods in `declare class` must have a return type".to_string(),
+                    });
+                }
+                if params.iter().any(|param| param.type_ann.is_none()) {
+                    return Err(ParseError {
+                        message: "params of methods in `declare class` must have type annotations"
+                            .to_string(),
+                    });
+                }
+                let end = self.scanner.cursor();
+                Block {
+                    span: Span { start: end, end },
+                    stmts: vec![],
+                }
+            }
+            false => self.parse_block()?,
+        };
         let end = self.scanner.cursor();
         let span = Span { start, end };
 
@@ -268,6 +311,7 @@ impl<'a> Parser<'a> {
                 type_params,
                 type_ann,
                 throws,
+                captures: None,
             },
         });
 
@@ -291,13 +335,14 @@ impl<'a> Parser<'a> {
             // }),
             TokenKind::LeftBracket => {
                 let expr = self.parse_expr()?;
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBracket
-                );
+                self.expect(TokenKind::RightBracket)?;
                 PropName::Computed(expr)
             }
-            _ => panic!("expected identifier or computed property name"),
+            _ => {
+                return Err(ParseError {
+                    message: "expected identifier or computed property name".to_string(),
+                })
+            }
         };
 
         Ok(name)
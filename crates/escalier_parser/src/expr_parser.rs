@@ -14,6 +14,7 @@ fn get_prefix_op_info(op: &Token) -> Option<OpInfo> {
         TokenKind::Yield => PRECEDENCE_TABLE.get(&Operator::Yield).cloned(),
         TokenKind::Throw => PRECEDENCE_TABLE.get(&Operator::Throw).cloned(),
         TokenKind::Not => PRECEDENCE_TABLE.get(&Operator::LogicalNot).cloned(),
+        TokenKind::Tilde => PRECEDENCE_TABLE.get(&Operator::BitwiseNot).cloned(),
         TokenKind::New => PRECEDENCE_TABLE
             .get(&Operator::NewWithArgumentList)
             .cloned(),
@@ -23,6 +24,9 @@ fn get_prefix_op_info(op: &Token) -> Option<OpInfo> {
 
 fn get_infix_op_info(op: &Token) -> Option<OpInfo> {
     match &op.kind {
+        // exponential
+        TokenKind::Power => PRECEDENCE_TABLE.get(&Operator::Exponentiation).cloned(),
+
         // multiplicative
         TokenKind::Times => PRECEDENCE_TABLE.get(&Operator::Multiplication).cloned(),
         TokenKind::Divide => PRECEDENCE_TABLE.get(&Operator::Division).cloned(),
@@ -32,6 +36,13 @@ fn get_infix_op_info(op: &Token) -> Option<OpInfo> {
         TokenKind::Plus => PRECEDENCE_TABLE.get(&Operator::Addition).cloned(),
         TokenKind::Minus => PRECEDENCE_TABLE.get(&Operator::Subtraction).cloned(),
 
+        // shift
+        TokenKind::LeftShift => PRECEDENCE_TABLE.get(&Operator::BitwiseLeftShift).cloned(),
+        TokenKind::RightShift => PRECEDENCE_TABLE.get(&Operator::BitwiseRightShift).cloned(),
+        TokenKind::UnsignedRightShift => PRECEDENCE_TABLE
+            .get(&Operator::BitwiseUnsignedRightShift)
+            .cloned(),
+
         // equality
         TokenKind::Equals => PRECEDENCE_TABLE.get(&Operator::Equals).cloned(),
         TokenKind::NotEquals => PRECEDENCE_TABLE.get(&Operator::NotEquals).cloned(),
@@ -41,6 +52,12 @@ fn get_infix_op_info(op: &Token) -> Option<OpInfo> {
         TokenKind::GreaterThanOrEqual => {
             PRECEDENCE_TABLE.get(&Operator::GreaterThanOrEqual).cloned()
         }
+        TokenKind::In => PRECEDENCE_TABLE.get(&Operator::In).cloned(),
+
+        // bitwise
+        TokenKind::Ampersand => PRECEDENCE_TABLE.get(&Operator::BitwiseAnd).cloned(),
+        TokenKind::Caret => PRECEDENCE_TABLE.get(&Operator::BitwiseXor).cloned(),
+        TokenKind::Pipe => PRECEDENCE_TABLE.get(&Operator::BitwiseOr).cloned(),
 
         // logic
         TokenKind::And => PRECEDENCE_TABLE.get(&Operator::LogicalAnd).cloned(),
@@ -76,17 +93,19 @@ fn get_postfix_op_info(op: &Token) -> Option<OpInfo> {
 impl<'a> Parser<'a> {
     // consumes leading '{' and trailing '}' tokens
     pub fn parse_block(&mut self) -> Result<Block, ParseError> {
-        let open = self.next().unwrap_or(EOF.clone());
-        assert_eq!(open.kind, TokenKind::LeftBrace);
+        let open = self.expect(TokenKind::LeftBrace)?;
         let mut stmts = Vec::new();
-        while self.peek().unwrap_or(&EOF).kind != TokenKind::RightBrace {
-            // TODO: attach comments to AST nodes
-            if let TokenKind::Comment(_) = &self.peek().unwrap_or(&EOF).kind {
-                self.next(); // consumes the comment
-                continue;
+        loop {
+            // Comments after the last statement aren't attached to anything.
+            let leading_comments = self.parse_comments();
+            if self.peek().unwrap_or(&EOF).kind == TokenKind::RightBrace {
+                break;
             }
 
-            stmts.push(self.parse_stmt()?);
+            let mut stmt = self.parse_stmt()?;
+            stmt.leading_comments = leading_comments;
+            stmt.trailing_comment = self.parse_trailing_comment(stmt.span.end);
+            stmts.push(stmt);
 
             // The last statement in a block is allowed to omit the trailing
             // semicolon.
@@ -94,8 +113,7 @@ impl<'a> Parser<'a> {
                 break;
             }
         }
-        let close = self.next().unwrap_or(EOF.clone());
-        assert_eq!(close.kind, TokenKind::RightBrace);
+        let close = self.expect(TokenKind::RightBrace)?;
         let span = merge_spans(&open.span, &close.span);
 
         Ok(Block { span, stmts })
@@ -107,9 +125,24 @@ impl<'a> Parser<'a> {
         let lhs = match &token.kind {
             TokenKind::NumLit(n) => {
                 self.next(); // consume number
+                let raw = self
+                    .scanner
+                    .slice(token.span.start, token.span.end)
+                    .replace('_', "");
                 Expr {
                     kind: ExprKind::Num(Num {
                         value: n.to_owned(),
+                        raw: (&raw != n).then_some(raw),
+                    }),
+                    span: token.span,
+                    inferred_type: None,
+                }
+            }
+            TokenKind::BigIntLit(n) => {
+                self.next(); // consume bigint
+                Expr {
+                    kind: ExprKind::BigInt(BigInt {
+                        value: n.to_owned(),
                     }),
                     span: token.span,
                     inferred_type: None,
@@ -155,7 +188,7 @@ impl<'a> Parser<'a> {
                                 span: token.span,
                                 value: value.to_owned(),
                             },
-                            _ => panic!("Expected string literal, got {:?}", token),
+                            _ => unreachable!("template string parts are always string literals"),
                         })
                         .collect(),
                     exprs: exprs.to_owned(),
@@ -205,9 +238,7 @@ impl<'a> Parser<'a> {
                     TokenKind::RightBracket,
                 )?;
 
-                assert_eq!(self.peek().unwrap_or(&EOF).kind, TokenKind::RightBracket);
-
-                let end = self.next().unwrap_or(EOF.clone());
+                let end = self.expect(TokenKind::RightBracket)?;
 
                 Expr {
                     kind: ExprKind::Tuple(Tuple { elements }),
@@ -219,9 +250,9 @@ impl<'a> Parser<'a> {
                 self.next(); // consumes '{'
                 let start = token;
 
-                let properties = self.parse_many(
+                let properties = self.parse_many_with_mode(
+                    IdentMode::PropName,
                     |p| {
-                        // TODO: we need `parse_many` to use the same `mode`
                         let next = p.next_with_mode(IdentMode::PropName).unwrap_or(EOF.clone());
 
                         match &next.kind {
@@ -248,21 +279,20 @@ impl<'a> Parser<'a> {
                                     TokenKind::NumLit(n) => ObjectKey::Number(n.to_owned()),
                                     TokenKind::LeftBracket => {
                                         let expr = p.parse_expr()?;
-                                        assert_eq!(
-                                            p.next().unwrap_or(EOF.clone()).kind,
-                                            TokenKind::RightBracket
-                                        );
+                                        p.expect(TokenKind::RightBracket)?;
                                         ObjectKey::Computed(Box::new(expr))
                                     }
                                     _ => {
-                                        panic!(
-                                            "Expected identifier or string literal, got {:?}",
-                                            next
-                                        )
+                                        return Err(ParseError {
+                                            message: format!(
+                                                "Expected identifier or string literal, got {:?}",
+                                                next
+                                            ),
+                                        })
                                     }
                                 };
 
-                                assert_eq!(p.next().unwrap_or(EOF.clone()).kind, TokenKind::Colon);
+                                p.expect(TokenKind::Colon)?;
 
                                 let value = p.parse_expr()?;
 
@@ -289,16 +319,45 @@ impl<'a> Parser<'a> {
             TokenKind::Match => {
                 let start = token;
                 self.next(); // consumes 'match'
-                let expr = self.parse_inside_parens(|p| p.parse_expr())?;
+                let mut exprs = self.parse_inside_parens(|p| {
+                    p.parse_many(|p| p.parse_expr(), TokenKind::Comma, TokenKind::RightParen)
+                })?;
 
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::LeftBrace
-                );
+                // `match (a, b) { (0, _) => ... }` matches the scrutinees as a
+                // tuple, i.e. it's the same as `match ([a, b]) { [0, _] => ... }`.
+                let scrutinee_count = exprs.len();
+                let expr = match scrutinee_count {
+                    0 => {
+                        return Err(ParseError {
+                            message: "match requires at least one scrutinee".to_string(),
+                        })
+                    }
+                    1 => exprs.pop().unwrap(),
+                    _ => {
+                        let span = merge_spans(
+                            &exprs[0].get_span(),
+                            &exprs[scrutinee_count - 1].get_span(),
+                        );
+                        Expr {
+                            kind: ExprKind::Tuple(Tuple {
+                                elements: exprs.into_iter().map(ExprOrSpread::Expr).collect(),
+                            }),
+                            span,
+                            inferred_type: None,
+                        }
+                    }
+                };
+
+                self.expect(TokenKind::LeftBrace)?;
 
                 let arms = self.parse_many(
                     |p| {
-                        let pattern = p.parse_pattern()?;
+                        let pattern = match p.peek().unwrap_or(&EOF).kind {
+                            TokenKind::LeftParen if scrutinee_count > 1 => {
+                                p.parse_scrutinee_patterns(scrutinee_count)?
+                            }
+                            _ => p.parse_pattern()?,
+                        };
 
                         let guard = if let TokenKind::If = p.peek().unwrap_or(&EOF).kind {
                             p.next(); // consumes 'if'
@@ -307,7 +366,7 @@ impl<'a> Parser<'a> {
                             None
                         };
 
-                        assert_eq!(p.next().unwrap_or(EOF.clone()).kind, TokenKind::DoubleArrow);
+                        p.expect(TokenKind::DoubleArrow)?;
 
                         let (body, end_span) = match p.peek().unwrap_or(&EOF).kind {
                             TokenKind::LeftBrace => {
@@ -333,8 +392,7 @@ impl<'a> Parser<'a> {
                     TokenKind::RightBrace,
                 )?;
 
-                let end = self.next().unwrap_or(EOF.clone());
-                assert_eq!(end.kind, TokenKind::RightBrace);
+                let end = self.expect(TokenKind::RightBrace)?;
 
                 Expr {
                     kind: ExprKind::Match(Match {
@@ -407,7 +465,9 @@ impl<'a> Parser<'a> {
                         }
                     }
                     _ => {
-                        panic!("expected catch or finally");
+                        return Err(ParseError {
+                            message: "expected catch or finally".to_string(),
+                        });
                     }
                 }
             }
@@ -422,6 +482,11 @@ impl<'a> Parser<'a> {
                     inferred_type: None,
                 }
             }
+            TokenKind::LessThan if !self.jsx => {
+                return Err(ParseError {
+                    message: "JSX isn't enabled".to_string(),
+                })
+            }
             TokenKind::LessThan => {
                 // HACK: We use self.scanner.peek() to lookahead further than
                 // self.peek() will allow.  The reason why this is scanner.peek(0)
@@ -449,7 +514,16 @@ impl<'a> Parser<'a> {
                 }
             }
             TokenKind::Class => self.parse_class()?,
-            _ => todo!(),
+            TokenKind::Error(message) => {
+                return Err(ParseError {
+                    message: message.to_owned(),
+                })
+            }
+            _ => {
+                return Err(ParseError {
+                    message: format!("unexpected token: {:?}", token),
+                })
+            }
         };
 
         Ok(lhs)
@@ -462,7 +536,15 @@ impl<'a> Parser<'a> {
             Some(op_info) => {
                 self.next(); // consume the token
 
-                let rhs = self.parse_expr_with_precedence(op_info.normalized_prec() - 1)?;
+                // `**` binds more tightly than the unary operators to its left,
+                // e.g. `-x ** 2` is `-(x ** 2)`.
+                let precedence = match token.kind {
+                    TokenKind::Plus | TokenKind::Minus | TokenKind::Not | TokenKind::Tilde => {
+                        PRECEDENCE_TABLE[&Operator::Exponentiation].normalized_prec() - 1
+                    }
+                    _ => op_info.normalized_prec() - 1,
+                };
+                let rhs = self.parse_expr_with_precedence(precedence)?;
                 let span = merge_spans(&token.span, &rhs.get_span());
 
                 let kind = match token.kind {
@@ -478,6 +560,10 @@ impl<'a> Parser<'a> {
                         op: UnaryOp::Not,
                         right: Box::new(rhs),
                     }),
+                    TokenKind::Tilde => ExprKind::Unary(Unary {
+                        op: UnaryOp::BitwiseNot,
+                        right: Box::new(rhs),
+                    }),
                     TokenKind::Await => ExprKind::Await(Await {
                         arg: Box::new(rhs),
                         throws: None,
@@ -508,7 +594,11 @@ impl<'a> Parser<'a> {
                             });
                         }
                     }
-                    t => panic!("unexpected token: {:?}", t),
+                    t => {
+                        return Err(ParseError {
+                            message: format!("unexpected token: {:?}", t),
+                        })
+                    }
                 };
 
                 Ok(Expr {
@@ -523,7 +613,19 @@ impl<'a> Parser<'a> {
 
     fn parse_if_else(&mut self) -> Result<Expr, ParseError> {
         let token = self.next().unwrap_or(EOF.clone()); // consumes 'if'
-        let cond = self.parse_inside_parens(|p| p.parse_expr())?;
+        let (pattern, cond) = self.parse_inside_parens(|p| {
+            // `if (let <pattern> = <expr>)` only takes the consequent if the
+            // pattern matches.
+            match p.peek().unwrap_or(&EOF).kind {
+                TokenKind::Let => {
+                    p.next(); // consumes 'let'
+                    let pattern = p.parse_pattern()?;
+                    p.expect(TokenKind::Assign)?;
+                    Ok((Some(pattern), p.parse_expr()?))
+                }
+                _ => Ok((None, p.parse_expr()?)),
+            }
+        })?;
         let consequent = self.parse_block()?;
 
         let expr = if self.peek().unwrap_or(&EOF).kind == TokenKind::Else {
@@ -542,22 +644,14 @@ impl<'a> Parser<'a> {
             };
 
             Expr {
-                kind: ExprKind::IfElse(IfElse {
-                    cond: Box::new(cond),
-                    consequent,
-                    alternate: Some(alternate),
-                }),
+                kind: build_if_kind(pattern, cond, consequent, Some(alternate)),
                 span,
                 inferred_type: None,
             }
         } else {
             let span = merge_spans(&token.span, &consequent.span);
             Expr {
-                kind: ExprKind::IfElse(IfElse {
-                    cond: Box::new(cond),
-                    consequent,
-                    alternate: None,
-                }),
+                kind: build_if_kind(pattern, cond, consequent, None),
                 span,
                 inferred_type: None,
             }
@@ -574,10 +668,7 @@ impl<'a> Parser<'a> {
                 TokenKind::Comma,
                 TokenKind::GreaterThan,
             )?;
-            assert_eq!(
-                self.next().unwrap_or(EOF.clone()).kind,
-                TokenKind::GreaterThan
-            );
+            self.expect(TokenKind::GreaterThan)?;
             Ok(Some(type_params))
         } else {
             Ok(None)
@@ -601,7 +692,7 @@ impl<'a> Parser<'a> {
             false
         };
 
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Fn);
+        self.expect(TokenKind::Fn)?;
 
         let type_params = self.maybe_parse_type_params()?;
         let params = self.parse_params()?;
@@ -652,6 +743,7 @@ impl<'a> Parser<'a> {
             throws, // TODO
             is_async,
             is_gen,
+            captures: None,
         });
 
         Ok(Expr {
@@ -662,32 +754,62 @@ impl<'a> Parser<'a> {
     }
 
     fn parse_type_param(&mut self) -> Result<TypeParam, ParseError> {
-        let start = self.scanner.cursor();
-        let name = match self.next().unwrap_or(EOF.clone()).kind {
+        let name_token = self.next().unwrap_or(EOF.clone());
+        let name = match name_token.kind {
             TokenKind::Identifier(name) => name,
-            _ => panic!("expected identifier"),
+            _ => {
+                return Err(ParseError {
+                    message: "expected identifier".to_string(),
+                })
+            }
         };
+        // Type params that are type constructors have a `_` for each of the
+        // type args they take, e.g. `F<_>`.
+        let mut span = name_token.span;
+        let mut arity = 0;
+        if self.peek().unwrap_or(&EOF).kind == TokenKind::LessThan {
+            self.next(); // consumes '<'
+            arity = self
+                .parse_many(
+                    |p| p.expect(TokenKind::Underscore),
+                    TokenKind::Comma,
+                    TokenKind::GreaterThan,
+                )?
+                .len();
+            let end = self.expect(TokenKind::GreaterThan)?;
+            span = merge_spans(&span, &end.span);
+        }
         let bound = if self.peek().unwrap_or(&EOF).kind == TokenKind::Colon {
             self.next().unwrap_or(EOF.clone());
             Some(self.parse_type_ann()?)
         } else {
             None
         };
-        let end = self.scanner.cursor();
+        if let Some(bound) = &bound {
+            span = merge_spans(&span, &bound.span);
+        }
 
         Ok(TypeParam {
-            span: Span { start, end },
+            span,
             name,
+            arity,
             bound,
             default: None,
         })
     }
 
     fn parse_expr_with_precedence(&mut self, precedence: Precedence) -> Result<Expr, ParseError> {
+        self.with_nesting(|p| p.parse_expr_with_precedence_inner(precedence))
+    }
+
+    fn parse_expr_with_precedence_inner(
+        &mut self,
+        precedence: Precedence,
+    ) -> Result<Expr, ParseError> {
         let mut lhs = self.parse_prefix()?;
 
         loop {
-            let next = self.peek().unwrap_or(&EOF).clone();
+            let next = self.peek_with_shifts();
             if let TokenKind::Eof = next.kind {
                 return Ok(lhs);
             }
@@ -720,6 +842,34 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // Combines adjacent `>` tokens into `>>` and `>>>` since they're lexed
+    // separately so that they can close nested type args, e.g. `Array<Array<T>>`.
+    fn peek_with_shifts(&mut self) -> Token {
+        let token = self.peek().unwrap_or(&EOF).clone();
+        if token.kind != TokenKind::GreaterThan || self.scanner.cursor() != token.span.end {
+            return token;
+        }
+
+        let (kind, len) = match (self.scanner.peek(0), self.scanner.peek(1)) {
+            (Some('>'), Some('>')) => (TokenKind::UnsignedRightShift, 2),
+            (Some('>'), _) => (TokenKind::RightShift, 1),
+            _ => return token,
+        };
+        for _ in 0..len {
+            self.scanner.pop();
+        }
+
+        let token = Token {
+            kind,
+            span: Span {
+                start: token.span.start,
+                end: self.scanner.cursor(),
+            },
+        };
+        self.peeked = Some(token.clone());
+        token
+    }
+
     fn parse_infix(&mut self, lhs: Expr, next_op_info: OpInfo) -> Result<Expr, ParseError> {
         let token = self.peek().unwrap_or(&EOF).clone();
 
@@ -740,7 +890,9 @@ impl<'a> Parser<'a> {
         if let Some(op) = op {
             if !lhs.is_lvalue() {
                 eprintln!("lhs = {:?}", lhs);
-                panic!("expected lvalue");
+                return Err(ParseError {
+                    message: "expected lvalue".to_string(),
+                });
             }
 
             let rhs = self.parse_expr_with_precedence(precedence)?;
@@ -763,15 +915,27 @@ impl<'a> Parser<'a> {
             TokenKind::Times => BinaryOp::Times,
             TokenKind::Divide => BinaryOp::Divide,
             TokenKind::Modulo => BinaryOp::Modulo,
+            TokenKind::Power => BinaryOp::Power,
             TokenKind::Equals => BinaryOp::Equals,
             TokenKind::NotEquals => BinaryOp::NotEquals,
             TokenKind::LessThan => BinaryOp::LessThan,
             TokenKind::LessThanOrEqual => BinaryOp::LessThanOrEqual,
             TokenKind::GreaterThan => BinaryOp::GreaterThan,
             TokenKind::GreaterThanOrEqual => BinaryOp::GreaterThanOrEqual,
+            TokenKind::In => BinaryOp::In,
             TokenKind::And => BinaryOp::And,
             TokenKind::Or => BinaryOp::Or,
-            _ => panic!("unexpected token: {:?}", token),
+            TokenKind::Ampersand => BinaryOp::BitwiseAnd,
+            TokenKind::Pipe => BinaryOp::BitwiseOr,
+            TokenKind::Caret => BinaryOp::BitwiseXor,
+            TokenKind::LeftShift => BinaryOp::LeftShift,
+            TokenKind::RightShift => BinaryOp::RightShift,
+            TokenKind::UnsignedRightShift => BinaryOp::UnsignedRightShift,
+            _ => {
+                return Err(ParseError {
+                    message: format!("unexpected token: {:?}", token),
+                })
+            }
         };
 
         let rhs = self.parse_expr_with_precedence(precedence)?;
@@ -806,10 +970,7 @@ impl<'a> Parser<'a> {
                 self.next(); // consumes '['
                 let rhs = self.parse_expr()?;
                 let span = merge_spans(&lhs.get_span(), &rhs.get_span());
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBracket
-                );
+                self.expect(TokenKind::RightBracket)?;
                 Expr {
                     kind: ExprKind::Member(Member {
                         object: Box::new(lhs),
@@ -851,6 +1012,9 @@ impl<'a> Parser<'a> {
                 // Parsing explicit type args conflicts with parsing expressions
                 // involving less-than and greater-than operators.  We can't know
                 // ahead of time which one to parse, so we have to try both.
+                if self.invalid_type_args.contains(&token.span.start) {
+                    return Ok(None);
+                }
                 let backup = self.clone();
 
                 self.next(); // consumes '<'
@@ -865,14 +1029,12 @@ impl<'a> Parser<'a> {
                         // If we failed to parse explicit type args, restore the
                         // parser state and continue parsing like nothing happened.
                         self.restore(backup);
+                        self.invalid_type_args.insert(token.span.start);
                         return Ok(None);
                     }
                 };
 
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::GreaterThan
-                );
+                self.expect(TokenKind::GreaterThan)?;
 
                 let args = self.parse_inside_parens(|p| {
                     p.parse_many(|p| p.parse_expr(), TokenKind::Comma, TokenKind::RightParen)
@@ -899,6 +1061,9 @@ impl<'a> Parser<'a> {
             }
             TokenKind::Dot => {
                 self.next(); // consumes '.'
+
+                // Property names can be keywords, e.g. `p.catch(...)`.
+                self.peek_with_mode(IdentMode::PropName);
                 let rhs = self.parse_expr_with_precedence(precedence)?;
                 match &rhs.kind {
                     ExprKind::Ident(ident) => {
@@ -923,7 +1088,11 @@ impl<'a> Parser<'a> {
             TokenKind::QuestionDot => {
                 self.next(); // consumes '?.'
 
-                let result = match self.peek().unwrap_or(&EOF).kind {
+                let result = match self
+                    .peek_with_mode(IdentMode::PropName)
+                    .unwrap_or(&EOF)
+                    .kind
+                {
                     TokenKind::LeftParen | TokenKind::LeftBracket => {
                         self.parse_postfix(lhs, next_op_info, true)?
                     }
@@ -973,7 +1142,9 @@ impl<'a> Parser<'a> {
                                     span: token.span,
                                     value: value.to_owned(),
                                 },
-                                _ => panic!("Expected string literal, got {:?}", token),
+                                _ => {
+                                    unreachable!("template string parts are always string literals")
+                                }
                             })
                             .collect(),
                         exprs: exprs.to_owned(),
@@ -987,7 +1158,11 @@ impl<'a> Parser<'a> {
                     inferred_type: None,
                 }
             }
-            _ => panic!("unexpected token: {:?}", token),
+            _ => {
+                return Err(ParseError {
+                    message: format!("unexpected token: {:?}", token),
+                })
+            }
         };
 
         Ok(Some(expr))
@@ -997,26 +1172,32 @@ impl<'a> Parser<'a> {
         &mut self,
         callback: impl FnOnce(&mut Self) -> Result<T, ParseError>,
     ) -> Result<T, ParseError> {
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftParen
-        );
-        let result = callback(self);
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightParen
-        );
-        result
+        self.expect(TokenKind::LeftParen)?;
+        let result = callback(self)?;
+        self.expect(TokenKind::RightParen)?;
+        Ok(result)
+    }
+
+    pub(crate) fn parse_many<T>(
+        &mut self,
+        callback: impl FnMut(&mut Self) -> Result<T, ParseError>,
+        separator: TokenKind,
+        terminator: TokenKind,
+    ) -> Result<Vec<T>, ParseError> {
+        self.parse_many_with_mode(IdentMode::Default, callback, separator, terminator)
     }
 
-    fn parse_many<T>(
+    // `mode` is used when peeking at the start of each item so that the token
+    // the callback sees is lexed the same way as it would be by the callback.
+    pub(crate) fn parse_many_with_mode<T>(
         &mut self,
+        mode: IdentMode,
         mut callback: impl FnMut(&mut Self) -> Result<T, ParseError>,
         separator: TokenKind,
         terminator: TokenKind,
     ) -> Result<Vec<T>, ParseError> {
         let mut result = Vec::new();
-        while self.peek().unwrap_or(&EOF).kind != terminator {
+        while self.peek_with_mode(mode).unwrap_or(&EOF).kind != terminator {
             result.push(callback(self)?);
 
             let next = self.peek().unwrap_or(&EOF);
@@ -1042,6 +1223,27 @@ impl<'a> Parser<'a> {
     }
 }
 
+fn build_if_kind(
+    pattern: Option<Pattern>,
+    cond: Expr,
+    consequent: Block,
+    alternate: Option<BlockOrExpr>,
+) -> ExprKind {
+    match pattern {
+        Some(pattern) => ExprKind::IfLet(IfLet {
+            pattern,
+            expr: Box::new(cond),
+            consequent,
+            alternate,
+        }),
+        None => ExprKind::IfElse(IfElse {
+            cond: Box::new(cond),
+            consequent,
+            alternate,
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1062,6 +1264,43 @@ mod tests {
         insta::assert_debug_snapshot!(parse(r#""hello""#));
     }
 
+    #[test]
+    fn parse_bigint_and_separator_literals() {
+        insta::assert_debug_snapshot!(parse("123n"));
+        insta::assert_debug_snapshot!(parse("1_000_000"));
+        insta::assert_debug_snapshot!(parse("-1_000n"));
+    }
+
+    #[test]
+    fn parse_number_literals_keep_their_source_text() {
+        let num = |input| match parse(input).kind {
+            ExprKind::Num(num) => num,
+            kind => panic!("expected a number, got {kind:?}"),
+        };
+
+        assert_eq!(
+            num("0xFF_FF"),
+            Num {
+                value: "65535".to_string(),
+                raw: Some("0xFFFF".to_string()),
+            }
+        );
+        assert_eq!(
+            num("1e-3"),
+            Num {
+                value: "0.001".to_string(),
+                raw: Some("1e-3".to_string()),
+            }
+        );
+        assert_eq!(
+            num("1_000"),
+            Num {
+                value: "1000".to_string(),
+                raw: None,
+            }
+        );
+    }
+
     #[test]
     fn parse_tuple_literals() {
         insta::assert_debug_snapshot!(parse("[]"));
@@ -1097,6 +1336,11 @@ mod tests {
         insta::assert_debug_snapshot!(parse("{ a, b }"));
     }
 
+    #[test]
+    fn parse_object_literals_with_keyword_keys() {
+        insta::assert_debug_snapshot!(parse(r#"{ type: "a", if: 1 }"#));
+    }
+
     #[test]
     #[should_panic]
     fn parse_object_literals_missing_colon() {
@@ -1135,6 +1379,47 @@ mod tests {
         insta::assert_debug_snapshot!(parse("1 * 2 + 3"));
     }
 
+    #[test]
+    fn parse_exponentiation() {
+        insta::assert_debug_snapshot!(parse("2 ** 3 ** 2"));
+        insta::assert_debug_snapshot!(parse("-x ** 2 * y"));
+    }
+
+    #[test]
+    fn parse_bitwise_operators() {
+        insta::assert_debug_snapshot!(parse("a | b ^ c & ~d"));
+        insta::assert_debug_snapshot!(parse("a << 1 >> 2 >>> 3 == x"));
+    }
+
+    #[test]
+    fn parse_shifts_and_nested_type_args() {
+        let mut parser = Parser::new("foo<Array<T>>(a > b) >> c");
+        let expr = parser.parse_expr().unwrap();
+
+        let (op, left) = match expr.kind {
+            ExprKind::Binary(Binary { op, left, .. }) => (op, left),
+            kind => panic!("expected a binary expression, got {kind:?}"),
+        };
+        assert_eq!(op, BinaryOp::RightShift);
+        match left.kind {
+            ExprKind::Call(Call {
+                type_args: Some(type_args),
+                args,
+                ..
+            }) => {
+                assert_eq!(type_args.len(), 1);
+                assert!(matches!(
+                    &args[0].kind,
+                    ExprKind::Binary(Binary {
+                        op: BinaryOp::GreaterThan,
+                        ..
+                    })
+                ));
+            }
+            kind => panic!("expected a call expression, got {kind:?}"),
+        }
+    }
+
     #[test]
     fn parse_parens() {
         insta::assert_debug_snapshot!(parse("5 * (x + 1)"));
@@ -1146,6 +1431,11 @@ mod tests {
         insta::assert_debug_snapshot!(parse("x != y && z == w"));
     }
 
+    #[test]
+    fn parse_in_operator() {
+        insta::assert_debug_snapshot!(parse(r#""key" in obj && key in obj"#));
+    }
+
     #[test]
     fn parse_unary_operators() {
         insta::assert_debug_snapshot!(parse("--a - +b"));
@@ -1242,6 +1532,12 @@ mod tests {
         insta::assert_debug_snapshot!(parse("a[b][c]"));
     }
 
+    #[test]
+    fn parse_member_access_with_keyword_props() {
+        insta::assert_debug_snapshot!(parse("p.catch"));
+        insta::assert_debug_snapshot!(parse("p?.finally"));
+    }
+
     #[test]
     fn parse_optional_chaining() {
         insta::assert_debug_snapshot!(parse("a?.b?.c"));
@@ -1278,6 +1574,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parse_if_let() {
+        insta::assert_debug_snapshot!(parse(r#"if (let {x, y} = p) { x + y }"#));
+        insta::assert_debug_snapshot!(parse(
+            r#"if (let x is number = a) { x } else if (let y is string = a) { y } else { z }"#
+        ));
+    }
+
     #[test]
     fn parse_param_destructuring() {
         insta::assert_debug_snapshot!(parse("fn ({x, y}) { return x + y }"));
@@ -1299,6 +1603,30 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parse_pattern_matching_multiple_scrutinees() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            match (a, b) {
+                (0, _) => "a",
+                (_, 0) => "b",
+                _ => "neither",
+            }
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_pattern_matching_wrong_number_of_patterns() {
+        let mut parser = Parser::new("match (a, b) { (0, _, _) => 0 }");
+        assert_eq!(
+            parser.parse_expr(),
+            Err(ParseError {
+                message: "Expected 2 patterns, one for each scrutinee, got 3".to_string()
+            })
+        );
+    }
+
     #[test]
     fn parse_try_catch() {
         insta::assert_debug_snapshot!(parse(
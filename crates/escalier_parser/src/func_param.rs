@@ -6,10 +6,7 @@ use crate::token::*;
 
 impl<'a> Parser<'a> {
     pub fn parse_params(&mut self) -> Result<Vec<FuncParam>, ParseError> {
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftParen
-        );
+        self.expect(TokenKind::LeftParen)?;
 
         let mut params: Vec<FuncParam> = Vec::new();
         while self.peek().unwrap_or(&EOF).kind != TokenKind::RightParen {
@@ -44,26 +41,24 @@ impl<'a> Parser<'a> {
                 TokenKind::Comma => {
                     self.next().unwrap_or(EOF.clone());
                 }
-                _ => panic!(
-                    "Expected comma or right paren, got {:?}",
-                    self.peek().unwrap_or(&EOF)
-                ),
+                _ => {
+                    return Err(ParseError {
+                        message: format!(
+                            "Expected comma or right paren, got {:?}",
+                            self.peek().unwrap_or(&EOF)
+                        ),
+                    })
+                }
             }
         }
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightParen
-        );
+        self.expect(TokenKind::RightParen)?;
 
         Ok(params)
     }
 
     pub fn parse_method_params(&mut self) -> Result<(Vec<FuncParam>, bool), ParseError> {
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftParen
-        );
+        self.expect(TokenKind::LeftParen)?;
 
         let mutates = if let TokenKind::Mut = self.peek().unwrap_or(&EOF).kind {
             self.next(); // consume 'mut'
@@ -72,10 +67,7 @@ impl<'a> Parser<'a> {
             false
         };
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::Identifier("self".to_string())
-        );
+        self.expect(TokenKind::Identifier("self".to_string()))?;
 
         if self.peek().unwrap_or(&EOF).kind == TokenKind::Comma {
             self.next(); // consume ','
@@ -114,17 +106,18 @@ impl<'a> Parser<'a> {
                 TokenKind::Comma => {
                     self.next().unwrap_or(EOF.clone());
                 }
-                _ => panic!(
-                    "Expected comma or right paren, got {:?}",
-                    self.peek().unwrap_or(&EOF)
-                ),
+                _ => {
+                    return Err(ParseError {
+                        message: format!(
+                            "Expected comma or right paren, got {:?}",
+                            self.peek().unwrap_or(&EOF)
+                        ),
+                    })
+                }
             }
         }
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightParen
-        );
+        self.expect(TokenKind::RightParen)?;
 
         Ok((params, mutates))
     }
@@ -1,32 +1,29 @@
 use escalier_ast::*;
+use unicode_ident::{is_xid_continue, is_xid_start};
 
 use crate::parse_error::ParseError;
 use crate::parser::*;
-use crate::token::{TokenKind, EOF};
+use crate::token::TokenKind;
 
 impl<'a> Parser<'a> {
     pub fn parse_jsx_element(&mut self) -> Result<JSXElement, ParseError> {
         let start = self.scanner.cursor();
 
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::LessThan);
-        let name_token = self.lex_ident_or_keyword(IdentMode::Default);
-        let name = match name_token.kind {
-            TokenKind::Identifier(name) => JSXElementName::Ident(Ident {
-                name,
-                span: name_token.span,
-            }),
-            _ => panic!("Expected identifier or keyword"),
-        };
+        self.expect(TokenKind::LessThan)?;
+        let name = self.parse_jsx_element_name()?;
 
         let mut attrs = vec![];
         let mut self_closing = false;
 
         while !self.scanner.is_done() {
-            eprintln!("peek: {:?}", self.scanner.peek(0));
             match self.scanner.peek(0).unwrap() {
                 '/' => {
                     self.scanner.pop();
-                    assert_eq!(self.scanner.pop(), Some('>'));
+                    if self.scanner.pop() != Some('>') {
+                        return Err(ParseError {
+                            message: "Expected '>' after '/' in JSX element".to_string(),
+                        });
+                    }
                     self_closing = true;
                     break;
                 }
@@ -34,7 +31,7 @@ impl<'a> Parser<'a> {
                     self.scanner.pop();
                     break;
                 }
-                ' ' => {
+                c if c.is_whitespace() => {
                     self.scanner.pop();
                 }
                 _ => {
@@ -56,24 +53,32 @@ impl<'a> Parser<'a> {
         } else {
             children = self.parse_jsx_children()?;
 
-            let start = self.scanner.cursor();
-
-            assert_eq!(self.scanner.pop(), Some('<'));
-            assert_eq!(self.scanner.pop(), Some('/'));
-            let end_name = self.lex_ident_or_keyword(IdentMode::Default);
-            assert_eq!(self.scanner.pop(), Some('>'));
+            if self.scanner.pop() != Some('<') || self.scanner.pop() != Some('/') {
+                return Err(ParseError {
+                    message: format!(
+                        "Expected closing tag for <{}>",
+                        jsx_element_name_to_string(&opening.name)
+                    ),
+                });
+            }
+            let end_name = self.parse_jsx_element_name()?;
+            if self.scanner.pop() != Some('>') {
+                return Err(ParseError {
+                    message: "Expected '>' at the end of closing tag".to_string(),
+                });
+            }
 
-            let end = self.scanner.cursor();
+            let opening_name = jsx_element_name_to_string(&opening.name);
+            let closing_name = jsx_element_name_to_string(&end_name);
+            if opening_name != closing_name {
+                return Err(ParseError {
+                    message: format!(
+                        "Expected closing tag </{opening_name}>, found </{closing_name}>"
+                    ),
+                });
+            }
 
-            Some(JSXClosingElement {
-                name: match end_name.kind {
-                    TokenKind::Identifier(name) => JSXElementName::Ident(Ident {
-                        name,
-                        span: Span { start, end },
-                    }),
-                    _ => panic!("Expected identifier or keyword"),
-                },
-            })
+            Some(JSXClosingElement { name: end_name })
         };
 
         let end = self.scanner.cursor();
@@ -86,23 +91,70 @@ impl<'a> Parser<'a> {
         })
     }
 
+    // Parses element names such as `div`, `Button`, or `Foo.Bar.Baz`.
+    fn parse_jsx_element_name(&mut self) -> Result<JSXElementName, ParseError> {
+        let ident = self.lex_jsx_ident()?;
+
+        if self.scanner.peek(0) != Some('.') {
+            return Ok(JSXElementName::Ident(ident));
+        }
+
+        let mut obj = JSXObject::Ident(ident);
+        loop {
+            self.scanner.pop(); // consumes '.'
+            let prop = self.lex_jsx_ident()?;
+            let member = JSXMemberExpr { obj, prop };
+            if self.scanner.peek(0) == Some('.') {
+                obj = JSXObject::JSXMemberExpr(Box::new(member));
+            } else {
+                return Ok(JSXElementName::JSXMemberExpr(member));
+            }
+        }
+    }
+
+    // JSX identifiers can contain dashes, e.g. `aria-label`, and may also be
+    // words that are keywords in Escalier, e.g. `type` or `for`.  Like other
+    // Escalier identifiers they can't contain `$`.
+    fn lex_jsx_ident(&mut self) -> Result<Ident, ParseError> {
+        let start = self.scanner.cursor();
+        let mut name = String::new();
+
+        while let Some(c) = self.scanner.peek(0) {
+            match c {
+                '_' => name.push(c),
+                c if is_xid_start(c) => name.push(c),
+                '-' if !name.is_empty() => name.push(c),
+                c if is_xid_continue(c) && !name.is_empty() => name.push(c),
+                _ => break,
+            }
+            self.scanner.pop();
+        }
+
+        if name.is_empty() {
+            return Err(ParseError {
+                message: format!("Expected JSX identifier, found {:?}", self.scanner.peek(0)),
+            });
+        }
+
+        let end = self.scanner.cursor();
+
+        Ok(Ident {
+            name,
+            span: Span { start, end },
+        })
+    }
+
     pub fn parse_jsx_fragment(&mut self) -> Result<JSXFragment, ParseError> {
         let start = self.scanner.cursor();
 
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::LessThan);
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::GreaterThan
-        );
+        self.expect(TokenKind::LessThan)?;
+        self.expect(TokenKind::GreaterThan)?;
 
         let children = self.parse_jsx_children()?;
 
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::LessThan);
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Divide);
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::GreaterThan
-        );
+        self.expect(TokenKind::LessThan)?;
+        self.expect(TokenKind::Divide)?;
+        self.expect(TokenKind::GreaterThan)?;
 
         let end = self.scanner.cursor();
 
@@ -115,12 +167,7 @@ impl<'a> Parser<'a> {
     }
 
     pub fn parse_jsx_attribute(&mut self) -> Result<JSXAttr, ParseError> {
-        let name = self.lex_ident_or_keyword(IdentMode::Default);
-
-        let name = match name.kind {
-            TokenKind::Identifier(name) => name,
-            _ => panic!("Unexpected token"),
-        };
+        let name = self.lex_jsx_ident()?.name;
 
         if let Some('=') = self.scanner.peek(0) {
             self.scanner.pop();
@@ -128,12 +175,16 @@ impl<'a> Parser<'a> {
             return Ok(JSXAttr { name, value: None });
         }
 
-        let attr = match self.scanner.peek(0).unwrap() {
-            '"' => {
+        let attr = match self.scanner.peek(0) {
+            Some('"') => {
                 let value = self.lex_string();
                 let value = match value.kind {
                     TokenKind::StrLit(value) => value,
-                    _ => panic!("Unexpected token"),
+                    _ => {
+                        return Err(ParseError {
+                            message: "Expected string literal".to_string(),
+                        })
+                    }
                 };
 
                 JSXAttr {
@@ -141,7 +192,7 @@ impl<'a> Parser<'a> {
                     value: Some(JSXAttrValue::Str(value)),
                 }
             }
-            '{' => {
+            Some('{') => {
                 self.scanner.pop(); // consumes '{'
 
                 self.brace_counts.push(0);
@@ -157,11 +208,18 @@ impl<'a> Parser<'a> {
                     })),
                 }
             }
-            _ => panic!("Unexpected character"),
+            Some(c) => {
+                return Err(ParseError {
+                    message: format!("Unexpected character '{c}' in JSX attribute value"),
+                })
+            }
+            None => {
+                return Err(ParseError {
+                    message: "Expected JSX attribute value".to_string(),
+                })
+            }
         };
 
-        eprintln!("attr: {:#?}", attr);
-
         Ok(attr)
     }
 
@@ -233,6 +291,24 @@ impl<'a> Parser<'a> {
     }
 }
 
+fn jsx_element_name_to_string(name: &JSXElementName) -> String {
+    fn obj_to_string(obj: &JSXObject) -> String {
+        match obj {
+            JSXObject::Ident(ident) => ident.name.to_owned(),
+            JSXObject::JSXMemberExpr(member) => {
+                format!("{}.{}", obj_to_string(&member.obj), member.prop.name)
+            }
+        }
+    }
+
+    match name {
+        JSXElementName::Ident(ident) => ident.name.to_owned(),
+        JSXElementName::JSXMemberExpr(member) => {
+            format!("{}.{}", obj_to_string(&member.obj), member.prop.name)
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -295,7 +371,7 @@ mod tests {
     fn parse_jsx_fragment() {
         let mut parser = Parser::new(r#"<><span>Hello, </span><span>world!</span></>"#);
 
-        let jsx_elem = parser.parse_jsx_element().unwrap();
+        let jsx_elem = parser.parse_jsx_fragment().unwrap();
 
         insta::assert_debug_snapshot!(jsx_elem);
     }
@@ -304,7 +380,7 @@ mod tests {
     fn parse_jsx_nested_fragments() {
         let mut parser = Parser::new(r#"<>a<>{b}{c}</>d</>"#);
 
-        let jsx_elem = parser.parse_jsx_element().unwrap();
+        let jsx_elem = parser.parse_jsx_fragment().unwrap();
 
         insta::assert_debug_snapshot!(jsx_elem);
     }
@@ -317,4 +393,55 @@ mod tests {
 
         insta::assert_debug_snapshot!(jsx_elem);
     }
+
+    #[test]
+    fn parse_jsx_member_expr_element_name() {
+        let mut parser = Parser::new(r#"<Foo.Bar.Baz>Hello</Foo.Bar.Baz>"#);
+
+        let jsx_elem = parser.parse_jsx_element().unwrap();
+
+        insta::assert_debug_snapshot!(jsx_elem);
+    }
+
+    #[test]
+    fn parse_jsx_attributes_with_dashes_and_keywords() {
+        let mut parser = Parser::new(
+            r#"<input
+    type="text"
+    aria-label="name"
+    data-id={id}
+/>"#,
+        );
+
+        let jsx_elem = parser.parse_jsx_element().unwrap();
+
+        insta::assert_debug_snapshot!(jsx_elem);
+    }
+
+    #[test]
+    fn parse_jsx_mismatched_closing_tag() {
+        let mut parser = Parser::new(r#"<Foo>Hello</Bar>"#);
+
+        let result = parser.parse_jsx_element();
+
+        assert_eq!(
+            result,
+            Err(ParseError {
+                message: "Expected closing tag </Foo>, found </Bar>".to_string(),
+            })
+        );
+    }
+    #[test]
+    fn parse_jsx_ident_with_dollar_sign() {
+        let mut parser = Parser::new(r#"<$foo />"#);
+
+        let result = parser.parse_jsx_element();
+
+        assert_eq!(
+            result,
+            Err(ParseError {
+                message: "Expected JSX identifier, found Some('$')".to_string(),
+            })
+        );
+    }
 }
@@ -6,6 +6,7 @@ use crate::token::*;
 
 impl<'a> Parser<'a> {
     fn parse_decl(&mut self) -> Result<Decl, ParseError> {
+        let opaque_span = self.parse_opaque();
         let token = self.peek().unwrap_or(&EOF).clone();
         let start = token.span.start;
 
@@ -54,12 +55,17 @@ impl<'a> Parser<'a> {
                         type_ann,
                     }),
                     span,
+                    is_export: false,
+                    doc: None,
+                    attrs: vec![],
                 }
             }
             TokenKind::Type => {
                 self.next(); // consumes 'type'
 
-                let name = match self.next().unwrap_or(EOF.clone()).kind {
+                let name_token = self.next().unwrap_or(EOF.clone());
+                let name_span = name_token.span;
+                let name = match name_token.kind {
                     TokenKind::Identifier(name) => name,
                     _ => {
                         return Err(ParseError {
@@ -70,17 +76,22 @@ impl<'a> Parser<'a> {
 
                 let type_params = self.maybe_parse_type_params()?;
 
-                assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Assign);
+                self.expect(TokenKind::Assign)?;
                 let type_ann = self.parse_type_ann()?;
-                let span = merge_spans(&token.span, &type_ann.span);
+                let span = merge_spans(&opaque_span.unwrap_or(token.span), &type_ann.span);
 
                 Decl {
                     kind: DeclKind::TypeDecl(TypeDecl {
                         name,
+                        name_span,
                         type_ann,
                         type_params,
+                        is_opaque: opaque_span.is_some(),
                     }),
                     span,
+                    is_export: false,
+                    doc: None,
+                    attrs: vec![],
                 }
             }
             _ => {
@@ -100,7 +111,10 @@ impl<'a> Parser<'a> {
             TokenKind::Export => {
                 self.next(); // consumes 'export'
 
-                let decl = self.parse_decl()?;
+                let decl = Decl {
+                    is_export: true,
+                    ..self.parse_decl()?
+                };
                 let span = merge_spans(&token.span, &decl.span);
 
                 ModuleItem {
@@ -109,65 +123,11 @@ impl<'a> Parser<'a> {
                 }
             }
             TokenKind::Import => {
-                self.next(); // consumes 'import'
-
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::LeftBrace
-                );
-
-                let mut specifiers: Vec<ImportSpecifier> = vec![];
-                while self.peek().unwrap_or(&EOF).kind != TokenKind::RightBrace {
-                    let local = match self.next().unwrap_or(EOF.clone()).kind {
-                        TokenKind::Identifier(name) => name,
-                        _ => panic!("expected identifier"),
-                    };
-
-                    match self.peek().unwrap_or(&EOF).kind {
-                        TokenKind::As => {
-                            self.next(); // consumes 'as'
-
-                            let imported = Some(local);
-
-                            match self.next().unwrap_or(EOF.clone()).kind {
-                                TokenKind::Identifier(local) => {
-                                    specifiers.push(ImportSpecifier { local, imported });
-                                }
-                                _ => panic!("expected identifier"),
-                            };
-                        }
-                        _ => {
-                            specifiers.push(ImportSpecifier {
-                                local,
-                                imported: None,
-                            });
-                        }
-                    };
-
-                    match self.peek().unwrap_or(&EOF).kind {
-                        TokenKind::RightBrace => break,
-                        TokenKind::Comma => {
-                            self.next().unwrap_or(EOF.clone());
-                        }
-                        _ => panic!(
-                            "Expected comma or right paren, got {:?}",
-                            self.peek().unwrap_or(&EOF)
-                        ),
-                    }
-                }
-
-                self.next(); // consumes '}'
-
-                assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::From);
-
-                let source = match self.next().unwrap_or(EOF.clone()).kind {
-                    TokenKind::StrLit(source) => source,
-                    _ => panic!("expected string literal"),
-                };
+                let (import, span) = self.parse_import()?;
 
                 ModuleItem {
-                    kind: ModuleItemKind::Import(Import { specifiers, source }),
-                    span: token.span,
+                    kind: ModuleItemKind::Import(import),
+                    span,
                 }
             }
             _ => {
@@ -184,20 +144,173 @@ impl<'a> Parser<'a> {
         Ok(item)
     }
 
+    // Parses `import {a, b as c} from "foo"`.  The import and each of its
+    // specifiers can be marked with `type`, e.g. `import type {Foo} from "foo"`
+    // or `import {type Foo, bar} from "foo"`.
+    pub(crate) fn parse_import(&mut self) -> Result<(Import, Span), ParseError> {
+        let start = self.expect(TokenKind::Import)?.span.start;
+        let is_type_only = self.parse_type_modifier();
+
+        let (specifiers, _) = self.parse_specifiers()?;
+        let specifiers = specifiers
+            .into_iter()
+            .map(|specifier| match specifier.alias {
+                Some(local) => ImportSpecifier {
+                    local,
+                    imported: Some(specifier.name),
+                    is_type_only: specifier.is_type_only,
+                    span: specifier.span,
+                },
+                None => ImportSpecifier {
+                    local: specifier.name,
+                    imported: None,
+                    is_type_only: specifier.is_type_only,
+                    span: specifier.span,
+                },
+            })
+            .collect();
+
+        self.expect(TokenKind::From)?;
+
+        let source_token = self.next().unwrap_or(EOF.clone());
+        let source = match source_token.kind {
+            TokenKind::StrLit(source) => source,
+            _ => {
+                return Err(ParseError {
+                    message: "expected string literal".to_string(),
+                })
+            }
+        };
+
+        let import = Import {
+            specifiers,
+            source,
+            is_type_only,
+        };
+        let span = Span {
+            start,
+            end: source_token.span.end,
+        };
+
+        Ok((import, span))
+    }
+
+    // Parses the specifiers after `export`, e.g. `{a, b as c}` or
+    // `type {Foo}`.  `export` must've already been consumed.
+    pub(crate) fn parse_named_export(&mut self) -> Result<(NamedExport, Span), ParseError> {
+        let is_type_only = self.parse_type_modifier();
+
+        let (specifiers, span) = self.parse_specifiers()?;
+        let specifiers = specifiers
+            .into_iter()
+            .map(|specifier| ExportSpecifier {
+                local: specifier.name,
+                exported: specifier.alias,
+                is_type_only: specifier.is_type_only,
+                span: specifier.span,
+            })
+            .collect();
+
+        let export = NamedExport {
+            specifiers,
+            is_type_only,
+        };
+
+        Ok((export, span))
+    }
+
+    // Consumes `type` if it's next, returns whether it was consumed.
+    fn parse_type_modifier(&mut self) -> bool {
+        match self.peek().unwrap_or(&EOF).kind {
+            TokenKind::Type => {
+                self.next(); // consumes 'type'
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Parses the names inside the braces of an import or export, e.g.
+    // `{a, type B, c as d}`, and returns them along with the span of the
+    // braces.
+    fn parse_specifiers(&mut self) -> Result<(Vec<Specifier>, Span), ParseError> {
+        let open = self.expect(TokenKind::LeftBrace)?;
+        let specifiers = self.parse_many(
+            |p| {
+                let start = p.peek().unwrap_or(&EOF).span.start;
+                let is_type_only = p.parse_type_modifier();
+                let (name, mut end) = p.parse_specifier_name()?;
+                let alias = match p.peek().unwrap_or(&EOF).kind {
+                    TokenKind::As => {
+                        p.next(); // consumes 'as'
+                        let (alias, alias_end) = p.parse_specifier_name()?;
+                        end = alias_end;
+                        Some(alias)
+                    }
+                    _ => None,
+                };
+                Ok(Specifier {
+                    name,
+                    alias,
+                    is_type_only,
+                    span: Span { start, end },
+                })
+            },
+            TokenKind::Comma,
+            TokenKind::RightBrace,
+        )?;
+        let close = self.expect(TokenKind::RightBrace)?;
+        Ok((specifiers, merge_spans(&open.span, &close.span)))
+    }
+
+    fn parse_specifier_name(&mut self) -> Result<(String, usize), ParseError> {
+        match self.next().unwrap_or(EOF.clone()) {
+            Token {
+                kind: TokenKind::Identifier(name),
+                span,
+            } => Ok((name, span.end)),
+            _ => Err(ParseError {
+                message: "expected identifier".to_string(),
+            }),
+        }
+    }
+
     pub fn parse_module(&mut self) -> Result<Module, ParseError> {
         let mut items = Vec::new();
-        while self.peek().unwrap_or(&EOF).kind != TokenKind::Eof {
-            // TODO: attach comments to AST nodes
-            if let TokenKind::Comment(_) = &self.peek().unwrap_or(&EOF).kind {
-                self.next(); // consumes the comment
-                continue;
+        loop {
+            // TODO: attach the rest of the comments to AST nodes
+            let comments = self.parse_comments();
+            if self.peek().unwrap_or(&EOF).kind == TokenKind::Eof {
+                break;
             }
-            items.push(self.parse_module_item()?);
+            let attrs = self.parse_attrs()?;
+            let mut item = self.parse_module_item()?;
+            match &mut item.kind {
+                ModuleItemKind::Decl(decl) | ModuleItemKind::Export(Export { decl }) => {
+                    decl.doc = Self::get_doc(&comments);
+                    decl.attrs = attrs;
+                }
+                ModuleItemKind::Import(_) if !attrs.is_empty() => {
+                    return Err(ParseError {
+                        message: "attributes can only be used on declarations".to_string(),
+                    })
+                }
+                ModuleItemKind::Import(_) => (),
+            }
+            items.push(item);
         }
         Ok(Module { items })
     }
 }
 
+// A name inside the braces of an import or export.
+struct Specifier {
+    name: String,
+    alias: Option<String>, // the name after `as`
+    is_type_only: bool,
+    span: Span,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
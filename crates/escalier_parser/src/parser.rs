@@ -1,17 +1,30 @@
-use core::panic;
+use std::collections::HashSet;
 use std::iter::Iterator;
 
 use escalier_ast::*;
+use unicode_ident::{is_xid_continue, is_xid_start};
 
 use crate::parse_error::ParseError;
 use crate::scanner::Scanner;
 use crate::token::*;
 
+// Expressions, type annotations, and patterns can be nested arbitrarily deeply
+// so the nesting depth is limited to avoid overflowing the stack.
+const MAX_NESTING_DEPTH: usize = 64;
+
 #[derive(Debug, Clone)]
 pub struct Parser<'a> {
     pub scanner: Scanner<'a>,
     pub brace_counts: Vec<usize>,
     pub peeked: Option<Token>,
+    pub depth: usize,
+    // The positions of `<` tokens that couldn't be parsed as the start of
+    // explicit type args.  Without this, backtracking after failing to parse
+    // nested type args takes exponential time.
+    pub invalid_type_args: HashSet<usize>,
+    // Whether JSX elements and fragments can be parsed, this is disabled when
+    // `CompilerOptions::jsx` is `None`.
+    pub jsx: bool,
 }
 
 impl<'a> Iterator for Parser<'a> {
@@ -40,6 +53,9 @@ impl<'a> Parser<'a> {
             scanner: Scanner::new(input),
             brace_counts: vec![0], // we need separate brace counts for each mode
             peeked: None,
+            depth: 0,
+            invalid_type_args: HashSet::new(),
+            jsx: true,
         }
     }
 
@@ -78,6 +94,134 @@ impl<'a> Parser<'a> {
         result
     }
 
+    // Consumes the next token and returns it if it's a `kind` token.
+    pub(crate) fn expect(&mut self, kind: TokenKind) -> Result<Token, ParseError> {
+        let token = self.next().unwrap_or(EOF.clone());
+        if token.kind != kind {
+            return Err(ParseError {
+                message: format!("Expected {:?}, got {:?}", kind, token),
+            });
+        }
+        Ok(token)
+    }
+
+    // Calls `parse` with the nesting depth incremented.
+    pub(crate) fn with_nesting<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth == MAX_NESTING_DEPTH {
+            return Err(ParseError {
+                message: format!("Exceeded the maximum nesting depth of {MAX_NESTING_DEPTH}"),
+            });
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    // Consumes comments up until the next token that isn't a comment.
+    pub fn parse_comments(&mut self) -> Vec<Comment> {
+        let mut comments = vec![];
+        loop {
+            let token = self.peek().unwrap_or(&EOF).clone();
+            match token.kind {
+                TokenKind::Comment(text) => {
+                    self.next(); // consumes the comment
+                    comments.push(Comment {
+                        text,
+                        span: token.span,
+                    });
+                }
+                _ => return comments,
+            }
+        }
+    }
+
+    // Consumes the next comment if it's on the same line as `end`.
+    pub fn parse_trailing_comment(&mut self, end: usize) -> Option<Comment> {
+        let token = self.peek().unwrap_or(&EOF).clone();
+        match token.kind {
+            TokenKind::Comment(text) if !self.scanner.has_newline(end, token.span.start) => {
+                self.next(); // consumes the comment
+                Some(Comment {
+                    text,
+                    span: token.span,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    // Returns the text of the doc comments, i.e. `///` comments, in `comments`
+    // with the slashes and the space following them removed.
+    pub(crate) fn get_doc(comments: &[Comment]) -> Option<String> {
+        let lines: Vec<&str> = comments
+            .iter()
+            .filter(|comment| comment.is_doc())
+            .map(|comment| {
+                let text = &comment.text[1..];
+                text.strip_prefix(' ').unwrap_or(text)
+            })
+            .collect();
+
+        match lines.is_empty() {
+            true => None,
+            false => Some(lines.join("\n")),
+        }
+    }
+
+    // Returns the pragmas in `comments`, e.g. `// @jsxRuntime classic @jsx h`.
+    // Only comments whose first word starts with `@` contain pragmas.
+    pub(crate) fn get_pragmas(comments: &[Comment]) -> Vec<Pragma> {
+        let mut pragmas = vec![];
+        for comment in comments.iter().filter(|comment| !comment.is_doc()) {
+            // The text of the comment starts after the leading `//`.
+            let offset = comment.span.start + 2;
+            let mut words: Vec<(Span, String)> = vec![];
+            let mut word = String::new();
+            for (i, c) in comment.text.chars().chain(std::iter::once(' ')).enumerate() {
+                if !c.is_whitespace() {
+                    word.push(c);
+                } else if !word.is_empty() {
+                    let end = offset + i;
+                    let start = end - word.chars().count();
+                    words.push((Span { start, end }, std::mem::take(&mut word)));
+                }
+            }
+
+            if !words.first().is_some_and(|(_, word)| word.starts_with('@')) {
+                continue;
+            }
+
+            let mut words = words.into_iter().peekable();
+            while let Some((span, word)) = words.next() {
+                let name = match word.strip_prefix('@') {
+                    Some(name) if !name.is_empty() => name.to_string(),
+                    _ => continue,
+                };
+                let value = match words.peek() {
+                    Some((_, next)) if !next.starts_with('@') => words.next(),
+                    _ => None,
+                };
+                let span = match &value {
+                    Some((value_span, _)) => Span {
+                        start: span.start,
+                        end: value_span.end,
+                    },
+                    None => span,
+                };
+                pragmas.push(Pragma {
+                    name,
+                    value: value.map(|(_, value)| value),
+                    span,
+                });
+            }
+        }
+        pragmas
+    }
+
     fn take(&mut self, mode: IdentMode) -> Option<Token> {
         if !self.scanner.is_done() {
             let mut character = match self.scanner.peek(0) {
@@ -97,7 +241,11 @@ impl<'a> Parser<'a> {
             let start = self.scanner.cursor();
 
             let kind = match character {
-                'a'..='z' | 'A'..='Z' | '_' => {
+                'r' if self.is_raw_string_start() => {
+                    // avoids an extra scanner.pop() call after the match
+                    return Some(self.lex_raw_string());
+                }
+                c if c == '_' || is_xid_start(c) => {
                     // avoids an extra scanner.pop() call after the match
                     return Some(self.lex_ident_or_keyword(mode));
                 }
@@ -113,7 +261,13 @@ impl<'a> Parser<'a> {
                     // avoids an extra scanner.pop() call after the match
                     return match self.lex_template_string(start) {
                         Ok(token) => Some(token),
-                        Err(ParseError { message }) => panic!("{}", message),
+                        Err(ParseError { message }) => Some(Token {
+                            kind: TokenKind::Error(message),
+                            span: Span {
+                                start,
+                                end: self.scanner.cursor(),
+                            },
+                        }),
                     };
                 }
                 '=' => match self.scanner.peek(1) {
@@ -150,6 +304,10 @@ impl<'a> Parser<'a> {
                         self.scanner.pop();
                         TokenKind::TimesAssign
                     }
+                    Some('*') => {
+                        self.scanner.pop();
+                        TokenKind::Power
+                    }
                     _ => TokenKind::Times,
                 },
                 '/' => match self.scanner.peek(1) {
@@ -212,6 +370,10 @@ impl<'a> Parser<'a> {
                             self.scanner.pop();
                             self.scanner.pop();
                             TokenKind::DotDotDot
+                        } else if self.scanner.peek(2) == Some('=') {
+                            self.scanner.pop();
+                            self.scanner.pop();
+                            TokenKind::DotDotEquals
                         } else {
                             self.scanner.pop();
                             TokenKind::DotDot
@@ -221,6 +383,7 @@ impl<'a> Parser<'a> {
                     }
                 }
                 ';' => TokenKind::Semicolon,
+                '@' => TokenKind::At,
                 ':' => TokenKind::Colon,
                 '?' => match self.scanner.peek(1) {
                     Some('.') => {
@@ -234,6 +397,10 @@ impl<'a> Parser<'a> {
                         self.scanner.pop();
                         TokenKind::LessThanOrEqual
                     }
+                    Some('<') => {
+                        self.scanner.pop();
+                        TokenKind::LeftShift
+                    }
                     _ => TokenKind::LessThan,
                 },
                 '>' => match self.scanner.peek(1) {
@@ -264,7 +431,9 @@ impl<'a> Parser<'a> {
                     }
                     _ => TokenKind::Pipe,
                 },
-                _ => panic!("Unexpected character: '{}'", character),
+                '^' => TokenKind::Caret,
+                '~' => TokenKind::Tilde,
+                _ => TokenKind::Error(format!("Unexpected character: '{}'", character)),
             };
             self.scanner.pop();
 
@@ -286,8 +455,8 @@ impl<'a> Parser<'a> {
         while !self.scanner.is_done() {
             let character = self.scanner.peek(0).unwrap();
             match character {
-                'a'..='z' | 'A'..='Z' | '_' | '0'..='9' => {
-                    ident.push(character);
+                c if is_xid_continue(c) => {
+                    ident.push(c);
                     self.scanner.pop();
                 }
                 _ => {
@@ -364,6 +533,7 @@ impl<'a> Parser<'a> {
             "null" => TokenKind::Null,
             "undefined" => TokenKind::Undefined,
             "number" => TokenKind::Number,
+            "bigint" => TokenKind::BigInt,
             "string" => TokenKind::String,
             "boolean" => TokenKind::Boolean,
             "symbol" => TokenKind::Symbol,
@@ -388,8 +558,20 @@ impl<'a> Parser<'a> {
     pub fn lex_number(&mut self) -> Token {
         let start = self.scanner.cursor();
 
+        let radix = match (self.scanner.peek(0), self.scanner.peek(1)) {
+            (Some('0'), Some('x' | 'X')) => Some(16),
+            (Some('0'), Some('o' | 'O')) => Some(8),
+            (Some('0'), Some('b' | 'B')) => Some(2),
+            _ => None,
+        };
+        if let Some(radix) = radix {
+            return self.lex_radix_number(start, radix);
+        }
+
         let mut number = String::new();
         let mut decimal = false;
+        let mut exponent = false;
+        let mut bigint = false;
 
         while !self.scanner.is_done() {
             let character = self.scanner.peek(0).unwrap();
@@ -398,21 +580,148 @@ impl<'a> Parser<'a> {
                     number.push(character);
                     self.scanner.pop();
                 }
+                // Numeric separators, e.g. 1_000_000, are only allowed between
+                // digits and are dropped from the literal's value.
+                '_' => {
+                    let prev_is_digit = number.ends_with(|c: char| c.is_ascii_digit());
+                    let next_is_digit = matches!(self.scanner.peek(1), Some('0'..='9'));
+                    if !prev_is_digit || !next_is_digit {
+                        self.scanner.pop();
+                        return self.lex_error(start, "Invalid numeric separator".to_string());
+                    }
+                    self.scanner.pop();
+                }
                 '.' => {
+                    // `0..10` is a range, not the number `0.` followed by `.10`
+                    // and `1e3.toString()` is a method call.
+                    if self.scanner.peek(1) == Some('.') || exponent {
+                        break;
+                    }
                     if decimal {
-                        panic!("Unexpected character: '{}'", character);
+                        self.scanner.pop();
+                        return self
+                            .lex_error(start, format!("Unexpected character: '{}'", character));
                     }
                     number.push(character);
                     self.scanner.pop();
                     decimal = true;
                 }
+                'e' | 'E' if !exponent => {
+                    let has_sign = matches!(self.scanner.peek(1), Some('+' | '-'));
+                    let digit = self.scanner.peek(if has_sign { 2 } else { 1 });
+                    self.scanner.pop();
+                    if !matches!(digit, Some('0'..='9')) {
+                        return self.lex_error(start, "Missing digits in exponent".to_string());
+                    }
+                    number.push('e');
+                    if has_sign {
+                        number.extend(self.scanner.pop());
+                    }
+                    exponent = true;
+                }
+                'n' => {
+                    if decimal {
+                        self.scanner.pop();
+                        return self.lex_error(
+                            start,
+                            "BigInt literals can't have a decimal point".to_string(),
+                        );
+                    }
+                    if exponent {
+                        self.scanner.pop();
+                        return self.lex_error(
+                            start,
+                            "BigInt literals can't have an exponent".to_string(),
+                        );
+                    }
+                    self.scanner.pop();
+                    bigint = true;
+                    break;
+                }
                 _ => {
                     break;
                 }
             }
         }
+
+        // Literal types use the canonical value so that `1e3` and `1000` have
+        // the same type.
+        if exponent {
+            number = match number.parse::<f64>() {
+                Ok(value) => value.to_string(),
+                Err(_) => return self.lex_error(start, "Invalid number".to_string()),
+            };
+        }
+
+        let kind = match bigint {
+            true => TokenKind::BigIntLit(number),
+            false => TokenKind::NumLit(number),
+        };
+
         Token {
-            kind: TokenKind::NumLit(number),
+            kind,
+            span: Span {
+                start,
+                end: self.scanner.cursor(),
+            },
+        }
+    }
+
+    // Lexes binary, octal, and hexadecimal literals, e.g. 0b1010, 0o777, and
+    // 0xFF.  The token contains the literal's value in decimal.
+    fn lex_radix_number(&mut self, start: usize, radix: u32) -> Token {
+        let name = match radix {
+            2 => "binary",
+            8 => "octal",
+            _ => "hexadecimal",
+        };
+        self.scanner.pop(); // consumes '0'
+        self.scanner.pop(); // consumes 'b', 'o', or 'x'
+
+        let mut digits: Vec<u32> = vec![];
+        let mut bigint = false;
+
+        loop {
+            match self.scanner.peek(0) {
+                Some('_') => {
+                    let next_is_digit =
+                        matches!(self.scanner.peek(1), Some(c) if c.is_digit(radix));
+                    self.scanner.pop();
+                    if digits.is_empty() || !next_is_digit {
+                        return self.lex_error(start, "Invalid numeric separator".to_string());
+                    }
+                }
+                Some('n') => {
+                    self.scanner.pop();
+                    bigint = true;
+                    break;
+                }
+                Some(c) if c.is_ascii_alphanumeric() => {
+                    self.scanner.pop();
+                    match c.to_digit(radix) {
+                        Some(digit) => digits.push(digit),
+                        None => {
+                            return self
+                                .lex_error(start, format!("Invalid digit '{c}' in {name} literal"))
+                        }
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        if digits.is_empty() {
+            return self.lex_error(start, format!("Missing digits in {name} literal"));
+        }
+
+        let number = digits_to_decimal(&digits, radix);
+        let kind = match bigint {
+            true => TokenKind::BigIntLit(number),
+            false => TokenKind::NumLit(number),
+        };
+
+        Token {
+            kind,
             span: Span {
                 start,
                 end: self.scanner.cursor(),
@@ -434,27 +743,10 @@ impl<'a> Parser<'a> {
                 }
                 '\\' => {
                     self.scanner.pop();
-                    let escaped = self.scanner.pop().unwrap();
-                    match escaped {
-                        '"' => string.push('"'),
-                        '\\' => string.push('\\'),
-                        '/' => string.push('/'),
-                        'b' => string.push('\u{0008}'),
-                        'f' => string.push('\u{000c}'),
-                        'n' => string.push('\n'),
-                        'r' => string.push('\r'),
-                        't' => string.push('\t'),
-                        'u' => {
-                            let mut code = String::new();
-                            for _ in 0..4 {
-                                code.push(self.scanner.peek(0).unwrap());
-                                self.scanner.pop();
-                            }
-                            let code = u32::from_str_radix(&code, 16).unwrap();
-                            string.push(char::from_u32(code).unwrap());
-                        }
-                        // NOTE: This doesn't match JS behavior
-                        character => panic!("Unexpected character: '{}'", character),
+                    match self.lex_escape_sequence() {
+                        Ok(Some(character)) => string.push(character),
+                        Ok(None) => {}
+                        Err(message) => return self.lex_error(start, message),
                     }
                 }
                 character => {
@@ -472,6 +764,130 @@ impl<'a> Parser<'a> {
         }
     }
 
+    // Lexes the escape sequence after a `\` in a string or template literal.
+    // Returns `None` for line continuations, i.e. a `\` at the end of a line,
+    // since they don't add anything to the string.
+    fn lex_escape_sequence(&mut self) -> Result<Option<char>, String> {
+        let escaped = match self.scanner.pop() {
+            Some(escaped) => escaped,
+            None => return Err("Unterminated escape sequence".to_string()),
+        };
+        let character = match escaped {
+            '"' | '\'' | '`' | '$' | '\\' | '/' => escaped,
+            'b' => '\u{0008}',
+            'f' => '\u{000c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{000b}',
+            '0' => '\0',
+            '\n' => return Ok(None),
+            'x' => match self.lex_code_point(2, 2) {
+                Some(character) => character,
+                None => return Err("Invalid hexadecimal escape sequence".to_string()),
+            },
+            'u' => {
+                let code_point = match self.scanner.peek(0) {
+                    Some('{') => {
+                        self.scanner.pop(); // consumes '{'
+                        match self.lex_code_point(1, 6) {
+                            Some(character) if self.scanner.pop() == Some('}') => Some(character),
+                            _ => None,
+                        }
+                    }
+                    _ => self.lex_code_point(4, 4),
+                };
+                match code_point {
+                    Some(character) => character,
+                    None => return Err("Invalid unicode escape sequence".to_string()),
+                }
+            }
+            // NOTE: This doesn't match JS behavior
+            character => return Err(format!("Unexpected character: '{}'", character)),
+        };
+        Ok(Some(character))
+    }
+
+    // Checks if the scanner is at the start of a raw string, e.g. r"..." or
+    // r#"..."#, as opposed to an identifier starting with 'r'.
+    fn is_raw_string_start(&self) -> bool {
+        let mut lookahead = 1;
+        while self.scanner.peek(lookahead) == Some('#') {
+            lookahead += 1;
+        }
+        self.scanner.peek(lookahead) == Some('"')
+    }
+
+    // Lexes a raw string.  Backslashes are treated like any other character so
+    // regexes and Windows paths don't need to be escaped.  Raw strings can
+    // contain `"` by adding `#`s after the `r`, e.g. r#"say "hi""#, in which
+    // case the string ends at the first `"` followed by the same number of `#`s.
+    pub fn lex_raw_string(&mut self) -> Token {
+        let start = self.scanner.cursor();
+        self.scanner.pop(); // consumes 'r'
+
+        let mut hashes = 0;
+        while self.scanner.peek(0) == Some('#') {
+            self.scanner.pop();
+            hashes += 1;
+        }
+        self.scanner.pop(); // consumes '"'
+
+        let mut string = String::new();
+        loop {
+            match self.scanner.pop() {
+                Some('"') if (0..hashes).all(|i| self.scanner.peek(i) == Some('#')) => {
+                    for _ in 0..hashes {
+                        self.scanner.pop();
+                    }
+                    break;
+                }
+                Some(character) => string.push(character),
+                None => return self.lex_error(start, "Unterminated raw string".to_string()),
+            }
+        }
+
+        Token {
+            kind: TokenKind::StrLit(string),
+            span: Span {
+                start,
+                end: self.scanner.cursor(),
+            },
+        }
+    }
+
+    // Lexes between `min` and `max` hex digits and returns the character with
+    // that code point.
+    fn lex_code_point(&mut self, min: usize, max: usize) -> Option<char> {
+        let mut code = String::new();
+        while code.len() < max {
+            match self.scanner.peek(0) {
+                Some(c) if c.is_ascii_hexdigit() => {
+                    code.push(c);
+                    self.scanner.pop();
+                }
+                _ => break,
+            }
+        }
+        if code.len() < min {
+            return None;
+        }
+        char::from_u32(u32::from_str_radix(&code, 16).ok()?)
+    }
+
+    // Returns an error token spanning from `start` to the current position.
+    // Callers consume the character that caused the error before calling this
+    // so that lexing always makes progress.
+    fn lex_error(&self, start: usize, message: String) -> Token {
+        Token {
+            kind: TokenKind::Error(message),
+            span: Span {
+                start,
+                end: self.scanner.cursor(),
+            },
+        }
+    }
+
     pub fn lex_template_string(&mut self, start: usize) -> Result<Token, ParseError> {
         let mut string = String::new();
         let mut parts: Vec<Token> = vec![];
@@ -486,32 +902,16 @@ impl<'a> Parser<'a> {
                 }
                 '\\' => {
                     self.scanner.pop();
-                    let escaped = self.scanner.pop().unwrap();
-                    match escaped {
-                        '`' => string.push('`'),
-                        '/' => string.push('/'),
-                        'b' => string.push('\u{0008}'),
-                        'f' => string.push('\u{000c}'),
-                        'n' => string.push('\n'),
-                        'r' => string.push('\r'),
-                        't' => string.push('\t'),
-                        'u' => {
-                            let mut code = String::new();
-                            for _ in 0..4 {
-                                code.push(self.scanner.peek(0).unwrap());
-                                self.scanner.pop();
-                            }
-                            let code = u32::from_str_radix(&code, 16).unwrap();
-                            string.push(char::from_u32(code).unwrap());
-                        }
-                        // NOTE: This doesn't match JS behavior
-                        character => panic!("Unexpected character: '{}'", character),
+                    match self.lex_escape_sequence() {
+                        Ok(Some(character)) => string.push(character),
+                        Ok(None) => {}
+                        Err(message) => return Err(ParseError { message }),
                     }
                 }
                 '$' => {
                     let string_end = self.scanner.cursor();
                     self.scanner.pop();
-                    if self.scanner.peek(0).unwrap() == '{' {
+                    if self.scanner.peek(0) == Some('{') {
                         parts.push(Token {
                             kind: TokenKind::StrLit(string),
                             span: Span {
@@ -525,7 +925,12 @@ impl<'a> Parser<'a> {
                         exprs.push(self.parse_expr()?);
                         self.brace_counts.pop();
 
-                        self.scanner.pop(); // consumes '}'
+                        if self.scanner.pop() != Some('}') {
+                            return Err(ParseError {
+                                message: "expected '}' after template string expression"
+                                    .to_string(),
+                            });
+                        }
 
                         string = String::new();
                         string_start = self.scanner.cursor();
@@ -558,6 +963,36 @@ impl<'a> Parser<'a> {
     }
 }
 
+// Converts `digits` in the given radix to a decimal string.  Arbitrary
+// precision is used since bigint literals can be larger than any integer type.
+fn digits_to_decimal(digits: &[u32], radix: u32) -> String {
+    const BASE: u64 = 1_000_000_000;
+
+    // Each limb holds nine decimal digits, least significant limb first.
+    let mut limbs: Vec<u64> = vec![0];
+    for digit in digits {
+        let mut carry = *digit as u64;
+        for limb in limbs.iter_mut() {
+            let value = *limb * radix as u64 + carry;
+            *limb = value % BASE;
+            carry = value / BASE;
+        }
+        if carry > 0 {
+            limbs.push(carry);
+        }
+    }
+
+    let mut limbs = limbs.iter().rev();
+    let mut result = limbs
+        .next()
+        .map(|limb| limb.to_string())
+        .unwrap_or_default();
+    for limb in limbs {
+        result.push_str(&format!("{limb:09}"));
+    }
+    result
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -599,11 +1034,139 @@ mod tests {
     }
 
     #[test]
-    #[should_panic = "Unexpected character: '.'"]
+    fn lex_numbers_with_separators() {
+        let parser = Parser::new("1_000_000 1_000.000_1");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::NumLit("1000000".to_string())
+        );
+        assert_eq!(
+            tokens[1].kind,
+            crate::token::TokenKind::NumLit("1000.0001".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_number_trailing_separator_error() {
+        let parser = Parser::new("1_");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::Error("Invalid numeric separator".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_number_consecutive_separators_error() {
+        let parser = Parser::new("1__000");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::Error("Invalid numeric separator".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_bigints() {
+        let parser = Parser::new("123n 9_007_199_254_740_993n");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::BigIntLit("123".to_string())
+        );
+        assert_eq!(
+            tokens[1].kind,
+            crate::token::TokenKind::BigIntLit("9007199254740993".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_radix_numbers() {
+        let parser =
+            Parser::new("0xFF 0Xff_ff 0o777 0b1010 0b1111_0000n 0xFFFF_FFFF_FFFF_FFFF_FFFFn");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(tokens[0].kind, TokenKind::NumLit("255".to_string()));
+        assert_eq!(tokens[1].kind, TokenKind::NumLit("65535".to_string()));
+        assert_eq!(tokens[2].kind, TokenKind::NumLit("511".to_string()));
+        assert_eq!(tokens[3].kind, TokenKind::NumLit("10".to_string()));
+        assert_eq!(tokens[4].kind, TokenKind::BigIntLit("240".to_string()));
+        assert_eq!(
+            tokens[5].kind,
+            TokenKind::BigIntLit("1208925819614629174706175".to_string())
+        );
+        assert_eq!(tokens[5].span, Span { start: 39, end: 66 });
+    }
+
+    #[test]
+    fn lex_numbers_with_exponents() {
+        let parser = Parser::new("1e-3 1.5E10 2e+5 1_0e1_0 1e3.toString");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(tokens[0].kind, TokenKind::NumLit("0.001".to_string()));
+        assert_eq!(tokens[1].kind, TokenKind::NumLit("15000000000".to_string()));
+        assert_eq!(tokens[2].kind, TokenKind::NumLit("200000".to_string()));
+        assert_eq!(
+            tokens[3].kind,
+            TokenKind::NumLit("100000000000".to_string())
+        );
+        assert_eq!(tokens[4].kind, TokenKind::NumLit("1000".to_string()));
+        assert_eq!(tokens[5].kind, TokenKind::Dot);
+    }
+
+    #[test]
+    fn lex_invalid_number_formats() {
+        let cases = [
+            ("0x", "Missing digits in hexadecimal literal"),
+            ("0b102", "Invalid digit '2' in binary literal"),
+            ("0o8", "Invalid digit '8' in octal literal"),
+            ("0x_FF", "Invalid numeric separator"),
+            ("0xFF_", "Invalid numeric separator"),
+            ("1e", "Missing digits in exponent"),
+            ("1e+", "Missing digits in exponent"),
+            ("1e3n", "BigInt literals can't have an exponent"),
+        ];
+        for (input, message) in cases {
+            let tokens = Parser::new(input).collect::<Vec<_>>();
+            assert_eq!(tokens[0].kind, TokenKind::Error(message.to_string()));
+        }
+    }
+
+    #[test]
+    fn lex_bigint_with_decimal_error() {
+        let parser = Parser::new("1.5n");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::Error(
+                "BigInt literals can't have a decimal point".to_string()
+            )
+        );
+    }
+
+    #[test]
     fn lex_number_multiple_decimals_error() {
         let parser = Parser::new("1.2.3");
 
-        let _ = parser.collect::<Vec<_>>();
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::Error("Unexpected character: '.'".to_string())
+        );
     }
 
     #[test]
@@ -651,6 +1214,132 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lex_more_string_escapes() {
+        let parser = Parser::new(r#""\'\`\$\v\0\x41\u{1F600}\u{41}""#);
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            crate::token::TokenKind::StrLit("'`$\u{b}\0A😀A".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_invalid_string_escapes() {
+        for input in [r#""\u{110000}""#, r#""\u{}""#, r#""\u12""#] {
+            let tokens = Parser::new(input).collect::<Vec<_>>();
+            assert_eq!(
+                tokens[0].kind,
+                TokenKind::Error("Invalid unicode escape sequence".to_string())
+            );
+        }
+
+        let tokens = Parser::new(r#""\xZZ""#).collect::<Vec<_>>();
+        assert_eq!(
+            tokens[0].kind,
+            TokenKind::Error("Invalid hexadecimal escape sequence".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_unicode_identifiers() {
+        let parser = Parser::new("café π _x1 日本語 \"ü\" a");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        // Spans are byte offsets
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    kind: TokenKind::Identifier("café".to_string()),
+                    span: Span { start: 0, end: 5 },
+                },
+                Token {
+                    kind: TokenKind::Identifier("π".to_string()),
+                    span: Span { start: 6, end: 8 },
+                },
+                Token {
+                    kind: TokenKind::Identifier("_x1".to_string()),
+                    span: Span { start: 9, end: 12 },
+                },
+                Token {
+                    kind: TokenKind::Identifier("日本語".to_string()),
+                    span: Span { start: 13, end: 22 },
+                },
+                Token {
+                    kind: TokenKind::StrLit("ü".to_string()),
+                    span: Span { start: 23, end: 27 },
+                },
+                Token {
+                    kind: TokenKind::Identifier("a".to_string()),
+                    span: Span { start: 28, end: 29 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn lex_identifiers_cant_start_with_non_xid_start_chars() {
+        let parser = Parser::new("€");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            TokenKind::Error("Unexpected character: '€'".to_string())
+        );
+    }
+
+    #[test]
+    fn lex_raw_strings() {
+        let parser = Parser::new(r##"r"^\d+(\.\d+)?$" r"C:\Users\" r#"say "hi""# rust"##);
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0],
+            Token {
+                kind: TokenKind::StrLit(r"^\d+(\.\d+)?$".to_string()),
+                span: Span { start: 0, end: 16 },
+            }
+        );
+        assert_eq!(tokens[1].kind, TokenKind::StrLit(r"C:\Users\".to_string()));
+        assert_eq!(tokens[2].kind, TokenKind::StrLit(r#"say "hi""#.to_string()));
+        assert_eq!(tokens[3].kind, TokenKind::Identifier("rust".to_string()));
+    }
+
+    #[test]
+    fn lex_multiline_strings() {
+        let parser = Parser::new("r\"line 1\n  line 2\" \"line 1\n  line 2\" \"foo \\\nbar\"");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            TokenKind::StrLit("line 1\n  line 2".to_string())
+        );
+        assert_eq!(
+            tokens[1].kind,
+            TokenKind::StrLit("line 1\n  line 2".to_string())
+        );
+        assert_eq!(tokens[2].kind, TokenKind::StrLit("foo bar".to_string()));
+    }
+
+    #[test]
+    fn lex_unterminated_raw_string() {
+        let parser = Parser::new(r##"r#"abc" def"##);
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            TokenKind::Error("Unterminated raw string".to_string())
+        );
+    }
+
     #[test]
     fn lex_template_string() {
         let parser = Parser::new("`abc`");
@@ -678,6 +1367,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn lex_template_string_escaped_dollar_and_backslash() {
+        let parser = Parser::new(r#"`\${x} \\ \u{1F600}`"#);
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(
+            tokens[0].kind,
+            TokenKind::StrTemplateLit {
+                parts: vec![Token {
+                    kind: TokenKind::StrLit("${x} \\ 😀".to_string()),
+                    span: Span { start: 0, end: 20 },
+                }],
+                exprs: vec![]
+            }
+        );
+    }
+
     #[test]
     fn lex_template_string_with_exprs() {
         let parser = Parser::new("`abc${x}`");
@@ -727,6 +1434,20 @@ mod tests {
         assert_eq!(tokens[2].kind, crate::token::TokenKind::Dot);
     }
 
+    #[test]
+    fn lex_ranges() {
+        let parser = Parser::new("0..10 1.5..=2");
+
+        let tokens = parser.collect::<Vec<_>>();
+
+        assert_eq!(tokens[0].kind, TokenKind::NumLit("0".to_string()));
+        assert_eq!(tokens[1].kind, TokenKind::DotDot);
+        assert_eq!(tokens[2].kind, TokenKind::NumLit("10".to_string()));
+        assert_eq!(tokens[3].kind, TokenKind::NumLit("1.5".to_string()));
+        assert_eq!(tokens[4].kind, TokenKind::DotDotEquals);
+        assert_eq!(tokens[5].kind, TokenKind::NumLit("2".to_string()));
+    }
+
     #[test]
     fn lex_assignment() {
         let parser = Parser::new("= += -= *= /= %=");
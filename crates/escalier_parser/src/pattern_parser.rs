@@ -6,6 +6,10 @@ use crate::token::*;
 
 impl<'a> Parser<'a> {
     pub fn parse_pattern(&mut self) -> Result<Pattern, ParseError> {
+        self.with_nesting(|p| p.parse_pattern_inner())
+    }
+
+    fn parse_pattern_inner(&mut self) -> Result<Pattern, ParseError> {
         let mut span = self.peek().unwrap_or(&EOF).span;
         let kind = match self.next().unwrap_or(EOF.clone()).kind {
             TokenKind::Identifier(name) => {
@@ -22,6 +26,10 @@ impl<'a> Parser<'a> {
                                 name: "number".to_string(),
                                 span: next.span,
                             },
+                            TokenKind::BigInt => Ident {
+                                name: "bigint".to_string(),
+                                span: next.span,
+                            },
                             TokenKind::String => Ident {
                                 name: "string".to_string(),
                                 span: next.span,
@@ -34,7 +42,11 @@ impl<'a> Parser<'a> {
                                 name: "symbol".to_string(),
                                 span: next.span,
                             },
-                            _ => panic!("expected identifier after 'is'"),
+                            _ => {
+                                return Err(ParseError {
+                                    message: "expected identifier after 'is'".to_string(),
+                                })
+                            }
                         };
                         PatternKind::Is(IsPat {
                             ident: BindingIdent {
@@ -58,13 +70,30 @@ impl<'a> Parser<'a> {
                     span,
                     mutable: true,
                 }),
-                _ => panic!("expected identifier after 'mut'"),
+                _ => {
+                    return Err(ParseError {
+                        message: "expected identifier after 'mut'".to_string(),
+                    })
+                }
             },
             TokenKind::StrLit(value) => PatternKind::Lit(LitPat {
                 lit: Literal::String(value),
             }),
-            TokenKind::NumLit(value) => PatternKind::Lit(LitPat {
-                lit: Literal::Number(value),
+            TokenKind::NumLit(value) => self.parse_num_or_range_pattern(value, &mut span)?,
+            TokenKind::Minus => match self.next().unwrap_or(EOF.clone()).kind {
+                TokenKind::NumLit(value) => {
+                    self.parse_num_or_range_pattern(format!("-{value}"), &mut span)?
+                }
+                _ => {
+                    return Err(ParseError {
+                        message: "expected number after '-'".to_string(),
+                    })
+                }
+            },
+            TokenKind::DotDot => self.parse_range_pattern(None, false, &mut span)?,
+            TokenKind::DotDotEquals => self.parse_range_pattern(None, true, &mut span)?,
+            TokenKind::BigIntLit(value) => PatternKind::Lit(LitPat {
+                lit: Literal::BigInt(value),
             }),
             TokenKind::BoolLit(value) => PatternKind::Lit(LitPat {
                 lit: Literal::Boolean(value),
@@ -80,7 +109,10 @@ impl<'a> Parser<'a> {
                     match &self.peek().unwrap_or(&EOF).kind {
                         TokenKind::DotDotDot => {
                             if has_rest {
-                                panic!("only one rest pattern is allowed per object pattern");
+                                return Err(ParseError {
+                                    message: "only one rest pattern is allowed per object pattern"
+                                        .to_string(),
+                                });
                             }
                             elems.push(Some(TuplePatElem {
                                 pattern: self.parse_pattern()?,
@@ -89,9 +121,10 @@ impl<'a> Parser<'a> {
                             has_rest = true;
                         }
                         _ => {
+                            let pattern = self.parse_pattern()?;
                             elems.push(Some(TuplePatElem {
-                                pattern: self.parse_pattern()?,
-                                init: None,
+                                pattern,
+                                init: self.parse_pattern_default()?,
                             }));
                         }
                     }
@@ -105,10 +138,7 @@ impl<'a> Parser<'a> {
                 }
 
                 span = merge_spans(&span, &self.peek().unwrap_or(&EOF).span);
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBracket
-                );
+                self.expect(TokenKind::RightBracket)?;
 
                 PatternKind::Tuple(TuplePat {
                     elems,
@@ -132,6 +162,7 @@ impl<'a> Parser<'a> {
                                 self.next();
 
                                 let pattern = self.parse_pattern()?;
+                                let init = self.parse_pattern_default()?;
 
                                 // TODO: handle `var` and `mut` modifiers
                                 props.push(ObjectPatProp::KeyValue(KeyValuePatProp {
@@ -141,7 +172,7 @@ impl<'a> Parser<'a> {
                                         span: first_span,
                                     },
                                     value: Box::new(pattern),
-                                    init: None,
+                                    init,
                                 }));
                             } else {
                                 // TODO: handle `var` and `mut` modifiers
@@ -152,7 +183,7 @@ impl<'a> Parser<'a> {
                                         span: first_span,
                                         mutable: false,
                                     },
-                                    init: None,
+                                    init: self.parse_pattern_default()?,
                                 }))
                             }
 
@@ -165,7 +196,11 @@ impl<'a> Parser<'a> {
                                 TokenKind::RightBrace => {
                                     break;
                                 }
-                                _ => panic!("expected comma or right brace"),
+                                _ => {
+                                    return Err(ParseError {
+                                        message: "expected comma or right brace".to_string(),
+                                    })
+                                }
                             }
                         }
                         TokenKind::DotDotDot => {
@@ -181,7 +216,11 @@ impl<'a> Parser<'a> {
                                 TokenKind::RightBrace => {
                                     break;
                                 }
-                                _ => panic!("expected comma or right brace"),
+                                _ => {
+                                    return Err(ParseError {
+                                        message: "expected comma or right brace".to_string(),
+                                    })
+                                }
                             }
                         }
                         TokenKind::Mut => match &self.next().unwrap_or(EOF.clone()).kind {
@@ -193,20 +232,25 @@ impl<'a> Parser<'a> {
                                         span: first_span,
                                         mutable: true,
                                     },
-                                    init: None,
+                                    init: self.parse_pattern_default()?,
                                 }))
                             }
-                            _ => panic!("expected identifier after 'mut'"),
+                            _ => {
+                                return Err(ParseError {
+                                    message: "expected identifier after 'mut'".to_string(),
+                                })
+                            }
                         },
-                        _ => panic!("expected identifier or rest pattern"),
+                        _ => {
+                            return Err(ParseError {
+                                message: "expected identifier or rest pattern".to_string(),
+                            })
+                        }
                     }
                 }
 
                 span = merge_spans(&span, &self.peek().unwrap_or(&EOF).span);
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBrace
-                );
+                self.expect(TokenKind::RightBrace)?;
 
                 PatternKind::Object(ObjectPat {
                     props,
@@ -219,7 +263,9 @@ impl<'a> Parser<'a> {
             }),
             TokenKind::Underscore => PatternKind::Wildcard,
             token => {
-                panic!("expected token to start type annotation, found {:?}", token)
+                return Err(ParseError {
+                    message: format!("expected token to start pattern, found {:?}", token),
+                })
             }
         };
 
@@ -229,6 +275,126 @@ impl<'a> Parser<'a> {
             inferred_type: None,
         })
     }
+
+    // Parses the default value for an element in a tuple or object pattern,
+    // e.g. `x = 5` in `[x = 5]`, if there is one.
+    fn parse_pattern_default(&mut self) -> Result<Option<Box<Expr>>, ParseError> {
+        match self.peek().unwrap_or(&EOF).kind {
+            TokenKind::Assign => {
+                self.next(); // consumes '='
+                Ok(Some(Box::new(self.parse_expr()?)))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    // Parses the patterns for a match with multiple scrutinees, e.g. `(0, _)`
+    // in `match (a, b) { (0, _) => ... }`.  The patterns are combined into a
+    // tuple pattern since the scrutinees are matched as a tuple.
+    pub(crate) fn parse_scrutinee_patterns(&mut self, count: usize) -> Result<Pattern, ParseError> {
+        let start = self.expect(TokenKind::LeftParen)?;
+
+        let patterns = self.parse_many(
+            |p| p.parse_pattern(),
+            TokenKind::Comma,
+            TokenKind::RightParen,
+        )?;
+
+        let end = self.expect(TokenKind::RightParen)?;
+
+        if patterns.len() != count {
+            return Err(ParseError {
+                message: format!(
+                    "Expected {count} patterns, one for each scrutinee, got {}",
+                    patterns.len()
+                ),
+            });
+        }
+
+        Ok(Pattern {
+            span: merge_spans(&start.span, &end.span),
+            kind: PatternKind::Tuple(TuplePat {
+                elems: patterns
+                    .into_iter()
+                    .map(|pattern| {
+                        Some(TuplePatElem {
+                            pattern,
+                            init: None,
+                        })
+                    })
+                    .collect(),
+                optional: false,
+            }),
+            inferred_type: None,
+        })
+    }
+
+    fn parse_num_or_range_pattern(
+        &mut self,
+        value: String,
+        span: &mut Span,
+    ) -> Result<PatternKind, ParseError> {
+        match self.peek().unwrap_or(&EOF).kind {
+            TokenKind::DotDot => {
+                self.next(); // consumes '..'
+                self.parse_range_pattern(Some(value), false, span)
+            }
+            TokenKind::DotDotEquals => {
+                self.next(); // consumes '..='
+                self.parse_range_pattern(Some(value), true, span)
+            }
+            _ => Ok(PatternKind::Lit(LitPat {
+                lit: Literal::Number(value),
+            })),
+        }
+    }
+
+    // Parses the end of a range pattern, the range operator has already been
+    // consumed.  The end is optional for exclusive ranges, e.g. `10..`.
+    fn parse_range_pattern(
+        &mut self,
+        start: Option<String>,
+        inclusive: bool,
+        span: &mut Span,
+    ) -> Result<PatternKind, ParseError> {
+        let next = self.peek().unwrap_or(&EOF).clone();
+        let end = match next.kind {
+            TokenKind::NumLit(value) => {
+                self.next();
+                Some(value)
+            }
+            TokenKind::Minus => {
+                self.next();
+                match self.next().unwrap_or(EOF.clone()).kind {
+                    TokenKind::NumLit(value) => Some(format!("-{value}")),
+                    _ => {
+                        return Err(ParseError {
+                            message: "expected number after '-'".to_string(),
+                        })
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        if end.is_some() {
+            *span = merge_spans(span, &next.span);
+        } else if inclusive {
+            return Err(ParseError {
+                message: "Inclusive ranges must have an end".to_string(),
+            });
+        } else if start.is_none() {
+            return Err(ParseError {
+                message: "Ranges must have a start or an end".to_string(),
+            });
+        }
+
+        Ok(PatternKind::Range(RangePat {
+            start,
+            end,
+            inclusive,
+        }))
+    }
 }
 
 #[cfg(test)]
@@ -271,11 +437,41 @@ mod tests {
         insta::assert_debug_snapshot!(parse("{x: {y: {z}}}"));
     }
 
+    #[test]
+    fn parse_object_patterns_with_keyword_keys() {
+        insta::assert_debug_snapshot!(parse("{type: t, if: i}"));
+    }
+
     #[test]
     fn parse_object_patterns_multiple_rest() {
         insta::assert_debug_snapshot!(parse("{...x, ...y, ...z}"));
     }
 
+    #[test]
+    fn parse_patterns_with_defaults() {
+        insta::assert_debug_snapshot!(parse("{x = 0, y: b = 1}"));
+        insta::assert_debug_snapshot!(parse("[a = 0, b]"));
+    }
+
+    #[test]
+    fn parse_range_patterns() {
+        insta::assert_debug_snapshot!(parse("0..10"));
+        insta::assert_debug_snapshot!(parse("-5..=5"));
+        insta::assert_debug_snapshot!(parse("..0"));
+        insta::assert_debug_snapshot!(parse("100.."));
+    }
+
+    #[test]
+    fn parse_inclusive_range_pattern_without_end() {
+        let mut parser = Parser::new("0..=");
+        assert_eq!(
+            parser.parse_pattern(),
+            Err(ParseError {
+                message: "Inclusive ranges must have an end".to_string()
+            })
+        );
+    }
+
     #[test]
     fn parse_wildcard() {
         insta::assert_debug_snapshot!(parse("_"));
@@ -290,4 +486,82 @@ mod tests {
     fn parse_mixed_patterns() {
         insta::assert_debug_snapshot!(parse(r#"{kind: "foo", bar: _, values: [head, ...tail]}"#));
     }
+
+    #[test]
+    fn parse_unterminated_object_pattern() {
+        let mut parser = Parser::new("{a, b");
+        assert_eq!(
+            parser.parse_pattern(),
+            Err(ParseError {
+                message: "expected comma or right brace".to_string()
+            })
+        );
+    }
+
+    // Malformed input should result in a ParseError instead of a panic.  The
+    // inputs are all made of complete tokens so that only the parser is
+    // exercised and not the scanner.
+    #[test]
+    fn parse_malformed_patterns_never_panics() {
+        let samples = [
+            "{kind: \"foo\", mut bar, baz = 5, qux: [head, ...tail], ...rest}",
+            "[a, , mut b = \"x\", ...c]",
+            "x is string",
+            "-5..=10",
+            "..0",
+            "{a: {b: [c, d is number]}}",
+        ];
+        let vocab = [
+            "{",
+            "}",
+            "[",
+            "]",
+            ",",
+            ":",
+            "=",
+            "...",
+            "..",
+            "..=",
+            "-",
+            "is",
+            "mut",
+            "x",
+            "string",
+            "number",
+            r#""a""#,
+            "5",
+            "true",
+            "null",
+            "undefined",
+            "_",
+        ];
+
+        let mut inputs = vec![];
+        for sample in samples {
+            let tokens: Vec<&str> = sample.split(' ').collect();
+            for i in 0..=tokens.len() {
+                inputs.push(tokens[..i].join(" "));
+            }
+        }
+        // xorshift so that the inputs are the same on every run.
+        let mut seed: u32 = 0x9e3779b9;
+        for _ in 0..2000 {
+            let mut tokens = vec![];
+            for _ in 0..(seed % 12) {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                tokens.push(vocab[seed as usize % vocab.len()]);
+            }
+            inputs.push(tokens.join(" "));
+        }
+
+        for input in inputs {
+            let result = std::panic::catch_unwind(|| {
+                let mut parser = Parser::new(&input);
+                let _ = parser.parse_pattern();
+            });
+            assert!(result.is_ok(), "parsing {input:?} panicked");
+        }
+    }
 }
@@ -32,7 +32,7 @@ pub enum Operator {
 
     // 14
     LogicalNot,
-    // BitwiseNot,
+    BitwiseNot,
     UnaryPlus,
     UnaryMinus,
     // PrefixIncrement,
@@ -56,9 +56,9 @@ pub enum Operator {
     Subtraction,
 
     // 10
-    // BitwiseLeftShift,
-    // BitwiseRightShift,
-    // BitwiseUnsignedRightShift,
+    BitwiseLeftShift,
+    BitwiseRightShift,
+    BitwiseUnsignedRightShift,
 
     // 9
     LessThan,
@@ -75,13 +75,13 @@ pub enum Operator {
     // StrictNotEquals,
 
     // 7
-    // BitwiseAnd,
+    BitwiseAnd,
 
     // 6
-    // BitwiseXor,
+    BitwiseXor,
 
     // 5
-    // BitwiseOr,
+    BitwiseOr,
 
     // 4
     LogicalAnd,
@@ -158,6 +158,7 @@ lazy_static! {
         table.insert(Operator::TemplateLiteral, OpInfo::new_postfix(17));
 
         table.insert(Operator::LogicalNot, OpInfo::new_prefix(14));
+        table.insert(Operator::BitwiseNot, OpInfo::new_prefix(14));
         table.insert(Operator::UnaryPlus, OpInfo::new_prefix(14));
         table.insert(Operator::UnaryMinus, OpInfo::new_prefix(14));
         table.insert(Operator::Typeof, OpInfo::new_prefix(14));
@@ -193,6 +194,19 @@ lazy_static! {
             OpInfo::new_infix(11, Associativity::Left),
         );
 
+        table.insert(
+            Operator::BitwiseLeftShift,
+            OpInfo::new_infix(10, Associativity::Left),
+        );
+        table.insert(
+            Operator::BitwiseRightShift,
+            OpInfo::new_infix(10, Associativity::Left),
+        );
+        table.insert(
+            Operator::BitwiseUnsignedRightShift,
+            OpInfo::new_infix(10, Associativity::Left),
+        );
+
         table.insert(
             Operator::LessThan,
             OpInfo::new_infix(9, Associativity::Left),
@@ -221,6 +235,21 @@ lazy_static! {
             OpInfo::new_infix(8, Associativity::Left),
         );
 
+        table.insert(
+            Operator::BitwiseAnd,
+            OpInfo::new_infix(7, Associativity::Left),
+        );
+
+        table.insert(
+            Operator::BitwiseXor,
+            OpInfo::new_infix(6, Associativity::Left),
+        );
+
+        table.insert(
+            Operator::BitwiseOr,
+            OpInfo::new_infix(5, Associativity::Left),
+        );
+
         table.insert(
             Operator::LogicalAnd,
             OpInfo::new_infix(4, Associativity::Left),
@@ -24,11 +24,7 @@ impl<'a> Scanner<'a> {
     /// Returns the next character without advancing the cursor.
     /// AKA "lookahead"
     pub fn peek(&self, lookahead: usize) -> Option<char> {
-        let start = self.cursor + lookahead;
-        let end = start + 1;
-        self.input
-            .get(start..end)
-            .map(|sub_str| sub_str.chars().next().unwrap())
+        self.input[self.cursor..].chars().nth(lookahead)
     }
 
     /// Returns true if further progress is not possible.
@@ -38,21 +34,28 @@ impl<'a> Scanner<'a> {
 
     /// Returns the next character (if available) and advances the cursor.
     pub fn pop(&mut self) -> Option<char> {
-        let start = self.cursor;
-        let end = start + 1;
-        match self.input.get(start..end) {
-            Some(str) => {
-                self.cursor += 1;
-                if str == "\n" {
-                    self.line += 1;
-                    self.column = 1;
-                } else {
-                    self.column += 1;
-                }
-
-                str.get(0..1).map(|sub_str| sub_str.chars().next().unwrap())
-            }
-            None => None,
+        let character = self.input[self.cursor..].chars().next()?;
+        // The cursor is a byte offset so that it can be used for spans.
+        self.cursor += character.len_utf8();
+        if character == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
         }
+
+        Some(character)
+    }
+
+    /// Returns the source text between `start` and `end`.
+    pub fn slice(&self, start: usize, end: usize) -> &'a str {
+        self.input.get(start..end).unwrap_or_default()
+    }
+
+    /// Returns true if there's a newline between `start` and `end`.
+    pub fn has_newline(&self, start: usize, end: usize) -> bool {
+        self.input
+            .get(start..end)
+            .is_some_and(|sub_str| sub_str.contains('\n'))
     }
 }
@@ -7,14 +7,24 @@ use crate::token::*;
 impl<'a> Parser<'a> {
     pub fn parse_script(&mut self) -> Result<Script, ParseError> {
         let mut stmts = Vec::new();
-        while self.peek().unwrap_or(&EOF).kind != TokenKind::Eof {
-            // TODO: attach comments to AST nodes
-            if let TokenKind::Comment(_) = &self.peek().unwrap_or(&EOF).kind {
-                self.next(); // consumes the comment
-                continue;
+        let mut pragmas = Vec::new();
+        loop {
+            // Comments after the last statement aren't attached to anything.
+            let leading_comments = self.parse_comments();
+            if stmts.is_empty() {
+                pragmas = Self::get_pragmas(&leading_comments);
             }
-            stmts.push(self.parse_stmt()?);
+            if self.peek().unwrap_or(&EOF).kind == TokenKind::Eof {
+                break;
+            }
+            let mut stmt = self.parse_stmt()?;
+            if let StmtKind::Decl(decl) = &mut stmt.kind {
+                decl.doc = Self::get_doc(&leading_comments);
+            }
+            stmt.leading_comments = leading_comments;
+            stmt.trailing_comment = self.parse_trailing_comment(stmt.span.end);
+            stmts.push(stmt);
         }
-        Ok(Script { stmts })
+        Ok(Script { stmts, pragmas })
     }
 }
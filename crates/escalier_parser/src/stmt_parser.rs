@@ -5,10 +5,110 @@ use crate::parser::*;
 use crate::token::*;
 
 impl<'a> Parser<'a> {
+    // Parses the attributes before a decl, e.g. `@deprecated("use bar instead")`.
+    pub(crate) fn parse_attrs(&mut self) -> Result<Vec<Attribute>, ParseError> {
+        let mut attrs = vec![];
+        while self.peek().unwrap_or(&EOF).kind == TokenKind::At {
+            let at = self.next().unwrap_or(EOF.clone()); // consumes '@'
+            let (name, mut end) = match self.next().unwrap_or(EOF.clone()) {
+                Token {
+                    kind: TokenKind::Identifier(name),
+                    span,
+                } => (name, span.end),
+                _ => {
+                    return Err(ParseError {
+                        message: "expected attribute name after `@`".to_string(),
+                    })
+                }
+            };
+            let args = match self.peek().unwrap_or(&EOF).kind {
+                TokenKind::LeftParen => {
+                    let args = self.parse_inside_parens(|p| {
+                        p.parse_many(|p| p.parse_expr(), TokenKind::Comma, TokenKind::RightParen)
+                    })?;
+                    end = self.scanner.cursor();
+                    args
+                }
+                _ => vec![],
+            };
+            attrs.push(Attribute {
+                name,
+                args,
+                span: Span {
+                    start: at.span.start,
+                    end,
+                },
+            });
+        }
+        Ok(attrs)
+    }
+
+    // Consumes `opaque` if it's followed by `type`, e.g. `opaque type UserId =
+    // string`, and returns its span.  `opaque` can be used as an identifier
+    // everywhere else.
+    pub(crate) fn parse_opaque(&mut self) -> Option<Span> {
+        let token = self.peek().unwrap_or(&EOF).clone();
+        if !matches!(&token.kind, TokenKind::Identifier(name) if name == "opaque") {
+            return None;
+        }
+        let backup = self.clone();
+        self.next(); // consumes 'opaque'
+        if self.peek().unwrap_or(&EOF).kind != TokenKind::Type {
+            self.restore(backup);
+            return None;
+        }
+        Some(token.span)
+    }
+
     pub fn parse_stmt(&mut self) -> Result<Stmt, ParseError> {
+        let attrs = self.parse_attrs()?;
         let mut token = self.peek().unwrap_or(&EOF).clone();
         let start = token.span.start;
 
+        let is_named_export = token.kind == TokenKind::Export && self.is_named_export();
+        if token.kind == TokenKind::Import || is_named_export {
+            if !attrs.is_empty() {
+                return Err(ParseError {
+                    message: "attributes can only be used on declarations".to_string(),
+                });
+            }
+            let (kind, span) = match is_named_export {
+                true => {
+                    self.next(); // consumes 'export'
+                    let (export, span) = self.parse_named_export()?;
+                    (StmtKind::Export(export), merge_spans(&token.span, &span))
+                }
+                false => {
+                    let (import, span) = self.parse_import()?;
+                    (StmtKind::Import(import), span)
+                }
+            };
+            return Ok(Stmt {
+                kind,
+                span,
+                inferred_type: None,
+                leading_comments: vec![],
+                trailing_comment: None,
+            });
+        }
+
+        let is_export = match &token.kind {
+            TokenKind::Export => {
+                self.next(); // consumes 'export'
+                token = self.peek().unwrap_or(&EOF).clone();
+                match &token.kind {
+                    TokenKind::Let | TokenKind::Var | TokenKind::Type | TokenKind::Declare => true,
+                    TokenKind::Identifier(name) if name == "opaque" => true,
+                    _ => {
+                        return Err(ParseError {
+                            message: "expected declaration after `export`".to_string(),
+                        })
+                    }
+                }
+            }
+            _ => false,
+        };
+
         // TODO: only allow `declare` in front of `let`
         let is_declare = match &token.kind {
             TokenKind::Declare => {
@@ -19,7 +119,75 @@ impl<'a> Parser<'a> {
             _ => false,
         };
 
+        let opaque_span = match is_declare {
+            true => None,
+            false => self.parse_opaque(),
+        };
+        if opaque_span.is_some() {
+            token = self.peek().unwrap_or(&EOF).clone();
+        }
+
+        let is_global =
+            is_declare && matches!(&token.kind, TokenKind::Identifier(name) if name == "global");
+        let is_ambient = is_declare && is_ambient_keyword(&token.kind);
+
+        if !attrs.is_empty()
+            && !is_global
+            && !is_ambient
+            && !matches!(
+                &token.kind,
+                TokenKind::Let | TokenKind::Var | TokenKind::Type
+            )
+        {
+            return Err(ParseError {
+                message: "attributes can only be used on declarations".to_string(),
+            });
+        }
+
         let stmt = match &token.kind {
+            _ if is_global => {
+                if is_export {
+                    return Err(ParseError {
+                        message: "`declare global` can't be exported".to_string(),
+                    });
+                }
+                self.next(); // consumes 'global'
+
+                let (decls, block_span) = self.parse_ambient_block("global")?;
+
+                let span = Span {
+                    start,
+                    end: block_span.end,
+                };
+
+                let decl = Decl {
+                    kind: DeclKind::GlobalDecl(GlobalDecl { decls }),
+                    span,
+                    is_export,
+                    doc: None,
+                    attrs,
+                };
+
+                Stmt {
+                    kind: StmtKind::Decl(decl),
+                    span,
+                    inferred_type: None,
+                    leading_comments: vec![],
+                    trailing_comment: None,
+                }
+            }
+            _ if is_ambient => {
+                let decl = self.parse_ambient_decl(start, is_export, attrs)?;
+                let span = decl.span;
+
+                Stmt {
+                    kind: StmtKind::Decl(decl),
+                    span,
+                    inferred_type: None,
+                    leading_comments: vec![],
+                    trailing_comment: None,
+                }
+            }
             TokenKind::Let | TokenKind::Var => {
                 let token = self.next().unwrap_or(EOF.clone()); // consumes 'let' or 'var'
 
@@ -43,6 +211,38 @@ impl<'a> Parser<'a> {
                     _ => None,
                 };
 
+                if self.peek().unwrap_or(&EOF).kind == TokenKind::Else {
+                    self.next(); // consumes 'else'
+
+                    let has_attrs = !attrs.is_empty();
+                    let expr = match (has_attrs, is_export, is_declare, is_var, type_ann, expr) {
+                        (false, false, false, false, None, Some(expr)) => expr,
+                        _ => {
+                            return Err(ParseError {
+                                message: "Expected `let <pattern> = <expr> else { ... }`"
+                                    .to_string(),
+                            })
+                        }
+                    };
+                    let alternate = self.parse_block()?;
+                    let span = Span {
+                        start,
+                        end: alternate.span.end,
+                    };
+
+                    return Ok(Stmt {
+                        kind: StmtKind::LetElse(LetElseStmt {
+                            pattern,
+                            expr,
+                            alternate,
+                        }),
+                        span,
+                        inferred_type: None,
+                        leading_comments: vec![],
+                        trailing_comment: None,
+                    });
+                }
+
                 let span = Span {
                     start,
                     end: if let Some(expr) = &expr {
@@ -63,6 +263,9 @@ impl<'a> Parser<'a> {
                         type_ann,
                     }),
                     span,
+                    is_export,
+                    doc: None,
+                    attrs,
                 };
 
                 // TODO: check invariants in semantic analysis pass
@@ -70,23 +273,18 @@ impl<'a> Parser<'a> {
                     kind: StmtKind::Decl(decl),
                     span,
                     inferred_type: None,
+                    leading_comments: vec![],
+                    trailing_comment: None,
                 }
             }
             TokenKind::For => {
                 self.next(); // consumes 'for'
 
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::LeftParen
-                );
+                self.expect(TokenKind::LeftParen)?;
                 let left = self.parse_pattern()?;
-                assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::In);
+                self.expect(TokenKind::In)?;
                 let right = self.parse_expr()?;
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightParen
-                );
-                assert_eq!(self.peek().unwrap_or(&EOF).kind, TokenKind::LeftBrace);
+                self.expect(TokenKind::RightParen)?;
                 let body = self.parse_block()?;
 
                 let span = merge_spans(&left.span, &body.span);
@@ -99,6 +297,8 @@ impl<'a> Parser<'a> {
                     }),
                     span,
                     inferred_type: None,
+                    leading_comments: vec![],
+                    trailing_comment: None,
                 }
             }
             TokenKind::Return => {
@@ -109,6 +309,8 @@ impl<'a> Parser<'a> {
                         kind: StmtKind::Return(ReturnStmt { arg: None }),
                         span: token.span,
                         inferred_type: None,
+                        leading_comments: vec![],
+                        trailing_comment: None,
                     },
                     _ => {
                         let arg = self.parse_expr()?;
@@ -118,6 +320,8 @@ impl<'a> Parser<'a> {
                             kind: StmtKind::Return(ReturnStmt { arg: Some(arg) }),
                             span,
                             inferred_type: None,
+                            leading_comments: vec![],
+                            trailing_comment: None,
                         }
                     }
                 }
@@ -125,7 +329,9 @@ impl<'a> Parser<'a> {
             TokenKind::Type => {
                 self.next(); // consumes 'type'
 
-                let name = match self.next().unwrap_or(EOF.clone()).kind {
+                let name_token = self.next().unwrap_or(EOF.clone());
+                let name_span = name_token.span;
+                let name = match name_token.kind {
                     TokenKind::Identifier(name) => name,
                     _ => {
                         return Err(ParseError {
@@ -136,23 +342,30 @@ impl<'a> Parser<'a> {
 
                 let type_params = self.maybe_parse_type_params()?;
 
-                assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Assign);
+                self.expect(TokenKind::Assign)?;
                 let type_ann = self.parse_type_ann()?;
-                let span = merge_spans(&token.span, &type_ann.span);
+                let span = merge_spans(&opaque_span.unwrap_or(token.span), &type_ann.span);
 
                 let decl = Decl {
                     kind: DeclKind::TypeDecl(TypeDecl {
                         name,
+                        name_span,
                         type_ann,
                         type_params,
+                        is_opaque: opaque_span.is_some(),
                     }),
                     span,
+                    is_export,
+                    doc: None,
+                    attrs,
                 };
 
                 Stmt {
                     kind: StmtKind::Decl(decl),
                     span,
                     inferred_type: None,
+                    leading_comments: vec![],
+                    trailing_comment: None,
                 }
             }
             _ => {
@@ -162,17 +375,190 @@ impl<'a> Parser<'a> {
                     kind: StmtKind::Expr(ExprStmt { expr }),
                     span,
                     inferred_type: None,
+                    leading_comments: vec![],
+                    trailing_comment: None,
                 }
             }
         };
 
         Ok(stmt)
     }
+
+    // Whether the `export` that's next is followed by specifiers, e.g.
+    // `export {a, b}` or `export type {Foo}`, instead of a decl.
+    fn is_named_export(&mut self) -> bool {
+        let backup = self.clone();
+        self.next(); // consumes 'export'
+        if self.peek().unwrap_or(&EOF).kind == TokenKind::Type {
+            self.next(); // consumes 'type'
+        }
+        let is_named_export = self.peek().unwrap_or(&EOF).kind == TokenKind::LeftBrace;
+        self.restore(backup);
+        is_named_export
+    }
+
+    // Parses the decl after `declare` for functions, classes, and modules,
+    // e.g. `declare fn parseInt(value: string) -> number`.  Nothing is
+    // emitted for these decls, they only describe values defined elsewhere.
+    fn parse_ambient_decl(
+        &mut self,
+        start: usize,
+        is_export: bool,
+        attrs: Vec<Attribute>,
+    ) -> Result<Decl, ParseError> {
+        let token = self.next().unwrap_or(EOF.clone()); // consumes 'fn', 'class', or 'module'
+
+        let name_token = self.next().unwrap_or(EOF.clone());
+        let name_span = name_token.span;
+        let name = match name_token.kind {
+            TokenKind::Identifier(name) => name,
+            _ => {
+                return Err(ParseError {
+                    message: "expected identifier".to_string(),
+                })
+            }
+        };
+
+        let (kind, end) = match &token.kind {
+            TokenKind::Fn => {
+                let type_params = self.maybe_parse_type_params()?;
+                let params = self.parse_type_ann_func_params()?;
+                self.expect(TokenKind::SingleArrow)?;
+                let ret = self.parse_type_ann()?;
+
+                let throws = match self.peek().unwrap_or(&EOF).kind {
+                    TokenKind::Throws => {
+                        self.next(); // consumes 'throws'
+                        Some(Box::new(self.parse_type_ann()?))
+                    }
+                    _ => None,
+                };
+
+                let end_span = match &throws {
+                    Some(throws) => throws.span,
+                    None => ret.span,
+                };
+                let span = merge_spans(&token.span, &end_span);
+
+                let type_ann = TypeAnn {
+                    kind: TypeAnnKind::Function(FunctionType {
+                        span,
+                        type_params,
+                        params,
+                        ret: Box::new(ret),
+                        throws,
+                    }),
+                    span,
+                    inferred_type: None,
+                };
+
+                let kind = DeclKind::FnDecl(FnDecl {
+                    name,
+                    name_span,
+                    type_ann,
+                });
+                (kind, span.end)
+            }
+            TokenKind::Class => {
+                let class = self.parse_class_def(token.span.start, true)?;
+                let end = class.span.end;
+                let kind = DeclKind::ClassDecl(ClassDecl {
+                    name,
+                    name_span,
+                    class,
+                });
+                (kind, end)
+            }
+            _ => {
+                let (decls, block_span) = self.parse_ambient_block("module")?;
+                let kind = DeclKind::ModuleDecl(ModuleDecl {
+                    name,
+                    name_span,
+                    decls,
+                });
+                (kind, block_span.end)
+            }
+        };
+
+        Ok(Decl {
+            kind,
+            span: Span { start, end },
+            is_export,
+            doc: None,
+            attrs,
+        })
+    }
+
+    // Parses the body of `declare global` and `declare module` decls.  The
+    // `let` decls inside of them are always `declare` decls and functions,
+    // classes, and modules can be declared without `declare`.
+    fn parse_ambient_block(&mut self, keyword: &str) -> Result<(Vec<Decl>, Span), ParseError> {
+        let open = self.expect(TokenKind::LeftBrace)?;
+        let mut decls = vec![];
+        loop {
+            let leading_comments = self.parse_comments();
+            if self.peek().unwrap_or(&EOF).kind == TokenKind::RightBrace {
+                break;
+            }
+
+            let backup = self.clone();
+            let attrs = self.parse_attrs()?;
+            let token = self.peek().unwrap_or(&EOF).clone();
+            let mut decl = match is_ambient_keyword(&token.kind) {
+                true => self.parse_ambient_decl(token.span.start, false, attrs)?,
+                false => {
+                    self.restore(backup);
+                    match self.parse_stmt()?.kind {
+                        StmtKind::Decl(decl) => decl,
+                        _ => {
+                            return Err(ParseError {
+                                message: format!("expected declaration inside `declare {keyword}`"),
+                            })
+                        }
+                    }
+                }
+            };
+            self.parse_trailing_comment(decl.span.end);
+
+            match &mut decl.kind {
+                DeclKind::VarDecl(VarDecl {
+                    is_declare,
+                    expr: None,
+                    type_ann: Some(_),
+                    ..
+                }) => *is_declare = true,
+                DeclKind::TypeDecl(_)
+                | DeclKind::FnDecl(_)
+                | DeclKind::ClassDecl(_)
+                | DeclKind::ModuleDecl(_) => (),
+                _ => {
+                    return Err(ParseError {
+                        message: format!("expected type, function, class, module, or `let` with a type annotation and no initializer inside `declare {keyword}`"),
+                    })
+                }
+            }
+            decl.doc = Self::get_doc(&leading_comments);
+            decls.push(decl);
+        }
+        let close = self.expect(TokenKind::RightBrace)?;
+
+        Ok((decls, merge_spans(&open.span, &close.span)))
+    }
 }
 
-// TODO: remove this function
-pub fn parse(input: &str) -> Result<Script, ParseError> {
+// `fn`, `class`, and `module` start ambient decls when they follow `declare`.
+fn is_ambient_keyword(kind: &TokenKind) -> bool {
+    match kind {
+        TokenKind::Fn | TokenKind::Class => true,
+        TokenKind::Identifier(name) => name == "module",
+        _ => false,
+    }
+}
+
+/// Parses `input` as a script.  JSX is only allowed if `options.jsx` is set.
+pub fn parse(input: &str, options: &CompilerOptions) -> Result<Script, ParseError> {
     let mut parser = Parser::new(input);
+    parser.jsx = options.jsx.is_some();
     parser.parse_script()
 }
 
@@ -218,6 +604,22 @@ mod tests {
         insta::assert_debug_snapshot!(parse(r#"let y = m*x + b"#));
     }
 
+    #[test]
+    fn parse_let_else() {
+        insta::assert_debug_snapshot!(parse(r#"let [x, ...rest] = items else { return 0 }"#));
+    }
+
+    #[test]
+    fn parse_let_else_with_type_annotation() {
+        let mut parser = Parser::new(r#"let [x]: number[] = items else { return 0 }"#);
+        assert_eq!(
+            parser.parse_stmt(),
+            Err(ParseError {
+                message: "Expected `let <pattern> = <expr> else { ... }`".to_string(),
+            })
+        );
+    }
+
     #[test]
     fn parse_let_with_type_annotation() {
         insta::assert_debug_snapshot!(parse(r#"let y: number = m*x + b"#));
@@ -229,6 +631,48 @@ mod tests {
         insta::assert_debug_snapshot!(parse(r#"declare let bar: fn () -> number"#));
     }
 
+    #[test]
+    fn parse_export() {
+        insta::assert_debug_snapshot!(parse(r#"export let x = 5"#));
+        insta::assert_debug_snapshot!(parse(r#"export type Point = {x: number, y: number}"#));
+    }
+
+    #[test]
+    fn parse_export_without_decl() {
+        let mut parser = Parser::new(r#"export x + y"#);
+        assert_eq!(
+            parser.parse_stmt(),
+            Err(ParseError {
+                message: "expected declaration after `export`".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_imports() {
+        insta::assert_debug_snapshot!(parse(r#"import {a, b as c} from "foo""#));
+        insta::assert_debug_snapshot!(parse(r#"import type {Point} from "geometry""#));
+        insta::assert_debug_snapshot!(parse(r#"import {type Point, distance} from "geometry""#));
+    }
+
+    #[test]
+    fn parse_named_exports() {
+        insta::assert_debug_snapshot!(parse(r#"export {a, b as c}"#));
+        insta::assert_debug_snapshot!(parse(r#"export type {Point}"#));
+        insta::assert_debug_snapshot!(parse(r#"export {type Point, distance}"#));
+    }
+
+    #[test]
+    fn parse_import_with_attrs() {
+        let mut parser = Parser::new(r#"@deprecated import {a} from "foo""#);
+        assert_eq!(
+            parser.parse_stmt(),
+            Err(ParseError {
+                message: "attributes can only be used on declarations".to_string(),
+            })
+        );
+    }
+
     #[test]
     fn parse_let_with_destructuring() {
         insta::assert_debug_snapshot!(parse(r#"let {x, y} = point"#));
@@ -306,6 +750,12 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn parse_type_alias_with_type_constructor_params() {
+        insta::assert_debug_snapshot!(parse(r#"type MapOver<F<_>, T> = F<T>"#));
+        insta::assert_debug_snapshot!(parse(r#"type Apply<F<_, _>: Foo> = F<number, string>"#));
+    }
+
     #[test]
     fn parse_var_decls() {
         insta::assert_debug_snapshot!(parse(r#"let mut p = {x: 5, y: 10}"#));
@@ -343,6 +793,183 @@ mod tests {
             }
             "#
         ));
+
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            // Copyright (c) 2023 Escalier
+            type Point = {x: number, y: number}
+            // comments after the last statement are ignored
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_doc_comments() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            /// A point in 2D space.
+            ///
+            /// Both coordinates are in pixels.
+            type Point = {x: number, y: number}
+            // not a doc comment
+            //// also not a doc comment
+            let origin: Point = {x: 0, y: 0}
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_pragmas() {
+        let mut parser = Parser::new(
+            r#"// @strict-returns off
+/// @deprecated isn't a pragma
+// @jsxRuntime classic @jsx h
+// see @jsx for details
+let x = 5
+// @index-access unchecked
+"#,
+        );
+        let script = parser.parse_script().unwrap();
+        assert_eq!(
+            script.pragmas,
+            vec![
+                Pragma {
+                    name: "strict-returns".to_string(),
+                    value: Some("off".to_string()),
+                    span: Span { start: 3, end: 22 },
+                },
+                Pragma {
+                    name: "jsxRuntime".to_string(),
+                    value: Some("classic".to_string()),
+                    span: Span { start: 57, end: 76 },
+                },
+                Pragma {
+                    name: "jsx".to_string(),
+                    value: Some("h".to_string()),
+                    span: Span { start: 77, end: 83 },
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_attributes() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            @deprecated("use bar instead")
+            export let foo = 5
+            @deprecated
+            type Foo = number
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_attributes_on_non_decls_fails() {
+        let mut parser = Parser::new("@deprecated foo()");
+        let result = parser.parse_script();
+        assert_eq!(
+            result,
+            Err(ParseError {
+                message: "attributes can only be used on declarations".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_declare_global() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            declare global {
+                /// The current environment.
+                type Env = {mode: string}
+                let env: Env
+            }
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_declare_global_with_initializer_fails() {
+        let mut parser = Parser::new("declare global { let env = 5 }");
+        let result = parser.parse_script();
+        assert_eq!(
+            result,
+            Err(ParseError {
+                message: "expected type, function, class, module, or `let` with a type annotation and no initializer inside `declare global`".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_declare_fn() {
+        insta::assert_debug_snapshot!(parse(
+            "declare fn parseInt(value: string, radix?: number) -> number"
+        ));
+    }
+
+    #[test]
+    fn parse_declare_class() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            declare class Box<T> {
+                value: T
+                fn constructor(mut self, value: T)
+                fn map<U>(self, f: fn(value: T) -> U) -> Box<U>
+            }
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_declare_module() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            declare module Intl {
+                type Locale = string
+                let defaultLocale: Locale
+                fn getLocales() -> Locale[]
+            }
+            "#
+        ));
+    }
+
+    #[test]
+    fn parse_declare_class_method_without_return_type_fails() {
+        let mut parser = Parser::new("declare class Foo { fn bar(self) }");
+        let result = parser.parse_script();
+        assert_eq!(
+            result,
+            Err(ParseError {
+                message: "methods in `declare class` must have a return type".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn parse_jsx_when_jsx_is_disabled_fails() {
+        let options = CompilerOptions {
+            jsx: None,
+            ..Default::default()
+        };
+        assert!(super::parse("let a = <div />", &CompilerOptions::default()).is_ok());
+        assert_eq!(
+            super::parse("let a = <div />", &options),
+            Err(ParseError {
+                message: "JSX isn't enabled".to_string()
+            })
+        );
+        assert!(super::parse("let a = b < c", &options).is_ok());
+    }
+
+    #[test]
+    fn parse_opaque_type() {
+        insta::assert_debug_snapshot!(parse(
+            r#"
+            export opaque type UserId = string
+            let opaque = 5
+            "#
+        ));
     }
 
     #[test]
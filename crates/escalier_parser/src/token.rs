@@ -4,10 +4,14 @@ use escalier_ast::*;
 pub enum TokenKind {
     Identifier(String), // [a-zA-Z_][a-zA-Z0-9_]*
     Comment(String),
+    // Lexing errors are returned as tokens so that the parser can report them
+    // as a ParseError when it encounters them.
+    Error(String),
 
     // Literals
     BoolLit(bool),
     NumLit(String),
+    BigIntLit(String),
     StrLit(String),
     StrTemplateLit {
         parts: Vec<Token>, // This should only contain StrLit tokens
@@ -18,6 +22,7 @@ pub enum TokenKind {
 
     // Types
     Number,
+    BigInt,
     Boolean,
     String,
     Symbol,
@@ -70,6 +75,7 @@ pub enum TokenKind {
     Times,
     Divide,
     Modulo,
+    Power,
 
     // Comparison Operators
     Equals,
@@ -84,6 +90,15 @@ pub enum TokenKind {
     And,
     Not,
 
+    // Bitwise Operators, `&` and `|` are `Ampersand` and `Pipe`
+    Caret,
+    Tilde,
+    LeftShift,
+    // `>>` and `>>>` are lexed as separate `>` tokens since they can also close
+    // nested type args.  The expression parser combines them into these.
+    RightShift,
+    UnsignedRightShift,
+
     // Assignment Operators
     Assign,
     PlusAssign,
@@ -108,10 +123,12 @@ pub enum TokenKind {
     Question,
     QuestionDot, // used for optional chaining
     Dot,
-    DotDot,    // used for ranges
-    DotDotDot, // used for rest/spread
+    DotDot,       // used for ranges
+    DotDotEquals, // used for inclusive ranges
+    DotDotDot,    // used for rest/spread
     Pipe,
     Ampersand,
+    At, // used for attributes
 
     Eof,
 }
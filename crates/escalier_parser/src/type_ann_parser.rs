@@ -50,6 +50,14 @@ impl<'a> Parser<'a> {
                 self.next();
                 TypeAnnKind::Number
             }
+            TokenKind::BigIntLit(value) => {
+                self.next();
+                TypeAnnKind::BigIntLit(value)
+            }
+            TokenKind::BigInt => {
+                self.next();
+                TypeAnnKind::BigInt
+            }
             TokenKind::StrLit(value) => {
                 self.next();
                 TypeAnnKind::StrLit(value)
@@ -92,12 +100,15 @@ impl<'a> Parser<'a> {
                     .kind
                     != TokenKind::RightBrace
                 {
-                    match self
+                    let token = self
                         .next_with_mode(IdentMode::PropName)
-                        .unwrap_or(EOF.clone())
-                        .kind
-                    {
+                        .unwrap_or(EOF.clone());
+                    match token.kind {
                         TokenKind::Identifier(name) => {
+                            let name = PropName::Ident(Ident {
+                                name,
+                                span: token.span,
+                            });
                             let optional =
                                 if self.peek().unwrap_or(&EOF).kind == TokenKind::Question {
                                     self.next().unwrap_or(EOF.clone());
@@ -105,7 +116,7 @@ impl<'a> Parser<'a> {
                                 } else {
                                     false
                                 };
-                            assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Colon);
+                            self.expect(TokenKind::Colon)?;
 
                             let type_span = self.peek().unwrap_or(&EOF).span;
                             let prop = match self.peek().unwrap_or(&EOF).kind {
@@ -114,10 +125,7 @@ impl<'a> Parser<'a> {
 
                                     // TODO - `params` should only be `self`
                                     let params = self.parse_type_ann_func_params()?;
-                                    assert_eq!(
-                                        self.next().unwrap_or(EOF.clone()).kind,
-                                        TokenKind::SingleArrow
-                                    );
+                                    self.expect(TokenKind::SingleArrow)?;
                                     let ret = self.parse_type_ann()?;
                                     let type_span = merge_spans(&type_span, &ret.span);
 
@@ -148,10 +156,7 @@ impl<'a> Parser<'a> {
 
                                     // TODO - `params` should only be `mut self, value`
                                     let params = self.parse_type_ann_func_params()?;
-                                    assert_eq!(
-                                        self.next().unwrap_or(EOF.clone()).kind,
-                                        TokenKind::SingleArrow
-                                    );
+                                    self.expect(TokenKind::SingleArrow)?;
                                     let ret = self.parse_type_ann()?;
                                     let type_span = merge_spans(&type_span, &ret.span);
 
@@ -196,81 +201,42 @@ impl<'a> Parser<'a> {
                             props.push(prop);
                         }
                         TokenKind::LeftBracket => {
-                            let key = self.parse_type_ann()?;
-                            assert_eq!(
-                                self.next().unwrap_or_else(|| EOF.clone()).kind,
-                                TokenKind::RightBracket
-                            );
-
-                            let mut optional: Option<MappedModifier> = None;
-                            if self.peek().unwrap_or(&EOF).kind == TokenKind::Plus {
-                                self.next(); // consume '+'
-                                assert_eq!(
-                                    self.next().unwrap_or(EOF.clone()).kind,
-                                    TokenKind::Question
-                                );
-                                optional = Some(MappedModifier::Add);
-                            } else if self.peek().unwrap_or(&EOF).kind == TokenKind::Minus {
-                                self.next(); // consume '-'
-                                assert_eq!(
-                                    self.next().unwrap_or(EOF.clone()).kind,
-                                    TokenKind::Question
-                                );
-                                optional = Some(MappedModifier::Remove);
-                            }
-
-                            assert_eq!(
-                                self.next().unwrap_or_else(|| EOF.clone()).kind,
-                                TokenKind::Colon
-                            );
-                            let value = self.parse_type_ann()?;
-
-                            assert_eq!(
-                                self.next().unwrap_or_else(|| EOF.clone()).kind,
-                                TokenKind::For
-                            );
-
-                            let target_token = self.next().unwrap_or_else(|| EOF.clone());
-                            let target = match target_token.kind {
-                                TokenKind::Identifier(name) => name,
+                            // Keys that are property accesses, e.g.
+                            // `[Symbol.iterator]`, can't be parsed as types so
+                            // they're parsed as expressions instead.
+                            let backup = self.clone();
+                            match self.parse_type_ann() {
+                                Ok(key)
+                                    if self.peek().unwrap_or(&EOF).kind
+                                        == TokenKind::RightBracket =>
+                                {
+                                    self.next(); // consumes ']'
+                                    props.push(self.parse_mapped_or_computed_prop_type(key)?);
+                                }
                                 _ => {
-                                    return Err(ParseError {
-                                        message: "target must be an identifier".to_string(),
-                                    })
+                                    self.restore(backup);
+                                    let key = self.parse_expr()?;
+                                    self.expect(TokenKind::RightBracket)?;
+                                    props.push(self.parse_computed_prop_type(key)?);
                                 }
-                            };
-
-                            assert_eq!(
-                                self.next().unwrap_or_else(|| EOF.clone()).kind,
-                                TokenKind::In
-                            );
-
-                            let source = self.parse_type_ann()?; // should expand to a union of valid key types
-
-                            props.push(ObjectProp::Mapped(Mapped {
-                                key: Box::new(key),
-                                value: Box::new(value),
-                                target,
-                                source: Box::new(source),
-                                optional,
-                                // TODO: handle 'if' clause
-                                check: None,
-                                extends: None,
-                            }))
+                            }
                         }
                         TokenKind::Fn => {
-                            match self.peek().unwrap_or(&EOF).kind.clone() {
+                            // Method names can be keywords, e.g. `fn catch(self)`.
+                            match self
+                                .peek_with_mode(IdentMode::PropName)
+                                .unwrap_or(&EOF)
+                                .kind
+                                .clone()
+                            {
                                 // Method
-                                TokenKind::Identifier(name) => {
-                                    self.next(); // consume identifier
+                                TokenKind::Identifier(_) | TokenKind::LeftBracket => {
+                                    let name = self.parse_method_type_name()?;
 
                                     let type_params = self.maybe_parse_type_params()?;
 
                                     let (params, mutates) = self.parse_type_ann_method_params()?;
-                                    assert_eq!(
-                                        self.next().unwrap_or(EOF.clone()).kind,
-                                        TokenKind::SingleArrow
-                                    );
+                                    self.expect(TokenKind::SingleArrow)?;
                                     let ret = self.parse_type_ann()?;
                                     let throws = match self.peek().unwrap_or(&EOF).kind {
                                         TokenKind::Throws => {
@@ -300,10 +266,7 @@ impl<'a> Parser<'a> {
                                 TokenKind::LeftParen => {
                                     let type_params = self.maybe_parse_type_params()?;
                                     let params = self.parse_type_ann_func_params()?;
-                                    assert_eq!(
-                                        self.next().unwrap_or(EOF.clone()).kind,
-                                        TokenKind::SingleArrow
-                                    );
+                                    self.expect(TokenKind::SingleArrow)?;
                                     let ret = self.parse_type_ann()?;
                                     let throws = match self.peek().unwrap_or(&EOF).kind {
                                         TokenKind::Throws => {
@@ -344,25 +307,13 @@ impl<'a> Parser<'a> {
                                 }
                             };
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::LeftParen
-                            );
+                            self.expect(TokenKind::LeftParen)?;
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::Identifier("self".to_string())
-                            );
+                            self.expect(TokenKind::Identifier("self".to_string()))?;
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::RightParen
-                            );
+                            self.expect(TokenKind::RightParen)?;
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::SingleArrow
-                            );
+                            self.expect(TokenKind::SingleArrow)?;
 
                             let ret = self.parse_type_ann()?;
 
@@ -382,23 +333,17 @@ impl<'a> Parser<'a> {
                                 }
                             };
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::LeftParen
-                            );
+                            self.expect(TokenKind::LeftParen)?;
 
-                            assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Mut,);
+                            self.expect(TokenKind::Mut)?;
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::Identifier("self".to_string())
-                            );
+                            self.expect(TokenKind::Identifier("self".to_string()))?;
 
-                            assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Comma);
+                            self.expect(TokenKind::Comma)?;
 
                             let pattern = self.parse_pattern()?;
 
-                            assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Colon);
+                            self.expect(TokenKind::Colon)?;
 
                             let param = TypeAnnFuncParam {
                                 pattern,
@@ -406,19 +351,17 @@ impl<'a> Parser<'a> {
                                 optional: false,
                             };
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::RightParen
-                            );
+                            self.expect(TokenKind::RightParen)?;
 
-                            assert_eq!(
-                                self.next().unwrap_or(EOF.clone()).kind,
-                                TokenKind::SingleArrow
-                            );
+                            self.expect(TokenKind::SingleArrow)?;
 
                             let ret = self.parse_type_ann()?;
 
-                            assert_eq!(ret.kind, TypeAnnKind::Undefined);
+                            if ret.kind != TypeAnnKind::Undefined {
+                                return Err(ParseError {
+                                    message: "setters must return undefined".to_string(),
+                                });
+                            }
 
                             props.push(ObjectProp::Setter(SetterType {
                                 span,
@@ -450,10 +393,7 @@ impl<'a> Parser<'a> {
                 }
 
                 span = merge_spans(&span, &self.peek().unwrap_or(&EOF).span);
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBrace
-                );
+                self.expect(TokenKind::RightBrace)?;
 
                 TypeAnnKind::Object(props)
             }
@@ -461,22 +401,46 @@ impl<'a> Parser<'a> {
                 self.next(); // consumes '['
                 let mut elems: Vec<TypeAnn> = vec![];
 
+                let mut label_count = 0;
+
                 while self.peek().unwrap_or(&EOF).kind != TokenKind::RightBracket {
-                    if self.peek().unwrap_or(&EOF).kind == TokenKind::DotDotDot {
-                        let token = self.next().ok_or(ParseError {
-                            message: "expected '...'".to_string(),
-                        })?;
-                        let type_ann = self.parse_type_ann()?;
-                        let span = merge_spans(&token.span, &type_ann.span);
+                    let start = self.peek().unwrap_or(&EOF).span;
+                    let is_rest = self.peek().unwrap_or(&EOF).kind == TokenKind::DotDotDot;
+                    if is_rest {
+                        self.next(); // consumes '...'
+                    }
 
-                        elems.push(TypeAnn {
-                            kind: TypeAnnKind::Rest(Box::new(type_ann)),
-                            span,
+                    // Elements can have labels, e.g. `[x: number, ...rest: T]`.
+                    let mut label: Option<String> = None;
+                    if let TokenKind::Identifier(name) = &self.peek().unwrap_or(&EOF).kind {
+                        let name = name.to_owned();
+                        let backup = self.clone();
+                        self.next(); // consumes identifier
+                        if self.peek().unwrap_or(&EOF).kind == TokenKind::Colon {
+                            self.next(); // consumes ':'
+                            label = Some(name);
+                        } else {
+                            self.restore(backup);
+                        }
+                    }
+
+                    let mut elem = self.parse_type_ann()?;
+                    if is_rest {
+                        elem = TypeAnn {
+                            span: merge_spans(&start, &elem.span),
+                            kind: TypeAnnKind::Rest(Box::new(elem)),
                             inferred_type: None,
-                        });
-                    } else {
-                        elems.push(self.parse_type_ann()?);
+                        };
                     }
+                    if let Some(label) = label {
+                        label_count += 1;
+                        elem = TypeAnn {
+                            span: merge_spans(&start, &elem.span),
+                            kind: TypeAnnKind::Labeled(label, Box::new(elem)),
+                            inferred_type: None,
+                        };
+                    }
+                    elems.push(elem);
 
                     if self.peek().unwrap_or(&EOF).kind == TokenKind::Comma {
                         self.next(); // consume the ','
@@ -486,10 +450,14 @@ impl<'a> Parser<'a> {
                 }
 
                 span = merge_spans(&span, &self.peek().unwrap_or(&EOF).span);
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBracket
-                );
+                self.expect(TokenKind::RightBracket)?;
+
+                if label_count > 0 && label_count < elems.len() {
+                    return Err(ParseError {
+                        message: "tuple elements must either all have labels or none of them"
+                            .to_string(),
+                    });
+                }
 
                 TypeAnnKind::Tuple(elems)
             }
@@ -497,9 +465,55 @@ impl<'a> Parser<'a> {
                 let atom = self.parse_inside_parens(|p| p.parse_type_ann())?;
                 return Ok(atom);
             }
+            TokenKind::Identifier(ident) if ident == "readonly" => {
+                self.next(); // consumes 'readonly'
+
+                // `readonly` binds more loosely than `[]` but more tightly than
+                // `|` and `&`, e.g. `readonly number[] | string`.
+                let precedence = OpInfo::new_postfix(12).normalized_prec() - 1;
+                let type_ann = self.parse_type_ann_with_precedence(precedence)?;
+                if !matches!(type_ann.kind, TypeAnnKind::Array(_) | TypeAnnKind::Tuple(_)) {
+                    return Err(ParseError {
+                        message: "`readonly` can only be used with array and tuple types"
+                            .to_string(),
+                    });
+                }
+                span = merge_spans(&span, &type_ann.span);
+                TypeAnnKind::Readonly(Box::new(type_ann))
+            }
+            TokenKind::Identifier(ident) if ident == "unique" => {
+                self.next(); // consumes 'unique'
+                let token = self.next().unwrap_or(EOF.clone());
+                if token.kind != TokenKind::Symbol {
+                    return Err(ParseError {
+                        message: "expected 'symbol' after 'unique'".to_string(),
+                    });
+                }
+                span = merge_spans(&span, &token.span);
+                TypeAnnKind::UniqueSymbol
+            }
             TokenKind::Identifier(ident) => {
                 self.next(); // consumes identifier
 
+                // Types inside of `declare module` decls are referenced by
+                // their qualified names, e.g. `Intl.Locale`.
+                let mut ident = ident;
+                while self.peek().unwrap_or(&EOF).kind == TokenKind::Dot {
+                    self.next(); // consumes '.'
+                    let token = self.next().unwrap_or(EOF.clone());
+                    match token.kind {
+                        TokenKind::Identifier(name) => {
+                            ident = format!("{ident}.{name}");
+                            span = merge_spans(&span, &token.span);
+                        }
+                        _ => {
+                            return Err(ParseError {
+                                message: "expected identifier after '.'".to_string(),
+                            })
+                        }
+                    }
+                }
+
                 if self.peek().unwrap_or(&EOF).kind == TokenKind::LessThan {
                     self.next().unwrap_or(EOF.clone());
                     let mut params: Vec<TypeAnn> = vec![];
@@ -515,10 +529,7 @@ impl<'a> Parser<'a> {
                     }
 
                     span = merge_spans(&span, &self.peek().unwrap_or(&EOF).span);
-                    assert_eq!(
-                        self.next().unwrap_or(EOF.clone()).kind,
-                        TokenKind::GreaterThan
-                    );
+                    self.expect(TokenKind::GreaterThan)?;
 
                     TypeAnnKind::TypeRef(ident, Some(params))
                 } else {
@@ -530,10 +541,7 @@ impl<'a> Parser<'a> {
 
                 let type_params = self.maybe_parse_type_params()?;
                 let params = self.parse_type_ann_func_params()?;
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::SingleArrow
-                );
+                self.expect(TokenKind::SingleArrow)?;
                 let return_type = self.parse_type_ann()?;
 
                 let throws = match self.peek().unwrap_or(&EOF).kind {
@@ -568,19 +576,57 @@ impl<'a> Parser<'a> {
             TokenKind::TypeOf => {
                 self.next(); // consumes 'typeof'
 
-                // TODO: support qualified identifiers, e.g. Foo.Bar.Baz
                 let arg = self.next().unwrap_or(EOF.clone());
 
-                if let TokenKind::Identifier(name) = arg.kind {
-                    TypeAnnKind::TypeOf(Ident {
-                        name,
+                let mut expr = if let TokenKind::Identifier(name) = arg.kind {
+                    Expr {
+                        kind: ExprKind::Ident(Ident {
+                            name,
+                            span: arg.span,
+                        }),
                         span: arg.span,
-                    })
+                        inferred_type: None,
+                    }
                 } else {
                     return Err(ParseError {
                         message: "expected identifier".to_string(),
                     });
+                };
+
+                // Qualified identifiers, e.g. Foo.Bar.Baz
+                while self.peek().unwrap_or(&EOF).kind == TokenKind::Dot {
+                    self.next(); // consumes '.'
+
+                    let prop = self
+                        .next_with_mode(IdentMode::PropName)
+                        .unwrap_or(EOF.clone());
+                    let name = match prop.kind {
+                        TokenKind::Identifier(name) => name,
+                        _ => {
+                            return Err(ParseError {
+                                message: "expected identifier".to_string(),
+                            })
+                        }
+                    };
+
+                    let expr_span = merge_spans(&expr.span, &prop.span);
+                    expr = Expr {
+                        kind: ExprKind::Member(Member {
+                            object: Box::new(expr),
+                            property: MemberProp::Ident(Ident {
+                                name,
+                                span: prop.span,
+                            }),
+                            opt_chain: false,
+                        }),
+                        span: expr_span,
+                        inferred_type: None,
+                    };
                 }
+
+                span = merge_spans(&span, &expr.span);
+
+                TypeAnnKind::TypeOf(Box::new(expr))
             }
             TokenKind::Infer => {
                 self.next(); // consumes 'infer'
@@ -600,28 +646,16 @@ impl<'a> Parser<'a> {
             TokenKind::Match => {
                 self.next(); // consumes 'match'
 
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::LeftParen
-                );
+                self.expect(TokenKind::LeftParen)?;
                 let matchable = self.parse_type_ann()?;
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightParen
-                );
+                self.expect(TokenKind::RightParen)?;
 
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::LeftBrace
-                );
+                self.expect(TokenKind::LeftBrace)?;
 
                 let mut cases: Vec<MatchTypeCase> = vec![];
                 while self.peek().unwrap_or(&EOF).kind != TokenKind::RightBrace {
                     let extends = self.parse_type_ann()?;
-                    assert_eq!(
-                        self.next().unwrap_or(EOF.clone()).kind,
-                        TokenKind::DoubleArrow
-                    );
+                    self.expect(TokenKind::DoubleArrow)?;
                     let true_type = self.parse_type_ann()?;
 
                     cases.push(MatchTypeCase {
@@ -644,7 +678,9 @@ impl<'a> Parser<'a> {
                 })
             }
             token => {
-                panic!("expected token to start type annotation, found {:?}", token)
+                return Err(ParseError {
+                    message: format!("expected token to start type annotation, found {:?}", token),
+                })
             }
         };
 
@@ -658,10 +694,7 @@ impl<'a> Parser<'a> {
     }
 
     pub fn parse_type_ann_func_params(&mut self) -> Result<Vec<TypeAnnFuncParam>, ParseError> {
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftParen
-        );
+        self.expect(TokenKind::LeftParen)?;
 
         let mut params: Vec<TypeAnnFuncParam> = Vec::new();
         while self.peek().unwrap_or(&EOF).kind != TokenKind::RightParen {
@@ -674,7 +707,7 @@ impl<'a> Parser<'a> {
                 false
             };
 
-            assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Colon);
+            self.expect(TokenKind::Colon)?;
 
             params.push(TypeAnnFuncParam {
                 pattern,
@@ -689,17 +722,18 @@ impl<'a> Parser<'a> {
                 TokenKind::Comma => {
                     self.next().unwrap_or(EOF.clone());
                 }
-                _ => panic!(
-                    "Expected comma or right paren, got {:?}",
-                    self.peek().unwrap_or(&EOF)
-                ),
+                _ => {
+                    return Err(ParseError {
+                        message: format!(
+                            "Expected comma or right paren, got {:?}",
+                            self.peek().unwrap_or(&EOF)
+                        ),
+                    })
+                }
             }
         }
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightParen
-        );
+        self.expect(TokenKind::RightParen)?;
 
         Ok(params)
     }
@@ -707,10 +741,7 @@ impl<'a> Parser<'a> {
     pub fn parse_type_ann_method_params(
         &mut self,
     ) -> Result<(Vec<TypeAnnFuncParam>, bool), ParseError> {
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftParen
-        );
+        self.expect(TokenKind::LeftParen)?;
 
         let mutates = if let TokenKind::Mut = self.peek().unwrap_or(&EOF).kind {
             self.next(); // consume 'mut'
@@ -719,10 +750,7 @@ impl<'a> Parser<'a> {
             false
         };
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::Identifier("self".to_string())
-        );
+        self.expect(TokenKind::Identifier("self".to_string()))?;
 
         if self.peek().unwrap_or(&EOF).kind == TokenKind::Comma {
             self.next(); // consume ','
@@ -739,7 +767,7 @@ impl<'a> Parser<'a> {
                 false
             };
 
-            assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Colon);
+            self.expect(TokenKind::Colon)?;
 
             params.push(TypeAnnFuncParam {
                 pattern,
@@ -754,17 +782,18 @@ impl<'a> Parser<'a> {
                 TokenKind::Comma => {
                     self.next().unwrap_or(EOF.clone());
                 }
-                _ => panic!(
-                    "Expected comma or right paren, got {:?}",
-                    self.peek().unwrap_or(&EOF)
-                ),
+                _ => {
+                    return Err(ParseError {
+                        message: format!(
+                            "Expected comma or right paren, got {:?}",
+                            self.peek().unwrap_or(&EOF)
+                        ),
+                    })
+                }
             }
         }
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightParen
-        );
+        self.expect(TokenKind::RightParen)?;
 
         Ok((params, mutates))
     }
@@ -795,10 +824,7 @@ impl<'a> Parser<'a> {
                     _ => {
                         let index_type = self.parse_type_ann()?;
                         let merged_span = merge_spans(&lhs.span, &index_type.span);
-                        assert_eq!(
-                            self.next().unwrap_or(EOF.clone()).kind,
-                            TokenKind::RightBracket
-                        );
+                        self.expect(TokenKind::RightBracket)?;
                         TypeAnn {
                             kind: TypeAnnKind::IndexedAccess(Box::new(lhs), Box::new(index_type)),
                             span: merged_span,
@@ -807,7 +833,11 @@ impl<'a> Parser<'a> {
                     }
                 }
             }
-            _ => panic!("unexpected token: {:?}", token),
+            _ => {
+                return Err(ParseError {
+                    message: format!("unexpected token: {:?}", token),
+                })
+            }
         };
 
         Ok(type_ann)
@@ -816,6 +846,13 @@ impl<'a> Parser<'a> {
     fn parse_type_ann_with_precedence(
         &mut self,
         precedence: Precedence,
+    ) -> Result<TypeAnn, ParseError> {
+        self.with_nesting(|p| p.parse_type_ann_with_precedence_inner(precedence))
+    }
+
+    fn parse_type_ann_with_precedence_inner(
+        &mut self,
+        precedence: Precedence,
     ) -> Result<TypeAnn, ParseError> {
         let mut lhs = self.parse_type_ann_atom()?;
 
@@ -908,7 +945,11 @@ impl<'a> Parser<'a> {
                     TokenKind::Times => BinaryOp::Times,
                     TokenKind::Divide => BinaryOp::Divide,
                     TokenKind::Modulo => BinaryOp::Modulo,
-                    _ => panic!("unexpected token: {:?}", token),
+                    _ => {
+                        return Err(ParseError {
+                            message: format!("unexpected token: {:?}", token),
+                        })
+                    }
                 };
 
                 let rhs = self.parse_type_ann_with_precedence(precedence)?;
@@ -934,41 +975,23 @@ impl<'a> Parser<'a> {
         let span = self.peek().unwrap_or(&EOF).span;
         self.next(); // consumes 'if'
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftParen
-        );
+        self.expect(TokenKind::LeftParen)?;
         let check = self.parse_type_ann()?;
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Colon);
+        self.expect(TokenKind::Colon)?;
         let extends = self.parse_type_ann()?;
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightParen
-        );
+        self.expect(TokenKind::RightParen)?;
 
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::LeftBrace
-        );
+        self.expect(TokenKind::LeftBrace)?;
         let true_type = self.parse_type_ann()?;
-        assert_eq!(
-            self.next().unwrap_or(EOF.clone()).kind,
-            TokenKind::RightBrace
-        );
-        assert_eq!(self.next().unwrap_or(EOF.clone()).kind, TokenKind::Else);
+        self.expect(TokenKind::RightBrace)?;
+        self.expect(TokenKind::Else)?;
 
         let false_type = match self.peek().unwrap_or(&EOF).kind {
             TokenKind::If => self.parse_conditional_type()?,
             _ => {
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::LeftBrace
-                );
+                self.expect(TokenKind::LeftBrace)?;
                 let false_type = self.parse_type_ann()?;
-                assert_eq!(
-                    self.next().unwrap_or(EOF.clone()).kind,
-                    TokenKind::RightBrace
-                );
+                self.expect(TokenKind::RightBrace)?;
                 false_type
             }
         };
@@ -989,11 +1012,197 @@ impl<'a> Parser<'a> {
         Ok(atom)
     }
 
+    // Parses the rest of an object type element whose key is a type, e.g.
+    // `[P]: T[P] for P in keyof T`.  If there's no `for` clause the key is
+    // treated as a computed key, e.g. `[iterator]: fn () -> Iterator<T>`.
+    fn parse_mapped_or_computed_prop_type(
+        &mut self,
+        key: TypeAnn,
+    ) -> Result<ObjectProp, ParseError> {
+        if self.peek().unwrap_or(&EOF).kind == TokenKind::Question {
+            let key = type_ann_to_computed_key(key)?;
+            return self.parse_computed_prop_type(key);
+        }
+
+        let mut optional: Option<MappedModifier> = None;
+        if self.peek().unwrap_or(&EOF).kind == TokenKind::Plus {
+            self.next(); // consume '+'
+            self.expect(TokenKind::Question)?;
+            optional = Some(MappedModifier::Add);
+        } else if self.peek().unwrap_or(&EOF).kind == TokenKind::Minus {
+            self.next(); // consume '-'
+            self.expect(TokenKind::Question)?;
+            optional = Some(MappedModifier::Remove);
+        }
+
+        self.expect(TokenKind::Colon)?;
+        let value = self.parse_type_ann()?;
+
+        if self.peek().unwrap_or(&EOF).kind != TokenKind::For {
+            if optional.is_some() {
+                return Err(ParseError {
+                    message: "`+?` and `-?` can only be used in mapped types".to_string(),
+                });
+            }
+            return Ok(ObjectProp::Prop(type_ann::Prop {
+                name: PropName::Computed(type_ann_to_computed_key(key)?),
+                modifier: None,
+                optional: false,
+                readonly: false, // TODO
+                type_ann: Box::new(value),
+                // TODO(#642): compute correct spans for type annotations
+                span: Span { start: 0, end: 0 },
+            }));
+        }
+
+        self.next(); // consumes 'for'
+
+        let target_token = self.next().unwrap_or_else(|| EOF.clone());
+        let target = match target_token.kind {
+            TokenKind::Identifier(name) => name,
+            _ => {
+                return Err(ParseError {
+                    message: "target must be an identifier".to_string(),
+                })
+            }
+        };
+
+        self.expect(TokenKind::In)?;
+
+        let source = self.parse_type_ann()?; // should expand to a union of valid key types
+
+        Ok(ObjectProp::Mapped(Mapped {
+            key: Box::new(key),
+            value: Box::new(value),
+            target,
+            source: Box::new(source),
+            optional,
+            // TODO: handle 'if' clause
+            check: None,
+            extends: None,
+        }))
+    }
+
+    // Parses the rest of a property with a computed key, e.g.
+    // `[Symbol.iterator]?: fn () -> Iterator<T>`.
+    fn parse_computed_prop_type(&mut self, key: Expr) -> Result<ObjectProp, ParseError> {
+        let optional = match self.peek().unwrap_or(&EOF).kind {
+            TokenKind::Question => {
+                self.next(); // consumes '?'
+                true
+            }
+            _ => false,
+        };
+        self.expect(TokenKind::Colon)?;
+        let type_ann = self.parse_type_ann()?;
+
+        Ok(ObjectProp::Prop(type_ann::Prop {
+            name: PropName::Computed(key),
+            modifier: None,
+            optional,
+            readonly: false, // TODO
+            type_ann: Box::new(type_ann),
+            // TODO(#642): compute correct spans for type annotations
+            span: Span { start: 0, end: 0 },
+        }))
+    }
+
+    // Parses the name of a method in an object type, e.g. `foo` or
+    // `[Symbol.iterator]`.
+    fn parse_method_type_name(&mut self) -> Result<PropName, ParseError> {
+        let token = self
+            .next_with_mode(IdentMode::PropName)
+            .unwrap_or(EOF.clone());
+        match token.kind {
+            TokenKind::Identifier(name) => Ok(PropName::Ident(Ident {
+                name,
+                span: token.span,
+            })),
+            TokenKind::LeftBracket => {
+                let key = self.parse_expr()?;
+                self.expect(TokenKind::RightBracket)?;
+                Ok(PropName::Computed(key))
+            }
+            _ => Err(ParseError {
+                message: "expected method name".to_string(),
+            }),
+        }
+    }
+
     pub fn parse_type_ann(&mut self) -> Result<TypeAnn, ParseError> {
         self.parse_type_ann_with_precedence(0)
     }
 }
 
+// Keys like `[iterator]` are parsed as type refs since they could be the
+// start of a mapped type.  When they turn out to be computed keys, they're
+// converted back to identifiers.
+fn type_ann_to_computed_key(key: TypeAnn) -> Result<Expr, ParseError> {
+    let kind = match key.kind {
+        // Qualified names, e.g. `Symbol.iterator`, are member expressions.
+        TypeAnnKind::TypeRef(name, None) if name.contains('.') => {
+            let mut parts = name.split('.');
+            let first = parts.next().unwrap_or_default();
+            let mut end = key.span.start + first.len();
+            let mut expr = Expr {
+                kind: ExprKind::Ident(Ident {
+                    name: first.to_string(),
+                    span: Span {
+                        start: key.span.start,
+                        end,
+                    },
+                }),
+                span: Span {
+                    start: key.span.start,
+                    end,
+                },
+                inferred_type: None,
+            };
+            for part in parts {
+                let start = end + 1; // skips '.'
+                end = start + part.len();
+                expr = Expr {
+                    kind: ExprKind::Member(Member {
+                        object: Box::new(expr),
+                        property: MemberProp::Ident(Ident {
+                            name: part.to_string(),
+                            span: Span { start, end },
+                        }),
+                        opt_chain: false,
+                    }),
+                    span: Span {
+                        start: key.span.start,
+                        end,
+                    },
+                    inferred_type: None,
+                };
+            }
+            return Ok(expr);
+        }
+        TypeAnnKind::TypeRef(name, None) => ExprKind::Ident(Ident {
+            name,
+            span: key.span,
+        }),
+        TypeAnnKind::StrLit(value) => ExprKind::Str(Str {
+            span: key.span,
+            value,
+        }),
+        TypeAnnKind::NumLit(value) => ExprKind::Num(Num { value, raw: None }),
+        _ => {
+            return Err(ParseError {
+                message: "computed keys must be identifiers, member expressions, or literals"
+                    .to_string(),
+            })
+        }
+    };
+
+    Ok(Expr {
+        kind,
+        span: key.span,
+        inferred_type: None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1066,6 +1275,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn parse_computed_keys_in_object_types() -> Result<(), ParseError> {
+        let input = r#"
+            {
+                [Symbol.iterator]: number,
+                fn [Symbol.asyncIterator](self) -> string,
+                [key]?: boolean,
+            }
+        "#;
+        let mut parser = Parser::new(input);
+        let result = parser.parse_type_ann()?;
+        insta::assert_debug_snapshot!(result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_unique_symbol() {
+        insta::assert_debug_snapshot!(parse("unique symbol"));
+    }
+
     #[test]
     #[should_panic]
     fn parse_object_type_missing_comma() {
@@ -1085,6 +1315,18 @@ mod tests {
         insta::assert_debug_snapshot!(parse("[number, ...number[]]"));
     }
 
+    #[test]
+    fn parse_labeled_tuple_types() {
+        insta::assert_debug_snapshot!(parse("[x: number, y: number]"));
+        insta::assert_debug_snapshot!(parse("[first: string, ...rest: T]"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_tuple_type_with_some_labels() {
+        insta::assert_debug_snapshot!(parse("[x: number, number]"));
+    }
+
     #[test]
     #[should_panic]
     fn parse_tuple_type_missing_comma() {
@@ -1097,6 +1339,18 @@ mod tests {
         insta::assert_debug_snapshot!(parse("[number, string"));
     }
 
+    #[test]
+    fn parse_readonly_types() {
+        insta::assert_debug_snapshot!(parse("readonly number[]"));
+        insta::assert_debug_snapshot!(parse("readonly [number, string] | string"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn parse_readonly_non_array_type() {
+        insta::assert_debug_snapshot!(parse("readonly string"));
+    }
+
     #[test]
     fn parse_array_types() {
         insta::assert_debug_snapshot!(parse("number[]"));
@@ -1177,6 +1431,12 @@ mod tests {
         insta::assert_debug_snapshot!(parse("fn (...args: _) -> _"));
     }
 
+    #[test]
+    fn parse_typeof_type() {
+        insta::assert_debug_snapshot!(parse("typeof foo"));
+        insta::assert_debug_snapshot!(parse("typeof foo.bar.baz"));
+    }
+
     #[test]
     fn parse_infer_type() {
         insta::assert_debug_snapshot!(parse("infer T"));
@@ -1205,4 +1465,105 @@ mod tests {
         insta::assert_debug_snapshot!(parse(r#"A * B + C"#));
         insta::assert_debug_snapshot!(parse(r#"A * (B + C)"#));
     }
+
+    #[test]
+    fn parse_setter_with_non_undefined_return() {
+        let mut parser = Parser::new("{set foo(mut self, value: number) -> number}");
+        assert_eq!(
+            parser.parse_type_ann(),
+            Err(ParseError {
+                message: "setters must return undefined".to_string()
+            })
+        );
+    }
+
+    // Malformed input should result in a ParseError instead of a panic.  The
+    // inputs are all made of complete tokens so that only the parser is
+    // exercised and not the scanner.
+    #[test]
+    fn parse_malformed_type_anns_never_panics() {
+        let samples = [
+            "{a: number, b?: string, [key]: boolean, mut c: T}",
+            "{get foo(self) -> number, set foo(mut self, value: number) -> undefined}",
+            "{fn foo<T>(self, x: T) -> T, [K]: T[K] for K in keyof T}",
+            "fn <T: string = \"a\">(a: T, ...rest: Array<T>) -> Promise<T> throws E",
+            "[number, ...string[]] | (A & B)[] | typeof foo.bar",
+            "if (T : U) { X } else if (T : V) { Y } else { Z }",
+            "match (T) { number => \"number\", _ => \"other\" }",
+            "keyof T[K] + 1 * -5 % infer U",
+        ];
+        let vocab = [
+            "{",
+            "}",
+            "(",
+            ")",
+            "[",
+            "]",
+            "<",
+            ">",
+            ",",
+            ":",
+            "?",
+            "|",
+            "&",
+            "->",
+            "...",
+            ".",
+            "=>",
+            "+",
+            "-",
+            "*",
+            "fn",
+            "get",
+            "set",
+            "self",
+            "mut",
+            "for",
+            "in",
+            "keyof",
+            "typeof",
+            "infer",
+            "if",
+            "else",
+            "match",
+            "throws",
+            "number",
+            "string",
+            "T",
+            r#""a""#,
+            "5",
+            "true",
+            "null",
+            "undefined",
+            "_",
+        ];
+
+        let mut inputs = vec![];
+        for sample in samples {
+            let tokens: Vec<&str> = sample.split(' ').collect();
+            for i in 0..=tokens.len() {
+                inputs.push(tokens[..i].join(" "));
+            }
+        }
+        // xorshift so that the inputs are the same on every run.
+        let mut seed: u32 = 0x9e3779b9;
+        for _ in 0..2000 {
+            let mut tokens = vec![];
+            for _ in 0..(seed % 12) {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                tokens.push(vocab[seed as usize % vocab.len()]);
+            }
+            inputs.push(tokens.join(" "));
+        }
+
+        for input in inputs {
+            let result = std::panic::catch_unwind(|| {
+                let mut parser = Parser::new(&input);
+                let _ = parser.parse_type_ann();
+            });
+            assert!(result.is_ok(), "parsing {input:?} panicked");
+        }
+    }
 }
@@ -0,0 +1,117 @@
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use proptest::prelude::*;
+use proptest::sample::{select, Index};
+
+use escalier_ast::CompilerOptions;
+use escalier_parser::{parse, ParseError};
+
+// Malformed input should result in a ParseError.  The parser shouldn't panic
+// or hang since editors parse whatever is in the buffer after every keystroke.
+fn parse_with_timeout(input: &str) -> Result<Result<(), ParseError>, String> {
+    let (sender, receiver) = mpsc::channel();
+    let handle = {
+        let input = input.to_owned();
+        // Uses the main thread's stack size instead of the smaller default for
+        // spawned threads since that's where the parser normally runs.
+        thread::Builder::new()
+            .stack_size(8 * 1024 * 1024)
+            .spawn(move || {
+                let _ = sender.send(parse(&input, &CompilerOptions::default()).map(|_| ()));
+            })
+            .unwrap()
+    };
+
+    match receiver.recv_timeout(Duration::from_secs(5)) {
+        Ok(result) => Ok(result),
+        Err(mpsc::RecvTimeoutError::Timeout) => Err(format!("parsing {input:?} didn't finish")),
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            let _ = handle.join();
+            Err(format!("parsing {input:?} panicked"))
+        }
+    }
+}
+
+fn parse_without_panicking(input: String) -> Result<(), TestCaseError> {
+    // Parse errors are expected, only panics and timeouts fail the test.
+    let _ = parse_with_timeout(&input).map_err(TestCaseError::fail)?;
+    Ok(())
+}
+
+#[test]
+fn parsing_deeply_nested_input_returns_an_error() {
+    let inputs = [
+        format!("let x = {}1{}", "(".repeat(1000), ")".repeat(1000)),
+        format!("let x = {}1", "-".repeat(1000)),
+        format!("let x: {}T{} = y", "Array<".repeat(1000), ">".repeat(1000)),
+        format!("let {}x{} = y", "[".repeat(1000), "]".repeat(1000)),
+    ];
+    for input in inputs {
+        assert_eq!(
+            parse_with_timeout(&input),
+            Ok(Err(ParseError {
+                message: "Exceeded the maximum nesting depth of 64".to_string()
+            }))
+        );
+    }
+}
+
+const SAMPLES: &[&str] = &[
+    r#"let add = fn (a: number, b: number) -> number => a + b"#,
+    r#"let {x, y: [head, ...tail], mut z = 5} = point"#,
+    r#"declare let foo: {a?: string, [key]: T, get bar(self) -> number}"#,
+    r#"type Foo<T: string = "a"> = if (T : U) { X } else { Y }"#,
+    r#"type Keys = {[K]: T[K] for K in keyof T} | typeof foo.bar"#,
+    r#"let msg = match (a, b) { (0, _) => "zero", (x is number, 1..=10) if x > 0 => `${x}` }"#,
+    r#"let res = try { await fetch(url) } catch (e) { throw e } finally { done() }"#,
+    r#"class Foo extends Bar { x: number; fn get(self) { return self.x } static fn new() {} }"#,
+    r#"let elem = <div class="foo" onClick={fn () => x += 1}>Hello, {name}! <Bar.Baz /></div>"#,
+    r#"import {a, b as c} from "./foo"; export {a}"#,
+    r#"for (const [k, v] in entries) { if (k == "foo") { continue } else if let {x} = v { x } }"#,
+    r#"let gen = gen fn () { yield 1_000.5; yield* [1n, -2, ...rest] }"#,
+    r#"let x = a?.b?.[c]?.(d) ?? new Foo<number>(1) // comment"#,
+    r#"let y = do { let x = 5; x * 2 } /// doc comment"#,
+    r##"let re = r"^\d+$"; let quote = r#"say "hi""#; let s = "a \
+b""##,
+];
+
+#[rustfmt::skip]
+const VOCAB: &[&str] = &[
+    "{", "}", "(", ")", "[", "]", "<", ">", "</", "/>", ",", ":", ";", "?", "?.", "|", "&", "->",
+    "=>", "=", "==", "+=", "...", "..", "..=", ".", "+", "-", "*", "/", "%", "!", "&&", "||", "@",
+    "`", "${", "\"", "\\", "$", "#", "'", "é", "\u{1F600}", "\n", "//", "let", "mut", "fn", "gen",
+    "async", "await", "yield", "return", "if", "else", "match", "is", "try", "catch", "finally",
+    "throw", "do", "for", "in", "class", "extends", "static", "get", "set", "self", "type",
+    "declare", "import", "export", "from", "as", "typeof", "keyof", "infer", "new", "number",
+    "string", "x", "T", "\"str\"", "r\"", "r#\"", "\"#", "5", "1.5", "2n", "true", "null",
+    "undefined", "_", "0x", "0b1", "0o7", "1e", "1e-3", "e", "^", "~", "<<", ">>", ">>>", "**",
+];
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(1000))]
+
+    #[test]
+    fn parsing_arbitrary_strings_never_panics(input in any::<String>()) {
+        parse_without_panicking(input)?;
+    }
+
+    #[test]
+    fn parsing_arbitrary_tokens_never_panics(
+        tokens in prop::collection::vec(select(VOCAB), 0..40),
+    ) {
+        parse_without_panicking(tokens.join(" "))?;
+        parse_without_panicking(tokens.concat())?;
+    }
+
+    // Truncating valid programs results in the sort of input that's common
+    // while editing, e.g. unclosed brackets and strings.
+    #[test]
+    fn parsing_truncated_programs_never_panics(sample in select(SAMPLES), index in any::<Index>()) {
+        let chars: Vec<char> = sample.chars().collect();
+        let len = index.index(chars.len() + 1);
+        parse_without_panicking(chars[..len].iter().collect())?;
+        parse_without_panicking(chars[len..].iter().collect())?;
+    }
+}
@@ -0,0 +1,148 @@
+use escalier_ast::*;
+use escalier_parser::parse;
+
+// Collects the names of the identifiers that are referenced or bound.
+#[derive(Default)]
+struct NameCollector {
+    names: Vec<String>,
+}
+
+impl Visitor for NameCollector {
+    fn visit_expr(&mut self, expr: &Expr) {
+        if let ExprKind::Ident(Ident { name, .. }) = &expr.kind {
+            self.names.push(name.to_owned());
+        }
+        walk_expr(self, expr);
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern) {
+        if let PatternKind::Ident(BindingIdent { name, .. }) = &pattern.kind {
+            self.names.push(name.to_owned());
+        }
+        walk_pattern(self, pattern);
+    }
+}
+
+fn collect_names(script: &Script) -> Vec<String> {
+    let mut collector = NameCollector::default();
+    collector.visit_program(script);
+    collector.names
+}
+
+struct Rename {
+    from: &'static str,
+    to: &'static str,
+}
+
+impl VisitorMut for Rename {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match &mut expr.kind {
+            ExprKind::Ident(Ident { name, .. }) if name == self.from => *name = self.to.to_owned(),
+            _ => walk_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_pattern_mut(&mut self, pattern: &mut Pattern) {
+        match &mut pattern.kind {
+            PatternKind::Ident(BindingIdent { name, .. }) if name == self.from => {
+                *name = self.to.to_owned()
+            }
+            _ => walk_pattern_mut(self, pattern),
+        }
+    }
+}
+
+#[test]
+fn visitor_mut_walks_nested_nodes() {
+    let src = r#"
+    let add = fn (x, y) => x + y
+    let z = if (add(x, 1) > 0) {
+        let [w = x] = [x]
+        w
+    } else {
+        -x
+    }
+    "#;
+    let mut script = parse(src, &CompilerOptions::default()).unwrap();
+
+    Rename { from: "x", to: "a" }.visit_program_mut(&mut script);
+
+    assert_eq!(
+        collect_names(&script),
+        vec!["add", "a", "y", "a", "y", "z", "add", "a", "w", "a", "a", "w", "a"]
+    );
+}
+
+// Replaces `-e` with `0 - e`.
+struct DesugarNegation;
+
+impl Folder for DesugarNegation {
+    fn fold_expr(&mut self, expr: Expr) -> Expr {
+        let expr = walk_expr_fold(self, expr);
+        match expr.kind {
+            ExprKind::Unary(Unary {
+                op: UnaryOp::Minus,
+                right,
+            }) => Expr {
+                kind: ExprKind::Binary(Binary {
+                    left: Box::new(Expr {
+                        kind: ExprKind::Num(Num {
+                            value: "0".to_string(),
+                            raw: None,
+                        }),
+                        span: expr.span,
+                        inferred_type: None,
+                    }),
+                    op: BinaryOp::Minus,
+                    right,
+                }),
+                ..expr
+            },
+            kind => Expr { kind, ..expr },
+        }
+    }
+}
+
+// Counts the unary and binary `-` operators.
+#[derive(Default)]
+struct MinusCounter {
+    unary: usize,
+    binary: usize,
+}
+
+impl Visitor for MinusCounter {
+    fn visit_expr(&mut self, expr: &Expr) {
+        match &expr.kind {
+            ExprKind::Unary(Unary {
+                op: UnaryOp::Minus, ..
+            }) => self.unary += 1,
+            ExprKind::Binary(Binary {
+                op: BinaryOp::Minus,
+                ..
+            }) => self.binary += 1,
+            _ => (),
+        }
+        walk_expr(self, expr);
+    }
+}
+
+#[test]
+fn folder_replaces_nested_nodes() {
+    let src = r#"
+    let f = fn (x) => -(-x)
+    let y = if (f(1) > 0) {
+        let [z = -1] = [-2]
+        z - 3
+    } else {
+        0
+    }
+    "#;
+    let script = parse(src, &CompilerOptions::default()).unwrap();
+
+    let script = DesugarNegation.fold_program(script);
+
+    let mut counter = MinusCounter::default();
+    counter.visit_program(&script);
+    assert_eq!(counter.unary, 0);
+    assert_eq!(counter.binary, 5);
+}